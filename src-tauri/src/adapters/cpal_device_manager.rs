@@ -1,37 +1,84 @@
 //! CPAL-based device manager adapter
 
-use crate::domain::{AudioDevice, DeviceId, DeviceType};
+use crate::domain::{normalize_device_name, AudioDevice, DeviceId, DeviceType, VirtualDeviceSettings};
 use crate::ports::{DeviceManager, DeviceManagerError};
 
-/// Known virtual audio device name patterns
-const VIRTUAL_DEVICE_PATTERNS: &[&str] = &[
-    "virtual audio",
-    "vb-audio",
-    "cable",
-    "voicemeeter",
-    "blackhole",
-    "loopback",
-    "virtual cable",
-];
-
 /// Device manager adapter using CPAL
+///
+/// Until the first `refresh()`, every query enumerates the hardware.
+/// After that, queries are served from the cached list.
 pub struct CpalDeviceManager {
     cached_devices: Vec<AudioDevice>,
+    refreshed: bool,
+    /// User patterns and marks deciding which devices are virtual
+    virtual_devices: VirtualDeviceSettings,
 }
 
 impl CpalDeviceManager {
     pub fn new() -> Self {
         Self {
             cached_devices: Vec::new(),
+            refreshed: false,
+            virtual_devices: VirtualDeviceSettings::default(),
+        }
+    }
+
+    /// Classify devices with these patterns and marks
+    ///
+    /// The cached devices are reclassified in place, without enumerating
+    /// the hardware again.
+    pub fn set_virtual_devices(&mut self, virtual_devices: VirtualDeviceSettings) {
+        self.virtual_devices = virtual_devices;
+        for device in &mut self.cached_devices {
+            let device_type = self.virtual_devices.classify(device.id().as_str(), device.device_type().is_input());
+            device.set_device_type(device_type);
+        }
+    }
+
+    /// Names of the current input and output devices
+    ///
+    /// Much cheaper than a full enumeration (no capability probing), so it
+    /// can be polled to detect devices being plugged in or removed.
+    pub fn device_names() -> (Vec<String>, Vec<String>) {
+        use cpal::traits::{DeviceTrait, HostTrait};
+
+        let host = cpal::default_host();
+        let names = |devices: Option<Vec<cpal::Device>>| {
+            let mut names: Vec<String> = devices
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|d| d.name().ok())
+                .collect();
+            names.sort();
+            names
+        };
+
+        (
+            names(host.input_devices().ok().map(|d| d.collect())),
+            names(host.output_devices().ok().map(|d| d.collect())),
+        )
+    }
+
+    /// Devices of the last refresh, None if never refreshed
+    ///
+    /// Never enumerates, for callers that only report on devices.
+    pub fn cached_devices(&self) -> Option<&[AudioDevice]> {
+        self.refreshed.then_some(self.cached_devices.as_slice())
+    }
+
+    /// Cached devices, or a fresh enumeration if never refreshed
+    fn devices(&self) -> Result<Vec<AudioDevice>, DeviceManagerError> {
+        if self.refreshed {
+            Ok(self.cached_devices.clone())
+        } else {
+            self.enumerate_devices()
         }
     }
 
-    /// Check if a device name matches known virtual device patterns
-    fn is_virtual_device(name: &str) -> bool {
-        let name_lower = name.to_lowercase();
-        VIRTUAL_DEVICE_PATTERNS
-            .iter()
-            .any(|pattern| name_lower.contains(pattern))
+    /// Type of a device from the virtual device patterns, marks and
+    /// overrides
+    fn device_type(&self, name: &str, is_input: bool) -> DeviceType {
+        self.virtual_devices.classify(name, is_input)
     }
 
     fn enumerate_devices(&self) -> Result<Vec<AudioDevice>, DeviceManagerError> {
@@ -53,20 +100,15 @@ impl CpalDeviceManager {
             for device in input_devices {
                 if let Ok(name) = device.name() {
                     let is_default = default_input_name.as_ref() == Some(&name);
-                    let is_virtual = Self::is_virtual_device(&name);
+                    let device_type = self.device_type(&name, true);
 
                     // Get supported configurations
                     let (sample_rates, channels) = Self::get_device_capabilities(&device, true);
 
-                    let device_type = if is_virtual {
-                        DeviceType::InputVirtual
-                    } else {
-                        DeviceType::InputPhysical
-                    };
-
+                    // The id stays the raw name, which is what opens the device
                     devices.push(AudioDevice::new(
                         DeviceId::new(&name),
-                        name,
+                        normalize_device_name(&name),
                         device_type,
                         is_default,
                         sample_rates,
@@ -81,19 +123,13 @@ impl CpalDeviceManager {
             for device in output_devices {
                 if let Ok(name) = device.name() {
                     let is_default = default_output_name.as_ref() == Some(&name);
-                    let is_virtual = Self::is_virtual_device(&name);
+                    let device_type = self.device_type(&name, false);
 
                     let (sample_rates, channels) = Self::get_device_capabilities(&device, false);
 
-                    let device_type = if is_virtual {
-                        DeviceType::OutputVirtual
-                    } else {
-                        DeviceType::OutputPhysical
-                    };
-
                     devices.push(AudioDevice::new(
                         DeviceId::new(&name),
-                        name,
+                        normalize_device_name(&name),
                         device_type,
                         is_default,
                         sample_rates,
@@ -186,14 +222,14 @@ impl Default for CpalDeviceManager {
 
 impl DeviceManager for CpalDeviceManager {
     fn list_devices(&self) -> Result<Vec<AudioDevice>, DeviceManagerError> {
-        self.enumerate_devices()
+        self.devices()
     }
 
     fn list_devices_by_type(
         &self,
         device_type: DeviceType,
     ) -> Result<Vec<AudioDevice>, DeviceManagerError> {
-        let devices = self.enumerate_devices()?;
+        let devices = self.devices()?;
         Ok(devices
             .into_iter()
             .filter(|d| d.device_type() == device_type)
@@ -211,12 +247,13 @@ impl DeviceManager for CpalDeviceManager {
     }
 
     fn get_device(&self, id: &DeviceId) -> Result<Option<AudioDevice>, DeviceManagerError> {
-        let devices = self.enumerate_devices()?;
+        let devices = self.devices()?;
         Ok(devices.into_iter().find(|d| d.id() == id))
     }
 
     fn refresh(&mut self) -> Result<(), DeviceManagerError> {
         self.cached_devices = self.enumerate_devices()?;
+        self.refreshed = true;
         Ok(())
     }
 }
@@ -229,14 +266,24 @@ mod tests {
     fn test_device_manager_creation() {
         let manager = CpalDeviceManager::new();
         assert!(manager.cached_devices.is_empty());
+        assert!(!manager.refreshed);
     }
 
     #[test]
     fn test_virtual_device_detection() {
-        assert!(CpalDeviceManager::is_virtual_device("Virtual Audio Device"));
-        assert!(CpalDeviceManager::is_virtual_device("CABLE Output (VB-Audio Virtual Cable)"));
-        assert!(CpalDeviceManager::is_virtual_device("Voicemeeter Input"));
-        assert!(!CpalDeviceManager::is_virtual_device("Realtek HD Audio"));
-        assert!(!CpalDeviceManager::is_virtual_device("Built-in Microphone"));
+        let mut manager = CpalDeviceManager::new();
+        assert!(manager.device_type("Virtual Audio Device", true).is_virtual());
+        assert!(manager.device_type("CABLE Output (VB-Audio Virtual Cable)", true).is_virtual());
+        assert!(manager.device_type("Voicemeeter Input", true).is_virtual());
+        assert!(!manager.device_type("Realtek HD Audio", true).is_virtual());
+        assert!(!manager.device_type("Built-in Microphone", true).is_virtual());
+
+        manager.set_virtual_devices(VirtualDeviceSettings {
+            patterns: vec!["sar".into()],
+            marked_devices: vec!["Built-in Microphone".into()],
+            ..Default::default()
+        });
+        assert!(manager.device_type("Built-in Microphone", true).is_virtual());
+        assert!(!manager.device_type("Voicemeeter Input", true).is_virtual());
     }
 }
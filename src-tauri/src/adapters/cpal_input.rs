@@ -1,6 +1,6 @@
 //! CPAL-based audio input adapter
 
-use crate::domain::{AudioBuffer, AudioFormat, DeviceId, Sample};
+use crate::domain::{find_device_name, AudioBuffer, AudioFormat, DeviceId, Sample};
 use crate::ports::{AudioInput, AudioInputError};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
@@ -42,12 +42,10 @@ impl CpalAudioInput {
             .input_devices()
             .map_err(|e| AudioInputError::DeviceNotFound(e.to_string()))?;
 
-        for device in devices {
-            if let Ok(name) = device.name() {
-                if name == device_id.as_str() {
-                    return Ok(device);
-                }
-            }
+        let mut devices: Vec<cpal::Device> = devices.collect();
+        let names: Vec<String> = devices.iter().map(|device| device.name().unwrap_or_default()).collect();
+        if let Some(index) = find_device_name(names.iter().map(String::as_str), device_id.as_str()) {
+            return Ok(devices.swap_remove(index));
         }
 
         Err(AudioInputError::DeviceNotFound(format!(
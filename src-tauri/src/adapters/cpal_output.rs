@@ -1,6 +1,6 @@
 //! CPAL-based audio output adapter
 
-use crate::domain::{AudioBuffer, AudioFormat, DeviceId};
+use crate::domain::{find_device_name, AudioBuffer, AudioFormat, DeviceId};
 use crate::ports::{AudioOutput, AudioOutputError};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ringbuf::{HeapRb, traits::{Consumer, Observer, Producer, Split}};
@@ -43,12 +43,10 @@ impl CpalAudioOutput {
             .output_devices()
             .map_err(|e| AudioOutputError::DeviceNotFound(e.to_string()))?;
 
-        for device in devices {
-            if let Ok(name) = device.name() {
-                if name == device_id.as_str() {
-                    return Ok(device);
-                }
-            }
+        let mut devices: Vec<cpal::Device> = devices.collect();
+        let names: Vec<String> = devices.iter().map(|device| device.name().unwrap_or_default()).collect();
+        if let Some(index) = find_device_name(names.iter().map(String::as_str), device_id.as_str()) {
+            return Ok(devices.swap_remove(index));
         }
 
         Err(AudioOutputError::DeviceNotFound(format!(
@@ -0,0 +1,80 @@
+//! FLAC encoder adapter using flacenc
+
+use crate::domain::{AudioBuffer, AudioFileFormat};
+use crate::ports::{FileEncoder, FileEncoderError};
+use flacenc::component::BitRepr;
+use flacenc::error::Verify;
+use std::path::Path;
+
+/// FLAC file encoder using flacenc (pure Rust)
+pub struct FlacEncoder;
+
+impl FlacEncoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for FlacEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileEncoder for FlacEncoder {
+    fn encode(
+        &self,
+        path: &Path,
+        buffer: &AudioBuffer,
+        bits_per_sample: u16,
+    ) -> Result<(), FileEncoderError> {
+        let scale = match bits_per_sample {
+            16 => i16::MAX as f32,
+            24 => 8_388_607.0,
+            other => {
+                return Err(FileEncoderError::UnsupportedFormat(format!(
+                    "{}-bit FLAC",
+                    other
+                )))
+            }
+        };
+
+        let samples: Vec<i32> = buffer
+            .samples()
+            .iter()
+            .map(|s| (s.value() * scale) as i32)
+            .collect();
+
+        let config = flacenc::config::Encoder::default()
+            .into_verified()
+            .map_err(|(_, e)| FileEncoderError::EncodeError(format!("{:?}", e)))?;
+        let source = flacenc::source::MemSource::from_samples(
+            &samples,
+            buffer.channels() as usize,
+            bits_per_sample as usize,
+            buffer.sample_rate() as usize,
+        );
+        let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+            .map_err(|e| FileEncoderError::EncodeError(format!("{:?}", e)))?;
+
+        let mut sink = flacenc::bitsink::ByteSink::new();
+        stream
+            .write(&mut sink)
+            .map_err(|e| FileEncoderError::EncodeError(format!("{:?}", e)))?;
+        std::fs::write(path, sink.as_slice()).map_err(|e| FileEncoderError::IoError(e.to_string()))?;
+
+        tracing::info!(
+            "FLAC written: {} ({} frames, {}Hz, {}ch, {}-bit)",
+            path.display(),
+            buffer.frame_count(),
+            buffer.sample_rate(),
+            buffer.channels(),
+            bits_per_sample
+        );
+        Ok(())
+    }
+
+    fn supports_format(&self, format: AudioFileFormat) -> bool {
+        format == AudioFileFormat::Flac
+    }
+}
@@ -0,0 +1,106 @@
+//! Hound-based WAV encoder adapter
+
+use crate::domain::{AudioBuffer, AudioFileFormat};
+use crate::ports::{FileEncoder, FileEncoderError};
+use std::path::Path;
+
+/// WAV file encoder using Hound
+pub struct HoundWavEncoder;
+
+impl HoundWavEncoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HoundWavEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileEncoder for HoundWavEncoder {
+    fn encode(
+        &self,
+        path: &Path,
+        buffer: &AudioBuffer,
+        bits_per_sample: u16,
+    ) -> Result<(), FileEncoderError> {
+        let sample_format = match bits_per_sample {
+            16 | 24 => hound::SampleFormat::Int,
+            32 => hound::SampleFormat::Float,
+            other => {
+                return Err(FileEncoderError::UnsupportedFormat(format!(
+                    "{}-bit WAV",
+                    other
+                )))
+            }
+        };
+
+        let spec = hound::WavSpec {
+            channels: buffer.channels(),
+            sample_rate: buffer.sample_rate(),
+            bits_per_sample,
+            sample_format,
+        };
+
+        let mut writer = hound::WavWriter::create(path, spec)
+            .map_err(|e| FileEncoderError::IoError(e.to_string()))?;
+
+        for sample in buffer.samples() {
+            let value = sample.value();
+            let result = match bits_per_sample {
+                16 => writer.write_sample((value * i16::MAX as f32) as i16),
+                24 => writer.write_sample((value * 8_388_607.0) as i32),
+                _ => writer.write_sample(value),
+            };
+            result.map_err(|e| FileEncoderError::EncodeError(e.to_string()))?;
+        }
+
+        writer
+            .finalize()
+            .map_err(|e| FileEncoderError::EncodeError(e.to_string()))?;
+
+        tracing::info!(
+            "WAV written: {} ({} frames, {}Hz, {}ch, {}-bit)",
+            path.display(),
+            buffer.frame_count(),
+            buffer.sample_rate(),
+            buffer.channels(),
+            bits_per_sample
+        );
+        Ok(())
+    }
+
+    fn supports_format(&self, format: AudioFileFormat) -> bool {
+        format == AudioFileFormat::Wav
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_and_read_back() {
+        let path = std::env::temp_dir().join(format!("voiceboard_test_{}.wav", uuid::Uuid::new_v4()));
+        let buffer = AudioBuffer::from_raw_f32(vec![0.0, 0.5, -0.5, 0.25], 2, 48000);
+
+        HoundWavEncoder::new().encode(&path, &buffer, 16).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 48000);
+        assert_eq!(reader.len(), 4);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_unsupported_bit_depth() {
+        let path = std::env::temp_dir().join("voiceboard_unsupported.wav");
+        let buffer = AudioBuffer::silence(10, 2, 48000);
+        let result = HoundWavEncoder::new().encode(&path, &buffer, 12);
+        assert!(matches!(result, Err(FileEncoderError::UnsupportedFormat(_))));
+    }
+}
@@ -0,0 +1,47 @@
+//! Keyring secret store adapter
+//!
+//! Uses the Windows Credential Manager, the macOS Keychain or the Secret
+//! Service on Linux.
+
+use crate::ports::{SecretStore, SecretStoreError};
+use keyring::Entry;
+
+/// Service name the secrets are filed under
+const SERVICE: &str = "voiceboard";
+
+/// Keeps secrets in the OS credential store
+#[derive(Debug, Default)]
+pub struct KeyringSecretStore;
+
+impl KeyringSecretStore {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn entry(name: &str) -> Result<Entry, SecretStoreError> {
+        Entry::new(SERVICE, name).map_err(|e| SecretStoreError::Unavailable(e.to_string()))
+    }
+}
+
+impl SecretStore for KeyringSecretStore {
+    fn get(&self, name: &str) -> Result<Option<String>, SecretStoreError> {
+        match Self::entry(name)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(SecretStoreError::Unavailable(e.to_string())),
+        }
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<(), SecretStoreError> {
+        Self::entry(name)?
+            .set_password(secret)
+            .map_err(|e| SecretStoreError::Unavailable(e.to_string()))
+    }
+
+    fn delete(&self, name: &str) -> Result<(), SecretStoreError> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(SecretStoreError::Unavailable(e.to_string())),
+        }
+    }
+}
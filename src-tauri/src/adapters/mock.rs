@@ -0,0 +1,227 @@
+//! In-memory adapters for testing without audio hardware
+//!
+//! Available in unit tests and behind the `test-harness` cargo feature.
+
+use crate::domain::{AudioBuffer, AudioDevice, AudioFormat, DeviceId, DeviceType};
+use crate::ports::{
+    AudioInput, AudioInputError, AudioOutput, AudioOutputError, DeviceManager,
+    DeviceManagerError,
+};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+
+/// Audio input that replays a fixed list of buffers
+pub struct MockAudioInput {
+    buffers: Vec<AudioBuffer>,
+    format: Option<AudioFormat>,
+    is_capturing: bool,
+}
+
+impl MockAudioInput {
+    pub fn new(buffers: Vec<AudioBuffer>) -> Self {
+        Self {
+            buffers,
+            format: None,
+            is_capturing: false,
+        }
+    }
+}
+
+impl AudioInput for MockAudioInput {
+    fn start(&mut self, _device_id: &DeviceId, format: AudioFormat) -> Result<(), AudioInputError> {
+        self.format = Some(format);
+        self.is_capturing = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), AudioInputError> {
+        self.is_capturing = false;
+        Ok(())
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.is_capturing
+    }
+
+    fn get_receiver(&self) -> Option<Receiver<AudioBuffer>> {
+        if !self.is_capturing {
+            return None;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        for buffer in &self.buffers {
+            let _ = tx.send(buffer.clone());
+        }
+        Some(rx)
+    }
+
+    fn current_format(&self) -> Option<AudioFormat> {
+        self.format
+    }
+}
+
+/// Audio output that records every written buffer
+pub struct MockAudioOutput {
+    written: Arc<Mutex<Vec<AudioBuffer>>>,
+    format: Option<AudioFormat>,
+    is_playing: bool,
+}
+
+impl MockAudioOutput {
+    pub fn new() -> Self {
+        Self {
+            written: Arc::new(Mutex::new(Vec::new())),
+            format: None,
+            is_playing: false,
+        }
+    }
+
+    /// Shared handle to the captured buffers
+    pub fn written(&self) -> Arc<Mutex<Vec<AudioBuffer>>> {
+        self.written.clone()
+    }
+}
+
+impl Default for MockAudioOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioOutput for MockAudioOutput {
+    fn start(&mut self, _device_id: &DeviceId, format: AudioFormat) -> Result<(), AudioOutputError> {
+        self.format = Some(format);
+        self.is_playing = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), AudioOutputError> {
+        self.is_playing = false;
+        Ok(())
+    }
+
+    fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    fn write(&mut self, buffer: &AudioBuffer) -> Result<(), AudioOutputError> {
+        if !self.is_playing {
+            return Err(AudioOutputError::StreamError("Output not started".into()));
+        }
+        self.written
+            .lock()
+            .map_err(|e| AudioOutputError::StreamError(e.to_string()))?
+            .push(buffer.clone());
+        Ok(())
+    }
+
+    fn current_format(&self) -> Option<AudioFormat> {
+        self.format
+    }
+
+    fn available_frames(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// Device manager serving a fixed device list
+pub struct MockDeviceManager {
+    devices: Vec<AudioDevice>,
+}
+
+impl MockDeviceManager {
+    pub fn new(devices: Vec<AudioDevice>) -> Self {
+        Self { devices }
+    }
+
+    /// A typical setup: one microphone, one speaker and one virtual cable
+    pub fn with_default_devices() -> Self {
+        let device = |name: &str, device_type: DeviceType| {
+            AudioDevice::new(
+                DeviceId::new(name),
+                name.to_string(),
+                device_type,
+                true,
+                vec![44100, 48000],
+                vec![1, 2],
+            )
+        };
+
+        Self::new(vec![
+            device("Mock Microphone", DeviceType::InputPhysical),
+            device("Mock Speakers", DeviceType::OutputPhysical),
+            device("CABLE Input (Mock Virtual Cable)", DeviceType::OutputVirtual),
+        ])
+    }
+}
+
+impl DeviceManager for MockDeviceManager {
+    fn list_devices(&self) -> Result<Vec<AudioDevice>, DeviceManagerError> {
+        Ok(self.devices.clone())
+    }
+
+    fn list_devices_by_type(
+        &self,
+        device_type: DeviceType,
+    ) -> Result<Vec<AudioDevice>, DeviceManagerError> {
+        Ok(self
+            .devices
+            .iter()
+            .filter(|d| d.device_type() == device_type)
+            .cloned()
+            .collect())
+    }
+
+    fn default_input_device(&self) -> Result<Option<AudioDevice>, DeviceManagerError> {
+        let devices = self.list_devices_by_type(DeviceType::InputPhysical)?;
+        Ok(devices.into_iter().find(|d| d.is_default()))
+    }
+
+    fn default_output_device(&self) -> Result<Option<AudioDevice>, DeviceManagerError> {
+        let devices = self.list_devices_by_type(DeviceType::OutputPhysical)?;
+        Ok(devices.into_iter().find(|d| d.is_default()))
+    }
+
+    fn get_device(&self, id: &DeviceId) -> Result<Option<AudioDevice>, DeviceManagerError> {
+        Ok(self.devices.iter().find(|d| d.id() == id).cloned())
+    }
+
+    fn refresh(&mut self) -> Result<(), DeviceManagerError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_output_records_buffers() {
+        let mut output = MockAudioOutput::new();
+        let written = output.written();
+
+        output.start(&DeviceId::new("mock"), AudioFormat::CD_QUALITY).unwrap();
+        output.write(&AudioBuffer::silence(10, 2, 44100)).unwrap();
+
+        assert_eq!(written.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_mock_input_replays_buffers() {
+        let buffer = AudioBuffer::from_raw_f32(vec![0.1, 0.2], 2, 48000);
+        let mut input = MockAudioInput::new(vec![buffer.clone()]);
+        assert!(input.get_receiver().is_none());
+
+        input.start(&DeviceId::new("mock"), AudioFormat::HIGH_QUALITY).unwrap();
+        let rx = input.get_receiver().unwrap();
+        assert_eq!(rx.recv().unwrap(), buffer);
+    }
+
+    #[test]
+    fn test_mock_device_manager() {
+        let manager = MockDeviceManager::with_default_devices();
+        assert_eq!(manager.list_devices().unwrap().len(), 3);
+        assert!(manager.default_input_device().unwrap().is_some());
+        assert_eq!(manager.list_devices_by_type(DeviceType::OutputVirtual).unwrap().len(), 1);
+    }
+}
@@ -1,17 +1,38 @@
 //! Adapters layer - Concrete implementations of ports
 //!
 //! Adapters implement the port interfaces using specific technologies
-//! (cpal, rodio, WASAPI, etc.)
+//! (cpal, Symphonia, WASAPI, etc.)
 
 mod cpal_input;
 mod cpal_output;
 mod cpal_device_manager;
+mod flac_encoder;
+mod hound_encoder;
+mod keyring_secret_store;
 mod rodio_decoder;
+mod s3_storage;
+mod symphonia_decoder;
+mod vorbis_encoder;
+mod webdav_storage;
 
 pub use cpal_input::*;
 pub use cpal_output::*;
 pub use cpal_device_manager::*;
+pub use flac_encoder::*;
+pub use hound_encoder::*;
+pub use keyring_secret_store::*;
 pub use rodio_decoder::*;
+pub use s3_storage::*;
+pub use symphonia_decoder::*;
+pub use vorbis_encoder::*;
+pub use webdav_storage::*;
+
+// In-memory adapters for tests and the offline test harness
+#[cfg(any(test, feature = "test-harness"))]
+mod mock;
+
+#[cfg(any(test, feature = "test-harness"))]
+pub use mock::*;
 
 // Virtual device adapter will be platform-specific
 #[cfg(target_os = "windows")]
@@ -19,3 +40,33 @@ mod windows_virtual_output;
 
 #[cfg(target_os = "windows")]
 pub use windows_virtual_output::*;
+
+#[cfg(target_os = "windows")]
+mod windows_app_capture;
+
+#[cfg(target_os = "windows")]
+pub use windows_app_capture::*;
+
+#[cfg(target_os = "windows")]
+mod windows_endpoint_info;
+
+#[cfg(target_os = "windows")]
+pub use windows_endpoint_info::*;
+
+#[cfg(target_os = "windows")]
+mod windows_endpoint_mute;
+
+#[cfg(target_os = "windows")]
+pub use windows_endpoint_mute::*;
+
+#[cfg(target_os = "windows")]
+mod windows_keyboard_hook;
+
+#[cfg(target_os = "windows")]
+pub use windows_keyboard_hook::*;
+
+#[cfg(target_os = "windows")]
+mod windows_key_state;
+
+#[cfg(target_os = "windows")]
+pub use windows_key_state::*;
@@ -1,16 +1,21 @@
 //! Rodio-based file decoder adapter
+//!
+//! Seeks through rodio's `try_seek`. Formats it can't seek in are reopened
+//! and decoded up to the target, so a seek always lands.
 
 use crate::domain::{AudioBuffer, AudioFileFormat, AudioFormat, Sample};
 use crate::ports::{AudioFileMetadata, FileDecoder, FileDecoderError, FileDecoderFactory};
 use rodio::Source;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// File decoder adapter using Rodio
 pub struct RodioFileDecoder {
     source: Option<rodio::Decoder<BufReader<File>>>,
+    /// Open file, reopened by `reset`
+    path: Option<PathBuf>,
     metadata: Option<AudioFileMetadata>,
     position: Duration,
     finished: bool,
@@ -21,6 +26,7 @@ impl RodioFileDecoder {
     pub fn new() -> Self {
         Self {
             source: None,
+            path: None,
             metadata: None,
             position: Duration::ZERO,
             finished: false,
@@ -80,6 +86,7 @@ impl FileDecoder for RodioFileDecoder {
         };
 
         self.source = Some(decoder);
+        self.path = Some(path.to_path_buf());
         self.metadata = Some(metadata.clone());
         self.position = Duration::ZERO;
         self.finished = false;
@@ -115,9 +122,27 @@ impl FileDecoder for RodioFileDecoder {
         )))
     }
 
-    fn seek(&mut self, _position: Duration) -> Result<(), FileDecoderError> {
-        // Rodio doesn't support seeking in all formats
-        Err(FileDecoderError::DecodeError("Seeking not supported".into()))
+    fn seek(&mut self, position: Duration) -> Result<(), FileDecoderError> {
+        let source = self.source.as_mut()
+            .ok_or_else(|| FileDecoderError::DecodeError("No file opened".into()))?;
+
+        if let Err(e) = source.try_seek(position) {
+            tracing::debug!(error = %e, "Seeking by decoding from the start");
+            self.reset()?;
+            let format = self.metadata.as_ref().map(|m| m.audio_format).unwrap_or_default();
+            let frames = (position.as_secs_f64() * format.sample_rate as f64) as usize;
+            let source = self.source.as_mut()
+                .ok_or_else(|| FileDecoderError::SeekFailed("File closed while seeking".into()))?;
+            let skipped = source.by_ref().take(frames * format.channels as usize).count();
+            if skipped < frames * format.channels as usize {
+                self.finished = true;
+            }
+        } else {
+            self.finished = false;
+        }
+
+        self.position = position;
+        Ok(())
     }
 
     fn position(&self) -> Duration {
@@ -128,17 +153,23 @@ impl FileDecoder for RodioFileDecoder {
         self.metadata.as_ref().map(|m| m.duration)
     }
 
+    fn metadata(&self) -> Option<AudioFileMetadata> {
+        self.metadata.clone()
+    }
+
     fn is_finished(&self) -> bool {
         self.finished
     }
 
     fn reset(&mut self) -> Result<(), FileDecoderError> {
-        // Would need to reopen the file
-        Err(FileDecoderError::DecodeError("Reset requires reopening file".into()))
+        let path = self.path.clone()
+            .ok_or_else(|| FileDecoderError::DecodeError("No file opened".into()))?;
+        self.open(&path).map(|_| ())
     }
 
     fn close(&mut self) {
         self.source = None;
+        self.path = None;
         self.metadata = None;
         self.position = Duration::ZERO;
         self.finished = true;
@@ -195,6 +226,37 @@ mod tests {
         assert!(!decoder.is_finished());
     }
 
+    #[test]
+    fn test_seek_and_reset() {
+        let path = std::env::temp_dir().join("rodio_decoder_seek.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for frame in 0..8000 {
+            writer.write_sample(frame as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut decoder = RodioFileDecoder::new();
+        decoder.open(&path).unwrap();
+        while decoder.read_next().unwrap().is_some() {}
+        assert!(decoder.is_finished());
+
+        decoder.seek(Duration::from_millis(250)).unwrap();
+        assert_eq!(decoder.position(), Duration::from_millis(250));
+        let buffer = decoder.read_next().unwrap().unwrap();
+        assert_eq!(buffer.to_raw_f32()[0], 2000.0 / 32768.0);
+
+        decoder.reset().unwrap();
+        assert!(!decoder.is_finished());
+        assert_eq!(decoder.read_next().unwrap().unwrap().to_raw_f32()[0], 0.0);
+        let _ = std::fs::remove_file(path);
+    }
+
     #[test]
     fn test_factory_supported_formats() {
         let factory = RodioDecoderFactory::new();
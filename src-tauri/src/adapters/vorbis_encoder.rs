@@ -0,0 +1,80 @@
+//! Ogg Vorbis encoder adapter using vorbis_rs
+
+use crate::domain::{AudioBuffer, AudioFileFormat};
+use crate::ports::{FileEncoder, FileEncoderError};
+use std::fs::File;
+use std::num::{NonZeroU32, NonZeroU8};
+use std::path::Path;
+use vorbis_rs::VorbisEncoderBuilder;
+
+/// Frames handed to the encoder per call
+const BLOCK_FRAMES: usize = 4096;
+
+/// Ogg Vorbis file encoder
+///
+/// Vorbis is lossy and float-based, so `bits_per_sample` is ignored.
+pub struct VorbisEncoder;
+
+impl VorbisEncoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for VorbisEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileEncoder for VorbisEncoder {
+    fn encode(
+        &self,
+        path: &Path,
+        buffer: &AudioBuffer,
+        _bits_per_sample: u16,
+    ) -> Result<(), FileEncoderError> {
+        let sample_rate = NonZeroU32::new(buffer.sample_rate())
+            .ok_or_else(|| FileEncoderError::UnsupportedFormat("0 Hz".into()))?;
+        let channels = u8::try_from(buffer.channels())
+            .ok()
+            .and_then(NonZeroU8::new)
+            .ok_or_else(|| FileEncoderError::UnsupportedFormat(format!("{} channels", buffer.channels())))?;
+
+        let file = File::create(path).map_err(|e| FileEncoderError::IoError(e.to_string()))?;
+        let mut encoder = VorbisEncoderBuilder::new(sample_rate, channels, file)
+            .and_then(|mut builder| builder.build())
+            .map_err(|e| FileEncoderError::EncodeError(e.to_string()))?;
+
+        // Vorbis takes planar audio
+        let channel_count = channels.get() as usize;
+        for block in buffer.samples().chunks(BLOCK_FRAMES * channel_count) {
+            let mut planar = vec![Vec::with_capacity(BLOCK_FRAMES); channel_count];
+            for frame in block.chunks_exact(channel_count) {
+                for (ch, sample) in frame.iter().enumerate() {
+                    planar[ch].push(sample.value());
+                }
+            }
+            encoder
+                .encode_audio_block(&planar)
+                .map_err(|e| FileEncoderError::EncodeError(e.to_string()))?;
+        }
+
+        encoder
+            .finish()
+            .map_err(|e| FileEncoderError::EncodeError(e.to_string()))?;
+
+        tracing::info!(
+            "Ogg Vorbis written: {} ({} frames, {}Hz, {}ch)",
+            path.display(),
+            buffer.frame_count(),
+            buffer.sample_rate(),
+            buffer.channels()
+        );
+        Ok(())
+    }
+
+    fn supports_format(&self, format: AudioFileFormat) -> bool {
+        format == AudioFileFormat::Ogg
+    }
+}
@@ -0,0 +1,82 @@
+//! WebDAV storage adapter
+
+use crate::ports::{CloudStorage, CloudStorageError};
+use reqwest::blocking::{Client, Response};
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Stores files in one folder of a WebDAV server
+pub struct WebDavStorage {
+    client: Client,
+    base_url: String,
+    username: String,
+    password: String,
+}
+
+impl WebDavStorage {
+    pub fn new(url: &str, username: &str, password: &str) -> Result<Self, CloudStorageError> {
+        let client = Client::builder()
+            .timeout(TIMEOUT)
+            .build()
+            .map_err(|e| CloudStorageError::Connection(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            base_url: format!("{}/", url.trim_end_matches('/')),
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    fn send(&self, method: Method, url: &str, body: Option<(Vec<u8>, &str)>) -> Result<Response, CloudStorageError> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .basic_auth(&self.username, Some(&self.password));
+        if let Some((data, content_type)) = body {
+            request = request.header("Content-Type", content_type).body(data);
+        }
+
+        let response = request
+            .send()
+            .map_err(|e| CloudStorageError::Connection(e.to_string()))?;
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(CloudStorageError::Unauthorized),
+            _ => Ok(response),
+        }
+    }
+}
+
+impl CloudStorage for WebDavStorage {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CloudStorageError> {
+        let response = self.send(Method::GET, &format!("{}{}", self.base_url, key), None)?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => response
+                .bytes()
+                .map(|b| Some(b.to_vec()))
+                .map_err(|e| CloudStorageError::Connection(e.to_string())),
+            status => Err(CloudStorageError::Server(format!("GET {}: {}", key, status))),
+        }
+    }
+
+    fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), CloudStorageError> {
+        let url = format!("{}{}", self.base_url, key);
+        let mut response = self.send(Method::PUT, &url, Some((data.clone(), content_type)))?;
+
+        // The sync folder doesn't exist yet: create it and retry once
+        if response.status() == StatusCode::CONFLICT || response.status() == StatusCode::NOT_FOUND {
+            let mkcol = Method::from_bytes(b"MKCOL").expect("valid method");
+            self.send(mkcol, &self.base_url, None)?;
+            response = self.send(Method::PUT, &url, Some((data, content_type)))?;
+        }
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(CloudStorageError::Server(format!("PUT {}: {}", key, response.status())))
+        }
+    }
+}
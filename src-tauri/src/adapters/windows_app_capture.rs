@@ -0,0 +1,306 @@
+//! Windows application capture adapter
+//!
+//! Captures a single process tree through WASAPI process loopback
+//! (`VAD\Process_Loopback`, Windows 10 build 20348 and later). Sessions of
+//! the default render endpoint list the applications that play audio.
+
+use crate::ports::{AppAudioSink, AppCapture, AppCaptureError, AppCaptureStream, CapturableApp};
+use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+use windows::core::{implement, Interface, IUnknown, HRESULT, PWSTR, PROPVARIANT};
+use windows::Win32::Foundation::{CloseHandle, HANDLE, S_OK};
+use windows::Win32::Media::Audio::{
+    eConsole, eRender, ActivateAudioInterfaceAsync, AudioSessionStateExpired, IActivateAudioInterfaceAsyncOperation,
+    IActivateAudioInterfaceCompletionHandler, IActivateAudioInterfaceCompletionHandler_Impl, IAudioCaptureClient,
+    IAudioClient, IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator, MMDeviceEnumerator,
+    AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM,
+    AUDCLNT_STREAMFLAGS_EVENTCALLBACK, AUDCLNT_STREAMFLAGS_LOOPBACK, AUDIOCLIENT_ACTIVATION_PARAMS,
+    AUDIOCLIENT_ACTIVATION_PARAMS_0, AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK, AUDIOCLIENT_PROCESS_LOOPBACK_PARAMS,
+    PROCESS_LOOPBACK_MODE_INCLUDE_TARGET_PROCESS_TREE, VIRTUAL_AUDIO_DEVICE_PROCESS_LOOPBACK, WAVEFORMATEX,
+};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED};
+use windows::Win32::System::Threading::{
+    CreateEventW, OpenProcess, QueryFullProcessImageNameW, WaitForSingleObject, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::System::Variant::VT_BLOB;
+
+/// `WAVE_FORMAT_IEEE_FLOAT`; process loopback converts to the format asked for
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// Buffer requested from the audio client, in 100 ns units (20 ms)
+const BUFFER_DURATION: i64 = 200_000;
+
+/// How long the capture thread waits for a packet before checking for stop
+const WAIT_MS: u32 = 100;
+
+/// Application capture adapter using WASAPI process loopback
+pub struct WindowsAppCapture;
+
+impl WindowsAppCapture {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WindowsAppCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppCapture for WindowsAppCapture {
+    fn list_apps(&self) -> Result<Vec<CapturableApp>, AppCaptureError> {
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(capture_error)?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .map_err(capture_error)?;
+            let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None).map_err(capture_error)?;
+            let sessions = manager.GetSessionEnumerator().map_err(capture_error)?;
+
+            let mut apps: Vec<CapturableApp> = Vec::new();
+            for i in 0..sessions.GetCount().map_err(capture_error)? {
+                let Ok(control) = sessions.GetSession(i).and_then(|s| s.cast::<IAudioSessionControl2>()) else {
+                    continue;
+                };
+                if control.IsSystemSoundsSession() == S_OK
+                    || control.GetState().map_or(true, |state| state == AudioSessionStateExpired)
+                {
+                    continue;
+                }
+                let Ok(process_id) = control.GetProcessId() else {
+                    continue;
+                };
+                if process_id == 0 || apps.iter().any(|app| app.process_id == process_id) {
+                    continue;
+                }
+                if let Some(name) = process_name(process_id) {
+                    apps.push(CapturableApp { process_id, name });
+                }
+            }
+
+            apps.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+            Ok(apps)
+        }
+    }
+
+    fn start(
+        &self,
+        process_id: u32,
+        sample_rate: u32,
+        channels: u16,
+        mut sink: AppAudioSink,
+    ) -> Result<AppCaptureStream, AppCaptureError> {
+        if process_name(process_id).is_none() {
+            return Err(AppCaptureError::ProcessNotFound(process_id));
+        }
+
+        // The client lives on the capture thread; its setup result comes back here
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name(format!("app-capture-{}", process_id))
+            .spawn(move || {
+                let (client, capture, event) = match unsafe { open_loopback(process_id, sample_rate, channels) } {
+                    Ok(opened) => {
+                        let _ = ready_tx.send(Ok(()));
+                        opened
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+
+                let channels = channels as usize;
+                let mut silence = Vec::new();
+                while !stopped.load(Ordering::Relaxed) {
+                    unsafe {
+                        WaitForSingleObject(event, WAIT_MS);
+                        while let Ok(frames) = capture.GetNextPacketSize() {
+                            if frames == 0 {
+                                break;
+                            }
+                            let mut data = std::ptr::null_mut();
+                            let mut frames = 0;
+                            let mut flags = 0;
+                            if capture.GetBuffer(&mut data, &mut frames, &mut flags, None, None).is_err() {
+                                break;
+                            }
+
+                            let len = frames as usize * channels;
+                            if flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0 || data.is_null() {
+                                silence.clear();
+                                silence.resize(len, 0.0);
+                                sink(&silence);
+                            } else {
+                                sink(std::slice::from_raw_parts(data as *const f32, len));
+                            }
+                            let _ = capture.ReleaseBuffer(frames);
+                        }
+                    }
+                }
+
+                unsafe {
+                    let _ = client.Stop();
+                    let _ = CloseHandle(event);
+                }
+            })
+            .map_err(|e| AppCaptureError::CaptureFailed(e.to_string()))?;
+
+        let stream = AppCaptureStream::new(stop, thread);
+        match ready_rx.recv_timeout(Duration::from_secs(5)) {
+            Ok(Ok(())) => Ok(stream),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(AppCaptureError::CaptureFailed("Process loopback did not start".into())),
+        }
+    }
+}
+
+/// Signals the waiting thread once the async activation completes
+#[implement(IActivateAudioInterfaceCompletionHandler)]
+struct ActivationHandler(Arc<(Mutex<bool>, Condvar)>);
+
+impl IActivateAudioInterfaceCompletionHandler_Impl for ActivationHandler_Impl {
+    fn ActivateCompleted(
+        &self,
+        _operation: Option<&IActivateAudioInterfaceAsyncOperation>,
+    ) -> windows::core::Result<()> {
+        // Never panic across the COM boundary; a poisoned flag only times out
+        let (done, signal) = &*self.0;
+        if let Ok(mut done) = done.lock() {
+            *done = true;
+        }
+        signal.notify_all();
+        Ok(())
+    }
+}
+
+/// Activate a loopback client for a process tree and start it
+unsafe fn open_loopback(
+    process_id: u32,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<(IAudioClient, IAudioCaptureClient, HANDLE), AppCaptureError> {
+    let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+    let params = AUDIOCLIENT_ACTIVATION_PARAMS {
+        ActivationType: AUDIOCLIENT_ACTIVATION_TYPE_PROCESS_LOOPBACK,
+        Anonymous: AUDIOCLIENT_ACTIVATION_PARAMS_0 {
+            ProcessLoopbackParams: AUDIOCLIENT_PROCESS_LOOPBACK_PARAMS {
+                TargetProcessId: process_id,
+                ProcessLoopbackMode: PROCESS_LOOPBACK_MODE_INCLUDE_TARGET_PROCESS_TREE,
+            },
+        },
+    };
+    // A blob pointing at `params`, which stays owned here
+    let raw = windows::core::imp::PROPVARIANT {
+        Anonymous: windows::core::imp::PROPVARIANT_0 {
+            Anonymous: windows::core::imp::PROPVARIANT_0_0 {
+                vt: VT_BLOB.0,
+                wReserved1: 0,
+                wReserved2: 0,
+                wReserved3: 0,
+                Anonymous: windows::core::imp::PROPVARIANT_0_0_0 {
+                    blob: windows::core::imp::BLOB {
+                        cbSize: std::mem::size_of::<AUDIOCLIENT_ACTIVATION_PARAMS>() as u32,
+                        pBlobData: &params as *const _ as *mut u8,
+                    },
+                },
+            },
+        },
+    };
+    let activation = ManuallyDrop::new(PROPVARIANT::from_raw(raw));
+
+    let completed = Arc::new((Mutex::new(false), Condvar::new()));
+    let handler: IActivateAudioInterfaceCompletionHandler = ActivationHandler(completed.clone()).into();
+    let operation = ActivateAudioInterfaceAsync(
+        VIRTUAL_AUDIO_DEVICE_PROCESS_LOOPBACK,
+        &IAudioClient::IID,
+        Some(&*activation as *const PROPVARIANT),
+        &handler,
+    )
+    .map_err(capture_error)?;
+
+    let (done, signal) = &*completed;
+    let done = done.lock().map_err(|_| activation_poisoned())?;
+    let (done, _) = signal
+        .wait_timeout_while(done, Duration::from_secs(3), |done| !*done)
+        .map_err(|_| activation_poisoned())?;
+    if !*done {
+        return Err(AppCaptureError::CaptureFailed("Process loopback activation timed out".into()));
+    }
+
+    let mut result = HRESULT(0);
+    let mut activated: Option<IUnknown> = None;
+    operation
+        .GetActivateResult(&mut result, &mut activated)
+        .map_err(capture_error)?;
+    result.ok().map_err(capture_error)?;
+    let client: IAudioClient = activated
+        .ok_or_else(|| AppCaptureError::CaptureFailed("No audio client".into()))?
+        .cast()
+        .map_err(capture_error)?;
+
+    let block_align = channels * 4;
+    let format = WAVEFORMATEX {
+        wFormatTag: WAVE_FORMAT_IEEE_FLOAT,
+        nChannels: channels,
+        nSamplesPerSec: sample_rate,
+        nAvgBytesPerSec: sample_rate * block_align as u32,
+        nBlockAlign: block_align,
+        wBitsPerSample: 32,
+        cbSize: 0,
+    };
+    client
+        .Initialize(
+            AUDCLNT_SHAREMODE_SHARED,
+            AUDCLNT_STREAMFLAGS_LOOPBACK | AUDCLNT_STREAMFLAGS_EVENTCALLBACK | AUDCLNT_STREAMFLAGS_AUTOCONVERTPCM,
+            BUFFER_DURATION,
+            0,
+            &format,
+            None,
+        )
+        .map_err(capture_error)?;
+
+    let event = CreateEventW(None, false, false, None).map_err(capture_error)?;
+    client.SetEventHandle(event).map_err(capture_error)?;
+    let capture: IAudioCaptureClient = client.GetService().map_err(capture_error)?;
+    client.Start().map_err(capture_error)?;
+
+    Ok((client, capture, event))
+}
+
+/// Executable name of a process without path or extension
+fn process_name(process_id: u32) -> Option<String> {
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let queried = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut len);
+        let _ = CloseHandle(process);
+        queried.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        std::path::Path::new(&path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(String::from)
+    }
+}
+
+fn activation_poisoned() -> AppCaptureError {
+    AppCaptureError::CaptureFailed("Process loopback activation state is poisoned".into())
+}
+
+fn capture_error(error: windows::core::Error) -> AppCaptureError {
+    AppCaptureError::CaptureFailed(error.to_string())
+}
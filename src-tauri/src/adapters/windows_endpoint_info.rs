@@ -0,0 +1,45 @@
+//! Windows endpoint info adapter
+//!
+//! Reads which audio adapter an endpoint belongs to, as Device Manager
+//! names it (e.g. "Realtek(R) Audio" or "VB-Audio Virtual Cable"), so bug
+//! reports show the driver a device runs on.
+
+use crate::domain::find_device_name;
+use windows::Win32::Devices::FunctionDiscovery::{PKEY_DeviceInterface_FriendlyName, PKEY_Device_FriendlyName};
+use windows::Win32::Media::Audio::{eAll, IMMDeviceEnumerator, MMDeviceEnumerator, DEVICE_STATE_ACTIVE};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ};
+
+/// Adapter name of the active endpoint called `device_name`, if found
+pub fn endpoint_adapter_name(device_name: &str) -> Option<String> {
+    unsafe {
+        // Commands run on pool threads that may not have COM yet
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).ok()?;
+        let devices = enumerator.EnumAudioEndpoints(eAll, DEVICE_STATE_ACTIVE).ok()?;
+
+        let mut names = Vec::new();
+        let mut adapters = Vec::new();
+        for i in 0..devices.GetCount().ok()? {
+            let Ok(properties) = devices.Item(i).and_then(|device| device.OpenPropertyStore(STGM_READ)) else {
+                continue;
+            };
+            names.push(
+                properties
+                    .GetValue(&PKEY_Device_FriendlyName)
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+            );
+            adapters.push(
+                properties
+                    .GetValue(&PKEY_DeviceInterface_FriendlyName)
+                    .ok()
+                    .map(|value| value.to_string()),
+            );
+        }
+
+        // Saved names may differ in normalization or driver suffix
+        let index = find_device_name(names.iter().map(String::as_str), device_name)?;
+        adapters.swap_remove(index)
+    }
+}
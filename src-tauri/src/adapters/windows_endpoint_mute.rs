@@ -0,0 +1,97 @@
+//! Windows capture endpoint mute adapter
+//!
+//! Reads and sets the mute flag of a WASAPI capture endpoint through
+//! `IAudioEndpointVolume`. This is the same flag toggled by the Windows
+//! mic-mute key and most headset mute buttons.
+
+use crate::domain::find_device_name;
+use crate::ports::{SystemMicMute, SystemMuteError};
+use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+use windows::Win32::Media::Audio::{
+    eCapture, eConsole, IMMDevice, IMMDeviceEnumerator, MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+};
+
+/// System mic mute adapter using the Windows Core Audio API
+pub struct WindowsEndpointMute;
+
+impl WindowsEndpointMute {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Find an active capture endpoint by friendly name
+    fn find_device(device_name: &str) -> Result<IMMDevice, SystemMuteError> {
+        unsafe {
+            // Each polling thread needs COM; repeated calls are harmless
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(system_error)?;
+
+            if device_name.is_empty() || device_name == "default" {
+                return enumerator
+                    .GetDefaultAudioEndpoint(eCapture, eConsole)
+                    .map_err(system_error);
+            }
+
+            let devices = enumerator
+                .EnumAudioEndpoints(eCapture, DEVICE_STATE_ACTIVE)
+                .map_err(system_error)?;
+
+            let mut endpoints = Vec::new();
+            let mut names = Vec::new();
+            for i in 0..devices.GetCount().map_err(system_error)? {
+                let device = devices.Item(i).map_err(system_error)?;
+                let properties = device.OpenPropertyStore(STGM_READ).map_err(system_error)?;
+                names.push(
+                    properties
+                        .GetValue(&PKEY_Device_FriendlyName)
+                        .map_err(system_error)?
+                        .to_string(),
+                );
+                endpoints.push(device);
+            }
+
+            // Saved names may differ in normalization or driver suffix
+            if let Some(index) = find_device_name(names.iter().map(String::as_str), device_name) {
+                return Ok(endpoints.swap_remove(index));
+            }
+
+            Err(SystemMuteError::DeviceNotFound(device_name.to_string()))
+        }
+    }
+
+    fn endpoint_volume(device_name: &str) -> Result<IAudioEndpointVolume, SystemMuteError> {
+        let device = Self::find_device(device_name)?;
+        unsafe { device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None) }.map_err(system_error)
+    }
+}
+
+impl Default for WindowsEndpointMute {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemMicMute for WindowsEndpointMute {
+    fn is_muted(&self, device_name: &str) -> Result<bool, SystemMuteError> {
+        let volume = Self::endpoint_volume(device_name)?;
+        let muted = unsafe { volume.GetMute() }.map_err(system_error)?;
+        Ok(muted.as_bool())
+    }
+
+    fn set_muted(&self, device_name: &str, muted: bool) -> Result<(), SystemMuteError> {
+        let volume = Self::endpoint_volume(device_name)?;
+        unsafe { volume.SetMute(BOOL::from(muted), std::ptr::null()) }.map_err(system_error)
+    }
+}
+
+fn system_error(error: windows::core::Error) -> SystemMuteError {
+    SystemMuteError::SystemError(error.to_string())
+}
+
@@ -0,0 +1,101 @@
+//! Windows key state adapter
+//!
+//! Polls keys with `GetAsyncKeyState`, which reads the physical state
+//! whatever window has focus. Mouse side buttons count as keys
+//! (`Mouse4`, `Mouse5`), since they are popular push-to-talk buttons.
+
+use crate::domain::KeyCombo;
+use crate::ports::KeyState;
+use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
+
+const VK_SHIFT: i32 = 0x10;
+const VK_CONTROL: i32 = 0x11;
+const VK_MENU: i32 = 0x12;
+const VK_LWIN: i32 = 0x5B;
+const VK_RWIN: i32 = 0x5C;
+
+/// Virtual-key code of a key name as spelled in a [`KeyCombo`]
+fn virtual_key(key: &str) -> Option<i32> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return match c {
+            'a'..='z' => Some(c.to_ascii_uppercase() as i32),
+            '0'..='9' => Some(c as i32),
+            '`' => Some(0xC0),
+            '-' => Some(0xBD),
+            '=' => Some(0xBB),
+            '[' => Some(0xDB),
+            ']' => Some(0xDD),
+            ';' => Some(0xBA),
+            '\'' => Some(0xDE),
+            ',' => Some(0xBC),
+            '.' => Some(0xBE),
+            '/' => Some(0xBF),
+            '\\' => Some(0xDC),
+            _ => None,
+        };
+    }
+
+    if let Some(n) = key.strip_prefix('f').and_then(|n| n.parse::<i32>().ok()) {
+        return (1..=24).contains(&n).then_some(0x6F + n);
+    }
+    let code = match key {
+        "mouse4" => 0x05,
+        "mouse5" => 0x06,
+        "backspace" => 0x08,
+        "tab" => 0x09,
+        "enter" => 0x0D,
+        "pause" => 0x13,
+        "capslock" => 0x14,
+        "escape" => 0x1B,
+        "space" => 0x20,
+        "pageup" => 0x21,
+        "pagedown" => 0x22,
+        "end" => 0x23,
+        "home" => 0x24,
+        "arrowleft" => 0x25,
+        "arrowup" => 0x26,
+        "arrowright" => 0x27,
+        "arrowdown" => 0x28,
+        "insert" => 0x2D,
+        "delete" => 0x2E,
+        "numlock" => 0x90,
+        "scrolllock" => 0x91,
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn is_down(vk: i32) -> bool {
+    // The high bit is set while the key is down
+    unsafe { GetAsyncKeyState(vk) as u16 & 0x8000 != 0 }
+}
+
+/// Key state polled from the OS
+#[derive(Debug, Default)]
+pub struct WindowsKeyState;
+
+impl WindowsKeyState {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl KeyState for WindowsKeyState {
+    fn supports(&self, combo: &KeyCombo) -> bool {
+        virtual_key(&combo.key).is_some()
+    }
+
+    fn is_held(&self, combo: &KeyCombo) -> bool {
+        let Some(key) = virtual_key(&combo.key) else {
+            return false;
+        };
+        let modifiers = [
+            (combo.ctrl, is_down(VK_CONTROL)),
+            (combo.alt, is_down(VK_MENU)),
+            (combo.shift, is_down(VK_SHIFT)),
+            (combo.meta, is_down(VK_LWIN) || is_down(VK_RWIN)),
+        ];
+        modifiers.iter().all(|&(needed, held)| !needed || held) && is_down(key)
+    }
+}
@@ -0,0 +1,105 @@
+//! Windows keyboard hook adapter
+//!
+//! Reports key presses system-wide through a low-level keyboard hook
+//! (`WH_KEYBOARD_LL`), for the keystroke-timed keyboard suppression. Only
+//! the moment of a press is passed on, never which key it was; held keys
+//! auto-repeating don't count as new presses.
+
+use std::sync::Mutex;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, PostThreadMessageW, SetWindowsHookExW, UnhookWindowsHookEx, HC_ACTION, HHOOK,
+    KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_QUIT, WM_SYSKEYDOWN, WM_SYSKEYUP,
+};
+
+type PressCallback = Box<dyn Fn() + Send>;
+
+/// Called on each press; the hook procedure has no context of its own
+static ON_PRESS: Mutex<Option<PressCallback>> = Mutex::new(None);
+/// Virtual-key codes held down, to skip auto-repeat
+static HELD_KEYS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+/// Thread running the hook's message loop
+static HOOK_THREAD: Mutex<Option<u32>> = Mutex::new(None);
+
+/// System-wide key press hook
+pub struct WindowsKeyboardHook;
+
+impl WindowsKeyboardHook {
+    /// Install the hook, or swap the callback of the installed one
+    pub fn start(on_press: impl Fn() + Send + 'static) -> Result<(), String> {
+        *ON_PRESS.lock().map_err(|e| e.to_string())? = Some(Box::new(on_press));
+
+        let mut thread = HOOK_THREAD.lock().map_err(|e| e.to_string())?;
+        if thread.is_some() {
+            return Ok(());
+        }
+
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("keyboard-hook".into())
+            .spawn(move || unsafe {
+                let module = GetModuleHandleW(PCWSTR::null()).map(HINSTANCE::from).unwrap_or_default();
+                let hook = match SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), module, 0) {
+                    Ok(hook) => hook,
+                    Err(e) => {
+                        let _ = started_tx.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                let _ = started_tx.send(Ok(GetCurrentThreadId()));
+
+                // The hook is called on this thread, from its message loop
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {}
+                let _ = UnhookWindowsHookEx(hook);
+            })
+            .map_err(|e| e.to_string())?;
+
+        *thread = Some(started_rx.recv().map_err(|e| e.to_string())??);
+        Ok(())
+    }
+
+    /// Remove the hook; no-op if it isn't installed
+    pub fn stop() {
+        let Some(thread_id) = HOOK_THREAD.lock().ok().and_then(|mut thread| thread.take()) else {
+            return;
+        };
+        unsafe {
+            let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        if let Ok(mut on_press) = ON_PRESS.lock() {
+            *on_press = None;
+        }
+        if let Ok(mut held) = HELD_KEYS.lock() {
+            held.clear();
+        }
+    }
+}
+
+unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let key = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        let pressed = match (wparam.0 as u32, HELD_KEYS.lock()) {
+            (WM_KEYDOWN | WM_SYSKEYDOWN, Ok(mut held)) if !held.contains(&key.vkCode) => {
+                held.push(key.vkCode);
+                true
+            }
+            (WM_KEYUP | WM_SYSKEYUP, Ok(mut held)) => {
+                held.retain(|&vk| vk != key.vkCode);
+                false
+            }
+            _ => false,
+        };
+        if pressed {
+            if let Ok(on_press) = ON_PRESS.lock() {
+                if let Some(on_press) = on_press.as_ref() {
+                    on_press();
+                }
+            }
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
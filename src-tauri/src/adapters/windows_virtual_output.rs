@@ -34,6 +34,11 @@ impl WindowsVirtualOutput {
         self
     }
 
+    /// Whether a device of this name belongs to the Virtual Audio Driver
+    pub fn is_driver_device(name: &str) -> bool {
+        name.contains("Virtual Audio")
+    }
+
     /// Check if the Virtual Audio Driver is installed
     pub fn check_driver_installed() -> bool {
         // In a full implementation, this would query the Windows audio
@@ -48,7 +53,7 @@ impl WindowsVirtualOutput {
             if let Ok(devices) = host.output_devices() {
                 for device in devices {
                     if let Ok(name) = device.name() {
-                        if name.contains("Virtual Audio") {
+                        if Self::is_driver_device(&name) {
                             return true;
                         }
                     }
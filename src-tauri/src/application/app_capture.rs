@@ -0,0 +1,133 @@
+//! App capture service - Captured applications as mixer channels
+//!
+//! Each application channel owns a capture of one process tree (e.g.
+//! Spotify without the game) feeding a ring buffer the engine mixes in.
+//! Captures run at the engine's format, so nothing is resampled.
+
+use crate::application::audio_processing::AppSource;
+use crate::ports::{AppCapture, AppCaptureError, AppCaptureStream, CapturableApp};
+use ringbuf::traits::{Producer, Split};
+use ringbuf::HeapRb;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Capture audio buffered for the engine, in milliseconds
+const CAPTURE_BUFFER_MS: usize = 500;
+
+/// Capture allowed to queue up before the oldest is dropped
+const MAX_QUEUED_MS: usize = 60;
+
+/// Running captures of application channels
+pub struct AppCaptureService {
+    backend: Option<Box<dyn AppCapture>>,
+    streams: Mutex<HashMap<String, AppCaptureStream>>,
+}
+
+impl AppCaptureService {
+    pub fn new(backend: Box<dyn AppCapture>) -> Self {
+        Self {
+            backend: Some(backend),
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A service without capture, for platforms without process loopback
+    pub fn disabled() -> Self {
+        Self {
+            backend: None,
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The capture backend of the current platform
+    pub fn for_platform() -> Self {
+        #[cfg(target_os = "windows")]
+        {
+            Self::new(Box::new(crate::adapters::WindowsAppCapture::new()))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            Self::disabled()
+        }
+    }
+
+    fn backend(&self) -> Result<&dyn AppCapture, AppCaptureError> {
+        self.backend.as_deref().ok_or(AppCaptureError::NotSupported)
+    }
+
+    /// Applications currently playing audio
+    pub fn list_apps(&self) -> Result<Vec<CapturableApp>, AppCaptureError> {
+        self.backend()?.list_apps()
+    }
+
+    /// Capture `process_id` for `channel_id`, replacing its previous capture
+    ///
+    /// Returns the source to hand to the engine.
+    pub fn start(
+        &self,
+        channel_id: &str,
+        process_id: u32,
+        sample_rate: u32,
+        channels: u16,
+        gain: f32,
+    ) -> Result<AppSource, AppCaptureError> {
+        let samples_per_ms = sample_rate as usize * channels as usize / 1000;
+        let (mut producer, consumer) = HeapRb::<f32>::new(CAPTURE_BUFFER_MS * samples_per_ms).split();
+
+        self.stop(channel_id);
+        let stream = self.backend()?.start(
+            process_id,
+            sample_rate,
+            channels,
+            Box::new(move |data| {
+                producer.push_slice(data);
+            }),
+        )?;
+        self.streams.lock().unwrap().insert(channel_id.to_string(), stream);
+
+        tracing::info!(channel = channel_id, process_id, "Application capture started");
+        Ok(AppSource::new(consumer, MAX_QUEUED_MS * samples_per_ms, gain))
+    }
+
+    /// Stop the capture of `channel_id`, if any
+    pub fn stop(&self, channel_id: &str) {
+        let stream = self.streams.lock().unwrap().remove(channel_id);
+        if stream.is_some() {
+            tracing::info!(channel = channel_id, "Application capture stopped");
+        }
+    }
+
+    pub fn is_capturing(&self, channel_id: &str) -> bool {
+        self.streams.lock().unwrap().contains_key(channel_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::MockAppCapture;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_channels_own_their_capture() {
+        let mut backend = MockAppCapture::new();
+        backend.expect_start().returning(|_, _, _, mut sink| {
+            sink(&[0.5; 4]);
+            let stop = Arc::new(AtomicBool::new(false));
+            Ok(AppCaptureStream::new(stop, std::thread::spawn(|| {})))
+        });
+        let service = AppCaptureService::new(Box::new(backend));
+
+        service.start("music", 42, 48_000, 2, 1.0).unwrap();
+        assert!(service.is_capturing("music"));
+        service.stop("music");
+        assert!(!service.is_capturing("music"));
+
+        assert!(matches!(
+            AppCaptureService::disabled().list_apps(),
+            Err(AppCaptureError::NotSupported)
+        ));
+    }
+}
@@ -0,0 +1,249 @@
+//! App info - Build and runtime environment, for the About dialog and bug reports
+//!
+//! Collected on demand: the version and build of the app, the audio host
+//! cpal runs on, the OS, the devices the engine is mixing on (or the
+//! selected ones) with their drivers, and the update channel. The same
+//! snapshot is attached to Sentry reports, on every start, so nothing here
+//! enumerates devices: the OS version and drivers are read once per run.
+
+use crate::application::audio_engine::StreamSetup;
+use crate::domain::{AudioDevice, AudioSettings, UpdateChannel, VirtualDeviceSettings};
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// How the running binary was built
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    /// "debug" or "release"
+    pub profile: &'static str,
+    pub target_os: &'static str,
+    pub target_arch: &'static str,
+    /// Cargo features compiled in
+    pub features: Vec<&'static str>,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+            target_os: std::env::consts::OS,
+            target_arch: std::env::consts::ARCH,
+            features: enabled_features(),
+        }
+    }
+}
+
+/// Role of a device in the running mix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceRole {
+    Input,
+    Output,
+    Monitor,
+}
+
+/// A device the engine has open, or will open
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceInfo {
+    pub role: DeviceRole,
+    pub name: String,
+    /// Counted as a virtual cable by the virtual device settings
+    pub is_virtual: bool,
+    /// Audio adapter the device belongs to, naming its driver; None when
+    /// unknown or off Windows
+    pub driver: Option<String>,
+}
+
+/// Build and runtime environment of the app
+#[derive(Debug, Clone, Serialize)]
+pub struct AppInfo {
+    pub version: &'static str,
+    pub build: BuildInfo,
+    /// cpal host the engine opens devices on, e.g. "WASAPI"
+    pub audio_host: String,
+    /// OS name and version as the OS reports it, when it can be read
+    pub os_version: Option<String>,
+    /// Devices of the running mix, or the selected ones when stopped
+    pub devices: Vec<DeviceInfo>,
+    /// Mix rate of the output device, None when stopped
+    pub device_sample_rate: Option<u32>,
+    /// Frames per device buffer, None for the driver's default
+    pub buffer_frames: Option<u32>,
+    /// Whether the virtual audio driver is installed; None off Windows or
+    /// before the devices were first listed
+    pub virtual_driver_installed: Option<bool>,
+    pub update_channel: UpdateChannel,
+    pub safe_mode: bool,
+}
+
+impl AppInfo {
+    /// Snapshot of the environment with the engine's current setup
+    ///
+    /// `listed` is the device list last read, if any; it isn't read again.
+    pub fn collect(
+        setup: Option<&StreamSetup>,
+        selected: &AudioSettings,
+        listed: Option<&[AudioDevice]>,
+        device_sample_rate: Option<u32>,
+        virtual_devices: &VirtualDeviceSettings,
+        update_channel: UpdateChannel,
+        safe_mode: bool,
+    ) -> Self {
+        let names = match setup {
+            Some(setup) => [
+                Some(setup.input_device.as_str()),
+                Some(setup.output_device.as_str()),
+                setup.monitor_device.as_deref(),
+            ],
+            None => [
+                selected.input_device_id.as_deref(),
+                selected.output_device_id.as_deref(),
+                selected.preview_device_id.as_deref(),
+            ],
+        };
+        let devices = [DeviceRole::Input, DeviceRole::Output, DeviceRole::Monitor]
+            .into_iter()
+            .zip(names)
+            .filter_map(|(role, name)| {
+                let name = name?;
+                Some(DeviceInfo {
+                    role,
+                    name: name.to_string(),
+                    is_virtual: virtual_devices.is_virtual(name),
+                    driver: device_driver(name),
+                })
+            })
+            .collect();
+
+        #[cfg(target_os = "windows")]
+        let virtual_driver_installed = listed.map(|devices| {
+            devices
+                .iter()
+                .any(|device| crate::adapters::WindowsVirtualOutput::is_driver_device(device.name()))
+        });
+        #[cfg(not(target_os = "windows"))]
+        let virtual_driver_installed = {
+            let _ = listed;
+            None
+        };
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            build: BuildInfo::current(),
+            audio_host: cpal::default_host().id().name().to_string(),
+            os_version: os_version(),
+            devices,
+            device_sample_rate,
+            buffer_frames: setup.and_then(|setup| setup.buffer_frames),
+            virtual_driver_installed,
+            update_channel,
+            safe_mode,
+        }
+    }
+}
+
+/// Cargo features of this build
+pub fn enabled_features() -> Vec<&'static str> {
+    [
+        ("mp3", cfg!(feature = "mp3")),
+        ("ogg", cfg!(feature = "ogg")),
+        ("wav", cfg!(feature = "wav")),
+        ("test-harness", cfg!(feature = "test-harness")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect()
+}
+
+/// OS version as printed by the OS's own tools, read once per run
+fn os_version() -> Option<String> {
+    static OS_VERSION: OnceLock<Option<String>> = OnceLock::new();
+    OS_VERSION.get_or_init(read_os_version).clone()
+}
+
+fn read_os_version() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let output = {
+        use std::os::windows::process::CommandExt;
+
+        // Without it, a GUI app flashes a console window
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        std::process::Command::new("cmd")
+            .args(["/C", "ver"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+    };
+    #[cfg(target_os = "macos")]
+    let output = std::process::Command::new("sw_vers").arg("-productVersion").output();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let output = std::process::Command::new("uname").arg("-sr").output();
+
+    let text = String::from_utf8(output.ok()?.stdout).ok()?;
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// Driver of a device, read once per device and run
+#[cfg(target_os = "windows")]
+fn device_driver(name: &str) -> Option<String> {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    static DRIVERS: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+    let mut drivers = DRIVERS.get_or_init(Default::default).lock().unwrap();
+    drivers
+        .entry(name.to_string())
+        .or_insert_with(|| crate::adapters::endpoint_adapter_name(name))
+        .clone()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn device_driver(_name: &str) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MonitorSettings, OutputLayoutSettings};
+
+    #[test]
+    fn test_collect_lists_the_devices_of_the_mix() {
+        let setup = StreamSetup {
+            input_device: "Microphone (USB)".into(),
+            output_device: "CABLE Input (VB-Audio Virtual Cable)".into(),
+            sample_rate: 48000,
+            channels: 2,
+            highest_input_channel: None,
+            monitor_device: None,
+            monitor: MonitorSettings::default(),
+            output_layout: OutputLayoutSettings::default(),
+            buffer_frames: Some(256),
+        };
+        let virtual_devices = VirtualDeviceSettings::default();
+        let selected = AudioSettings {
+            input_device_id: Some("Headset Microphone".into()),
+            ..AudioSettings::new()
+        };
+
+        let info = AppInfo::collect(
+            Some(&setup),
+            &selected,
+            None,
+            Some(48000),
+            &virtual_devices,
+            UpdateChannel::Beta,
+            false,
+        );
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(info.build.features.contains(&"wav"));
+        assert_eq!(info.buffer_frames, Some(256));
+        let roles: Vec<_> = info.devices.iter().map(|device| (device.role, device.is_virtual)).collect();
+        assert_eq!(roles, vec![(DeviceRole::Input, false), (DeviceRole::Output, true)]);
+
+        // Stopped, the selected devices are listed
+        let stopped = AppInfo::collect(None, &selected, None, None, &virtual_devices, UpdateChannel::Stable, true);
+        let names: Vec<_> = stopped.devices.iter().map(|device| (device.role, device.name.as_str())).collect();
+        assert_eq!(names, vec![(DeviceRole::Input, "Headset Microphone")]);
+        assert!(stopped.safe_mode);
+    }
+}
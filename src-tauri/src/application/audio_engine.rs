@@ -3,21 +3,51 @@
 //! This module handles the real-time audio capture, mixing, and output.
 //! It uses ring buffers for lock-free communication between audio threads.
 
+use crate::application::audio_processing::{AppSource, EngineCore, PlayingSoundInfo};
+use crate::application::correlation::{traced_channel, Traced, TracedSender};
+use crate::application::engine_watchdog::{
+    spawn_watchdog, StalledStream, StreamHeartbeats, WatchdogDiagnostics, WatchedStreams,
+};
+use crate::application::null_audio::{NullAudioDevices, NullStreams};
+use crate::application::session_recorder::RecordingTap;
+use crate::application::sound_stream::SoundStream;
+use crate::domain::{
+    AgcSettings, CensorMode, DuckingSettings, GeneratorSettings, HighpassSettings, InputChannelMap, KeyboardSuppressionSettings, MicChainLayout, MonitorSettings, NoiseGateSettings, NoiseProfile, OutputFormatSettings, OutputLayoutSettings, SoundInsert,
+    SpectralQuality, VoiceActivitySettings, VoiceChangerSettings, find_device_name,
+};
+use crate::dsp::{ChannelRemixer, CodecSimulator, FeedbackDetector, PeakLimiter, VoiceActivityDetector};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use crossbeam_channel::{bounded, Receiver, Sender};
-use ringbuf::{HeapRb, traits::{Consumer, Producer, Split}};
+use crossbeam_channel::Receiver;
+use ringbuf::{HeapRb, traits::{Consumer, Observer, Producer, Split}};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tracing::Span;
 
 /// Size of the ring buffer in samples (not frames)
 const RING_BUFFER_SIZE: usize = 8192;
 
+/// Largest device buffer, in frames, the output callback is prepared for
+/// without allocating
+const MAX_CALLBACK_FRAMES: usize = 8192;
+
+/// Silence queued ahead of the mic when the device buffer size is left to
+/// the driver, in frames
+const DEFAULT_PREFILL_FRAMES: u32 = 512;
+
+/// Drift between the output and monitor clocks tolerated before the
+/// monitor drops audio to catch up
+const MONITOR_SLACK_MS: usize = 50;
+
 /// Level update interval in milliseconds (~30Hz)
 const LEVEL_UPDATE_INTERVAL_MS: u64 = 33;
 
+/// How often the engine publishes the playing sounds for pollers
+const PLAYING_SNAPSHOT_INTERVAL: Duration = Duration::from_millis(50);
+
 /// Commands that can be sent to the audio engine
 #[derive(Debug)]
 pub enum AudioEngineCommand {
@@ -27,27 +57,162 @@ pub enum AudioEngineCommand {
         output_device: String,
         sample_rate: u32,
         channels: u16,
+        /// Channels the output device is opened with; the mix is remixed
+        /// to them
+        output_layout: OutputLayoutSettings,
+        /// Frames per device buffer, the driver's default when None
+        buffer_frames: Option<u32>,
     },
     /// Stop mixing
     Stop,
-    /// Play an audio buffer (from a sound file)
+    /// Play an audio buffer (from a sound file); samples are converted
+    /// from their `sample_rate` and `channels` to the engine's
     PlaySound {
         id: String,
         samples: Vec<f32>,
+        sample_rate: u32,
+        channels: u16,
+        /// Volume of the sound (0.0 - 2.0), kept for later plays until
+        /// changed by SetSoundVolume
+        volume: f32,
+        /// Restart at the end until StopSound, e.g. ambience or a music bed
+        looping: bool,
+    },
+    /// Play a sound decoded while it plays, already in the engine's format
+    PlayStream {
+        id: String,
+        stream: SoundStream,
+        /// Volume of the sound (0.0 - 2.0), as for PlaySound
+        volume: f32,
+        /// The stream rewinds itself; kept so the mixer reports it as looping
+        looping: bool,
     },
-    /// Stop a playing sound
-    StopSound { id: String },
+    /// Play several sounds starting on the same frame; each is converted
+    /// to the engine's format like PlaySound
+    PlaySoundsSynced { sounds: Vec<SyncedSound> },
+    /// Set the volume of a sound (0.0 - 2.0), now and for later plays
+    SetSoundVolume { id: String, volume: f32 },
+    /// Ramp the gain a scene puts on a sound (0.0 - 2.0) over `fade_ms`;
+    /// applied on top of its volume and never saved
+    SetSceneGain { id: String, gain: f32, fade_ms: u32 },
+    /// Mix a captured application into the output under a mixer channel
+    /// id (None removes it)
+    SetAppSource {
+        channel_id: String,
+        source: Option<AppSource>,
+    },
+    /// Gain of a captured application (0.0 - 2.0, 0 when muted)
+    SetAppSourceGain { channel_id: String, gain: f32 },
+    /// Start a generator channel afresh at `gain`, replacing one with the
+    /// same id
+    AddGenerator {
+        channel_id: String,
+        settings: GeneratorSettings,
+        gain: f32,
+    },
+    /// Start or change the test signal of a generator channel (None
+    /// removes it)
+    SetGenerator {
+        channel_id: String,
+        settings: Option<GeneratorSettings>,
+    },
+    /// Gain of a generator channel (0.0 - 2.0, 0 when muted)
+    SetGeneratorGain { channel_id: String, gain: f32 },
+    /// Stop a playing sound, fading out over `fade_ms` (the stop fade when None)
+    StopSound { id: String, fade_ms: Option<u32> },
+    /// Stop every sound, fading out over `fade_ms` (the stop fade when None)
+    StopAllSounds { fade_ms: Option<u32> },
+    /// Hold a playing sound at its position until ResumeSound
+    PauseSound { id: String },
+    /// Continue a paused sound from where it was paused
+    ResumeSound { id: String },
+    /// Move a playing sound to `position_secs` from its start
+    SeekSound { id: String, position_secs: f64 },
+    /// Fade-out of stops, retriggers and engine stop, in milliseconds
+    SetStopFade(u32),
+    /// Fade of the whole output on start and before stop, in milliseconds
+    SetOutputRamp(u32),
+    /// Delay of the output bus ("broadcast delay") in milliseconds, 0 for none
+    SetBroadcastDelay(u32),
+    /// Silence everything held in the broadcast delay
+    DumpBroadcastDelay,
+    /// Overwrite the latest `seconds` held in the broadcast delay
+    CensorBroadcastDelay { seconds: f32, mode: CensorMode },
+    /// Threshold and hangover of the speaking detection on the mic
+    SetVoiceActivity(VoiceActivitySettings),
+    /// Ceiling of the monitor's headphone limiter in dBFS, None for none
+    SetHeadphoneLimiter(Option<f32>),
+    /// Auto-mute of the mic when a feedback loop is detected; turning it
+    /// off also lifts a mute it set
+    SetFeedbackProtection(bool),
+    /// Lift the mute set on a detected feedback loop
+    ClearFeedbackMute,
+    /// Lower the mic while sounds play
+    SetMicDucking(DuckingSettings),
+    /// Set the stereo width of a sound (0 = mono, 1 = unchanged, 2 = widest)
+    SetSoundWidth { id: String, width: f32 },
+    /// Set the effect inserts of a sound, e.g. vocal reduction on a music pad
+    SetSoundInserts { id: String, inserts: Vec<SoundInsert> },
     /// Set microphone volume (0.0 - 2.0)
     SetMicVolume(f32),
     /// Set master volume (0.0 - 2.0)
     SetMasterVolume(f32),
     /// Mute/unmute microphone
     SetMicMuted(bool),
+    /// Mute/unmute the mixer's mic channel, with a short fade
+    SetMicChannelMuted(bool),
+    /// Denoise the mic with a learned noise print (None disables it)
+    SetNoiseProfile {
+        profile: Option<NoiseProfile>,
+        quality: SpectralQuality,
+    },
+    /// Cancel the soundboard mix picked up by the mic from speakers
+    SetEchoCancellation(bool),
+    /// Automatic gain control on the mic (None disables it)
+    SetMicAgc(Option<AgcSettings>),
+    /// Noise gate on the mic (None disables it)
+    SetNoiseGate(Option<NoiseGateSettings>),
+    /// Keystroke-timed keyboard suppression on the mic (None disables it)
+    SetKeyboardSuppression(Option<KeyboardSuppressionSettings>),
+    /// Rumble filter at the start of the mic chain
+    SetMicHighpass(HighpassSettings),
+    /// Character voice on the mic (None disables it)
+    SetVoiceChanger(Option<VoiceChangerSettings>),
+    /// Bypass the whole mic chain for A/B comparison
+    SetMicChainBypass(bool),
+    /// Order and on/off state of the mic effects
+    SetMicChainLayout(MicChainLayout),
+    /// Device channels feeding the mic; channel count changes take effect
+    /// on the next Start
+    SetInputChannelMap(InputChannelMap),
+    /// Bit depth and dithering of the output
+    SetOutputFormat(OutputFormatSettings),
+    /// Copy the final mix into a session recording (None detaches it)
+    SetRecordingTap(Option<RecordingTap>),
+    /// Record the podcast mics (host, guest) as stems (empty detaches them)
+    SetStemTaps(Vec<RecordingTap>),
+    /// Play the final mix on a monitor device (None or disabled closes it)
+    SetMonitor {
+        device: Option<String>,
+        settings: MonitorSettings,
+    },
+    /// Restart the running streams with the last Start's settings; sent
+    /// by the watchdog after a stall
+    RebuildStreams,
     /// Shutdown the engine
     Shutdown,
 }
 
 /// Events emitted by the audio engine
+/// One sound of a PlaySoundsSynced group, with the format of its samples
+#[derive(Debug)]
+pub struct SyncedSound {
+    pub id: String,
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
 #[derive(Debug, Clone)]
 pub enum AudioEngineEvent {
     /// Engine started successfully
@@ -56,6 +221,22 @@ pub enum AudioEngineEvent {
     Stopped,
     /// Error occurred
     Error(String),
+    /// The output device's shared-mode rate differs from the engine's, so
+    /// Windows resamples and pitch can drift
+    SampleRateMismatch {
+        device: String,
+        device_rate: u32,
+        engine_rate: u32,
+    },
+    /// Another application holds the device in exclusive mode
+    DeviceInUse { device: String, is_input: bool },
+    /// A stream's callbacks stopped while running, e.g. a hung driver
+    Stalled {
+        stream: StalledStream,
+        silent_ms: u64,
+        /// Whether the streams are being rebuilt
+        rebuilding: bool,
+    },
     /// Audio level update (for UI meters)
     LevelUpdate {
         input_rms: f32,
@@ -63,72 +244,285 @@ pub enum AudioEngineEvent {
         output_rms: f32,
         output_peak: f32,
     },
+    /// The user started or stopped speaking into the mic
+    VoiceActivity { speaking: bool },
+    /// A mic-to-output loop was detected and the mic muted
+    FeedbackDetected,
+    /// The output ran out of mic audio in `count` more buffers
+    Underruns { count: u32 },
+    /// A sound ended or was stopped, and has left the mix
+    SoundFinished { id: String },
+    /// A streamed sound couldn't seek and played on from where it was
+    SeekFailed { id: String },
 }
 
-/// A sound that is currently playing
-struct PlayingSound {
-    samples: Vec<f32>,
-    position: usize,
+/// Devices and format the streams are started with
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSetup {
+    pub input_device: String,
+    pub output_device: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Highest device channel the input channel map reads, which decides
+    /// how many channels the input stream opens with
+    pub highest_input_channel: Option<u16>,
+    /// Monitor device (None when off) and its settings
+    pub monitor_device: Option<String>,
+    pub monitor: MonitorSettings,
+    /// Channel layout of the output device
+    pub output_layout: OutputLayoutSettings,
+    /// Frames per device buffer, the driver's default when None
+    pub buffer_frames: Option<u32>,
 }
 
-/// Shared state for audio processing
-#[allow(dead_code)]
-struct AudioState {
-    playing_sounds: HashMap<String, PlayingSound>,
-    mic_volume: f32,
-    master_volume: f32,
-    mic_muted: bool,
+/// Part of the stream setup that differs from the running one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupChange {
+    InputDevice,
+    OutputDevice,
+    SampleRate,
+    Channels,
+    InputChannels,
+    Monitor,
+    OutputLayout,
+    BufferSize,
 }
 
-impl Default for AudioState {
-    fn default() -> Self {
-        Self {
-            playing_sounds: HashMap::new(),
-            mic_volume: 1.0,
-            master_volume: 1.0,
-            mic_muted: false,
-        }
+impl StreamSetup {
+    /// What changed from `running` to this setup
+    pub fn changes_from(&self, running: &StreamSetup) -> Vec<SetupChange> {
+        let monitor = |setup: &StreamSetup| setup.monitor_device.clone().filter(|_| setup.monitor.enabled);
+        [
+            (SetupChange::InputDevice, self.input_device != running.input_device),
+            (SetupChange::OutputDevice, self.output_device != running.output_device),
+            (SetupChange::SampleRate, self.sample_rate != running.sample_rate),
+            (SetupChange::Channels, self.channels != running.channels),
+            (SetupChange::InputChannels, self.highest_input_channel != running.highest_input_channel),
+            (
+                SetupChange::Monitor,
+                monitor(self) != monitor(running) || (self.monitor.enabled && self.monitor != running.monitor),
+            ),
+            (SetupChange::OutputLayout, self.output_layout != running.output_layout),
+            (SetupChange::BufferSize, self.buffer_frames != running.buffer_frames),
+        ]
+        .into_iter()
+        .filter_map(|(change, changed)| changed.then_some(change))
+        .collect()
     }
 }
 
+/// What starting the streams did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartAction {
+    /// The engine was stopped and has been started
+    Started,
+    /// Already running with the same setup; nothing was touched
+    Unchanged,
+    /// Only the monitor changed and was switched without touching the mix
+    HotSwitched,
+    /// A device or the format changed, so the streams were rebuilt
+    Restarted,
+}
+
+/// Result of [`AudioEngine::start`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StartOutcome {
+    pub action: StartAction,
+    pub changes: Vec<SetupChange>,
+}
+
 /// The audio engine that manages real-time audio processing
+///
+/// A handle shared by the commands without an outer lock: commands go
+/// through a channel and queries read shared state, so every method takes
+/// `&self`.
 pub struct AudioEngine {
-    command_tx: Sender<AudioEngineCommand>,
-    event_rx: Receiver<AudioEngineEvent>,
+    /// Commands carry the span of the command that sent them
+    command_tx: TracedSender<AudioEngineCommand>,
+    /// Taken once by the event forwarder, which then owns it alone
+    event_rx: Mutex<Option<Receiver<Traced<AudioEngineEvent>>>>,
     is_running: Arc<AtomicBool>,
-    thread_handle: Option<JoinHandle<()>>,
+    /// Mix format rate of the last started output device (0 before a start)
+    device_sample_rate: Arc<AtomicU32>,
+    /// Shared with the engine thread, for read-only queries
+    core: EngineCore,
+    /// Stalls found by the watchdog
+    diagnostics: Arc<Mutex<WatchdogDiagnostics>>,
+    /// Setup of the last start, cleared when the engine is stopped
+    setup: Mutex<Option<StreamSetup>>,
+    thread_handle: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl AudioEngine {
     /// Create and start a new audio engine
     pub fn new() -> Self {
-        let (command_tx, command_rx) = bounded(32);
-        let (event_tx, event_rx) = bounded(64);
+        Self::spawn(None)
+    }
+
+    /// Create an engine that runs on the null devices instead of sound
+    /// hardware, whatever devices it is started with
+    pub fn with_null_devices(devices: NullAudioDevices) -> Self {
+        Self::spawn(Some(devices))
+    }
+
+    /// Create an engine without a thread: every command fails and no
+    /// event ever comes
+    ///
+    /// For safe mode, where nothing may open the sound hardware.
+    pub fn disabled() -> Self {
+        let (command_tx, _) = traced_channel(1);
+        let (_, event_rx) = traced_channel(1);
+        Self {
+            command_tx,
+            event_rx: Mutex::new(Some(event_rx)),
+            is_running: Arc::new(AtomicBool::new(false)),
+            device_sample_rate: Arc::new(AtomicU32::new(0)),
+            core: EngineCore::new(),
+            diagnostics: Arc::new(Mutex::new(WatchdogDiagnostics::default())),
+            setup: Mutex::new(None),
+            thread_handle: Mutex::new(None),
+        }
+    }
+
+    fn spawn(null_devices: Option<NullAudioDevices>) -> Self {
+        let (command_tx, command_rx) = traced_channel(32);
+        let (event_tx, event_rx) = traced_channel(64);
         let is_running = Arc::new(AtomicBool::new(false));
         let is_running_clone = is_running.clone();
+        let device_sample_rate = Arc::new(AtomicU32::new(0));
+        let device_sample_rate_clone = device_sample_rate.clone();
+        let core = EngineCore::new();
+        let core_clone = core.clone();
+        let diagnostics = Arc::new(Mutex::new(WatchdogDiagnostics::default()));
+        let watchdog = Watchdog {
+            command_tx: command_tx.clone(),
+            diagnostics: diagnostics.clone(),
+        };
 
         let thread_handle = thread::spawn(move || {
-            run_engine_thread(command_rx, event_tx, is_running_clone);
+            run_engine_thread(
+                command_rx,
+                event_tx,
+                is_running_clone,
+                device_sample_rate_clone,
+                core_clone,
+                watchdog,
+                null_devices,
+            );
         });
 
         Self {
             command_tx,
-            event_rx,
+            event_rx: Mutex::new(Some(event_rx)),
             is_running,
-            thread_handle: Some(thread_handle),
+            device_sample_rate,
+            core,
+            diagnostics,
+            setup: Mutex::new(None),
+            thread_handle: Mutex::new(Some(thread_handle)),
+        }
+    }
+
+    /// Start the streams with `setup`, touching only what differs from
+    /// the running setup
+    ///
+    /// Starting again with the same setup does nothing, a monitor change
+    /// only reopens the monitor, and a device or format change rebuilds
+    /// the streams.
+    pub fn start(&self, setup: StreamSetup) -> Result<StartOutcome, String> {
+        // Held throughout, so concurrent starts are compared in turn
+        let mut current = self.setup.lock().map_err(|e| e.to_string())?;
+        let running = current.clone().filter(|_| self.is_running());
+        let changes = running.map(|running| setup.changes_from(&running));
+
+        let action = match changes.as_deref() {
+            None => StartAction::Started,
+            Some([]) => StartAction::Unchanged,
+            Some([SetupChange::Monitor]) => StartAction::HotSwitched,
+            Some(_) => StartAction::Restarted,
+        };
+        let changes = changes.unwrap_or_default();
+
+        if action == StartAction::Started || changes.contains(&SetupChange::Monitor) {
+            self.send_raw(AudioEngineCommand::SetMonitor {
+                device: setup.monitor_device.clone(),
+                settings: setup.monitor,
+            })?;
+        }
+        if matches!(action, StartAction::Started | StartAction::Restarted) {
+            self.send_raw(AudioEngineCommand::Start {
+                input_device: setup.input_device.clone(),
+                output_device: setup.output_device.clone(),
+                sample_rate: setup.sample_rate,
+                channels: setup.channels,
+                output_layout: setup.output_layout,
+                buffer_frames: setup.buffer_frames,
+            })?;
+        }
+
+        *current = Some(setup);
+        Ok(StartOutcome { action, changes })
+    }
+
+    /// Restart the running streams with another buffer size
+    ///
+    /// Only while `expected` is still what runs, checked under the same
+    /// lock as `start`, so a start or stop that raced the caller wins.
+    /// Returns whether the streams were restarted.
+    pub fn restart_with_buffer(&self, expected: &StreamSetup, buffer_frames: u32) -> Result<bool, String> {
+        let mut current = self.setup.lock().map_err(|e| e.to_string())?;
+        if !self.is_running() || current.as_ref() != Some(expected) {
+            return Ok(false);
         }
+
+        let setup = StreamSetup {
+            buffer_frames: Some(buffer_frames),
+            ..expected.clone()
+        };
+        self.send_raw(AudioEngineCommand::Start {
+            input_device: setup.input_device.clone(),
+            output_device: setup.output_device.clone(),
+            sample_rate: setup.sample_rate,
+            channels: setup.channels,
+            output_layout: setup.output_layout,
+            buffer_frames: setup.buffer_frames,
+        })?;
+        *current = Some(setup);
+        Ok(true)
     }
 
     /// Send a command to the audio engine
     pub fn send_command(&self, command: AudioEngineCommand) -> Result<(), String> {
+        // Keep the setup in step with commands that change it directly
+        if let Ok(mut setup) = self.setup.lock() {
+            match &command {
+                AudioEngineCommand::Stop | AudioEngineCommand::Shutdown => *setup = None,
+                AudioEngineCommand::SetMonitor { device, settings } => {
+                    if let Some(setup) = setup.as_mut() {
+                        setup.monitor_device = device.clone();
+                        setup.monitor = *settings;
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.send_raw(command)
+    }
+
+    /// Send a command without updating the setup
+    fn send_raw(&self, command: AudioEngineCommand) -> Result<(), String> {
         self.command_tx
             .send(command)
             .map_err(|e| format!("Failed to send command: {}", e))
     }
 
-    /// Try to receive an event from the audio engine (non-blocking)
-    pub fn try_recv_event(&self) -> Option<AudioEngineEvent> {
-        self.event_rx.try_recv().ok()
+    /// Take the receiver of engine events; only the first call gets it
+    ///
+    /// Events come with the span of the command they result from, if any.
+    pub fn take_event_receiver(&self) -> Option<Receiver<Traced<AudioEngineEvent>>> {
+        self.event_rx.lock().ok()?.take()
     }
 
     /// Check if the engine is currently running
@@ -136,10 +530,72 @@ impl AudioEngine {
         self.is_running.load(Ordering::SeqCst)
     }
 
+    /// Delay added by the active mic effects, in frames
+    pub fn mic_latency_frames(&self) -> usize {
+        self.core.mic_editor.lock().map(|editor| editor.latency()).unwrap_or(0)
+    }
+
+    /// Sounds playing on the soundboard, with their positions, as last
+    /// published by the engine (at most [`PLAYING_SNAPSHOT_INTERVAL`] old)
+    ///
+    /// Never waits on the mixer, so it suits pollers like the now-playing
+    /// overlay.
+    pub fn playing_snapshot(&self) -> Arc<[PlayingSoundInfo]> {
+        self.core.playing_snapshot()
+    }
+
+    pub fn mic_volume(&self) -> f32 {
+        self.core.controls.mic_volume()
+    }
+
+    pub fn is_mic_muted(&self) -> bool {
+        self.core.controls.is_mic_muted()
+    }
+
+    /// Whether the feedback guard holds the mic closed
+    pub fn is_feedback_muted(&self) -> bool {
+        self.core.controls.is_feedback_muted()
+    }
+
+    /// Close or open the push-to-talk gate without going through the
+    /// command queue, so a key press takes effect on the very next buffer
+    ///
+    /// The gate is its own mute source: opening it leaves the user's mute
+    /// and the feedback mute as they are.
+    pub fn set_talk_gate_closed(&self, closed: bool) {
+        self.core.controls.set_talk_gate_closed(closed);
+    }
+
+    /// Record a key press, for the keyboard suppression
+    pub fn record_keystroke(&self) {
+        self.core.keystrokes.press();
+    }
+
+    /// Shared-mode mix rate of the output device the engine last started on
+    pub fn device_sample_rate(&self) -> Option<u32> {
+        Some(self.device_sample_rate.load(Ordering::Relaxed)).filter(|rate| *rate > 0)
+    }
+
+    /// Sample rate and channel count sounds are mixed at, None until the
+    /// engine has started
+    pub fn mix_format(&self) -> Option<(u32, usize)> {
+        self.core.sounds.lock().ok()?.format()
+    }
+
+    /// Setup the streams were last started with, None when stopped
+    pub fn stream_setup(&self) -> Option<StreamSetup> {
+        self.setup.lock().ok()?.clone()
+    }
+
+    /// Stalls found by the watchdog since the engine was created
+    pub fn watchdog_diagnostics(&self) -> WatchdogDiagnostics {
+        self.diagnostics.lock().map(|d| d.clone()).unwrap_or_default()
+    }
+
     /// Shutdown the audio engine
-    pub fn shutdown(&mut self) {
+    pub fn shutdown(&self) {
         let _ = self.command_tx.send(AudioEngineCommand::Shutdown);
-        if let Some(handle) = self.thread_handle.take() {
+        if let Some(handle) = self.thread_handle.lock().ok().and_then(|mut handle| handle.take()) {
             let _ = handle.join();
         }
     }
@@ -157,6 +613,142 @@ impl Drop for AudioEngine {
     }
 }
 
+/// Open the monitor, reporting a failure as an engine error
+fn open_monitor_or_report(
+    host: &cpal::Host,
+    core: &EngineCore,
+    device: &str,
+    settings: &MonitorSettings,
+    sample_rate: u32,
+    channels: u16,
+    event_tx: &TracedSender<AudioEngineEvent>,
+) -> Option<cpal::Stream> {
+    match open_monitor_stream(host, core, device, settings, sample_rate, channels) {
+        Ok(stream) => {
+            tracing::info!(
+                device,
+                delay_ms = settings.delay_ms,
+                codec_kbps = ?settings.codec_bitrate_kbps,
+                "Monitor started"
+            );
+            Some(stream)
+        }
+        Err(e) => {
+            let _ = event_tx.send(stream_error_event(device, false, "Failed to open monitor", e));
+            None
+        }
+    }
+}
+
+/// Play the final mix on `device`, delayed by the monitor delay and
+/// optionally through the codec simulator, then the headphone limiter
+fn open_monitor_stream(
+    host: &cpal::Host,
+    core: &EngineCore,
+    device: &str,
+    settings: &MonitorSettings,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<cpal::Stream, String> {
+    let monitor_dev = find_device(host, device, false).ok_or_else(|| format!("Monitor device not found: {}", device))?;
+    let config = cpal::StreamConfig {
+        channels,
+        sample_rate: cpal::SampleRate(sample_rate),
+        buffer_size: cpal::BufferSize::Default,
+    };
+
+    let samples_per_ms = sample_rate as usize * channels as usize / 1000;
+    let delay = settings.delay_ms.min(MonitorSettings::MAX_DELAY_MS) as usize * samples_per_ms;
+    let delay = delay - delay % channels as usize;
+    let slack = MONITOR_SLACK_MS * samples_per_ms;
+    let (mut producer, mut consumer) = HeapRb::<f32>::new(delay + slack + RING_BUFFER_SIZE).split();
+
+    // Silence queued up front delays everything after it by exactly `delay`
+    producer.push_iter(std::iter::repeat(0.0).take(delay));
+
+    let mut codec = settings
+        .codec_bitrate_kbps
+        .map(|kbps| CodecSimulator::new(kbps, sample_rate))
+        .transpose()
+        .map_err(|e| format!("Failed to create the codec simulator: {}", e))?;
+
+    let volume = settings.volume.clamp(0.0, 2.0);
+    let channels = channels as usize;
+    let controls = core.controls.clone();
+    let mut ceiling_db = controls.headphone_ceiling_db();
+    let mut limiter = PeakLimiter::new(ceiling_db.unwrap_or(0.0), sample_rate);
+    let stream = monitor_dev
+        .build_output_stream(
+            &config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                // Drop audio piled up by clock drift between the two devices
+                let excess = consumer.occupied_len().saturating_sub(delay + slack);
+                consumer.skip(excess - excess % channels);
+
+                let read = consumer.pop_slice(data);
+                data[read..].fill(0.0);
+                if let Some(codec) = codec.as_mut() {
+                    codec.process(data, channels);
+                }
+                // The codec's output runs late, so scale every sample, not just those read
+                for sample in data.iter_mut() {
+                    *sample *= volume;
+                }
+
+                // The ceiling can change while monitoring
+                let ceiling = controls.headphone_ceiling_db();
+                if ceiling != ceiling_db {
+                    ceiling_db = ceiling;
+                    limiter.set_ceiling_db(ceiling.unwrap_or(0.0));
+                }
+                if ceiling_db.is_some() {
+                    limiter.process(data, channels);
+                }
+            },
+            move |err| {
+                tracing::error!("Monitor stream error: {}", err);
+            },
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+
+    if let Ok(mut monitor) = core.monitor.lock() {
+        *monitor = Some(producer);
+    }
+    Ok(stream)
+}
+
+/// HRESULT of AUDCLNT_E_DEVICE_IN_USE as cpal prints it
+const DEVICE_IN_USE_HRESULT: &str = "0x8889000a";
+
+/// Whether a stream error means another app holds the device exclusively
+///
+/// cpal reports WASAPI failures as backend-specific text, so the HRESULT
+/// is matched in the message.
+pub fn is_device_in_use(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains(DEVICE_IN_USE_HRESULT) || message.contains("device is already in use")
+}
+
+/// Event for a failure to open or start a stream on `device`
+fn stream_error_event(device: &str, is_input: bool, context: &str, error: impl std::fmt::Display) -> AudioEngineEvent {
+    let message = error.to_string();
+    if is_device_in_use(&message) {
+        tracing::warn!(device, "{}: device is in exclusive use by another app", context);
+        return AudioEngineEvent::DeviceInUse {
+            device: device.to_string(),
+            is_input,
+        };
+    }
+    AudioEngineEvent::Error(format!("{}: {}", context, message))
+}
+
+/// Native integer depth of a sample format, None for float formats
+fn integer_bits(format: cpal::SampleFormat) -> Option<u32> {
+    (!format.is_float()).then(|| format.sample_size() as u32 * 8)
+}
+
 /// Find a device by name
 fn find_device(host: &cpal::Host, name: &str, is_input: bool) -> Option<cpal::Device> {
     if name == "default" {
@@ -167,59 +759,206 @@ fn find_device(host: &cpal::Host, name: &str, is_input: bool) -> Option<cpal::De
         };
     }
 
-    let devices = if is_input {
-        host.input_devices().ok()?
+    let mut devices: Vec<cpal::Device> = if is_input {
+        host.input_devices().ok()?.collect()
     } else {
-        host.output_devices().ok()?
+        host.output_devices().ok()?.collect()
     };
 
-    for device in devices {
-        if let Ok(device_name) = device.name() {
-            if device_name == name {
-                return Some(device);
-            }
+    // Saved names may differ in normalization or driver suffix
+    let names: Vec<String> = devices.iter().map(|device| device.name().unwrap_or_default()).collect();
+    let index = find_device_name(names.iter().map(String::as_str), name)?;
+    Some(devices.swap_remove(index))
+}
+
+/// Spans of the sounds started, to log their end under the command that
+/// played them
+#[derive(Default)]
+struct PlaySpans {
+    spans: HashMap<String, Span>,
+}
+
+impl PlaySpans {
+    fn started(&mut self, command: &AudioEngineCommand) {
+        let ids: Vec<&String> = match command {
+            AudioEngineCommand::PlaySound { id, .. } | AudioEngineCommand::PlayStream { id, .. } => vec![id],
+            AudioEngineCommand::PlaySoundsSynced { sounds } => sounds.iter().map(|sound| &sound.id).collect(),
+            _ => return,
+        };
+        for id in ids {
+            tracing::debug!(sound = %id, "Sound queued on the engine");
+            self.spans.insert(id.clone(), Span::current());
+        }
+    }
+
+    /// Log the end of a sound under the command that played it, and
+    /// return that span
+    fn finished(&mut self, id: &str) -> Span {
+        let span = self.spans.remove(id).unwrap_or_else(Span::none);
+        span.in_scope(|| tracing::info!(sound = %id, "Sound finished"));
+        span
+    }
+}
+
+/// What the engine thread hands each watchdog it starts
+struct Watchdog {
+    command_tx: TracedSender<AudioEngineCommand>,
+    diagnostics: Arc<Mutex<WatchdogDiagnostics>>,
+}
+
+/// Fade every sound out and ramp the output down
+///
+/// Returns when the streams may go, None if there is nothing to wait for.
+fn fade_out_output(core: &EngineCore) -> Option<Instant> {
+    let fade_ms = core.sounds.lock().map(|mut sounds| sounds.stop_all(None)).unwrap_or(0);
+    core.controls.close_output();
+    let wait_ms = fade_ms.max(core.controls.output_ramp_ms());
+    (wait_ms > 0).then(|| Instant::now() + Duration::from_millis(wait_ms as u64))
+}
+
+/// Build a stream with the requested buffer size, falling back to the
+/// device's default when the device rejects it
+fn build_with_fallback(
+    buffer_size: cpal::BufferSize,
+    mut build: impl FnMut(cpal::BufferSize) -> Result<cpal::Stream, cpal::BuildStreamError>,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
+    match build(buffer_size) {
+        Err(e) if buffer_size != cpal::BufferSize::Default => {
+            tracing::warn!(error = %e, ?buffer_size, "Device rejected the buffer size, using its default");
+            build(cpal::BufferSize::Default)
         }
+        result => result,
     }
-    None
 }
 
 /// The main engine thread that manages audio streams
 fn run_engine_thread(
-    command_rx: Receiver<AudioEngineCommand>,
-    event_tx: Sender<AudioEngineEvent>,
+    command_rx: Receiver<Traced<AudioEngineCommand>>,
+    event_tx: TracedSender<AudioEngineEvent>,
     is_running: Arc<AtomicBool>,
+    device_sample_rate: Arc<AtomicU32>,
+    core: EngineCore,
+    watchdog: Watchdog,
+    null_devices: Option<NullAudioDevices>,
 ) {
     let host = cpal::default_host();
 
     // Active streams (kept alive while running)
     let mut input_stream: Option<cpal::Stream> = None;
     let mut output_stream: Option<cpal::Stream> = None;
+    let mut monitor_stream: Option<cpal::Stream> = None;
+    let mut null_streams: Option<NullStreams> = None;
 
-    // Shared state for audio processing
-    let audio_state = Arc::new(Mutex::new(AudioState::default()));
+    // Monitor to open with the streams, and the format they run at
+    let mut monitor: Option<(String, MonitorSettings)> = None;
+    let mut running_format: Option<(u32, u16)> = None;
+
+    // Settings of the last start, replayed to rebuild stalled streams
+    let mut last_start: Option<(String, String, u32, u16, OutputLayoutSettings, Option<u32>)> = None;
+    // Cleared to end the level and watchdog threads of the running streams
+    let mut session: Option<Arc<AtomicBool>> = None;
 
     // Ring buffer for passing audio from input to output
     let ring_buffer = Arc::new(Mutex::new(None::<(ringbuf::HeapProd<f32>, ringbuf::HeapCons<f32>)>));
 
-    // Atomic volume controls (for lock-free access in callbacks)
-    let mic_volume = Arc::new(AtomicU32::new(f32::to_bits(1.0)));
-    let master_volume = Arc::new(AtomicU32::new(f32::to_bits(1.0)));
-    let mic_muted = Arc::new(AtomicBool::new(false));
+    let mut plays = PlaySpans::default();
+    let mut last_snapshot = Instant::now();
+    // Stop or shutdown held back while the output fades out, with when it runs
+    let mut deferred: Option<(Instant, Traced<AudioEngineCommand>)> = None;
 
     loop {
-        // Process commands
-        match command_rx.recv_timeout(Duration::from_millis(10)) {
-            Ok(command) => {
+        // The mixer hands back the sounds that left it; the event is only
+        // informational, so a full channel doesn't hold the loop up
+        for id in core.drop_retired() {
+            let _ = plays.finished(&id).in_scope(|| event_tx.try_send(AudioEngineEvent::SoundFinished { id }));
+        }
+        if last_snapshot.elapsed() >= PLAYING_SNAPSHOT_INTERVAL {
+            last_snapshot = Instant::now();
+            core.publish_playing();
+            for id in core.take_failed_seeks() {
+                let _ = event_tx.try_send(AudioEngineEvent::SeekFailed { id });
+            }
+        }
+
+        // Process commands, the deferred one first once its fade is over
+        let (received, faded) = match deferred.take() {
+            Some((at, command)) if Instant::now() >= at => (Ok(command), true),
+            pending => {
+                deferred = pending;
+                (command_rx.recv_timeout(Duration::from_millis(10)), false)
+            }
+        };
+        match received {
+            Ok(Traced { span, value: command }) => {
+                // Logs and events of the command stay under its caller's span
+                let _entered = span.enter();
+                let rebuilding = matches!(command, AudioEngineCommand::RebuildStreams);
+                let command = match command {
+                    AudioEngineCommand::RebuildStreams => match last_start.clone() {
+                        Some((input_device, output_device, sample_rate, channels, output_layout, buffer_frames))
+                            if is_running.load(Ordering::SeqCst) =>
+                        {
+                            tracing::warn!("Rebuilding stalled audio streams");
+                            AudioEngineCommand::Start {
+                                input_device,
+                                output_device,
+                                sample_rate,
+                                channels,
+                                output_layout,
+                                buffer_frames,
+                            }
+                        }
+                        _ => continue,
+                    },
+                    other => other,
+                };
+
                 match command {
                     AudioEngineCommand::Start {
                         input_device,
                         output_device,
                         sample_rate,
                         channels,
+                        output_layout,
+                        buffer_frames,
                     } => {
-                        // Stop any existing streams
+                        // Stop any existing streams; a stop still fading
+                        // out has nothing left to do
+                        deferred = None;
+                        if let Some(active) = session.take() {
+                            active.store(false, Ordering::Relaxed);
+                        }
                         input_stream = None;
                         output_stream = None;
+                        monitor_stream = None;
+                        null_streams = None;
+
+                        if !rebuilding {
+                            if let Ok(mut diagnostics) = watchdog.diagnostics.lock() {
+                                diagnostics.rebuilds = 0;
+                            }
+                        }
+                        last_start = Some((
+                            input_device.clone(),
+                            output_device.clone(),
+                            sample_rate,
+                            channels,
+                            output_layout,
+                            buffer_frames,
+                        ));
+                        core.controls.reset_underruns();
+                        // A fresh start may be on fixed routing, so a loop found before doesn't carry over
+                        core.controls.set_feedback_muted(false);
+
+                        // Test mode: no hardware, no monitor and nothing to watch
+                        if let Some(devices) = &null_devices {
+                            null_streams = Some(devices.start(&core, sample_rate, channels));
+                            running_format = Some((sample_rate, channels));
+                            is_running.store(true, Ordering::SeqCst);
+                            let _ = event_tx.send(AudioEngineEvent::Started);
+                            tracing::info!("Audio engine started on the null devices");
+                            continue;
+                        }
 
                         // Find devices
                         let input_dev = match find_device(&host, &input_device, true) {
@@ -242,6 +981,45 @@ fn run_engine_thread(
                             }
                         };
 
+                        // On WASAPI the default config is the endpoint's shared-mode
+                        // mix format (IAudioClient::GetMixFormat)
+                        let device_config = output_dev.default_output_config().ok();
+
+                        // Auto bit depth follows the device's native format
+                        let device_bits = device_config
+                            .as_ref()
+                            .and_then(|config| integer_bits(config.sample_format()));
+                        core.controls.set_device_bits(device_bits);
+
+                        // The mix stays stereo; devices with another layout get it remixed
+                        let device_channels = output_layout
+                            .layout
+                            .device_channels(device_config.as_ref().map(|config| config.channels()), channels);
+                        let remixer = ChannelRemixer::new(
+                            channels as usize,
+                            device_channels as usize,
+                            output_layout.center_from_stereo,
+                        );
+                        if remixer.is_active() {
+                            tracing::info!(device = %output_device, device_channels, "Remixing the output to the device layout");
+                        }
+
+                        let device_rate = device_config.map(|config| config.sample_rate().0).unwrap_or(0);
+                        device_sample_rate.store(device_rate, Ordering::Relaxed);
+                        if device_rate > 0 && device_rate != sample_rate {
+                            tracing::warn!(
+                                device = %output_device,
+                                device_rate,
+                                engine_rate = sample_rate,
+                                "Output device sample rate differs from the engine"
+                            );
+                            let _ = event_tx.send(AudioEngineEvent::SampleRateMismatch {
+                                device: output_device.clone(),
+                                device_rate,
+                                engine_rate: sample_rate,
+                            });
+                        }
+
                         // Create ring buffer for audio pass-through
                         let rb = HeapRb::<f32>::new(RING_BUFFER_SIZE);
                         let (producer, consumer) = rb.split();
@@ -253,132 +1031,124 @@ fn run_engine_thread(
                         let config = cpal::StreamConfig {
                             channels,
                             sample_rate: cpal::SampleRate(sample_rate),
-                            buffer_size: cpal::BufferSize::Default,
+                            buffer_size: buffer_frames.map_or(cpal::BufferSize::Default, cpal::BufferSize::Fixed),
                         };
 
                         // Atomic level values for lock-free reading
                         let input_level = Arc::new(AtomicU32::new(0));
                         let output_level = Arc::new(AtomicU32::new(0));
-                        let input_level_clone = input_level.clone();
-
-                        // Clone references for callbacks
-                        let producer_clone = producer.clone();
-                        let mic_volume_clone = mic_volume.clone();
-                        let mic_muted_clone = mic_muted.clone();
+                        let heartbeats = Arc::new(StreamHeartbeats::new());
+
+                        // A channel map reads the interface with all its channels
+                        let channel_map = core.input_channel_map();
+                        let input_channels = match channel_map.highest_channel() {
+                            Some(highest) => input_dev
+                                .default_input_config()
+                                .map(|config| config.channels())
+                                .unwrap_or(channels)
+                                .max(highest + 1),
+                            None => channels,
+                        };
+                        // One device buffer of silence ahead of the mic, so
+                        // periods that don't line up aren't taken for underruns
+                        let prefill = (buffer_frames.unwrap_or(DEFAULT_PREFILL_FRAMES) as usize
+                            * channels as usize)
+                            .min(RING_BUFFER_SIZE / 2);
 
                         // Build input stream
-                        let input_result = input_dev.build_input_stream(
-                            &config,
-                            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                                let muted = mic_muted_clone.load(Ordering::Relaxed);
-                                let volume = f32::from_bits(mic_volume_clone.load(Ordering::Relaxed));
-
-                                // Calculate RMS for input level
-                                let mut sum_squares = 0.0f32;
-
-                                if let Ok(mut prod) = producer_clone.try_lock() {
-                                    for &sample in data {
-                                        let processed = if muted { 0.0 } else { sample * volume };
-                                        sum_squares += processed * processed;
-                                        let _ = prod.try_push(processed);
+                        let input_result = build_with_fallback(config.buffer_size, |buffer_size| {
+                            let producer = producer.clone();
+                            let input_level = input_level.clone();
+                            let input_heartbeats = heartbeats.clone();
+                            let mut input_processor =
+                                core.input_processor(input_channels, channels, sample_rate).with_prefill(prefill);
+                            let input_config = cpal::StreamConfig {
+                                channels: input_channels,
+                                buffer_size,
+                                ..config.clone()
+                            };
+                            input_dev.build_input_stream(
+                                &input_config,
+                                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                                    input_heartbeats.input.beat();
+                                    if let Ok(mut prod) = producer.try_lock() {
+                                        let rms = input_processor.process(data, |sample| {
+                                            let _ = prod.try_push(sample);
+                                        });
+
+                                        // Store RMS level (will be read by level monitoring thread)
+                                        if !data.is_empty() {
+                                            input_level.store(rms.to_bits(), Ordering::Relaxed);
+                                        }
                                     }
-                                }
-
-                                // Store RMS level (will be read by level monitoring thread)
-                                if !data.is_empty() {
-                                    let rms = (sum_squares / data.len() as f32).sqrt();
-                                    input_level_clone.store(rms.to_bits(), Ordering::Relaxed);
-                                }
-                            },
-                            move |err| {
-                                tracing::error!("Input stream error: {}", err);
-                            },
-                            None,
-                        );
+                                },
+                                move |err| {
+                                    tracing::error!("Input stream error: {}", err);
+                                },
+                                None,
+                            )
+                        });
 
                         let input_s = match input_result {
                             Ok(s) => s,
                             Err(e) => {
-                                let _ = event_tx.send(AudioEngineEvent::Error(
-                                    format!("Failed to create input stream: {}", e)
+                                let _ = event_tx.send(stream_error_event(
+                                    &input_device, true, "Failed to create input stream", e
                                 ));
                                 continue;
                             }
                         };
 
-                        // Clone references for output callback
-                        let consumer_clone = consumer.clone();
-                        let master_volume_clone = master_volume.clone();
-                        let audio_state_clone = audio_state.clone();
-                        let output_level_for_callback = output_level.clone();
-
                         // Build output stream
-                        let output_result = output_dev.build_output_stream(
-                            &config,
-                            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                                let master_vol = f32::from_bits(master_volume_clone.load(Ordering::Relaxed));
-
-                                // First, fill with mic input from ring buffer
-                                if let Ok(mut cons) = consumer_clone.try_lock() {
-                                    for sample in data.iter_mut() {
-                                        *sample = cons.try_pop().unwrap_or(0.0);
-                                    }
-                                } else {
-                                    // If we can't get the lock, output silence
-                                    for sample in data.iter_mut() {
-                                        *sample = 0.0;
+                        let output_result = build_with_fallback(config.buffer_size, |buffer_size| {
+                            let consumer = consumer.clone();
+                            let mut output_processor = core.output_processor(channels);
+                            let output_level = output_level.clone();
+                            let output_heartbeats = heartbeats.clone();
+                            let output_config = cpal::StreamConfig {
+                                channels: device_channels,
+                                buffer_size,
+                                ..config.clone()
+                            };
+                            // Room for any buffer the device hands us, so the callback never grows it
+                            let mut mix = Vec::with_capacity(MAX_CALLBACK_FRAMES * remixer.in_channels());
+                            output_dev.build_output_stream(
+                                &output_config,
+                                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                                    output_heartbeats.output.beat();
+                                    // Mixed at the engine's channels, then fitted to the device
+                                    let out: &mut [f32] = if remixer.is_active() {
+                                        mix.resize(data.len() / remixer.out_channels() * remixer.in_channels(), 0.0);
+                                        &mut mix
+                                    } else {
+                                        &mut *data
+                                    };
+                                    // Mic input comes from the ring buffer (silence if we can't get the lock)
+                                    let rms = if let Ok(mut cons) = consumer.try_lock() {
+                                        output_processor.process(out, || cons.try_pop())
+                                    } else {
+                                        output_processor.process(out, || None)
+                                    };
+                                    if remixer.is_active() {
+                                        remixer.process(&mix, data);
                                     }
-                                }
-
-                                // Mix in playing sounds
-                                if let Ok(mut state) = audio_state_clone.try_lock() {
-                                    let mut finished = Vec::new();
 
-                                    for (id, sound) in state.playing_sounds.iter_mut() {
-                                        let remaining = sound.samples.len() - sound.position;
-                                        let to_mix = remaining.min(data.len());
-
-                                        for (i, sample) in data.iter_mut().take(to_mix).enumerate() {
-                                            *sample = (*sample + sound.samples[sound.position + i]).clamp(-1.0, 1.0);
-                                        }
-
-                                        sound.position += to_mix;
-                                        if sound.position >= sound.samples.len() {
-                                            finished.push(id.clone());
-                                        }
-                                    }
-
-                                    for id in finished {
-                                        state.playing_sounds.remove(&id);
+                                    if !data.is_empty() {
+                                        output_level.store(rms.to_bits(), Ordering::Relaxed);
                                     }
-                                }
-
-                                // Apply master volume
-                                for sample in data.iter_mut() {
-                                    *sample = (*sample * master_vol).clamp(-1.0, 1.0);
-                                }
-
-                                // Calculate output RMS after master volume
-                                let mut sum_squares = 0.0f32;
-                                for sample in data.iter() {
-                                    sum_squares += sample * sample;
-                                }
-                                if !data.is_empty() {
-                                    let rms = (sum_squares / data.len() as f32).sqrt();
-                                    output_level_for_callback.store(rms.to_bits(), Ordering::Relaxed);
-                                }
-                            },
-                            move |err| {
-                                tracing::error!("Output stream error: {}", err);
-                            },
-                            None,
-                        );
+                                },
+                                move |err| {
+                                    tracing::error!("Output stream error: {}", err);
+                                },
+                                None,
+                            )
+                        });
 
                         let output_s = match output_result {
                             Ok(s) => s,
                             Err(e) => {
-                                let _ = event_tx.send(AudioEngineEvent::Error(
-                                    format!("Failed to create output stream: {}", e)
+                                let _ = event_tx.send(stream_error_event(
+                                    &output_device, false, "Failed to create output stream", e
                                 ));
                                 continue;
                             }
@@ -386,15 +1156,15 @@ fn run_engine_thread(
 
                         // Start streams
                         if let Err(e) = input_s.play() {
-                            let _ = event_tx.send(AudioEngineEvent::Error(
-                                format!("Failed to start input: {}", e)
+                            let _ = event_tx.send(stream_error_event(
+                                &input_device, true, "Failed to start input", e
                             ));
                             continue;
                         }
 
                         if let Err(e) = output_s.play() {
-                            let _ = event_tx.send(AudioEngineEvent::Error(
-                                format!("Failed to start output: {}", e)
+                            let _ = event_tx.send(stream_error_event(
+                                &output_device, false, "Failed to start output", e
                             ));
                             continue;
                         }
@@ -402,26 +1172,80 @@ fn run_engine_thread(
                         // Store streams to keep them alive
                         input_stream = Some(input_s);
                         output_stream = Some(output_s);
+                        running_format = Some((sample_rate, channels));
+
+                        if let Some((device, settings)) = &monitor {
+                            monitor_stream = open_monitor_or_report(
+                                &host, &core, device, settings, sample_rate, channels, &event_tx,
+                            );
+                        }
 
                         is_running.store(true, Ordering::SeqCst);
                         let _ = event_tx.send(AudioEngineEvent::Started);
                         tracing::info!("Audio engine started: {} -> {}", input_device, output_device);
 
+                        let active = Arc::new(AtomicBool::new(true));
+                        session = Some(active.clone());
+                        spawn_watchdog(
+                            heartbeats,
+                            active.clone(),
+                            WatchedStreams {
+                                input_device: input_device.clone(),
+                                output_device: output_device.clone(),
+                                sample_rate,
+                            },
+                            watchdog.diagnostics.clone(),
+                            watchdog.command_tx.clone(),
+                            event_tx.clone(),
+                        );
+
                         // Start level monitoring thread
                         let input_level_monitor = input_level.clone();
                         let output_level_monitor = output_level.clone();
                         let event_tx_monitor = event_tx.clone();
-                        let is_running_monitor = is_running.clone();
+                        let session_monitor = active;
+                        let controls_monitor = core.controls.clone();
 
                         std::thread::spawn(move || {
                             let mut input_peak = 0.0f32;
                             let mut output_peak = 0.0f32;
                             let decay_rate = 0.05; // ~20dB/sec at 30Hz
+                            let mut voice_activity = VoiceActivityDetector::new(controls_monitor.voice_activity());
+                            let mut feedback = FeedbackDetector::new();
+                            let mut underruns = 0;
 
-                            while is_running_monitor.load(Ordering::Relaxed) {
+                            while session_monitor.load(Ordering::Relaxed) {
                                 let input_rms = f32::from_bits(input_level_monitor.load(Ordering::Relaxed));
                                 let output_rms = f32::from_bits(output_level_monitor.load(Ordering::Relaxed));
 
+                                let vad_settings = controls_monitor.voice_activity();
+                                if vad_settings != voice_activity.settings() {
+                                    voice_activity.set_settings(vad_settings);
+                                }
+                                if let Some(speaking) = voice_activity.update(input_rms, LEVEL_UPDATE_INTERVAL_MS as u32) {
+                                    let _ = event_tx_monitor.send(AudioEngineEvent::VoiceActivity { speaking });
+                                }
+
+                                // A muted mic can't feed back, so only watch a live one; the
+                                // mute is its own source, so the user's mute and PTT stay as set
+                                if controls_monitor.feedback_protection() && !controls_monitor.is_mic_silenced() {
+                                    if feedback.update(input_rms, output_rms, LEVEL_UPDATE_INTERVAL_MS as u32) {
+                                        controls_monitor.set_feedback_muted(true);
+                                        tracing::warn!("Feedback loop detected, mic muted");
+                                        let _ = event_tx_monitor.send(AudioEngineEvent::FeedbackDetected);
+                                    }
+                                } else {
+                                    feedback.reset();
+                                }
+
+                                let total_underruns = controls_monitor.underruns();
+                                if total_underruns > underruns {
+                                    let _ = event_tx_monitor.send(AudioEngineEvent::Underruns {
+                                        count: total_underruns - underruns,
+                                    });
+                                }
+                                underruns = total_underruns;
+
                                 // Update peaks
                                 if input_rms > input_peak {
                                     input_peak = input_rms;
@@ -444,10 +1268,35 @@ fn run_engine_thread(
 
                                 std::thread::sleep(std::time::Duration::from_millis(LEVEL_UPDATE_INTERVAL_MS));
                             }
+
+                            // Stopping the engine ends any speech in progress
+                            if let Some(speaking) = voice_activity.reset() {
+                                let _ = event_tx_monitor.send(AudioEngineEvent::VoiceActivity { speaking });
+                            }
                         });
                     }
 
                     AudioEngineCommand::Stop => {
+                        // Let playing sounds fade out, and the output ramp
+                        // down, before the streams go; commands keep being
+                        // served meanwhile
+                        if !faded {
+                            if deferred.is_some() {
+                                continue;
+                            }
+                            let streaming = output_stream.is_some() || null_streams.is_some();
+                            if let Some(at) = streaming.then(|| fade_out_output(&core)).flatten() {
+                                let span = Span::current();
+                                deferred = Some((at, Traced { span, value: AudioEngineCommand::Stop }));
+                                continue;
+                            }
+                        }
+
+                        if let Some(active) = session.take() {
+                            active.store(false, Ordering::Relaxed);
+                        }
+                        last_start = None;
+
                         // Pause streams before dropping to ensure clean stop
                         if let Some(ref stream) = input_stream {
                             let _ = stream.pause();
@@ -459,6 +1308,12 @@ fn run_engine_thread(
                         // Drop the streams
                         input_stream = None;
                         output_stream = None;
+                        monitor_stream = None;
+                        null_streams = None;
+                        running_format = None;
+                        if let Ok(mut producer) = core.monitor.lock() {
+                            *producer = None;
+                        }
 
                         // Clear the ring buffer to prevent any leftover audio
                         if let Ok(mut rb) = ring_buffer.lock() {
@@ -467,42 +1322,34 @@ fn run_engine_thread(
 
                         is_running.store(false, Ordering::SeqCst);
 
-                        if let Ok(mut state) = audio_state.lock() {
-                            state.playing_sounds.clear();
+                        if let Ok(mut sounds) = core.sounds.lock() {
+                            sounds.clear();
                         }
 
                         let _ = event_tx.send(AudioEngineEvent::Stopped);
                         tracing::info!("Audio engine stopped");
                     }
 
-                    AudioEngineCommand::PlaySound { id, samples } => {
-                        if let Ok(mut state) = audio_state.lock() {
-                            state.playing_sounds.insert(id, PlayingSound {
-                                samples,
-                                position: 0,
-                            });
+                    AudioEngineCommand::Shutdown => {
+                        // Fades out like a stop, keeping a stop's deadline
+                        // if one is already fading
+                        if !faded {
+                            let streaming = output_stream.is_some() || null_streams.is_some();
+                            let at = match deferred.take() {
+                                Some((at, _)) => Some(at),
+                                None => streaming.then(|| fade_out_output(&core)).flatten(),
+                            };
+                            if let Some(at) = at {
+                                let span = Span::current();
+                                deferred = Some((at, Traced { span, value: AudioEngineCommand::Shutdown }));
+                                continue;
+                            }
                         }
-                    }
 
-                    AudioEngineCommand::StopSound { id } => {
-                        if let Ok(mut state) = audio_state.lock() {
-                            state.playing_sounds.remove(&id);
+                        if let Some(active) = session.take() {
+                            active.store(false, Ordering::Relaxed);
                         }
-                    }
-
-                    AudioEngineCommand::SetMicVolume(volume) => {
-                        mic_volume.store(f32::to_bits(volume.clamp(0.0, 2.0)), Ordering::Relaxed);
-                    }
-
-                    AudioEngineCommand::SetMasterVolume(volume) => {
-                        master_volume.store(f32::to_bits(volume.clamp(0.0, 2.0)), Ordering::Relaxed);
-                    }
-
-                    AudioEngineCommand::SetMicMuted(muted) => {
-                        mic_muted.store(muted, Ordering::Relaxed);
-                    }
 
-                    AudioEngineCommand::Shutdown => {
                         // Pause streams before dropping
                         if let Some(ref stream) = input_stream {
                             let _ = stream.pause();
@@ -513,10 +1360,33 @@ fn run_engine_thread(
 
                         drop(input_stream);
                         drop(output_stream);
+                        drop(monitor_stream);
+                        drop(null_streams);
                         is_running.store(false, Ordering::SeqCst);
                         tracing::info!("Audio engine shutdown");
                         return;
                     }
+
+                    AudioEngineCommand::SetMonitor { device, settings } => {
+                        monitor = device.filter(|_| settings.enabled).map(|device| (device, settings));
+
+                        monitor_stream = None;
+                        if let Ok(mut producer) = core.monitor.lock() {
+                            *producer = None;
+                        }
+                        let hardware_format = running_format.filter(|_| null_devices.is_none());
+                        if let (Some((device, settings)), Some((sample_rate, channels))) = (&monitor, hardware_format) {
+                            monitor_stream = open_monitor_or_report(
+                                &host, &core, device, settings, sample_rate, channels, &event_tx,
+                            );
+                        }
+                    }
+
+                    // Playback and volume commands are shared with the offline driver
+                    other => {
+                        plays.started(&other);
+                        core.handle_command(other)
+                    }
                 }
             }
             Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
@@ -534,10 +1404,128 @@ fn run_engine_thread(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::OutputLayout;
 
     #[test]
     fn test_engine_creation() {
         let engine = AudioEngine::new();
         assert!(!engine.is_running());
     }
+
+    #[test]
+    fn test_event_receiver_has_one_owner() {
+        let engine = AudioEngine::new();
+        assert!(engine.take_event_receiver().is_some());
+        assert!(engine.take_event_receiver().is_none());
+    }
+
+    #[test]
+    fn test_null_devices_capture_the_mix() {
+        let devices = NullAudioDevices::new();
+        let engine = AudioEngine::with_null_devices(devices.clone());
+        engine
+            .send_command(AudioEngineCommand::Start {
+                input_device: "any".into(),
+                output_device: "any".into(),
+                sample_rate: 48000,
+                channels: 2,
+                output_layout: OutputLayoutSettings::default(),
+                buffer_frames: None,
+            })
+            .unwrap();
+        engine
+            .send_command(AudioEngineCommand::PlaySound {
+                id: "beep".into(),
+                samples: vec![0.5; 2 * 4800],
+                sample_rate: 48000,
+                channels: 2,
+                volume: 1.0,
+                looping: false,
+            })
+            .unwrap();
+
+        // Let the null devices render the 100 ms sound
+        thread::sleep(Duration::from_millis(300));
+        assert!(engine.is_running());
+        let captured = devices.take_captured();
+        assert!(captured.len() >= 2 * 4800);
+        assert!(captured.iter().any(|s| (*s - 0.5).abs() < 1e-6));
+
+        engine.send_command(AudioEngineCommand::Stop).unwrap();
+    }
+
+    fn setup() -> StreamSetup {
+        StreamSetup {
+            input_device: "Mic".into(),
+            output_device: "Cable".into(),
+            sample_rate: 48000,
+            channels: 2,
+            highest_input_channel: None,
+            monitor_device: Some("Headphones".into()),
+            monitor: MonitorSettings::default(),
+            output_layout: OutputLayoutSettings::default(),
+            buffer_frames: None,
+        }
+    }
+
+    #[test]
+    fn test_setup_changes() {
+        let running = setup();
+        assert!(setup().changes_from(&running).is_empty());
+
+        // A monitor that is off doesn't care about its device or volume
+        let mut quiet = setup();
+        quiet.monitor_device = None;
+        quiet.monitor.volume = 0.5;
+        assert!(quiet.changes_from(&running).is_empty());
+
+        let mut switched = setup();
+        switched.output_device = "Other".into();
+        switched.sample_rate = 44100;
+        switched.monitor.enabled = true;
+        assert_eq!(
+            switched.changes_from(&running),
+            vec![SetupChange::OutputDevice, SetupChange::SampleRate, SetupChange::Monitor]
+        );
+
+        let mut surround = setup();
+        surround.output_layout.layout = OutputLayout::Surround51;
+        assert_eq!(surround.changes_from(&running), vec![SetupChange::OutputLayout]);
+
+        let mut tuned = setup();
+        tuned.buffer_frames = Some(256);
+        assert_eq!(tuned.changes_from(&running), vec![SetupChange::BufferSize]);
+    }
+
+    #[test]
+    fn test_start_is_idempotent() {
+        let engine = AudioEngine::with_null_devices(NullAudioDevices::new());
+        assert_eq!(engine.start(setup()).unwrap().action, StartAction::Started);
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(engine.start(setup()).unwrap().action, StartAction::Unchanged);
+        let mut monitored = setup();
+        monitored.monitor.enabled = true;
+        assert_eq!(engine.start(monitored.clone()).unwrap().action, StartAction::HotSwitched);
+        monitored.input_device = "Other mic".into();
+        assert_eq!(
+            engine.start(monitored).unwrap(),
+            StartOutcome {
+                action: StartAction::Restarted,
+                changes: vec![SetupChange::InputDevice],
+            }
+        );
+
+        // A stop forgets the setup, even before the engine thread gets to it
+        engine.send_command(AudioEngineCommand::Stop).unwrap();
+        assert_eq!(engine.start(setup()).unwrap().action, StartAction::Started);
+        engine.send_command(AudioEngineCommand::Stop).unwrap();
+    }
+
+    #[test]
+    fn test_device_in_use_detection() {
+        assert!(is_device_in_use("A backend-specific error has occurred: 0x8889000A"));
+        assert!(is_device_in_use("The device is already in use."));
+        assert!(!is_device_in_use("The requested device is no longer available"));
+    }
 }
@@ -0,0 +1,2453 @@
+//! Audio processing stages shared by the real-time engine and the offline driver
+//!
+//! The cpal callbacks in the audio engine and the deterministic offline
+//! driver both run the exact same processors, so anything tested offline
+//! behaves identically on real hardware.
+
+use crate::application::audio_engine::AudioEngineCommand;
+use crate::application::session_recorder::RecordingTap;
+use crate::application::sound_stream::SoundStream;
+use crate::domain::{
+    AudioBuffer, DuckingSettings, GeneratorSettings, InputChannelMap, OutputFormatSettings, SoundInsert,
+    VoiceActivitySettings,
+};
+use crate::dsp::{
+    AudioResampler, BroadcastDelay, ChainEditor, Dither, EchoCanceller, EffectChain, KeystrokeClock, MicDucker, OutputRamp, SignalGenerator, SoundInsertChain, StereoWidener,
+    ECHO_CANCELLER_TAPS,
+};
+use ringbuf::traits::{Consumer, Observer, Producer};
+use serde::Serialize;
+use ringbuf::{HeapCons, HeapProd};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Lock-free controls read by the audio callbacks
+pub struct EngineControls {
+    mic_volume: AtomicU32,
+    master_volume: AtomicU32,
+    mic_muted: AtomicBool,
+    /// Mute of the mixer's mic channel (mute groups, cough button), faded
+    mic_channel_muted: AtomicBool,
+    /// Whether the broadcast delay holds the output back
+    broadcast_delay: AtomicBool,
+    /// Dump of the broadcast delay requested, taken by the output callback
+    dump_delay: AtomicBool,
+    echo_cancellation: AtomicBool,
+    /// Requested output format; only touched outside the callbacks
+    output_format: Mutex<OutputFormatSettings>,
+    /// Native integer depth of the output device (0 for float)
+    device_bits: AtomicU32,
+    /// Depth the mix is rounded to (0 for none), resolved from the two above
+    quantize_bits: AtomicU32,
+    dither: AtomicBool,
+    /// Speaking detection, read by the level thread
+    voice_activity: Mutex<VoiceActivitySettings>,
+    /// Headphone limiter ceiling (linear) of the monitor, 0 when off
+    headphone_ceiling: AtomicU32,
+    /// Auto-mute on a feedback loop, read by the level thread
+    feedback_protection: AtomicBool,
+    /// Mute set by the level thread on a feedback loop, apart from the user's
+    feedback_muted: AtomicBool,
+    /// Push-to-talk gate closed, written by its poll thread
+    talk_gate_closed: AtomicBool,
+    /// Output buffers the mic ran dry in since the last start
+    underruns: AtomicU32,
+    /// Fade of the whole output on start and before stop, 0 for none
+    output_ramp_ms: AtomicU32,
+    /// Stop requested: the output callback ramps down to silence
+    output_closing: AtomicBool,
+}
+
+impl EngineControls {
+    pub fn new() -> Self {
+        Self {
+            mic_volume: AtomicU32::new(f32::to_bits(1.0)),
+            master_volume: AtomicU32::new(f32::to_bits(1.0)),
+            mic_muted: AtomicBool::new(false),
+            mic_channel_muted: AtomicBool::new(false),
+            broadcast_delay: AtomicBool::new(false),
+            dump_delay: AtomicBool::new(false),
+            echo_cancellation: AtomicBool::new(false),
+            output_format: Mutex::new(OutputFormatSettings::default()),
+            device_bits: AtomicU32::new(0),
+            quantize_bits: AtomicU32::new(0),
+            dither: AtomicBool::new(true),
+            voice_activity: Mutex::new(VoiceActivitySettings::default()),
+            headphone_ceiling: AtomicU32::new(0),
+            feedback_protection: AtomicBool::new(false),
+            feedback_muted: AtomicBool::new(false),
+            talk_gate_closed: AtomicBool::new(false),
+            underruns: AtomicU32::new(0),
+            output_ramp_ms: AtomicU32::new(0),
+            output_closing: AtomicBool::new(false),
+        }
+    }
+
+    pub fn mic_volume(&self) -> f32 {
+        f32::from_bits(self.mic_volume.load(Ordering::Relaxed))
+    }
+
+    /// Set microphone volume (0.0 - 2.0)
+    pub fn set_mic_volume(&self, volume: f32) {
+        self.mic_volume.store(f32::to_bits(volume.clamp(0.0, 2.0)), Ordering::Relaxed);
+    }
+
+    pub fn master_volume(&self) -> f32 {
+        f32::from_bits(self.master_volume.load(Ordering::Relaxed))
+    }
+
+    /// Set master volume (0.0 - 2.0)
+    pub fn set_master_volume(&self, volume: f32) {
+        self.master_volume.store(f32::to_bits(volume.clamp(0.0, 2.0)), Ordering::Relaxed);
+    }
+
+    pub fn is_mic_muted(&self) -> bool {
+        self.mic_muted.load(Ordering::Relaxed)
+    }
+
+    pub fn set_mic_muted(&self, muted: bool) {
+        self.mic_muted.store(muted, Ordering::Relaxed);
+    }
+
+    /// Whether any mute source holds the mic closed
+    pub fn is_mic_silenced(&self) -> bool {
+        self.is_mic_muted() || self.is_feedback_muted() || self.is_talk_gate_closed()
+    }
+
+    pub fn is_talk_gate_closed(&self) -> bool {
+        self.talk_gate_closed.load(Ordering::Relaxed)
+    }
+
+    pub fn set_talk_gate_closed(&self, closed: bool) {
+        self.talk_gate_closed.store(closed, Ordering::Relaxed);
+    }
+
+    pub fn is_mic_channel_muted(&self) -> bool {
+        self.mic_channel_muted.load(Ordering::Relaxed)
+    }
+
+    pub fn set_mic_channel_muted(&self, muted: bool) {
+        self.mic_channel_muted.store(muted, Ordering::Relaxed);
+    }
+
+    pub fn broadcast_delay(&self) -> bool {
+        self.broadcast_delay.load(Ordering::Relaxed)
+    }
+
+    /// Ask the output callback to dump the broadcast delay
+    pub fn request_delay_dump(&self) {
+        self.dump_delay.store(true, Ordering::Relaxed);
+    }
+
+    fn take_delay_dump(&self) -> bool {
+        self.dump_delay.swap(false, Ordering::Relaxed)
+    }
+
+    pub fn voice_activity(&self) -> VoiceActivitySettings {
+        self.voice_activity.lock().map(|settings| *settings).unwrap_or_default()
+    }
+
+    pub fn set_voice_activity(&self, settings: VoiceActivitySettings) {
+        if let Ok(mut voice_activity) = self.voice_activity.lock() {
+            *voice_activity = settings;
+        }
+    }
+
+    pub fn feedback_protection(&self) -> bool {
+        self.feedback_protection.load(Ordering::Relaxed)
+    }
+
+    pub fn set_feedback_protection(&self, enabled: bool) {
+        self.feedback_protection.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.set_feedback_muted(false);
+        }
+    }
+
+    pub fn is_feedback_muted(&self) -> bool {
+        self.feedback_muted.load(Ordering::Relaxed)
+    }
+
+    pub fn set_feedback_muted(&self, muted: bool) {
+        self.feedback_muted.store(muted, Ordering::Relaxed);
+    }
+
+    /// Output buffers the mic ran dry in since the last start
+    pub fn underruns(&self) -> u32 {
+        self.underruns.load(Ordering::Relaxed)
+    }
+
+    fn record_underrun(&self) {
+        self.underruns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn reset_underruns(&self) {
+        self.underruns.store(0, Ordering::Relaxed);
+    }
+
+    pub fn output_ramp_ms(&self) -> u32 {
+        self.output_ramp_ms.load(Ordering::Relaxed)
+    }
+
+    /// Ask the output callback to ramp down to silence, ahead of a stop
+    pub fn close_output(&self) {
+        self.output_closing.store(true, Ordering::Relaxed);
+    }
+
+    fn is_output_closing(&self) -> bool {
+        self.output_closing.load(Ordering::Relaxed)
+    }
+
+    /// Ceiling of the monitor's headphone limiter in dBFS, None when off
+    pub fn headphone_ceiling_db(&self) -> Option<f32> {
+        let ceiling = f32::from_bits(self.headphone_ceiling.load(Ordering::Relaxed));
+        (ceiling > 0.0).then(|| 20.0 * ceiling.log10())
+    }
+
+    pub fn set_headphone_ceiling_db(&self, ceiling_db: Option<f32>) {
+        let ceiling = ceiling_db.map(|db| 10f32.powf(db / 20.0)).unwrap_or(0.0);
+        self.headphone_ceiling.store(ceiling.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn echo_cancellation(&self) -> bool {
+        self.echo_cancellation.load(Ordering::Relaxed)
+    }
+
+    pub fn set_echo_cancellation(&self, enabled: bool) {
+        self.echo_cancellation.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Depth the mix is rounded to, None to keep full float
+    pub fn quantize_bits(&self) -> Option<u32> {
+        Some(self.quantize_bits.load(Ordering::Relaxed)).filter(|bits| *bits > 0)
+    }
+
+    pub fn dither(&self) -> bool {
+        self.dither.load(Ordering::Relaxed)
+    }
+
+    pub fn set_output_format(&self, format: OutputFormatSettings) {
+        if let Ok(mut output_format) = self.output_format.lock() {
+            *output_format = format;
+        }
+        self.dither.store(format.dither, Ordering::Relaxed);
+        self.resolve_quantize_bits();
+    }
+
+    /// Record the output device's native integer depth (None for float)
+    pub fn set_device_bits(&self, bits: Option<u32>) {
+        self.device_bits.store(bits.unwrap_or(0), Ordering::Relaxed);
+        self.resolve_quantize_bits();
+    }
+
+    fn resolve_quantize_bits(&self) {
+        let device_bits = Some(self.device_bits.load(Ordering::Relaxed)).filter(|bits| *bits > 0);
+        let bits = self
+            .output_format
+            .lock()
+            .map(|format| format.bit_depth.quantize_bits(device_bits))
+            .unwrap_or_default();
+        self.quantize_bits.store(bits.unwrap_or(0), Ordering::Relaxed);
+    }
+}
+
+impl Default for EngineControls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where a playing sound's samples come from
+enum SoundSource {
+    /// Decoded up front
+    Buffered(Vec<f32>),
+    /// Decoded while it plays; `chunk` holds what was last read from it
+    Streamed { stream: SoundStream, chunk: Vec<f32> },
+}
+
+/// Samples read from a stream per chunk, reserved so the callback
+/// doesn't allocate
+const STREAM_CHUNK: usize = 8192;
+
+/// Dip of a sound around a seek, so the jump doesn't click
+const SEEK_RAMP_MS: u32 = 5;
+
+impl SoundSource {
+    fn streamed(stream: SoundStream) -> Self {
+        SoundSource::Streamed {
+            stream,
+            chunk: Vec::with_capacity(STREAM_CHUNK),
+        }
+    }
+
+    /// Length in samples; a stream's is the file's reported duration
+    fn len(&self) -> usize {
+        match self {
+            SoundSource::Buffered(samples) => samples.len(),
+            SoundSource::Streamed { stream, .. } => stream.len_hint(),
+        }
+    }
+}
+
+/// A sound that is currently playing
+struct PlayingSound {
+    /// Id it was played under, to report its end
+    id: String,
+    source: SoundSource,
+    /// Samples played so far (within the loop, for a buffered loop)
+    position: usize,
+    /// Restart from the first sample at the end, until stopped
+    looping: bool,
+    /// Held at its position; ramps out over one buffer, then goes silent
+    paused: bool,
+    widener: Option<StereoWidener>,
+    inserts: Option<SoundInsertChain>,
+    /// Gain reached at the end of the last mix, ramped toward the sound's volume
+    gain: f32,
+    /// Fade-out level (1.0 until stopped) and its drop per frame once stopped
+    fade_level: f32,
+    fade_step: Option<f32>,
+    /// Seek waiting for the sound to dip out, or for its stream's decoder
+    seek: Option<PendingSeek>,
+    /// Closed for a seek, reopened once the playhead has moved
+    seek_ramp: OutputRamp,
+    /// A seek failed since last reported; the sound played on from where it was
+    seek_failed: bool,
+}
+
+/// Where a seek moves a sound to
+struct PendingSeek {
+    /// Playhead after the seek, in samples
+    position: usize,
+    /// Time from the start of the sound, for a stream's decoder
+    time: Duration,
+    /// Playhead asked of the stream's decoder, until it answers
+    requested: Option<usize>,
+}
+
+impl PlayingSound {
+    /// An empty sound left in place of one moved out, owning nothing
+    fn ended() -> Self {
+        Self {
+            id: String::new(),
+            source: SoundSource::Buffered(Vec::new()),
+            position: 0,
+            looping: false,
+            paused: false,
+            widener: None,
+            inserts: None,
+            gain: 0.0,
+            fade_level: 0.0,
+            fade_step: None,
+            seek: None,
+            seek_ramp: OutputRamp::unity(0, 0),
+            seek_failed: false,
+        }
+    }
+
+    /// Move the playhead once the sound has dipped out for it
+    fn seek(&mut self, position: usize, time: Duration, channels: usize) {
+        match self.seek.as_mut() {
+            // A stream keeps the seek in flight and moves on once it answers
+            Some(seek) => {
+                seek.position = position;
+                seek.time = time;
+            }
+            None => {
+                self.seek = Some(PendingSeek {
+                    position,
+                    time,
+                    requested: None,
+                })
+            }
+        }
+        self.seek_ramp.close();
+        self.advance_seek(channels);
+    }
+
+    /// Carry a pending seek forward: jump once silent, ramping back in
+    ///
+    /// A stream is asked to seek, and only moves once its decoder did; when
+    /// that fails the sound ramps back in where it was.
+    fn advance_seek(&mut self, channels: usize) {
+        let silent = self.seek_ramp.is_closed() || (self.paused && self.gain <= 0.0);
+        let Some(seek) = self.seek.as_mut().filter(|_| silent) else {
+            return;
+        };
+        match &mut self.source {
+            SoundSource::Buffered(samples) => {
+                let last_frame = samples.len() / channels;
+                self.position = seek.position.min(last_frame * channels);
+            }
+            SoundSource::Streamed { stream, .. } => {
+                let Some(requested) = seek.requested else {
+                    stream.seek(seek.time);
+                    seek.requested = Some(seek.position);
+                    return;
+                };
+                match stream.seek_result() {
+                    None => return,
+                    Some(true) => self.position = requested,
+                    Some(false) => self.seek_failed = true,
+                }
+                // Sought again while the decoder was busy
+                if requested != seek.position {
+                    seek.requested = None;
+                    return;
+                }
+            }
+        }
+        self.seek = None;
+        self.seek_ramp.reopen();
+    }
+
+    /// Add the next chunk into interleaved `data`, ramping the gain to
+    /// `target` and applying any fade-out
+    ///
+    /// A looping sound wraps around within the buffer, so its end runs
+    /// straight into its start through the same widener and inserts; a
+    /// looping stream is rewound by its decoder. A stream that runs dry
+    /// leaves the rest of the buffer silent and carries on next time.
+    /// Returns true once the sound has ended or faded out.
+    fn mix_into(&mut self, data: &mut [f32], channels: usize, target: f32, scratch: &mut Vec<f32>) -> bool {
+        self.advance_seek(channels);
+        let total = match &self.source {
+            SoundSource::Buffered(samples) if !self.looping => (samples.len() - self.position).min(data.len()),
+            _ => data.len(),
+        };
+        let step = (target - self.gain) / (total / channels).max(1) as f32;
+
+        let mut offset = 0;
+        while offset < total {
+            let chunk: &[f32] = match &mut self.source {
+                SoundSource::Buffered(samples) => {
+                    if self.position >= samples.len() {
+                        if !self.looping || samples.is_empty() {
+                            break;
+                        }
+                        self.position = 0;
+                    }
+                    let to_mix = (samples.len() - self.position).min(total - offset);
+                    &samples[self.position..self.position + to_mix]
+                }
+                SoundSource::Streamed { stream, chunk } => {
+                    stream.pop_into(chunk, (total - offset).min(STREAM_CHUNK));
+                    if chunk.is_empty() {
+                        break;
+                    }
+                    chunk
+                }
+            };
+            let to_mix = chunk.len();
+
+            let chunk = if self.widener.is_some() || self.inserts.is_some() {
+                scratch.clear();
+                scratch.extend_from_slice(chunk);
+                if let Some(widener) = self.widener.as_mut() {
+                    widener.process(scratch, channels);
+                }
+                if let Some(inserts) = self.inserts.as_mut() {
+                    inserts.process(scratch, channels);
+                }
+                &scratch[..]
+            } else {
+                chunk
+            };
+
+            for (frame, values) in data[offset..offset + to_mix].chunks_mut(channels).zip(chunk.chunks(channels)) {
+                self.gain += step;
+                if let Some(fade_step) = self.fade_step {
+                    self.fade_level = (self.fade_level - fade_step).max(0.0);
+                }
+                let mut gain = self.gain * self.fade_level;
+                if !self.seek_ramp.is_open() {
+                    gain *= self.seek_ramp.next_gain();
+                }
+                for (sample, &value) in frame.iter_mut().zip(values) {
+                    *sample = (*sample + value * gain).clamp(-1.0, 1.0);
+                }
+            }
+
+            self.position += to_mix;
+            offset += to_mix;
+        }
+        self.gain = target;
+
+        let ended = match &self.source {
+            SoundSource::Buffered(samples) => (!self.looping || samples.is_empty()) && self.position >= samples.len(),
+            SoundSource::Streamed { stream, .. } => stream.is_drained(),
+        };
+        // Ending inside the dip of a seek, it plays on from where it lands
+        (ended && self.seek.is_none()) || self.fade_level <= 0.0
+    }
+}
+
+/// A sound the mixer is playing, as shown to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayingSoundInfo {
+    pub id: String,
+    pub position_secs: f64,
+    pub duration_secs: f64,
+    pub volume: f32,
+    pub looping: bool,
+    pub paused: bool,
+}
+
+/// Gain of a scene on a sound, ramping to its target
+#[derive(Debug, Clone, Copy)]
+struct SceneGain {
+    current: f32,
+    target: f32,
+    /// Change per frame
+    step: f32,
+}
+
+impl SceneGain {
+    /// Move `frames` closer to the target
+    fn advance(&mut self, frames: usize) {
+        let change = self.step * frames as f32;
+        self.current = if self.current < self.target {
+            (self.current + change).min(self.target)
+        } else {
+            (self.current - change).max(self.target)
+        };
+    }
+}
+
+/// The set of sounds mixed into the output
+#[derive(Default)]
+pub struct SoundMixer {
+    playing_sounds: HashMap<String, PlayingSound>,
+    /// Sounds started together at the beginning of the next mixed buffer
+    pending: Vec<(String, PlayingSound)>,
+    /// Stopped or retriggered sounds still fading out
+    fading: Vec<PlayingSound>,
+    /// Sounds that ended or faded out in the callback, freed by the
+    /// engine thread
+    retired: Vec<PlayingSound>,
+    /// Fade-out of stops that don't ask for their own, in milliseconds
+    stop_fade_ms: u32,
+    /// Stereo width per sound id, kept across plays
+    widths: HashMap<String, f32>,
+    /// Volume per sound id, kept across plays
+    volumes: HashMap<String, f32>,
+    /// Gain a scene puts on top of the volume, per sound id; never saved
+    scene_gains: HashMap<String, SceneGain>,
+    /// Effect inserts per sound id, kept across plays
+    inserts: HashMap<String, Vec<SoundInsert>>,
+    /// Rate the inserts are tuned for (0 until the engine starts)
+    sample_rate: u32,
+    /// Channels the samples are interleaved with (0 until the engine starts)
+    channels: usize,
+    scratch: Vec<f32>,
+}
+
+impl SoundMixer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start playing a sound, replacing any sound with the same id
+    pub fn play(&mut self, id: String, samples: Vec<f32>) {
+        self.play_sound(id, samples, false);
+    }
+
+    /// Start playing a sound that restarts at its end until stopped
+    pub fn play_looping(&mut self, id: String, samples: Vec<f32>) {
+        self.play_sound(id, samples, true);
+    }
+
+    /// Start playing a sound decoded while it plays, replacing any sound
+    /// with the same id
+    pub fn play_stream(&mut self, id: String, stream: SoundStream, looping: bool) {
+        self.start(id, SoundSource::streamed(stream), looping);
+    }
+
+    fn play_sound(&mut self, id: String, samples: Vec<f32>, looping: bool) {
+        self.start(id, SoundSource::Buffered(samples), looping);
+    }
+
+    fn start(&mut self, id: String, source: SoundSource, looping: bool) {
+        let sound = PlayingSound {
+            looping,
+            ..self.start_sound(&id, source)
+        };
+        // A retriggered pad fades its previous play out under the new one
+        if let Some(previous) = self.playing_sounds.insert(id, sound) {
+            self.fade_out(previous, None);
+        }
+        self.reserve_retired();
+    }
+
+    /// Room to retire every sound held, so the callback never grows it
+    fn reserve_retired(&mut self) {
+        self.retired.reserve(self.playing_sounds.len() + self.pending.len() + self.fading.len());
+    }
+
+    /// Start sounds together, on the same buffer boundary and sample offset
+    ///
+    /// The sounds wait in the pending queue and are committed at the start
+    /// of the next mix, so layered stingers or stems stay frame-aligned
+    /// however the commands and callbacks interleave.
+    pub fn play_synced(&mut self, sounds: Vec<(String, Vec<f32>)>) {
+        for (id, samples) in sounds {
+            self.pending.retain(|(pending, _)| *pending != id);
+            let sound = self.start_sound(&id, SoundSource::Buffered(samples));
+            self.pending.push((id, sound));
+        }
+        // Room for the whole group, so committing it never grows the map
+        // inside the callback
+        self.playing_sounds.reserve(self.pending.len());
+        self.reserve_retired();
+    }
+
+    fn start_sound(&self, id: &str, source: SoundSource) -> PlayingSound {
+        PlayingSound {
+            id: id.to_string(),
+            source,
+            position: 0,
+            looping: false,
+            paused: false,
+            widener: self.widener_for(id),
+            inserts: self.inserts_for(id),
+            gain: self.volume_of(id) * self.scene_gain_of(id),
+            fade_level: 1.0,
+            fade_step: None,
+            seek: None,
+            seek_ramp: OutputRamp::unity(SEEK_RAMP_MS, self.sample_rate()),
+            seek_failed: false,
+        }
+    }
+
+    /// Format of the output, used to tune inserts of later plays and to
+    /// report positions
+    pub fn set_format(&mut self, sample_rate: u32, channels: usize) {
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+        // Room for a whole chunk, so widening and inserts never grow it in the callback
+        self.scratch.reserve(STREAM_CHUNK);
+    }
+
+    /// Rate and channels sounds are mixed at, None until the engine starts
+    pub fn format(&self) -> Option<(u32, usize)> {
+        (self.sample_rate > 0 && self.channels > 0).then_some((self.sample_rate, self.channels))
+    }
+
+    /// Set the stereo width of a sound (1.0 = unchanged), now and for later plays
+    pub fn set_width(&mut self, id: String, width: f32) {
+        if (width - 1.0).abs() < f32::EPSILON {
+            self.widths.remove(&id);
+        } else {
+            self.widths.insert(id.clone(), width);
+        }
+
+        let widened = self.widths.contains_key(&id);
+        let pending = self.pending.iter_mut().filter(|(pending, _)| *pending == id).map(|(_, sound)| sound);
+        for sound in self.playing_sounds.get_mut(&id).into_iter().chain(pending) {
+            match (sound.widener.as_mut(), widened) {
+                (Some(current), true) => current.set_width(width),
+                (_, widened) => sound.widener = widened.then(|| StereoWidener::new(width)),
+            }
+        }
+    }
+
+    fn widener_for(&self, id: &str) -> Option<StereoWidener> {
+        self.widths.get(id).map(|&width| StereoWidener::new(width))
+    }
+
+    /// Set the effect inserts of a sound, now and for later plays
+    ///
+    /// A playing sound keeps its filter state when only parameters change,
+    /// so a strength slider can be dragged while the track plays.
+    pub fn set_inserts(&mut self, id: String, inserts: Vec<SoundInsert>) {
+        if inserts.is_empty() {
+            self.inserts.remove(&id);
+        } else {
+            self.inserts.insert(id.clone(), inserts);
+        }
+
+        let rate = self.sample_rate();
+        let inserts = self.inserts.get(&id).map(Vec::as_slice).unwrap_or_default();
+        let pending = self.pending.iter_mut().filter(|(pending, _)| *pending == id).map(|(_, sound)| sound);
+        for sound in self.playing_sounds.get_mut(&id).into_iter().chain(pending) {
+            let updated = sound.inserts.as_mut().is_some_and(|current| current.update(inserts));
+            if !updated {
+                sound.inserts = Some(SoundInsertChain::new(inserts, rate)).filter(|chain| !chain.is_empty());
+            }
+        }
+    }
+
+    fn inserts_for(&self, id: &str) -> Option<SoundInsertChain> {
+        self.inserts.get(id).map(|inserts| SoundInsertChain::new(inserts, self.sample_rate()))
+    }
+
+    fn sample_rate(&self) -> u32 {
+        if self.sample_rate > 0 { self.sample_rate } else { 48_000 }
+    }
+
+    /// Set the volume of a sound (0.0 - 2.0), now and for later plays
+    ///
+    /// A playing sound ramps to the new volume over the next buffer, so a
+    /// stem can be muted on the fly without a click.
+    pub fn set_volume(&mut self, id: String, volume: f32) {
+        let volume = volume.clamp(0.0, 2.0);
+        if (volume - 1.0).abs() < f32::EPSILON {
+            self.volumes.remove(&id);
+        } else {
+            self.volumes.insert(id, volume);
+        }
+    }
+
+    fn volume_of(&self, id: &str) -> f32 {
+        self.volumes.get(id).copied().unwrap_or(1.0)
+    }
+
+    /// Ramp the scene gain of a sound (0.0 - 2.0) to `gain` over `fade_ms`,
+    /// from wherever it is now
+    ///
+    /// It applies on top of the volume, to this play and later ones, so a
+    /// scene crossfade never touches the volume that is saved.
+    pub fn set_scene_gain(&mut self, id: String, gain: f32, fade_ms: u32) {
+        let target = gain.clamp(0.0, 2.0);
+        let frames = fade_ms as u64 * self.sample_rate() as u64 / 1000;
+        let gain = match frames {
+            // Playing sounds still ramp to it over a buffer
+            0 => SceneGain { current: target, target, step: 0.0 },
+            frames => {
+                let current = self.scene_gain_of(&id);
+                SceneGain { current, target, step: (target - current).abs() / frames as f32 }
+            }
+        };
+        self.scene_gains.insert(id, gain);
+    }
+
+    fn scene_gain_of(&self, id: &str) -> f32 {
+        self.scene_gains.get(id).map_or(1.0, |gain| gain.current)
+    }
+
+    /// Fade-out used by stops without their own (0 cuts instantly)
+    pub fn set_stop_fade(&mut self, fade_ms: u32) {
+        self.stop_fade_ms = fade_ms;
+    }
+
+    pub fn stop_fade_ms(&self) -> u32 {
+        self.stop_fade_ms
+    }
+
+    /// Stop a playing or pending sound, fading it out over `fade_ms`
+    /// (the stop fade when `None`)
+    pub fn stop(&mut self, id: &str, fade_ms: Option<u32>) {
+        // Not heard yet, so nothing to fade
+        while let Some(index) = self.pending.iter().position(|(pending, _)| pending == id) {
+            let (_, sound) = self.pending.remove(index);
+            self.retired.push(sound);
+        }
+        if let Some(sound) = self.playing_sounds.remove(id) {
+            self.fade_out(sound, fade_ms);
+        }
+    }
+
+    /// Stop every sound, fading out over `fade_ms` (the stop fade when `None`)
+    ///
+    /// Returns the fade applied, 0 if nothing was playing.
+    pub fn stop_all(&mut self, fade_ms: Option<u32>) -> u32 {
+        self.retired.extend(self.pending.drain(..).map(|(_, sound)| sound));
+        let playing: Vec<PlayingSound> = self.playing_sounds.drain().map(|(_, sound)| sound).collect();
+        if playing.is_empty() && self.fading.is_empty() {
+            return 0;
+        }
+        for sound in playing {
+            self.fade_out(sound, fade_ms);
+        }
+        fade_ms.unwrap_or(self.stop_fade_ms)
+    }
+
+    /// Keep mixing a stopped sound until it has faded out
+    fn fade_out(&mut self, mut sound: PlayingSound, fade_ms: Option<u32>) {
+        let frames = fade_ms.unwrap_or(self.stop_fade_ms) as u64 * self.sample_rate() as u64 / 1000;
+        if frames == 0 {
+            self.retired.push(sound);
+            return;
+        }
+        let step = 1.0 / frames as f32;
+        // A sound already fading keeps the faster of the two fades
+        sound.fade_step = Some(sound.fade_step.map_or(step, |current| current.max(step)));
+        self.fading.push(sound);
+    }
+
+    /// Pause or resume a playing sound, keeping its position
+    ///
+    /// Both ramp over a buffer, so neither clicks.
+    pub fn set_paused(&mut self, id: &str, paused: bool) {
+        for sound in self.sounds_mut(id) {
+            sound.paused = paused;
+        }
+    }
+
+    /// Move a playing sound to `position_secs` from its start, clamped to
+    /// its length
+    ///
+    /// The sound dips out, jumps and ramps back in, so the seek doesn't
+    /// click. A streamed sound stays quiet until its decoder has moved; if
+    /// that fails it carries on from where it was, and the failure is
+    /// reported by [`Self::take_failed_seeks`].
+    pub fn seek(&mut self, id: &str, position_secs: f64) {
+        let channels = self.channels.max(1);
+        let time = Duration::from_secs_f64(position_secs.max(0.0));
+        let position = (time.as_secs_f64() * self.sample_rate() as f64) as usize * channels;
+        for sound in self.sounds_mut(id) {
+            sound.seek(position, time, channels);
+        }
+    }
+
+    /// Ids of the sounds whose seek failed since the last call
+    pub fn take_failed_seeks(&mut self) -> Vec<String> {
+        self.playing_sounds
+            .values_mut()
+            .filter(|sound| std::mem::take(&mut sound.seek_failed))
+            .map(|sound| sound.id.clone())
+            .collect()
+    }
+
+    /// The playing and pending plays of a sound
+    fn sounds_mut<'a>(&'a mut self, id: &'a str) -> impl Iterator<Item = &'a mut PlayingSound> {
+        let pending = self.pending.iter_mut().filter(move |(pending, _)| pending == id).map(|(_, sound)| sound);
+        self.playing_sounds.get_mut(id).into_iter().chain(pending)
+    }
+
+    /// Cut every sound at once, fading ones included
+    pub fn clear(&mut self) {
+        self.retired.extend(self.playing_sounds.drain().map(|(_, sound)| sound));
+        self.retired.extend(self.pending.drain(..).map(|(_, sound)| sound));
+        self.retired.append(&mut self.fading);
+    }
+
+    /// Whether a sound is playing or about to start
+    pub fn is_playing(&self, id: &str) -> bool {
+        self.playing_sounds.contains_key(id) || self.pending.iter().any(|(pending, _)| pending == id)
+    }
+
+    pub fn playing_count(&self) -> usize {
+        self.playing_sounds.len() + self.pending.len()
+    }
+
+    /// Whether any sound is heard; paused ones aren't
+    pub fn is_audible(&self) -> bool {
+        self.playing_sounds.values().any(|sound| !sound.paused)
+    }
+
+    /// Playing and pending sounds with their positions, by id
+    pub fn playing(&self) -> Vec<PlayingSoundInfo> {
+        let samples_per_sec = self.sample_rate() as f64 * self.channels.max(1) as f64;
+        let pending = self.pending.iter().map(|(id, sound)| (id, sound));
+        let mut playing: Vec<PlayingSoundInfo> = self
+            .playing_sounds
+            .iter()
+            .chain(pending)
+            .map(|(id, sound)| PlayingSoundInfo {
+                id: id.clone(),
+                position_secs: sound.position as f64 / samples_per_sec,
+                duration_secs: sound.source.len() as f64 / samples_per_sec,
+                volume: self.volume_of(id),
+                looping: sound.looping,
+                paused: sound.paused,
+            })
+            .collect();
+        playing.sort_by(|a, b| a.id.cmp(&b.id));
+        playing
+    }
+
+    /// Add the next chunk of every playing and fading sound into
+    /// interleaved `data`
+    ///
+    /// Finished sounds are moved to the retired list rather than dropped,
+    /// so their buffers are freed off the audio thread.
+    pub fn mix_into(&mut self, data: &mut [f32], channels: usize) {
+        self.playing_sounds.extend(self.pending.drain(..));
+        let channels = channels.max(1);
+        // Each sound ramps to where its scene gain is at the end of the buffer
+        for gain in self.scene_gains.values_mut() {
+            gain.advance(data.len() / channels);
+        }
+        let volumes = &self.volumes;
+        let scene_gains = &self.scene_gains;
+        let scratch = &mut self.scratch;
+        let retired = &mut self.retired;
+
+        self.playing_sounds.retain(|id, sound| {
+            // Paused sounds ramp to silence, then hold their position
+            if sound.paused && sound.gain <= 0.0 {
+                sound.advance_seek(channels);
+                return true;
+            }
+            let target = if sound.paused {
+                0.0
+            } else {
+                volumes.get(id).copied().unwrap_or(1.0) * scene_gains.get(id).map_or(1.0, |gain| gain.current)
+            };
+            if sound.mix_into(data, channels, target, scratch) {
+                retired.push(std::mem::replace(sound, PlayingSound::ended()));
+                return false;
+            }
+            true
+        });
+
+        let mut index = 0;
+        while index < self.fading.len() {
+            let sound = &mut self.fading[index];
+            let target = sound.gain;
+            if sound.mix_into(data, channels, target, scratch) {
+                retired.push(self.fading.swap_remove(index));
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+/// Audio captured from another application, mixed in like a sound
+pub struct AppSource {
+    consumer: HeapCons<f32>,
+    /// Samples allowed to queue before the oldest are dropped, so clock
+    /// drift between the capture and the output never builds up delay
+    max_queued: usize,
+    gain: f32,
+}
+
+impl AppSource {
+    pub fn new(consumer: HeapCons<f32>, max_queued: usize, gain: f32) -> Self {
+        Self {
+            consumer,
+            max_queued,
+            gain,
+        }
+    }
+
+    /// Add the queued audio into interleaved `data`
+    fn mix_into(&mut self, data: &mut [f32], channels: usize) {
+        let excess = self.consumer.occupied_len().saturating_sub(self.max_queued + data.len());
+        self.consumer.skip(excess - excess % channels.max(1));
+
+        for sample in data.iter_mut() {
+            let Some(value) = self.consumer.try_pop() else {
+                break;
+            };
+            *sample = (*sample + value * self.gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+impl std::fmt::Debug for AppSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppSource").field("gain", &self.gain).finish()
+    }
+}
+
+/// A generator channel's test signal, mixed in like a sound
+pub struct GeneratorSource {
+    generator: SignalGenerator,
+    gain: f32,
+}
+
+impl GeneratorSource {
+    pub fn new(settings: &GeneratorSettings, sample_rate: u32, gain: f32) -> Self {
+        Self {
+            generator: SignalGenerator::new(settings, sample_rate),
+            gain,
+        }
+    }
+}
+
+impl std::fmt::Debug for GeneratorSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeneratorSource").field("gain", &self.gain).finish()
+    }
+}
+
+/// Fade of the mic channel mute, short enough for a cough button
+const MUTE_FADE_MS: f32 = 10.0;
+
+/// Longest echo reference kept waiting for the mic (about 1 s at 48 kHz)
+const MAX_ECHO_REFERENCE: usize = 48_000;
+
+/// State shared between the engine thread and the audio callbacks
+#[derive(Clone)]
+pub struct EngineCore {
+    pub controls: Arc<EngineControls>,
+    pub sounds: Arc<Mutex<SoundMixer>>,
+    /// Mic chain run by the input callback; only stream setup takes its
+    /// lock, changes go through `mic_editor`
+    pub mic_chain: Arc<Mutex<EffectChain>>,
+    /// Builds mic chain stages on the engine thread and queues them for
+    /// the callback
+    pub mic_editor: Arc<Mutex<ChainEditor>>,
+    /// Mono sound mix handed from the output to the echo canceller
+    pub echo_reference: Arc<Mutex<VecDeque<f32>>>,
+    /// Session recording fed with the final mix
+    pub recording: Arc<Mutex<Option<RecordingTap>>>,
+    /// Device channels feeding the mic; the channel count is fixed when
+    /// the input is built, gains and pans apply live
+    pub input_channel_map: Arc<Mutex<InputChannelMap>>,
+    /// Mono stems of the podcast mics (host, guest) for the recording
+    pub stem_taps: Arc<Mutex<Vec<RecordingTap>>>,
+    /// Copy of the final mix for the headphone monitor
+    pub monitor: Arc<Mutex<Option<HeapProd<f32>>>>,
+    /// Captured applications by mixer channel id
+    pub app_sources: Arc<Mutex<HashMap<String, AppSource>>>,
+    /// Test signals of generator channels by mixer channel id
+    pub generators: Arc<Mutex<HashMap<String, GeneratorSource>>>,
+    /// Delay of the output bus only; the monitor and recording stay live
+    pub broadcast_delay: Arc<Mutex<BroadcastDelay>>,
+    /// Key presses reported by the keyboard hook
+    pub keystrokes: KeystrokeClock,
+    /// Mic gain lowered while sounds play
+    pub mic_ducker: Arc<Mutex<MicDucker>>,
+    /// Playing sounds as last published by the engine loop
+    playing: Arc<Mutex<Arc<[PlayingSoundInfo]>>>,
+}
+
+impl EngineCore {
+    pub fn new() -> Self {
+        let mic_chain = Arc::new(Mutex::new(EffectChain::new()));
+        Self {
+            controls: Arc::new(EngineControls::new()),
+            sounds: Arc::new(Mutex::new(SoundMixer::new())),
+            mic_editor: Arc::new(Mutex::new(ChainEditor::new(mic_chain.clone()))),
+            mic_chain,
+            echo_reference: Arc::new(Mutex::new(VecDeque::new())),
+            recording: Arc::new(Mutex::new(None)),
+            input_channel_map: Arc::new(Mutex::new(InputChannelMap::AsIs)),
+            stem_taps: Arc::new(Mutex::new(Vec::new())),
+            monitor: Arc::new(Mutex::new(None)),
+            app_sources: Arc::new(Mutex::new(HashMap::new())),
+            generators: Arc::new(Mutex::new(HashMap::new())),
+            broadcast_delay: Arc::new(Mutex::new(BroadcastDelay::new(0, 48_000, 2))),
+            keystrokes: KeystrokeClock::new(),
+            mic_ducker: Arc::new(Mutex::new(MicDucker::new(DuckingSettings::default(), 48_000))),
+            playing: Arc::new(Mutex::new(Arc::from([]))),
+        }
+    }
+
+    /// Publish the playing sounds for [`Self::playing_snapshot`]
+    ///
+    /// Skipped while the callback holds the mixer, and while nothing plays
+    /// and nothing did at the last publish.
+    pub fn publish_playing(&self) {
+        let Ok(sounds) = self.sounds.try_lock() else {
+            return;
+        };
+        let idle = sounds.playing_count() == 0;
+        if idle && self.playing_snapshot().is_empty() {
+            return;
+        }
+        let playing: Arc<[PlayingSoundInfo]> = if idle { Arc::from([]) } else { sounds.playing().into() };
+        drop(sounds);
+        if let Ok(mut snapshot) = self.playing.lock() {
+            *snapshot = playing;
+        }
+    }
+
+    /// Ids of the sounds whose seek failed since the last call; empty while
+    /// the callback holds the mixer
+    pub fn take_failed_seeks(&self) -> Vec<String> {
+        self.sounds.try_lock().map(|mut sounds| sounds.take_failed_seeks()).unwrap_or_default()
+    }
+
+    /// Playing sounds as of the last [`Self::publish_playing`]
+    pub fn playing_snapshot(&self) -> Arc<[PlayingSoundInfo]> {
+        self.playing.lock().map(|snapshot| snapshot.clone()).unwrap_or_else(|_| Arc::from([]))
+    }
+
+    /// Device channels currently mapped to the mic
+    pub fn input_channel_map(&self) -> InputChannelMap {
+        self.input_channel_map.lock().map(|map| *map).unwrap_or_default()
+    }
+
+    /// Drop what the audio callbacks swapped out, off the audio thread
+    ///
+    /// Called from the engine loop, so replaced mic stages (and the worker
+    /// threads they own) don't linger until the next settings change, and
+    /// ended sounds free their samples here rather than in the output
+    /// callback. Returns the ids of the sounds that have finished, ended or
+    /// stopped, and aren't playing again; sounds retired while the mixer is
+    /// busy are reported on a later call.
+    pub fn drop_retired(&self) -> Vec<String> {
+        if let Ok(mut editor) = self.mic_editor.try_lock() {
+            editor.drop_retired();
+        }
+        // Moved out under the lock, freed once it is released
+        let (retired, mut finished): (Vec<PlayingSound>, Vec<String>) = match self.sounds.try_lock() {
+            Ok(mut sounds) if !sounds.retired.is_empty() => {
+                let retired: Vec<PlayingSound> = sounds.retired.drain(..).collect();
+                let finished = retired
+                    .iter()
+                    .filter(|sound| !sounds.is_playing(&sound.id))
+                    .map(|sound| sound.id.clone())
+                    .collect();
+                (retired, finished)
+            }
+            _ => Default::default(),
+        };
+        drop(retired);
+        finished.sort();
+        finished.dedup();
+        finished
+    }
+
+    /// Apply a playback or volume command
+    ///
+    /// Stream lifecycle commands (Start, Stop, SetMonitor, RebuildStreams,
+    /// Shutdown) are owned by whoever drives the processors and are ignored
+    /// here.
+    pub fn handle_command(&self, command: AudioEngineCommand) {
+        match command {
+            AudioEngineCommand::PlaySound { id, samples, sample_rate, channels, volume, looping } => {
+                // Converted before taking the lock the callback mixes under
+                let samples = self.to_mix_format(samples, sample_rate, channels);
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_volume(id.clone(), volume);
+                    if looping {
+                        sounds.play_looping(id, samples);
+                    } else {
+                        sounds.play(id, samples);
+                    }
+                }
+            }
+            AudioEngineCommand::PlayStream { id, stream, volume, looping } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_volume(id.clone(), volume);
+                    sounds.play_stream(id, stream, looping);
+                }
+            }
+            AudioEngineCommand::PlaySoundsSynced { sounds: group } => {
+                // Each on its own, so their frames line up in the mix
+                let group: Vec<(String, Vec<f32>)> = group
+                    .into_iter()
+                    .map(|sound| {
+                        let samples = self.to_mix_format(sound.samples, sound.sample_rate, sound.channels);
+                        (sound.id, samples)
+                    })
+                    .collect();
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.play_synced(group);
+                }
+            }
+            AudioEngineCommand::StopSound { id, fade_ms } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.stop(&id, fade_ms);
+                }
+            }
+            AudioEngineCommand::StopAllSounds { fade_ms } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.stop_all(fade_ms);
+                }
+            }
+            AudioEngineCommand::PauseSound { id } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_paused(&id, true);
+                }
+            }
+            AudioEngineCommand::ResumeSound { id } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_paused(&id, false);
+                }
+            }
+            AudioEngineCommand::SeekSound { id, position_secs } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.seek(&id, position_secs);
+                }
+            }
+            AudioEngineCommand::SetBroadcastDelay(delay_ms) => {
+                // Resized here, off the audio thread
+                if let Ok(mut delay) = self.broadcast_delay.lock() {
+                    delay.set_delay_ms(delay_ms);
+                }
+                self.controls.broadcast_delay.store(delay_ms > 0, Ordering::Relaxed);
+            }
+            AudioEngineCommand::DumpBroadcastDelay => self.controls.request_delay_dump(),
+            AudioEngineCommand::SetVoiceActivity(settings) => self.controls.set_voice_activity(settings),
+            AudioEngineCommand::SetHeadphoneLimiter(ceiling_db) => self.controls.set_headphone_ceiling_db(ceiling_db),
+            AudioEngineCommand::SetFeedbackProtection(enabled) => self.controls.set_feedback_protection(enabled),
+            AudioEngineCommand::ClearFeedbackMute => self.controls.set_feedback_muted(false),
+            AudioEngineCommand::CensorBroadcastDelay { seconds, mode } => {
+                if let Ok(mut delay) = self.broadcast_delay.lock() {
+                    let frames = (seconds.max(0.0) * delay.sample_rate() as f32) as usize;
+                    delay.censor_last(frames, mode);
+                }
+            }
+            AudioEngineCommand::SetStopFade(fade_ms) => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_stop_fade(fade_ms);
+                }
+            }
+            // Takes effect from the next start
+            AudioEngineCommand::SetOutputRamp(ramp_ms) => self.controls.output_ramp_ms.store(ramp_ms, Ordering::Relaxed),
+            AudioEngineCommand::SetSoundVolume { id, volume } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_volume(id, volume);
+                }
+            }
+            AudioEngineCommand::SetSceneGain { id, gain, fade_ms } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_scene_gain(id, gain, fade_ms);
+                }
+            }
+            AudioEngineCommand::SetSoundWidth { id, width } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_width(id, width);
+                }
+            }
+            AudioEngineCommand::SetSoundInserts { id, inserts } => {
+                if let Ok(mut sounds) = self.sounds.lock() {
+                    sounds.set_inserts(id, inserts);
+                }
+            }
+            AudioEngineCommand::SetAppSource { channel_id, source } => {
+                if let Ok(mut sources) = self.app_sources.lock() {
+                    match source {
+                        Some(source) => sources.insert(channel_id, source),
+                        None => sources.remove(&channel_id),
+                    };
+                }
+            }
+            AudioEngineCommand::SetAppSourceGain { channel_id, gain } => {
+                if let Ok(mut sources) = self.app_sources.lock() {
+                    if let Some(source) = sources.get_mut(&channel_id) {
+                        source.gain = gain.clamp(0.0, 2.0);
+                    }
+                }
+            }
+            AudioEngineCommand::AddGenerator { channel_id, settings, gain } => {
+                let source = GeneratorSource::new(&settings, self.mix_rate(), gain.clamp(0.0, 2.0));
+                if let Ok(mut generators) = self.generators.lock() {
+                    generators.insert(channel_id, source);
+                }
+            }
+            AudioEngineCommand::SetGenerator { channel_id, settings } => {
+                let sample_rate = self.mix_rate();
+                if let Ok(mut generators) = self.generators.lock() {
+                    match (settings, generators.get_mut(&channel_id)) {
+                        (Some(settings), Some(source)) => source.generator.set_settings(&settings),
+                        (Some(settings), None) => {
+                            generators.insert(channel_id, GeneratorSource::new(&settings, sample_rate, 1.0));
+                        }
+                        (None, _) => {
+                            generators.remove(&channel_id);
+                        }
+                    }
+                }
+            }
+            AudioEngineCommand::SetGeneratorGain { channel_id, gain } => {
+                if let Ok(mut generators) = self.generators.lock() {
+                    if let Some(source) = generators.get_mut(&channel_id) {
+                        source.gain = gain.clamp(0.0, 2.0);
+                    }
+                }
+            }
+            AudioEngineCommand::SetMicVolume(volume) => self.controls.set_mic_volume(volume),
+            AudioEngineCommand::SetMasterVolume(volume) => self.controls.set_master_volume(volume),
+            AudioEngineCommand::SetMicMuted(muted) => self.controls.set_mic_muted(muted),
+            AudioEngineCommand::SetMicChannelMuted(muted) => self.controls.set_mic_channel_muted(muted),
+            // The editor builds stages here and queues them, so the audio callback never
+            // waits on them; unchanged settings keep the running stage and its state
+            AudioEngineCommand::SetNoiseProfile { profile, quality } => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_noise_profile(profile, quality);
+                }
+            }
+            AudioEngineCommand::SetEchoCancellation(enabled) => {
+                if self.controls.echo_cancellation() == enabled {
+                    return;
+                }
+                let echo_canceller = enabled.then(|| EchoCanceller::new(ECHO_CANCELLER_TAPS));
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_echo_canceller(echo_canceller);
+                }
+                if let Ok(mut reference) = self.echo_reference.lock() {
+                    reference.clear();
+                }
+                self.controls.set_echo_cancellation(enabled);
+            }
+            AudioEngineCommand::SetMicAgc(settings) => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_agc(settings);
+                }
+            }
+            AudioEngineCommand::SetNoiseGate(settings) => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_noise_gate(settings);
+                }
+            }
+            AudioEngineCommand::SetMicDucking(settings) => {
+                if let Ok(mut ducker) = self.mic_ducker.lock() {
+                    ducker.set_settings(settings);
+                }
+            }
+            AudioEngineCommand::SetKeyboardSuppression(settings) => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_keystroke_gate(settings, &self.keystrokes);
+                }
+            }
+            AudioEngineCommand::SetMicHighpass(settings) => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_highpass(Some(settings));
+                }
+            }
+            AudioEngineCommand::SetVoiceChanger(settings) => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_voice_changer(settings);
+                }
+            }
+            AudioEngineCommand::SetMicChainLayout(layout) => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_layout(&layout);
+                }
+            }
+            AudioEngineCommand::SetMicChainBypass(bypassed) => {
+                if let Ok(mut editor) = self.mic_editor.lock() {
+                    editor.set_bypassed(bypassed);
+                }
+            }
+            AudioEngineCommand::SetInputChannelMap(map) => {
+                if let Ok(mut input_channel_map) = self.input_channel_map.lock() {
+                    *input_channel_map = map;
+                }
+            }
+            AudioEngineCommand::SetStemTaps(taps) => {
+                if let Ok(mut stem_taps) = self.stem_taps.lock() {
+                    *stem_taps = taps;
+                }
+            }
+            AudioEngineCommand::SetOutputFormat(format) => self.controls.set_output_format(format),
+            AudioEngineCommand::SetRecordingTap(tap) => {
+                if let Ok(mut recording) = self.recording.lock() {
+                    *recording = tap;
+                }
+            }
+            AudioEngineCommand::Start { .. }
+            | AudioEngineCommand::SetMonitor { .. }
+            | AudioEngineCommand::RebuildStreams
+            | AudioEngineCommand::Stop
+            | AudioEngineCommand::Shutdown => {}
+        }
+    }
+
+    /// Convert decoded samples to the rate and channels sounds are mixed at
+    ///
+    /// Samples pass through unchanged until the engine format is known, and
+    /// when the loader already converted them with [`convert_to_mix_format`].
+    fn to_mix_format(&self, samples: Vec<f32>, sample_rate: u32, channels: u16) -> Vec<f32> {
+        match self.sounds.lock().ok().and_then(|sounds| sounds.format()) {
+            Some((mix_rate, mix_channels)) => convert_to_mix_format(samples, sample_rate, channels, mix_rate, mix_channels),
+            None => samples,
+        }
+    }
+
+    /// Build the mic processor turning captured audio with `input_channels`
+    /// channels into the engine's `channels`
+    ///
+    /// Applies the current input channel map and retunes the mic chain,
+    /// sound inserts, broadcast delay and ducker for `sample_rate` and
+    /// `channels`.
+    pub fn input_processor(&self, input_channels: u16, channels: u16, sample_rate: u32) -> InputProcessor {
+        if let Ok(mut editor) = self.mic_editor.lock() {
+            editor.set_format(sample_rate, channels.max(1) as usize);
+        }
+        if let Ok(mut sounds) = self.sounds.lock() {
+            sounds.set_format(sample_rate, channels.max(1) as usize);
+        }
+        if let Ok(mut delay) = self.broadcast_delay.lock() {
+            delay.set_format(sample_rate, channels.max(1) as usize);
+        }
+        if let Ok(mut ducker) = self.mic_ducker.lock() {
+            ducker.set_sample_rate(sample_rate);
+        }
+
+        InputProcessor {
+            controls: self.controls.clone(),
+            mic_chain: self.mic_chain.clone(),
+            echo_reference: self.echo_reference.clone(),
+            shared_channel_map: self.input_channel_map.clone(),
+            channel_map: self.input_channel_map(),
+            stem_taps: self.stem_taps.clone(),
+            stems: [Vec::new(), Vec::new()],
+            input_channels: input_channels.max(1) as usize,
+            channels: channels.max(1) as usize,
+            channel_gain: if self.controls.is_mic_channel_muted() { 0.0 } else { 1.0 },
+            fade_step: 1000.0 / (MUTE_FADE_MS * sample_rate.max(1) as f32),
+            scratch: Vec::new(),
+            reference: Vec::new(),
+            prefill: 0,
+        }
+    }
+
+    /// Rate sounds are mixed at, 48 kHz until the engine has started
+    fn mix_rate(&self) -> u32 {
+        self.sounds.lock().ok().and_then(|sounds| sounds.format()).map_or(48_000, |(rate, _)| rate)
+    }
+
+    /// Build the output processor for interleaved audio with `channels` channels
+    ///
+    /// The processor ramps in from silence, and clears a close left by the
+    /// previous stop.
+    pub fn output_processor(&self, channels: u16) -> OutputProcessor {
+        let sample_rate = self.mix_rate();
+        self.controls.output_closing.store(false, Ordering::Relaxed);
+        if let Ok(mut generators) = self.generators.lock() {
+            for source in generators.values_mut() {
+                source.generator.set_sample_rate(sample_rate);
+            }
+        }
+        OutputProcessor {
+            controls: self.controls.clone(),
+            sounds: self.sounds.clone(),
+            app_sources: self.app_sources.clone(),
+            generators: self.generators.clone(),
+            echo_reference: self.echo_reference.clone(),
+            recording: self.recording.clone(),
+            monitor: self.monitor.clone(),
+            broadcast_delay: self.broadcast_delay.clone(),
+            ducker: self.mic_ducker.clone(),
+            channels: channels.max(1) as usize,
+            sound_mix: Vec::new(),
+            generator_mix: Vec::new(),
+            duck_gain: 1.0,
+            dither: Dither::new(),
+            ramp: OutputRamp::new(self.controls.output_ramp_ms(), sample_rate),
+            mic_flowing: false,
+        }
+    }
+}
+
+impl Default for EngineCore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Processes captured microphone samples before they are queued for output
+pub struct InputProcessor {
+    controls: Arc<EngineControls>,
+    mic_chain: Arc<Mutex<EffectChain>>,
+    echo_reference: Arc<Mutex<VecDeque<f32>>>,
+    shared_channel_map: Arc<Mutex<InputChannelMap>>,
+    /// Last map read, kept when the shared one is busy
+    channel_map: InputChannelMap,
+    stem_taps: Arc<Mutex<Vec<RecordingTap>>>,
+    stems: [Vec<f32>; 2],
+    input_channels: usize,
+    channels: usize,
+    /// Gain of the mic channel mute, fading between 0 and 1
+    channel_gain: f32,
+    /// Change of `channel_gain` per frame
+    fade_step: f32,
+    scratch: Vec<f32>,
+    reference: Vec<f32>,
+    /// Silent samples still to queue ahead of the first buffer
+    prefill: usize,
+}
+
+impl InputProcessor {
+    /// Queue `samples` of silence ahead of the first captured buffer
+    ///
+    /// The cushion absorbs input and output periods that don't line up, so
+    /// the output only runs dry when the input really falls behind.
+    pub fn with_prefill(mut self, samples: usize) -> Self {
+        self.prefill = samples;
+        self
+    }
+
+    /// Run the mic effect chain, then apply mic volume, mute and the faded
+    /// channel mute, handing each sample to `push`
+    ///
+    /// Returns the RMS level of the processed samples.
+    pub fn process(&mut self, data: &[f32], mut push: impl FnMut(f32)) -> f32 {
+        for _ in 0..std::mem::take(&mut self.prefill) {
+            push(0.0);
+        }
+        let muted = self.controls.is_mic_silenced();
+        let volume = self.controls.mic_volume();
+
+        if let Ok(map) = self.shared_channel_map.try_lock() {
+            self.channel_map = *map;
+        }
+        self.channel_map
+            .remap(data, self.input_channels, self.channels, &mut self.scratch);
+        self.push_stems(data);
+
+        // One reference value per frame, silence if the output fell behind
+        self.reference.clear();
+        if self.controls.echo_cancellation() {
+            let frames = self.scratch.len() / self.channels;
+            if let Ok(mut queue) = self.echo_reference.try_lock() {
+                let available = frames.min(queue.len());
+                self.reference.extend(queue.drain(..available));
+            }
+        }
+
+        match self.mic_chain.try_lock() {
+            Ok(mut chain) => chain.process(&mut self.scratch, self.channels, &self.reference),
+            // Only a stream rebuild holds the chain; drop the block rather
+            // than send the unprocessed mic
+            Err(_) => self.scratch.fill(0.0),
+        }
+
+        let target = if self.controls.is_mic_channel_muted() { 0.0 } else { 1.0 };
+        let mut sum_squares = 0.0f32;
+        for frame in self.scratch.chunks(self.channels) {
+            self.channel_gain = if self.channel_gain < target {
+                (self.channel_gain + self.fade_step).min(target)
+            } else {
+                (self.channel_gain - self.fade_step).max(target)
+            };
+            for &sample in frame {
+                let processed = if muted { 0.0 } else { sample * volume * self.channel_gain };
+                sum_squares += processed * processed;
+                push(processed);
+            }
+        }
+
+        rms(sum_squares, self.scratch.len())
+    }
+
+    /// Record each podcast mic on its own, after its gain and mute
+    fn push_stems(&mut self, data: &[f32]) {
+        let InputChannelMap::DualMono { host, guest } = self.channel_map else {
+            return;
+        };
+        let Ok(mut taps) = self.stem_taps.try_lock() else {
+            return;
+        };
+        if taps.is_empty() {
+            return;
+        }
+
+        let [host_stem, guest_stem] = &mut self.stems;
+        host_stem.clear();
+        guest_stem.clear();
+        for frame in data.chunks_exact(self.input_channels) {
+            host_stem.push(host.level(frame));
+            guest_stem.push(guest.level(frame));
+        }
+        for (tap, stem) in taps.iter_mut().zip(&self.stems) {
+            tap.push(stem);
+        }
+    }
+}
+
+/// Builds each output buffer from the mic signal and playing sounds
+pub struct OutputProcessor {
+    controls: Arc<EngineControls>,
+    sounds: Arc<Mutex<SoundMixer>>,
+    app_sources: Arc<Mutex<HashMap<String, AppSource>>>,
+    generators: Arc<Mutex<HashMap<String, GeneratorSource>>>,
+    echo_reference: Arc<Mutex<VecDeque<f32>>>,
+    recording: Arc<Mutex<Option<RecordingTap>>>,
+    monitor: Arc<Mutex<Option<HeapProd<f32>>>>,
+    broadcast_delay: Arc<Mutex<BroadcastDelay>>,
+    ducker: Arc<Mutex<MicDucker>>,
+    channels: usize,
+    sound_mix: Vec<f32>,
+    generator_mix: Vec<f32>,
+    /// Last ducking gain, kept when the ducker is busy
+    duck_gain: f32,
+    dither: Dither,
+    ramp: OutputRamp,
+    /// Whether mic samples have arrived yet; the queue is empty until then
+    mic_flowing: bool,
+}
+
+impl OutputProcessor {
+    /// Fill `data` with the mix, pulling mic samples from `next_mic_sample`
+    ///
+    /// Returns the RMS level of the output after master volume.
+    pub fn process(&mut self, data: &mut [f32], mut next_mic_sample: impl FnMut() -> Option<f32>) -> f32 {
+        let master_vol = self.controls.master_volume();
+
+        // Mix playing sounds on their own, so they can be used as the echo reference
+        self.sound_mix.clear();
+        self.sound_mix.resize(data.len(), 0.0);
+        let mut sounds_audible = false;
+        if let Ok(mut sounds) = self.sounds.try_lock() {
+            sounds.mix_into(&mut self.sound_mix, self.channels);
+            sounds_audible = sounds.is_audible();
+        }
+        if let Ok(mut sources) = self.app_sources.try_lock() {
+            for source in sources.values_mut() {
+                source.mix_into(&mut self.sound_mix, self.channels);
+            }
+        }
+        // Test signals stay off the master volume, so they leave at their set level
+        self.generator_mix.clear();
+        self.generator_mix.resize(data.len(), 0.0);
+        if let Ok(mut generators) = self.generators.try_lock() {
+            for source in generators.values_mut() {
+                source.generator.mix_into(&mut self.generator_mix, self.channels, source.gain);
+            }
+        }
+
+        if self.controls.echo_cancellation() {
+            self.push_echo_reference();
+        }
+
+        // Mic input, ducked under the sounds, plus sounds
+        let mut mic_samples = 0;
+        let mut ducker = self.ducker.try_lock().ok();
+        for (i, (sample, &sound)) in data.iter_mut().zip(&self.sound_mix).enumerate() {
+            if i % self.channels == 0 {
+                if let Some(ducker) = ducker.as_mut() {
+                    self.duck_gain = ducker.next_gain(sounds_audible);
+                }
+            }
+            let mic = next_mic_sample();
+            mic_samples += usize::from(mic.is_some());
+            *sample = (mic.unwrap_or(0.0) * self.duck_gain + sound).clamp(-1.0, 1.0);
+        }
+        drop(ducker);
+        self.count_underrun(mic_samples, data.len());
+
+        // Apply master volume, add the test signals, ramp on start and stop,
+        // and measure the output level
+        if self.controls.is_output_closing() {
+            self.ramp.close();
+        }
+        let mut sum_squares = 0.0f32;
+        for (frame, tones) in data.chunks_mut(self.channels).zip(self.generator_mix.chunks(self.channels)) {
+            let ramp = if self.ramp.is_open() { 1.0 } else { self.ramp.next_gain() };
+            for (sample, &tone) in frame.iter_mut().zip(tones) {
+                *sample = ((*sample * master_vol + tone) * ramp).clamp(-1.0, 1.0);
+                sum_squares += *sample * *sample;
+            }
+        }
+
+        if let Ok(mut recording) = self.recording.try_lock() {
+            if let Some(tap) = recording.as_mut() {
+                tap.push(data);
+            }
+        }
+
+        // Whole buffers only, so the monitor's channels stay in step
+        if let Ok(mut monitor) = self.monitor.try_lock() {
+            if let Some(producer) = monitor.as_mut() {
+                if producer.vacant_len() >= data.len() {
+                    producer.push_slice(data);
+                }
+            }
+        }
+
+        // Listeners hear the mix late; the monitor and recording above don't
+        self.delay_output(data);
+
+        // Round to the device depth last, so nothing undoes the dither
+        if let Some(bits) = self.controls.quantize_bits() {
+            self.dither.process(data, bits, self.controls.dither());
+        }
+
+        rms(sum_squares, data.len())
+    }
+
+    /// Count a buffer the mic queue ran dry in, once the mic is flowing
+    ///
+    /// The input queues a cushion before its first buffer, so a short
+    /// buffer past that point is real starvation, not mismatched periods.
+    fn count_underrun(&mut self, mic_samples: usize, len: usize) {
+        if self.mic_flowing && mic_samples < len {
+            self.controls.record_underrun();
+        }
+        self.mic_flowing |= mic_samples > 0;
+    }
+
+    /// Run the output through the broadcast delay, dumping it on request
+    fn delay_output(&mut self, data: &mut [f32]) {
+        match self.broadcast_delay.try_lock() {
+            Ok(mut delay) => {
+                // Taken only once the delay is ours, so a busy lock can't lose the request
+                if self.controls.take_delay_dump() {
+                    delay.dump();
+                }
+                delay.process(data);
+            }
+            // Never let live audio past a delay being reconfigured
+            Err(_) if self.controls.broadcast_delay() => data.fill(0.0),
+            Err(_) => {}
+        }
+    }
+
+    /// Queue the mono sound mix for the echo canceller
+    fn push_echo_reference(&mut self) {
+        let Ok(mut queue) = self.echo_reference.try_lock() else {
+            return;
+        };
+
+        for frame in self.sound_mix.chunks_exact(self.channels) {
+            queue.push_back(frame.iter().sum::<f32>() / self.channels as f32);
+        }
+        let excess = queue.len().saturating_sub(MAX_ECHO_REFERENCE);
+        queue.drain(..excess);
+    }
+}
+
+/// Convert decoded samples to `mix_rate` and `mix_channels`
+///
+/// A 44.1 kHz file on a 48 kHz engine would otherwise play pitched up. The
+/// FFT resample is slow for long sounds, so loaders run this before the
+/// samples reach the engine thread.
+pub fn convert_to_mix_format(samples: Vec<f32>, sample_rate: u32, channels: u16, mix_rate: u32, mix_channels: usize) -> Vec<f32> {
+    let samples = if channels as usize != mix_channels {
+        AudioBuffer::from_raw_f32(samples, channels, sample_rate)
+            .convert_channels(mix_channels as u16)
+            .to_raw_f32()
+    } else {
+        samples
+    };
+    let resampler = AudioResampler::new(sample_rate, mix_rate, mix_channels);
+    if resampler.is_passthrough() {
+        return samples;
+    }
+    tracing::debug!("Resampling sound from {} Hz to {} Hz", sample_rate, mix_rate);
+    resampler.process(&samples)
+}
+
+fn rms(sum_squares: f32, len: usize) -> f32 {
+    if len == 0 {
+        0.0
+    } else {
+        (sum_squares / len as f32).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sound_mixer_removes_finished_sounds() {
+        let mut mixer = SoundMixer::new();
+        mixer.play("a".into(), vec![0.5; 4]);
+
+        let mut data = vec![0.0; 8];
+        mixer.mix_into(&mut data, 2);
+
+        assert_eq!(&data[..4], &[0.5; 4]);
+        assert_eq!(&data[4..], &[0.0; 4]);
+        assert!(!mixer.is_playing("a"));
+    }
+
+    #[test]
+    fn test_synced_sounds_start_on_the_same_frame() {
+        let mut mixer = SoundMixer::new();
+        mixer.play("bed".into(), vec![0.1; 8]);
+        let mut data = vec![0.0; 4];
+        mixer.mix_into(&mut data, 2);
+
+        // Queued mid-way through the bed, both layers start on the next buffer
+        mixer.play_synced(vec![("hit".into(), vec![0.2, 0.2]), ("tail".into(), vec![0.3; 4])]);
+        assert!(mixer.is_playing("hit"));
+        assert_eq!(mixer.playing_count(), 3);
+
+        let mut data = vec![0.0; 4];
+        mixer.mix_into(&mut data, 2);
+        assert!(data.iter().zip([0.6, 0.6, 0.4, 0.4]).all(|(s, e)| (s - e).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_synced_group_is_converted_to_the_mix_format() {
+        use crate::application::audio_engine::SyncedSound;
+
+        let core = EngineCore::new();
+        core.sounds.lock().unwrap().set_format(48_000, 2);
+        core.handle_command(AudioEngineCommand::PlaySoundsSynced {
+            sounds: vec![
+                SyncedSound { id: "mono".into(), samples: vec![0.5; 4800], sample_rate: 48_000, channels: 1 },
+                SyncedSound { id: "slow".into(), samples: vec![0.0; 4800], sample_rate: 24_000, channels: 2 },
+            ],
+        });
+
+        let playing = core.sounds.lock().unwrap().playing();
+        assert_eq!(playing[0].id, "mono");
+        assert!((playing[0].duration_secs - 0.1).abs() < 1e-6);
+        // 2400 frames at 24 kHz: 100 ms, not the 50 ms they'd play in at 48 kHz
+        assert!((playing[1].duration_secs - 0.1).abs() < 0.002, "{}", playing[1].duration_secs);
+    }
+
+    #[test]
+    fn test_scene_gain_ramps_on_top_of_the_volume() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_format(1000, 1);
+        mixer.set_volume("bed".into(), 0.5);
+        mixer.play_looping("bed".into(), vec![1.0; 10]);
+
+        // From unity down to 0.2 over 20 frames, the volume left as it was
+        mixer.set_scene_gain("bed".into(), 0.2, 20);
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        assert!((data[9] - 0.3).abs() < 1e-6, "{data:?}");
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        assert!((data[9] - 0.1).abs() < 1e-6, "{data:?}");
+        assert_eq!(mixer.playing()[0].volume, 0.5);
+
+        // Later plays start at the scene gain, and a new fade starts from it
+        mixer.play("bed".into(), vec![1.0; 10]);
+        mixer.set_scene_gain("bed".into(), 1.0, 0);
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        assert!((data[0] - 0.1).abs() < 0.05 && (data[9] - 0.5).abs() < 1e-6, "{data:?}");
+    }
+
+    #[test]
+    fn test_sound_volume_ramps_and_sticks() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_volume("vocals".into(), 0.5);
+        mixer.play("vocals".into(), vec![0.4; 12]);
+
+        let mut data = vec![0.0; 4];
+        mixer.mix_into(&mut data, 2);
+        assert_eq!(data, vec![0.2; 4]);
+
+        // Muted while playing: ramps down over the next buffer, then silent
+        mixer.set_volume("vocals".into(), 0.0);
+        let mut data = vec![0.0; 4];
+        mixer.mix_into(&mut data, 2);
+        assert!((data[0] - 0.1).abs() < 1e-6 && data[2] == 0.0);
+        let mut data = vec![0.0; 4];
+        mixer.mix_into(&mut data, 2);
+        assert_eq!(data, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn test_looping_sound_wraps_until_stopped() {
+        let mut mixer = SoundMixer::new();
+        mixer.play_looping("rain".into(), vec![0.1, 0.1, 0.2, 0.2, 0.3, 0.3]);
+
+        // Three frames of sound across a five-frame buffer, twice over
+        for _ in 0..2 {
+            let mut data = vec![0.0; 10];
+            mixer.mix_into(&mut data, 2);
+            assert!(mixer.is_playing("rain"));
+            assert!(data.iter().all(|&s| s > 0.0));
+        }
+        let mut data = vec![0.0; 2];
+        mixer.mix_into(&mut data, 2);
+        // 10 frames played so far, so the next one is the second frame
+        assert_eq!(data, vec![0.2, 0.2]);
+        assert!(mixer.playing()[0].looping);
+
+        mixer.set_stop_fade(0);
+        mixer.stop("rain", None);
+        assert!(!mixer.is_playing("rain"));
+    }
+
+    #[test]
+    fn test_streamed_sound_plays_until_drained() {
+        use crate::application::sound_stream::StreamFormat;
+        use crate::ports::MockFileDecoder;
+
+        let mut decoder = MockFileDecoder::new();
+        let mut blocks = 4;
+        decoder.expect_read_next().returning(move || {
+            if blocks == 0 {
+                return Ok(None);
+            }
+            blocks -= 1;
+            Ok(Some(AudioBuffer::from_raw_f32(vec![0.5; 250], 1, 1000)))
+        });
+        decoder.expect_duration().return_const(Some(Duration::from_secs(1)));
+        decoder.expect_close().return_const(());
+        let format = StreamFormat {
+            sample_rate: 1000,
+            channels: 1,
+            gain: 1.0,
+            looping: false,
+            start: Duration::ZERO,
+        };
+        let stream = SoundStream::spawn(Box::new(decoder), format).unwrap();
+
+        let mut mixer = SoundMixer::new();
+        mixer.set_format(1000, 1);
+        mixer.play_stream("bed".into(), stream, false);
+        assert_eq!(mixer.playing()[0].duration_secs, 1.0);
+
+        let mut played = 0;
+        for _ in 0..1000 {
+            let mut data = vec![0.0; 100];
+            mixer.mix_into(&mut data, 1);
+            assert!(data.iter().all(|&s| s == 0.0 || s == 0.5));
+            played += data.iter().filter(|&&s| s == 0.5).count();
+            if !mixer.is_playing("bed") {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(played, 1000);
+        assert!(!mixer.is_playing("bed"));
+    }
+
+    #[test]
+    fn test_pause_holds_the_position_and_seek_moves_it() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_format(1000, 1);
+        mixer.play("talk".into(), (0..100).map(|i| i as f32 / 1000.0).collect());
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+
+        // Ramps out over a buffer, then stays silent where it was
+        mixer.set_paused("talk", true);
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        assert!(data[0] > 0.0 && data[9].abs() < 1e-6);
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        assert_eq!(data, vec![0.0; 10]);
+        assert!(mixer.playing()[0].paused);
+        assert_eq!(mixer.playing()[0].position_secs, 0.02);
+
+        // Resumes from the seeked position, ramping back in
+        mixer.seek("talk", 0.05);
+        mixer.set_paused("talk", false);
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        assert!((data[9] - 0.059).abs() < 1e-6);
+        assert_eq!(mixer.playing()[0].position_secs, 0.06);
+
+        // Seeking past the end finishes the sound, once it has dipped out
+        mixer.seek("talk", 10.0);
+        mixer.mix_into(&mut data, 1);
+        assert!(mixer.is_playing("talk"));
+        mixer.mix_into(&mut data, 1);
+        assert!(!mixer.is_playing("talk"));
+    }
+
+    #[test]
+    fn test_seek_dips_around_the_jump() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_format(1000, 1);
+        mixer.play("talk".into(), vec![0.8; 100]);
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+
+        // Out over the seek ramp, still where it was
+        mixer.seek("talk", 0.05);
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        for (sample, expected) in data.iter().zip([0.64, 0.48, 0.32, 0.16, 0.0, 0.0]) {
+            assert!((sample - expected).abs() < 1e-6, "{data:?}");
+        }
+
+        // Then in again from the new position
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        for (sample, expected) in data.iter().zip([0.16, 0.32, 0.48, 0.64, 0.8, 0.8]) {
+            assert!((sample - expected).abs() < 1e-6, "{data:?}");
+        }
+        assert_eq!(mixer.playing()[0].position_secs, 0.06);
+    }
+
+    #[test]
+    fn test_failed_stream_seek_keeps_the_position() {
+        use crate::application::sound_stream::StreamFormat;
+        use crate::ports::{FileDecoderError, MockFileDecoder};
+
+        let mut decoder = MockFileDecoder::new();
+        decoder
+            .expect_read_next()
+            .returning(|| Ok(Some(AudioBuffer::from_raw_f32(vec![0.5; 100], 1, 1000))));
+        decoder
+            .expect_seek()
+            .returning(|_| Err(FileDecoderError::DecodeError("not seekable".into())));
+        decoder.expect_duration().return_const(Some(Duration::from_secs(60)));
+        decoder.expect_close().return_const(());
+        let format = StreamFormat {
+            sample_rate: 1000,
+            channels: 1,
+            gain: 1.0,
+            looping: false,
+            start: Duration::ZERO,
+        };
+        let stream = SoundStream::spawn(Box::new(decoder), format).unwrap();
+
+        let mut mixer = SoundMixer::new();
+        mixer.set_format(1000, 1);
+        mixer.play_stream("bed".into(), stream, false);
+        let mut data = vec![0.0; 100];
+        mixer.mix_into(&mut data, 1);
+
+        mixer.seek("bed", 30.0);
+        let mut failed = Vec::new();
+        for _ in 0..1000 {
+            let mut data = vec![0.0; 10];
+            mixer.mix_into(&mut data, 1);
+            failed = mixer.take_failed_seeks();
+            if !failed.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(failed, vec!["bed".to_string()]);
+        assert!(mixer.take_failed_seeks().is_empty());
+
+        // Back in from where it was, not from the failed target
+        let mut data = vec![0.0; 10];
+        mixer.mix_into(&mut data, 1);
+        assert_eq!(data[9], 0.5);
+        assert!(mixer.playing()[0].position_secs < 1.0);
+    }
+
+    #[test]
+    fn test_stop_fades_out() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_format(1000, 1);
+        mixer.set_stop_fade(4);
+        mixer.play("a".into(), vec![0.8; 100]);
+
+        // Four frames at 1 kHz, then gone
+        mixer.stop("a", None);
+        assert!(!mixer.is_playing("a"));
+        let mut data = vec![0.0; 6];
+        mixer.mix_into(&mut data, 1);
+        for (sample, expected) in data.iter().zip([0.6, 0.4, 0.2, 0.0, 0.0, 0.0]) {
+            assert!((sample - expected).abs() < 1e-6, "{data:?}");
+        }
+
+        // An explicit fade of 0 cuts at once
+        mixer.play("a".into(), vec![0.8; 100]);
+        assert_eq!(mixer.stop_all(Some(0)), 0);
+        let mut data = vec![0.0; 2];
+        mixer.mix_into(&mut data, 1);
+        assert_eq!(data, vec![0.0; 2]);
+    }
+
+    #[test]
+    fn test_ended_sounds_are_freed_off_the_callback() {
+        let core = EngineCore::new();
+        {
+            let mut mixer = core.sounds.lock().unwrap();
+            mixer.set_format(1000, 1);
+            mixer.set_stop_fade(2);
+            mixer.play("a".into(), vec![0.5; 4]);
+            mixer.play("b".into(), vec![0.5; 100]);
+            mixer.stop("b", None);
+
+            // Both end within the buffer, and wait for the engine thread
+            let capacity = mixer.retired.capacity();
+            let mut data = vec![0.0; 8];
+            mixer.mix_into(&mut data, 1);
+            assert_eq!(mixer.retired.len(), 2);
+            assert_eq!(mixer.retired.capacity(), capacity);
+        }
+
+        assert_eq!(core.drop_retired(), vec!["a".to_string(), "b".to_string()]);
+        assert!(core.sounds.lock().unwrap().retired.is_empty());
+        assert!(core.drop_retired().is_empty());
+    }
+
+    #[test]
+    fn test_playing_snapshot_follows_the_mixer() {
+        let core = EngineCore::new();
+        core.sounds.lock().unwrap().set_format(1000, 1);
+        core.sounds.lock().unwrap().play("a".into(), vec![0.1; 100]);
+        assert!(core.playing_snapshot().is_empty());
+
+        core.publish_playing();
+        let playing = core.playing_snapshot();
+        assert_eq!(playing.len(), 1);
+        assert_eq!(playing[0].id, "a");
+
+        core.sounds.lock().unwrap().clear();
+        core.publish_playing();
+        assert!(core.playing_snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_playing_reports_positions() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_format(1000, 2);
+        mixer.play("b".into(), vec![0.1; 4000]);
+        mixer.play_synced(vec![("a".into(), vec![0.1; 1000])]);
+        mixer.set_volume("b".into(), 0.5);
+        let mut data = vec![0.0; 500];
+        mixer.mix_into(&mut data, 2);
+
+        let playing = mixer.playing();
+        assert_eq!(playing.iter().map(|sound| sound.id.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+        assert!((playing[0].position_secs - 0.25).abs() < 1e-9 && (playing[0].duration_secs - 0.5).abs() < 1e-9);
+        assert!((playing[1].duration_secs - 2.0).abs() < 1e-9 && playing[1].volume == 0.5);
+    }
+
+    #[test]
+    fn test_sound_width_applies_to_later_plays() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_width("music".into(), 0.0);
+        mixer.play("music".into(), vec![0.4, 0.0, 0.4, 0.0]);
+
+        let mut data = vec![0.0; 4];
+        mixer.mix_into(&mut data, 2);
+        assert_eq!(data, vec![0.2, 0.2, 0.2, 0.2]);
+    }
+
+    #[test]
+    fn test_vocal_reduction_insert_removes_the_centre() {
+        let mut mixer = SoundMixer::new();
+        mixer.set_inserts("song".into(), vec![SoundInsert::VocalReduction { strength: 1.0 }]);
+        // A centred voice and a hard-left instrument, above the bass crossover
+        let voice: Vec<f32> = (0..9_600)
+            .flat_map(|n| {
+                let s = (2.0 * std::f32::consts::PI * 1000.0 * n as f32 / 48_000.0).sin() * 0.5;
+                [s + 0.2, s]
+            })
+            .collect();
+        mixer.play("song".into(), voice);
+
+        let mut data = vec![0.0; 9_600];
+        mixer.mix_into(&mut data, 2);
+        let tail = &data[4_800..];
+        let right = tail.iter().skip(1).step_by(2).map(|s| s.abs()).fold(0.0, f32::max);
+        assert!(right < 0.15, "voice left on the right channel: {right}");
+
+        // Removing the insert plays the sound untouched again
+        mixer.set_inserts("song".into(), Vec::new());
+        assert!(mixer.playing_sounds["song"].inserts.is_none());
+    }
+
+    #[test]
+    fn test_app_source_is_mixed_and_drops_backlog() {
+        use ringbuf::traits::Split;
+
+        let core = EngineCore::new();
+        let (mut producer, consumer) = ringbuf::HeapRb::<f32>::new(64).split();
+        producer.push_slice(&[0.9; 8]);
+        producer.push_slice(&[0.4, 0.4, 0.2, 0.2]);
+        core.handle_command(AudioEngineCommand::SetAppSource {
+            channel_id: "spotify".into(),
+            source: Some(AppSource::new(consumer, 0, 1.0)),
+        });
+        core.handle_command(AudioEngineCommand::SetAppSourceGain {
+            channel_id: "spotify".into(),
+            gain: 0.5,
+        });
+
+        // Only the newest buffer's worth plays
+        let mut data = vec![0.0; 4];
+        core.output_processor(2).process(&mut data, || None);
+        assert_eq!(data, vec![0.2, 0.2, 0.1, 0.1]);
+    }
+
+    #[test]
+    fn test_generator_is_mixed_until_removed() {
+        use crate::domain::GeneratorSignal;
+
+        let core = EngineCore::new();
+        let settings = GeneratorSettings { signal: GeneratorSignal::Reference1k, level_dbfs: -6.0 };
+        core.handle_command(AudioEngineCommand::AddGenerator {
+            channel_id: "tone".into(),
+            settings,
+            gain: 0.5,
+        });
+        // The master volume leaves the known level alone
+        core.handle_command(AudioEngineCommand::SetMasterVolume(0.3));
+
+        let mut output = core.output_processor(2);
+        let mut data = vec![0.0; 960];
+        output.process(&mut data, || None);
+        let peak = data.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.25).abs() < 0.01, "peak {}", peak);
+
+        // Added again under the same id, the muted gain doesn't carry over
+        core.handle_command(AudioEngineCommand::SetGeneratorGain {
+            channel_id: "tone".into(),
+            gain: 0.0,
+        });
+        core.handle_command(AudioEngineCommand::AddGenerator {
+            channel_id: "tone".into(),
+            settings,
+            gain: 1.0,
+        });
+        output.process(&mut data, || None);
+        let peak = data.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.01, "peak {}", peak);
+
+        core.handle_command(AudioEngineCommand::SetGenerator {
+            channel_id: "tone".into(),
+            settings: None,
+        });
+        output.process(&mut data, || None);
+        assert!(data.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_input_processor_mute() {
+        let core = EngineCore::new();
+        core.handle_command(AudioEngineCommand::SetMicMuted(true));
+
+        let mut out = Vec::new();
+        let rms = core.input_processor(2, 2, 48000).process(&[0.5, -0.5], |s| out.push(s));
+
+        assert_eq!(out, vec![0.0, 0.0]);
+        assert_eq!(rms, 0.0);
+    }
+
+    #[test]
+    fn test_feedback_mute_is_apart_from_the_user_mute() {
+        let core = EngineCore::new();
+        let mut input = core.input_processor(1, 1, 48000);
+        core.handle_command(AudioEngineCommand::SetFeedbackProtection(true));
+        core.controls.set_feedback_muted(true);
+
+        // Unmuting by hand doesn't reopen a mic the feedback guard closed
+        core.handle_command(AudioEngineCommand::SetMicMuted(false));
+        let mut out = Vec::new();
+        input.process(&[0.5], |s| out.push(s));
+        assert_eq!(out, vec![0.0]);
+
+        core.handle_command(AudioEngineCommand::ClearFeedbackMute);
+        out.clear();
+        input.process(&[0.5], |s| out.push(s));
+        assert_eq!(out, vec![0.5]);
+        assert!(!core.controls.is_mic_muted());
+    }
+
+    #[test]
+    fn test_talk_gate_only_adds_to_the_user_mute() {
+        let core = EngineCore::new();
+        let mut input = core.input_processor(1, 1, 48000);
+        core.handle_command(AudioEngineCommand::SetMicMuted(true));
+
+        // Opening the gate keeps a mic the user muted shut
+        core.controls.set_talk_gate_closed(false);
+        let mut out = Vec::new();
+        input.process(&[0.5], |s| out.push(s));
+        assert_eq!(out, vec![0.0]);
+
+        core.handle_command(AudioEngineCommand::SetMicMuted(false));
+        core.controls.set_talk_gate_closed(true);
+        out.clear();
+        input.process(&[0.5], |s| out.push(s));
+        assert_eq!(out, vec![0.0]);
+        assert!(!core.controls.is_mic_muted());
+    }
+
+    #[test]
+    fn test_prefill_queues_silence_ahead_of_the_first_buffer() {
+        let core = EngineCore::new();
+        let mut input = core.input_processor(1, 1, 48_000).with_prefill(3);
+
+        let mut out = Vec::new();
+        input.process(&[0.5], |s| out.push(s));
+        input.process(&[0.5], |s| out.push(s));
+        assert_eq!(out, vec![0.0, 0.0, 0.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_mic_channel_mute_fades() {
+        let core = EngineCore::new();
+        let mut input = core.input_processor(1, 1, 48_000);
+        core.handle_command(AudioEngineCommand::SetMicChannelMuted(true));
+
+        // 10 ms at 48 kHz: 480 frames from full level to silence
+        let mut out = Vec::new();
+        input.process(&[0.5; 600], |s| out.push(s));
+        assert!(out[0] > 0.49);
+        assert!(out[..480].windows(2).all(|w| w[1] <= w[0]));
+        assert!(out[480..].iter().all(|s| s.abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_output_ramps_in_on_start_and_out_on_close() {
+        let core = EngineCore::new();
+        // 10 ms at 1 kHz: ten frames each way
+        core.input_processor(1, 1, 1000);
+        core.handle_command(AudioEngineCommand::SetOutputRamp(10));
+        let mut output = core.output_processor(1);
+
+        let mut data = vec![0.0; 12];
+        output.process(&mut data, || Some(0.5));
+        assert!(data[..10].windows(2).all(|w| w[1] > w[0]));
+        assert_eq!(&data[9..], &[0.5, 0.5, 0.5]);
+
+        core.controls.close_output();
+        output.process(&mut data, || Some(0.5));
+        assert!(data[0] < 0.5);
+        assert!(data[9..].iter().all(|s| *s == 0.0));
+
+        // The next start ramps in again
+        let mut output = core.output_processor(1);
+        output.process(&mut data, || Some(0.5));
+        assert!(data[0] > 0.0 && data[0] < 0.1);
+    }
+
+    #[test]
+    fn test_underruns_count_once_the_mic_flows() {
+        let core = EngineCore::new();
+        let mut output = core.output_processor(2);
+        let mut data = vec![0.0; 4];
+
+        // Nothing queued before the input stream starts is not an underrun
+        output.process(&mut data, || None);
+        assert_eq!(core.controls.underruns(), 0);
+
+        let mut mic = vec![0.1; 6].into_iter();
+        output.process(&mut data, || mic.next());
+        output.process(&mut data, || mic.next());
+        output.process(&mut data, || mic.next());
+        assert_eq!(core.controls.underruns(), 2);
+
+        core.controls.reset_underruns();
+        assert_eq!(core.controls.underruns(), 0);
+    }
+
+    #[test]
+    fn test_broadcast_delay_holds_back_the_output_only() {
+        let core = EngineCore::new();
+        core.input_processor(2, 2, 1000);
+        core.handle_command(AudioEngineCommand::SetBroadcastDelay(2));
+        core.handle_command(AudioEngineCommand::PlaySound {
+            id: "a".into(),
+            samples: vec![0.5; 8],
+            sample_rate: 1000,
+            channels: 2,
+            volume: 1.0,
+            looping: false,
+        });
+
+        let mut data = vec![0.0; 8];
+        core.output_processor(2).process(&mut data, || None);
+        assert_eq!(data, vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5]);
+
+        // After a dump the queued audio never comes out
+        core.handle_command(AudioEngineCommand::DumpBroadcastDelay);
+        let mut data = vec![0.0; 4];
+        core.output_processor(2).process(&mut data, || None);
+        assert!(data[0] == 0.5 && data[2] < 0.5);
+    }
+
+    #[test]
+    fn test_mic_ducks_while_a_sound_plays() {
+        let core = EngineCore::new();
+        core.input_processor(2, 2, 1000);
+        core.handle_command(AudioEngineCommand::SetMicDucking(DuckingSettings {
+            enabled: true,
+            depth_db: -20.0,
+            attack_ms: 1,
+            release_ms: 1,
+        }));
+        let mut output = core.output_processor(2);
+
+        let mut data = vec![0.0; 4];
+        output.process(&mut data, || Some(0.5));
+        assert!(data.iter().all(|&s| (s - 0.5).abs() < 1e-6));
+
+        // A silent looping sound still ducks the mic to a tenth
+        core.handle_command(AudioEngineCommand::PlaySound {
+            id: "a".into(),
+            samples: vec![0.0; 8],
+            sample_rate: 1000,
+            channels: 2,
+            volume: 1.0,
+            looping: true,
+        });
+        let mut data = vec![0.0; 40];
+        output.process(&mut data, || Some(0.5));
+        assert!((data[38] - 0.05).abs() < 1e-3);
+
+        core.handle_command(AudioEngineCommand::StopAllSounds { fade_ms: None });
+        let mut data = vec![0.0; 40];
+        output.process(&mut data, || Some(0.5));
+        assert!((data[38] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_output_feeds_echo_reference_when_enabled() {
+        let core = EngineCore::new();
+        core.handle_command(AudioEngineCommand::PlaySound {
+            id: "a".into(),
+            samples: vec![0.5, 0.3, 0.5, 0.3],
+            sample_rate: 48000,
+            channels: 2,
+            volume: 1.0,
+            looping: false,
+        });
+
+        let mut data = vec![0.0; 4];
+        core.output_processor(2).process(&mut data, || None);
+        assert!(core.echo_reference.lock().unwrap().is_empty());
+
+        core.handle_command(AudioEngineCommand::SetEchoCancellation(true));
+        core.handle_command(AudioEngineCommand::PlaySound {
+            id: "a".into(),
+            samples: vec![0.5, 0.3, 0.5, 0.3],
+            sample_rate: 48000,
+            channels: 2,
+            volume: 1.0,
+            looping: false,
+        });
+        core.output_processor(2).process(&mut data, || None);
+
+        let reference: Vec<f32> = core.echo_reference.lock().unwrap().iter().copied().collect();
+        assert_eq!(reference, vec![0.4, 0.4]);
+    }
+
+    #[test]
+    fn test_resending_echo_cancellation_keeps_its_reference() {
+        let core = EngineCore::new();
+        core.handle_command(AudioEngineCommand::SetEchoCancellation(true));
+        core.echo_reference.lock().unwrap().extend([0.4, 0.4]);
+
+        core.handle_command(AudioEngineCommand::SetEchoCancellation(true));
+        assert_eq!(core.echo_reference.lock().unwrap().len(), 2);
+
+        core.handle_command(AudioEngineCommand::SetEchoCancellation(false));
+        assert!(core.echo_reference.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sounds_are_converted_to_the_engine_format() {
+        let core = EngineCore::new();
+        core.input_processor(2, 2, 48000);
+        core.handle_command(AudioEngineCommand::PlaySound {
+            id: "a".into(),
+            samples: vec![0.25; 44100],
+            sample_rate: 44100,
+            channels: 1,
+            volume: 1.0,
+            looping: false,
+        });
+
+        // One second of mono at 44.1 kHz is one second of stereo at 48 kHz
+        let playing = core.sounds.lock().unwrap().playing();
+        assert!((playing[0].duration_secs - 1.0).abs() < 1e-6);
+        let mut data = vec![0.0; 2 * 4800];
+        core.output_processor(2).process(&mut data, || None);
+        assert!(data[2 * 2400..].iter().all(|s| (s - 0.25).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_play_volume_applies_and_can_change_mid_playback() {
+        let core = EngineCore::new();
+        core.handle_command(AudioEngineCommand::PlaySound {
+            id: "a".into(),
+            samples: vec![0.4; 8],
+            sample_rate: 48000,
+            channels: 2,
+            volume: 0.5,
+            looping: false,
+        });
+
+        let mut data = vec![0.0; 4];
+        core.output_processor(2).process(&mut data, || None);
+        assert_eq!(data, vec![0.2; 4]);
+
+        core.handle_command(AudioEngineCommand::SetSoundVolume { id: "a".into(), volume: 1.5 });
+        let mut data = vec![0.0; 4];
+        core.output_processor(2).process(&mut data, || None);
+        assert!((data[2] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_volume_clamping() {
+        let controls = EngineControls::new();
+        controls.set_master_volume(5.0);
+        assert_eq!(controls.master_volume(), 2.0);
+    }
+}
@@ -0,0 +1,173 @@
+//! Auto-save - Debounced persistence for values changed in quick succession
+//!
+//! Dragging a fader sends dozens of commands a second, and saving on each
+//! one rewrote the whole file as often. Those commands only mark what they
+//! changed as dirty; it is written once nothing changed for `IDLE_DELAY`,
+//! and whatever is still dirty is written on exit.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Something saved as a whole when dirty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SaveTarget {
+    /// App settings, in the settings file
+    Settings,
+    /// Channel volumes and mutes
+    Mixer,
+    /// The soundboard store
+    Soundboard,
+}
+
+/// Dirty flags, with when each target last changed
+pub struct AutoSave {
+    dirty: Mutex<HashMap<SaveTarget, Instant>>,
+    changed: Condvar,
+    /// Held while saving, so an exit flush waits for a save in progress
+    saving: Mutex<()>,
+}
+
+impl AutoSave {
+    /// Time without changes before a dirty target is written
+    pub const IDLE_DELAY: Duration = Duration::from_millis(500);
+
+    pub fn new() -> Self {
+        Self {
+            dirty: Mutex::new(HashMap::new()),
+            changed: Condvar::new(),
+            saving: Mutex::new(()),
+        }
+    }
+
+    /// Mark a target as changed, pushing its save back by `IDLE_DELAY`
+    pub fn mark_dirty(&self, target: SaveTarget) {
+        self.dirty.lock().unwrap().insert(target, Instant::now());
+        self.changed.notify_one();
+    }
+
+    pub fn is_dirty(&self, target: SaveTarget) -> bool {
+        self.dirty.lock().unwrap().contains_key(&target)
+    }
+
+    /// Write every dirty target now, e.g. on exit
+    pub fn flush(&self, save: impl Fn(SaveTarget)) {
+        let _saving = self.saving.lock().unwrap();
+        let targets: Vec<SaveTarget> = self.dirty.lock().unwrap().drain().map(|(target, _)| target).collect();
+        for target in targets {
+            save(target);
+        }
+    }
+
+    /// Wait until some targets have been idle long enough and take them
+    fn wait_idle(&self) -> Vec<SaveTarget> {
+        let mut dirty = self.dirty.lock().unwrap();
+        loop {
+            let (idle, next) = take_idle(&mut dirty, Instant::now());
+            if !idle.is_empty() {
+                return idle;
+            }
+            dirty = match next {
+                Some(wait) => self.changed.wait_timeout(dirty, wait).unwrap().0,
+                None => self.changed.wait(dirty).unwrap(),
+            };
+        }
+    }
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Remove the targets idle for `IDLE_DELAY` at `now`; also returns how long
+/// until the next remaining one is
+fn take_idle(dirty: &mut HashMap<SaveTarget, Instant>, now: Instant) -> (Vec<SaveTarget>, Option<Duration>) {
+    let idle: Vec<SaveTarget> = dirty
+        .iter()
+        .filter(|(_, changed)| now.duration_since(**changed) >= AutoSave::IDLE_DELAY)
+        .map(|(target, _)| *target)
+        .collect();
+    for target in &idle {
+        dirty.remove(target);
+    }
+    let next = dirty
+        .values()
+        .map(|changed| AutoSave::IDLE_DELAY.saturating_sub(now.duration_since(*changed)))
+        .min();
+    (idle, next)
+}
+
+/// Start the thread writing targets once they have been idle
+///
+/// `save` writes one target; its failures are only logged, the next
+/// change marks the target dirty again.
+pub fn spawn_auto_save(
+    auto_save: Arc<AutoSave>,
+    save: impl Fn(SaveTarget) -> Result<(), String> + Send + 'static,
+) -> JoinHandle<()> {
+    thread::Builder::new()
+        .name("auto-save".into())
+        .spawn(move || loop {
+            let targets = auto_save.wait_idle();
+            let _saving = auto_save.saving.lock().unwrap();
+            for target in targets {
+                if let Err(e) = save(target) {
+                    tracing::error!(target = ?target, error = %e, "Auto-save failed");
+                }
+            }
+        })
+        .expect("spawn auto-save")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets_wait_until_idle() {
+        let start = Instant::now();
+        let mut dirty = HashMap::from([
+            (SaveTarget::Settings, start),
+            (SaveTarget::Mixer, start + Duration::from_millis(300)),
+        ]);
+
+        let (idle, next) = take_idle(&mut dirty, start + Duration::from_millis(100));
+        assert!(idle.is_empty());
+        assert_eq!(next, Some(Duration::from_millis(400)));
+
+        let (idle, next) = take_idle(&mut dirty, start + AutoSave::IDLE_DELAY);
+        assert_eq!(idle, vec![SaveTarget::Settings]);
+        assert_eq!(next, Some(Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn test_rapid_changes_are_saved_once() {
+        let auto_save = Arc::new(AutoSave::new());
+        let (saved, saves) = crossbeam_channel::unbounded();
+        spawn_auto_save(auto_save.clone(), move |target| {
+            saved.send(target).unwrap();
+            Ok(())
+        });
+
+        for _ in 0..20 {
+            auto_save.mark_dirty(SaveTarget::Mixer);
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(saves.recv_timeout(Duration::from_secs(2)), Ok(SaveTarget::Mixer));
+        assert!(saves.recv_timeout(AutoSave::IDLE_DELAY * 2).is_err());
+        assert!(!auto_save.is_dirty(SaveTarget::Mixer));
+    }
+
+    #[test]
+    fn test_flush_writes_pending_targets() {
+        let auto_save = AutoSave::new();
+        auto_save.mark_dirty(SaveTarget::Soundboard);
+        let saved = Mutex::new(Vec::new());
+        auto_save.flush(|target| saved.lock().unwrap().push(target));
+        assert_eq!(*saved.lock().unwrap(), vec![SaveTarget::Soundboard]);
+        assert!(!auto_save.is_dirty(SaveTarget::Soundboard));
+    }
+}
@@ -0,0 +1,71 @@
+//! Automation - Built-in rules reacting to engine events
+//!
+//! Rules are picked on the engine-events thread and run by the commands
+//! layer, so the thread never waits on a decode or a store.
+
+use crate::application::audio_engine::AudioEngineEvent;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A built-in automation rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomationRule {
+    /// Play the start jingle, and recall its scene, when mixing starts
+    StartJingle,
+}
+
+/// Picks the rules an engine event triggers
+#[derive(Debug, Default)]
+pub struct Automations {
+    /// Mixing was running before the event
+    running: AtomicBool,
+}
+
+impl Automations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rules triggered by `event`
+    ///
+    /// The engine also reports `Started` after rebuilding its streams or
+    /// restarting with new settings; only a start from stopped counts. An
+    /// error on the way doesn't count as stopped, since the streams are
+    /// restarted without a `Stopped`.
+    pub fn rules_for(&self, event: &AudioEngineEvent) -> Vec<AutomationRule> {
+        match event {
+            AudioEngineEvent::Started if !self.running.swap(true, Ordering::Relaxed) => {
+                vec![AutomationRule::StartJingle]
+            }
+            AudioEngineEvent::Stopped => {
+                self.running.store(false, Ordering::Relaxed);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jingle_only_on_start_from_stopped() {
+        let automations = Automations::new();
+        assert_eq!(automations.rules_for(&AudioEngineEvent::Started), vec![AutomationRule::StartJingle]);
+        // Rebuild or restart while running
+        assert!(automations.rules_for(&AudioEngineEvent::Started).is_empty());
+
+        assert!(automations.rules_for(&AudioEngineEvent::Stopped).is_empty());
+        assert_eq!(automations.rules_for(&AudioEngineEvent::Started), vec![AutomationRule::StartJingle]);
+    }
+
+    #[test]
+    fn test_recovering_from_an_error_replays_no_jingle() {
+        let automations = Automations::new();
+        automations.rules_for(&AudioEngineEvent::Started);
+
+        assert!(automations.rules_for(&AudioEngineEvent::Error("device lost".into())).is_empty());
+        assert!(automations.rules_for(&AudioEngineEvent::Started).is_empty());
+    }
+}
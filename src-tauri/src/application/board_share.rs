@@ -0,0 +1,358 @@
+//! Board sharing - A read-only view of selected pads for co-hosts on the LAN
+//!
+//! Serves a small web page, a JSON listing and the audio files of the
+//! shared pads. Co-hosts can listen and ask the host to play a pad, but
+//! nothing they do reaches the mix directly: requests only raise an event.
+//!
+//! Every URL lives under a random token (`/s/<token>/`), so only people
+//! given the link can browse the board.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+
+/// Errors that can occur while sharing a board
+#[derive(Debug, thiserror::Error)]
+pub enum BoardShareError {
+    #[error("Could not start the share server: {0}")]
+    Bind(String),
+
+    #[error("No pads with sounds were selected")]
+    NoPads,
+}
+
+/// A pad visible on the shared board
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SharedPad {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub duration: f64,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// A co-host asking for a pad to be played
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareRequest {
+    pub pad_id: String,
+    pub name: String,
+    /// Address of the co-host
+    pub from: String,
+}
+
+/// Where the shared board can be reached
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareInfo {
+    pub url: String,
+    pub port: u16,
+    pub pad_count: usize,
+}
+
+struct RunningShare {
+    server: Arc<Server>,
+    info: ShareInfo,
+}
+
+/// Serves at most one shared board at a time
+pub struct BoardShare {
+    running: Mutex<Option<RunningShare>>,
+}
+
+impl BoardShare {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Where the current share can be reached, if sharing
+    pub fn info(&self) -> Option<ShareInfo> {
+        self.running.lock().unwrap().as_ref().map(|share| share.info.clone())
+    }
+
+    /// Start sharing `pads`, replacing any current share
+    ///
+    /// `port` 0 picks a free port. `on_request` runs on the server thread
+    /// whenever a co-host asks for a pad.
+    pub fn start(
+        &self,
+        pads: Vec<SharedPad>,
+        port: u16,
+        on_request: impl Fn(ShareRequest) + Send + 'static,
+    ) -> Result<ShareInfo, BoardShareError> {
+        if pads.is_empty() {
+            return Err(BoardShareError::NoPads);
+        }
+        self.stop();
+
+        let server = Server::http((Ipv4Addr::UNSPECIFIED, port)).map_err(|e| BoardShareError::Bind(e.to_string()))?;
+        let port = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.port())
+            .ok_or_else(|| BoardShareError::Bind("not an IP socket".into()))?;
+        let server = Arc::new(server);
+
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let info = ShareInfo {
+            url: format!("http://{}:{}/s/{}/", lan_address(), port, token),
+            port,
+            pad_count: pads.len(),
+        };
+
+        let worker = server.clone();
+        std::thread::Builder::new()
+            .name("board-share".into())
+            .spawn(move || {
+                for request in worker.incoming_requests() {
+                    handle(request, &token, &pads, &on_request);
+                }
+            })
+            .map_err(|e| BoardShareError::Bind(e.to_string()))?;
+
+        tracing::info!(url = %info.url, pads = info.pad_count, "Sharing board");
+        *self.running.lock().unwrap() = Some(RunningShare {
+            server,
+            info: info.clone(),
+        });
+        Ok(info)
+    }
+
+    /// Stop sharing; open links stop working immediately
+    pub fn stop(&self) {
+        if let Some(share) = self.running.lock().unwrap().take() {
+            share.server.unblock();
+            tracing::info!("Board share stopped");
+        }
+    }
+}
+
+impl Default for BoardShare {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a request URL asks for
+#[derive(Debug, PartialEq)]
+enum Route<'a> {
+    Page,
+    Listing,
+    Audio(&'a SharedPad),
+    RequestPad(&'a SharedPad),
+    NotFound,
+}
+
+fn route<'a>(method: &Method, url: &str, token: &str, pads: &'a [SharedPad]) -> Route<'a> {
+    let path = url.split('?').next().unwrap_or_default();
+    let Some(rest) = path
+        .strip_prefix("/s/")
+        .and_then(|rest| rest.strip_prefix(token))
+        .and_then(|rest| rest.strip_prefix('/'))
+    else {
+        return Route::NotFound;
+    };
+    let pad = |id: &str| pads.iter().find(|pad| pad.id == id);
+
+    match (method, rest.split_once('/')) {
+        (Method::Get, None) if rest.is_empty() => Route::Page,
+        (Method::Get, None) if rest == "board.json" => Route::Listing,
+        (Method::Get, Some(("audio", id))) => pad(id).map_or(Route::NotFound, Route::Audio),
+        (Method::Post, Some(("request", id))) => pad(id).map_or(Route::NotFound, Route::RequestPad),
+        _ => Route::NotFound,
+    }
+}
+
+fn handle(request: Request, token: &str, pads: &[SharedPad], on_request: &dyn Fn(ShareRequest)) {
+    let result = match route(request.method(), request.url(), token, pads) {
+        Route::Page => request.respond(
+            Response::from_string(SHARE_PAGE).with_header(header("Content-Type", "text/html; charset=utf-8")),
+        ),
+        Route::Listing => {
+            let json = serde_json::to_string(pads).unwrap_or_else(|_| "[]".into());
+            request.respond(Response::from_string(json).with_header(header("Content-Type", "application/json")))
+        }
+        Route::Audio(pad) => serve_audio(request, pad),
+        Route::RequestPad(pad) => {
+            let from = request.remote_addr().map(|addr| addr.ip().to_string()).unwrap_or_default();
+            on_request(ShareRequest {
+                pad_id: pad.id.clone(),
+                name: pad.name.clone(),
+                from,
+            });
+            request.respond(Response::empty(StatusCode(202)))
+        }
+        Route::NotFound => request.respond(Response::empty(StatusCode(404))),
+    };
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "Board share client went away");
+    }
+}
+
+/// Stream a pad's file, honouring `Range` so players can seek
+fn serve_audio(request: Request, pad: &SharedPad) -> std::io::Result<()> {
+    let Ok(mut file) = File::open(&pad.path) else {
+        return request.respond(Response::empty(StatusCode(404)));
+    };
+    let length = file.metadata()?.len();
+    let content_type = header("Content-Type", audio_content_type(&pad.path));
+
+    let range = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Range"))
+        .and_then(|h| parse_range(h.value.as_str(), length));
+
+    match range {
+        Some((start, end)) => {
+            file.seek(SeekFrom::Start(start))?;
+            let size = end - start + 1;
+            let response = Response::new(
+                StatusCode(206),
+                vec![
+                    content_type,
+                    header("Accept-Ranges", "bytes"),
+                    header("Content-Range", &format!("bytes {}-{}/{}", start, end, length)),
+                ],
+                file.take(size),
+                Some(size as usize),
+                None,
+            );
+            request.respond(response)
+        }
+        None => request.respond(
+            Response::new(
+                StatusCode(200),
+                vec![content_type, header("Accept-Ranges", "bytes")],
+                file,
+                Some(length as usize),
+                None,
+            ),
+        ),
+    }
+}
+
+/// Parse a single `bytes=start-end` range into inclusive bounds
+fn parse_range(value: &str, length: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.strip_prefix("bytes=")?.split_once('-')?;
+    let last = length.checked_sub(1)?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => (length.saturating_sub(suffix.parse().ok()?), last),
+        (start, "") => (start.parse().ok()?, last),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(last)),
+    };
+    (start <= end).then_some((start, end))
+}
+
+fn audio_content_type(path: &std::path::Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        Some("flac") => "audio/flac",
+        _ => "application/octet-stream",
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+/// This machine's address on the LAN, as seen by other hosts
+fn lan_address() -> IpAddr {
+    // Connecting a UDP socket sends nothing; it only picks the route
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+/// Page shown to co-hosts; loads the listing relative to its own URL
+const SHARE_PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Voiceboard</title>
+<style>
+  body { font-family: system-ui, sans-serif; background: #1e1e2e; color: #eee; margin: 1rem; }
+  .pad { display: flex; align-items: center; gap: .75rem; padding: .5rem; border-left: 6px solid; margin-bottom: .5rem; background: #2a2a3c; }
+  .pad span { flex: 1; }
+  button { padding: .4rem .8rem; }
+</style>
+</head>
+<body>
+<h1>Shared board</h1>
+<div id="pads"></div>
+<script>
+  fetch('board.json').then(r => r.json()).then(pads => {
+    const list = document.getElementById('pads');
+    for (const pad of pads) {
+      const row = document.createElement('div');
+      row.className = 'pad';
+      row.style.borderColor = pad.color || '#888';
+      const name = document.createElement('span');
+      name.textContent = `${pad.name} (${pad.duration.toFixed(1)}s)`;
+      const audio = document.createElement('audio');
+      audio.controls = true;
+      audio.preload = 'none';
+      audio.src = `audio/${encodeURIComponent(pad.id)}`;
+      const ask = document.createElement('button');
+      ask.textContent = 'Request';
+      ask.onclick = () => fetch(`request/${encodeURIComponent(pad.id)}`, { method: 'POST' })
+        .then(() => { ask.textContent = 'Requested'; });
+      row.append(name, audio, ask);
+      list.append(row);
+    }
+  });
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pads() -> Vec<SharedPad> {
+        vec![SharedPad {
+            id: "pad-1".into(),
+            name: "Horn".into(),
+            color: None,
+            duration: 1.0,
+            path: PathBuf::from("horn.wav"),
+        }]
+    }
+
+    #[test]
+    fn test_routes_require_token() {
+        let pads = pads();
+        assert_eq!(route(&Method::Get, "/s/abc/", "abc", &pads), Route::Page);
+        assert_eq!(route(&Method::Get, "/s/abc/board.json?x=1", "abc", &pads), Route::Listing);
+        assert_eq!(route(&Method::Get, "/s/abc/audio/pad-1", "abc", &pads), Route::Audio(&pads[0]));
+        assert_eq!(route(&Method::Post, "/s/abc/request/pad-1", "abc", &pads), Route::RequestPad(&pads[0]));
+
+        assert_eq!(route(&Method::Get, "/s/wrong/", "abc", &pads), Route::NotFound);
+        assert_eq!(route(&Method::Get, "/s/abcd/", "abc", &pads), Route::NotFound);
+        assert_eq!(route(&Method::Get, "/s/abc/audio/pad-2", "abc", &pads), Route::NotFound);
+        assert_eq!(route(&Method::Get, "/s/abc/request/pad-1", "abc", &pads), Route::NotFound);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=500-", 1000), Some((500, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+    }
+}
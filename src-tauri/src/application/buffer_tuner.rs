@@ -0,0 +1,130 @@
+//! Buffer tuner - Grows the device buffers when the mic keeps running dry
+//!
+//! With auto-tune on, mixing starts at a small buffer for low latency. The
+//! engine reports every output buffer the mic queue ran dry in; when too
+//! many land in a short time the buffer is doubled, saved for the device
+//! pair, and the streams restarted with it.
+
+use crate::application::auto_save::SaveTarget;
+use crate::application::state::AppState;
+use crate::domain::BufferAutoTuneSettings;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Underruns reported over the last window
+pub struct BufferTuner {
+    underruns: Mutex<VecDeque<(Instant, u32)>>,
+}
+
+impl BufferTuner {
+    pub fn new() -> Self {
+        Self {
+            underruns: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Record `count` underruns at `now`
+    ///
+    /// Returns true when the window reaches the threshold, and starts a new
+    /// window so one burst grows the buffer only once.
+    pub fn record(&self, count: u32, now: Instant) -> bool {
+        let window = Duration::from_secs(BufferAutoTuneSettings::UNDERRUN_WINDOW_SECS);
+        let Ok(mut underruns) = self.underruns.lock() else {
+            return false;
+        };
+
+        underruns.push_back((now, count));
+        while underruns.front().is_some_and(|(at, _)| now.duration_since(*at) > window) {
+            underruns.pop_front();
+        }
+        if underruns.iter().map(|(_, count)| count).sum::<u32>() < BufferAutoTuneSettings::UNDERRUN_THRESHOLD {
+            return false;
+        }
+        underruns.clear();
+        true
+    }
+}
+
+impl Default for BufferTuner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Handle underruns reported by the engine
+///
+/// Once they cross the threshold with auto-tune on, the running streams are
+/// restarted with twice the buffer and `buffer-auto-tuned` tells the user
+/// why the audio blinked.
+pub fn on_underruns(app: &AppHandle, count: u32) {
+    let state = app.state::<AppState>();
+    if !state.buffer_tuner.record(count, Instant::now()) {
+        return;
+    }
+    let Some(setup) = state.audio_engine.stream_setup() else {
+        return;
+    };
+
+    if !state.settings.blocking_read().buffer_auto_tune.enabled {
+        return;
+    }
+    let previous = setup.buffer_frames.unwrap_or(BufferAutoTuneSettings::START_FRAMES);
+    let Some(frames) = BufferAutoTuneSettings::next_frames(previous) else {
+        tracing::warn!(frames = previous, "Audio keeps breaking up at the largest buffer size");
+        return;
+    };
+
+    // Underruns reported by streams the user has since stopped or replaced
+    // don't restart anything
+    match state.audio_engine.restart_with_buffer(&setup, frames) {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to restart the streams with a larger buffer");
+            return;
+        }
+    }
+
+    let pair = BufferAutoTuneSettings::pair_key(&setup.input_device, &setup.output_device);
+    state.settings.blocking_write().buffer_auto_tune.tuned.insert(pair.clone(), frames);
+    state.auto_save.mark_dirty(SaveTarget::Settings);
+
+    let latency_ms = frames as f32 * 1000.0 / setup.sample_rate.max(1) as f32;
+
+    tracing::info!(pair = %pair, previous, frames, "Buffer size auto-tuned after underruns");
+    let _ = app.emit("buffer-auto-tuned", serde_json::json!({
+        "previousFrames": previous,
+        "frames": frames,
+        "latencyMs": latency_ms,
+        "message": format!(
+            "Audio was breaking up, so the buffer was raised to {:.0} ms. \
+             This setting is remembered for these devices.",
+            latency_ms
+        ),
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_within_the_window() {
+        let tuner = BufferTuner::new();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        assert!(!tuner.record(2, at(0)));
+        assert!(!tuner.record(2, at(10)));
+        assert!(tuner.record(1, at(20)));
+
+        // The burst that grew the buffer doesn't count again
+        assert!(!tuner.record(1, at(21)));
+
+        // Underruns spread wider than the window never add up
+        assert!(!tuner.record(3, at(60)));
+        assert!(!tuner.record(3, at(100)));
+    }
+}
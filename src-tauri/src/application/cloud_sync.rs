@@ -0,0 +1,467 @@
+//! Cloud sync - Boards and settings that follow the user across machines
+//!
+//! A sync uploads one zip archive holding the soundboard, the synced
+//! settings and every audio file the pads use, plus a small metadata
+//! document read first to detect changes (see [`crate::domain::plan_sync`]).
+//! Machine-specific settings (audio devices, tally light) stay local.
+
+use crate::adapters::{S3Storage, WebDavStorage};
+use crate::application::clock::now_millis;
+use crate::domain::{
+    plan_sync, resolve_sync, SyncAction, SyncBackendConfig, SyncMetadata, SyncResolution, SyncState,
+};
+use crate::ports::{CloudStorage, CloudStorageError};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Remote name of the metadata document
+pub const SYNC_METADATA_KEY: &str = "voiceboard-sync.json";
+
+/// Remote name of the board archive
+pub const SYNC_ARCHIVE_KEY: &str = "voiceboard-sync.zip";
+
+/// Settings fields that describe this machine and are never synced
+const LOCAL_SETTINGS: [&str; 2] = ["audio", "tally"];
+
+const BOARD_ENTRY: &str = "board.json";
+const SETTINGS_ENTRY: &str = "settings.json";
+const SOUNDS_PREFIX: &str = "sounds/";
+
+/// Errors that can occur while syncing
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    #[error("Cloud sync is not configured")]
+    NotConfigured,
+
+    #[error("A sync is already running")]
+    AlreadyRunning,
+
+    #[error("Cloud sync needs an https:// server, not {0}")]
+    InsecureUrl(String),
+
+    #[error(transparent)]
+    Storage(#[from] CloudStorageError),
+
+    #[error("Invalid remote data: {0}")]
+    InvalidRemote(String),
+
+    #[error("Archive error: {0}")]
+    Archive(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The synced part of the local state
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncSnapshot {
+    /// Saved soundboard pads, as stored by the frontend
+    pub pads: Value,
+    /// Settings without the machine-specific fields
+    pub settings: Value,
+}
+
+impl SyncSnapshot {
+    /// Build a snapshot, dropping settings that stay on this machine
+    pub fn new(pads: Value, mut settings: Value) -> Self {
+        if let Some(settings) = settings.as_object_mut() {
+            for field in LOCAL_SETTINGS {
+                settings.remove(field);
+            }
+        }
+        Self { pads, settings }
+    }
+
+    /// Stable hash of the content, used to detect local changes
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.pads.to_string());
+        hasher.update([0]);
+        hasher.update(self.settings.to_string());
+        hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Overlay the synced settings onto this machine's settings
+    pub fn merge_settings(&self, local: &Value) -> Value {
+        let mut merged = local.clone();
+        if let (Some(merged), Some(synced)) = (merged.as_object_mut(), self.settings.as_object()) {
+            for (key, value) in synced {
+                if !LOCAL_SETTINGS.contains(&key.as_str()) {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        merged
+    }
+}
+
+/// Result of one sync
+#[derive(Debug, Clone)]
+pub struct SyncOutcome {
+    pub action: SyncAction,
+    pub remote: Option<SyncMetadata>,
+    /// Remote content to apply locally, for downloads
+    pub downloaded: Option<SyncSnapshot>,
+    /// State to remember for the next sync (unchanged on conflict)
+    pub state: SyncState,
+}
+
+/// Snapshot of the last sync, for the status command
+#[derive(Debug, Clone, Default)]
+pub struct SyncStatus {
+    pub last_action: Option<SyncAction>,
+    pub last_attempt_at: Option<u64>,
+    pub remote: Option<SyncMetadata>,
+    pub error: Option<String>,
+}
+
+/// Runs one sync at a time and remembers how the last one went
+pub struct CloudSync {
+    running: AtomicBool,
+    status: Mutex<SyncStatus>,
+}
+
+impl CloudSync {
+    pub fn new() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            status: Mutex::new(SyncStatus::default()),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Compare with the remote and upload or download as needed
+    ///
+    /// Audio files of downloaded boards are written to `sounds_dir`.
+    /// Blocks the calling thread on network I/O.
+    pub fn sync(
+        &self,
+        storage: &dyn CloudStorage,
+        local: &SyncSnapshot,
+        state: &SyncState,
+        resolution: Option<SyncResolution>,
+        sounds_dir: &Path,
+    ) -> Result<SyncOutcome, SyncError> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err(SyncError::AlreadyRunning);
+        }
+
+        let result = run_sync(storage, local, state, resolution, sounds_dir);
+        self.running.store(false, Ordering::SeqCst);
+
+        let mut status = self.status.lock().unwrap();
+        status.last_attempt_at = Some(now_millis());
+        match &result {
+            Ok(outcome) => {
+                status.last_action = Some(outcome.action);
+                status.remote = outcome.remote.clone();
+                status.error = None;
+            }
+            Err(e) => status.error = Some(e.to_string()),
+        }
+        result
+    }
+}
+
+impl Default for CloudSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create the storage adapter for a backend
+pub fn storage_for(config: &SyncBackendConfig) -> Result<Box<dyn CloudStorage>, SyncError> {
+    if !config.is_https() {
+        return Err(SyncError::InsecureUrl(config.url().to_string()));
+    }
+    Ok(match config {
+        SyncBackendConfig::WebDav { url, username, password } => {
+            Box::new(WebDavStorage::new(url, username, password)?)
+        }
+        SyncBackendConfig::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+            prefix,
+        } => Box::new(S3Storage::new(
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+            prefix,
+        )?),
+    })
+}
+
+fn run_sync(
+    storage: &dyn CloudStorage,
+    local: &SyncSnapshot,
+    state: &SyncState,
+    resolution: Option<SyncResolution>,
+    sounds_dir: &Path,
+) -> Result<SyncOutcome, SyncError> {
+    let remote: Option<SyncMetadata> = storage
+        .get(SYNC_METADATA_KEY)?
+        .map(|data| serde_json::from_slice(&data))
+        .transpose()
+        .map_err(|e| SyncError::InvalidRemote(e.to_string()))?;
+
+    let local_hash = local.content_hash();
+    let action = resolve_sync(plan_sync(&local_hash, remote.as_ref(), state), resolution);
+    tracing::info!(?action, remote = ?remote.as_ref().map(|r| &r.revision), "Cloud sync planned");
+
+    match action {
+        SyncAction::UpToDate | SyncAction::Conflict => Ok(SyncOutcome {
+            action,
+            remote,
+            downloaded: None,
+            state: state.clone(),
+        }),
+        SyncAction::Upload => {
+            let metadata = SyncMetadata {
+                revision: uuid::Uuid::new_v4().to_string(),
+                updated_at: now_millis(),
+                device: device_name(),
+            };
+            // Archive first: a reader never sees metadata without its archive
+            storage.put(SYNC_ARCHIVE_KEY, build_archive(local)?, "application/zip")?;
+            let json = serde_json::to_vec(&metadata).map_err(|e| SyncError::Archive(e.to_string()))?;
+            storage.put(SYNC_METADATA_KEY, json, "application/json")?;
+
+            Ok(SyncOutcome {
+                action,
+                state: SyncState {
+                    last_revision: Some(metadata.revision.clone()),
+                    last_local_hash: Some(local_hash),
+                    last_synced_at: Some(metadata.updated_at),
+                },
+                remote: Some(metadata),
+                downloaded: None,
+            })
+        }
+        SyncAction::Download => {
+            let metadata = remote.ok_or_else(|| SyncError::InvalidRemote("missing metadata".into()))?;
+            let archive = storage
+                .get(SYNC_ARCHIVE_KEY)?
+                .ok_or_else(|| SyncError::InvalidRemote("missing archive".into()))?;
+            let snapshot = extract_archive(&archive, sounds_dir)?;
+
+            Ok(SyncOutcome {
+                action,
+                state: SyncState {
+                    last_revision: Some(metadata.revision.clone()),
+                    last_local_hash: Some(snapshot.content_hash()),
+                    last_synced_at: Some(now_millis()),
+                },
+                remote: Some(metadata),
+                downloaded: Some(snapshot),
+            })
+        }
+    }
+}
+
+/// Zip the board, settings and every referenced audio file
+///
+/// Sound paths in the archived board point inside the archive.
+fn build_archive(snapshot: &SyncSnapshot) -> Result<Vec<u8>, SyncError> {
+    let archive_error = |e: zip::result::ZipError| SyncError::Archive(e.to_string());
+    let options = zip::write::SimpleFileOptions::default();
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+
+    let mut pads = snapshot.pads.clone();
+    let mut entries: HashMap<String, String> = HashMap::new();
+    let mut missing = Vec::new();
+    for_each_sound_path(&mut pads, |path| {
+        let local = path.clone();
+        if let Some(entry) = entries.get(&local) {
+            *path = entry.clone();
+            return;
+        }
+        let file_name = Path::new(&local)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "sound".to_string());
+        let entry = format!("{}{}-{}", SOUNDS_PREFIX, entries.len(), file_name);
+        entries.insert(local.clone(), entry.clone());
+        *path = entry;
+        if !Path::new(&local).is_file() {
+            missing.push(local);
+        }
+    });
+
+    for (local, entry) in &entries {
+        if missing.contains(local) {
+            tracing::warn!(path = %local, "Sound file missing, not synced");
+            continue;
+        }
+        zip.start_file(entry.as_str(), options).map_err(archive_error)?;
+        zip.write_all(&fs::read(local)?)?;
+    }
+
+    zip.start_file(BOARD_ENTRY, options).map_err(archive_error)?;
+    zip.write_all(pads.to_string().as_bytes())?;
+    zip.start_file(SETTINGS_ENTRY, options).map_err(archive_error)?;
+    zip.write_all(snapshot.settings.to_string().as_bytes())?;
+
+    Ok(zip.finish().map_err(archive_error)?.into_inner())
+}
+
+/// Unpack a board archive, pointing sounds at the extracted files
+fn extract_archive(data: &[u8], sounds_dir: &Path) -> Result<SyncSnapshot, SyncError> {
+    let archive_error = |e: zip::result::ZipError| SyncError::Archive(e.to_string());
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(data)).map_err(archive_error)?;
+
+    let mut read_json = |name: &str| -> Result<Value, SyncError> {
+        let mut text = String::new();
+        zip.by_name(name).map_err(archive_error)?.read_to_string(&mut text)?;
+        serde_json::from_str(&text).map_err(|e| SyncError::InvalidRemote(e.to_string()))
+    };
+    let mut pads = read_json(BOARD_ENTRY)?;
+    let settings = read_json(SETTINGS_ENTRY)?;
+
+    fs::create_dir_all(sounds_dir)?;
+    let mut extracted: HashMap<String, PathBuf> = HashMap::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i).map_err(archive_error)?;
+        let name = file.name().to_string();
+        let Some(file_name) = name.strip_prefix(SOUNDS_PREFIX) else {
+            continue;
+        };
+        // Reject anything that would escape the sounds folder
+        if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+            continue;
+        }
+        let target = sounds_dir.join(file_name);
+        let mut out = fs::File::create(&target)?;
+        std::io::copy(&mut file, &mut out)?;
+        extracted.insert(name, target);
+    }
+
+    for_each_sound_path(&mut pads, |path| {
+        if let Some(target) = extracted.get(path.as_str()) {
+            *path = target.to_string_lossy().to_string();
+        }
+    });
+
+    Ok(SyncSnapshot { pads, settings })
+}
+
+/// Visit the file path of every pad sound and variant
+fn for_each_sound_path(pads: &mut Value, mut visit: impl FnMut(&mut String)) {
+    let Some(pads) = pads.as_array_mut() else {
+        return;
+    };
+    for pad in pads {
+        let variants = pad
+            .get_mut("variants")
+            .and_then(|v| v.as_array_mut())
+            .map(|v| v.iter_mut().filter_map(|v| v.get_mut("sound")).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for sound in variants {
+            if let Some(Value::String(path)) = sound.get_mut("path") {
+                visit(path);
+            }
+        }
+        if let Some(Value::String(path)) = pad.get_mut("sound").and_then(|s| s.get_mut("path")) {
+            visit(path);
+        }
+    }
+}
+
+/// Name shown to other machines as the uploader
+fn device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// In-memory remote shared by two "machines"
+    #[derive(Default)]
+    struct MemoryStorage(Mutex<HashMap<String, Vec<u8>>>);
+
+    impl CloudStorage for MemoryStorage {
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CloudStorageError> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+
+        fn put(&self, key: &str, data: Vec<u8>, _content_type: &str) -> Result<(), CloudStorageError> {
+            self.0.lock().unwrap().insert(key.to_string(), data);
+            Ok(())
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("voiceboard_sync_{}_{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_board_follows_user_to_second_machine() {
+        let desk = temp_dir("desk");
+        let sound = desk.join("horn.wav");
+        fs::write(&sound, b"RIFF fake wav").unwrap();
+
+        let local = SyncSnapshot::new(
+            json!([{ "id": "pad-0", "color": "#fff", "sound": { "id": "s1", "name": "Horn", "path": sound.to_string_lossy() } }]),
+            json!({ "audio": { "input_device_id": "desk-mic" }, "echo_cancellation": true }),
+        );
+        assert!(local.settings.get("audio").is_none());
+
+        let remote = MemoryStorage::default();
+        let sync = CloudSync::new();
+        let uploaded = sync.sync(&remote, &local, &SyncState::default(), None, &desk).unwrap();
+        assert_eq!(uploaded.action, SyncAction::Upload);
+
+        // Unchanged: nothing to do
+        let again = sync.sync(&remote, &local, &uploaded.state, None, &desk).unwrap();
+        assert_eq!(again.action, SyncAction::UpToDate);
+
+        // A fresh machine with its own board must choose, then downloads
+        let laptop = temp_dir("laptop");
+        let other = SyncSnapshot::new(json!([]), json!({}));
+        let conflict = sync.sync(&remote, &other, &SyncState::default(), None, &laptop).unwrap();
+        assert_eq!(conflict.action, SyncAction::Conflict);
+        let downloaded = sync
+            .sync(&remote, &other, &SyncState::default(), Some(SyncResolution::KeepRemote), &laptop)
+            .unwrap();
+        assert_eq!(downloaded.action, SyncAction::Download);
+
+        let snapshot = downloaded.downloaded.unwrap();
+        let path = snapshot.pads[0]["sound"]["path"].as_str().unwrap().to_string();
+        assert!(path.starts_with(&*laptop.to_string_lossy()));
+        assert_eq!(fs::read(path).unwrap(), b"RIFF fake wav");
+
+        let merged = snapshot.merge_settings(&json!({ "audio": { "input_device_id": "laptop-mic" } }));
+        assert_eq!(merged["audio"]["input_device_id"], "laptop-mic");
+        assert_eq!(merged["echo_cancellation"], true);
+
+        // Applied as-is, the laptop is now in sync
+        let settled = sync.sync(&remote, &snapshot, &downloaded.state, None, &laptop).unwrap();
+        assert_eq!(settled.action, SyncAction::UpToDate);
+
+        fs::remove_dir_all(desk).ok();
+        fs::remove_dir_all(laptop).ok();
+    }
+}
@@ -1,43 +1,52 @@
 //! Tauri commands - Bridge between frontend and Rust backend
 
-use crate::adapters::CpalDeviceManager;
-use crate::application::audio_engine::AudioEngineCommand;
+use crate::application::app_info::AppInfo;
+use crate::application::audio_engine::{AudioEngineCommand, StartOutcome, StreamSetup, SyncedSound};
+use crate::application::audio_processing::{convert_to_mix_format, PlayingSoundInfo};
+use crate::application::auto_save::SaveTarget;
+use crate::application::automation::AutomationRule;
+use crate::application::board_share::{ShareInfo, SharedPad};
+use crate::application::decoder_service::DecodedSound;
+use crate::application::engine_watchdog::WatchdogDiagnostics;
+use crate::application::errors::CommandError;
+use crate::application::gain_wizard::{GainRecommendation, GainWizardState};
+use crate::application::hot_folder::HotFolderCommand;
+use crate::application::global_hotkeys::GlobalAction;
+use crate::application::hotkey_registry::{ActiveBoard, HotkeyClash, HotkeyScope, ScopedBinding};
+use crate::application::keystroke_listener::set_keystroke_listener;
+use crate::application::session_stats::{read_history, session_history_file};
+use crate::application::now_playing::{NowPlaying, NowPlayingEntry, OverlayInfo};
+use crate::application::pad_actions::{obs_secret_name, pad_actions, run_external_action, PadAction};
+use crate::application::null_audio::{NullAudioDevices, TEST_AUDIO_FLAG};
+use crate::application::safe_mode::{SafeModeReason, SAFE_MODE_FLAG};
+use crate::application::saved_pads::merge_saved_pads;
+use crate::application::session_recorder::RecordingSummary;
+use crate::application::sound_stream::{SoundStream, StreamFormat};
 use crate::application::AppState;
-use crate::domain::{AppSettings, AudioDevice, AudioSettings, ChannelType, DeviceType, MixerChannel, MixerConfig};
-use crate::ports::DeviceManager;
+use crate::domain::{
+    AgcSettings, AppSettings, AudioBuffer, AudioDevice, BroadcastDelaySettings, CensorMode, AudioSettings, ChannelType, DeviceType, GeneratorSettings, MixerChannel, MixerConfig,
+    FeedbackProtectionSettings, HeadphoneLimiterSettings, HotFolderSettings, HighpassSettings, HotkeyBinding, InputChannelMap, find_conflicts, HotkeySequence, MicChainLayout,
+    MarkerKind, MicChainError, MicProfile, MicProfiles, PlaybackSettings, PlaybackSpeed, SpeedMode, PLAYBACK_RATES, MicEffectNode, MonitorSettings, MuteGroup, NoiseGateSettings, PodcastMic, OnboardingState, OutputFormatSettings, OutputLayoutSettings, SpectralQuality, VoiceActivitySettings, VoiceChangerSettings, VoicePreset,
+    NoiseReductionSettings, OnboardingStep, PlayLogEntry, PlaySource, RecordingMarker, SyncAction, SyncBackendConfig, SyncConfig, SyncResolution,
+    SoundEdits, SoundInsert, SyncState, TallySettings, UiState, UpdateChannel, VirtualDeviceSettings, WindowGeometry, check_routing, ConfigIssue, IssueSeverity, MixingRouting,
+    CueRoute, TimerConfig, TimerCue, Scene, SceneChannel, ScenePad, crossfade_volumes,
+    Integration, IntegrationAction, IntegrationPermissions, IntegrationPolicy, PermissionDenied, StartJingleSettings,
+    KeyboardSuppressionSettings, CleanupPolicy, CleanupReport, LibraryStats, SessionSummary, BoardHotkeySettings, BufferAutoTuneSettings, DuckingSettings, GlobalHotkeySettings, KeyCombo, PushToTalkMode, PushToTalkSettings,
+};
+use crate::dsp::{codec_latency_frames, AudioResampler, OFFLOAD_LATENCY_FRAMES};
+use crate::infrastructure::{set_sentry_context, TelemetryReport};
+use crate::ports::{AudioFileMetadata, CapturableApp, DeviceManager};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tauri::State;
 use tauri_plugin_store::StoreExt;
 
 /// Settings store key
-const SETTINGS_STORE: &str = "settings.json";
 const SETTINGS_KEY: &str = "app_settings";
-
-/// Response wrapper for API calls
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub error: Option<String>,
-}
-
-impl<T> ApiResponse<T> {
-    pub fn ok(data: T) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            error: None,
-        }
-    }
-
-    pub fn err(error: impl Into<String>) -> Self {
-        Self {
-            success: false,
-            data: None,
-            error: Some(error.into()),
-        }
-    }
-}
+const UI_STATE_KEY: &str = "ui_state";
+const MIXER_LEVELS_KEY: &str = "mixer_levels";
 
 /// DTO for audio device information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +80,8 @@ pub struct MixerChannelDto {
     pub volume: f32,
     pub muted: bool,
     pub solo: bool,
+    /// Signal of a generator channel
+    pub generator: Option<GeneratorSettings>,
 }
 
 impl From<&MixerChannel> for MixerChannelDto {
@@ -82,6 +93,7 @@ impl From<&MixerChannel> for MixerChannelDto {
             volume: channel.volume(),
             muted: channel.is_muted(),
             solo: channel.is_solo(),
+            generator: channel.generator_settings(),
         }
     }
 }
@@ -91,6 +103,7 @@ impl From<&MixerChannel> for MixerChannelDto {
 pub struct MixerConfigDto {
     pub master_volume: f32,
     pub channels: Vec<MixerChannelDto>,
+    pub mute_groups: Vec<MuteGroup>,
     pub sample_rate: u32,
     pub buffer_size: u32,
 }
@@ -100,6 +113,7 @@ impl From<&MixerConfig> for MixerConfigDto {
         Self {
             master_volume: config.master_volume,
             channels: config.channels.iter().map(MixerChannelDto::from).collect(),
+            mute_groups: config.mute_groups.clone(),
             sample_rate: config.output_format.sample_rate,
             buffer_size: config.buffer_size,
         }
@@ -115,6 +129,8 @@ pub struct AudioSettingsDto {
     pub master_volume: f32,
     pub sample_rate: u32,
     pub buffer_size: u32,
+    #[serde(default)]
+    pub ducking: DuckingSettings,
 }
 
 impl From<&AudioSettings> for AudioSettingsDto {
@@ -126,6 +142,7 @@ impl From<&AudioSettings> for AudioSettingsDto {
             master_volume: settings.master_volume,
             sample_rate: settings.sample_rate,
             buffer_size: settings.buffer_size,
+            ducking: settings.ducking,
         }
     }
 }
@@ -139,6 +156,7 @@ impl From<AudioSettingsDto> for AudioSettings {
             master_volume: dto.master_volume,
             sample_rate: dto.sample_rate,
             buffer_size: dto.buffer_size,
+            ducking: dto.ducking,
         }
     }
 }
@@ -149,6 +167,71 @@ pub struct AppSettingsDto {
     pub audio: AudioSettingsDto,
     pub start_minimized: bool,
     pub auto_start_mixing: bool,
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    #[serde(default)]
+    pub install_on_quit: bool,
+    #[serde(default)]
+    pub tally: TallySettings,
+    #[serde(default)]
+    pub noise_reduction: NoiseReductionSettings,
+    #[serde(default)]
+    pub echo_cancellation: bool,
+    #[serde(default)]
+    pub mic_profiles: MicProfiles,
+    /// Mic settings saved before they were kept in profiles, read once
+    #[serde(default, skip_serializing)]
+    pub mic_agc: HashMap<String, AgcSettings>,
+    #[serde(default, skip_serializing)]
+    pub noise_gate: HashMap<String, NoiseGateSettings>,
+    #[serde(default, skip_serializing)]
+    pub mic_highpass: Option<HighpassSettings>,
+    #[serde(default)]
+    pub mic_chain: MicChainLayout,
+    #[serde(default)]
+    pub spectral_quality: SpectralQuality,
+    #[serde(default)]
+    pub voice_changer: VoiceChangerSettings,
+    #[serde(default)]
+    pub output_format: OutputFormatSettings,
+    #[serde(default)]
+    pub input_channel_maps: HashMap<String, InputChannelMap>,
+    #[serde(default)]
+    pub monitor: MonitorSettings,
+    #[serde(default)]
+    pub active_hotkey_profile: Option<String>,
+    #[serde(default)]
+    pub playback: PlaybackSettings,
+    #[serde(default)]
+    pub broadcast_delay: BroadcastDelaySettings,
+    #[serde(default)]
+    pub voice_activity: VoiceActivitySettings,
+    #[serde(default)]
+    pub headphone_limiter: HeadphoneLimiterSettings,
+    #[serde(default)]
+    pub feedback_protection: FeedbackProtectionSettings,
+    #[serde(default)]
+    pub hot_folder: HotFolderSettings,
+    #[serde(default)]
+    pub virtual_devices: VirtualDeviceSettings,
+    #[serde(default)]
+    pub output_layout: OutputLayoutSettings,
+    #[serde(default)]
+    pub integrations: IntegrationPermissions,
+    #[serde(default)]
+    pub start_jingle: StartJingleSettings,
+    #[serde(default)]
+    pub keyboard_suppression: KeyboardSuppressionSettings,
+    #[serde(default)]
+    pub buffer_auto_tune: BufferAutoTuneSettings,
+    #[serde(default)]
+    pub board_hotkeys: BoardHotkeySettings,
+    #[serde(default)]
+    pub global_hotkeys: GlobalHotkeySettings,
+    #[serde(default)]
+    pub push_to_talk: PushToTalkSettings,
 }
 
 impl From<&AppSettings> for AppSettingsDto {
@@ -157,75 +240,348 @@ impl From<&AppSettings> for AppSettingsDto {
             audio: AudioSettingsDto::from(&settings.audio),
             start_minimized: settings.start_minimized,
             auto_start_mixing: settings.auto_start_mixing,
+            telemetry_enabled: settings.telemetry_enabled,
+            update_channel: settings.update_channel,
+            install_on_quit: settings.install_on_quit,
+            tally: settings.tally.clone(),
+            noise_reduction: settings.noise_reduction.clone(),
+            echo_cancellation: settings.echo_cancellation,
+            mic_profiles: settings.mic_profiles.clone(),
+            mic_agc: HashMap::new(),
+            noise_gate: HashMap::new(),
+            mic_highpass: None,
+            mic_chain: settings.mic_chain.clone(),
+            spectral_quality: settings.spectral_quality,
+            voice_changer: settings.voice_changer,
+            output_format: settings.output_format,
+            input_channel_maps: settings.input_channel_maps.clone(),
+            monitor: settings.monitor,
+            active_hotkey_profile: settings.active_hotkey_profile.clone(),
+            playback: settings.playback,
+            broadcast_delay: settings.broadcast_delay,
+            voice_activity: settings.voice_activity,
+            headphone_limiter: settings.headphone_limiter,
+            feedback_protection: settings.feedback_protection,
+            hot_folder: settings.hot_folder.clone(),
+            virtual_devices: settings.virtual_devices.clone(),
+            output_layout: settings.output_layout,
+            integrations: settings.integrations.clone(),
+            start_jingle: settings.start_jingle.clone(),
+            keyboard_suppression: settings.keyboard_suppression,
+            buffer_auto_tune: settings.buffer_auto_tune.clone(),
+            board_hotkeys: settings.board_hotkeys.clone(),
+            global_hotkeys: settings.global_hotkeys.clone(),
+            push_to_talk: settings.push_to_talk.clone(),
         }
     }
 }
 
 impl From<AppSettingsDto> for AppSettings {
-    fn from(dto: AppSettingsDto) -> Self {
+    fn from(mut dto: AppSettingsDto) -> Self {
+        let mic_profiles = migrate_mic_profiles(&mut dto);
         Self {
             audio: AudioSettings::from(dto.audio),
             start_minimized: dto.start_minimized,
             auto_start_mixing: dto.auto_start_mixing,
+            telemetry_enabled: dto.telemetry_enabled,
+            update_channel: dto.update_channel,
+            install_on_quit: dto.install_on_quit,
+            tally: dto.tally,
+            noise_reduction: dto.noise_reduction,
+            echo_cancellation: dto.echo_cancellation,
+            mic_profiles,
+            mic_chain: dto.mic_chain,
+            spectral_quality: dto.spectral_quality,
+            voice_changer: dto.voice_changer,
+            output_format: dto.output_format,
+            input_channel_maps: dto.input_channel_maps,
+            monitor: dto.monitor,
+            active_hotkey_profile: dto.active_hotkey_profile,
+            playback: dto.playback,
+            broadcast_delay: dto.broadcast_delay,
+            voice_activity: dto.voice_activity,
+            headphone_limiter: dto.headphone_limiter,
+            feedback_protection: dto.feedback_protection,
+            hot_folder: dto.hot_folder,
+            virtual_devices: dto.virtual_devices,
+            output_layout: dto.output_layout,
+            integrations: dto.integrations,
+            start_jingle: dto.start_jingle,
+            keyboard_suppression: dto.keyboard_suppression,
+            buffer_auto_tune: dto.buffer_auto_tune,
+            board_hotkeys: dto.board_hotkeys,
+            global_hotkeys: dto.global_hotkeys,
+            push_to_talk: dto.push_to_talk,
         }
     }
 }
 
+/// Mic profiles of the settings, with mic settings saved before profiles
+/// moved in: the rumble filter applied to every mic, so it becomes the
+/// default's; AGC and gate were already per device
+fn migrate_mic_profiles(dto: &mut AppSettingsDto) -> MicProfiles {
+    let mut profiles = std::mem::take(&mut dto.mic_profiles);
+    if let Some(highpass) = dto.mic_highpass.take() {
+        profiles.default.highpass = highpass;
+    }
+    for (device, agc) in dto.mic_agc.drain() {
+        profiles.for_device_mut(&device).agc = agc;
+    }
+    for (device, gate) in dto.noise_gate.drain() {
+        profiles.for_device_mut(&device).noise_gate = gate;
+    }
+    profiles
+}
+
+// ============================================================================
+// Startup Commands
+// ============================================================================
+
+/// Whether the parts started after the window is shown (device list,
+/// preview engine) are up, for a frontend that missed `backend-ready`
+#[tauri::command]
+pub async fn is_backend_ready(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    Ok(state.readiness.is_ready())
+}
+
+/// Whether this run is in safe mode
+#[derive(Debug, Clone, Serialize)]
+pub struct SafeModeDto {
+    pub active: bool,
+    pub reason: Option<SafeModeReason>,
+    /// Flag that starts the app in safe mode
+    pub flag: &'static str,
+}
+
+/// Whether audio and hotkeys are off for this run, and why
+#[tauri::command]
+pub async fn get_safe_mode(state: State<'_, AppState>) -> Result<SafeModeDto, CommandError> {
+    Ok(SafeModeDto {
+        active: state.safe_mode.is_active(),
+        reason: state.safe_mode.reason(),
+        flag: SAFE_MODE_FLAG,
+    })
+}
+
 // ============================================================================
 // Device Commands
 // ============================================================================
 
 /// Get list of all available audio devices
 #[tauri::command]
-pub async fn get_audio_devices() -> ApiResponse<Vec<AudioDeviceDto>> {
-    let manager = CpalDeviceManager::new();
+pub async fn get_audio_devices(state: State<'_, AppState>) -> Result<Vec<AudioDeviceDto>, CommandError> {
+    let devices = state.device_manager.read().await.list_devices()?;
+    Ok(devices.into_iter().map(AudioDeviceDto::from).collect())
+}
 
-    match manager.list_devices() {
-        Ok(devices) => {
-            let dtos: Vec<AudioDeviceDto> = devices.into_iter().map(AudioDeviceDto::from).collect();
-            ApiResponse::ok(dtos)
-        }
-        Err(e) => ApiResponse::err(e.to_string()),
+/// Get physical input devices (microphones)
+#[tauri::command]
+pub async fn get_input_devices(state: State<'_, AppState>) -> Result<Vec<AudioDeviceDto>, CommandError> {
+    let devices = state
+        .device_manager
+        .read()
+        .await
+        .list_devices_by_type(DeviceType::InputPhysical)?;
+    Ok(devices.into_iter().map(AudioDeviceDto::from).collect())
+}
+
+/// Get virtual output devices (for sending mixed audio)
+#[tauri::command]
+pub async fn get_virtual_output_devices(
+    state: State<'_, AppState>,
+) -> Result<Vec<AudioDeviceDto>, CommandError> {
+    let devices = state.device_manager.read().await.find_virtual_outputs()?;
+    Ok(devices.into_iter().map(AudioDeviceDto::from).collect())
+}
+
+/// Re-enumerate audio devices now instead of waiting for the hot-plug watcher
+#[tauri::command]
+pub async fn refresh_devices(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<AudioDeviceDto>, CommandError> {
+    use crate::application::device_watcher::refresh_device_cache;
+
+    let mut manager = state.device_manager.write().await;
+    Ok(refresh_device_cache(&app, &mut manager)?)
+}
+
+/// Check if virtual audio driver is installed
+#[tauri::command]
+pub async fn check_virtual_driver(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    let installed = !state.device_manager.read().await.find_virtual_outputs()?.is_empty();
+
+    if installed {
+        let _ = state
+            .onboarding
+            .complete_step(&app, OnboardingStep::VirtualDriverInstalled);
     }
+    Ok(installed)
 }
 
-/// Get physical input devices (microphones)
+/// Get the name patterns and marks deciding which devices are virtual
 #[tauri::command]
-pub async fn get_input_devices() -> ApiResponse<Vec<AudioDeviceDto>> {
-    let manager = CpalDeviceManager::new();
+pub async fn get_virtual_device_settings(state: State<'_, AppState>) -> Result<VirtualDeviceSettings, CommandError> {
+    Ok(state.settings.read().await.virtual_devices.clone())
+}
 
-    match manager.list_devices_by_type(DeviceType::InputPhysical) {
-        Ok(devices) => {
-            let dtos: Vec<AudioDeviceDto> = devices.into_iter().map(AudioDeviceDto::from).collect();
-            ApiResponse::ok(dtos)
-        }
-        Err(e) => ApiResponse::err(e.to_string()),
+/// Detect devices whose name contains `pattern` as virtual, for drivers
+/// the defaults miss (e.g. "Synchronous Audio Router", localized names)
+#[tauri::command]
+pub async fn add_virtual_device_pattern(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pattern: String,
+) -> Result<VirtualDeviceSettings, CommandError> {
+    let pattern = pattern.trim().to_lowercase();
+    if pattern.is_empty() {
+        return Err(CommandError::InvalidArgument("Pattern is empty".into()));
     }
+    update_virtual_devices(&app, &state, |virtual_devices| {
+        if !virtual_devices.patterns.contains(&pattern) {
+            virtual_devices.patterns.push(pattern);
+        }
+    })
+    .await
 }
 
-/// Get virtual output devices (for sending mixed audio)
+/// Stop detecting devices by `pattern`, including a default one
+#[tauri::command]
+pub async fn remove_virtual_device_pattern(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pattern: String,
+) -> Result<VirtualDeviceSettings, CommandError> {
+    let pattern = pattern.trim().to_lowercase();
+    update_virtual_devices(&app, &state, |virtual_devices| {
+        virtual_devices.patterns.retain(|p| *p != pattern);
+    })
+    .await
+}
+
+/// Treat one device as virtual whatever its name; `is_virtual: false`
+/// removes the mark
 #[tauri::command]
-pub async fn get_virtual_output_devices() -> ApiResponse<Vec<AudioDeviceDto>> {
-    let manager = CpalDeviceManager::new();
+pub async fn mark_device_as_virtual(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    is_virtual: Option<bool>,
+) -> Result<VirtualDeviceSettings, CommandError> {
+    let is_virtual = is_virtual.unwrap_or(true);
+    tracing::info!(device = %device_id, is_virtual, "Device virtual mark set");
+    update_virtual_devices(&app, &state, |virtual_devices| {
+        virtual_devices.marked_devices.retain(|marked| *marked != device_id);
+        if is_virtual {
+            virtual_devices.marked_devices.push(device_id);
+        }
+    })
+    .await
+}
 
-    match manager.find_virtual_outputs() {
-        Ok(devices) => {
-            let dtos: Vec<AudioDeviceDto> = devices.into_iter().map(AudioDeviceDto::from).collect();
-            ApiResponse::ok(dtos)
+/// Force the type of a device the detection gets wrong, e.g. a virtual
+/// cable taken for hardware; `device_type: None` clears the override
+///
+/// The override keeps the direction the device is enumerated in: an
+/// input can be made virtual or physical, not an output.
+#[tauri::command]
+pub async fn set_device_type_override(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    device_type: Option<DeviceType>,
+) -> Result<VirtualDeviceSettings, CommandError> {
+    if let Some(device_type) = device_type {
+        let devices = state.device_manager.read().await.list_devices()?;
+        let enumerated = devices
+            .iter()
+            .any(|d| d.id().as_str() == device_id && d.device_type().is_input() == device_type.is_input());
+        if !enumerated {
+            let direction = if device_type.is_input() { "input" } else { "output" };
+            return Err(CommandError::InvalidArgument(format!("{} is not an {} device", device_id, direction)));
         }
-        Err(e) => ApiResponse::err(e.to_string()),
     }
+
+    tracing::info!(device = %device_id, device_type = ?device_type, "Device type override set");
+    update_virtual_devices(&app, &state, |virtual_devices| match device_type {
+        Some(device_type) => {
+            virtual_devices.type_overrides.insert(device_id, device_type);
+        }
+        None => {
+            virtual_devices.type_overrides.remove(&device_id);
+        }
+    })
+    .await
 }
 
-/// Check if virtual audio driver is installed
+/// Change the virtual device settings, reclassify the devices and save
+async fn update_virtual_devices(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    update: impl FnOnce(&mut VirtualDeviceSettings),
+) -> Result<VirtualDeviceSettings, CommandError> {
+    use tauri::Emitter;
+
+    let virtual_devices = {
+        let mut settings = state.settings.write().await;
+        update(&mut settings.virtual_devices);
+        settings.virtual_devices.clone()
+    };
+
+    let mut manager = state.device_manager.write().await;
+    manager.set_virtual_devices(virtual_devices.clone());
+    let devices: Vec<AudioDeviceDto> = manager.list_devices()?.into_iter().map(AudioDeviceDto::from).collect();
+    drop(manager);
+    let _ = app.emit("devices-changed", &devices);
+
+    persist_settings(app, state).await?;
+    Ok(virtual_devices)
+}
+
+/// A configuration issue with its severity, for the UI
+#[derive(Debug, Serialize)]
+pub struct ConfigIssueDto {
+    pub severity: IssueSeverity,
+    #[serde(flatten)]
+    pub issue: ConfigIssue,
+}
+
+/// Check the selected devices before starting: that they exist and
+/// support the engine's format, that a virtual cable is installed and that
+/// the mix doesn't feed itself
+///
+/// Returns machine-readable issues, errors first; empty when Start should
+/// succeed.
 #[tauri::command]
-pub async fn check_virtual_driver() -> ApiResponse<bool> {
-    let manager = CpalDeviceManager::new();
+pub async fn validate_mixing_config(state: State<'_, AppState>) -> Result<Vec<ConfigIssueDto>, CommandError> {
+    let devices = state.device_manager.read().await.list_devices()?;
+    let settings = state.settings.read().await;
+    let input_channels = settings
+        .audio
+        .input_device_id
+        .as_ref()
+        .and_then(|device| settings.input_channel_maps.get(device))
+        .and_then(|map| map.highest_channel())
+        .map_or(2, |highest| highest + 1);
+    let routing = MixingRouting {
+        input_device: settings.audio.input_device_id.as_deref(),
+        output_device: settings.audio.output_device_id.as_deref(),
+        monitor_device: settings.audio.preview_device_id.as_deref().filter(|_| settings.monitor.enabled),
+        sample_rate: settings.audio.sample_rate,
+        channels: 2,
+        input_channels,
+    };
 
-    match manager.find_virtual_outputs() {
-        Ok(devices) => ApiResponse::ok(!devices.is_empty()),
-        Err(e) => ApiResponse::err(e.to_string()),
-    }
+    Ok(check_routing(&routing, &devices)
+        .into_iter()
+        .map(|issue| ConfigIssueDto {
+            severity: issue.severity(),
+            issue,
+        })
+        .collect())
 }
 
 // ============================================================================
@@ -234,7 +590,7 @@ pub async fn check_virtual_driver() -> ApiResponse<bool> {
 
 /// Get current application settings
 #[tauri::command]
-pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettingsDto, String> {
+pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettingsDto, CommandError> {
     let settings = state.settings.read().await;
     Ok(AppSettingsDto::from(&*settings))
 }
@@ -245,17 +601,23 @@ pub async fn save_settings(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     settings: AppSettingsDto,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     // Update in-memory state
-    {
+    let tally_changed = {
         let mut current = state.settings.write().await;
+        let tally_changed = current.tally != settings.tally;
         *current = AppSettings::from(settings.clone());
+        tally_changed
+    };
+
+    // Reopening the light's port makes it flicker, so only on a change
+    if tally_changed {
+        if let Err(e) = state.tally.configure(&settings.tally) {
+            tracing::warn!(error = %e, "Failed to configure tally light");
+        }
     }
 
-    // Persist to store
-    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
-    store.set(SETTINGS_KEY, serde_json::to_value(&settings).map_err(|e| e.to_string())?);
-    store.save().map_err(|e| e.to_string())?;
+    write_setting(&state, SETTINGS_KEY, serde_json::to_value(&settings).map_err(|e| e.to_string())?).await?;
 
     tracing::info!("Settings saved");
     Ok(())
@@ -263,21 +625,8 @@ pub async fn save_settings(
 
 /// Load settings from persistent storage
 #[tauri::command]
-pub async fn load_settings(
-    app: tauri::AppHandle,
-    state: State<'_, AppState>,
-) -> Result<AppSettingsDto, String> {
-    let store = app.store(SETTINGS_STORE).map_err(|e| {
-        tracing::error!("Failed to open settings store: {}", e);
-        e.to_string()
-    })?;
-
-    // Explicitly reload from disk to ensure we have the latest data
-    if let Err(e) = store.reload() {
-        tracing::warn!("Could not reload settings from disk (may be first run): {}", e);
-    }
-
-    if let Some(value) = store.get(SETTINGS_KEY) {
+pub async fn load_settings(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<AppSettingsDto, CommandError> {
+    if let Some(value) = state.settings_store.get(SETTINGS_KEY) {
         tracing::info!("Found saved settings: {:?}", value);
         let settings: AppSettingsDto = serde_json::from_value(value.clone())
             .map_err(|e| {
@@ -295,6 +644,26 @@ pub async fn load_settings(
             *current = AppSettings::from(settings.clone());
         }
 
+        if let Err(e) = state.tally.configure(&settings.tally) {
+            tracing::warn!(error = %e, "Failed to configure tally light");
+        }
+        state
+            .device_manager
+            .write()
+            .await
+            .set_virtual_devices(settings.virtual_devices.clone());
+        state.decoder.set_cache_limit(settings.playback.sound_cache_mb);
+        apply_global_hotkeys(&app, &state).await;
+        if !state.safe_mode.is_active() {
+            if let Err(e) = state
+                .push_to_talk
+                .configure(&app, &state.audio_engine, &state.tally, &settings.push_to_talk)
+            {
+                tracing::warn!(error = %e, "Push-to-talk unavailable");
+            }
+        }
+        set_sentry_context("app", &app_info(&state).await);
+
         Ok(settings)
     } else {
         tracing::info!("No saved settings found, returning defaults");
@@ -304,13 +673,163 @@ pub async fn load_settings(
     }
 }
 
+/// Persist the in-memory settings to the settings file
+async fn persist_settings(_app: &tauri::AppHandle, state: &AppState) -> Result<(), CommandError> {
+    let settings = state.settings.read().await;
+    let dto = AppSettingsDto::from(&*settings);
+    drop(settings);
+
+    write_setting(state, SETTINGS_KEY, serde_json::to_value(&dto).map_err(|e| e.to_string())?).await
+}
+
+/// Saved volume and mute of a mixer channel, restored when it is added
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct ChannelLevel {
+    volume: f32,
+    muted: bool,
+}
+
+/// Write a target marked dirty by a debounced command, on the auto-save
+/// thread or on exit
+pub fn save_dirty(app: &tauri::AppHandle, target: SaveTarget) -> Result<(), String> {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    match target {
+        SaveTarget::Settings => {
+            let dto = AppSettingsDto::from(&*state.settings.blocking_read());
+            let value = serde_json::to_value(&dto).map_err(|e| e.to_string())?;
+            state.settings_store.set(SETTINGS_KEY, value).map_err(|e| e.to_string())
+        }
+        SaveTarget::Mixer => {
+            // Levels of channels not added this session are kept
+            let mut levels: HashMap<String, ChannelLevel> = state
+                .settings_store
+                .get(MIXER_LEVELS_KEY)
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_default();
+            for channel in &state.mixer_config.blocking_read().channels {
+                levels.insert(
+                    channel.id().to_string(),
+                    ChannelLevel {
+                        volume: channel.volume(),
+                        muted: channel.is_muted() && !channel.is_momentarily_muted(),
+                    },
+                );
+            }
+            let value = serde_json::to_value(&levels).map_err(|e| e.to_string())?;
+            state.settings_store.set(MIXER_LEVELS_KEY, value).map_err(|e| e.to_string())
+        }
+        SaveTarget::Soundboard => app
+            .store(SOUNDBOARD_STORE)
+            .and_then(|store| store.save())
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Give a channel the volume and mute it had when last saved
+fn restore_channel_level(state: &AppState, channel: &mut MixerChannel) {
+    let level = state
+        .settings_store
+        .get(MIXER_LEVELS_KEY)
+        .and_then(|value| serde_json::from_value::<HashMap<String, ChannelLevel>>(value).ok())
+        .and_then(|mut levels| levels.remove(channel.id()));
+    if let Some(level) = level {
+        channel.set_volume(level.volume);
+        channel.set_muted(level.muted);
+    }
+}
+
+/// Write one key of the settings file, waiting for it to reach the disk
+async fn write_setting(state: &AppState, key: &'static str, value: serde_json::Value) -> Result<(), CommandError> {
+    let settings_store = state.settings_store.clone();
+    tauri::async_runtime::spawn_blocking(move || settings_store.set(key, value))
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))??;
+    Ok(())
+}
+
+/// The UI state saved by the last session; defaults when none was saved
+/// or it came from a newer build
+fn load_ui_state(state: &AppState) -> UiState {
+    let Some(value) = state.settings_store.get(UI_STATE_KEY) else {
+        return UiState::new();
+    };
+    UiState::from_stored(value).unwrap_or_else(|| {
+        tracing::warn!("Saved UI state is unreadable or from a newer version, using defaults");
+        UiState::new()
+    })
+}
+
+/// Put the main window back where the last session left it
+///
+/// A position on a screen that is gone is skipped, so the window opens
+/// where the OS puts it.
+pub fn restore_window(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let Some(geometry) = load_ui_state(&app.state::<AppState>()).window else {
+        return;
+    };
+    let on_screen = window.available_monitors().unwrap_or_default().iter().any(|monitor| {
+        let (position, size) = (monitor.position(), monitor.size());
+        (position.x..position.x + size.width as i32).contains(&geometry.x)
+            && (position.y..position.y + size.height as i32).contains(&geometry.y)
+    });
+    if on_screen {
+        let _ = window.set_position(tauri::PhysicalPosition::new(geometry.x, geometry.y));
+    }
+    let _ = window.set_size(tauri::PhysicalSize::new(geometry.width, geometry.height));
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Remember where the main window is, for the next launch
+pub fn save_window(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+        return;
+    };
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().unwrap_or(false),
+    };
+    // Minimized, the window reports no usable size
+    if !geometry.is_valid() {
+        return;
+    }
+
+    let state = app.state::<AppState>();
+    let ui_state = UiState {
+        window: Some(geometry),
+        ..load_ui_state(&state)
+    };
+    let saved = serde_json::to_value(&ui_state)
+        .map_err(|e| e.to_string())
+        .and_then(|value| state.settings_store.set(UI_STATE_KEY, value).map_err(|e| e.to_string()));
+    if let Err(e) = saved {
+        tracing::warn!(error = %e, "Failed to save the window position");
+    }
+}
+
 /// Set input device (microphone)
 #[tauri::command]
 pub async fn set_input_device(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     device_id: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     tracing::info!("Setting input device to: {:?}", device_id);
 
     {
@@ -319,18 +838,17 @@ pub async fn set_input_device(
     }
 
     // Auto-save settings
-    let settings = state.settings.read().await;
-    let dto = AppSettingsDto::from(&*settings);
-    drop(settings);
+    persist_settings(&app, &state).await?;
 
-    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
-    // Ensure store is reloaded before updating to avoid overwriting other settings
-    let _ = store.reload();
-    store.set(SETTINGS_KEY, serde_json::to_value(&dto).map_err(|e| e.to_string())?);
-    store.save().map_err(|e| {
-        tracing::error!("Failed to save settings: {}", e);
-        e.to_string()
-    })?;
+    // Each mic has its own noise print and profile (volume, filter, AGC, gate)
+    if let Err(e) = apply_mic_processing(&state).await {
+        tracing::warn!(error = %e, "Failed to switch mic processing");
+    }
+    let _ = app.emit("mic-profile-changed", current_mic_profile(&state).await);
+
+    if device_id.is_some() {
+        let _ = state.onboarding.complete_step(&app, OnboardingStep::InputSelected);
+    }
 
     tracing::info!("Input device saved: {:?}", device_id);
     Ok(())
@@ -342,7 +860,7 @@ pub async fn set_output_device(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     device_id: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     tracing::info!("Setting output device to: {:?}", device_id);
 
     {
@@ -351,18 +869,11 @@ pub async fn set_output_device(
     }
 
     // Auto-save settings
-    let settings = state.settings.read().await;
-    let dto = AppSettingsDto::from(&*settings);
-    drop(settings);
+    persist_settings(&app, &state).await?;
 
-    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
-    // Ensure store is reloaded before updating to avoid overwriting other settings
-    let _ = store.reload();
-    store.set(SETTINGS_KEY, serde_json::to_value(&dto).map_err(|e| e.to_string())?);
-    store.save().map_err(|e| {
-        tracing::error!("Failed to save settings: {}", e);
-        e.to_string()
-    })?;
+    if device_id.is_some() {
+        let _ = state.onboarding.complete_step(&app, OnboardingStep::OutputSelected);
+    }
 
     tracing::info!("Output device saved: {:?}", device_id);
     Ok(())
@@ -374,27 +885,26 @@ pub async fn set_preview_device(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     device_id: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     tracing::info!("Setting preview device to: {:?}", device_id);
 
-    {
+    let monitor = {
         let mut settings = state.settings.write().await;
         settings.audio.preview_device_id = device_id.clone();
-    }
+        settings.monitor
+    };
 
     // Auto-save settings
-    let settings = state.settings.read().await;
-    let dto = AppSettingsDto::from(&*settings);
-    drop(settings);
-
-    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
-    // Ensure store is reloaded before updating to avoid overwriting other settings
-    let _ = store.reload();
-    store.set(SETTINGS_KEY, serde_json::to_value(&dto).map_err(|e| e.to_string())?);
-    store.save().map_err(|e| {
-        tracing::error!("Failed to save settings: {}", e);
-        e.to_string()
-    })?;
+    persist_settings(&app, &state).await?;
+
+    // The headphone monitor follows the preview device
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetMonitor {
+            device: device_id.clone(),
+            settings: monitor,
+        })
+        .map_err(CommandError::EngineError)?;
 
     tracing::info!("Preview device saved: {:?}", device_id);
     Ok(())
@@ -406,7 +916,7 @@ pub async fn set_preview_device(
 
 /// Get current mixer configuration
 #[tauri::command]
-pub async fn get_mixer_config(state: State<'_, AppState>) -> Result<MixerConfigDto, String> {
+pub async fn get_mixer_config(state: State<'_, AppState>) -> Result<MixerConfigDto, CommandError> {
     let config = state.mixer_config.read().await;
     Ok(MixerConfigDto::from(&*config))
 }
@@ -416,7 +926,7 @@ pub async fn get_mixer_config(state: State<'_, AppState>) -> Result<MixerConfigD
 pub async fn set_master_volume(
     state: State<'_, AppState>,
     volume: f32,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let clamped_volume = volume.clamp(0.0, 1.0);
 
     // Update mixer config
@@ -430,12 +940,13 @@ pub async fn set_master_volume(
         let mut settings = state.settings.write().await;
         settings.audio.master_volume = clamped_volume;
     }
+    state.auto_save.mark_dirty(SaveTarget::Settings);
 
     // Send to audio engine
-    let engine = state.audio_engine.lock().await;
+    let engine = &state.audio_engine;
     engine
         .send_command(AudioEngineCommand::SetMasterVolume(clamped_volume))
-        .map_err(|e| format!("Failed to set master volume: {}", e))?;
+        .map_err(CommandError::EngineError)?;
 
     Ok(())
 }
@@ -446,11 +957,22 @@ pub async fn add_microphone_channel(
     state: State<'_, AppState>,
     id: String,
     name: String,
-) -> Result<MixerChannelDto, String> {
-    let channel = MixerChannel::new(&id, &name, ChannelType::Microphone);
-    let dto = MixerChannelDto::from(&channel);
-
+) -> Result<MixerChannelDto, CommandError> {
+    // One mic input, so a second channel's mute couldn't be told apart
     let mut config = state.mixer_config.write().await;
+    if let Some(existing) = config.mic_channel() {
+        return Err(CommandError::InvalidArgument(format!(
+            "The microphone already has a channel: {}",
+            existing.name()
+        )));
+    }
+
+    let mut channel = MixerChannel::new(&id, &name, ChannelType::Microphone);
+    restore_channel_level(&state, &mut channel);
+    let dto = MixerChannelDto::from(&channel);
+    if channel.is_muted() {
+        apply_channel_gain(&state, &channel).await?;
+    }
     config.add_channel(channel);
 
     Ok(dto)
@@ -462,42 +984,216 @@ pub async fn add_audio_file_channel(
     state: State<'_, AppState>,
     id: String,
     name: String,
-) -> Result<MixerChannelDto, String> {
-    let channel = MixerChannel::new(&id, &name, ChannelType::AudioFile);
+) -> Result<MixerChannelDto, CommandError> {
+    let mut channel = MixerChannel::new(&id, &name, ChannelType::AudioFile);
+    restore_channel_level(&state, &mut channel);
+    let dto = MixerChannelDto::from(&channel);
+
+    let mut config = state.mixer_config.write().await;
+    config.add_channel(channel);
+
+    Ok(dto)
+}
+
+/// List applications playing audio that can be captured as a channel
+///
+/// Needs WASAPI process loopback (Windows 10 build 20348 or later).
+#[tauri::command]
+pub async fn list_capturable_apps(state: State<'_, AppState>) -> Result<Vec<CapturableApp>, CommandError> {
+    let capture = state.app_capture.clone();
+    Ok(tauri::async_runtime::spawn_blocking(move || capture.list_apps())
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))??)
+}
+
+/// Add a channel carrying only one application's audio, e.g. a music
+/// player, instead of the whole desktop
+#[tauri::command]
+pub async fn add_app_channel(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+    process_id: u32,
+) -> Result<MixerChannelDto, CommandError> {
+    let mut channel = MixerChannel::new(&id, &name, ChannelType::Application);
+    restore_channel_level(&state, &mut channel);
+    let dto = MixerChannelDto::from(&channel);
+
+    // The rate the engine runs at, which the settings only ask for
+    let sample_rate = match state.audio_engine.mix_format() {
+        Some((rate, _)) => rate,
+        None => state.settings.read().await.audio.sample_rate,
+    };
+    let capture = state.app_capture.clone();
+    let channel_id = id.clone();
+    let gain = channel.effective_volume();
+    let source = tauri::async_runtime::spawn_blocking(move || capture.start(&channel_id, process_id, sample_rate, 2, gain))
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))??;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetAppSource {
+            channel_id: id.clone(),
+            source: Some(source),
+        })
+        .map_err(CommandError::EngineError)?;
+
+    let mut config = state.mixer_config.write().await;
+    config.remove_channel(&id);
+    config.add_channel(channel);
+
+    tracing::info!(channel = %id, process_id, "Application channel added");
+    Ok(dto)
+}
+
+/// Add a channel generating a test signal, to calibrate downstream apps
+/// (input sensitivity, filters) against a known level
+#[tauri::command]
+pub async fn add_generator_channel(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+    settings: GeneratorSettings,
+) -> Result<MixerChannelDto, CommandError> {
+    check_generator_settings(&settings)?;
+    let mut channel = MixerChannel::generator(&id, &name, settings);
+    restore_channel_level(&state, &mut channel);
     let dto = MixerChannelDto::from(&channel);
 
+    // A fresh source, so a replaced channel's gain and sweep don't linger
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::AddGenerator {
+            channel_id: id.clone(),
+            settings,
+            gain: channel.effective_volume(),
+        })
+        .map_err(CommandError::EngineError)?;
+
     let mut config = state.mixer_config.write().await;
+    config.remove_channel(&id);
     config.add_channel(channel);
 
+    tracing::info!(channel = %id, signal = ?settings.signal, level_dbfs = settings.level_dbfs, "Generator channel added");
     Ok(dto)
 }
 
+/// Change the signal or level of a generator channel
+#[tauri::command]
+pub async fn set_generator(
+    state: State<'_, AppState>,
+    channel_id: String,
+    settings: GeneratorSettings,
+) -> Result<(), CommandError> {
+    check_generator_settings(&settings)?;
+    {
+        let mut config = state.mixer_config.write().await;
+        let channel = config
+            .get_channel_mut(&channel_id)
+            .filter(|channel| channel.channel_type() == ChannelType::Generator)
+            .ok_or_else(|| CommandError::ChannelNotFound(channel_id.clone()))?;
+        channel.set_generator_settings(settings);
+    }
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetGenerator {
+            channel_id,
+            settings: Some(settings),
+        })
+        .map_err(CommandError::EngineError)
+}
+
+fn check_generator_settings(settings: &GeneratorSettings) -> Result<(), CommandError> {
+    if !GeneratorSettings::LEVELS_DBFS.contains(&settings.level_dbfs) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Generator level must be between {} and {} dBFS",
+            GeneratorSettings::LEVELS_DBFS.start(),
+            GeneratorSettings::LEVELS_DBFS.end()
+        )));
+    }
+    Ok(())
+}
+
 /// Remove a channel
 #[tauri::command]
 pub async fn remove_channel(
     state: State<'_, AppState>,
     channel_id: String,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut config = state.mixer_config.write().await;
-    config
+    let channel = config
         .remove_channel(&channel_id)
-        .ok_or_else(|| format!("Channel '{}' not found", channel_id))?;
+        .ok_or_else(|| CommandError::ChannelNotFound(channel_id.clone()))?;
+    drop(config);
+
+    if channel.channel_type() == ChannelType::Application {
+        state
+            .audio_engine
+            .send_command(AudioEngineCommand::SetAppSource {
+                channel_id: channel_id.clone(),
+                source: None,
+            })
+            .map_err(CommandError::EngineError)?;
+        state.app_capture.stop(&channel_id);
+    }
+    if channel.channel_type() == ChannelType::Generator {
+        state
+            .audio_engine
+            .send_command(AudioEngineCommand::SetGenerator {
+                channel_id: channel_id.clone(),
+                settings: None,
+            })
+            .map_err(CommandError::EngineError)?;
+    }
     Ok(())
 }
 
+/// Apply a channel's volume and mute to the engine, for channels it mixes
+///
+/// The mic channel only carries a mute, faded by the engine; the mic
+/// volume has its own control. The podcast channels set the gain and
+/// mute of their mic in the input channel map.
+async fn apply_channel_gain(state: &AppState, channel: &MixerChannel) -> Result<(), CommandError> {
+    if apply_podcast_channel(state, channel).await? {
+        return Ok(());
+    }
+    let command = match channel.channel_type() {
+        ChannelType::Application => AudioEngineCommand::SetAppSourceGain {
+            channel_id: channel.id().to_string(),
+            gain: channel.effective_volume(),
+        },
+        ChannelType::Generator => AudioEngineCommand::SetGeneratorGain {
+            channel_id: channel.id().to_string(),
+            gain: channel.effective_volume(),
+        },
+        ChannelType::Microphone => AudioEngineCommand::SetMicChannelMuted(channel.is_muted()),
+        ChannelType::AudioFile | ChannelType::SystemAudio => return Ok(()),
+    };
+    state
+        .audio_engine
+        .send_command(command)
+        .map_err(CommandError::EngineError)
+}
+
 /// Set channel volume
 #[tauri::command]
 pub async fn set_channel_volume(
     state: State<'_, AppState>,
     channel_id: String,
     volume: f32,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let mut config = state.mixer_config.write().await;
     let channel = config
         .get_channel_mut(&channel_id)
-        .ok_or_else(|| format!("Channel '{}' not found", channel_id))?;
+        .ok_or_else(|| CommandError::ChannelNotFound(channel_id.clone()))?;
     channel.set_volume(volume);
-    Ok(())
+    let channel = channel.clone();
+    drop(config);
+
+    state.auto_save.mark_dirty(SaveTarget::Mixer);
+    apply_channel_gain(&state, &channel).await
 }
 
 /// Toggle channel mute
@@ -505,62 +1201,618 @@ pub async fn set_channel_volume(
 pub async fn toggle_channel_mute(
     state: State<'_, AppState>,
     channel_id: String,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     let mut config = state.mixer_config.write().await;
     let channel = config
         .get_channel_mut(&channel_id)
-        .ok_or_else(|| format!("Channel '{}' not found", channel_id))?;
+        .ok_or_else(|| CommandError::ChannelNotFound(channel_id.clone()))?;
     channel.toggle_mute();
+    let channel = channel.clone();
+    drop(config);
+    state.auto_save.mark_dirty(SaveTarget::Mixer);
+
+    apply_channel_gain(&state, &channel).await?;
     Ok(channel.is_muted())
 }
 
-// ============================================================================
-// Mixing Control Commands
-// ============================================================================
+/// Mute length of a cough button press without a duration
+const DEFAULT_MOMENTARY_MUTE_MS: u32 = 1500;
 
-/// Start mixing
+/// Mute a channel for a moment, e.g. a cough button on the mic
+///
+/// The channel unmutes after `duration_ms` (1.5 s by default); pressing
+/// again meanwhile extends the mute. A channel already muted by hand is
+/// left alone.
 #[tauri::command]
-pub async fn start_mixing(state: State<'_, AppState>) -> Result<(), String> {
-    // Verify we have devices selected
-    let settings = state.settings.read().await;
-    let input_device = settings
-        .audio
-        .input_device_id
-        .clone()
-        .ok_or_else(|| "No input device selected".to_string())?;
-    let output_device = settings
-        .audio
-        .output_device_id
-        .clone()
-        .ok_or_else(|| "No output device selected".to_string())?;
-    let sample_rate = settings.audio.sample_rate;
-    drop(settings);
-
-    // Send start command to audio engine
-    let engine = state.audio_engine.lock().await;
-    engine
-        .send_command(AudioEngineCommand::Start {
-            input_device,
-            output_device,
-            sample_rate,
-            channels: 2, // Stereo
-        })
-        .map_err(|e| format!("Failed to start audio engine: {}", e))?;
+pub async fn momentary_mute(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    channel_id: String,
+    duration_ms: Option<u32>,
+) -> Result<(), CommandError> {
+    let duration = duration_ms.unwrap_or(DEFAULT_MOMENTARY_MUTE_MS);
+
+    let mut config = state.mixer_config.write().await;
+    let channel = config
+        .get_channel_mut(&channel_id)
+        .ok_or_else(|| CommandError::ChannelNotFound(channel_id.clone()))?;
+    let Some(generation) = channel.start_momentary_mute() else {
+        return Ok(());
+    };
+    let channel = channel.clone();
+    drop(config);
+    apply_channel_gain(&state, &channel).await?;
+
+    tauri::async_runtime::spawn(async move {
+        use tauri::Manager;
+
+        tokio::time::sleep(std::time::Duration::from_millis(duration as u64)).await;
+        let state = app.state::<AppState>();
+        let mut config = state.mixer_config.write().await;
+        let Some(channel) = config.get_channel_mut(&channel_id) else {
+            return;
+        };
+        if !channel.end_momentary_mute(generation) {
+            return;
+        }
+        let channel = channel.clone();
+        drop(config);
+        if let Err(e) = apply_channel_gain(&state, &channel).await {
+            tracing::warn!(channel = %channel_id, "Failed to end momentary mute: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Replace the mixer's mute groups
+#[tauri::command]
+pub async fn set_mute_groups(
+    state: State<'_, AppState>,
+    groups: Vec<MuteGroup>,
+) -> Result<(), CommandError> {
+    let ids: std::collections::HashSet<&str> = groups.iter().map(|g| g.id.as_str()).collect();
+    if ids.len() != groups.len() {
+        return Err(CommandError::InvalidArgument("Mute group ids must be unique".to_string()));
+    }
+    if let Some(link) = groups
+        .iter()
+        .flat_map(|g| &g.linked_groups)
+        .find(|link| !ids.contains(link.as_str()))
+    {
+        return Err(CommandError::InvalidArgument(format!("Unknown linked mute group: {}", link)));
+    }
+
+    state.mixer_config.write().await.mute_groups = groups;
+    Ok(())
+}
+
+/// Mute or unmute a group and the groups linked to it
+///
+/// Returns the ids of the channels whose mute changed.
+#[tauri::command]
+pub async fn set_mute_group_muted(
+    state: State<'_, AppState>,
+    group_id: String,
+    muted: bool,
+) -> Result<Vec<String>, CommandError> {
+    let mut config = state.mixer_config.write().await;
+    let changed = config
+        .set_group_muted(&group_id, muted)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown mute group: {}", group_id)))?;
+    let channels: Vec<MixerChannel> = changed
+        .iter()
+        .filter_map(|id| config.get_channel(id).cloned())
+        .collect();
+    drop(config);
+    state.auto_save.mark_dirty(SaveTarget::Mixer);
+
+    for channel in &channels {
+        apply_channel_gain(&state, channel).await?;
+    }
+    Ok(changed)
+}
+
+// ============================================================================
+// Mixing Control Commands
+// ============================================================================
+
+/// Start mixing
+///
+/// Idempotent: while running with the same devices and format nothing is
+/// touched, a monitor change only switches the monitor, and a device or
+/// format change rebuilds the streams. The outcome says which happened.
+#[tauri::command]
+pub async fn start_mixing(state: State<'_, AppState>) -> Result<StartOutcome, CommandError> {
+    if state.safe_mode.is_active() {
+        return Err(CommandError::SafeMode);
+    }
+
+    // Verify we have devices selected
+    let settings = state.settings.read().await;
+    let input_device = settings
+        .audio
+        .input_device_id
+        .clone()
+        .ok_or_else(|| CommandError::NoDeviceSelected("input".into()))?;
+    let output_device = settings
+        .audio
+        .output_device_id
+        .clone()
+        .ok_or_else(|| CommandError::NoDeviceSelected("output".into()))?;
+    let sample_rate = settings.audio.sample_rate;
+    let mut setup = mic_processing_commands(&settings);
+    set_keystroke_listener(&state.audio_engine, settings.keyboard_suppression.enabled);
+    setup.push(AudioEngineCommand::SetOutputFormat(settings.output_format));
+    setup.push(AudioEngineCommand::SetStopFade(settings.playback.stop_fade_ms));
+    setup.push(AudioEngineCommand::SetOutputRamp(settings.playback.output_ramp_ms));
+    setup.push(AudioEngineCommand::SetBroadcastDelay(settings.broadcast_delay.effective_delay_ms()));
+    setup.push(AudioEngineCommand::SetHeadphoneLimiter(settings.headphone_limiter.effective_ceiling_db()));
+    let highest_input_channel = settings.input_channel_maps.get(&input_device).and_then(|map| map.highest_channel());
+    let buffer_frames = settings.buffer_auto_tune.buffer_frames(&input_device, &output_device);
+    let streams = StreamSetup {
+        input_device,
+        output_device,
+        sample_rate,
+        channels: 2, // Stereo
+        highest_input_channel,
+        monitor_device: settings.audio.preview_device_id.clone(),
+        monitor: settings.monitor,
+        output_layout: settings.output_layout,
+        buffer_frames,
+    };
+    drop(settings);
+
+    // Processing settings apply live, keeping unchanged stages running;
+    // the streams only change if needed
+    let engine = &state.audio_engine;
+    for command in setup {
+        engine.send_command(command).map_err(CommandError::EngineError)?;
+    }
+    let outcome = engine.start(streams).map_err(CommandError::EngineError)?;
 
     let mut is_mixing = state.is_mixing.write().await;
     *is_mixing = true;
-    tracing::info!("Mixing started");
+    state.telemetry.record("start_mixing");
+    set_sentry_context("app", &app_info(&state).await);
+    tracing::info!(action = ?outcome.action, changes = ?outcome.changes, "Mixing started");
+    Ok(outcome)
+}
+
+/// Get the broadcast delay of the output
+#[tauri::command]
+pub async fn get_broadcast_delay(state: State<'_, AppState>) -> Result<BroadcastDelaySettings, CommandError> {
+    Ok(state.settings.read().await.broadcast_delay)
+}
+
+/// Set the broadcast delay: the virtual mic carries the mix `delay_ms`
+/// late, while the monitor and recording stay live
+///
+/// Changing the delay restarts it, so listeners hear silence while it fills.
+#[tauri::command]
+pub async fn set_broadcast_delay(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    delay: BroadcastDelaySettings,
+) -> Result<(), CommandError> {
+    if delay.delay_ms > BroadcastDelaySettings::MAX_DELAY_MS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Broadcast delay must be at most {} ms",
+            BroadcastDelaySettings::MAX_DELAY_MS
+        )));
+    }
+
+    state.settings.write().await.broadcast_delay = delay;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetBroadcastDelay(delay.effective_delay_ms()))
+        .map_err(CommandError::EngineError)?;
+    tracing::info!(enabled = delay.enabled, delay_ms = delay.delay_ms, "Broadcast delay set");
+    Ok(())
+}
+
+/// Get the speaking detection settings
+#[tauri::command]
+pub async fn get_voice_activity(state: State<'_, AppState>) -> Result<VoiceActivitySettings, CommandError> {
+    Ok(state.settings.read().await.voice_activity)
+}
+
+/// Set the speaking detection behind the `voice-activity` event: speech
+/// starts when the processed mic rises above `threshold_db` and stops after
+/// `hangover_ms` below it
+#[tauri::command]
+pub async fn set_voice_activity(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: VoiceActivitySettings,
+) -> Result<(), CommandError> {
+    if !settings.threshold_db.is_finite() || settings.threshold_db > 0.0 {
+        return Err(CommandError::InvalidArgument("Threshold must be at most 0 dBFS".into()));
+    }
+    if settings.hangover_ms > VoiceActivitySettings::MAX_HANGOVER_MS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Hangover must be at most {} ms",
+            VoiceActivitySettings::MAX_HANGOVER_MS
+        )));
+    }
+
+    state.settings.write().await.voice_activity = settings;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetVoiceActivity(settings))
+        .map_err(CommandError::EngineError)?;
+    Ok(())
+}
+
+/// Panic button: silence everything held in the broadcast delay before
+/// listeners hear it
+#[tauri::command]
+pub async fn dump_delay(state: State<'_, AppState>) -> Result<(), CommandError> {
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::DumpBroadcastDelay)
+        .map_err(CommandError::EngineError)?;
+    tracing::warn!("Broadcast delay dumped");
+    Ok(())
+}
+
+/// Bleep the last `seconds` still held in the broadcast delay, before
+/// listeners hear them
+///
+/// Only audio not yet sent can be censored, so `seconds` is capped at the
+/// delay; the beep can be swapped for silence with `mode`.
+#[tauri::command]
+pub async fn censor_last(
+    state: State<'_, AppState>,
+    seconds: f32,
+    mode: Option<CensorMode>,
+) -> Result<(), CommandError> {
+    let delay_ms = state.settings.read().await.broadcast_delay.effective_delay_ms();
+    if delay_ms == 0 {
+        return Err(CommandError::InvalidArgument("The broadcast delay is off".into()));
+    }
+    if seconds.is_nan() || seconds <= 0.0 {
+        return Err(CommandError::InvalidArgument("Seconds to censor must be positive".into()));
+    }
+
+    let seconds = seconds.min(delay_ms as f32 / 1000.0);
+    let mode = mode.unwrap_or_default();
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::CensorBroadcastDelay { seconds, mode })
+        .map_err(CommandError::EngineError)?;
+    tracing::info!(seconds, ?mode, "Broadcast delay censored");
+    Ok(())
+}
+
+/// DTO for the headphone monitor
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorDto {
+    pub settings: MonitorSettings,
+    /// Delay of the active mic effects, already part of the monitored mix
+    pub effect_latency_ms: f32,
+    /// When the monitor is heard relative to the mic: effects, delay and
+    /// the codec simulator's frame and resampling
+    pub total_delay_ms: f32,
+}
+
+/// Get the headphone monitor settings and its alignment
+#[tauri::command]
+pub async fn get_monitor(state: State<'_, AppState>) -> Result<MonitorDto, CommandError> {
+    let settings = state.settings.read().await;
+    let monitor = settings.monitor;
+    // The rate the streams run at, which the settings only ask for
+    let sample_rate = match state.audio_engine.mix_format() {
+        Some((rate, _)) => rate,
+        None => settings.audio.sample_rate,
+    }
+    .max(1);
+    drop(settings);
+
+    let latency_frames = state.audio_engine.mic_latency_frames();
+    let effect_latency_ms = latency_frames as f32 * 1000.0 / sample_rate as f32;
+    let codec_ms = match monitor.codec_bitrate_kbps {
+        Some(_) => codec_latency_frames(sample_rate) as f32 * 1000.0 / sample_rate as f32,
+        None => 0.0,
+    };
+    Ok(MonitorDto {
+        settings: monitor,
+        effect_latency_ms,
+        total_delay_ms: effect_latency_ms + monitor.delay_ms as f32 + codec_ms,
+    })
+}
+
+/// Monitor the processed mix on the preview device
+///
+/// The monitor carries the final mix, so effect latency is already
+/// included; `delay_ms` adds the rest of the way to remote listeners
+/// (e.g. the voice chat's own delay), so hearing yourself doesn't feel
+/// like an echo. With `codec_bitrate_kbps` set, the monitor goes through
+/// an Opus round trip at that bitrate, so streamers hear their voice the
+/// way Discord or phone listeners do.
+#[tauri::command]
+pub async fn set_monitor(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    monitor: MonitorSettings,
+) -> Result<(), CommandError> {
+    if monitor.delay_ms > MonitorSettings::MAX_DELAY_MS || !(0.0..=2.0).contains(&monitor.volume) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Monitor delay must be at most {} ms and volume between 0 and 2",
+            MonitorSettings::MAX_DELAY_MS
+        )));
+    }
+    if let Some(kbps) = monitor.codec_bitrate_kbps {
+        if !MonitorSettings::CODEC_BITRATES_KBPS.contains(&kbps) {
+            return Err(CommandError::InvalidArgument(format!(
+                "Codec bitrate must be between {} and {} kbps",
+                MonitorSettings::CODEC_BITRATES_KBPS.start(),
+                MonitorSettings::CODEC_BITRATES_KBPS.end()
+            )));
+        }
+    }
+
+    let device = {
+        let mut settings = state.settings.write().await;
+        settings.monitor = monitor;
+        settings.audio.preview_device_id.clone()
+    };
+    persist_settings(&app, &state).await?;
+
+    if monitor.enabled && device.is_none() {
+        return Err(CommandError::NoDeviceSelected("preview".into()));
+    }
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetMonitor {
+            device,
+            settings: monitor,
+        })
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(
+        enabled = monitor.enabled,
+        delay_ms = monitor.delay_ms,
+        codec_kbps = ?monitor.codec_bitrate_kbps,
+        "Monitor updated"
+    );
+    Ok(())
+}
+
+/// Get the safety limiter of the monitor and preview outputs
+#[tauri::command]
+pub async fn get_headphone_limiter(state: State<'_, AppState>) -> Result<HeadphoneLimiterSettings, CommandError> {
+    Ok(state.settings.read().await.headphone_limiter)
+}
+
+/// Set the brick-wall ceiling of what the operator hears on the monitor
+/// and previews; the virtual mic is not affected
+#[tauri::command]
+pub async fn set_headphone_limiter(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    limiter: HeadphoneLimiterSettings,
+) -> Result<(), CommandError> {
+    if !HeadphoneLimiterSettings::CEILINGS_DB.contains(&limiter.ceiling_db) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Headphone ceiling must be between {} and {} dBFS",
+            HeadphoneLimiterSettings::CEILINGS_DB.start(),
+            HeadphoneLimiterSettings::CEILINGS_DB.end()
+        )));
+    }
+
+    state.settings.write().await.headphone_limiter = limiter;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetHeadphoneLimiter(limiter.effective_ceiling_db()))
+        .map_err(CommandError::EngineError)?;
+    tracing::info!(enabled = limiter.enabled, ceiling_db = limiter.ceiling_db, "Headphone limiter set");
+    Ok(())
+}
+
+/// Get the auto-mute of the mic on a feedback loop
+#[tauri::command]
+pub async fn get_feedback_protection(state: State<'_, AppState>) -> Result<FeedbackProtectionSettings, CommandError> {
+    Ok(state.settings.read().await.feedback_protection)
+}
+
+/// Turn the feedback loop detection on or off; when on, a detected loop
+/// mutes the mic on its own, apart from the user's mute, and emits
+/// `feedback-detected`
+#[tauri::command]
+pub async fn set_feedback_protection(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    protection: FeedbackProtectionSettings,
+) -> Result<(), CommandError> {
+    state.settings.write().await.feedback_protection = protection;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetFeedbackProtection(protection.enabled))
+        .map_err(CommandError::EngineError)?;
+    tracing::info!(enabled = protection.enabled, "Feedback protection set");
+    Ok(())
+}
+
+/// Reopen a mic the feedback guard muted; the user's mute is left as set
+#[tauri::command]
+pub async fn clear_feedback_mute(state: State<'_, AppState>) -> Result<(), CommandError> {
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::ClearFeedbackMute)
+        .map_err(CommandError::EngineError)?;
+    tracing::info!("Feedback mute cleared");
+    Ok(())
+}
+
+/// Get the ducking of the mic under playing sounds
+#[tauri::command]
+pub async fn get_ducking_config(state: State<'_, AppState>) -> Result<DuckingSettings, CommandError> {
+    Ok(state.settings.read().await.audio.ducking)
+}
+
+/// Set how far the mic drops while a sound plays, and how fast it drops
+/// and comes back
+#[tauri::command]
+pub async fn set_ducking_config(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    config: DuckingSettings,
+) -> Result<(), CommandError> {
+    if !config.is_valid() {
+        return Err(CommandError::InvalidArgument(format!(
+            "Ducking depth must be within {:?} dB, attack within {:?} ms and release within {:?} ms",
+            DuckingSettings::DEPTHS_DB,
+            DuckingSettings::ATTACKS_MS,
+            DuckingSettings::RELEASES_MS
+        )));
+    }
+
+    state.settings.write().await.audio.ducking = config;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetMicDucking(config))
+        .map_err(CommandError::EngineError)?;
+    tracing::info!(enabled = config.enabled, depth_db = config.depth_db, "Mic ducking set");
+    Ok(())
+}
+
+/// Get the buffer auto-tune setting and the sizes tuned so far
+#[tauri::command]
+pub async fn get_buffer_auto_tune(state: State<'_, AppState>) -> Result<BufferAutoTuneSettings, CommandError> {
+    Ok(state.settings.read().await.buffer_auto_tune.clone())
+}
+
+/// Turn buffer auto-tune on or off, or forget tuned sizes by leaving them
+/// out of `tuned`
+///
+/// Running streams are restarted with the buffer the setting gives them.
+#[tauri::command]
+pub async fn set_buffer_auto_tune(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    auto_tune: BufferAutoTuneSettings,
+) -> Result<(), CommandError> {
+    let range = BufferAutoTuneSettings::START_FRAMES..=BufferAutoTuneSettings::MAX_FRAMES;
+    if auto_tune.tuned.values().any(|frames| !range.contains(frames)) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Buffer sizes must be between {} and {} frames",
+            range.start(),
+            range.end()
+        )));
+    }
+
+    state.settings.write().await.buffer_auto_tune = auto_tune.clone();
+    persist_settings(&app, &state).await?;
+
+    let engine = &state.audio_engine;
+    if let Some(setup) = engine.stream_setup().filter(|_| engine.is_running()) {
+        let buffer_frames = auto_tune.buffer_frames(&setup.input_device, &setup.output_device);
+        engine
+            .start(StreamSetup { buffer_frames, ..setup })
+            .map_err(CommandError::EngineError)?;
+    }
+    tracing::info!(enabled = auto_tune.enabled, tuned = auto_tune.tuned.len(), "Buffer auto-tune set");
+    Ok(())
+}
+
+/// Switch the engine to the output device's sample rate
+///
+/// Fixes the "weird pitch" users hear when the virtual cable runs at a
+/// different rate than the engine. Restarts mixing when it is running and
+/// returns the new rate.
+#[tauri::command]
+pub async fn match_device_sample_rate(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<u32, CommandError> {
+    let device_rate = state
+        .audio_engine
+        .device_sample_rate()
+        .ok_or(CommandError::EngineNotRunning)?;
+
+    state.settings.write().await.audio.sample_rate = device_rate;
+    persist_settings(&app, &state).await?;
+
+    // Start replaces the running streams
+    if *state.is_mixing.read().await {
+        start_mixing(state.clone()).await?;
+    }
+
+    tracing::info!(sample_rate = device_rate, "Engine sample rate matched to the output device");
+    Ok(device_rate)
+}
+
+/// Get the channel layout of the output device
+#[tauri::command]
+pub async fn get_output_layout(state: State<'_, AppState>) -> Result<OutputLayoutSettings, CommandError> {
+    Ok(state.settings.read().await.output_layout)
+}
+
+/// Set the channel layout the output device is opened with
+///
+/// The mix stays stereo and is placed on the front pair (and the center if
+/// asked) of a 5.1 or 7.1 device. Auto follows the device's own layout.
+#[tauri::command]
+pub async fn set_output_layout(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    layout: OutputLayoutSettings,
+) -> Result<(), CommandError> {
+    state.settings.write().await.output_layout = layout;
+    persist_settings(&app, &state).await?;
+
+    // Start reopens the output with the new layout
+    if *state.is_mixing.read().await {
+        start_mixing(state.clone()).await?;
+    }
+
+    tracing::info!(layout = ?layout.layout, center = layout.center_from_stereo, "Output layout set");
     Ok(())
 }
 
+/// Fail with `RecordingActive` while a recording runs, unless the caller
+/// confirmed with `finalize_recording`
+fn guard_recording(state: &AppState, finalize_recording: bool) -> Result<(), CommandError> {
+    match state.recorder.path() {
+        Some(path) if !finalize_recording => Err(CommandError::RecordingActive {
+            path: path.to_string_lossy().into_owned(),
+        }),
+        _ => Ok(()),
+    }
+}
+
 /// Stop mixing
+///
+/// While a recording runs this fails with `RECORDING_ACTIVE`, so the UI can
+/// ask first; call again with `finalize_recording` to finish the file.
 #[tauri::command]
-pub async fn stop_mixing(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn stop_mixing(
+    state: State<'_, AppState>,
+    finalize_recording: Option<bool>,
+) -> Result<(), CommandError> {
+    guard_recording(&state, finalize_recording.unwrap_or(false))?;
+
+    // The recording has no audio to capture once the streams are gone
+    if state.recorder.is_recording() {
+        finish_recording(&state).await?;
+    }
+
     // Send stop command to audio engine
-    let engine = state.audio_engine.lock().await;
-    engine
+    state
+        .audio_engine
         .send_command(AudioEngineCommand::Stop)
-        .map_err(|e| format!("Failed to stop audio engine: {}", e))?;
+        .map_err(CommandError::EngineError)?;
 
     let mut is_mixing = state.is_mixing.write().await;
     *is_mixing = false;
@@ -570,11 +1822,99 @@ pub async fn stop_mixing(state: State<'_, AppState>) -> Result<(), String> {
 
 /// Get mixing status
 #[tauri::command]
-pub async fn is_mixing(state: State<'_, AppState>) -> Result<bool, String> {
-    let engine = state.audio_engine.lock().await;
+pub async fn is_mixing(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let engine = &state.audio_engine;
     Ok(engine.is_running())
 }
 
+/// Everything the UI shows about the engine, in one call
+#[derive(Debug, Serialize)]
+pub struct EngineSnapshotDto {
+    pub running: bool,
+    /// Devices the engine runs on; None while stopped
+    pub input_device: Option<String>,
+    pub output_device: Option<String>,
+    pub monitor_device: Option<String>,
+    pub sample_rate: u32,
+    pub device_sample_rate: Option<u32>,
+    /// Master and channel volumes, mutes and mute groups
+    pub mixer: MixerConfigDto,
+    pub mic_volume: f32,
+    pub mic_muted: bool,
+    /// Mic held closed by the feedback guard, apart from `mic_muted`
+    pub feedback_muted: bool,
+    pub playing_sounds: Vec<PlayingSoundInfo>,
+    pub preview_pad_id: Option<String>,
+    pub mic_chain: Vec<MicEffectNode>,
+    pub voice_changer: VoiceChangerSettings,
+    pub monitor: MonitorSettings,
+    pub broadcast_delay: BroadcastDelaySettings,
+    pub headphone_limiter: HeadphoneLimiterSettings,
+    pub recording_path: Option<String>,
+}
+
+/// Get the whole engine state at once, so a reloaded or reconnecting
+/// frontend can rebuild its view without replaying every getter
+#[tauri::command]
+pub async fn get_engine_snapshot(state: State<'_, AppState>) -> Result<EngineSnapshotDto, CommandError> {
+    let (running, device_sample_rate, mic_volume, mic_muted, feedback_muted, playing_sounds) = {
+        let engine = &state.audio_engine;
+        (
+            engine.is_running(),
+            engine.device_sample_rate(),
+            engine.mic_volume(),
+            engine.is_mic_muted(),
+            engine.is_feedback_muted(),
+            engine.playing_snapshot().to_vec(),
+        )
+    };
+    let preview_pad_id = state.preview_engine.lock().await.as_ref().and_then(|e| e.current_pad_id());
+    let mixer = MixerConfigDto::from(&*state.mixer_config.read().await);
+    let settings = state.settings.read().await;
+    let monitor_device = settings.audio.preview_device_id.clone().filter(|_| settings.monitor.enabled);
+
+    Ok(EngineSnapshotDto {
+        running,
+        input_device: settings.audio.input_device_id.clone().filter(|_| running),
+        output_device: settings.audio.output_device_id.clone().filter(|_| running),
+        monitor_device: monitor_device.filter(|_| running),
+        sample_rate: settings.audio.sample_rate,
+        device_sample_rate,
+        mixer,
+        mic_volume,
+        mic_muted,
+        feedback_muted,
+        playing_sounds,
+        preview_pad_id,
+        mic_chain: settings.mic_chain.nodes().to_vec(),
+        voice_changer: settings.voice_changer,
+        monitor: settings.monitor,
+        broadcast_delay: settings.broadcast_delay,
+        headphone_limiter: settings.headphone_limiter,
+        recording_path: state.recorder.path().map(|path| path.to_string_lossy().into_owned()),
+    })
+}
+
+/// Get the audio callback stalls found by the engine watchdog
+#[tauri::command]
+pub async fn get_watchdog_diagnostics(state: State<'_, AppState>) -> Result<WatchdogDiagnostics, CommandError> {
+    Ok(state.audio_engine.watchdog_diagnostics())
+}
+
+/// Take the output rendered on the null devices since the last call
+///
+/// Only available when the app runs with `--test-audio`; lets headless
+/// tests assert on what `start_mixing` and `play_sound` produced.
+#[tauri::command]
+pub async fn take_test_audio_capture(app: tauri::AppHandle) -> Result<Vec<f32>, CommandError> {
+    use tauri::Manager;
+
+    let devices = app
+        .try_state::<NullAudioDevices>()
+        .ok_or_else(|| CommandError::EngineError(format!("The app was not started with {}", TEST_AUDIO_FLAG)))?;
+    Ok(devices.take_captured())
+}
+
 // ============================================================================
 // Sound Playback Commands
 // ============================================================================
@@ -590,61 +1930,43 @@ pub struct SoundFileDto {
     pub channels: u16,
 }
 
-/// Load and decode an audio file, returning its metadata
+/// Load an audio file, returning its metadata
 #[tauri::command]
-pub async fn load_sound_file(path: String) -> Result<SoundFileDto, String> {
-    use rodio::Source;
-    use std::fs::File;
-    use std::io::BufReader;
+pub async fn load_sound_file(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<SoundFileDto, CommandError> {
     use std::path::Path;
 
     tracing::info!("[load_sound_file] Called with path: {}", path);
 
     let file_path = Path::new(&path);
 
-    // Validate file exists
-    if !file_path.exists() {
-        tracing::error!("[load_sound_file] File not found: {}", path);
-        return Err(format!("File not found: {}", path));
-    }
-    tracing::info!("[load_sound_file] File exists: {}", path);
-
     // Get file name
     let name = file_path
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("Unknown")
         .to_string();
-    tracing::info!("[load_sound_file] File name: {}", name);
-
-    // Open and decode the file to get metadata
-    let file = File::open(&path).map_err(|e| {
-        tracing::error!("[load_sound_file] Failed to open file: {}", e);
-        format!("Failed to open file: {}", e)
-    })?;
-    let reader = BufReader::new(file);
-    tracing::info!("[load_sound_file] File opened successfully");
 
-    let decoder = rodio::Decoder::new(reader).map_err(|e| {
-        tracing::error!("[load_sound_file] Failed to decode audio: {}", e);
-        format!("Failed to decode audio file: {}", e)
+    // Only the header is read; long files are streamed when played
+    let metadata = probe_sound(&state, &path).await.inspect_err(|e| {
+        tracing::error!("[load_sound_file] Failed to load {}: {}", path, e);
     })?;
-    tracing::info!("[load_sound_file] Decoder created successfully");
-
-    let sample_rate = decoder.sample_rate();
-    let channels = decoder.channels();
-    tracing::info!("[load_sound_file] Audio info: {}Hz, {} channels", sample_rate, channels);
+    // Short sounds are decoded up front so playback starts instantly from the cache
+    let decoder = state.decoder.clone();
+    let preload = file_path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || decoder.preload(&[preload.as_path()]));
 
-    // Get duration in seconds
-    let duration = decoder.total_duration()
-        .map(|d| d.as_secs_f64())
-        .unwrap_or(0.0);
-    tracing::info!("[load_sound_file] Duration: {:.2}s", duration);
+    let sample_rate = metadata.audio_format.sample_rate;
+    let channels = metadata.audio_format.channels;
+    let duration = metadata.duration.as_secs_f64();
 
     // Generate unique ID
     let id = format!("sound_{}", &uuid::Uuid::new_v4().to_string().replace("-", "")[..8]);
 
     tracing::info!("[load_sound_file] Success: {} ({:.1}s, {}Hz, {}ch)", name, duration, sample_rate, channels);
+    state.telemetry.record("load_sound_file");
 
     Ok(SoundFileDto {
         id,
@@ -656,169 +1978,4554 @@ pub async fn load_sound_file(path: String) -> Result<SoundFileDto, String> {
     })
 }
 
-/// Play a sound file (mix with microphone)
+/// Play a sound file (mix with microphone)
+///
+/// Every play is added to the session's play log with its `source`
+/// (defaults to the UI). A `looping` sound restarts seamlessly at its end
+/// until stopped.
+#[tauri::command]
+pub async fn play_sound(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    path: String,
+    source: Option<PlaySource>,
+    looping: Option<bool>,
+    board_generation: Option<u64>,
+) -> Result<(), CommandError> {
+    // A hotkey matched on the board just swapped out must not fire
+    if board_generation.is_some_and(|generation| !state.hotkeys.is_current(generation)) {
+        tracing::debug!(pad = %id, "Dropped a hotkey press of a previous board");
+        return Err(CommandError::StaleBoard);
+    }
+    check_sound_armed(&app, &state, &id).await?;
+    let looping = looping.unwrap_or(false);
+    let volume = soundboard_sound_volume(&app, &id);
+    let duration = match open_sound_stream(&app, &state, &id, &path, looping).await? {
+        Some(stream) => {
+            let duration = probe_sound(&state, &path).await?.duration;
+            let duration = duration.saturating_sub(soundboard_sound_edits(&app, &id).start()).as_secs_f64();
+            state
+                .audio_engine
+                .send_command(AudioEngineCommand::PlayStream { id: id.clone(), stream, volume, looping })
+                .map_err(CommandError::EngineError)?;
+            tracing::info!("Streaming sound: {} ({:.1}s)", path, duration);
+            duration
+        }
+        None => decode_and_play(&app, &state, &id, &path, volume, looping).await?,
+    };
+    state.telemetry.record("play_sound");
+
+    let name = soundboard_sound(&app, &id)
+        .and_then(|sound| sound.get("name")?.as_str().map(String::from))
+        .or_else(|| std::path::Path::new(&path).file_stem()?.to_str().map(String::from))
+        .unwrap_or_default();
+    state
+        .play_log
+        .record(&play_log_dir(&app)?, &id, &name, &path, duration, source.unwrap_or_default());
+    if state.recorder.is_recording() {
+        state.recorder.add_marker(&name, MarkerKind::Pad)?;
+    }
+    state.now_playing.started(now_playing_entry(&app, &id, name));
+
+    Ok(())
+}
+
+/// Decode a sound whole and send it to the engine; its duration
+async fn decode_and_play(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    id: &str,
+    path: &str,
+    volume: f32,
+    looping: bool,
+) -> Result<f64, CommandError> {
+    let sound = decode_sound(state, path).await?;
+    let buffer = soundboard_sound_edits(app, id).apply(&sound.buffer);
+
+    // Get format info
+    let sample_rate = buffer.sample_rate();
+    let channels = buffer.channels();
+    let duration = buffer.frame_count() as f64 / sample_rate.max(1) as f64;
+
+    let samples = buffer.to_raw_f32();
+    let samples = with_auto_level(app, state, id, samples, channels, sample_rate).await?;
+    let samples = match soundboard_sound_speed(app, id) {
+        Some(speed) => with_speed(samples, channels, speed).await?,
+        None => samples,
+    };
+    let (samples, sample_rate, channels) = with_mix_format(state, samples, sample_rate, channels).await?;
+    let samples_len = samples.len();
+
+    // Send to audio engine
+    let engine = &state.audio_engine;
+    engine
+        .send_command(AudioEngineCommand::PlaySound {
+            id: id.to_string(),
+            samples,
+            sample_rate,
+            channels,
+            volume,
+            looping,
+        })
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!("Playing sound: {} ({} samples, {}Hz, {} ch)",
+        path, samples_len, sample_rate, channels);
+
+    Ok(duration)
+}
+
+/// Start streaming a long sound, or None when it should be decoded whole
+///
+/// Streaming needs the engine's mix format and can't change a sound's
+/// speed or cut its end. With auto-level on, a sound is only streamed once its loudness
+/// is stored; the first play decodes it whole to measure it.
+async fn open_sound_stream(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    id: &str,
+    path: &str,
+    looping: bool,
+) -> Result<Option<SoundStream>, CommandError> {
+    let edits = soundboard_sound_edits(app, id);
+    if soundboard_sound_speed(app, id).is_some() || edits.trim_end.is_some() {
+        return Ok(None);
+    }
+    let Some((sample_rate, channels)) = state.audio_engine.mix_format() else {
+        return Ok(None);
+    };
+    let Some(gain) = stream_gain(app, state, id).await else {
+        return Ok(None);
+    };
+
+    let decoder = state.decoder.clone();
+    let path = std::path::PathBuf::from(path);
+    let format = StreamFormat {
+        sample_rate,
+        channels,
+        gain: gain * edits.gain(),
+        looping,
+        start: edits.start(),
+    };
+    let stream = tauri::async_runtime::spawn_blocking(move || {
+        if !decoder.should_stream(&path)? {
+            return Ok(None);
+        }
+        decoder.stream(&path, format).map(Some)
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))??;
+    Ok(stream)
+}
+
+/// Auto-level gain of a streamed sound, None when its loudness is unknown
+///
+/// The peak of a stream isn't known before it plays, so auto-level can
+/// only turn a streamed sound down.
+async fn stream_gain(app: &tauri::AppHandle, state: &AppState, sound_id: &str) -> Option<f32> {
+    let playback = state.settings.read().await.playback;
+    if !playback.auto_level {
+        return Some(1.0);
+    }
+    let sound = soundboard_sound(app, sound_id)?;
+    if sound.get("autoLevel").and_then(|enabled| enabled.as_bool()) == Some(false) {
+        return Some(1.0);
+    }
+    let lufs = sound.get("loudnessLufs")?.as_f64()? as f32;
+    Some(playback.auto_level_gain(lufs, 1.0))
+}
+
+/// Decode the soundboard's sounds into the cache ahead of their first press
+///
+/// Most played first, counted over every logged session; stops once the
+/// cache is full rather than evicting sounds for them. Blocks, so it runs
+/// on the startup thread.
+pub fn preload_board_sounds(app: &tauri::AppHandle) {
+    use crate::domain::play_stats;
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    let stats = match play_log_dir(app) {
+        Ok(log_dir) => play_stats(&logged_plays(&state, &log_dir)),
+        Err(_) => HashMap::new(),
+    };
+    let pads = app
+        .store(SOUNDBOARD_STORE)
+        .ok()
+        .and_then(|store| store.get(SOUNDBOARD_KEY))
+        .unwrap_or_default();
+    let mut sounds: Vec<(u32, &str)> = pads
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|pad| pad.get("sound").into_iter().chain(nested_sounds(pad, "variants")))
+        .filter_map(|sound| {
+            let id = sound.get("id")?.as_str()?;
+            let path = sound.get("path")?.as_str()?;
+            Some((stats.get(id).map_or(0, |stats| stats.play_count), path))
+        })
+        .collect();
+    sounds.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let paths: Vec<&std::path::Path> = sounds.iter().map(|(_, path)| std::path::Path::new(*path)).collect();
+    let loaded = state.decoder.preload(&paths);
+    tracing::debug!(sounds = paths.len(), loaded, "Preloaded sounds");
+}
+
+/// Play soundboard sounds starting on the same output frame
+///
+/// For layered stingers or stems: every sound is decoded first, then all
+/// start on the same callback boundary with identical sample offsets.
+#[tauri::command]
+pub async fn play_sounds_synced(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    source: Option<PlaySource>,
+) -> Result<(), CommandError> {
+    start_synced(&app, &state, &ids, source.unwrap_or_default()).await?;
+    tracing::info!("Playing {} synced sound(s)", ids.len());
+    Ok(())
+}
+
+/// Decode soundboard sounds and start them on the same frame, logging each
+async fn start_synced(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    ids: &[String],
+    source: PlaySource,
+) -> Result<(), CommandError> {
+    if ids.is_empty() {
+        return Err(CommandError::InvalidArgument("No sounds to play".into()));
+    }
+    if let Some(duplicate) = ids.iter().enumerate().find(|(i, id)| ids[..*i].contains(id)).map(|(_, id)| id) {
+        return Err(CommandError::InvalidArgument(format!("{} is listed twice", duplicate)));
+    }
+    for id in ids {
+        check_sound_armed(app, state, id).await?;
+    }
+
+    let mut sounds = Vec::with_capacity(ids.len());
+    let mut played = Vec::with_capacity(ids.len());
+    for id in ids {
+        let path = soundboard_sound_path(app, id).ok_or_else(|| CommandError::SoundNotFound(id.clone()))?;
+        let sound = decode_sound(state, &path).await?;
+        let buffer = soundboard_sound_edits(app, id).apply(&sound.buffer);
+        let (sample_rate, channels) = (buffer.sample_rate(), buffer.channels());
+        let samples = with_auto_level(app, state, id, buffer.to_raw_f32(), channels, sample_rate).await?;
+        let samples = match soundboard_sound_speed(app, id) {
+            Some(speed) => with_speed(samples, channels, speed).await?,
+            None => samples,
+        };
+        let (samples, sample_rate, channels) = with_mix_format(state, samples, sample_rate, channels).await?;
+        sounds.push(SyncedSound { id: id.clone(), samples, sample_rate, channels });
+        played.push((id, path, buffer.frame_count() as f64 / buffer.sample_rate().max(1) as f64));
+    }
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::PlaySoundsSynced { sounds })
+        .map_err(CommandError::EngineError)?;
+    state.telemetry.record("play_sounds_synced");
+
+    let log_dir = play_log_dir(app)?;
+    for (id, path, duration) in played {
+        let name = soundboard_sound(app, id)
+            .and_then(|sound| sound.get("name")?.as_str().map(String::from))
+            .or_else(|| std::path::Path::new(&path).file_stem()?.to_str().map(String::from))
+            .unwrap_or_default();
+        state.play_log.record(&log_dir, id, &name, &path, duration, source);
+        if state.recorder.is_recording() {
+            state.recorder.add_marker(&name, MarkerKind::Pad)?;
+        }
+        state.now_playing.started(now_playing_entry(app, id, name));
+    }
+    Ok(())
+}
+
+/// Overlay entry of a started sound, with the artwork and color of its pad
+fn now_playing_entry(app: &tauri::AppHandle, sound_id: &str, name: String) -> NowPlayingEntry {
+    let pads = app.store(SOUNDBOARD_STORE).ok().and_then(|store| store.get(SOUNDBOARD_KEY));
+    let pad = pads.as_ref().and_then(|pads| pads.as_array()).and_then(|pads| {
+        pads.iter().find(|pad| pad_has_sound(pad, sound_id))
+    });
+    let field = |key: &str| pad.and_then(|pad| pad.get(key)?.as_str().map(String::from));
+
+    NowPlayingEntry {
+        id: sound_id.to_string(),
+        name,
+        artwork: field("artwork").map(Into::into),
+        color: field("color"),
+    }
+}
+
+/// Convert decoded samples to the engine's mix format off the async
+/// runtime, so the engine thread doesn't resample them
+///
+/// Returns the samples with their rate and channels, unchanged while the
+/// engine is stopped.
+async fn with_mix_format(
+    state: &AppState,
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<(Vec<f32>, u32, u16), CommandError> {
+    let Some((mix_rate, mix_channels)) = state.audio_engine.mix_format() else {
+        return Ok((samples, sample_rate, channels));
+    };
+    let samples = tauri::async_runtime::spawn_blocking(move || {
+        convert_to_mix_format(samples, sample_rate, channels, mix_rate, mix_channels)
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))?;
+    Ok((samples, mix_rate, mix_channels as u16))
+}
+
+/// Decode a file, or take it from the cache, off the async runtime
+async fn decode_sound(state: &AppState, path: &str) -> Result<Arc<DecodedSound>, CommandError> {
+    let decoder = state.decoder.clone();
+    let path = std::path::PathBuf::from(path);
+    tauri::async_runtime::spawn_blocking(move || decoder.decode(&path))
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))?
+        .map_err(CommandError::from)
+}
+
+/// Read a file's metadata off the async runtime
+async fn probe_sound(state: &AppState, path: &str) -> Result<AudioFileMetadata, CommandError> {
+    let decoder = state.decoder.clone();
+    let path = std::path::PathBuf::from(path);
+    tauri::async_runtime::spawn_blocking(move || decoder.probe(&path))
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))?
+        .map_err(CommandError::from)
+}
+
+/// Change the speed of decoded samples off the async runtime
+async fn with_speed(samples: Vec<f32>, channels: u16, speed: PlaybackSpeed) -> Result<Vec<f32>, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || crate::dsp::change_speed(&samples, channels as usize, speed))
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))
+}
+
+/// Bring a soundboard sound to the auto-level target, when enabled
+///
+/// The loudness is measured on the first play and saved with the sound, so
+/// later plays only apply the gain.
+async fn with_auto_level(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    sound_id: &str,
+    samples: Vec<f32>,
+    channels: u16,
+    sample_rate: u32,
+) -> Result<Vec<f32>, CommandError> {
+    let playback = state.settings.read().await.playback;
+    if !playback.auto_level {
+        return Ok(samples);
+    }
+    let Some(sound) = soundboard_sound(app, sound_id) else {
+        return Ok(samples);
+    };
+    if sound.get("autoLevel").and_then(|enabled| enabled.as_bool()) == Some(false) {
+        return Ok(samples);
+    }
+
+    let stored = sound.get("loudnessLufs").and_then(|lufs| lufs.as_f64()).map(|lufs| lufs as f32);
+    let (samples, lufs) = tauri::async_runtime::spawn_blocking(move || {
+        let mut samples = samples;
+        let lufs = stored.or_else(|| crate::dsp::integrated_loudness(&samples, channels as usize, sample_rate));
+        if let Some(lufs) = lufs {
+            let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+            let gain = playback.auto_level_gain(lufs, peak);
+            samples.iter_mut().for_each(|sample| *sample *= gain);
+        }
+        (samples, lufs)
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))?;
+
+    if let (None, Some(lufs)) = (stored, lufs) {
+        save_sound_loudness(app, sound_id, lufs)?;
+    }
+    Ok(samples)
+}
+
+/// Save the measured loudness of a soundboard sound for auto-level
+fn save_sound_loudness(app: &tauri::AppHandle, sound_id: &str, lufs: f32) -> Result<(), CommandError> {
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let sound = soundboard_sound_mut(&mut pads, sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.into()))?;
+    sound.insert("loudnessLufs".into(), lufs.into());
+    store.set(SOUNDBOARD_KEY, pads);
+    store.save()?;
+    tracing::debug!(sound = %sound_id, lufs, "Sound loudness measured");
+    Ok(())
+}
+
+/// Measure the integrated loudness of a soundboard sound and save it for
+/// auto-level; None when the sound is silent
+#[tauri::command]
+pub async fn measure_sound_loudness(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    sound_id: String,
+) -> Result<Option<f32>, CommandError> {
+    let path = soundboard_sound_path(&app, &sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.clone()))?;
+    let sound = state.decoder.decode(std::path::Path::new(&path))?;
+    let channels = sound.buffer.channels() as usize;
+    let sample_rate = sound.buffer.sample_rate();
+    let samples = sound.buffer.to_raw_f32();
+    let lufs = tauri::async_runtime::spawn_blocking(move || crate::dsp::integrated_loudness(&samples, channels, sample_rate))
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))?;
+
+    if let Some(lufs) = lufs {
+        save_sound_loudness(&app, &sound_id, lufs)?;
+    }
+    Ok(lufs)
+}
+
+/// Opt a soundboard sound out of (or back into) auto-level, e.g. a
+/// deliberately quiet ambience pad
+#[tauri::command]
+pub async fn set_sound_auto_level(app: tauri::AppHandle, sound_id: String, enabled: bool) -> Result<(), CommandError> {
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let sound = soundboard_sound_mut(&mut pads, &sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.clone()))?;
+    if enabled {
+        sound.remove("autoLevel");
+    } else {
+        sound.insert("autoLevel".into(), false.into());
+    }
+    store.set(SOUNDBOARD_KEY, pads);
+    store.save()?;
+
+    tracing::info!(sound = %sound_id, enabled, "Sound auto-level set");
+    Ok(())
+}
+
+/// Set the playback speed of a soundboard sound (0.5x - 2x), saved with it
+///
+/// `time_stretch` keeps the pitch; `resample` (the default) shifts it like
+/// a tape. Applies from the next play.
+#[tauri::command]
+pub async fn set_sound_speed(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    sound_id: String,
+    rate: f32,
+    mode: Option<SpeedMode>,
+) -> Result<(), CommandError> {
+    if !PLAYBACK_RATES.contains(&rate) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Speed must be between {}x and {}x",
+            PLAYBACK_RATES.start(),
+            PLAYBACK_RATES.end()
+        )));
+    }
+    let mode = mode.unwrap_or_default();
+    let speed = PlaybackSpeed::new(rate, mode);
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let sound = soundboard_sound_mut(&mut pads, &sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.clone()))?;
+    if speed.is_normal() {
+        sound.remove("speed");
+    } else {
+        let speed = serde_json::to_value(speed).map_err(|e| CommandError::Internal(e.to_string()))?;
+        sound.insert("speed".into(), speed);
+    }
+    store.set(SOUNDBOARD_KEY, pads);
+    state.auto_save.mark_dirty(SaveTarget::Soundboard);
+
+    tracing::info!(sound = %sound_id, rate, mode = ?mode, "Sound speed set");
+    Ok(())
+}
+
+/// Stems of a stem pad as (sound id, volume)
+fn pad_stems(pad: &serde_json::Value) -> Vec<(String, f32)> {
+    pad.get("stems")
+        .and_then(|stems| stems.as_array())
+        .map(|stems| {
+            stems
+                .iter()
+                .filter_map(|stem| {
+                    let id = stem.get("sound")?.get("id")?.as_str()?.to_string();
+                    let volume = stem.get("volume").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32;
+                    Some((id, volume))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Play every stem of a stem pad (e.g. music, drums, vocals) together
+///
+/// Stems always start on the same frame, each at its saved volume, and
+/// keep their own live volume through `set_stem_volume`.
+#[tauri::command]
+pub async fn play_stem_sound(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+    source: Option<PlaySource>,
+) -> Result<(), CommandError> {
+    let stems = soundboard_pad(&app, &pad_id)
+        .map(|pad| pad_stems(&pad))
+        .ok_or_else(|| CommandError::SoundNotFound(pad_id.clone()))?;
+    if stems.is_empty() {
+        return Err(CommandError::InvalidArgument(format!("Pad {} has no stems", pad_id)));
+    }
+
+    {
+        let engine = &state.audio_engine;
+        for (id, volume) in &stems {
+            engine
+                .send_command(AudioEngineCommand::SetSoundVolume {
+                    id: id.clone(),
+                    volume: *volume,
+                })
+                .map_err(CommandError::EngineError)?;
+        }
+    }
+
+    let ids: Vec<String> = stems.into_iter().map(|(id, _)| id).collect();
+    start_synced(&app, &state, &ids, source.unwrap_or_default()).await?;
+    tracing::info!(pad = %pad_id, stems = ids.len(), "Playing stem sound");
+    Ok(())
+}
+
+/// Set the volume of one stem of a stem pad (0.0 - 2.0), live and saved
+///
+/// A volume of 0 mutes the stem, e.g. the vocals of a backing track.
+#[tauri::command]
+pub async fn set_stem_volume(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+    stem_id: String,
+    volume: f32,
+) -> Result<(), CommandError> {
+    if !(0.0..=2.0).contains(&volume) {
+        return Err(CommandError::InvalidArgument("Stem volume must be between 0 and 2".into()));
+    }
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let stem = pads
+        .as_array_mut()
+        .and_then(|pads| pads.iter_mut().find(|p| p.get("id").and_then(|id| id.as_str()) == Some(&pad_id)))
+        .and_then(|pad| pad.get_mut("stems")?.as_array_mut())
+        .and_then(|stems| {
+            stems
+                .iter_mut()
+                .find(|s| s.get("sound").and_then(|s| s.get("id")).and_then(|id| id.as_str()) == Some(&stem_id))
+        })
+        .and_then(|stem| stem.as_object_mut())
+        .ok_or_else(|| CommandError::SoundNotFound(stem_id.clone()))?;
+    stem.insert("volume".into(), volume.into());
+    store.set(SOUNDBOARD_KEY, pads);
+    state.auto_save.mark_dirty(SaveTarget::Soundboard);
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetSoundVolume { id: stem_id, volume })
+        .map_err(CommandError::EngineError)
+}
+
+/// Set the volume of a soundboard sound (0.0 - 2.0), live and saved
+///
+/// Changes the sound mid-playback, ramped over one buffer, and applies to
+/// every later play.
+#[tauri::command]
+pub async fn set_sound_volume(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    sound_id: String,
+    volume: f32,
+) -> Result<(), CommandError> {
+    if !(0.0..=2.0).contains(&volume) {
+        return Err(CommandError::InvalidArgument("Sound volume must be between 0 and 2".into()));
+    }
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let sound = soundboard_sound_mut(&mut pads, &sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.clone()))?;
+    if (volume - 1.0).abs() < f32::EPSILON {
+        sound.remove("volume");
+    } else {
+        sound.insert("volume".into(), volume.into());
+    }
+    store.set(SOUNDBOARD_KEY, pads);
+    state.auto_save.mark_dirty(SaveTarget::Soundboard);
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetSoundVolume { id: sound_id, volume })
+        .map_err(CommandError::EngineError)
+}
+
+/// Set the stereo width of a sound, e.g. to widen background music
+///
+/// Applies to the sound if playing and to every later play of the same id.
+/// Mono outputs are left untouched.
+#[tauri::command]
+pub async fn set_sound_width(
+    state: State<'_, AppState>,
+    id: String,
+    width: f32,
+) -> Result<(), CommandError> {
+    use crate::dsp::MAX_STEREO_WIDTH;
+
+    if !(0.0..=MAX_STEREO_WIDTH).contains(&width) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Width must be between 0 and {}",
+            MAX_STEREO_WIDTH
+        )));
+    }
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetSoundWidth { id, width })
+        .map_err(CommandError::EngineError)
+}
+
+/// Set the effect inserts of a sound, e.g. vocal reduction for karaoke
+///
+/// Like the width, applies to the sound if playing and to every later play
+/// of the same id; an empty list removes every insert. Saved on the pad the
+/// sound belongs to, if it is on the soundboard.
+#[tauri::command]
+pub async fn set_sound_inserts(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    inserts: Vec<SoundInsert>,
+) -> Result<(), CommandError> {
+    if inserts.iter().any(|insert| !insert.is_valid()) {
+        return Err(CommandError::InvalidArgument(
+            "Insert strength must be between 0 and 1".to_string(),
+        ));
+    }
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let pad = pads
+        .as_array_mut()
+        .and_then(|pads| pads.iter_mut().find(|pad| pad_has_sound(pad, &id)))
+        .and_then(|pad| pad.as_object_mut());
+    if let Some(pad) = pad {
+        if inserts.is_empty() {
+            pad.remove("inserts");
+        } else {
+            let value = serde_json::to_value(&inserts).map_err(|e| CommandError::Internal(e.to_string()))?;
+            pad.insert("inserts".into(), value);
+        }
+        store.set(SOUNDBOARD_KEY, pads);
+        state.auto_save.mark_dirty(SaveTarget::Soundboard);
+    }
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetSoundInserts { id, inserts })
+        .map_err(CommandError::EngineError)
+}
+
+/// Preview a sound file on a specific output device
+#[tauri::command]
+pub async fn preview_sound(
+    state: State<'_, AppState>,
+    path: String,
+    device_name: String,
+    pad_id: String,
+) -> Result<(), CommandError> {
+    use crate::application::preview_engine::PreviewCommand;
+
+    state.telemetry.record("preview_sound");
+    let ceiling_db = state.settings.read().await.headphone_limiter.effective_ceiling_db();
+    let preview = state.preview_engine.lock().await;
+    if let Some(ref engine) = *preview {
+        engine.send_command(PreviewCommand::Play {
+            path,
+            device_name,
+            pad_id,
+            ceiling_db,
+        })
+    } else {
+        Err(CommandError::EngineError("Preview engine not initialized".into()))
+    }
+}
+
+/// Stop the currently playing preview
+#[tauri::command]
+pub async fn stop_preview(state: State<'_, AppState>) -> Result<(), CommandError> {
+    use crate::application::preview_engine::PreviewCommand;
+
+    let preview = state.preview_engine.lock().await;
+    if let Some(ref engine) = *preview {
+        engine.send_command(PreviewCommand::Stop)
+    } else {
+        Err(CommandError::EngineError("Preview engine not initialized".into()))
+    }
+}
+
+/// Get the currently previewing pad ID
+#[tauri::command]
+pub async fn get_preview_state(state: State<'_, AppState>) -> Result<Option<String>, CommandError> {
+    let preview = state.preview_engine.lock().await;
+    Ok(preview.as_ref().and_then(|e| e.current_pad_id()))
+}
+
+/// Stop a playing sound
+///
+/// Fades out over `fade_ms`, or the stop fade from the settings when unset.
+#[tauri::command]
+pub async fn stop_sound(
+    state: State<'_, AppState>,
+    id: String,
+    fade_ms: Option<u32>,
+) -> Result<(), CommandError> {
+    let engine = &state.audio_engine;
+    engine
+        .send_command(AudioEngineCommand::StopSound { id, fade_ms })
+        .map_err(CommandError::EngineError)?;
+
+    Ok(())
+}
+
+/// Pause a playing sound, keeping its position
+#[tauri::command]
+pub async fn pause_sound(state: State<'_, AppState>, id: String) -> Result<(), CommandError> {
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::PauseSound { id })
+        .map_err(CommandError::EngineError)
+}
+
+/// Resume a paused sound from where it was paused
+#[tauri::command]
+pub async fn resume_sound(state: State<'_, AppState>, id: String) -> Result<(), CommandError> {
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::ResumeSound { id })
+        .map_err(CommandError::EngineError)
+}
+
+/// Jump a playing sound to `position_secs` from its start
+///
+/// Positions past the end finish the sound.
+#[tauri::command]
+pub async fn seek_sound(
+    state: State<'_, AppState>,
+    id: String,
+    position_secs: f64,
+) -> Result<(), CommandError> {
+    if !position_secs.is_finite() || position_secs < 0.0 {
+        return Err(CommandError::InvalidArgument("Position must be a positive number of seconds".into()));
+    }
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SeekSound { id, position_secs })
+        .map_err(CommandError::EngineError)
+}
+
+/// Stop every playing sound
+///
+/// Fades out over `fade_ms`, or the stop fade from the settings when unset.
+#[tauri::command]
+pub async fn stop_all_sounds(
+    state: State<'_, AppState>,
+    fade_ms: Option<u32>,
+) -> Result<(), CommandError> {
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::StopAllSounds { fade_ms })
+        .map_err(CommandError::EngineError)
+}
+
+/// Get the soundboard playback settings
+#[tauri::command]
+pub async fn get_playback_settings(state: State<'_, AppState>) -> Result<PlaybackSettings, CommandError> {
+    Ok(state.settings.read().await.playback)
+}
+
+/// Set the soundboard playback settings, e.g. the default stop fade or
+/// auto-level
+#[tauri::command]
+pub async fn set_playback_settings(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    playback: PlaybackSettings,
+) -> Result<(), CommandError> {
+    if playback.stop_fade_ms > PlaybackSettings::MAX_STOP_FADE_MS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Stop fade must be at most {} ms",
+            PlaybackSettings::MAX_STOP_FADE_MS
+        )));
+    }
+    if playback.output_ramp_ms > PlaybackSettings::MAX_OUTPUT_RAMP_MS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Output ramp must be at most {} ms",
+            PlaybackSettings::MAX_OUTPUT_RAMP_MS
+        )));
+    }
+    if !PlaybackSettings::AUTO_LEVEL_TARGETS.contains(&playback.auto_level_target_lufs) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Auto-level target must be between {} and {} LUFS",
+            PlaybackSettings::AUTO_LEVEL_TARGETS.start(),
+            PlaybackSettings::AUTO_LEVEL_TARGETS.end()
+        )));
+    }
+    if !PlaybackSettings::SOUND_CACHE_MB.contains(&playback.sound_cache_mb) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Sound cache must be between {} and {} MB",
+            PlaybackSettings::SOUND_CACHE_MB.start(),
+            PlaybackSettings::SOUND_CACHE_MB.end()
+        )));
+    }
+
+    state.settings.write().await.playback = playback;
+    persist_settings(&app, &state).await?;
+    state.decoder.set_cache_limit(playback.sound_cache_mb);
+
+    let engine = &state.audio_engine;
+    engine
+        .send_command(AudioEngineCommand::SetStopFade(playback.stop_fade_ms))
+        .map_err(CommandError::EngineError)?;
+    engine
+        .send_command(AudioEngineCommand::SetOutputRamp(playback.output_ramp_ms))
+        .map_err(CommandError::EngineError)
+}
+
+/// Set microphone volume (0.0 - 2.0), remembered for the current input
+/// device
+#[tauri::command]
+pub async fn set_mic_volume(
+    state: State<'_, AppState>,
+    volume: f32,
+) -> Result<(), CommandError> {
+    let volume = volume.clamp(0.0, MicProfile::MAX_VOLUME);
+    {
+        let mut settings = state.settings.write().await;
+        let device = settings.audio.input_device().to_string();
+        settings.mic_profiles.for_device_mut(&device).volume = volume;
+    }
+    state.auto_save.mark_dirty(SaveTarget::Settings);
+
+    let engine = &state.audio_engine;
+    engine
+        .send_command(AudioEngineCommand::SetMicVolume(volume))
+        .map_err(CommandError::EngineError)?;
+
+    Ok(())
+}
+
+/// Mute/unmute microphone
+#[tauri::command]
+pub async fn set_mic_muted(
+    state: State<'_, AppState>,
+    muted: bool,
+) -> Result<(), CommandError> {
+    let engine = &state.audio_engine;
+    engine
+        .send_command(AudioEngineCommand::SetMicMuted(muted))
+        .map_err(CommandError::EngineError)?;
+    state.telemetry.record("set_mic_muted");
+    state.tally.set_mic_muted(muted);
+
+    // Keep the OS endpoint (and hardware mute LEDs) in step
+    let device = state.settings.read().await.audio.input_device().to_string();
+    if let Err(e) = state.mic_mute_sync.apply(&device, muted) {
+        tracing::warn!(error = %e, device = %device, "Failed to sync system mic mute");
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Soundboard Persistence Commands
+// ============================================================================
+
+const SOUNDBOARD_STORE: &str = "soundboard.json";
+const SOUNDBOARD_KEY: &str = "pads";
+
+/// Save soundboard pads to persistent storage
+///
+/// OBS passwords given with pad actions go to the credential store and
+/// are left out of the saved pads.
+#[tauri::command]
+pub async fn save_soundboard(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    mut pads: serde_json::Value,
+) -> Result<(), CommandError> {
+    store_obs_passwords(&state, &mut pads)?;
+
+    // Commands may have written fields since the frontend loaded its pads
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let stored = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    store.set(SOUNDBOARD_KEY, merge_saved_pads(&stored, pads));
+    store.save()?;
+    reload_hotkeys(&app, &state)?;
+    if prune_global_hotkeys(&app, &state).await? {
+        apply_global_hotkeys(&app, &state).await;
+    }
+    tracing::debug!("Soundboard state saved");
+    Ok(())
+}
+
+/// Move the OBS passwords of pad actions into the credential store
+fn store_obs_passwords(state: &AppState, pads: &mut serde_json::Value) -> Result<(), CommandError> {
+    let actions = pads
+        .as_array_mut()
+        .into_iter()
+        .flatten()
+        .filter_map(|pad| pad.get_mut("actions")?.as_array_mut())
+        .flatten()
+        .filter_map(|action| action.as_object_mut())
+        .filter(|action| action.get("type").and_then(|t| t.as_str()) == Some("obsScene"));
+    for action in actions {
+        let Some(password) = action.remove("password") else {
+            continue;
+        };
+        let Some(password) = password.as_str().filter(|password| !password.is_empty()) else {
+            continue;
+        };
+        let name = obs_secret_name(action.get("url").and_then(|url| url.as_str()));
+        state
+            .secrets
+            .set(&name, password)
+            .map_err(|e| CommandError::StorageError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Load soundboard pads from persistent storage
+#[tauri::command]
+pub async fn load_soundboard(
+    app: tauri::AppHandle,
+) -> Result<Option<serde_json::Value>, CommandError> {
+    let store = app.store(SOUNDBOARD_STORE)?;
+    #[allow(clippy::map_clone)]
+    let pads = store.get(SOUNDBOARD_KEY).map(|v| v.clone());
+    tracing::debug!("Soundboard state loaded: {:?}", pads.is_some());
+    Ok(pads)
+}
+
+/// Pick the sound a pad plays on this trigger
+///
+/// Pads with `variants` rotate through them (round-robin or random, by
+/// weight); other pads always play their `sound`.
+#[tauri::command]
+pub async fn pick_pad_variant(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+) -> Result<Option<serde_json::Value>, CommandError> {
+    use crate::domain::VariantMode;
+
+    check_pad_armed(&state, &pad_id).await?;
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let pad = pads
+        .as_array()
+        .and_then(|pads| pads.iter().find(|p| p.get("id").and_then(|id| id.as_str()) == Some(&pad_id)))
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown pad: {}", pad_id)))?;
+
+    let variants: Vec<&serde_json::Value> = pad
+        .get("variants")
+        .and_then(|v| v.as_array())
+        .map(|v| v.iter().filter(|v| v.get("sound").is_some()).collect())
+        .unwrap_or_default();
+    if variants.is_empty() {
+        return Ok(pad.get("sound").filter(|s| !s.is_null()).cloned());
+    }
+
+    let weights: Vec<f32> = variants
+        .iter()
+        .map(|v| v.get("weight").and_then(|w| w.as_f64()).unwrap_or(1.0) as f32)
+        .collect();
+    let mode: VariantMode = pad
+        .get("variantMode")
+        .and_then(|m| serde_json::from_value(m.clone()).ok())
+        .unwrap_or_default();
+
+    let index = state
+        .pad_variants
+        .lock()
+        .await
+        .pick(&pad_id, &weights, mode, fastrand::f64());
+    Ok(index.and_then(|i| variants[i].get("sound").cloned()))
+}
+
+/// Run the non-audio actions of a pad, in order
+///
+/// A pad with actions and no sound only runs its actions; one with both
+/// plays the sound as well (the frontend starts it). Every action runs
+/// even if an earlier one failed or is invalid; the first failure is
+/// returned.
+#[tauri::command]
+pub async fn trigger_pad_actions(
+    app: tauri::AppHandle,
+    pad_id: String,
+    source: Option<PlaySource>,
+) -> Result<(), CommandError> {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    check_pad_armed(&state, &pad_id).await?;
+    let actions = pad_actions(&saved_pad(&app, &pad_id)?);
+
+    let mut first_error = None;
+    for action in actions {
+        let result = match action {
+            Err(e) => Err(CommandError::from(e)),
+            Ok(PadAction::App { command }) => run_app_command(&app, command, source.unwrap_or_default()).await,
+            Ok(external) => {
+                let secrets = Arc::clone(&state.secrets);
+                tauri::async_runtime::spawn_blocking(move || run_external_action(&external, secrets.as_ref()))
+                    .await
+                    .map_err(|e| CommandError::Internal(e.to_string()))
+                    .and_then(|result| result.map_err(CommandError::from))
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!(pad = %pad_id, error = %e, "Pad action failed");
+            first_error.get_or_insert(e);
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// A pad of the saved soundboard
+fn saved_pad(app: &tauri::AppHandle, pad_id: &str) -> Result<serde_json::Value, CommandError> {
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    pads.as_array()
+        .and_then(|pads| pads.iter().find(|p| p.get("id").and_then(|id| id.as_str()) == Some(pad_id)))
+        .cloned()
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown pad: {}", pad_id)))
+}
+
+// ============================================================================
+// Timer Pad Commands
+// ============================================================================
+
+/// Progress of a running timer, emitted each second as "timer-progress"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerProgress {
+    pub pad_id: String,
+    pub elapsed_secs: u32,
+    pub remaining_secs: u32,
+    pub duration_secs: u32,
+    /// Cycles an interval timer has completed
+    pub cycle: u32,
+    /// Cue of this second, whether or not it has a sound
+    pub cue: Option<TimerCue>,
+}
+
+/// End of a timer, emitted as "timer-ended"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerEnded {
+    pub pad_id: String,
+    pub cancelled: bool,
+}
+
+/// Start the timer of a timer pad, restarting it if it runs
+///
+/// Progress is emitted each second as "timer-progress" and the end as
+/// "timer-ended". Returns the pad's timer settings.
+#[tauri::command]
+pub async fn start_timer(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+) -> Result<TimerConfig, CommandError> {
+    let config: TimerConfig = saved_pad(&app, &pad_id)?
+        .get("timer")
+        .filter(|timer| !timer.is_null())
+        .map(|timer| serde_json::from_value(timer.clone()))
+        .transpose()
+        .map_err(|e| CommandError::InvalidArgument(format!("Pad {}: {}", pad_id, e)))?
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Pad {} has no timer", pad_id)))?;
+    if config.duration_secs == 0 {
+        return Err(CommandError::InvalidArgument("Timer duration must be at least a second".into()));
+    }
+
+    let sounds: Vec<&String> = [&config.tick_sound, &config.halfway_sound, &config.end_sound]
+        .into_iter()
+        .flatten()
+        .collect();
+    for sound_id in &sounds {
+        soundboard_sound_path(&app, sound_id).ok_or_else(|| CommandError::SoundNotFound((*sound_id).clone()))?;
+    }
+    if config.route == CueRoute::Monitor
+        && !sounds.is_empty()
+        && state.settings.read().await.audio.preview_device_id.is_none()
+    {
+        return Err(CommandError::NoDeviceSelected("preview".into()));
+    }
+
+    let cancelled = state.timers.start(&pad_id);
+    tauri::async_runtime::spawn(run_timer(app.clone(), pad_id.clone(), config.clone(), cancelled));
+    tracing::info!(pad = %pad_id, duration_secs = config.duration_secs, repeat = config.repeat, "Timer started");
+    Ok(config)
+}
+
+/// Cancel the timer of a pad; false if it wasn't running
+#[tauri::command]
+pub async fn cancel_timer(state: State<'_, AppState>, pad_id: String) -> Result<bool, CommandError> {
+    let cancelled = state.timers.cancel(&pad_id);
+    if cancelled {
+        tracing::info!(pad = %pad_id, "Timer cancelled");
+    }
+    Ok(cancelled)
+}
+
+/// Get the pads whose timer is running
+#[tauri::command]
+pub async fn get_running_timers(state: State<'_, AppState>) -> Result<Vec<String>, CommandError> {
+    Ok(state.timers.running())
+}
+
+/// Count a timer down, playing its cues, until it ends or is cancelled
+async fn run_timer(app: tauri::AppHandle, pad_id: String, config: TimerConfig, cancelled: Arc<AtomicBool>) {
+    use tauri::{Emitter, Manager};
+
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(1));
+    ticker.tick().await;
+    let mut cycle = 0;
+    'cycles: loop {
+        for elapsed_secs in 1..=config.duration_secs {
+            ticker.tick().await;
+            if cancelled.load(Ordering::Relaxed) {
+                break 'cycles;
+            }
+
+            let cue = config.cue_at(elapsed_secs);
+            if let Some(sound_id) = cue.and_then(|cue| config.cue_sound(cue)) {
+                if let Err(e) = play_timer_cue(&app, &pad_id, sound_id, config.route).await {
+                    tracing::warn!(pad = %pad_id, sound = %sound_id, error = %e, "Timer cue failed");
+                }
+            }
+            let _ = app.emit("timer-progress", TimerProgress {
+                pad_id: pad_id.clone(),
+                elapsed_secs,
+                remaining_secs: config.duration_secs - elapsed_secs,
+                duration_secs: config.duration_secs,
+                cycle,
+                cue,
+            });
+        }
+        if !config.repeat {
+            break;
+        }
+        cycle += 1;
+    }
+
+    if app.state::<AppState>().timers.finish(&pad_id, &cancelled) {
+        let _ = app.emit("timer-ended", TimerEnded {
+            pad_id,
+            cancelled: cancelled.load(Ordering::Relaxed),
+        });
+    }
+}
+
+/// Play a cue sound on the preview device or into the mix
+///
+/// Into the mix, a cue plays like a press of its pad, with the pad's trim,
+/// gain, auto-level and speed.
+async fn play_timer_cue(
+    app: &tauri::AppHandle,
+    pad_id: &str,
+    sound_id: &str,
+    route: CueRoute,
+) -> Result<(), CommandError> {
+    use crate::application::preview_engine::PreviewCommand;
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    let path = soundboard_sound_path(app, sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.into()))?;
+    match route {
+        CueRoute::Mic => {
+            let volume = soundboard_sound_volume(app, sound_id);
+            decode_and_play(app, &state, sound_id, &path, volume, false).await.map(|_| ())
+        }
+        CueRoute::Monitor => {
+            let settings = state.settings.read().await;
+            let device_name = settings
+                .audio
+                .preview_device_id
+                .clone()
+                .ok_or_else(|| CommandError::NoDeviceSelected("preview".into()))?;
+            let ceiling_db = settings.headphone_limiter.effective_ceiling_db();
+            drop(settings);
+
+            let preview = state.preview_engine.lock().await;
+            let engine = preview
+                .as_ref()
+                .ok_or_else(|| CommandError::EngineError("Preview engine not initialized".into()))?;
+            engine
+                .send_command(PreviewCommand::Play {
+                    path,
+                    device_name,
+                    pad_id: pad_id.to_string(),
+                    ceiling_db,
+                })
+                .map_err(CommandError::EngineError)
+        }
+    }
+}
+
+// ============================================================================
+// Scene Commands
+// ============================================================================
+
+/// Saved scenes, in the settings file
+const SCENES_KEY: &str = "scenes";
+/// Time between volume steps of a scene crossfade
+const SCENE_FADE_STEP_MS: u32 = 20;
+
+fn saved_scenes(state: &AppState) -> Vec<Scene> {
+    state
+        .settings_store
+        .get(SCENES_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+async fn write_scenes(state: &AppState, scenes: &[Scene]) -> Result<(), CommandError> {
+    write_setting(state, SCENES_KEY, serde_json::to_value(scenes).map_err(|e| e.to_string())?).await
+}
+
+/// List the saved scenes
+#[tauri::command]
+pub async fn get_scenes(state: State<'_, AppState>) -> Result<Vec<Scene>, CommandError> {
+    Ok(saved_scenes(&state))
+}
+
+/// Save the current audio state as a scene, replacing the scene `id`
+///
+/// Pad states come from the caller; channel levels and the voice changer
+/// are captured as they are now.
+#[tauri::command]
+pub async fn save_scene(
+    state: State<'_, AppState>,
+    id: Option<String>,
+    name: String,
+    pads: HashMap<String, ScenePad>,
+) -> Result<Scene, CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::InvalidArgument("Scene name is empty".into()));
+    }
+
+    let channels = state
+        .mixer_config
+        .read()
+        .await
+        .channels
+        .iter()
+        .map(|channel| {
+            let level = SceneChannel {
+                volume: channel.volume(),
+                muted: channel.is_muted() && !channel.is_momentarily_muted(),
+            };
+            (channel.id().to_string(), level)
+        })
+        .collect();
+    let scene = Scene {
+        id: id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name,
+        pads,
+        channels,
+        voice_changer: Some(state.settings.read().await.voice_changer),
+    };
+    if !scene.is_valid() {
+        return Err(CommandError::InvalidArgument("Scene volumes must be between 0 and 2".into()));
+    }
+
+    let mut scenes = saved_scenes(&state);
+    match scenes.iter_mut().find(|saved| saved.id == scene.id) {
+        Some(saved) => *saved = scene.clone(),
+        None => scenes.push(scene.clone()),
+    }
+    write_scenes(&state, &scenes).await?;
+
+    tracing::info!(scene = %scene.name, pads = scene.pads.len(), "Scene saved");
+    Ok(scene)
+}
+
+/// Delete a saved scene
+#[tauri::command]
+pub async fn delete_scene(state: State<'_, AppState>, id: String) -> Result<(), CommandError> {
+    let mut scenes = saved_scenes(&state);
+    let count = scenes.len();
+    scenes.retain(|scene| scene.id != id);
+    if scenes.len() == count {
+        return Err(CommandError::InvalidArgument(format!("Unknown scene: {}", id)));
+    }
+    write_scenes(&state, &scenes).await?;
+
+    let mut active = state.active_scene.lock().await;
+    if active.scene_id.as_deref() == Some(id.as_str()) {
+        active.scene_id = None;
+    }
+    Ok(())
+}
+
+/// Get the id of the scene last recalled
+#[tauri::command]
+pub async fn get_active_scene(state: State<'_, AppState>) -> Result<Option<String>, CommandError> {
+    Ok(state.active_scene.lock().await.scene_id.clone())
+}
+
+/// Recall a scene, crossfading pad and channel volumes over `crossfade_ms`
+///
+/// Mutes and the voice changer switch halfway through. Pads the scene
+/// disarms refuse triggers from then on. Emits "scene-recalled" with the
+/// scene right away, so the frontend shows which pads are armed.
+#[tauri::command]
+pub async fn recall_scene(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    crossfade_ms: Option<u32>,
+) -> Result<Scene, CommandError> {
+    use tauri::Emitter;
+
+    let scene = saved_scenes(&state)
+        .into_iter()
+        .find(|scene| scene.id == id)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown scene: {}", id)))?;
+
+    let generation = state.active_scene.lock().await.recall(&scene);
+    let _ = app.emit("scene-recalled", &scene);
+    tauri::async_runtime::spawn(crossfade_to_scene(app.clone(), scene.clone(), crossfade_ms.unwrap_or(0), generation));
+
+    tracing::info!(scene = %scene.name, crossfade_ms, "Scene recalled");
+    Ok(scene)
+}
+
+/// Fade to the volumes of `scene`, unless another recall takes over
+///
+/// Pad sounds ramp in the engine, from wherever their scene gain is;
+/// channel levels are stepped here.
+async fn crossfade_to_scene(app: tauri::AppHandle, scene: Scene, crossfade_ms: u32, generation: u64) {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    for (pad_id, pad) in &scene.pads {
+        let Ok(saved) = saved_pad(&app, pad_id) else {
+            continue;
+        };
+        let sounds = saved
+            .get("sound")
+            .into_iter()
+            .chain(nested_sounds(&saved, "variants"))
+            .chain(nested_sounds(&saved, "stems"));
+        for sound_id in sounds.filter_map(|sound| sound.get("id")?.as_str()) {
+            let _ = state.audio_engine.send_command(AudioEngineCommand::SetSceneGain {
+                id: sound_id.to_string(),
+                gain: pad.volume,
+                fade_ms: crossfade_ms,
+            });
+        }
+    }
+
+    let from_channels: HashMap<String, f32> = state
+        .mixer_config
+        .read()
+        .await
+        .channels
+        .iter()
+        .map(|channel| (channel.id().to_string(), channel.volume()))
+        .collect();
+    let to_channels: HashMap<String, f32> = scene
+        .channels
+        .iter()
+        .filter(|(id, _)| from_channels.contains_key(*id))
+        .map(|(id, channel)| (id.clone(), channel.volume))
+        .collect();
+
+    let steps = (crossfade_ms / SCENE_FADE_STEP_MS).max(1);
+    let mut ticker = tokio::time::interval(std::time::Duration::from_millis(SCENE_FADE_STEP_MS as u64));
+    for step in 1..=steps {
+        ticker.tick().await;
+        if state.active_scene.lock().await.generation != generation {
+            return;
+        }
+        let t = step as f32 / steps as f32;
+
+        let halfway = step == steps.div_ceil(2);
+        let mut changed = Vec::new();
+        {
+            let mut config = state.mixer_config.write().await;
+            for (channel_id, volume) in crossfade_volumes(&from_channels, &to_channels, t) {
+                if let Some(channel) = config.get_channel_mut(&channel_id) {
+                    channel.set_volume(volume);
+                    if halfway {
+                        channel.set_muted(scene.channels[&channel_id].muted);
+                    }
+                    changed.push(channel.clone());
+                }
+            }
+        }
+        for channel in &changed {
+            if let Err(e) = apply_channel_gain(&state, channel).await {
+                tracing::warn!(channel = %channel.id(), error = %e, "Scene level not applied");
+            }
+        }
+
+        if halfway {
+            if let Some(voice_changer) = scene.voice_changer {
+                state.settings.write().await.voice_changer = voice_changer;
+                let _ = state
+                    .audio_engine
+                    .send_command(AudioEngineCommand::SetVoiceChanger(Some(voice_changer)));
+                state.auto_save.mark_dirty(SaveTarget::Settings);
+            }
+        }
+    }
+
+    state.auto_save.mark_dirty(SaveTarget::Mixer);
+}
+
+// ============================================================================
+// Start Jingle Commands
+// ============================================================================
+
+/// Get the jingle played when mixing starts
+#[tauri::command]
+pub async fn get_start_jingle(state: State<'_, AppState>) -> Result<StartJingleSettings, CommandError> {
+    Ok(state.settings.read().await.start_jingle.clone())
+}
+
+/// Set the sound played, and the scene recalled, when mixing starts
+#[tauri::command]
+pub async fn set_start_jingle(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    start_jingle: StartJingleSettings,
+) -> Result<(), CommandError> {
+    if start_jingle.enabled && start_jingle.sound_id.is_none() && start_jingle.scene_id.is_none() {
+        return Err(CommandError::InvalidArgument("No jingle sound or scene given".into()));
+    }
+    if let Some(sound_id) = &start_jingle.sound_id {
+        if soundboard_sound(&app, sound_id).is_none() {
+            return Err(CommandError::SoundNotFound(sound_id.clone()));
+        }
+    }
+    if let Some(scene_id) = &start_jingle.scene_id {
+        if !saved_scenes(&state).iter().any(|scene| &scene.id == scene_id) {
+            return Err(CommandError::InvalidArgument(format!("Unknown scene: {}", scene_id)));
+        }
+    }
+
+    tracing::info!(enabled = start_jingle.enabled, sound = ?start_jingle.sound_id, scene = ?start_jingle.scene_id, "Start jingle set");
+    state.settings.write().await.start_jingle = start_jingle;
+    persist_settings(&app, &state).await
+}
+
+/// Run a built-in automation rule, off the engine-events thread
+pub fn run_automation(app: &tauri::AppHandle, rule: AutomationRule) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match rule {
+            AutomationRule::StartJingle => play_start_jingle(&app).await,
+        };
+        if let Err(e) = result {
+            tracing::warn!(?rule, error = %e, "Automation failed");
+        }
+    });
+}
+
+/// Recall the jingle's scene, then play its sound
+async fn play_start_jingle(app: &tauri::AppHandle) -> Result<(), CommandError> {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    let jingle = state.settings.read().await.start_jingle.clone();
+    if !jingle.enabled {
+        return Ok(());
+    }
+    if let Some(scene_id) = jingle.scene_id {
+        recall_scene(app.clone(), app.state::<AppState>(), scene_id, None).await?;
+    }
+    if let Some(sound_id) = jingle.sound_id {
+        tracing::info!(sound = %sound_id, "Playing start jingle");
+        run_app_command(app, HotFolderCommand::Play { sound_id }, PlaySource::default()).await?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Sound Pack Commands
+// ============================================================================
+
+/// DTO for a sound installed from a pack
+#[derive(Debug, Clone, Serialize)]
+pub struct PackSoundDto {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub duration: f64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub hotkey: Option<String>,
+    pub color: Option<String>,
+    pub gain_db: Option<f32>,
+    pub license: Option<String>,
+    pub source_url: Option<String>,
+    pub attribution: Option<String>,
+}
+
+/// DTO for an installed sound pack
+#[derive(Debug, Clone, Serialize)]
+pub struct SoundPackDto {
+    pub name: String,
+    pub author: Option<String>,
+    pub sounds: Vec<PackSoundDto>,
+    /// Entries that were missing or could not be decoded
+    pub skipped: Vec<String>,
+}
+
+/// Install a sound pack (folder, `.zip` or Soundpad `.spl`)
+///
+/// Files are copied into the app's `packs` folder, so the pack keeps
+/// working after the download is deleted. The frontend turns the returned
+/// sounds into pads.
+#[tauri::command]
+pub async fn import_sound_pack(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<SoundPackDto, CommandError> {
+    use crate::application::sound_pack::install_sound_pack;
+    use tauri::Manager;
+
+    let packs_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::StorageError(e.to_string()))?
+        .join("packs");
+
+    let source = std::path::PathBuf::from(&path);
+    let installed = tauri::async_runtime::spawn_blocking(move || install_sound_pack(&source, &packs_dir))
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))??;
+
+    let mut skipped = installed.skipped;
+    let mut sounds = Vec::with_capacity(installed.sounds.len());
+    for sound in installed.sounds {
+        let decoded = match state.decoder.decode(&sound.path) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                tracing::warn!(path = %sound.path.display(), "Skipping pack sound: {}", e);
+                skipped.push(sound.name);
+                continue;
+            }
+        };
+
+        sounds.push(PackSoundDto {
+            id: format!("sound_{}", &uuid::Uuid::new_v4().to_string().replace("-", "")[..8]),
+            name: sound.name,
+            path: sound.path.to_string_lossy().to_string(),
+            duration: decoded.metadata.duration.as_secs_f64(),
+            sample_rate: decoded.metadata.audio_format.sample_rate,
+            channels: decoded.metadata.audio_format.channels,
+            hotkey: sound.hotkey,
+            color: sound.color,
+            gain_db: sound.gain_db,
+            license: sound.attribution.license,
+            source_url: sound.attribution.source_url,
+            attribution: sound.attribution.attribution,
+        });
+    }
+
+    tracing::info!(
+        pack = %installed.name,
+        sounds = sounds.len(),
+        skipped = skipped.len(),
+        "Sound pack imported"
+    );
+    state.telemetry.record("import_sound_pack");
+
+    Ok(SoundPackDto {
+        name: installed.name,
+        author: installed.author,
+        sounds,
+        skipped,
+    })
+}
+
+// ============================================================================
+// Play Log Commands
+// ============================================================================
+
+/// Folder holding one play log file per session
+/// Plays of every logged session
+fn logged_plays(state: &AppState, log_dir: &std::path::Path) -> Vec<PlayLogEntry> {
+    state
+        .play_log
+        .sessions(log_dir)
+        .iter()
+        .flat_map(|session| state.play_log.entries(log_dir, Some(session)).unwrap_or_default())
+        .collect()
+}
+
+fn play_log_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, CommandError> {
+    use tauri::Manager;
+
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::StorageError(e.to_string()))?
+        .join("play_log"))
+}
+
+/// Get the sounds played in a session, the running one by default
+#[tauri::command]
+pub async fn get_play_log(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session: Option<String>,
+) -> Result<Vec<PlayLogEntry>, CommandError> {
+    state
+        .play_log
+        .entries(&play_log_dir(&app)?, session.as_deref())
+        .map_err(|e| CommandError::StorageError(e.to_string()))
+}
+
+/// List logged sessions, newest (the running one) first
+#[tauri::command]
+pub async fn list_play_sessions(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, CommandError> {
+    Ok(state.play_log.sessions(&play_log_dir(&app)?))
+}
+
+/// Export a session's play log as CSV, for lining drops up in an editor
+#[tauri::command]
+pub async fn export_play_log_csv(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session: Option<String>,
+    path: String,
+) -> Result<usize, CommandError> {
+    use crate::domain::play_log_csv;
+
+    let entries = state
+        .play_log
+        .entries(&play_log_dir(&app)?, session.as_deref())
+        .map_err(|e| CommandError::StorageError(e.to_string()))?;
+    std::fs::write(&path, play_log_csv(&entries)).map_err(|e| CommandError::StorageError(e.to_string()))?;
+
+    tracing::info!(entries = entries.len(), path = %path, "Play log exported");
+    Ok(entries.len())
+}
+
+/// Get the summaries of past mixing sessions, newest first
+///
+/// A summary is added each time mixing stops, when it is also emitted as
+/// `session-summary`.
+#[tauri::command]
+pub async fn get_session_history(app: tauri::AppHandle, limit: Option<usize>) -> Result<Vec<SessionSummary>, CommandError> {
+    let file = session_history_file(&app).ok_or_else(|| CommandError::StorageError("No app data folder".into()))?;
+    let mut history = read_history(&file).map_err(|e| CommandError::StorageError(e.to_string()))?;
+    if let Some(limit) = limit {
+        history.truncate(limit);
+    }
+    Ok(history)
+}
+
+// ============================================================================
+// Attribution Commands
+// ============================================================================
+
+/// Write the credits of the board's sounds to a text file
+///
+/// Lists every sound, variant and stem with a license, source URL or
+/// attribution, once each. Returns the number of sounds credited.
+#[tauri::command]
+pub async fn export_attributions(app: tauri::AppHandle, path: String) -> Result<usize, CommandError> {
+    use crate::domain::{credits_text, unique_credits, SoundAttribution, SoundCredit};
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let sounds = pads
+        .as_array()
+        .map(|pads| pads.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|pad| {
+            pad.get("sound")
+                .into_iter()
+                .chain(nested_sounds(pad, "variants"))
+                .chain(nested_sounds(pad, "stems"))
+        })
+        .filter_map(|sound| {
+            Some(SoundCredit {
+                name: sound.get("name")?.as_str()?.to_string(),
+                attribution: serde_json::from_value::<SoundAttribution>(sound.clone()).ok()?,
+            })
+        });
+    let credits = unique_credits(sounds);
+    std::fs::write(&path, credits_text(&credits)).map_err(|e| CommandError::StorageError(e.to_string()))?;
+
+    tracing::info!(sounds = credits.len(), path = %path, "Attributions exported");
+    Ok(credits.len())
+}
+
+// ============================================================================
+// Library Cleanup Commands
+// ============================================================================
+
+/// Propose sounds to archive or delete, with the disk space they take
+///
+/// Combines play counts and last-played dates from every logged session
+/// with each file's size and age. Archived sounds are left out.
+#[tauri::command]
+pub async fn suggest_cleanup(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    policy: Option<CleanupPolicy>,
+) -> Result<CleanupReport, CommandError> {
+    use crate::domain::{cleanup_report, play_stats, SoundUsage};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let log_dir = play_log_dir(&app)?;
+    let stats = play_stats(&logged_plays(&state, &log_dir));
+
+    let unix_millis = |time: SystemTime| time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as u64);
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let board_sounds = pads.as_array().map(|pads| pads.as_slice()).unwrap_or_default().iter().flat_map(|pad| {
+        pad.get("sound")
+            .into_iter()
+            .chain(nested_sounds(pad, "variants"))
+            .chain(nested_sounds(pad, "stems"))
+    });
+    let mut sounds: Vec<SoundUsage> = Vec::new();
+    // One spelling per file, so a shared file is only reclaimed once
+    let mut spellings: HashMap<std::path::PathBuf, String> = HashMap::new();
+    for sound in board_sounds {
+        let field = |key: &str| sound.get(key).and_then(|v| v.as_str()).map(String::from);
+        let (Some(sound_id), Some(path)) = (field("id"), field("path")) else {
+            continue;
+        };
+        let path = spellings.entry(file_key(&path)).or_insert(path).clone();
+        if sound.get("archived").and_then(|v| v.as_bool()) == Some(true) || sounds.iter().any(|s| s.sound_id == sound_id) {
+            continue;
+        }
+        let metadata = std::fs::metadata(&path).ok();
+        sounds.push(SoundUsage {
+            stats: stats.get(&sound_id).copied(),
+            sound_id,
+            name: field("name").unwrap_or_default(),
+            size_bytes: metadata.as_ref().map_or(0, |m| m.len()),
+            added: metadata.and_then(|m| m.created().or_else(|_| m.modified()).ok()).and_then(unix_millis),
+            path,
+        });
+    }
+
+    let now = unix_millis(SystemTime::now()).unwrap_or_default();
+    let report = cleanup_report(&sounds, now, policy.unwrap_or_default());
+    tracing::info!(
+        sounds = sounds.len(),
+        suggested = report.suggestions.len(),
+        reclaimable = report.reclaimable_bytes,
+        "Cleanup suggested"
+    );
+    Ok(report)
+}
+
+/// Folder archived sound files are moved to
+fn sound_archive_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, CommandError> {
+    use tauri::Manager;
+
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::StorageError(e.to_string()))?
+        .join("sound_archive"))
+}
+
+/// A sound `archive_sounds` couldn't archive, and why
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFailureDto {
+    pub sound_id: String,
+    pub error: String,
+}
+
+/// What `archive_sounds` did
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveResultDto {
+    /// Sounds now pointing at their archived file
+    pub archived: usize,
+    /// Sounds left in place because sounds not being archived play the
+    /// same file
+    pub shared: Vec<String>,
+    pub failed: Vec<ArchiveFailureDto>,
+}
+
+impl ArchiveResultDto {
+    fn fail(&mut self, sound_id: &str, error: String) {
+        self.failed.push(ArchiveFailureDto {
+            sound_id: sound_id.to_string(),
+            error,
+        });
+    }
+}
+
+/// Move the files of sounds to the archive folder
+///
+/// The sounds stay on the board flagged `archived`, pointing at their
+/// archived file, so they still play and can be restored by hand. A file
+/// other sounds on the board still play is left alone, and a file that
+/// can't be moved doesn't stop the others; both are reported per sound.
+#[tauri::command]
+pub async fn archive_sounds(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    sound_ids: Vec<String>,
+) -> Result<ArchiveResultDto, CommandError> {
+    let archive_dir = sound_archive_dir(&app)?;
+    std::fs::create_dir_all(&archive_dir).map_err(|e| CommandError::StorageError(e.to_string()))?;
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let board_sounds: Vec<&serde_json::Value> = pads
+        .as_array()
+        .map(|pads| pads.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|pad| {
+            pad.get("sound")
+                .into_iter()
+                .chain(nested_sounds(pad, "variants"))
+                .chain(nested_sounds(pad, "stems"))
+        })
+        .collect();
+    let field = |sound: &serde_json::Value, key: &str| sound.get(key).and_then(|v| v.as_str()).map(String::from);
+    let is_archived = |sound: &serde_json::Value| sound.get("archived").and_then(|v| v.as_bool()) == Some(true);
+
+    // Files still played by sounds that stay on the board
+    let kept: std::collections::HashSet<std::path::PathBuf> = board_sounds
+        .iter()
+        .filter(|sound| !is_archived(sound) && !field(sound, "id").is_some_and(|id| sound_ids.contains(&id)))
+        .filter_map(|sound| field(sound, "path"))
+        .map(|path| file_key(&path))
+        .collect();
+
+    let mut result = ArchiveResultDto::default();
+    // Sounds sharing a file move it once
+    let mut moved: HashMap<std::path::PathBuf, String> = HashMap::new();
+    let mut archived_ids: Vec<&str> = Vec::new();
+    for sound_id in &sound_ids {
+        let Some(sound) = board_sounds.iter().find(|sound| field(sound, "id").as_ref() == Some(sound_id)) else {
+            result.fail(sound_id, "not on the board".into());
+            continue;
+        };
+        if is_archived(sound) {
+            continue;
+        }
+        let path = field(sound, "path").unwrap_or_default();
+        let key = file_key(&path);
+        if kept.contains(&key) {
+            result.shared.push(sound_id.clone());
+            continue;
+        }
+        if !moved.contains_key(&key) {
+            let source = std::path::Path::new(&path);
+            let Some(file_name) = source.file_name() else {
+                result.fail(sound_id, format!("{}: not a file", path));
+                continue;
+            };
+            let target = archive_dir.join(format!("{}_{}", sound_id, file_name.to_string_lossy()));
+            if let Err(e) = move_file(source, &target) {
+                result.fail(sound_id, format!("{}: {}", path, e));
+                continue;
+            }
+            state.decoder.evict(source);
+            moved.insert(key, target.to_string_lossy().into_owned());
+        }
+        archived_ids.push(sound_id.as_str());
+    }
+
+    // Every row of an archived sound follows its file, variants included
+    for sound in soundboard_sounds_mut(&mut pads) {
+        if !sound.get("id").and_then(|v| v.as_str()).is_some_and(|id| archived_ids.contains(&id)) {
+            continue;
+        }
+        let Some(to) = sound.get("path").and_then(|v| v.as_str()).and_then(|path| moved.get(&file_key(path))) else {
+            continue;
+        };
+        let to = to.clone();
+        sound.insert("path".into(), to.into());
+        sound.insert("archived".into(), true.into());
+    }
+    store.set(SOUNDBOARD_KEY, pads);
+    state.auto_save.mark_dirty(SaveTarget::Soundboard);
+
+    result.archived = archived_ids.len();
+    tracing::info!(
+        sounds = result.archived,
+        files = moved.len(),
+        shared = result.shared.len(),
+        failed = result.failed.len(),
+        "Sounds archived"
+    );
+    Ok(result)
+}
+
+/// A file's identity on disk, so differently spelled paths to it compare
+/// equal
+fn file_key(path: &str) -> std::path::PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| std::path::PathBuf::from(path))
+}
+
+/// Size of the sound library on disk, by category, and of the decode cache
+///
+/// Every sound, variant and stem file on the board is counted once, by
+/// format or as archived. Durations come from the saved sounds, probing
+/// files that don't have one.
+#[tauri::command]
+pub async fn get_library_stats(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<LibraryStats, CommandError> {
+    use crate::domain::{LibraryFile, ARCHIVED_CATEGORY};
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let board_sounds = pads.as_array().map(|pads| pads.as_slice()).unwrap_or_default().iter().flat_map(|pad| {
+        pad.get("sound")
+            .into_iter()
+            .chain(nested_sounds(pad, "variants"))
+            .chain(nested_sounds(pad, "stems"))
+    });
+
+    // Path, saved duration and archived flag; the files are read below
+    let sounds: Vec<(String, Option<f64>, bool)> = board_sounds
+        .filter_map(|sound| {
+            let path = sound.get("path")?.as_str()?.to_string();
+            let duration = sound.get("duration").and_then(|v| v.as_f64());
+            let archived = sound.get("archived").and_then(|v| v.as_bool()) == Some(true);
+            Some((path, duration, archived))
+        })
+        .collect();
+
+    // Canonicalizing, stat-ing and probing touch the disk for every file
+    let decoder = state.decoder.clone();
+    let files = tauri::async_runtime::spawn_blocking(move || {
+        let mut seen = std::collections::HashSet::new();
+        let mut files: Vec<LibraryFile> = Vec::new();
+        for (path, duration, archived) in sounds {
+            if !seen.insert(file_key(&path)) {
+                continue;
+            }
+
+            let file_path = std::path::Path::new(&path);
+            let size_bytes = std::fs::metadata(file_path).map(|m| m.len()).ok();
+            let duration_secs = duration.unwrap_or_else(|| {
+                decoder.probe(file_path).map(|m| m.duration.as_secs_f64()).unwrap_or_default()
+            });
+            let category = if archived {
+                ARCHIVED_CATEGORY.to_string()
+            } else {
+                file_path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map(str::to_lowercase)
+                    .unwrap_or_else(|| "other".into())
+            };
+            files.push(LibraryFile {
+                category,
+                size_bytes: size_bytes.unwrap_or(0),
+                duration_secs,
+                present: size_bytes.is_some(),
+            });
+        }
+        files
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))?;
+
+    Ok(LibraryStats {
+        cached_sounds: state.decoder.cached_count(),
+        cache_bytes: state.decoder.cached_bytes(),
+        ..LibraryStats::from_files(&files)
+    })
+}
+
+/// Move a file, copying it when the archive sits on another volume
+fn move_file(from: &std::path::Path, to: &std::path::Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+// ============================================================================
+// Recording Commands
+// ============================================================================
+
+/// Start recording the live mix to a WAV file
+///
+/// Without a path, the recording goes to the app's `recordings` folder,
+/// named after the current time. In podcast mode each mic is also written
+/// as a mono stem next to it. Returns the path being written.
+#[tauri::command]
+pub async fn start_recording(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<String, CommandError> {
+    use crate::domain::format_utc_timestamp;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use tauri::Manager;
+
+    if !*state.is_mixing.read().await {
+        return Err(CommandError::EngineNotRunning);
+    }
+
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default();
+            app.path()
+                .app_data_dir()
+                .map_err(|e| CommandError::StorageError(e.to_string()))?
+                .join("recordings")
+                .join(format!("{}.wav", format_utc_timestamp(now)[..19].replace(':', "-")))
+        }
+    };
+
+    // Record what the output stream runs at, not what the settings ask for
+    let (sample_rate, channels) = state.audio_engine.mix_format().ok_or(CommandError::EngineNotRunning)?;
+    let settings = state.settings.read().await;
+    let device = settings.audio.input_device();
+    let stems: &[&str] = match settings.input_channel_maps.get(device) {
+        Some(InputChannelMap::DualMono { .. }) => &PODCAST_STEMS,
+        _ => &[],
+    };
+    let taps = state.recorder.start(path.clone(), sample_rate, channels as u16, stems)?;
+    drop(settings);
+
+    let engine = &state.audio_engine;
+    engine
+        .send_command(AudioEngineCommand::SetRecordingTap(Some(taps.mix)))
+        .map_err(CommandError::EngineError)?;
+    engine
+        .send_command(AudioEngineCommand::SetStemTaps(taps.stems))
+        .map_err(CommandError::EngineError)?;
+
+    state.telemetry.record("start_recording");
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Stop recording and export its markers
+///
+/// Markers are embedded in the WAV file and written next to it as a
+/// `.cue` sheet and a `.markers.json` file.
+#[tauri::command]
+pub async fn stop_recording(state: State<'_, AppState>) -> Result<RecordingSummary, CommandError> {
+    finish_recording(&state).await
+}
+
+/// Detach the recording taps and finalize the files
+async fn finish_recording(state: &AppState) -> Result<RecordingSummary, CommandError> {
+    // Detach the taps first: each writer drains until its tap is dropped
+    let engine = &state.audio_engine;
+    engine
+        .send_command(AudioEngineCommand::SetRecordingTap(None))
+        .map_err(CommandError::EngineError)?;
+    engine
+        .send_command(AudioEngineCommand::SetStemTaps(Vec::new()))
+        .map_err(CommandError::EngineError)?;
+
+    let recorder = state.recorder.clone();
+    let summary = tauri::async_runtime::spawn_blocking(move || recorder.stop())
+        .await
+        .map_err(|e| CommandError::Internal(e.to_string()))??;
+    Ok(summary)
+}
+
+/// Quit the app
+///
+/// Like `stop_mixing`, fails with `RECORDING_ACTIVE` while a recording
+/// runs unless `finalize_recording` confirms it may be finished first.
+#[tauri::command]
+pub async fn quit_app(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    finalize_recording: Option<bool>,
+) -> Result<(), CommandError> {
+    guard_recording(&state, finalize_recording.unwrap_or(false))?;
+    if state.recorder.is_recording() {
+        finish_recording(&state).await?;
+    }
+    app.exit(0);
+    Ok(())
+}
+
+/// Whether the live mix is being recorded
+#[tauri::command]
+pub async fn is_recording(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    Ok(state.recorder.is_recording())
+}
+
+/// Mark the current position of the recording
+#[tauri::command]
+pub async fn add_marker(state: State<'_, AppState>, label: String) -> Result<RecordingMarker, CommandError> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(CommandError::InvalidArgument("Marker label cannot be empty".into()));
+    }
+    Ok(state.recorder.add_marker(label, MarkerKind::Manual)?)
+}
+
+/// Get the markers of the running recording
+#[tauri::command]
+pub async fn get_markers(state: State<'_, AppState>) -> Result<Vec<RecordingMarker>, CommandError> {
+    Ok(state.recorder.markers())
+}
+
+// ============================================================================
+// Board Share Commands
+// ============================================================================
+
+/// Share selected pads read-only with co-hosts on the LAN
+///
+/// Co-hosts open the returned URL to browse and listen to the pads, and
+/// can ask for one to be played, which emits `share-sound-requested`.
+/// `port` defaults to a free one.
+#[tauri::command]
+pub async fn start_board_share(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_ids: Vec<String>,
+    port: Option<u16>,
+) -> Result<ShareInfo, CommandError> {
+    use tauri::Emitter;
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let saved = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let pads: Vec<SharedPad> = saved
+        .as_array()
+        .map(|pads| pads.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|pad| {
+            pad.get("id")
+                .and_then(|id| id.as_str())
+                .is_some_and(|id| pad_ids.iter().any(|selected| selected == id))
+        })
+        .filter_map(|pad| {
+            let sound = pad.get("sound")?;
+            Some(SharedPad {
+                id: pad.get("id")?.as_str()?.to_string(),
+                name: sound.get("name")?.as_str()?.to_string(),
+                color: pad.get("color").and_then(|c| c.as_str()).map(String::from),
+                duration: sound.get("duration").and_then(|d| d.as_f64()).unwrap_or_default(),
+                path: sound.get("path")?.as_str()?.into(),
+            })
+        })
+        .collect();
+
+    let request_app = app.clone();
+    let info = state.board_share.start(pads, port.unwrap_or(0), move |request| {
+        use tauri::Manager;
+
+        tracing::info!(pad = %request.pad_id, from = %request.from, "Co-host requested a sound");
+        let state = request_app.state::<AppState>();
+        let sound_id = saved_pad(&request_app, &request.pad_id)
+            .ok()
+            .and_then(|pad| pad.get("sound")?.get("id")?.as_str().map(String::from))
+            .unwrap_or_default();
+        let allowed = tauri::async_runtime::block_on(authorize_trigger(
+            &state,
+            Integration::Http,
+            IntegrationAction::PlaySound,
+            Some(&sound_id),
+        ));
+        match allowed {
+            Ok(()) => {
+                let _ = request_app.emit("share-sound-requested", &request);
+            }
+            Err(e) => tracing::warn!(pad = %request.pad_id, error = %e, "Co-host request refused"),
+        }
+    })?;
+    Ok(info)
+}
+
+/// Stop sharing the board
+#[tauri::command]
+pub async fn stop_board_share(state: State<'_, AppState>) -> Result<(), CommandError> {
+    state.board_share.stop();
+    Ok(())
+}
+
+/// Get the current board share, if any
+#[tauri::command]
+pub async fn get_board_share(state: State<'_, AppState>) -> Result<Option<ShareInfo>, CommandError> {
+    Ok(state.board_share.info())
+}
+
+// ============================================================================
+// Hot Folder Commands
+// ============================================================================
+
+/// Get the trigger folder settings
+#[tauri::command]
+pub async fn get_hot_folder(state: State<'_, AppState>) -> Result<HotFolderSettings, CommandError> {
+    Ok(state.settings.read().await.hot_folder.clone())
+}
+
+/// Set the folder watched for trigger files
+///
+/// Dropping `<sound-id>.play` there plays the sound; a `.json` file holds
+/// any other command, e.g. `{"action": "stop_all"}`.
+#[tauri::command]
+pub async fn set_hot_folder(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    hot_folder: HotFolderSettings,
+) -> Result<(), CommandError> {
+    if let Some(path) = hot_folder.active_path() {
+        if !std::path::Path::new(path).is_dir() {
+            return Err(CommandError::InvalidArgument(format!("Trigger folder {} does not exist", path)));
+        }
+    }
+    if hot_folder.enabled && hot_folder.path.is_none() {
+        return Err(CommandError::InvalidArgument("No trigger folder given".into()));
+    }
+
+    tracing::info!(enabled = hot_folder.enabled, path = ?hot_folder.path, "Hot folder set");
+    state.settings.write().await.hot_folder = hot_folder;
+    persist_settings(&app, &state).await
+}
+
+/// Run a command read from the trigger folder, on the watcher thread
+pub fn run_hot_folder_command(app: &tauri::AppHandle, command: HotFolderCommand) -> Result<(), String> {
+    tauri::async_runtime::block_on(run_trigger(app, Integration::HotFolder, IntegrationTrigger::Command(command)))
+        .map_err(|e| e.to_string())
+}
+
+/// Run one of the commands shared by trigger files and pad actions
+async fn run_app_command(app: &tauri::AppHandle, command: HotFolderCommand, source: PlaySource) -> Result<(), CommandError> {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    match command {
+        HotFolderCommand::Play { sound_id } => {
+            let path = soundboard_sound_path(app, &sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.clone()))?;
+            let looping = soundboard_sound_looping(app, &sound_id);
+            play_sound(app.clone(), state, sound_id, path, Some(source), Some(looping), None).await
+        }
+        HotFolderCommand::Stop { sound_id, fade_ms } => stop_sound(state, sound_id, fade_ms).await,
+        HotFolderCommand::StopAll { fade_ms } => stop_all_sounds(state, fade_ms).await,
+        HotFolderCommand::MuteMic => set_mic_muted(state, true).await,
+        HotFolderCommand::UnmuteMic => set_mic_muted(state, false).await,
+    }
+}
+
+// ============================================================================
+// Integration Permission Commands
+// ============================================================================
+
+/// Get the policy of every integration, defaults included
+#[tauri::command]
+pub async fn get_integration_permissions(
+    state: State<'_, AppState>,
+) -> Result<HashMap<Integration, IntegrationPolicy>, CommandError> {
+    let settings = state.settings.read().await;
+    Ok(Integration::ALL
+        .into_iter()
+        .map(|integration| (integration, settings.integrations.policy(integration)))
+        .collect())
+}
+
+/// Set what an integration may trigger
+#[tauri::command]
+pub async fn set_integration_policy(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    integration: Integration,
+    policy: IntegrationPolicy,
+) -> Result<(), CommandError> {
+    if let Some(limit) = policy.rate_limit {
+        if limit.max_triggers == 0 || limit.per_secs == 0 {
+            return Err(CommandError::InvalidArgument(
+                "Rate limit needs at least one trigger per second".into(),
+            ));
+        }
+    }
+
+    tracing::info!(%integration, enabled = policy.enabled, "Integration policy set");
+    state.settings.write().await.integrations.policies.insert(integration, policy);
+    persist_settings(&app, &state).await
+}
+
+/// What an outside integration asks the board to do
+///
+/// `{"pad_id": ...}` runs a pad's actions, `{"scene_id": ...}` recalls a
+/// scene, anything else is a trigger-file command such as
+/// `{"action": "play", "sound_id": ...}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IntegrationTrigger {
+    PadActions { pad_id: String },
+    RecallScene { scene_id: String, crossfade_ms: Option<u32> },
+    Command(HotFolderCommand),
+}
+
+impl IntegrationTrigger {
+    /// What the trigger asks permission for
+    fn permission(&self) -> (IntegrationAction, Option<&str>) {
+        match self {
+            Self::PadActions { .. } => (IntegrationAction::PadActions, None),
+            Self::RecallScene { .. } => (IntegrationAction::RecallScene, None),
+            Self::Command(command) => command_permission(command),
+        }
+    }
+}
+
+/// Run a trigger of an integration driven by the frontend (WebSocket,
+/// OSC, MIDI, chat)
+///
+/// Fails with `PERMISSION_DENIED` when the policy refuses it and with
+/// `RATE_LIMITED` when the integration is over its rate limit.
+#[tauri::command]
+pub async fn run_integration_trigger(
+    app: tauri::AppHandle,
+    integration: Integration,
+    trigger: IntegrationTrigger,
+) -> Result<(), CommandError> {
+    run_trigger(&app, integration, trigger).await
+}
+
+/// Check a trigger against the integration's policy, then run it
+///
+/// Every outside trigger goes through here, so none skips its policy.
+async fn run_trigger(app: &tauri::AppHandle, integration: Integration, trigger: IntegrationTrigger) -> Result<(), CommandError> {
+    use tauri::Manager;
+
+    let (action, sound_id) = trigger.permission();
+    authorize_trigger(&app.state::<AppState>(), integration, action, sound_id).await?;
+
+    let source = match integration {
+        Integration::Twitch => PlaySource::Twitch,
+        _ => PlaySource::Remote,
+    };
+    match trigger {
+        IntegrationTrigger::PadActions { pad_id } => trigger_pad_actions(app.clone(), pad_id, Some(source)).await,
+        IntegrationTrigger::RecallScene { scene_id, crossfade_ms } => {
+            recall_scene(app.clone(), app.state::<AppState>(), scene_id, crossfade_ms).await.map(|_| ())
+        }
+        IntegrationTrigger::Command(command) => run_app_command(app, command, source).await,
+    }
+}
+
+/// Check a trigger against the integration's policy, then its rate limit
+async fn authorize_trigger(
+    state: &AppState,
+    integration: Integration,
+    action: IntegrationAction,
+    sound_id: Option<&str>,
+) -> Result<(), CommandError> {
+    let policy = state.settings.read().await.integrations.policy(integration);
+    policy.check(integration, action, sound_id)?;
+    if let Some(limit) = policy.rate_limit {
+        if !state.trigger_limiter.allow(integration, &limit, std::time::Instant::now()) {
+            return Err(PermissionDenied::RateLimited(integration).into());
+        }
+    }
+    Ok(())
+}
+
+/// What a trigger-file command asks permission for
+fn command_permission(command: &HotFolderCommand) -> (IntegrationAction, Option<&str>) {
+    match command {
+        HotFolderCommand::Play { sound_id } => (IntegrationAction::PlaySound, Some(sound_id)),
+        HotFolderCommand::Stop { sound_id, .. } => (IntegrationAction::StopSound, Some(sound_id)),
+        HotFolderCommand::StopAll { .. } => (IntegrationAction::StopAll, None),
+        HotFolderCommand::MuteMic | HotFolderCommand::UnmuteMic => (IntegrationAction::MuteMic, None),
+    }
+}
+
+// ============================================================================
+// Now Playing Overlay Commands
+// ============================================================================
+
+/// Get the sound shown on the now-playing overlay, if one is playing
+#[tauri::command]
+pub async fn get_now_playing(state: State<'_, AppState>) -> Result<Option<NowPlaying>, CommandError> {
+    let playing = state.audio_engine.playing_snapshot();
+    Ok(state.now_playing.current(&playing))
+}
+
+/// Serve the now-playing overlay on localhost
+///
+/// Add the returned `url` as an OBS browser source, or poll `json_url`.
+/// `port` defaults to a free one.
+#[tauri::command]
+pub async fn start_overlay(state: State<'_, AppState>, port: Option<u16>) -> Result<OverlayInfo, CommandError> {
+    let engine = state.audio_engine.clone();
+    let info = state.overlay.start(port.unwrap_or(0), state.now_playing.clone(), move || {
+        engine.playing_snapshot()
+    })?;
+    Ok(info)
+}
+
+/// Stop serving the now-playing overlay
+#[tauri::command]
+pub async fn stop_overlay(state: State<'_, AppState>) -> Result<(), CommandError> {
+    state.overlay.stop();
+    Ok(())
+}
+
+/// Get where the now-playing overlay is served, if it is
+#[tauri::command]
+pub async fn get_overlay(state: State<'_, AppState>) -> Result<Option<OverlayInfo>, CommandError> {
+    Ok(state.overlay.info())
+}
+
+// ============================================================================
+// Cloud Sync Commands
+// ============================================================================
+
+/// Sync config and state store (per machine, never synced)
+const SYNC_STORE: &str = "sync.json";
+const SYNC_CONFIG_KEY: &str = "config";
+const SYNC_STATE_KEY: &str = "state";
+
+/// DTO for the cloud sync status
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatusDto {
+    pub enabled: bool,
+    /// "web_dav" or "s3", None when no backend is configured
+    pub backend: Option<String>,
+    pub syncing: bool,
+    pub last_action: Option<SyncAction>,
+    /// Both sides changed; call sync_now with a resolution
+    pub conflict: bool,
+    pub last_attempt_at: Option<u64>,
+    pub last_synced_at: Option<u64>,
+    pub remote_updated_at: Option<u64>,
+    pub remote_device: Option<String>,
+    pub error: Option<String>,
+}
+
+fn load_sync_store<T: serde::de::DeserializeOwned + Default>(
+    app: &tauri::AppHandle,
+    key: &str,
+) -> Result<T, CommandError> {
+    let store = app.store(SYNC_STORE)?;
+    Ok(store
+        .get(key)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+fn save_sync_store<T: Serialize>(app: &tauri::AppHandle, key: &str, value: &T) -> Result<(), CommandError> {
+    let store = app.store(SYNC_STORE)?;
+    store.set(key, serde_json::to_value(value).map_err(|e| e.to_string())?);
+    Ok(store.save()?)
+}
+
+fn sync_status_dto(app: &tauri::AppHandle, state: &AppState) -> Result<SyncStatusDto, CommandError> {
+    let config: SyncConfig = load_sync_store(app, SYNC_CONFIG_KEY)?;
+    let sync_state: SyncState = load_sync_store(app, SYNC_STATE_KEY)?;
+    let status = state.cloud_sync.status();
+
+    Ok(SyncStatusDto {
+        enabled: config.enabled,
+        backend: config.backend.map(|backend| {
+            match backend {
+                SyncBackendConfig::WebDav { .. } => "web_dav",
+                SyncBackendConfig::S3 { .. } => "s3",
+            }
+            .to_string()
+        }),
+        syncing: state.cloud_sync.is_running(),
+        last_action: status.last_action,
+        conflict: status.last_action == Some(SyncAction::Conflict),
+        last_attempt_at: status.last_attempt_at,
+        last_synced_at: sync_state.last_synced_at,
+        remote_updated_at: status.remote.as_ref().map(|r| r.updated_at),
+        remote_device: status.remote.map(|r| r.device),
+        error: status.error,
+    })
+}
+
+/// DTO for the cloud sync configuration, without the secret
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConfigDto {
+    pub enabled: bool,
+    pub backend: Option<SyncBackendConfig>,
+    /// A password or secret key is stored for the backend
+    pub has_password: bool,
+}
+
+/// Load the sync config, moving a secret saved by older versions into
+/// the credential store
+fn load_sync_config(app: &tauri::AppHandle, state: &AppState) -> Result<SyncConfig, CommandError> {
+    let mut config: SyncConfig = load_sync_store(app, SYNC_CONFIG_KEY)?;
+    if let Some(backend) = config.backend.as_mut() {
+        let secret = std::mem::take(backend.secret_mut());
+        if !secret.is_empty() {
+            state
+                .secrets
+                .set(&backend.secret_name(), &secret)
+                .map_err(|e| CommandError::StorageError(e.to_string()))?;
+            save_sync_store(app, SYNC_CONFIG_KEY, &config)?;
+        }
+    }
+    Ok(config)
+}
+
+/// Get the cloud sync backend configuration
+///
+/// The password or secret key stays in the credential store; only
+/// whether one is stored is returned.
+#[tauri::command]
+pub async fn get_sync_config(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<SyncConfigDto, CommandError> {
+    let config = load_sync_config(&app, &state)?;
+    let has_password = match &config.backend {
+        Some(backend) => state
+            .secrets
+            .get(&backend.secret_name())
+            .map_err(|e| CommandError::StorageError(e.to_string()))?
+            .is_some(),
+        None => false,
+    };
+    Ok(SyncConfigDto {
+        enabled: config.enabled,
+        backend: config.backend,
+        has_password,
+    })
+}
+
+/// Configure the cloud sync backend
+///
+/// The password or secret key goes to the credential store; an empty
+/// one keeps the stored secret of the same server and account. Only
+/// https servers are accepted, as the credentials travel with every
+/// request. Switching backends forgets the last sync, so the first sync
+/// against the new remote detects conflicts from scratch.
+#[tauri::command]
+pub async fn set_sync_config(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    mut config: SyncConfig,
+) -> Result<(), CommandError> {
+    use crate::application::cloud_sync::SyncError;
+
+    let previous = load_sync_config(&app, &state)?;
+    if let Some(backend) = config.backend.as_mut() {
+        if !backend.is_https() {
+            return Err(SyncError::InsecureUrl(backend.url().to_string()).into());
+        }
+        let secret = std::mem::take(backend.secret_mut());
+        if !secret.is_empty() {
+            state
+                .secrets
+                .set(&backend.secret_name(), &secret)
+                .map_err(|e| CommandError::StorageError(e.to_string()))?;
+        }
+    }
+
+    if previous.backend != config.backend {
+        save_sync_store(&app, SYNC_STATE_KEY, &SyncState::default())?;
+    }
+    // Forget the credentials of a server no longer synced with
+    if let Some(old) = previous.backend.map(|backend| backend.secret_name()) {
+        if config.backend.as_ref().map(|backend| backend.secret_name()).as_ref() != Some(&old) {
+            if let Err(e) = state.secrets.delete(&old) {
+                tracing::warn!(error = %e, "Failed to remove the previous cloud sync secret");
+            }
+        }
+    }
+    save_sync_store(&app, SYNC_CONFIG_KEY, &config)?;
+
+    tracing::info!(enabled = config.enabled, "Cloud sync configured");
+    Ok(())
+}
+
+/// Get the cloud sync status
+#[tauri::command]
+pub async fn get_sync_status(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<SyncStatusDto, CommandError> {
+    sync_status_dto(&app, &state)
+}
+
+/// Sync the soundboard and settings with the cloud now
+///
+/// Uploads local changes or downloads remote ones. When both sides
+/// changed, nothing is transferred and the status reports a conflict
+/// until this is called again with a `resolution`. Downloads replace the
+/// board and settings and emit `sync-applied`.
+#[tauri::command]
+pub async fn sync_now(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    resolution: Option<SyncResolution>,
+) -> Result<SyncStatusDto, CommandError> {
+    use crate::application::cloud_sync::{storage_for, SyncError, SyncSnapshot};
+    use tauri::{Emitter, Manager};
+
+    let config = load_sync_config(&app, &state)?;
+    let mut backend = match config.backend {
+        Some(backend) if config.enabled => backend,
+        _ => return Err(SyncError::NotConfigured.into()),
+    };
+    *backend.secret_mut() = state
+        .secrets
+        .get(&backend.secret_name())
+        .map_err(|e| CommandError::StorageError(e.to_string()))?
+        .unwrap_or_default();
+
+    let board_store = app.store(SOUNDBOARD_STORE)?;
+    let pads = board_store.get(SOUNDBOARD_KEY).unwrap_or_else(|| serde_json::json!([]));
+    let local_settings = serde_json::to_value(AppSettingsDto::from(&*state.settings.read().await))
+        .map_err(|e| CommandError::Internal(e.to_string()))?;
+    let local = SyncSnapshot::new(pads, local_settings.clone());
+    let sync_state: SyncState = load_sync_store(&app, SYNC_STATE_KEY)?;
+
+    let sounds_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::StorageError(e.to_string()))?
+        .join("synced_sounds");
+
+    let cloud_sync = state.cloud_sync.clone();
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        let storage = storage_for(&backend)?;
+        cloud_sync.sync(storage.as_ref(), &local, &sync_state, resolution, &sounds_dir)
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))??;
+
+    if let Some(snapshot) = &outcome.downloaded {
+        board_store.set(SOUNDBOARD_KEY, snapshot.pads.clone());
+        board_store.save()?;
+
+        let settings: AppSettingsDto = serde_json::from_value(snapshot.merge_settings(&local_settings))
+            .map_err(|e| CommandError::StorageError(format!("Invalid synced settings: {}", e)))?;
+        *state.settings.write().await = AppSettings::from(settings);
+        persist_settings(&app, &state).await?;
+        if let Err(e) = apply_mic_processing(&state).await {
+            tracing::warn!(error = %e, "Failed to apply synced mic settings");
+        }
+
+        let _ = app.emit("sync-applied", ());
+    }
+    if outcome.action != SyncAction::Conflict {
+        save_sync_store(&app, SYNC_STATE_KEY, &outcome.state)?;
+    }
+
+    tracing::info!(action = ?outcome.action, "Cloud sync finished");
+    sync_status_dto(&app, &state)
+}
+
+// ============================================================================
+// Hotkey Commands
+// ============================================================================
+
+/// Hotkeys the frontend assigns to the first pads when none is set
+const DEFAULT_PAD_HOTKEYS: [&str; 12] = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "-", "="];
+
+/// DTO for the result of validating a hotkey
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyValidationDto {
+    /// Canonical spelling of the hotkey
+    pub normalized: String,
+    pub conflicts: Vec<HotkeyClash>,
+}
+
+/// DTO for a hotkey that is live in the active profile
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveHotkeyDto {
+    pub pad_id: String,
+    pub hotkey: String,
+    pub bank: u8,
+    /// Keys actually pressed, bank modifiers included
+    pub keys: String,
+}
+
+/// Read the hotkey bindings of all saved pads
+fn saved_hotkey_bindings(app: &tauri::AppHandle) -> Result<Vec<ScopedBinding>, CommandError> {
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+
+    let bindings = pads
+        .as_array()
+        .map(|pads| pads.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, pad)| {
+            let pad_id = pad.get("id")?.as_str()?;
+            let hotkey = pad
+                .get("hotkey")
+                .and_then(|h| h.as_str())
+                .or_else(|| DEFAULT_PAD_HOTKEYS.get(index).copied())?;
+            let bank = pad.get("hotkeyBank").and_then(|b| b.as_u64()).unwrap_or(0) as u8;
+            let profile = pad.get("hotkeyProfile").and_then(|p| p.as_str());
+
+            // Unparseable saved hotkeys can't conflict with anything
+            let sequence = HotkeySequence::parse(hotkey).ok()?;
+            Some(ScopedBinding {
+                binding: HotkeyBinding::new(pad_id, bank, sequence).ok()?,
+                scope: HotkeyScope::from_profile(profile),
+            })
+        })
+        .collect();
+
+    Ok(bindings)
+}
+
+/// Reload the hotkey registry from the saved pads
+fn reload_hotkeys(app: &tauri::AppHandle, state: &AppState) -> Result<(), CommandError> {
+    state.hotkeys.load(saved_hotkey_bindings(app)?);
+    Ok(())
+}
+
+/// Hotkeys live in the active profile
+fn active_hotkeys(state: &AppState) -> Vec<ActiveHotkeyDto> {
+    state
+        .hotkeys
+        .active_bindings()
+        .into_iter()
+        .map(|binding| ActiveHotkeyDto {
+            keys: binding.effective().to_string(),
+            hotkey: binding.sequence.to_string(),
+            bank: binding.bank,
+            pad_id: binding.pad_id,
+        })
+        .collect()
+}
+
+/// Board hotkeys that are live: a key a pad already holds stays the pad's
+///
+/// Pads and board hotkeys are checked against each other when set, so
+/// only the defaults can clash, with pads bound before they existed.
+fn live_board_hotkeys(state: &AppState, hotkeys: &BoardHotkeySettings) -> BoardHotkeySettings {
+    let free = |id: &str, hotkey: &Option<String>| {
+        hotkey.clone().filter(|hotkey| {
+            HotkeySequence::parse(hotkey)
+                .and_then(|sequence| HotkeyBinding::new(id, 0, sequence))
+                .is_ok_and(|binding| state.hotkeys.conflicts(&binding, &HotkeyScope::Global).is_empty())
+        })
+    };
+    BoardHotkeySettings {
+        next: free("next-board", &hotkeys.next),
+        previous: free("previous-board", &hotkeys.previous),
+    }
+}
+
+/// Conflicts of `candidate` with hotkeys that aren't pad hotkeys: the
+/// live board hotkeys and the global combos of other pads
+///
+/// Both are live on every board, so they clash whatever the scope. Global
+/// combos count while switched off too, so turning them on clashes nothing.
+fn reserved_hotkey_conflicts(
+    state: &AppState,
+    candidate: &HotkeyBinding,
+    global: &GlobalHotkeySettings,
+    boards: &BoardHotkeySettings,
+) -> Vec<HotkeyClash> {
+    let boards = live_board_hotkeys(state, boards);
+    let board_bindings = [("next-board", boards.next), ("previous-board", boards.previous)]
+        .into_iter()
+        .filter_map(|(id, hotkey)| Some((id.to_string(), hotkey?)));
+    let global_bindings = global
+        .pads
+        .iter()
+        .map(|(pad_id, hotkey)| (pad_id.clone(), hotkey.clone()));
+    let reserved: Vec<HotkeyBinding> = board_bindings
+        .chain(global_bindings)
+        .filter_map(|(id, hotkey)| HotkeyBinding::new(id, 0, HotkeySequence::parse(&hotkey).ok()?).ok())
+        .collect();
+
+    find_conflicts(&reserved, candidate)
+        .into_iter()
+        .map(|conflict| HotkeyClash {
+            pad_id: conflict.binding.pad_id,
+            hotkey: conflict.binding.sequence.to_string(),
+            bank: 0,
+            profile: None,
+            kind: conflict.kind,
+        })
+        .collect()
+}
+
+/// Validate a pad hotkey and report conflicts with other pads, the board
+/// hotkeys and global combos
+///
+/// Conflicts include identical keys (also across banks) and sequences
+/// that would shadow each other, like `Space` and `Space 1`. Pad hotkeys
+/// of other profiles never conflict.
+#[tauri::command]
+pub async fn validate_hotkey(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+    hotkey: String,
+    bank: Option<u8>,
+    profile: Option<String>,
+) -> Result<HotkeyValidationDto, CommandError> {
+    let sequence = HotkeySequence::parse(&hotkey)?;
+    let candidate = HotkeyBinding::new(pad_id, bank.unwrap_or(0), sequence)?;
+
+    reload_hotkeys(&app, &state)?;
+    let mut conflicts = state
+        .hotkeys
+        .conflicts(&candidate, &HotkeyScope::from_profile(profile.as_deref()));
+    let settings = state.settings.read().await;
+    conflicts.extend(reserved_hotkey_conflicts(&state, &candidate, &settings.global_hotkeys, &settings.board_hotkeys));
+
+    Ok(HotkeyValidationDto {
+        normalized: candidate.sequence.to_string(),
+        conflicts,
+    })
+}
+
+/// Set a pad's hotkey and save it with the pad
+///
+/// With `profile`, the hotkey is only live while that profile is active
+/// and may reuse keys of other profiles. A hotkey conflicting with another
+/// pad, a board hotkey or a global combo is refused with
+/// `HOTKEY_CONFLICT`, listing what it conflicts with. Returns the
+/// canonical spelling of the hotkey.
+#[tauri::command]
+pub async fn set_sound_hotkey(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+    hotkey: String,
+    bank: Option<u8>,
+    profile: Option<String>,
+) -> Result<String, CommandError> {
+    let bank = bank.unwrap_or(0);
+    let binding = HotkeyBinding::new(pad_id.clone(), bank, HotkeySequence::parse(&hotkey)?)?;
+    let scope = HotkeyScope::from_profile(profile.as_deref());
+    let normalized = binding.sequence.to_string();
+
+    let store = app.store(SOUNDBOARD_STORE)?;
+    let mut pads = store.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let pad = pads
+        .as_array_mut()
+        .and_then(|pads| pads.iter_mut().find(|p| p.get("id").and_then(|id| id.as_str()) == Some(&pad_id)))
+        .and_then(|pad| pad.as_object_mut())
+        .ok_or_else(|| CommandError::SoundNotFound(pad_id.clone()))?;
+
+    reload_hotkeys(&app, &state)?;
+    let reserved = {
+        let settings = state.settings.read().await;
+        reserved_hotkey_conflicts(&state, &binding, &settings.global_hotkeys, &settings.board_hotkeys)
+    };
+    if !reserved.is_empty() {
+        return Err(CommandError::HotkeyConflict {
+            hotkey: normalized,
+            conflicts: reserved,
+        });
+    }
+    state.hotkeys.bind(binding, scope.clone())?;
+
+    pad.insert("hotkey".into(), normalized.clone().into());
+    pad.insert("hotkeyBank".into(), bank.into());
+    match scope.profile() {
+        Some(profile) => pad.insert("hotkeyProfile".into(), profile.into()),
+        None => pad.remove("hotkeyProfile"),
+    };
+    store.set(SOUNDBOARD_KEY, pads);
+    store.save()?;
+
+    tracing::info!(pad = %pad_id, hotkey = %normalized, bank, profile = ?scope.profile(), "Pad hotkey set");
+    Ok(normalized)
+}
+
+/// Get the hotkeys live in the active profile, global ones included
+#[tauri::command]
+pub async fn get_active_hotkeys(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<ActiveHotkeyDto>, CommandError> {
+    reload_hotkeys(&app, &state)?;
+    Ok(active_hotkeys(&state))
+}
+
+/// Make a profile's hotkeys live (None leaves only global hotkeys)
+///
+/// Returns the hotkeys now live.
+#[tauri::command]
+pub async fn set_active_hotkey_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile: Option<String>,
+) -> Result<Vec<ActiveHotkeyDto>, CommandError> {
+    let profile = HotkeyScope::from_profile(profile.as_deref()).profile().map(String::from);
+    state.settings.write().await.active_hotkey_profile = profile.clone();
+    persist_settings(&app, &state).await?;
+
+    let board = state.hotkeys.set_active_profile(profile.clone());
+    reload_hotkeys(&app, &state)?;
+    emit_board_changed(&app, &state, board);
+
+    tracing::info!(profile = ?profile, "Hotkey profile activated");
+    Ok(active_hotkeys(&state))
+}
+
+/// DTO for the active board, payload of `board-changed`
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardDto {
+    pub board: Option<String>,
+    /// Passed back with hotkey presses, so stale ones are dropped
+    pub generation: u64,
+    /// Every board, in switching order
+    pub boards: Vec<String>,
+    /// Hotkeys live on the board, global ones included
+    pub hotkeys: Vec<ActiveHotkeyDto>,
+}
+
+fn board_dto(state: &AppState, active: ActiveBoard) -> BoardDto {
+    BoardDto {
+        board: active.board,
+        generation: active.generation,
+        boards: state.hotkeys.boards(),
+        hotkeys: active_hotkeys(state),
+    }
+}
+
+fn emit_board_changed(app: &tauri::AppHandle, state: &AppState, active: ActiveBoard) -> BoardDto {
+    use tauri::Emitter;
+
+    let board = board_dto(state, active);
+    let _ = app.emit("board-changed", &board);
+    board
+}
+
+/// Get the active board and the ones hotkeys switch between
+#[tauri::command]
+pub async fn get_board(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<BoardDto, CommandError> {
+    reload_hotkeys(&app, &state)?;
+    Ok(board_dto(&state, state.hotkeys.active_board()))
+}
+
+/// Step through the boards and save the one landed on
+async fn step_board(app: &tauri::AppHandle, state: &AppState, step: isize) -> Result<BoardDto, CommandError> {
+    reload_hotkeys(app, state)?;
+    let Some(active) = state.hotkeys.step_board(step) else {
+        return Ok(board_dto(state, state.hotkeys.active_board()));
+    };
+
+    state.settings.write().await.active_hotkey_profile = active.board.clone();
+    persist_settings(app, state).await?;
+
+    tracing::info!(board = ?active.board, generation = active.generation, "Board switched");
+    Ok(emit_board_changed(app, state, active))
+}
+
+/// Switch to the next board, wrapping after the last
+///
+/// The pad hotkeys of the new board replace the old ones at once; presses
+/// matched against the old board are dropped by `play_sound`. Emits
+/// `board-changed`.
+#[tauri::command]
+pub async fn next_board(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<BoardDto, CommandError> {
+    step_board(&app, &state, 1).await
+}
+
+/// Switch to the previous board, wrapping before the first
+#[tauri::command]
+pub async fn previous_board(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<BoardDto, CommandError> {
+    step_board(&app, &state, -1).await
+}
+
+/// Get the hotkeys switching boards, leaving out one a pad already holds
+#[tauri::command]
+pub async fn get_board_hotkeys(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<BoardHotkeySettings, CommandError> {
+    reload_hotkeys(&app, &state)?;
+    Ok(live_board_hotkeys(&state, &state.settings.read().await.board_hotkeys))
+}
+
+/// Set the hotkeys switching boards, None to unbind one
+///
+/// They are live on every board, so they may not conflict with any pad
+/// hotkey. Returns them in canonical spelling.
+#[tauri::command]
+pub async fn set_board_hotkeys(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    hotkeys: BoardHotkeySettings,
+) -> Result<BoardHotkeySettings, CommandError> {
+    reload_hotkeys(&app, &state)?;
+    let global = state.settings.read().await.global_hotkeys.clone();
+    // The previous board hotkeys are replaced, so only pads and global combos count
+    let replaced = BoardHotkeySettings { next: None, previous: None };
+    let normalize = |id: &str, hotkey: Option<String>| -> Result<Option<String>, CommandError> {
+        let Some(hotkey) = hotkey else {
+            return Ok(None);
+        };
+        let binding = HotkeyBinding::new(id, 0, HotkeySequence::parse(&hotkey)?)?;
+        let mut conflicts = state.hotkeys.conflicts(&binding, &HotkeyScope::Global);
+        conflicts.extend(reserved_hotkey_conflicts(&state, &binding, &global, &replaced));
+        if !conflicts.is_empty() {
+            return Err(CommandError::HotkeyConflict {
+                hotkey: binding.sequence.to_string(),
+                conflicts,
+            });
+        }
+        Ok(Some(binding.sequence.to_string()))
+    };
+    let hotkeys = BoardHotkeySettings {
+        next: normalize("next-board", hotkeys.next)?,
+        previous: normalize("previous-board", hotkeys.previous)?,
+    };
+    if hotkeys.next.is_some() && hotkeys.next == hotkeys.previous {
+        return Err(CommandError::InvalidArgument("Next and previous board need different hotkeys".into()));
+    }
+
+    state.settings.write().await.board_hotkeys = hotkeys.clone();
+    persist_settings(&app, &state).await?;
+    apply_global_hotkeys(&app, &state).await;
+
+    tracing::info!(next = ?hotkeys.next, previous = ?hotkeys.previous, "Board hotkeys set");
+    Ok(hotkeys)
+}
+
+// ============================================================================
+// Global Hotkey Commands
+// ============================================================================
+
+/// DTO for the global hotkeys and the ones the OS refused
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalHotkeysDto {
+    #[serde(flatten)]
+    pub settings: GlobalHotkeySettings,
+    /// Combos another app already holds
+    pub unavailable: Vec<String>,
+}
+
+/// Register the global hotkeys of the current settings with the OS
+///
+/// Nothing is registered in safe mode.
+pub async fn apply_global_hotkeys(app: &tauri::AppHandle, state: &AppState) {
+    if state.safe_mode.is_active() {
+        return;
+    }
+    if let Err(e) = reload_hotkeys(app, state) {
+        tracing::warn!(error = %e, "Failed to load the pad hotkeys");
+    }
+    if let Err(e) = prune_global_hotkeys(app, state).await {
+        tracing::warn!(error = %e, "Failed to drop the global hotkeys of removed pads");
+    }
+    let settings = state.settings.read().await;
+    let boards = live_board_hotkeys(state, &settings.board_hotkeys);
+    state.global_hotkeys.apply(app, &settings.global_hotkeys, &boards);
+}
+
+/// Drop the global combos of pads no longer on the board
+///
+/// Returns whether any were dropped, so they need registering again.
+async fn prune_global_hotkeys(app: &tauri::AppHandle, state: &AppState) -> Result<bool, CommandError> {
+    let store = app.store(SOUNDBOARD_STORE)?;
+    // Before the first save there is no board to compare against
+    let Some(pads) = store.get(SOUNDBOARD_KEY) else {
+        return Ok(false);
+    };
+    let pad_ids: std::collections::HashSet<&str> = pads
+        .as_array()
+        .map(|pads| pads.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|pad| pad.get("id")?.as_str())
+        .collect();
+
+    let mut settings = state.settings.write().await;
+    let before = settings.global_hotkeys.pads.len();
+    settings.global_hotkeys.pads.retain(|pad_id, _| pad_ids.contains(pad_id.as_str()));
+    let removed = before - settings.global_hotkeys.pads.len();
+    drop(settings);
+    if removed == 0 {
+        return Ok(false);
+    }
+    state.auto_save.mark_dirty(SaveTarget::Settings);
+    tracing::info!(removed, "Global hotkeys of removed pads dropped");
+    Ok(true)
+}
+
+/// Run what a pressed global hotkey triggers, on the shortcut thread
+///
+/// Pads are played by the frontend, like an in-app press, so variants,
+/// actions and timers behave the same.
+pub fn run_global_hotkey(app: &tauri::AppHandle, shortcut_id: u32) {
+    use tauri::{Emitter, Manager};
+
+    let Some(action) = app.state::<AppState>().global_hotkeys.action(shortcut_id) else {
+        return;
+    };
+    match action {
+        GlobalAction::Pad(pad_id) => {
+            let _ = app.emit("global-hotkey", pad_id);
+        }
+        GlobalAction::NextBoard | GlobalAction::PreviousBoard => {
+            let step = if action == GlobalAction::NextBoard { 1 } else { -1 };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = step_board(&app, &app.state::<AppState>(), step).await {
+                    tracing::warn!(error = %e, "Board switch from a global hotkey failed");
+                }
+            });
+        }
+    }
+}
+
+/// Save the global hotkey settings and re-register them
+async fn save_global_hotkeys(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    settings: GlobalHotkeySettings,
+) -> Result<GlobalHotkeysDto, CommandError> {
+    state.settings.write().await.global_hotkeys = settings.clone();
+    persist_settings(app, state).await?;
+    apply_global_hotkeys(app, state).await;
+    Ok(GlobalHotkeysDto {
+        settings,
+        unavailable: state.global_hotkeys.unavailable(),
+    })
+}
+
+/// Get the global hotkeys, with the combos the OS refused
+#[tauri::command]
+pub async fn get_global_hotkeys(state: State<'_, AppState>) -> Result<GlobalHotkeysDto, CommandError> {
+    Ok(GlobalHotkeysDto {
+        settings: state.settings.read().await.global_hotkeys.clone(),
+        unavailable: state.global_hotkeys.unavailable(),
+    })
+}
+
+/// Bind a pad to a combo that works while other apps have focus
+///
+/// The combo must be one that can't be typed (held Ctrl, Alt or Meta, or a
+/// function key). It is refused with `HOTKEY_CONFLICT` when another pad
+/// uses it, globally or in the app, or when it switches boards. A combo
+/// another app holds is saved but listed as unavailable.
+#[tauri::command]
+pub async fn register_global_hotkey(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+    hotkey: String,
+) -> Result<GlobalHotkeysDto, CommandError> {
+    let sequence = HotkeySequence::parse(&hotkey)?;
+    sequence.global_combo()?;
+    let candidate = HotkeyBinding::new(pad_id.clone(), 0, sequence)?;
+
+    reload_hotkeys(&app, &state)?;
+    let mut conflicts = state.hotkeys.conflicts(&candidate, &HotkeyScope::Global);
+    let (mut global, boards) = {
+        let settings = state.settings.read().await;
+        (settings.global_hotkeys.clone(), settings.board_hotkeys.clone())
+    };
+    conflicts.extend(reserved_hotkey_conflicts(&state, &candidate, &global, &boards));
+    if !conflicts.is_empty() {
+        return Err(CommandError::HotkeyConflict {
+            hotkey: candidate.sequence.to_string(),
+            conflicts,
+        });
+    }
+
+    let normalized = candidate.sequence.to_string();
+    global.pads.insert(pad_id.clone(), normalized.clone());
+    tracing::info!(pad = %pad_id, hotkey = %normalized, "Global hotkey bound");
+    save_global_hotkeys(&app, &state, global).await
+}
+
+/// Remove a pad's global combo
+#[tauri::command]
+pub async fn unregister_global_hotkey(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pad_id: String,
+) -> Result<GlobalHotkeysDto, CommandError> {
+    let mut settings = state.settings.read().await.global_hotkeys.clone();
+    if settings.pads.remove(&pad_id).is_some() {
+        tracing::info!(pad = %pad_id, "Global hotkey unbound");
+    }
+    save_global_hotkeys(&app, &state, settings).await
+}
+
+/// Turn every global hotkey on or off, and whether board switching is global
+#[tauri::command]
+pub async fn set_global_hotkeys_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+    board_switching: Option<bool>,
+) -> Result<GlobalHotkeysDto, CommandError> {
+    let mut settings = state.settings.read().await.global_hotkeys.clone();
+    settings.enabled = enabled;
+    if let Some(board_switching) = board_switching {
+        settings.board_switching = board_switching;
+    }
+    tracing::info!(enabled, board_switching = settings.board_switching, "Global hotkeys set");
+    save_global_hotkeys(&app, &state, settings).await
+}
+
+/// Get the push-to-talk settings
+#[tauri::command]
+pub async fn get_push_to_talk(state: State<'_, AppState>) -> Result<PushToTalkSettings, CommandError> {
+    Ok(state.settings.read().await.push_to_talk.clone())
+}
+
+/// Set the push-to-talk mode, key and release tail
+///
+/// Applies right away: the mic follows the key from the next poll.
+#[tauri::command]
+pub async fn set_push_to_talk(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: PushToTalkSettings,
+) -> Result<(), CommandError> {
+    let combo = KeyCombo::parse(&settings.key).map_err(|e| CommandError::InvalidArgument(e.to_string()))?;
+    if settings.release_ms > PushToTalkSettings::MAX_RELEASE_MS {
+        return Err(CommandError::InvalidArgument(format!(
+            "Release must be at most {} ms",
+            PushToTalkSettings::MAX_RELEASE_MS
+        )));
+    }
+    if settings.mode != PushToTalkMode::Off && !state.push_to_talk.is_supported() {
+        return Err(CommandError::InvalidArgument(
+            "Push-to-talk isn't supported on this platform".to_string(),
+        ));
+    }
+    if settings.mode != PushToTalkMode::Off && !state.push_to_talk.supports(&combo) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Key {combo} can't be used for push-to-talk"
+        )));
+    }
+
+    state.settings.write().await.push_to_talk = settings.clone();
+    persist_settings(&app, &state).await?;
+
+    // Saved in safe mode, applied on the next normal start
+    if !state.safe_mode.is_active() {
+        state
+            .push_to_talk
+            .configure(&app, &state.audio_engine, &state.tally, &settings)
+            .map_err(CommandError::InvalidArgument)?;
+    }
+    tracing::info!(mode = ?settings.mode, key = %settings.key, "Push-to-talk set");
+    Ok(())
+}
+
+// ============================================================================
+// Offline Render Commands
+// ============================================================================
+
+/// Longest mix that can be rendered, in seconds
+const MAX_RENDER_SECS: u32 = 10 * 60;
+
+/// Output settings for an offline render
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RenderSettingsDto {
+    /// Defaults to the configured engine sample rate
+    pub sample_rate: Option<u32>,
+    /// Defaults to the current master volume
+    pub master_volume: Option<f32>,
+    /// 16, 24 or 32 (float); defaults to 16
+    pub bits_per_sample: Option<u16>,
+}
+
+/// DTO describing a rendered file
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderResultDto {
+    pub path: String,
+    pub duration: f64,      // Duration in seconds
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Find the file path of a soundboard sound by id
+fn soundboard_sound_path(app: &tauri::AppHandle, sound_id: &str) -> Option<String> {
+    soundboard_sound(app, sound_id).and_then(|sound| sound.get("path")?.as_str().map(String::from))
+}
+
+/// Whether a soundboard sound is set to loop
+fn soundboard_sound_looping(app: &tauri::AppHandle, sound_id: &str) -> bool {
+    soundboard_sound(app, sound_id).is_some_and(|sound| sound.get("looping").and_then(|looping| looping.as_bool()) == Some(true))
+}
+
+/// Playback speed saved on a soundboard sound, unless it plays as recorded
+fn soundboard_sound_speed(app: &tauri::AppHandle, sound_id: &str) -> Option<PlaybackSpeed> {
+    soundboard_sound(app, sound_id)
+        .and_then(|sound| serde_json::from_value::<PlaybackSpeed>(sound.get("speed")?.clone()).ok())
+        .filter(|speed| !speed.is_normal())
+}
+
+/// Trim and gain saved on a soundboard sound
+fn soundboard_sound_edits(app: &tauri::AppHandle, sound_id: &str) -> SoundEdits {
+    soundboard_sound(app, sound_id).map_or_else(SoundEdits::default, |sound| SoundEdits::from_sound(&sound))
+}
+
+/// Effect inserts of the pad a soundboard sound belongs to
+fn soundboard_sound_inserts(app: &tauri::AppHandle, sound_id: &str) -> Vec<SoundInsert> {
+    let Some(pads) = app.store(SOUNDBOARD_STORE).ok().and_then(|store| store.get(SOUNDBOARD_KEY)) else {
+        return Vec::new();
+    };
+    pads.as_array()
+        .into_iter()
+        .flatten()
+        .find(|pad| pad_has_sound(pad, sound_id))
+        .and_then(|pad| serde_json::from_value::<Vec<SoundInsert>>(pad.get("inserts")?.clone()).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(SoundInsert::is_valid)
+        .collect()
+}
+
+/// Volume saved on a soundboard sound (0.0 - 2.0), 1.0 when unset
+fn soundboard_sound_volume(app: &tauri::AppHandle, sound_id: &str) -> f32 {
+    soundboard_sound(app, sound_id)
+        .and_then(|sound| sound.get("volume")?.as_f64())
+        .map_or(1.0, |volume| (volume as f32).clamp(0.0, 2.0))
+}
+
+/// Mutable access to every sound in the saved pads, including variants and stems
+fn soundboard_sounds_mut(
+    pads: &mut serde_json::Value,
+) -> impl Iterator<Item = &mut serde_json::Map<String, serde_json::Value>> {
+    pads.as_array_mut()
+        .into_iter()
+        .flatten()
+        .flat_map(|pad| {
+            let pad = pad.as_object_mut().into_iter().flat_map(|pad| pad.iter_mut());
+            pad.flat_map(|(key, value)| match key.as_str() {
+                "sound" => vec![value],
+                "variants" | "stems" => value
+                    .as_array_mut()
+                    .map(|entries| entries.iter_mut().filter_map(|entry| entry.get_mut("sound")).collect())
+                    .unwrap_or_default(),
+                _ => Vec::new(),
+            })
+        })
+        .filter_map(|sound| sound.as_object_mut())
+}
+
+/// Mutable access to a sound in the saved pads, including variants and stems
+fn soundboard_sound_mut<'a>(
+    pads: &'a mut serde_json::Value,
+    sound_id: &str,
+) -> Option<&'a mut serde_json::Map<String, serde_json::Value>> {
+    soundboard_sounds_mut(pads).find(|sound| sound.get("id").and_then(|id| id.as_str()) == Some(sound_id))
+}
+
+/// Find a pad saved on the soundboard by id
+fn soundboard_pad(app: &tauri::AppHandle, pad_id: &str) -> Option<serde_json::Value> {
+    let store = app.store(SOUNDBOARD_STORE).ok()?;
+    let pads = store.get(SOUNDBOARD_KEY)?;
+
+    pads.as_array()?
+        .iter()
+        .find(|pad| pad.get("id").and_then(|id| id.as_str()) == Some(pad_id))
+        .cloned()
+}
+
+/// Refuse a trigger of a pad the active scene disarmed
+async fn check_pad_armed(state: &AppState, pad_id: &str) -> Result<(), CommandError> {
+    if state.active_scene.lock().await.is_armed(pad_id) {
+        Ok(())
+    } else {
+        Err(CommandError::PadDisarmed(pad_id.to_string()))
+    }
+}
+
+/// Refuse a play of a sound when every pad it is on is disarmed
+///
+/// Sounds on no pad (previews, cues) always play.
+async fn check_sound_armed(app: &tauri::AppHandle, state: &AppState, sound_id: &str) -> Result<(), CommandError> {
+    let active = state.active_scene.lock().await;
+    if active.disarmed.is_empty() {
+        return Ok(());
+    }
+    let pads = app.store(SOUNDBOARD_STORE)?.get(SOUNDBOARD_KEY).unwrap_or_default();
+    let mut pad_ids = pads
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|pad| pad_has_sound(pad, sound_id))
+        .filter_map(|pad| pad.get("id")?.as_str())
+        .peekable();
+    let first = pad_ids.peek().copied();
+    match first {
+        Some(pad_id) if pad_ids.all(|id| !active.is_armed(id)) => Err(CommandError::PadDisarmed(pad_id.to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// Whether a saved pad plays a sound, as its own, a variant or a stem
+fn pad_has_sound(pad: &serde_json::Value, sound_id: &str) -> bool {
+    pad.get("sound")
+        .into_iter()
+        .chain(nested_sounds(pad, "variants"))
+        .chain(nested_sounds(pad, "stems"))
+        .any(|sound| sound.get("id").and_then(|id| id.as_str()) == Some(sound_id))
+}
+
+/// Sounds of a pad's `variants` or `stems` list
+fn nested_sounds<'a>(pad: &'a serde_json::Value, key: &str) -> impl Iterator<Item = &'a serde_json::Value> {
+    pad.get(key)
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|v| v.get("sound"))
+}
+
+/// Find a sound saved on the soundboard by id, including pad variants and stems
+fn soundboard_sound(app: &tauri::AppHandle, sound_id: &str) -> Option<serde_json::Value> {
+    let store = app.store(SOUNDBOARD_STORE).ok()?;
+    let pads = store.get(SOUNDBOARD_KEY)?;
+
+    pads.as_array()?
+        .iter()
+        .flat_map(|pad| {
+            pad.get("sound")
+                .into_iter()
+                .chain(nested_sounds(pad, "variants"))
+                .chain(nested_sounds(pad, "stems"))
+        })
+        .find(|sound| sound.get("id").and_then(|id| id.as_str()) == Some(sound_id))
+        .cloned()
+}
+
+/// Render a mix of soundboard sounds to a WAV file, faster than real time
+///
+/// All sounds start together and are mixed through the same processors as
+/// the live engine, each with its stored trim, gain, volume and inserts
+/// and converted to the render rate, so the file matches what listeners
+/// would hear.
+#[tauri::command]
+pub async fn render_mix(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    sound_ids: Vec<String>,
+    settings: RenderSettingsDto,
+    path: String,
+) -> Result<RenderResultDto, CommandError> {
+    use crate::adapters::HoundWavEncoder;
+    use crate::application::offline_engine::{bounce_mix, MixSound};
+    use crate::ports::FileEncoder;
+
+    if sound_ids.is_empty() {
+        return Err(CommandError::InvalidArgument("No sounds to render".into()));
+    }
+
+    let sample_rate = match settings.sample_rate {
+        Some(rate) => rate,
+        None => state.settings.read().await.audio.sample_rate,
+    };
+    let master_volume = match settings.master_volume {
+        Some(volume) => volume,
+        None => state.mixer_config.read().await.master_volume,
+    };
+    let bits_per_sample = settings.bits_per_sample.unwrap_or(16);
+    let channels = 2;
+
+    let mut sounds = Vec::with_capacity(sound_ids.len());
+    for (index, sound_id) in sound_ids.iter().enumerate() {
+        let entry = soundboard_sound(&app, sound_id).ok_or_else(|| CommandError::SoundNotFound(sound_id.clone()))?;
+        let sound_path = entry
+            .get("path")
+            .and_then(|p| p.as_str())
+            .ok_or_else(|| CommandError::SoundNotFound(sound_id.clone()))?;
+        let sound = state.decoder.decode(std::path::Path::new(sound_path))?;
+
+        // The same sound may appear more than once in a mix
+        sounds.push(MixSound {
+            id: format!("{}#{}", sound_id, index),
+            buffer: SoundEdits::from_sound(&entry).apply(&sound.buffer),
+            volume: soundboard_sound_volume(&app, sound_id),
+            inserts: soundboard_sound_inserts(&app, sound_id),
+        });
+    }
+
+    let rendered = bounce_mix(sounds, sample_rate, channels, master_volume, MAX_RENDER_SECS);
+    HoundWavEncoder::new()
+        .encode(std::path::Path::new(&path), &rendered, bits_per_sample)
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    let duration = rendered.frame_count() as f64 / sample_rate as f64;
+    tracing::info!("Rendered {} sound(s) to {} ({:.1}s)", sound_ids.len(), path, duration);
+
+    Ok(RenderResultDto {
+        path,
+        duration,
+        sample_rate,
+        channels,
+    })
+}
+
+// ============================================================================
+// Mic Processing Commands
+// ============================================================================
+
+/// Default length of room tone captured for a noise print
+const NOISE_CAPTURE_SECS: f32 = 3.0;
+
+/// DTO describing a learned noise print
+#[derive(Debug, Clone, Serialize)]
+pub struct NoiseProfileDto {
+    pub device: String,
+    pub fft_size: usize,
+    pub seconds: f32,
+}
+
+/// DTO of the mic profile in use
+#[derive(Debug, Clone, Serialize)]
+pub struct MicProfileDto {
+    pub device: String,
+    pub profile: MicProfile,
+    /// The device has no profile of its own and uses the default one
+    pub is_default: bool,
+}
+
+/// Engine commands configuring the mic chain for the current input device
+fn mic_processing_commands(settings: &AppSettings) -> Vec<AudioEngineCommand> {
+    let device = settings.audio.input_device();
+    let profile = settings.mic_profiles.for_device(device);
+    vec![
+        AudioEngineCommand::SetMicVolume(profile.volume),
+        AudioEngineCommand::SetMicHighpass(profile.highpass),
+        AudioEngineCommand::SetEchoCancellation(settings.echo_cancellation),
+        noise_profile_command(settings),
+        AudioEngineCommand::SetMicAgc(Some(profile.agc)),
+        AudioEngineCommand::SetNoiseGate(Some(profile.noise_gate)),
+        AudioEngineCommand::SetKeyboardSuppression(Some(settings.keyboard_suppression)),
+        AudioEngineCommand::SetInputChannelMap(settings.input_channel_maps.get(device).copied().unwrap_or_default()),
+        AudioEngineCommand::SetVoiceChanger(Some(settings.voice_changer)),
+        AudioEngineCommand::SetMicChainLayout(settings.mic_chain.clone()),
+        AudioEngineCommand::SetVoiceActivity(settings.voice_activity),
+        AudioEngineCommand::SetFeedbackProtection(settings.feedback_protection.enabled),
+        AudioEngineCommand::SetMicDucking(settings.audio.ducking),
+    ]
+}
+
+/// Engine command setting the denoiser for the current input device
+fn noise_profile_command(settings: &AppSettings) -> AudioEngineCommand {
+    let device = settings.audio.input_device();
+    AudioEngineCommand::SetNoiseProfile {
+        profile: settings.noise_reduction.active_profile(device).cloned(),
+        quality: settings.spectral_quality,
+    }
+}
+
+/// Send the mic chain settings for the current input device to the engine
+async fn apply_mic_processing(state: &AppState) -> Result<(), CommandError> {
+    let settings = state.settings.read().await;
+    let commands = mic_processing_commands(&settings);
+    set_keystroke_listener(&state.audio_engine, settings.keyboard_suppression.enabled);
+    drop(settings);
+
+    let engine = &state.audio_engine;
+    for command in commands {
+        engine.send_command(command).map_err(CommandError::EngineError)?;
+    }
+    Ok(())
+}
+
+/// The mic profile of the current input device
+async fn current_mic_profile(state: &AppState) -> MicProfile {
+    let settings = state.settings.read().await;
+    let device = settings.audio.input_device();
+    *settings.mic_profiles.for_device(device)
+}
+
+/// Get the mic profile of the current input device, and whether the
+/// device has one of its own rather than the default
+#[tauri::command]
+pub async fn get_mic_profile(state: State<'_, AppState>) -> Result<MicProfileDto, CommandError> {
+    let settings = state.settings.read().await;
+    let device = settings.audio.input_device().to_string();
+    Ok(MicProfileDto {
+        profile: *settings.mic_profiles.for_device(&device),
+        is_default: !settings.mic_profiles.has_device(&device),
+        device,
+    })
+}
+
+/// Make the current input device's profile the one new devices start from
+#[tauri::command]
+pub async fn save_mic_profile_as_default(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    {
+        let mut settings = state.settings.write().await;
+        let device = settings.audio.input_device().to_string();
+        settings.mic_profiles.default = *settings.mic_profiles.for_device(&device);
+    }
+    persist_settings(&app, &state).await?;
+    tracing::info!("Mic profile saved as the default");
+    Ok(())
+}
+
+/// Forget the current input device's profile, going back to the default
+#[tauri::command]
+pub async fn reset_mic_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<MicProfile, CommandError> {
+    {
+        let mut settings = state.settings.write().await;
+        let device = settings.audio.input_device().to_string();
+        settings.mic_profiles.remove(&device);
+    }
+    persist_settings(&app, &state).await?;
+    apply_mic_processing(&state).await?;
+
+    let profile = current_mic_profile(&state).await;
+    let _ = app.emit("mic-profile-changed", profile);
+    Ok(profile)
+}
+
+/// Capture room tone from the input device and learn its noise print
+///
+/// Stay quiet while this runs. The print is stored for the current input
+/// device and spectral denoising is switched on.
+#[tauri::command]
+pub async fn learn_noise_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    seconds: Option<f32>,
+) -> Result<NoiseProfileDto, CommandError> {
+    use crate::adapters::CpalAudioInput;
+    use crate::application::quick_memo::capture_input;
+    use crate::domain::{AudioFormat, DeviceId};
+    use crate::dsp::NOISE_PROFILE_FFT_SIZE;
+    use std::time::Duration;
+
+    // Opens the input device, which safe mode keeps closed
+    if state.safe_mode.is_active() {
+        return Err(CommandError::SafeMode);
+    }
+
+    let seconds = seconds.unwrap_or(NOISE_CAPTURE_SECS).clamp(1.0, 10.0);
+    let (device, sample_rate) = {
+        let settings = state.settings.read().await;
+        let device = settings.audio.input_device().to_string();
+        (device, settings.audio.sample_rate)
+    };
+
+    tracing::info!(device = %device, seconds, "Learning noise print");
+
+    // The cpal stream is !Send, so it must live on the capture thread
+    let capture_device = DeviceId::new(device.clone());
+    let room_tone = tauri::async_runtime::spawn_blocking(move || {
+        capture_input(
+            &mut CpalAudioInput::new(),
+            &capture_device,
+            AudioFormat::new(sample_rate, 2, 16),
+            Duration::from_secs_f32(seconds),
+            || false,
+            |_, _| {},
+        )
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))??;
+
+    let mono = room_tone.convert_channels(1).to_raw_f32();
+    let profile = crate::dsp::learn_noise_profile(&mono, NOISE_PROFILE_FFT_SIZE)
+        .ok_or_else(|| CommandError::InvalidArgument("Not enough room tone was captured".into()))?;
+
+    let dto = NoiseProfileDto {
+        device: device.clone(),
+        fft_size: profile.fft_size,
+        seconds: room_tone.frame_count() as f32 / sample_rate as f32,
+    };
+
+    {
+        let mut settings = state.settings.write().await;
+        settings.noise_reduction.profiles.insert(device, profile);
+        settings.noise_reduction.enabled = true;
+    }
+    persist_settings(&app, &state).await?;
+    apply_mic_processing(&state).await?;
+
+    state.telemetry.record("learn_noise_profile");
+    Ok(dto)
+}
+
+/// Turn learned-noise-print denoising on or off
+#[tauri::command]
+pub async fn set_noise_reduction_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    state.settings.write().await.noise_reduction.enabled = enabled;
+    persist_settings(&app, &state).await?;
+    apply_mic_processing(&state).await?;
+
+    tracing::info!(enabled, "Noise reduction toggled");
+    Ok(())
+}
+
+/// Forget the noise print of the current input device
+#[tauri::command]
+pub async fn clear_noise_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    {
+        let mut settings = state.settings.write().await;
+        let device = settings.audio.input_device().to_string();
+        settings.noise_reduction.profiles.remove(&device);
+    }
+    persist_settings(&app, &state).await?;
+    apply_mic_processing(&state).await
+}
+
+/// Cancel soundboard playback picked up by the mic when monitoring on speakers
+#[tauri::command]
+pub async fn set_echo_cancellation(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    state.settings.write().await.echo_cancellation = enabled;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetEchoCancellation(enabled))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(enabled, "Echo cancellation toggled");
+    Ok(())
+}
+
+/// Get the output bit depth and dithering
+#[tauri::command]
+pub async fn get_output_format(state: State<'_, AppState>) -> Result<OutputFormatSettings, CommandError> {
+    Ok(state.settings.read().await.output_format)
+}
+
+/// Set the output bit depth and whether to dither when rounding to it
+///
+/// Use 16-bit with dither when the virtual cable runs at 16-bit, so quiet
+/// voice material isn't truncated.
+#[tauri::command]
+pub async fn set_output_format(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    format: OutputFormatSettings,
+) -> Result<(), CommandError> {
+    state.settings.write().await.output_format = format;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetOutputFormat(format))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(bit_depth = ?format.bit_depth, dither = format.dither, "Output format set");
+    Ok(())
+}
+
+/// Get the AGC settings of the current input device
+#[tauri::command]
+pub async fn get_mic_agc(state: State<'_, AppState>) -> Result<AgcSettings, CommandError> {
+    let settings = state.settings.read().await;
+    let device = settings.audio.input_device();
+    Ok(settings.mic_profiles.for_device(device).agc)
+}
+
+/// Configure automatic gain control for the current input device
+#[tauri::command]
+pub async fn set_mic_agc(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    agc: AgcSettings,
+) -> Result<(), CommandError> {
+    if agc.max_gain_db < 0.0 || agc.target_db > 0.0 {
+        return Err(CommandError::InvalidArgument(
+            "AGC target must be at most 0 dBFS and max gain at least 0 dB".into(),
+        ));
+    }
+
+    {
+        let mut settings = state.settings.write().await;
+        let device = settings.audio.input_device().to_string();
+        settings.mic_profiles.for_device_mut(&device).agc = agc;
+    }
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetMicAgc(Some(agc)))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(enabled = agc.enabled, target_db = agc.target_db, "Mic AGC updated");
+    Ok(())
+}
+
+/// Get the noise gate settings of the current input device
+#[tauri::command]
+pub async fn get_noise_gate(state: State<'_, AppState>) -> Result<NoiseGateSettings, CommandError> {
+    let settings = state.settings.read().await;
+    let device = settings.audio.input_device();
+    Ok(settings.mic_profiles.for_device(device).noise_gate)
+}
+
+/// Configure the noise gate for the current input device
+#[tauri::command]
+pub async fn set_noise_gate(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    gate: NoiseGateSettings,
+) -> Result<(), CommandError> {
+    if !(-100.0..=0.0).contains(&gate.threshold_db) {
+        return Err(CommandError::InvalidArgument(
+            "Noise gate threshold must be between -100 and 0 dBFS".into(),
+        ));
+    }
+
+    {
+        let mut settings = state.settings.write().await;
+        let device = settings.audio.input_device().to_string();
+        settings.mic_profiles.for_device_mut(&device).noise_gate = gate;
+    }
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetNoiseGate(Some(gate)))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(enabled = gate.enabled, threshold_db = gate.threshold_db, "Noise gate updated");
+    Ok(())
+}
+
+/// Get the keyboard suppression settings
+#[tauri::command]
+pub async fn get_keyboard_suppression(
+    state: State<'_, AppState>,
+) -> Result<KeyboardSuppressionSettings, CommandError> {
+    Ok(state.settings.read().await.keyboard_suppression)
+}
+
+/// Configure the experimental keystroke-timed keyboard suppression
+///
+/// While enabled a system-wide keyboard hook reports when keys go down
+/// (not which keys), and the mic is gated for `window_ms` after each press.
+#[tauri::command]
+pub async fn set_keyboard_suppression(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    suppression: KeyboardSuppressionSettings,
+) -> Result<(), CommandError> {
+    if !suppression.is_valid() {
+        return Err(CommandError::InvalidArgument(
+            "Keyboard suppression depth must be between -40 and 0 dB, its window between 10 and 100 ms".into(),
+        ));
+    }
+
+    state.settings.write().await.keyboard_suppression = suppression;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetKeyboardSuppression(Some(suppression)))
+        .map_err(CommandError::EngineError)?;
+    set_keystroke_listener(&state.audio_engine, suppression.enabled);
+
+    tracing::info!(enabled = suppression.enabled, depth_db = suppression.depth_db, window_ms = suppression.window_ms, "Keyboard suppression updated");
+    Ok(())
+}
+
+/// DTO for the input channel selection of the current input device
+#[derive(Debug, Clone, Serialize)]
+pub struct InputChannelsDto {
+    pub map: InputChannelMap,
+    /// Most channels the device offers, None when unknown
+    pub channel_count: Option<u16>,
+}
+
+/// Most channels the current input device offers
+async fn input_channel_count(state: &AppState, device: &str) -> Result<Option<u16>, CommandError> {
+    use crate::domain::DeviceId;
+
+    let found = state.device_manager.read().await.get_device(&DeviceId::new(device))?;
+    Ok(found.and_then(|device| device.channels().iter().copied().max()))
+}
+
+/// Get which channels of the current input device feed the mic
+#[tauri::command]
+pub async fn get_input_channel_map(state: State<'_, AppState>) -> Result<InputChannelsDto, CommandError> {
+    let settings = state.settings.read().await;
+    let device = settings.audio.input_device().to_string();
+    let map = settings.input_channel_maps.get(&device).copied().unwrap_or_default();
+    drop(settings);
+
+    Ok(InputChannelsDto {
+        map,
+        channel_count: input_channel_count(&state, &device).await?,
+    })
+}
+
+/// Choose which channels of the current input device feed the mic
+///
+/// E.g. channels 3-4 of an 8-channel interface as a stereo pair, or
+/// channel 1 as a mono mic. Restarts mixing when it is running, since the
+/// input stream has to be reopened.
+#[tauri::command]
+pub async fn set_input_channel_map(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    map: InputChannelMap,
+) -> Result<(), CommandError> {
+    let device = state.settings.read().await.audio.input_device().to_string();
+
+    if let (Some(highest), Some(count)) = (map.highest_channel(), input_channel_count(&state, &device).await?) {
+        if highest >= count {
+            return Err(CommandError::InvalidArgument(format!(
+                "Channel {} does not exist, the device has {} channels",
+                highest + 1,
+                count
+            )));
+        }
+    }
+
+    state.settings.write().await.input_channel_maps.insert(device, map);
+    persist_settings(&app, &state).await?;
+
+    // Start sends the map and reopens the input if its channel count changed
+    if *state.is_mixing.read().await {
+        start_mixing(state.clone()).await?;
+    }
+
+    tracing::info!(map = ?map, "Input channel map set");
+    Ok(())
+}
+
+/// Ids of the mixer channels created by podcast mode, host then guest
+const PODCAST_CHANNELS: [(&str, &str); 2] = [("podcast_host", "Host"), ("podcast_guest", "Guest")];
+
+/// Stem names of the podcast mics, as recorded next to the mix
+const PODCAST_STEMS: [&str; 2] = ["host", "guest"];
+
+/// `mic` with the volume and mute of its mixer channel
+fn podcast_mic(mic: PodcastMic, channel: &MixerChannel) -> PodcastMic {
+    PodcastMic {
+        gain: channel.volume(),
+        muted: channel.is_muted(),
+        ..mic
+    }
+}
+
+/// Apply the level of a podcast mixer channel to its mic in the input map
+///
+/// Returns false when `channel` isn't one of the podcast channels.
+async fn apply_podcast_channel(state: &AppState, channel: &MixerChannel) -> Result<bool, CommandError> {
+    let Some(role) = PODCAST_CHANNELS.iter().position(|(id, _)| *id == channel.id()) else {
+        return Ok(false);
+    };
+    let mut settings = state.settings.write().await;
+    let device = settings.audio.input_device().to_string();
+    // Left from podcast mode on another device: no mic to drive here
+    let Some(InputChannelMap::DualMono { host, guest }) = settings.input_channel_maps.get_mut(&device) else {
+        return Ok(true);
+    };
+    let mic = if role == 0 { host } else { guest };
+    *mic = podcast_mic(*mic, channel);
+    let map = settings.input_channel_maps[&device];
+    drop(settings);
+    state.auto_save.mark_dirty(SaveTarget::Settings);
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetInputChannelMap(map))
+        .map_err(CommandError::EngineError)?;
+    Ok(true)
+}
+
+/// Set up two-mic podcast mode on the current input device
+///
+/// Maps two mono mics (host and guest) of one interface to their own
+/// mixer channels, each with independent gain, pan and mute, and records
+/// them as separate stems. The volume and mute of the Host and Guest
+/// mixer channels drive the mics. Channels are zero-based.
+#[tauri::command]
+pub async fn setup_podcast_mode(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    host_channel: u16,
+    guest_channel: u16,
+) -> Result<InputChannelsDto, CommandError> {
+    if host_channel == guest_channel {
+        return Err(CommandError::InvalidArgument("Host and guest need different channels".into()));
+    }
+
+    // Channels kept from an earlier setup bring their levels along
+    let config = state.mixer_config.read().await;
+    let [host, guest] = PODCAST_CHANNELS.map(|(id, _)| config.get_channel(id).cloned());
+    drop(config);
+    let mic = |channel: u16, mixer: Option<MixerChannel>| match mixer {
+        Some(mixer) => podcast_mic(PodcastMic::new(channel), &mixer),
+        None => PodcastMic::new(channel),
+    };
+    let map = InputChannelMap::DualMono {
+        host: mic(host_channel, host),
+        guest: mic(guest_channel, guest),
+    };
+    set_input_channel_map(app, state.clone(), map).await?;
+
+    let mut config = state.mixer_config.write().await;
+    for (id, name) in PODCAST_CHANNELS {
+        if config.get_channel(id).is_none() {
+            config.add_channel(MixerChannel::new(id, name, ChannelType::Microphone));
+        }
+    }
+    drop(config);
+    state.auto_save.mark_dirty(SaveTarget::Mixer);
+
+    tracing::info!(host_channel, guest_channel, "Podcast mode set up");
+    get_input_channel_map(state).await
+}
+
+/// Adjust one podcast mic ("host" or "guest") while live
+#[tauri::command]
+pub async fn set_podcast_mic(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    role: String,
+    mic: PodcastMic,
+) -> Result<(), CommandError> {
+    let mut settings = state.settings.write().await;
+    let device = settings.audio.input_device().to_string();
+    let Some(InputChannelMap::DualMono { host, guest }) = settings.input_channel_maps.get_mut(&device) else {
+        return Err(CommandError::InvalidArgument("Podcast mode is not set up".into()));
+    };
+    let channel_id = match role.as_str() {
+        "host" => {
+            *host = mic;
+            PODCAST_CHANNELS[0].0
+        }
+        "guest" => {
+            *guest = mic;
+            PODCAST_CHANNELS[1].0
+        }
+        other => return Err(CommandError::InvalidArgument(format!("Unknown podcast mic: {}", other))),
+    };
+    let map = settings.input_channel_maps[&device];
+    drop(settings);
+    persist_settings(&app, &state).await?;
+
+    // Keep the mic's mixer channel showing its level
+    if let Some(channel) = state.mixer_config.write().await.get_channel_mut(channel_id) {
+        channel.set_volume(mic.gain);
+        channel.set_muted(mic.muted);
+        state.auto_save.mark_dirty(SaveTarget::Mixer);
+    }
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetInputChannelMap(map))
+        .map_err(CommandError::EngineError)
+}
+
+/// Configure the rumble filter at the start of the mic chain
+#[tauri::command]
+pub async fn set_mic_highpass(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    highpass: HighpassSettings,
+) -> Result<(), CommandError> {
+    if !(HighpassSettings::MIN_CUTOFF_HZ..=HighpassSettings::MAX_CUTOFF_HZ).contains(&highpass.cutoff_hz) {
+        return Err(CommandError::InvalidArgument(format!(
+            "Cutoff must be between {} and {} Hz",
+            HighpassSettings::MIN_CUTOFF_HZ,
+            HighpassSettings::MAX_CUTOFF_HZ
+        )));
+    }
+
+    {
+        let mut settings = state.settings.write().await;
+        let device = settings.audio.input_device().to_string();
+        settings.mic_profiles.for_device_mut(&device).highpass = highpass;
+    }
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetMicHighpass(highpass))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(
+        enabled = highpass.enabled,
+        cutoff_hz = highpass.cutoff_hz,
+        slope = highpass.slope.db_per_octave(),
+        "Mic high-pass updated"
+    );
+    Ok(())
+}
+
+/// DTO describing the spectral quality and the latency it adds
+#[derive(Debug, Clone, Serialize)]
+pub struct SpectralQualityDto {
+    pub quality: SpectralQuality,
+    pub fft_size: usize,
+    pub overlap: usize,
+    pub latency_ms: f32,
+}
+
+fn spectral_quality_dto(settings: &AppSettings) -> SpectralQualityDto {
+    let quality = settings.spectral_quality;
+    let sample_rate = settings.audio.sample_rate.max(1);
+    // The denoiser runs on a worker, whose handshake delays it further
+    let offload_ms = OFFLOAD_LATENCY_FRAMES as f32 * 1000.0 / sample_rate as f32;
+    SpectralQualityDto {
+        quality,
+        fft_size: quality.fft_size(),
+        overlap: quality.overlap(),
+        latency_ms: quality.latency_ms(sample_rate) + offload_ms,
+    }
+}
+
+/// Get the FFT quality of spectral mic effects
+#[tauri::command]
+pub async fn get_spectral_quality(state: State<'_, AppState>) -> Result<SpectralQualityDto, CommandError> {
+    Ok(spectral_quality_dto(&*state.settings.read().await))
+}
+
+/// Choose Low-latency, Balanced or High-quality FFT processing
+///
+/// Spectral effects are rebuilt off the audio thread and swapped in.
+#[tauri::command]
+pub async fn set_spectral_quality(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    quality: SpectralQuality,
+) -> Result<SpectralQualityDto, CommandError> {
+    let (dto, command) = {
+        let mut settings = state.settings.write().await;
+        settings.spectral_quality = quality;
+        (spectral_quality_dto(&settings), noise_profile_command(&settings))
+    };
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(command)
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(?quality, latency_ms = dto.latency_ms, "Spectral quality updated");
+    Ok(dto)
+}
+
+/// List the character voices shipped with the app
+#[tauri::command]
+pub async fn get_builtin_presets() -> Result<Vec<VoicePreset>, CommandError> {
+    Ok(crate::domain::BUILTIN_VOICE_PRESETS.to_vec())
+}
+
+/// Apply a built-in character voice to the mic
+#[tauri::command]
+pub async fn apply_builtin_preset(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<VoiceChangerSettings, CommandError> {
+    let preset = crate::domain::builtin_voice_preset(&id)
+        .ok_or_else(|| CommandError::InvalidArgument(format!("Unknown preset: {}", id)))?;
+
+    set_voice_changer(app, state, preset.settings).await?;
+    Ok(preset.settings)
+}
+
+/// Set the voice changer parameters (all zero for a clean voice)
+#[tauri::command]
+pub async fn set_voice_changer(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    voice_changer: VoiceChangerSettings,
+) -> Result<(), CommandError> {
+    if !voice_changer.is_valid() {
+        return Err(CommandError::InvalidArgument("Voice changer parameters out of range".into()));
+    }
+
+    state.settings.write().await.voice_changer = voice_changer;
+    persist_settings(&app, &state).await?;
+
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetVoiceChanger(Some(voice_changer)))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(?voice_changer, "Voice changer updated");
+    Ok(())
+}
+
+/// Get the order and on/off state of the mic effects
+#[tauri::command]
+pub async fn get_mic_chain(state: State<'_, AppState>) -> Result<Vec<MicEffectNode>, CommandError> {
+    Ok(state.settings.read().await.mic_chain.nodes().to_vec())
+}
+
+/// Move a mic effect, e.g. to run AGC before noise reduction
+#[tauri::command]
+pub async fn move_effect(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    index_from: usize,
+    index_to: usize,
+) -> Result<Vec<MicEffectNode>, CommandError> {
+    update_mic_chain(&app, &state, |chain| chain.move_effect(index_from, index_to)).await
+}
+
+/// Switch a mic effect on or off without losing its settings
+#[tauri::command]
+pub async fn set_effect_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    index: usize,
+    enabled: bool,
+) -> Result<Vec<MicEffectNode>, CommandError> {
+    update_mic_chain(&app, &state, |chain| chain.set_enabled(index, enabled)).await
+}
+
+/// Blend a mic effect with the dry signal (0 = dry, 1 = fully processed)
+#[tauri::command]
+pub async fn set_effect_mix(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    index: usize,
+    mix: f32,
+) -> Result<Vec<MicEffectNode>, CommandError> {
+    update_mic_chain(&app, &state, |chain| chain.set_mix(index, mix)).await
+}
+
+/// Edit the stored chain layout, persist it and send it to the engine
+async fn update_mic_chain(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    edit: impl FnOnce(&mut MicChainLayout) -> Result<(), MicChainError>,
+) -> Result<Vec<MicEffectNode>, CommandError> {
+    let layout = {
+        let mut settings = state.settings.write().await;
+        edit(&mut settings.mic_chain)?;
+        settings.mic_chain.clone()
+    };
+    persist_settings(app, state).await?;
+
+    let nodes = layout.nodes().to_vec();
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetMicChainLayout(layout))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(?nodes, "Mic chain layout updated");
+    Ok(nodes)
+}
+
+/// Bypass the whole mic chain to compare processed and dry mic live
+///
+/// Not persisted: the chain is re-enabled on the next launch.
+#[tauri::command]
+pub async fn set_mic_chain_bypass(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    state
+        .audio_engine
+        .send_command(AudioEngineCommand::SetMicChainBypass(enabled))
+        .map_err(CommandError::EngineError)?;
+
+    tracing::info!(bypassed = enabled, "Mic chain bypass toggled");
+    Ok(())
+}
+
+// ============================================================================
+// Export Commands
+// ============================================================================
+
+/// Output settings for exporting a single sound
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExportSettingsDto {
+    /// Defaults to the sound's own sample rate
+    pub sample_rate: Option<u32>,
+    /// 16 or 24 for FLAC, 16/24/32 for WAV; ignored for OGG. Defaults to 16
+    pub bits_per_sample: Option<u16>,
+}
+
+/// Decode a soundboard sound and re-encode it as WAV, FLAC or OGG
+///
+/// The pad's stored trim (`trimStart`/`trimEnd`, seconds) and gain
+/// (`gainDb`) are applied, so the file matches what the pad plays.
 #[tauri::command]
-pub async fn play_sound(
+pub async fn export_sound(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     id: String,
+    format: String,
     path: String,
-) -> Result<(), String> {
-    use rodio::Source;
-    use std::fs::File;
-    use std::io::BufReader;
+    settings: Option<ExportSettingsDto>,
+) -> Result<RenderResultDto, CommandError> {
+    use crate::adapters::{FlacEncoder, HoundWavEncoder, VorbisEncoder};
+    use crate::domain::AudioFileFormat;
+    use crate::ports::FileEncoder;
+
+    let settings = settings.unwrap_or_default();
+    let encoder: Box<dyn FileEncoder> = match AudioFileFormat::from_extension(&format) {
+        Some(AudioFileFormat::Wav) => Box::new(HoundWavEncoder::new()),
+        Some(AudioFileFormat::Flac) => Box::new(FlacEncoder::new()),
+        Some(AudioFileFormat::Ogg) => Box::new(VorbisEncoder::new()),
+        _ => return Err(CommandError::UnsupportedFormat(format!("Cannot export to {}", format))),
+    };
 
-    // Decode the audio file
-    let file = File::open(&path).map_err(|e| format!("Failed to open file: {}", e))?;
-    let reader = BufReader::new(file);
+    let sound = soundboard_sound(&app, &id).ok_or_else(|| CommandError::SoundNotFound(id.clone()))?;
+    let source = sound
+        .get("path")
+        .and_then(|p| p.as_str())
+        .ok_or_else(|| CommandError::SoundNotFound(id.clone()))?;
+    let decoded = state.decoder.decode(std::path::Path::new(source))?;
+    // Stored pad edits, cut and levelled as the pad plays
+    let mut buffer = SoundEdits::from_sound(&sound).apply(&decoded.buffer);
+    if let Some(sample_rate) = settings.sample_rate.filter(|&rate| rate != buffer.sample_rate()) {
+        // Same band-limited resampler the engine uses, so exports match playback
+        let channels = buffer.channels();
+        let resampler = AudioResampler::new(buffer.sample_rate(), sample_rate, channels as usize);
+        let samples = resampler.process(&buffer.to_raw_f32());
+        buffer = AudioBuffer::from_raw_f32(samples, channels, sample_rate);
+    }
 
-    let decoder = rodio::Decoder::new(reader)
-        .map_err(|e| format!("Failed to decode audio file: {}", e))?;
+    encoder
+        .encode(std::path::Path::new(&path), &buffer, settings.bits_per_sample.unwrap_or(16))?;
 
-    // Get format info
-    let sample_rate = decoder.sample_rate();
-    let channels = decoder.channels();
+    let duration = buffer.frame_count() as f64 / buffer.sample_rate() as f64;
+    tracing::info!("Exported sound {} to {} ({:.1}s)", id, path, duration);
+    state.telemetry.record("export_sound");
 
-    // Collect all samples as f32
-    let samples: Vec<f32> = decoder.convert_samples::<f32>().collect();
-    let samples_len = samples.len();
+    Ok(RenderResultDto {
+        path,
+        duration,
+        sample_rate: buffer.sample_rate(),
+        channels: buffer.channels(),
+    })
+}
+
+// ============================================================================
+// Quick Memo Commands
+// ============================================================================
 
-    if samples.is_empty() {
-        return Err("Audio file contains no samples".to_string());
+/// Record the mic for up to 30 s and save it as a trimmed, normalized WAV
+///
+/// Emits `quick-memo-progress` while recording and `quick-memo-completed`
+/// with the saved sound. Stop early with `stop_quick_memo`.
+#[tauri::command]
+pub async fn quick_memo(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SoundFileDto, CommandError> {
+    use crate::adapters::{CpalAudioInput, HoundWavEncoder};
+    use crate::application::quick_memo::{clean_up_memo, QUICK_MEMO_MAX_SECS};
+    use crate::domain::{AudioFormat, DeviceId};
+    use crate::ports::FileEncoder;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use tauri::{Emitter, Manager};
+
+    // Opens the input device, which safe mode keeps closed
+    if state.safe_mode.is_active() {
+        return Err(CommandError::SafeMode);
     }
 
-    // Send to audio engine
-    let engine = state.audio_engine.lock().await;
-    engine
-        .send_command(AudioEngineCommand::PlaySound { id, samples })
-        .map_err(|e| format!("Failed to play sound: {}", e))?;
+    let (device, sample_rate) = {
+        let settings = state.settings.read().await;
+        let device = settings.audio.input_device().to_string();
+        (device, settings.audio.sample_rate)
+    };
 
-    tracing::info!("Playing sound: {} ({} samples, {}Hz, {} ch)",
-        path, samples_len, sample_rate, channels);
+    let memos_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::StorageError(e.to_string()))?
+        .join("memos");
+    std::fs::create_dir_all(&memos_dir).map_err(|e| CommandError::StorageError(e.to_string()))?;
+
+    tracing::info!(device = %device, "Recording quick memo");
+
+    // The cpal stream is !Send, so it must live on the recording thread
+    let recorder = state.quick_memo.clone();
+    let progress_app = app.clone();
+    let raw = tauri::async_runtime::spawn_blocking(move || {
+        let mut input = CpalAudioInput::new();
+        recorder.record(
+            &mut input,
+            &DeviceId::new(device),
+            AudioFormat::new(sample_rate, 2, 16),
+            Duration::from_secs(QUICK_MEMO_MAX_SECS),
+            |elapsed, level| {
+                let _ = progress_app.emit("quick-memo-progress", serde_json::json!({
+                    "elapsed": elapsed,
+                    "maxSecs": QUICK_MEMO_MAX_SECS,
+                    "level": level,
+                }));
+            },
+        )
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))??;
 
-    Ok(())
-}
+    let memo = clean_up_memo(&raw)?;
 
-/// Preview a sound file on a specific output device
-#[tauri::command]
-pub async fn preview_sound(
-    state: State<'_, AppState>,
-    path: String,
-    device_name: String,
-    pad_id: String,
-) -> Result<(), String> {
-    use crate::application::preview_engine::PreviewCommand;
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let name = format!("memo-{}", stamp);
+    let path = memos_dir.join(format!("{}.wav", name));
+    HoundWavEncoder::new()
+        .encode(&path, &memo, 16)
+        .map_err(|e| CommandError::StorageError(format!("Failed to write {}: {}", path.display(), e)))?;
 
-    let preview = state.preview_engine.lock().await;
-    if let Some(ref engine) = *preview {
-        engine.send_command(PreviewCommand::Play {
-            path,
-            device_name,
-            pad_id,
-        })
-    } else {
-        Err("Preview engine not initialized".to_string())
-    }
-}
+    let sound = SoundFileDto {
+        id: format!("sound_{}", &uuid::Uuid::new_v4().to_string().replace("-", "")[..8]),
+        name,
+        path: path.to_string_lossy().to_string(),
+        duration: memo.frame_count() as f64 / memo.sample_rate() as f64,
+        sample_rate: memo.sample_rate(),
+        channels: memo.channels(),
+    };
 
-/// Stop the currently playing preview
-#[tauri::command]
-pub async fn stop_preview(state: State<'_, AppState>) -> Result<(), String> {
-    use crate::application::preview_engine::PreviewCommand;
+    tracing::info!("Quick memo saved to {} ({:.1}s)", sound.path, sound.duration);
+    state.telemetry.record("quick_memo");
+    let _ = app.emit("quick-memo-completed", &sound);
 
-    let preview = state.preview_engine.lock().await;
-    if let Some(ref engine) = *preview {
-        engine.send_command(PreviewCommand::Stop)
-    } else {
-        Err("Preview engine not initialized".to_string())
-    }
+    Ok(sound)
 }
 
-/// Get the currently previewing pad ID
+/// Stop the quick memo being recorded, keeping what was captured
 #[tauri::command]
-pub async fn get_preview_state(state: State<'_, AppState>) -> Result<Option<String>, String> {
-    let preview = state.preview_engine.lock().await;
-    Ok(preview.as_ref().and_then(|e| e.current_pad_id()))
+pub async fn stop_quick_memo(state: State<'_, AppState>) -> Result<(), CommandError> {
+    if state.quick_memo.is_recording() {
+        state.quick_memo.request_stop();
+    }
+    Ok(())
 }
 
-/// Stop a playing sound
+// ============================================================================
+// Gain Wizard Commands
+// ============================================================================
+
+/// Measure the mic and recommend a mic volume and noise gate threshold
+///
+/// Stay quiet for the first 3 s, then speak normally for 10 s. Emits
+/// `gain-wizard-progress` with the wizard state as it goes. With `apply`,
+/// the recommendation is applied right away; otherwise pass it to
+/// `apply_gain_recommendation` once the user accepts it.
 #[tauri::command]
-pub async fn stop_sound(
+pub async fn start_gain_wizard(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    id: String,
-) -> Result<(), String> {
-    let engine = state.audio_engine.lock().await;
-    engine
-        .send_command(AudioEngineCommand::StopSound { id })
-        .map_err(|e| format!("Failed to stop sound: {}", e))?;
+    apply: Option<bool>,
+) -> Result<GainRecommendation, CommandError> {
+    use crate::adapters::CpalAudioInput;
+    use crate::domain::DeviceId;
+    use tauri::Emitter;
+
+    // Opens the input device, which safe mode keeps closed
+    if state.safe_mode.is_active() {
+        return Err(CommandError::SafeMode);
+    }
 
-    Ok(())
-}
+    let (device, sample_rate) = {
+        let settings = state.settings.read().await;
+        let device = settings.audio.input_device().to_string();
+        (device, settings.audio.sample_rate)
+    };
 
-/// Set microphone volume (0.0 - 2.0)
-#[tauri::command]
-pub async fn set_mic_volume(
-    state: State<'_, AppState>,
-    volume: f32,
-) -> Result<(), String> {
-    let engine = state.audio_engine.lock().await;
-    engine
-        .send_command(AudioEngineCommand::SetMicVolume(volume))
-        .map_err(|e| format!("Failed to set mic volume: {}", e))?;
+    tracing::info!(device = %device, "Starting gain wizard");
 
-    Ok(())
+    // The cpal stream is !Send, so it must live on the capture thread
+    let wizard = state.gain_wizard.clone();
+    let progress_app = app.clone();
+    let recommendation = tauri::async_runtime::spawn_blocking(move || {
+        wizard.run(&mut CpalAudioInput::new(), &DeviceId::new(device), sample_rate, |progress| {
+            let _ = progress_app.emit("gain-wizard-progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| CommandError::Internal(e.to_string()))??;
+
+    tracing::info!(
+        peak_db = recommendation.peak_db,
+        noise_floor_db = recommendation.noise_floor_db,
+        mic_volume = recommendation.mic_volume,
+        gate_threshold_db = recommendation.gate_threshold_db,
+        "Gain wizard finished"
+    );
+
+    if apply.unwrap_or(false) {
+        apply_gain_recommendation(app, state.clone(), recommendation).await?;
+    }
+    state.telemetry.record("gain_wizard");
+    Ok(recommendation)
 }
 
-/// Mute/unmute microphone
+/// Get the state of the gain wizard, including the last result
 #[tauri::command]
-pub async fn set_mic_muted(
-    state: State<'_, AppState>,
-    muted: bool,
-) -> Result<(), String> {
-    let engine = state.audio_engine.lock().await;
-    engine
-        .send_command(AudioEngineCommand::SetMicMuted(muted))
-        .map_err(|e| format!("Failed to set mic muted: {}", e))?;
-
-    Ok(())
+pub async fn get_gain_wizard(state: State<'_, AppState>) -> Result<GainWizardState, CommandError> {
+    Ok(state.gain_wizard.state())
 }
 
-// ============================================================================
-// Soundboard Persistence Commands
-// ============================================================================
-
-const SOUNDBOARD_STORE: &str = "soundboard.json";
-const SOUNDBOARD_KEY: &str = "pads";
-
-/// Save soundboard pads to persistent storage
+/// Stop the running gain wizard without a recommendation
 #[tauri::command]
-pub async fn save_soundboard(
-    app: tauri::AppHandle,
-    pads: serde_json::Value,
-) -> Result<(), String> {
-    let store = app.store(SOUNDBOARD_STORE).map_err(|e| e.to_string())?;
-    store.set(SOUNDBOARD_KEY, pads);
-    store.save().map_err(|e| e.to_string())?;
-    tracing::debug!("Soundboard state saved");
+pub async fn cancel_gain_wizard(state: State<'_, AppState>) -> Result<(), CommandError> {
+    if state.gain_wizard.state().step.is_running() {
+        state.gain_wizard.cancel();
+    }
     Ok(())
 }
 
-/// Load soundboard pads from persistent storage
+/// Apply a gain wizard recommendation: set the mic volume, and switch on
+/// the noise gate of the current input device at the recommended threshold
 #[tauri::command]
-pub async fn load_soundboard(
+pub async fn apply_gain_recommendation(
     app: tauri::AppHandle,
-) -> Result<Option<serde_json::Value>, String> {
-    let store = app.store(SOUNDBOARD_STORE).map_err(|e| e.to_string())?;
-    #[allow(clippy::map_clone)]
-    let pads = store.get(SOUNDBOARD_KEY).map(|v| v.clone());
-    tracing::debug!("Soundboard state loaded: {:?}", pads.is_some());
-    Ok(pads)
+    state: State<'_, AppState>,
+    recommendation: GainRecommendation,
+) -> Result<(), CommandError> {
+    set_mic_volume(state.clone(), recommendation.mic_volume).await?;
+    set_noise_gate(
+        app,
+        state,
+        NoiseGateSettings {
+            enabled: true,
+            threshold_db: recommendation.gate_threshold_db.clamp(-100.0, 0.0),
+        },
+    )
+    .await
 }
 
 // ============================================================================
 // Update Commands
 // ============================================================================
 
-use tauri_plugin_updater::UpdaterExt;
+use tauri_plugin_updater::{Update, UpdaterExt};
+use crate::application::updates::{self, ReleaseNotesSection, UpdateDownloadState};
+
+const UPDATER_STORE: &str = "updater.json";
+const ROLLOUT_BUCKET_KEY: &str = "rollout_bucket";
 
 /// Information about an available update
 #[derive(Debug, Serialize)]
@@ -826,40 +6533,114 @@ pub struct UpdateInfo {
     pub available: bool,
     pub version: Option<String>,
     pub body: Option<String>,
+    pub channel: UpdateChannel,
 }
 
-/// Check if an update is available
-#[tauri::command]
-pub async fn check_for_update(app: tauri::AppHandle) -> Result<UpdateInfo, String> {
-    tracing::info!("Starting update check");
+/// Release notes of an available update, split into sections
+#[derive(Debug, Serialize)]
+pub struct ReleaseNotesDto {
+    pub version: String,
+    pub sections: Vec<ReleaseNotesSection>,
+}
 
-    let updater = match app.updater() {
-        Ok(u) => {
-            tracing::debug!("Updater instance created successfully");
-            u
-        }
+/// Get the stable per-installation rollout bucket (0-99), creating it on first use
+fn rollout_bucket(app: &tauri::AppHandle) -> u8 {
+    let store = match app.store(UPDATER_STORE) {
+        Ok(store) => store,
         Err(e) => {
-            tracing::error!(error = %e, "Failed to create updater instance");
-            return Err(e.to_string());
+            tracing::warn!(error = %e, "Failed to open updater store, using bucket 0");
+            return 0;
         }
     };
 
-    tracing::debug!("Checking for updates from remote endpoint");
+    if let Some(bucket) = store.get(ROLLOUT_BUCKET_KEY).and_then(|v| v.as_u64()) {
+        return bucket.min(99) as u8;
+    }
+
+    let bucket = updates::rollout_bucket_for(uuid::Uuid::new_v4());
+    store.set(ROLLOUT_BUCKET_KEY, serde_json::json!(bucket));
+    if let Err(e) = store.save() {
+        tracing::warn!(error = %e, "Failed to persist rollout bucket");
+    }
+    bucket
+}
+
+/// Check for an update on the configured channel, honoring staged rollouts
+async fn find_update(
+    app: &tauri::AppHandle,
+    channel: UpdateChannel,
+) -> Result<Option<Update>, CommandError> {
+    let endpoint = tauri::Url::parse(updates::channel_endpoint(channel))
+        .map_err(|e| format!("Invalid update endpoint: {}", e))?;
+
+    let updater = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to create updater instance");
+            CommandError::UpdateError(format!("Failed to initialize updater: {}", e))
+        })?;
+
+    tracing::debug!(channel = ?channel, "Checking for updates from remote endpoint");
+
+    let update = updater.check().await.map_err(|e| {
+        tracing::error!(
+            error = %e,
+            error_debug = ?e,
+            current_version = env!("CARGO_PKG_VERSION"),
+            "Update check failed"
+        );
+        CommandError::UpdateError(format!("Update check failed: {}", e))
+    })?;
+
+    let Some(update) = update else {
+        return Ok(None);
+    };
+
+    let rollout = updates::rollout_percentage(&update.raw_json);
+    let bucket = rollout_bucket(app);
+    if !updates::is_in_rollout(bucket, rollout) {
+        tracing::info!(
+            version = %update.version,
+            rollout = ?rollout,
+            bucket = bucket,
+            "Update available but not yet rolled out to this installation"
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(update))
+}
+
+/// Check if an update is available
+#[tauri::command]
+pub async fn check_for_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateInfo, CommandError> {
+    let channel = state.settings.read().await.update_channel;
+    tracing::info!(channel = ?channel, "Starting update check");
 
-    match updater.check().await {
-        Ok(Some(update)) => {
+    match find_update(&app, channel).await? {
+        Some(update) => {
             tracing::info!(
                 version = %update.version,
                 current_version = env!("CARGO_PKG_VERSION"),
                 "Update available"
             );
-            Ok(UpdateInfo {
+            let info = UpdateInfo {
                 available: true,
                 version: Some(update.version.clone()),
                 body: update.body.clone(),
-            })
+                channel,
+            };
+
+            // Fetch the update in the background so installing later is instant
+            state.update_downloader.start(app.clone(), update);
+            Ok(info)
         }
-        Ok(None) => {
+        None => {
             tracing::info!(
                 current_version = env!("CARGO_PKG_VERSION"),
                 "No update available - already on latest version"
@@ -868,51 +6649,66 @@ pub async fn check_for_update(app: tauri::AppHandle) -> Result<UpdateInfo, Strin
                 available: false,
                 version: None,
                 body: None,
+                channel,
             })
         }
-        Err(e) => {
-            tracing::error!(
-                error = %e,
-                error_debug = ?e,
-                current_version = env!("CARGO_PKG_VERSION"),
-                "Update check failed"
-            );
-            // Return error instead of silently failing
-            Err(format!("Update check failed: {}", e))
-        }
     }
 }
 
-/// Download and install an available update, then restart
+/// Get the parsed release notes of the available update, if any
 #[tauri::command]
-pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
-    tracing::info!("Starting update installation");
+pub async fn get_release_notes(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<ReleaseNotesDto>, CommandError> {
+    let channel = state.settings.read().await.update_channel;
 
-    let updater = match app.updater() {
-        Ok(u) => {
-            tracing::debug!("Updater instance created for installation");
-            u
-        }
-        Err(e) => {
-            tracing::error!(error = %e, "Failed to create updater instance for installation");
-            return Err(format!("Failed to initialize updater: {}", e));
-        }
-    };
+    Ok(find_update(&app, channel).await?.map(|update| ReleaseNotesDto {
+        version: update.version.clone(),
+        sections: updates::parse_release_notes(update.body.as_deref().unwrap_or_default()),
+    }))
+}
+
+/// Select the release channel used for updates
+#[tauri::command]
+pub async fn set_update_channel(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    channel: UpdateChannel,
+) -> Result<(), CommandError> {
+    {
+        let mut settings = state.settings.write().await;
+        settings.update_channel = channel;
+    }
+    persist_settings(&app, &state).await?;
+
+    tracing::info!(channel = ?channel, "Update channel changed");
+    Ok(())
+}
 
-    tracing::debug!("Checking for update before installation");
+/// Download and install an available update, then restart
+#[tauri::command]
+pub async fn install_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let channel = state.settings.read().await.update_channel;
+    tracing::info!(channel = ?channel, "Starting update installation");
+
+    // Install the background download if it already completed
+    if state.update_downloader.install_pending()? {
+        tracing::info!("Update installed successfully, restarting application");
+        app.restart();
+    }
 
-    let update = match updater.check().await {
-        Ok(Some(update)) => {
+    let update = match find_update(&app, channel).await? {
+        Some(update) => {
             tracing::info!(version = %update.version, "Update found, proceeding with download");
             update
         }
-        Ok(None) => {
+        None => {
             tracing::warn!("No update available when trying to install");
-            return Err("No update available".to_string());
-        }
-        Err(e) => {
-            tracing::error!(error = %e, "Failed to check for update during installation");
-            return Err(format!("Failed to check for update: {}", e));
+            return Err(CommandError::UpdateError("No update available".into()));
         }
     };
 
@@ -950,11 +6746,156 @@ pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
                 error_debug = ?e,
                 "Failed to download and install update"
             );
-            Err(format!("Failed to install update: {}", e))
+            Err(CommandError::UpdateError(format!("Failed to install update: {}", e)))
+        }
+    }
+}
+
+/// Get the state of the background update download
+#[tauri::command]
+pub async fn get_update_download_state(
+    state: State<'_, AppState>,
+) -> Result<UpdateDownloadState, CommandError> {
+    Ok(state.update_downloader.state())
+}
+
+/// Defer installing downloaded updates until the app quits
+#[tauri::command]
+pub async fn set_install_on_quit(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    {
+        let mut settings = state.settings.write().await;
+        settings.install_on_quit = enabled;
+    }
+    persist_settings(&app, &state).await?;
+
+    tracing::info!(enabled = enabled, "Install on quit changed");
+    Ok(())
+}
+
+// ============================================================================
+// Onboarding Commands
+// ============================================================================
+
+/// DTO for onboarding progress
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingStateDto {
+    pub completed: Vec<OnboardingStep>,
+    pub current_step: Option<OnboardingStep>,
+    pub finished: bool,
+}
+
+impl From<&OnboardingState> for OnboardingStateDto {
+    fn from(state: &OnboardingState) -> Self {
+        let progress = state.progress();
+        Self {
+            completed: progress.completed,
+            current_step: progress.current_step,
+            finished: progress.finished,
         }
     }
 }
 
+/// Get first-run onboarding progress
+#[tauri::command]
+pub async fn get_onboarding_state(state: State<'_, AppState>) -> Result<OnboardingStateDto, CommandError> {
+    Ok(OnboardingStateDto::from(&state.onboarding.state()))
+}
+
+/// Mark an onboarding step as completed
+#[tauri::command]
+pub async fn complete_onboarding_step(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    step: OnboardingStep,
+) -> Result<OnboardingStateDto, CommandError> {
+    let onboarding = state.onboarding.complete_step(&app, step)?;
+    Ok(OnboardingStateDto::from(&onboarding))
+}
+
+// ============================================================================
+// Telemetry Commands
+// ============================================================================
+
+/// DTO for telemetry status
+#[derive(Debug, Serialize)]
+pub struct TelemetryStatusDto {
+    pub enabled: bool,
+    pub endpoint_configured: bool,
+    pub last_upload: Option<u64>,
+}
+
+/// Get telemetry opt-in status
+#[tauri::command]
+pub async fn get_telemetry_status(state: State<'_, AppState>) -> Result<TelemetryStatusDto, CommandError> {
+    let enabled = state.settings.read().await.telemetry_enabled;
+    Ok(TelemetryStatusDto {
+        enabled,
+        endpoint_configured: crate::infrastructure::telemetry_endpoint().is_some(),
+        last_upload: state.telemetry.last_upload(),
+    })
+}
+
+/// Opt in or out of anonymous usage telemetry
+#[tauri::command]
+pub async fn set_telemetry_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    {
+        let mut settings = state.settings.write().await;
+        settings.telemetry_enabled = enabled;
+    }
+    persist_settings(&app, &state).await?;
+
+    tracing::info!(enabled = enabled, "Telemetry opt-in changed");
+    Ok(())
+}
+
+/// Get the exact telemetry report that would be uploaded
+#[tauri::command]
+pub async fn get_telemetry_report(state: State<'_, AppState>) -> Result<TelemetryReport, CommandError> {
+    Ok(state.telemetry.report())
+}
+
+// ============================================================================
+// Tally Light Commands
+// ============================================================================
+
+/// Get the "on air" tally light configuration
+#[tauri::command]
+pub async fn get_tally_settings(state: State<'_, AppState>) -> Result<TallySettings, CommandError> {
+    Ok(state.settings.read().await.tally.clone())
+}
+
+/// Configure the "on air" tally light and open its device
+#[tauri::command]
+pub async fn set_tally_settings(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: TallySettings,
+) -> Result<(), CommandError> {
+    state.tally.configure(&settings)?;
+    {
+        let mut current = state.settings.write().await;
+        current.tally = settings;
+    }
+    persist_settings(&app, &state).await?;
+
+    tracing::info!("Tally light settings updated");
+    Ok(())
+}
+
+/// Check if the tally light is currently lit
+#[tauri::command]
+pub async fn is_on_air(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    Ok(state.tally.is_on_air())
+}
+
 // ============================================================================
 // Debug Configuration
 // ============================================================================
@@ -983,10 +6924,10 @@ pub fn get_debug_mode(app: tauri::AppHandle) -> bool {
 
 /// Toggle debug mode and persist the setting
 #[tauri::command]
-pub fn set_debug_mode(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
-    let store = app.store(DEBUG_STORE).map_err(|e| e.to_string())?;
+pub fn set_debug_mode(app: tauri::AppHandle, enabled: bool) -> Result<(), CommandError> {
+    let store = app.store(DEBUG_STORE)?;
     store.set(DEBUG_MODE_KEY, serde_json::json!(enabled));
-    store.save().map_err(|e| e.to_string())?;
+    store.save()?;
 
     tracing::info!(enabled = enabled, "Debug mode toggled");
     Ok(())
@@ -999,3 +6940,25 @@ pub fn get_sentry_dsn() -> Option<String> {
         .ok()
         .filter(|s| !s.is_empty())
 }
+
+/// Get the build and runtime environment, for the About dialog and bug
+/// reports
+#[tauri::command]
+pub async fn get_app_info(state: State<'_, AppState>) -> Result<AppInfo, CommandError> {
+    Ok(app_info(&state).await)
+}
+
+async fn app_info(state: &AppState) -> AppInfo {
+    let settings = state.settings.read().await;
+    let device_manager = state.device_manager.read().await;
+    let engine = &state.audio_engine;
+    AppInfo::collect(
+        engine.stream_setup().as_ref(),
+        &settings.audio,
+        device_manager.cached_devices(),
+        engine.device_sample_rate(),
+        &settings.virtual_devices,
+        settings.update_channel,
+        state.safe_mode.is_active(),
+    )
+}
@@ -0,0 +1,106 @@
+//! Correlation - Following one user action through the logs
+//!
+//! Every command runs in a `command` span with a fresh correlation id.
+//! Messages to and from the engine thread carry the span they were sent
+//! from, so a pad click, its decode, the engine play and the end of the
+//! sound all log under the same `cid`.
+
+use crossbeam_channel::{bounded, Receiver, SendError, Sender, TrySendError};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::Span;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Id shared by the log lines of one command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CorrelationId(u64);
+
+impl CorrelationId {
+    pub fn next() -> Self {
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:06x}", self.0)
+    }
+}
+
+/// Span wrapping one invocation of the command `name`
+pub fn command_span(name: &str) -> Span {
+    tracing::info_span!("command", name, cid = %CorrelationId::next())
+}
+
+/// A message with the span it was sent from
+#[derive(Debug)]
+pub struct Traced<T> {
+    pub span: Span,
+    pub value: T,
+}
+
+/// Sending half of a channel that records the sender's current span
+#[derive(Debug)]
+pub struct TracedSender<T>(Sender<Traced<T>>);
+
+impl<T> TracedSender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.0
+            .send(Traced {
+                span: Span::current(),
+                value,
+            })
+            .map_err(|SendError(traced)| SendError(traced.value))
+    }
+
+    /// Send without waiting on a full channel
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.0
+            .try_send(Traced {
+                span: Span::current(),
+                value,
+            })
+            .map_err(|error| match error {
+                TrySendError::Full(traced) => TrySendError::Full(traced.value),
+                TrySendError::Disconnected(traced) => TrySendError::Disconnected(traced.value),
+            })
+    }
+}
+
+impl<T> Clone for TracedSender<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// Bounded channel whose messages carry the sender's span
+pub fn traced_channel<T>(capacity: usize) -> (TracedSender<T>, Receiver<Traced<T>>) {
+    let (tx, rx) = bounded(capacity);
+    (TracedSender(tx), rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_are_unique() {
+        let a = CorrelationId::next();
+        let b = CorrelationId::next();
+        assert_ne!(a, b);
+        assert_eq!(CorrelationId(42).to_string(), "00002a");
+    }
+
+    #[test]
+    fn test_messages_keep_their_order() {
+        let (tx, rx) = traced_channel(4);
+        tx.send(1).unwrap();
+        tx.clone().send(2).unwrap();
+        let values: Vec<i32> = rx.try_iter().map(|traced| traced.value).collect();
+        assert_eq!(values, vec![1, 2]);
+
+        drop(rx);
+        assert_eq!(tx.send(3), Err(SendError(3)));
+    }
+}
@@ -0,0 +1,285 @@
+//! Decoder service - Single entry point for decoding sound files
+//!
+//! Commands, the preview engine and offline rendering all decode through
+//! this service, so format policy and caching live in one place.
+
+use crate::adapters::SymphoniaDecoderFactory;
+use crate::application::sound_cache::SoundCache;
+use crate::application::sound_stream::{SoundStream, StreamFormat, STREAM_MIN_SECS};
+use crate::domain::{AudioBuffer, AudioFileFormat, AudioFormat};
+use crate::ports::{AudioFileMetadata, FileDecoderError, FileDecoderFactory};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A fully decoded sound
+#[derive(Debug, Clone)]
+pub struct DecodedSound {
+    pub metadata: AudioFileMetadata,
+    pub buffer: AudioBuffer,
+}
+
+/// Service owning the decoder factory and the decoded-sound cache
+pub struct DecoderService {
+    factory: Box<dyn FileDecoderFactory>,
+    cache: SoundCache,
+}
+
+impl DecoderService {
+    pub fn new() -> Self {
+        Self::with_factory(Box::new(SymphoniaDecoderFactory::new()))
+    }
+
+    pub fn with_factory(factory: Box<dyn FileDecoderFactory>) -> Self {
+        Self {
+            factory,
+            cache: SoundCache::default(),
+        }
+    }
+
+    /// Check that a file exists and is in a supported format
+    pub fn check_format(&self, path: &Path) -> Result<AudioFileFormat, FileDecoderError> {
+        if !path.exists() {
+            return Err(FileDecoderError::FileNotFound(path.display().to_string()));
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| FileDecoderError::InvalidFile("No file extension".into()))?;
+
+        AudioFileFormat::from_extension(extension)
+            .filter(|format| self.factory.supports_format(*format))
+            .ok_or_else(|| FileDecoderError::UnsupportedFormat(extension.to_string()))
+    }
+
+    /// Read a file's metadata, decoding only the first block
+    pub fn probe(&self, path: &Path) -> Result<AudioFileMetadata, FileDecoderError> {
+        let format = self.check_format(path)?;
+        if let Some(sound) = self.cache.get(path) {
+            return Ok(sound.metadata.clone());
+        }
+
+        let mut decoder = self.factory.create_decoder(path)?;
+        let opened = decoder.metadata();
+        let first = decoder.read_next()?.ok_or_else(empty_file)?;
+        let metadata = AudioFileMetadata {
+            format,
+            duration: decoder.duration().unwrap_or(Duration::ZERO),
+            audio_format: AudioFormat::new(first.sample_rate(), first.channels(), 16),
+            title: opened.as_ref().and_then(|m| m.title.clone()),
+            artist: opened.and_then(|m| m.artist),
+        };
+        decoder.close();
+
+        Ok(metadata)
+    }
+
+    /// Decode a whole file, reusing the cached result if the file is unchanged
+    ///
+    /// Files long enough to be streamed are decoded but never cached.
+    pub fn decode(&self, path: &Path) -> Result<Arc<DecodedSound>, FileDecoderError> {
+        let format = self.check_format(path)?;
+        if let Some(sound) = self.cache.get(path) {
+            return Ok(sound);
+        }
+
+        let mut decoder = self.factory.create_decoder(path)?;
+        let opened = decoder.metadata();
+        let first = decoder.read_next()?.ok_or_else(empty_file)?;
+        let (channels, sample_rate) = (first.channels(), first.sample_rate());
+
+        let mut samples = first.samples().to_vec();
+        while let Some(buffer) = decoder.read_next()? {
+            samples.extend_from_slice(buffer.samples());
+        }
+        decoder.close();
+
+        let buffer = AudioBuffer::new(samples, channels, sample_rate);
+        // Containers don't always report a duration, the decoded audio does
+        let duration = Duration::from_secs_f64(buffer.frame_count() as f64 / sample_rate as f64);
+        let metadata = AudioFileMetadata {
+            format,
+            duration,
+            audio_format: AudioFormat::new(sample_rate, channels, 16),
+            title: opened.as_ref().and_then(|m| m.title.clone()),
+            artist: opened.and_then(|m| m.artist),
+        };
+        let sound = Arc::new(DecodedSound { metadata, buffer });
+
+        if duration.as_secs_f64() < STREAM_MIN_SECS {
+            self.cache.insert(path, sound.clone());
+        }
+
+        tracing::debug!("Decoded {} ({:.1}s)", path.display(), duration.as_secs_f64());
+        Ok(sound)
+    }
+
+    /// Whether a file is long enough to be streamed instead of decoded whole
+    pub fn should_stream(&self, path: &Path) -> Result<bool, FileDecoderError> {
+        Ok(self.probe(path)?.duration.as_secs_f64() >= STREAM_MIN_SECS)
+    }
+
+    /// Start decoding a file on its own thread, converted to `format`
+    ///
+    /// Returns once enough is decoded for the play to start. Streamed
+    /// sounds never enter the cache.
+    pub fn stream(&self, path: &Path, format: StreamFormat) -> Result<SoundStream, FileDecoderError> {
+        self.check_format(path)?;
+        let decoder = self.factory.create_decoder(path)?;
+        let stream = SoundStream::spawn(decoder, format)?;
+        tracing::debug!("Streaming {}", path.display());
+        Ok(stream)
+    }
+
+    /// Decode files into the cache ahead of their first press, while
+    /// they fit without evicting anything; the number newly cached
+    ///
+    /// Files long enough to be streamed are skipped.
+    pub fn preload(&self, paths: &[&Path]) -> usize {
+        let mut loaded = 0;
+        for path in paths {
+            if self.cache.contains(path) {
+                continue;
+            }
+            let Ok(metadata) = self.probe(path) else {
+                continue;
+            };
+            let duration = metadata.duration.as_secs_f64();
+            let format = metadata.audio_format;
+            // Decoded as f32 at the file's own rate and channels
+            let bytes = (duration * format.sample_rate as f64 * format.channels as f64) as u64 * 4;
+            if duration >= STREAM_MIN_SECS || !self.cache.has_room(bytes) {
+                continue;
+            }
+            match self.decode(path) {
+                Ok(_) => loaded += 1,
+                Err(e) => tracing::debug!(error = %e, "Failed to preload {}", path.display()),
+            }
+        }
+        loaded
+    }
+
+    /// Change the cache size limit, evicting what no longer fits
+    pub fn set_cache_limit(&self, limit_mb: u32) {
+        self.cache.set_limit(limit_mb);
+    }
+
+    /// Drop a file from the cache
+    pub fn evict(&self, path: &Path) {
+        self.cache.remove(path);
+    }
+
+    /// Drop every cached sound
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// Number of cached sounds
+    pub fn cached_count(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Memory held by the decoded samples of the cached sounds, in bytes
+    pub fn cached_bytes(&self) -> u64 {
+        self.cache.bytes()
+    }
+}
+
+impl Default for DecoderService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn empty_file() -> FileDecoderError {
+    FileDecoderError::InvalidFile("Audio file contains no samples".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write_test_wav(name: &str, frames: usize) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..frames * 2 {
+            writer.write_sample(1000i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_check_format_policy() {
+        let service = DecoderService::new();
+        assert!(matches!(
+            service.check_format(Path::new("/nonexistent/sound.mp3")),
+            Err(FileDecoderError::FileNotFound(_))
+        ));
+
+        let path = std::env::temp_dir().join("decoder_service_policy.txt");
+        std::fs::write(&path, b"not audio").unwrap();
+        assert!(matches!(
+            service.check_format(&path),
+            Err(FileDecoderError::UnsupportedFormat(_))
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_decode_is_cached() {
+        let path = write_test_wav("decoder_service_cache.wav", 4410);
+        let service = DecoderService::new();
+
+        let first = service.decode(&path).unwrap();
+        assert_eq!(first.buffer.frame_count(), 4410);
+        assert_eq!(first.metadata.audio_format.channels, 2);
+        assert_eq!(first.metadata.duration, Duration::from_millis(100));
+        assert_eq!(service.cached_count(), 1);
+        assert_eq!(service.cached_bytes(), 4410 * 2 * 4);
+
+        let second = service.decode(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        service.evict(&path);
+        assert_eq!(service.cached_count(), 0);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_streamable_files_are_not_cached() {
+        let path = write_test_wav("decoder_service_long.wav", (STREAM_MIN_SECS * 44100.0) as usize);
+        let service = DecoderService::new();
+
+        assert!(service.should_stream(&path).unwrap());
+        let sound = service.decode(&path).unwrap();
+        assert_eq!(sound.metadata.duration.as_secs_f64(), STREAM_MIN_SECS);
+        assert_eq!(service.cached_count(), 0);
+        assert!(service.should_stream(&path).unwrap());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_preload_caches_what_fits() {
+        let path = write_test_wav("decoder_service_preload.wav", 4410);
+        let service = DecoderService::new();
+
+        assert_eq!(service.preload(&[path.as_path(), Path::new("/nonexistent/sound.wav")]), 1);
+        assert_eq!(service.cached_count(), 1);
+        // Already cached
+        assert_eq!(service.preload(&[path.as_path()]), 0);
+
+        service.clear_cache();
+        service.set_cache_limit(0);
+        assert_eq!(service.preload(&[path.as_path()]), 0);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,67 @@
+//! Device watcher - Keeps the shared device cache in sync with the hardware
+//!
+//! cpal has no hot-plug notifications, so the watcher polls the (cheap)
+//! device name list and only re-enumerates capabilities when it changes.
+
+use crate::adapters::CpalDeviceManager;
+use crate::application::commands::AudioDeviceDto;
+use crate::application::startup::{part_ready, BackendPart};
+use crate::ports::{DeviceManager, DeviceManagerError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+
+/// How often device names are polled for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Re-enumerate devices and notify the frontend with the new list
+pub fn refresh_device_cache(
+    app: &AppHandle,
+    manager: &mut CpalDeviceManager,
+) -> Result<Vec<AudioDeviceDto>, DeviceManagerError> {
+    manager.refresh()?;
+    let devices: Vec<AudioDeviceDto> = manager
+        .list_devices()?
+        .into_iter()
+        .map(AudioDeviceDto::from)
+        .collect();
+
+    let _ = app.emit("devices-changed", &devices);
+    Ok(devices)
+}
+
+/// Start the hot-plug watcher thread
+///
+/// The first poll populates the cache, so list commands stop enumerating
+/// hardware shortly after startup, and reports the devices ready even if
+/// it failed (commands then enumerate on demand).
+pub fn spawn_device_watcher(
+    app: AppHandle,
+    manager: Arc<RwLock<CpalDeviceManager>>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut last_names = None;
+        let mut first_poll = true;
+
+        loop {
+            let names = CpalDeviceManager::device_names();
+            if last_names.as_ref() != Some(&names) {
+                let mut manager = manager.blocking_write();
+                match refresh_device_cache(&app, &mut manager) {
+                    Ok(devices) => {
+                        tracing::info!(count = devices.len(), "Device list refreshed");
+                        last_names = Some(names);
+                    }
+                    Err(e) => tracing::warn!(error = %e, "Failed to refresh devices"),
+                }
+            }
+            if first_poll {
+                first_poll = false;
+                part_ready(&app, BackendPart::Devices);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    })
+}
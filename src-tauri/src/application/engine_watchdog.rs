@@ -0,0 +1,230 @@
+//! Engine watchdog - Detects audio callbacks that stopped being called
+//!
+//! Each stream callback stamps a heartbeat. A watchdog thread checks the
+//! stamps; when a stream has been silent for over a second while the
+//! engine runs (typically a hung driver), it reports the stall, records it
+//! for diagnostics and asks the engine thread to rebuild the streams.
+
+use crate::application::audio_engine::{AudioEngineCommand, AudioEngineEvent};
+use crate::application::correlation::TracedSender;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Silence after which a stream counts as stalled
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the watchdog checks the heartbeats
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Rebuilds attempted per start before giving up
+pub const MAX_REBUILDS: u32 = 3;
+
+/// Stalls kept for diagnostics
+const MAX_STALL_RECORDS: usize = 20;
+
+/// Time of the last callback of a stream
+pub struct Heartbeat {
+    epoch: Instant,
+    /// Milliseconds from `epoch` to the last beat
+    last_ms: AtomicU64,
+}
+
+impl Heartbeat {
+    /// A heartbeat that last beat now
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            last_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Stamp the current time; called from the audio callback
+    pub fn beat(&self) {
+        self.last_ms
+            .store(self.epoch.elapsed().as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Time between the last beat and `now`
+    pub fn silent_for(&self, now: Instant) -> Duration {
+        let last = self.epoch + Duration::from_millis(self.last_ms.load(Ordering::Relaxed));
+        now.saturating_duration_since(last)
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which stream stopped calling back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StalledStream {
+    Input,
+    Output,
+}
+
+/// Heartbeats of the streams of one engine start
+#[derive(Default)]
+pub struct StreamHeartbeats {
+    pub input: Heartbeat,
+    pub output: Heartbeat,
+}
+
+impl StreamHeartbeats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The stream silent for longer than `timeout` at `now`, output first
+    pub fn stalled(&self, now: Instant, timeout: Duration) -> Option<(StalledStream, Duration)> {
+        [(StalledStream::Output, &self.output), (StalledStream::Input, &self.input)]
+            .into_iter()
+            .map(|(stream, heartbeat)| (stream, heartbeat.silent_for(now)))
+            .find(|(_, silent)| *silent > timeout)
+    }
+}
+
+/// A detected stall
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StallRecord {
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub stream: StalledStream,
+    /// How long the stream had been silent when detected
+    pub silent_ms: u64,
+    pub input_device: String,
+    pub output_device: String,
+    pub sample_rate: u32,
+    /// Whether a rebuild was attempted
+    pub rebuilt: bool,
+}
+
+/// Stalls seen since the app started
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WatchdogDiagnostics {
+    /// Most recent stalls, oldest first
+    pub stalls: VecDeque<StallRecord>,
+    /// Rebuilds since the last start requested by the user
+    pub rebuilds: u32,
+}
+
+impl WatchdogDiagnostics {
+    fn record(&mut self, record: StallRecord) {
+        if self.stalls.len() == MAX_STALL_RECORDS {
+            self.stalls.pop_front();
+        }
+        self.stalls.push_back(record);
+    }
+}
+
+/// Devices and format of the running streams, for the stall record
+#[derive(Debug, Clone)]
+pub struct WatchedStreams {
+    pub input_device: String,
+    pub output_device: String,
+    pub sample_rate: u32,
+}
+
+/// Watch the heartbeats until `active` is cleared or a stall is handled
+///
+/// On a stall, the watchdog emits [`AudioEngineEvent::Stalled`] and sends
+/// [`AudioEngineCommand::RebuildStreams`], which starts a new watchdog, so
+/// each one handles at most one stall.
+pub fn spawn_watchdog(
+    heartbeats: Arc<StreamHeartbeats>,
+    active: Arc<AtomicBool>,
+    streams: WatchedStreams,
+    diagnostics: Arc<Mutex<WatchdogDiagnostics>>,
+    command_tx: TracedSender<AudioEngineCommand>,
+    event_tx: TracedSender<AudioEngineEvent>,
+) {
+    let spawned = thread::Builder::new().name("engine-watchdog".into()).spawn(move || {
+        while active.load(Ordering::Relaxed) {
+            thread::sleep(CHECK_INTERVAL);
+            if !active.load(Ordering::Relaxed) {
+                break;
+            }
+            let Some((stream, silent)) = heartbeats.stalled(Instant::now(), STALL_TIMEOUT) else {
+                continue;
+            };
+
+            let rebuild = {
+                let mut diagnostics = diagnostics.lock().unwrap();
+                let rebuild = diagnostics.rebuilds < MAX_REBUILDS;
+                if rebuild {
+                    diagnostics.rebuilds += 1;
+                }
+                diagnostics.record(StallRecord {
+                    timestamp: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_millis() as u64)
+                        .unwrap_or_default(),
+                    stream,
+                    silent_ms: silent.as_millis() as u64,
+                    input_device: streams.input_device.clone(),
+                    output_device: streams.output_device.clone(),
+                    sample_rate: streams.sample_rate,
+                    rebuilt: rebuild,
+                });
+                rebuild
+            };
+
+            tracing::error!(
+                stream = ?stream,
+                silent_ms = silent.as_millis() as u64,
+                input = %streams.input_device,
+                output = %streams.output_device,
+                rebuild,
+                "Audio callbacks stalled"
+            );
+            let _ = event_tx.send(AudioEngineEvent::Stalled {
+                stream,
+                silent_ms: silent.as_millis() as u64,
+                rebuilding: rebuild,
+            });
+            if rebuild {
+                let _ = command_tx.send(AudioEngineCommand::RebuildStreams);
+            }
+            break;
+        }
+    });
+
+    if let Err(e) = spawned {
+        tracing::warn!(error = %e, "Failed to start the engine watchdog");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silent_stream_is_reported() {
+        let heartbeats = StreamHeartbeats::new();
+        let start = Instant::now();
+        assert_eq!(heartbeats.stalled(start, STALL_TIMEOUT), None);
+
+        // Both went quiet; the output is reported first
+        let later = start + Duration::from_millis(1500);
+        let (stream, silent) = heartbeats.stalled(later, STALL_TIMEOUT).unwrap();
+        assert_eq!(stream, StalledStream::Output);
+        assert!(silent >= Duration::from_millis(1400));
+
+        // The output last beat 1.2 s in, so only the input is stalled
+        let heartbeats = StreamHeartbeats {
+            output: Heartbeat {
+                epoch: start,
+                last_ms: AtomicU64::new(1200),
+            },
+            ..StreamHeartbeats::new()
+        };
+        assert_eq!(heartbeats.stalled(later, STALL_TIMEOUT).unwrap().0, StalledStream::Input);
+        assert_eq!(heartbeats.stalled(later, Duration::from_secs(2)), None);
+    }
+}
@@ -0,0 +1,477 @@
+//! Command errors - Uniform error type returned by every Tauri command
+//!
+//! Errors serialize as
+//! `{ "code": "DEVICE_NOT_FOUND", "params": { "device": "..." }, "message": "..." }`.
+//! Codes and parameter names are stable so the frontend can branch on them
+//! and fill in localized messages; the message is an English fallback for
+//! logs and debugging.
+
+use crate::application::audio_engine::is_device_in_use;
+use crate::application::board_share::BoardShareError;
+use crate::application::cloud_sync::SyncError;
+use crate::application::gain_wizard::GainWizardError;
+use crate::application::hotkey_registry::{HotkeyClash, HotkeyRegistryError};
+use crate::application::now_playing::OverlayError;
+use crate::application::pad_actions::PadActionError;
+use crate::application::quick_memo::QuickMemoError;
+use crate::application::session_recorder::RecorderError;
+use crate::application::settings_service::SettingsError;
+use crate::application::sound_pack::SoundPackError;
+use crate::domain::{normalize_device_name, HotkeyError, MicChainError, PermissionDenied};
+use crate::infrastructure::TallyError;
+use crate::ports::{AppCaptureError, AudioInputError, DeviceManagerError, FileDecoderError, FileEncoderError};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Error returned by Tauri commands
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CommandError {
+    #[error("Device not found: {0}")]
+    DeviceNotFound(String),
+
+    #[error("No {0} device selected")]
+    NoDeviceSelected(String),
+
+    #[error("Device is in exclusive use by another application: {0}")]
+    DeviceInUse(String),
+
+    #[error("No virtual audio driver installed")]
+    DriverMissing,
+
+    #[error("File not found: {0}")]
+    FileNotFound(String),
+
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Failed to decode audio: {0}")]
+    DecodeFailed(String),
+
+    #[error("Unsupported audio codec: {0}")]
+    UnsupportedCodec(String),
+
+    #[error("Audio file is corrupt: {0}")]
+    CorruptFile(String),
+
+    #[error("Audio engine is not running")]
+    EngineNotRunning,
+
+    #[error("Audio engine error: {0}")]
+    EngineError(String),
+
+    #[error("Channel not found: {0}")]
+    ChannelNotFound(String),
+
+    #[error("Sound not found: {0}")]
+    SoundNotFound(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Not supported on this system: {0}")]
+    NotSupported(String),
+
+    #[error("Hotkey {hotkey} is already used by another pad")]
+    HotkeyConflict { hotkey: String, conflicts: Vec<HotkeyClash> },
+
+    #[error("A recording is in progress: {path}")]
+    RecordingActive { path: String },
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Too many {integration} triggers, try again later")]
+    RateLimited { integration: String },
+
+    #[error("Pad is disarmed by the active scene: {0}")]
+    PadDisarmed(String),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    #[error("Update error: {0}")]
+    UpdateError(String),
+
+    #[error("Audio is off in safe mode")]
+    SafeMode,
+
+    #[error("The hotkey was pressed on a board that is no longer active")]
+    StaleBoard,
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl CommandError {
+    /// Stable, machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DeviceNotFound(_) => "DEVICE_NOT_FOUND",
+            Self::NoDeviceSelected(_) => "NO_DEVICE_SELECTED",
+            Self::DeviceInUse(_) => "DEVICE_IN_USE",
+            Self::DriverMissing => "DRIVER_MISSING",
+            Self::FileNotFound(_) => "FILE_NOT_FOUND",
+            Self::UnsupportedFormat(_) => "UNSUPPORTED_FORMAT",
+            Self::DecodeFailed(_) => "DECODE_FAILED",
+            Self::UnsupportedCodec(_) => "UNSUPPORTED_CODEC",
+            Self::CorruptFile(_) => "CORRUPT_FILE",
+            Self::EngineNotRunning => "ENGINE_NOT_RUNNING",
+            Self::EngineError(_) => "ENGINE_ERROR",
+            Self::ChannelNotFound(_) => "CHANNEL_NOT_FOUND",
+            Self::SoundNotFound(_) => "SOUND_NOT_FOUND",
+            Self::InvalidArgument(_) => "INVALID_ARGUMENT",
+            Self::NotSupported(_) => "NOT_SUPPORTED",
+            Self::HotkeyConflict { .. } => "HOTKEY_CONFLICT",
+            Self::RecordingActive { .. } => "RECORDING_ACTIVE",
+            Self::PermissionDenied(_) => "PERMISSION_DENIED",
+            Self::RateLimited { .. } => "RATE_LIMITED",
+            Self::PadDisarmed(_) => "PAD_DISARMED",
+            Self::StorageError(_) => "STORAGE_ERROR",
+            Self::UpdateError(_) => "UPDATE_ERROR",
+            Self::SafeMode => "SAFE_MODE",
+            Self::StaleBoard => "STALE_BOARD",
+            Self::Internal(_) => "INTERNAL",
+        }
+    }
+
+    /// Named values of the message, for the localized text of the code
+    pub fn params(&self) -> serde_json::Map<String, serde_json::Value> {
+        let (name, value) = match self {
+            Self::DeviceNotFound(device) | Self::DeviceInUse(device) => ("device", normalize_device_name(device)),
+            Self::NoDeviceSelected(role) => ("role", role.clone()),
+            Self::FileNotFound(path) | Self::RecordingActive { path } => ("path", path.clone()),
+            Self::UnsupportedFormat(format) | Self::UnsupportedCodec(format) => ("format", format.clone()),
+            Self::ChannelNotFound(channel) => ("channel", channel.clone()),
+            Self::SoundNotFound(sound) => ("sound", sound.clone()),
+            Self::PadDisarmed(pad) => ("pad", pad.clone()),
+            Self::NotSupported(feature) => ("feature", feature.clone()),
+            Self::RateLimited { integration } => ("integration", integration.clone()),
+            Self::HotkeyConflict { hotkey, .. } => ("hotkey", hotkey.clone()),
+            Self::DecodeFailed(detail)
+            | Self::CorruptFile(detail)
+            | Self::EngineError(detail)
+            | Self::InvalidArgument(detail)
+            | Self::PermissionDenied(detail)
+            | Self::StorageError(detail)
+            | Self::UpdateError(detail)
+            | Self::Internal(detail) => ("detail", detail.clone()),
+            Self::DriverMissing | Self::EngineNotRunning | Self::SafeMode | Self::StaleBoard => return serde_json::Map::new(),
+        };
+        serde_json::Map::from_iter([(name.to_string(), serde_json::Value::String(value))])
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 5)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("params", &self.params())?;
+        state.serialize_field("message", &self.to_string())?;
+        // Conflicting pads, so the frontend can point at them
+        match self {
+            Self::HotkeyConflict { conflicts, .. } => state.serialize_field("conflicts", conflicts)?,
+            _ => state.skip_field("conflicts")?,
+        }
+        // File being recorded, for the confirm dialog
+        match self {
+            Self::RecordingActive { path } => state.serialize_field("path", path)?,
+            _ => state.skip_field("path")?,
+        }
+        state.end()
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Internal(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::Internal(message.to_string())
+    }
+}
+
+impl From<FileDecoderError> for CommandError {
+    fn from(error: FileDecoderError) -> Self {
+        match error {
+            FileDecoderError::FileNotFound(path) => Self::FileNotFound(path),
+            FileDecoderError::UnsupportedFormat(format) => Self::UnsupportedFormat(format),
+            FileDecoderError::UnsupportedCodec(codec) => Self::UnsupportedCodec(codec),
+            FileDecoderError::CorruptFile(detail) => Self::CorruptFile(detail),
+            other => Self::DecodeFailed(other.to_string()),
+        }
+    }
+}
+
+impl From<FileEncoderError> for CommandError {
+    fn from(error: FileEncoderError) -> Self {
+        match error {
+            FileEncoderError::UnsupportedFormat(format) => Self::UnsupportedFormat(format),
+            other => Self::StorageError(other.to_string()),
+        }
+    }
+}
+
+impl From<DeviceManagerError> for CommandError {
+    fn from(error: DeviceManagerError) -> Self {
+        match error {
+            DeviceManagerError::DeviceNotFound(name) => Self::DeviceNotFound(name),
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<HotkeyError> for CommandError {
+    fn from(error: HotkeyError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<MicChainError> for CommandError {
+    fn from(error: MicChainError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+impl From<TallyError> for CommandError {
+    fn from(error: TallyError) -> Self {
+        match error {
+            TallyError::DeviceNotFound(name) => Self::DeviceNotFound(name),
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<AudioInputError> for CommandError {
+    fn from(error: AudioInputError) -> Self {
+        match error {
+            AudioInputError::DeviceNotFound(name) => Self::DeviceNotFound(name),
+            AudioInputError::OpenError(message) | AudioInputError::StreamError(message)
+                if is_device_in_use(&message) =>
+            {
+                Self::DeviceInUse(message)
+            }
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<QuickMemoError> for CommandError {
+    fn from(error: QuickMemoError) -> Self {
+        match error {
+            QuickMemoError::Input(input) => input.into(),
+            QuickMemoError::NoAudio => Self::InvalidArgument(error.to_string()),
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<HotkeyRegistryError> for CommandError {
+    fn from(error: HotkeyRegistryError) -> Self {
+        match error {
+            HotkeyRegistryError::Hotkey(hotkey) => hotkey.into(),
+            HotkeyRegistryError::Conflict { hotkey, conflicts } => Self::HotkeyConflict { hotkey, conflicts },
+        }
+    }
+}
+
+impl From<GainWizardError> for CommandError {
+    fn from(error: GainWizardError) -> Self {
+        match error {
+            GainWizardError::Input(input) => input.into(),
+            GainWizardError::NoSpeech | GainWizardError::Cancelled => Self::InvalidArgument(error.to_string()),
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<AppCaptureError> for CommandError {
+    fn from(error: AppCaptureError) -> Self {
+        match error {
+            AppCaptureError::NotSupported => Self::NotSupported("app_capture".into()),
+            AppCaptureError::ProcessNotFound(_) => Self::DeviceNotFound(error.to_string()),
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<BoardShareError> for CommandError {
+    fn from(error: BoardShareError) -> Self {
+        match error {
+            BoardShareError::NoPads => Self::InvalidArgument(error.to_string()),
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<OverlayError> for CommandError {
+    fn from(error: OverlayError) -> Self {
+        Self::Internal(error.to_string())
+    }
+}
+
+impl From<PadActionError> for CommandError {
+    fn from(error: PadActionError) -> Self {
+        match error {
+            PadActionError::Invalid(_) => Self::InvalidArgument(error.to_string()),
+            other => Self::Internal(other.to_string()),
+        }
+    }
+}
+
+impl From<PermissionDenied> for CommandError {
+    fn from(error: PermissionDenied) -> Self {
+        match error {
+            PermissionDenied::RateLimited(integration) => Self::RateLimited {
+                integration: integration.to_string(),
+            },
+            other => Self::PermissionDenied(other.to_string()),
+        }
+    }
+}
+
+impl From<SyncError> for CommandError {
+    fn from(error: SyncError) -> Self {
+        match error {
+            SyncError::NotConfigured | SyncError::AlreadyRunning | SyncError::InsecureUrl(_) => {
+                Self::InvalidArgument(error.to_string())
+            }
+            other => Self::StorageError(other.to_string()),
+        }
+    }
+}
+
+impl From<RecorderError> for CommandError {
+    fn from(error: RecorderError) -> Self {
+        match error {
+            RecorderError::AlreadyRecording | RecorderError::NotRecording => {
+                Self::InvalidArgument(error.to_string())
+            }
+            other => Self::StorageError(other.to_string()),
+        }
+    }
+}
+
+impl From<SettingsError> for CommandError {
+    fn from(error: SettingsError) -> Self {
+        Self::StorageError(error.to_string())
+    }
+}
+
+impl From<SoundPackError> for CommandError {
+    fn from(error: SoundPackError) -> Self {
+        match error {
+            SoundPackError::NotFound(path) => Self::FileNotFound(path),
+            SoundPackError::UnsupportedFormat(format) => Self::UnsupportedFormat(format),
+            SoundPackError::Io(e) => Self::StorageError(e.to_string()),
+            other => Self::InvalidArgument(other.to_string()),
+        }
+    }
+}
+
+impl From<tauri_plugin_store::Error> for CommandError {
+    fn from(error: tauri_plugin_store::Error) -> Self {
+        Self::StorageError(error.to_string())
+    }
+}
+
+impl From<tauri_plugin_updater::Error> for CommandError {
+    fn from(error: tauri_plugin_updater::Error) -> Self {
+        Self::UpdateError(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Integration;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let error = CommandError::DeviceNotFound("USB Mic".into());
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["code"], "DEVICE_NOT_FOUND");
+        assert_eq!(json["params"], serde_json::json!({ "device": "USB Mic" }));
+        assert_eq!(json["message"], "Device not found: USB Mic");
+        assert!(json.get("conflicts").is_none());
+    }
+
+    #[test]
+    fn test_hotkey_conflicts_are_listed() {
+        let error = CommandError::HotkeyConflict {
+            hotkey: "F1".into(),
+            conflicts: vec![HotkeyClash {
+                pad_id: "horn".into(),
+                hotkey: "F1".into(),
+                bank: 0,
+                profile: Some("gaming".into()),
+                kind: crate::domain::HotkeyConflictKind::Duplicate,
+            }],
+        };
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["code"], "HOTKEY_CONFLICT");
+        assert_eq!(json["conflicts"][0]["pad_id"], "horn");
+        assert_eq!(json["conflicts"][0]["profile"], "gaming");
+    }
+
+    #[test]
+    fn test_recording_active_carries_the_file() {
+        let error = CommandError::RecordingActive {
+            path: "session.wav".into(),
+        };
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["code"], "RECORDING_ACTIVE");
+        assert_eq!(json["path"], "session.wav");
+    }
+
+    #[test]
+    fn test_decoder_errors_keep_their_meaning() {
+        let error: CommandError = FileDecoderError::FileNotFound("a.mp3".into()).into();
+        assert_eq!(error.code(), "FILE_NOT_FOUND");
+
+        let error: CommandError = FileDecoderError::IoError("denied".into()).into();
+        assert_eq!(error.code(), "DECODE_FAILED");
+
+        let error: CommandError = FileDecoderError::UnsupportedCodec("alac".into()).into();
+        assert_eq!(error.code(), "UNSUPPORTED_CODEC");
+        assert_eq!(error.params()["format"], "alac");
+    }
+
+    #[test]
+    fn test_params_name_the_message_values() {
+        assert_eq!(CommandError::DriverMissing.params().len(), 0);
+        let params = CommandError::NoDeviceSelected("output".into()).params();
+        assert_eq!(params["role"], "output");
+        let params = CommandError::DeviceInUse("Micro (Re\u{301}altek)\0".into()).params();
+        assert_eq!(params["device"], "Micro (R\u{e9}altek)");
+    }
+
+    #[test]
+    fn test_rate_limits_are_not_refusals() {
+        let error: CommandError = PermissionDenied::RateLimited(Integration::Twitch).into();
+        assert_eq!(error.code(), "RATE_LIMITED");
+        assert_eq!(error.params()["integration"], "Twitch");
+
+        let error: CommandError = PermissionDenied::Disabled(Integration::Twitch).into();
+        assert_eq!(error.code(), "PERMISSION_DENIED");
+    }
+
+    #[test]
+    fn test_unsupported_capture_is_not_a_bad_argument() {
+        let error: CommandError = AppCaptureError::NotSupported.into();
+        assert_eq!(error.code(), "NOT_SUPPORTED");
+        assert_eq!(error.params()["feature"], "app_capture");
+    }
+
+    #[test]
+    fn test_plain_strings_are_internal() {
+        let error: CommandError = "boom".into();
+        assert_eq!(error.code(), "INTERNAL");
+        assert_eq!(error.to_string(), "boom");
+    }
+}
@@ -0,0 +1,374 @@
+//! Gain wizard - Measures the mic and recommends safe levels ("set my gain")
+//!
+//! The user stays quiet for a few seconds so the room noise can be
+//! measured, then speaks normally for ten. From the speech peaks the wizard
+//! recommends a mic volume leaving headroom below full scale, and a noise
+//! gate threshold between the room noise and the voice.
+
+use crate::application::quick_memo::capture_input;
+use crate::domain::{AudioFormat, DeviceId};
+use crate::ports::{AudioInput, AudioInputError};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Length of the room noise measurement
+pub const NOISE_SECS: f32 = 3.0;
+
+/// Length of the speech measurement
+pub const SPEECH_SECS: f32 = 10.0;
+
+/// Speech peak level the recommended mic volume aims for, in dBFS
+pub const TARGET_PEAK_DB: f32 = -6.0;
+
+/// Highest mic volume the engine accepts
+const MAX_MIC_VOLUME: f32 = 2.0;
+
+/// Length of the windows the levels are measured over
+const WINDOW_MS: u32 = 50;
+
+/// Windows this far above the room noise count as speech
+const SPEECH_ABOVE_NOISE_DB: f32 = 10.0;
+
+/// Less speech than this and the measurement is rejected
+const MIN_SPEECH_SECS: f32 = 1.0;
+
+/// The gate threshold sits this far above the room noise, when the voice allows
+const GATE_ABOVE_NOISE_DB: f32 = 6.0;
+
+/// Peaks at or above this are treated as clipped
+const CLIP_LEVEL: f32 = 0.99;
+
+/// Level reported for digital silence
+const SILENCE_DB: f32 = -100.0;
+
+/// Least time between two progress reports
+const PROGRESS_INTERVAL: f32 = 0.1;
+
+/// Errors that can occur while running the wizard
+#[derive(Debug, thiserror::Error)]
+pub enum GainWizardError {
+    #[error("The gain wizard is already running")]
+    AlreadyRunning,
+
+    #[error("The gain wizard was cancelled")]
+    Cancelled,
+
+    #[error("No speech was heard above the room noise; check the mic and speak up")]
+    NoSpeech,
+
+    #[error(transparent)]
+    Input(#[from] AudioInputError),
+}
+
+/// Where the wizard is in its run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GainWizardStep {
+    #[default]
+    Idle,
+    /// Recording the room while the user stays quiet
+    MeasuringNoise,
+    /// Recording the user speaking normally
+    MeasuringSpeech,
+    Analyzing,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl GainWizardStep {
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::MeasuringNoise | Self::MeasuringSpeech | Self::Analyzing)
+    }
+}
+
+/// Levels measured by the wizard and the settings it recommends
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GainRecommendation {
+    /// Typical room noise level, in dBFS RMS
+    pub noise_floor_db: f32,
+    /// Average speech level, in dBFS RMS
+    pub speech_rms_db: f32,
+    /// Loudest speech peak, in dBFS
+    pub peak_db: f32,
+    /// The input clipped; the interface gain should be turned down, since
+    /// the true peak is unknown
+    pub clipped: bool,
+    /// Recommended mic volume (0.0 - 2.0)
+    pub mic_volume: f32,
+    /// Recommended noise gate threshold, in dBFS RMS before mic volume
+    pub gate_threshold_db: f32,
+}
+
+/// Progress of the wizard, as reported to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
+pub struct GainWizardState {
+    pub step: GainWizardStep,
+    /// Seconds recorded in the current step
+    pub elapsed: f32,
+    /// Length of the current step in seconds
+    pub duration: f32,
+    /// Peak of the last captured buffer, in dBFS
+    pub level_db: f32,
+    pub result: Option<GainRecommendation>,
+    pub error: Option<String>,
+}
+
+/// Runs the wizard, one run at a time, and lets another command cancel it
+pub struct GainWizard {
+    state: Mutex<GainWizardState>,
+    cancel_requested: AtomicBool,
+}
+
+impl GainWizard {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(GainWizardState::default()),
+            cancel_requested: AtomicBool::new(false),
+        }
+    }
+
+    pub fn state(&self) -> GainWizardState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Stop the running wizard at the next buffer boundary
+    pub fn cancel(&self) {
+        self.cancel_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Measure the room, then the voice, on `device` and analyze both
+    ///
+    /// `on_progress` is called on every change of step and about ten times
+    /// a second while recording. Blocks the calling thread.
+    pub fn run(
+        &self,
+        input: &mut dyn AudioInput,
+        device: &DeviceId,
+        sample_rate: u32,
+        mut on_progress: impl FnMut(&GainWizardState),
+    ) -> Result<GainRecommendation, GainWizardError> {
+        // Claim the run under the same lock as the check, so two starts
+        // can't both see an idle wizard
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.step.is_running() {
+                return Err(GainWizardError::AlreadyRunning);
+            }
+            *state = GainWizardState {
+                step: GainWizardStep::MeasuringNoise,
+                ..GainWizardState::default()
+            };
+            self.cancel_requested.store(false, Ordering::SeqCst);
+        }
+
+        let result = self.measure(input, device, sample_rate, &mut on_progress);
+        let state = self.update(|state| match &result {
+            Ok(recommendation) => {
+                state.step = GainWizardStep::Done;
+                state.result = Some(*recommendation);
+            }
+            Err(GainWizardError::Cancelled) => state.step = GainWizardStep::Cancelled,
+            Err(e) => {
+                state.step = GainWizardStep::Failed;
+                state.error = Some(e.to_string());
+            }
+        });
+        on_progress(&state);
+        result
+    }
+
+    fn measure(
+        &self,
+        input: &mut dyn AudioInput,
+        device: &DeviceId,
+        sample_rate: u32,
+        on_progress: &mut impl FnMut(&GainWizardState),
+    ) -> Result<GainRecommendation, GainWizardError> {
+        let format = AudioFormat::new(sample_rate, 2, 16);
+        let noise = self.capture_step(input, device, format, GainWizardStep::MeasuringNoise, NOISE_SECS, on_progress)?;
+        let speech = self.capture_step(input, device, format, GainWizardStep::MeasuringSpeech, SPEECH_SECS, on_progress)?;
+
+        let state = self.update(|state| state.step = GainWizardStep::Analyzing);
+        on_progress(&state);
+        recommend_gain(&noise, &speech, format.channels as usize, sample_rate)
+    }
+
+    /// Record one step, returning the interleaved samples
+    fn capture_step(
+        &self,
+        input: &mut dyn AudioInput,
+        device: &DeviceId,
+        format: AudioFormat,
+        step: GainWizardStep,
+        seconds: f32,
+        on_progress: &mut impl FnMut(&GainWizardState),
+    ) -> Result<Vec<f32>, GainWizardError> {
+        let state = self.update(|state| {
+            state.step = step;
+            state.elapsed = 0.0;
+            state.duration = seconds;
+        });
+        on_progress(&state);
+
+        let mut reported = 0.0;
+        let captured = capture_input(
+            input,
+            device,
+            format,
+            Duration::from_secs_f32(seconds),
+            || self.cancel_requested.load(Ordering::SeqCst),
+            |elapsed, peak| {
+                let state = self.update(|state| {
+                    state.elapsed = elapsed;
+                    state.level_db = to_db(peak);
+                });
+                if elapsed - reported >= PROGRESS_INTERVAL {
+                    reported = elapsed;
+                    on_progress(&state);
+                }
+            },
+        )?;
+
+        if self.cancel_requested.load(Ordering::SeqCst) {
+            return Err(GainWizardError::Cancelled);
+        }
+        Ok(captured.to_raw_f32())
+    }
+
+    fn update(&self, change: impl FnOnce(&mut GainWizardState)) -> GainWizardState {
+        let mut state = self.state.lock().unwrap();
+        change(&mut state);
+        state.clone()
+    }
+}
+
+impl Default for GainWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recommend a mic volume and gate threshold from a room noise and a speech
+/// recording (interleaved, `channels` channels)
+///
+/// Levels are measured over 50 ms windows. The noise floor is the median
+/// room noise window; speech is every window clearly above it.
+pub fn recommend_gain(
+    noise: &[f32],
+    speech: &[f32],
+    channels: usize,
+    sample_rate: u32,
+) -> Result<GainRecommendation, GainWizardError> {
+    let window = (sample_rate * WINDOW_MS / 1000).max(1) as usize * channels.max(1);
+
+    let mut noise_powers = window_powers(noise, window);
+    noise_powers.sort_unstable_by(f32::total_cmp);
+    let noise_floor = noise_powers.get(noise_powers.len() / 2).copied().unwrap_or(0.0);
+    let noise_floor_db = power_to_db(noise_floor);
+
+    let speech_threshold = noise_floor_db + SPEECH_ABOVE_NOISE_DB;
+    let speech_powers: Vec<f32> = window_powers(speech, window)
+        .into_iter()
+        .filter(|&power| power_to_db(power) > speech_threshold)
+        .collect();
+    let speech_secs = (speech_powers.len() * WINDOW_MS as usize) as f32 / 1000.0;
+    if speech_secs < MIN_SPEECH_SECS {
+        return Err(GainWizardError::NoSpeech);
+    }
+    let speech_rms_db = power_to_db(speech_powers.iter().sum::<f32>() / speech_powers.len() as f32);
+
+    let peak = speech.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let peak_db = to_db(peak);
+    let mic_volume = 10f32.powf((TARGET_PEAK_DB - peak_db) / 20.0).min(MAX_MIC_VOLUME);
+
+    // Well clear of the room noise, but never more than halfway up to the voice
+    let gate_threshold_db =
+        (noise_floor_db + GATE_ABOVE_NOISE_DB).min((noise_floor_db + speech_rms_db) / 2.0);
+
+    Ok(GainRecommendation {
+        noise_floor_db,
+        speech_rms_db,
+        peak_db,
+        clipped: peak >= CLIP_LEVEL,
+        mic_volume,
+        gate_threshold_db,
+    })
+}
+
+/// Mean power of each whole window of `window` samples
+fn window_powers(samples: &[f32], window: usize) -> Vec<f32> {
+    samples
+        .chunks_exact(window)
+        .map(|chunk| chunk.iter().map(|s| s * s).sum::<f32>() / window as f32)
+        .collect()
+}
+
+fn to_db(level: f32) -> f32 {
+    if level > 0.0 {
+        (20.0 * level.log10()).max(SILENCE_DB)
+    } else {
+        SILENCE_DB
+    }
+}
+
+fn power_to_db(power: f32) -> f32 {
+    to_db(power.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interleaved stereo sine of `seconds` at `amplitude`
+    fn tone(amplitude: f32, seconds: f32) -> Vec<f32> {
+        let frames = (seconds * 48_000.0) as usize;
+        (0..frames)
+            .flat_map(|n| {
+                let s = (n as f32 * 0.05).sin() * amplitude;
+                [s, s]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recommendation_leaves_headroom() {
+        // Room at about -60 dBFS RMS, voice peaking at -20 dBFS, with pauses
+        let noise = tone(0.0014, 3.0);
+        let mut speech = tone(0.1, 6.0);
+        speech.extend(tone(0.0014, 4.0));
+
+        let rec = recommend_gain(&noise, &speech, 2, 48_000).unwrap();
+        assert!((rec.noise_floor_db + 60.0).abs() < 0.5, "{}", rec.noise_floor_db);
+        assert!((rec.peak_db + 20.0).abs() < 0.1, "{}", rec.peak_db);
+        assert!((rec.speech_rms_db + 23.0).abs() < 0.5, "{}", rec.speech_rms_db);
+        assert!(!rec.clipped);
+
+        // +14 dB brings the peaks to -6 dBFS, capped at the engine's 2.0
+        assert_eq!(rec.mic_volume, MAX_MIC_VOLUME);
+        assert!((rec.gate_threshold_db + 54.0).abs() < 0.5, "{}", rec.gate_threshold_db);
+    }
+
+    #[test]
+    fn test_hot_input_is_turned_down() {
+        let noise = tone(0.01, 3.0);
+        let speech = tone(1.0, 10.0);
+
+        let rec = recommend_gain(&noise, &speech, 2, 48_000).unwrap();
+        assert!(rec.clipped);
+        assert!((rec.mic_volume - 0.5).abs() < 0.01, "{}", rec.mic_volume);
+    }
+
+    #[test]
+    fn test_silence_is_rejected() {
+        let noise = tone(0.01, 3.0);
+        let speech = tone(0.012, 10.0);
+
+        assert!(matches!(
+            recommend_gain(&noise, &speech, 2, 48_000),
+            Err(GainWizardError::NoSpeech)
+        ));
+    }
+}
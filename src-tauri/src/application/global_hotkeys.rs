@@ -0,0 +1,169 @@
+//! Global hotkeys - Pad hotkeys that fire while other apps have focus
+//!
+//! In-app hotkeys only reach the window while it is focused. Pads can
+//! also get a global combo, registered with the OS through the global
+//! shortcut plugin. A press is forwarded to the frontend as
+//! `global-hotkey`, which plays the pad as it would for an in-app press;
+//! the board switching hotkeys can be made global too. Combos another app
+//! already holds are listed as unavailable instead of failing the rest.
+
+use crate::domain::{BoardHotkeySettings, GlobalHotkeySettings, HotkeySequence, KeyCombo};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+/// What a global combo triggers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobalAction {
+    Pad(String),
+    NextBoard,
+    PreviousBoard,
+}
+
+/// Combos to register for the settings, with what each triggers
+///
+/// Saved combos that are no longer valid globally are skipped, and so is
+/// a combo already taken by an action before it, since the OS holds each
+/// combo once.
+pub fn global_actions(settings: &GlobalHotkeySettings, boards: &BoardHotkeySettings) -> Vec<(KeyCombo, GlobalAction)> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+
+    let mut pads: Vec<(&String, &String)> = settings.pads.iter().collect();
+    pads.sort();
+    let mut actions: Vec<(&str, GlobalAction)> = pads
+        .into_iter()
+        .map(|(pad_id, hotkey)| (hotkey.as_str(), GlobalAction::Pad(pad_id.clone())))
+        .collect();
+    if settings.board_switching {
+        actions.extend(boards.next.as_deref().map(|hotkey| (hotkey, GlobalAction::NextBoard)));
+        actions.extend(boards.previous.as_deref().map(|hotkey| (hotkey, GlobalAction::PreviousBoard)));
+    }
+
+    let mut taken = HashSet::new();
+    actions
+        .into_iter()
+        .filter_map(|(hotkey, action)| {
+            let sequence = HotkeySequence::parse(hotkey).ok()?;
+            let combo = sequence.global_combo().ok()?.clone();
+            if !taken.insert(combo.clone()) {
+                tracing::warn!(hotkey = %combo, action = ?action, "Global hotkey already taken, skipped");
+                return None;
+            }
+            Some((combo, action))
+        })
+        .collect()
+}
+
+/// Spelling of a combo the shortcut plugin parses
+fn shortcut_text(combo: &KeyCombo) -> String {
+    let mut parts: Vec<&str> = [
+        (combo.ctrl, "ctrl"),
+        (combo.alt, "alt"),
+        (combo.shift, "shift"),
+        (combo.meta, "super"),
+    ]
+    .into_iter()
+    .filter_map(|(held, name)| held.then_some(name))
+    .collect();
+    parts.push(&combo.key);
+    parts.join("+")
+}
+
+#[derive(Default)]
+struct Registered {
+    actions: HashMap<u32, GlobalAction>,
+    unavailable: Vec<String>,
+}
+
+/// Combos registered with the OS, by shortcut id
+pub struct GlobalHotkeyTable {
+    registered: Mutex<Registered>,
+}
+
+impl GlobalHotkeyTable {
+    pub fn new() -> Self {
+        Self {
+            registered: Mutex::new(Registered::default()),
+        }
+    }
+
+    /// Replace every registration with the combos of the settings
+    pub fn apply(&self, app: &AppHandle, settings: &GlobalHotkeySettings, boards: &BoardHotkeySettings) {
+        let shortcuts = app.global_shortcut();
+        let mut registered = self.registered.lock().unwrap();
+        if let Err(e) = shortcuts.unregister_all() {
+            tracing::warn!(error = %e, "Failed to unregister the global hotkeys");
+        }
+        *registered = Registered::default();
+
+        for (combo, action) in global_actions(settings, boards) {
+            let result = shortcut_text(&combo)
+                .parse::<Shortcut>()
+                .map_err(|e| e.to_string())
+                .and_then(|shortcut| shortcuts.register(shortcut).map(|_| shortcut).map_err(|e| e.to_string()));
+            match result {
+                Ok(shortcut) => {
+                    registered.actions.insert(shortcut.id(), action);
+                }
+                Err(e) => {
+                    tracing::warn!(hotkey = %combo, error = %e, "Global hotkey unavailable");
+                    registered.unavailable.push(combo.to_string());
+                }
+            }
+        }
+        tracing::info!(
+            registered = registered.actions.len(),
+            unavailable = registered.unavailable.len(),
+            "Global hotkeys applied"
+        );
+    }
+
+    /// What the shortcut with `id` triggers
+    pub fn action(&self, id: u32) -> Option<GlobalAction> {
+        self.registered.lock().unwrap().actions.get(&id).cloned()
+    }
+
+    /// Combos the OS refused, e.g. because another app holds them
+    pub fn unavailable(&self) -> Vec<String> {
+        self.registered.lock().unwrap().unavailable.clone()
+    }
+}
+
+impl Default for GlobalHotkeyTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_of_valid_combos_only() {
+        let mut settings = GlobalHotkeySettings::default();
+        settings.pads.insert("horn".into(), "Ctrl+Shift+H".into());
+        settings.pads.insert("typed".into(), "H".into());
+        let boards = BoardHotkeySettings::default();
+
+        let actions = global_actions(&settings, &boards);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].1, GlobalAction::Pad("horn".into()));
+        assert_eq!(shortcut_text(&actions[0].0), "ctrl+shift+h");
+
+        settings.board_switching = true;
+        assert_eq!(global_actions(&settings, &boards).len(), 3);
+
+        // The first holder of a combo keeps it
+        settings.pads.insert("siren".into(), "ctrl+shift+h".into());
+        let actions = global_actions(&settings, &boards);
+        assert_eq!(actions.len(), 3);
+        assert_eq!(actions[0].1, GlobalAction::Pad("horn".into()));
+
+        settings.enabled = false;
+        assert!(global_actions(&settings, &boards).is_empty());
+    }
+}
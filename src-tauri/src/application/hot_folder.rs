@@ -0,0 +1,172 @@
+//! Hot folder - File drops as triggers, for tools that can't speak HTTP
+//!
+//! Dropping `<sound-id>.play` into the trigger folder plays that sound; a
+//! `.json` file holds any other command, e.g. `{"action": "stop_all"}`.
+//! Trigger files are deleted once read. Other files are left alone, so
+//! tools can write a temporary name and rename it when done. Scans only
+//! list the folder: a file is read when it is a trigger, and a JSON file
+//! still being written is read again only once its size or time changes.
+
+use crate::domain::AppSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+
+/// How often the trigger folder is scanned
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// A JSON file that doesn't parse may still be being written for this long
+const WRITE_GRACE: Duration = Duration::from_secs(2);
+
+/// Action asked for by a trigger file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum HotFolderCommand {
+    Play { sound_id: String },
+    Stop { sound_id: String, fade_ms: Option<u32> },
+    StopAll { fade_ms: Option<u32> },
+    MuteMic,
+    UnmuteMic,
+}
+
+/// Whether a file's name makes it a trigger file
+pub fn is_trigger(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("play") || extension.eq_ignore_ascii_case("json"))
+}
+
+/// Read a trigger file; None for files that aren't triggers
+pub fn parse_trigger(path: &Path, contents: &str) -> Option<Result<HotFolderCommand, String>> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "play" => {
+            let sound_id = path.file_stem()?.to_str()?.to_string();
+            Some(Ok(HotFolderCommand::Play { sound_id }))
+        }
+        "json" => Some(serde_json::from_str(contents).map_err(|e| e.to_string())),
+        _ => None,
+    }
+}
+
+/// Change time and size of a file, telling whether it changed since read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+/// Trigger files in the folder, oldest first so drops run in order
+fn trigger_files(folder: &Path) -> Vec<(Stamp, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(Stamp, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()) && is_trigger(&entry.path()))
+        .map(|entry| {
+            let metadata = entry.metadata().ok();
+            let stamp = Stamp {
+                modified: metadata
+                    .as_ref()
+                    .and_then(|m| m.modified().ok())
+                    .unwrap_or(SystemTime::UNIX_EPOCH),
+                len: metadata.map_or(0, |m| m.len()),
+            };
+            (stamp, entry.path())
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Start the trigger folder watcher thread
+///
+/// The folder is read from the settings on every scan, so changing it
+/// takes effect without a restart. `run` executes each command.
+pub fn spawn_hot_folder_watcher(
+    app: AppHandle,
+    settings: Arc<RwLock<AppSettings>>,
+    run: impl Fn(&AppHandle, HotFolderCommand) -> Result<(), String> + Send + 'static,
+) -> JoinHandle<()> {
+    // JSON files that didn't parse yet, as they were when read
+    let mut unparsed: HashMap<PathBuf, (Stamp, String)> = HashMap::new();
+    thread::spawn(move || loop {
+        let folder = settings.blocking_read().hot_folder.active_path().map(PathBuf::from);
+        let files = folder.as_deref().map(trigger_files).unwrap_or_default();
+        unparsed.retain(|path, _| files.iter().any(|(_, file)| file == path));
+        for (stamp, path) in files {
+            let command = match unparsed.remove(&path) {
+                Some((read, error)) if read == stamp => Err(error),
+                _ => {
+                    let contents = std::fs::read_to_string(&path).unwrap_or_default();
+                    let Some(command) = parse_trigger(&path, &contents) else {
+                        continue;
+                    };
+                    command
+                }
+            };
+            if let Err(error) = &command {
+                if stamp.modified.elapsed().unwrap_or_default() < WRITE_GRACE {
+                    unparsed.insert(path, (stamp, error.clone()));
+                    continue;
+                }
+            }
+            if let Err(e) = std::fs::remove_file(&path) {
+                // Running it again on every scan would be worse
+                tracing::warn!(error = %e, file = %path.display(), "Could not consume trigger file, skipping");
+                continue;
+            }
+
+            let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let result = command.and_then(|command| {
+                tracing::info!(file = %file, command = ?command, "Hot folder trigger");
+                run(&app, command)
+            });
+            if let Err(message) = result {
+                tracing::warn!(file = %file, error = %message, "Hot folder trigger failed");
+                let _ = app.emit("hot-folder-error", serde_json::json!({
+                    "file": file,
+                    "message": message,
+                }));
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_files_name_the_sound() {
+        assert_eq!(
+            parse_trigger(Path::new("/hot/sound-123.play"), ""),
+            Some(Ok(HotFolderCommand::Play { sound_id: "sound-123".into() }))
+        );
+        assert_eq!(parse_trigger(Path::new("/hot/sound-123.play.tmp"), ""), None);
+        assert_eq!(parse_trigger(Path::new("/hot/readme"), ""), None);
+        assert!(is_trigger(Path::new("/hot/cmd.JSON")));
+        assert!(!is_trigger(Path::new("/hot/sound-123.play.tmp")));
+    }
+
+    #[test]
+    fn test_json_files_hold_commands() {
+        let path = Path::new("cmd.json");
+        assert_eq!(
+            parse_trigger(path, r#"{"action": "stop", "sound_id": "a", "fade_ms": 500}"#),
+            Some(Ok(HotFolderCommand::Stop { sound_id: "a".into(), fade_ms: Some(500) }))
+        );
+        assert_eq!(
+            parse_trigger(path, r#"{"action": "stop_all"}"#),
+            Some(Ok(HotFolderCommand::StopAll { fade_ms: None }))
+        );
+        assert!(matches!(parse_trigger(path, r#"{"action": "explode"}"#), Some(Err(_))));
+        assert!(matches!(parse_trigger(path, r#"{"action": "pl"#), Some(Err(_))));
+    }
+}
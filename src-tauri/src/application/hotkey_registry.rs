@@ -0,0 +1,350 @@
+//! Hotkey registry - Pad hotkeys of every profile, with conflict checks
+//!
+//! A pad hotkey is either global, live whatever profile is active, or
+//! scoped to a profile, live only while that profile is active. Two
+//! bindings conflict only when they can be live at the same time, so
+//! profiles may reuse each other's keys. Conflicts are refused when a
+//! hotkey is saved instead of making a later registration fail.
+//!
+//! Profiles double as boards: stepping to the next board swaps the live
+//! pad hotkeys in one go and bumps a generation, so a press that was
+//! matched against the old board can be told apart and dropped.
+
+use crate::domain::{find_conflicts, HotkeyBinding, HotkeyConflictKind, HotkeyError};
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Where a hotkey is live
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum HotkeyScope {
+    /// Live in every profile
+    #[default]
+    Global,
+    /// Live only while the named profile is active
+    Profile(String),
+}
+
+impl HotkeyScope {
+    /// Scope of a profile name, global for None or an empty name
+    pub fn from_profile(profile: Option<&str>) -> Self {
+        match profile.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(profile) => Self::Profile(profile.to_string()),
+            None => Self::Global,
+        }
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        match self {
+            Self::Global => None,
+            Self::Profile(profile) => Some(profile),
+        }
+    }
+
+    /// Whether bindings of the two scopes can be live at the same time
+    pub fn overlaps(&self, other: &HotkeyScope) -> bool {
+        match (self, other) {
+            (Self::Profile(a), Self::Profile(b)) => a == b,
+            _ => true,
+        }
+    }
+
+    /// Whether bindings of this scope are live while `active` is the profile
+    pub fn is_live(&self, active: Option<&str>) -> bool {
+        match self {
+            Self::Global => true,
+            Self::Profile(profile) => active == Some(profile.as_str()),
+        }
+    }
+}
+
+/// A pad hotkey and where it is live
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedBinding {
+    pub binding: HotkeyBinding,
+    pub scope: HotkeyScope,
+}
+
+/// An existing hotkey that conflicts with a candidate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HotkeyClash {
+    pub pad_id: String,
+    pub hotkey: String,
+    pub bank: u8,
+    /// Profile of the conflicting hotkey, None when global
+    pub profile: Option<String>,
+    pub kind: HotkeyConflictKind,
+}
+
+/// Errors that can occur when binding a hotkey
+#[derive(Debug, thiserror::Error)]
+pub enum HotkeyRegistryError {
+    #[error(transparent)]
+    Hotkey(#[from] HotkeyError),
+
+    #[error("{hotkey} conflicts with the hotkeys of {} other pad(s)", conflicts.len())]
+    Conflict { hotkey: String, conflicts: Vec<HotkeyClash> },
+}
+
+/// The board whose pad hotkeys are live
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActiveBoard {
+    /// Active profile, None when only global hotkeys are live
+    pub board: Option<String>,
+    /// Bumped on every switch; presses seen under an older one are stale
+    pub generation: u64,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    bindings: Vec<ScopedBinding>,
+    active_profile: Option<String>,
+    generation: u64,
+}
+
+impl RegistryState {
+    fn activate(&mut self, profile: Option<String>) {
+        if self.active_profile != profile {
+            self.active_profile = profile;
+            self.generation += 1;
+        }
+    }
+
+    fn active_board(&self) -> ActiveBoard {
+        ActiveBoard {
+            board: self.active_profile.clone(),
+            generation: self.generation,
+        }
+    }
+}
+
+/// Pad hotkeys of all profiles and the profile currently active
+pub struct HotkeyRegistry {
+    state: Mutex<RegistryState>,
+}
+
+impl HotkeyRegistry {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(RegistryState::default()),
+        }
+    }
+
+    /// Replace every binding, e.g. with the ones of the saved pads
+    ///
+    /// Saved bindings are taken as they are, conflicts included; they are
+    /// only checked when a hotkey is bound.
+    pub fn load(&self, bindings: Vec<ScopedBinding>) {
+        self.state.lock().unwrap().bindings = bindings;
+    }
+
+    /// Existing bindings that would conflict with `candidate` in `scope`
+    ///
+    /// The candidate pad's own binding is ignored, since binding replaces it.
+    pub fn conflicts(&self, candidate: &HotkeyBinding, scope: &HotkeyScope) -> Vec<HotkeyClash> {
+        let state = self.state.lock().unwrap();
+        let overlapping: Vec<&ScopedBinding> = state.bindings.iter().filter(|b| b.scope.overlaps(scope)).collect();
+        let bindings: Vec<HotkeyBinding> = overlapping.iter().map(|b| b.binding.clone()).collect();
+
+        find_conflicts(&bindings, candidate)
+            .into_iter()
+            .map(|conflict| {
+                let profile = overlapping
+                    .iter()
+                    .find(|b| b.binding == conflict.binding)
+                    .and_then(|b| b.scope.profile().map(String::from));
+                HotkeyClash {
+                    pad_id: conflict.binding.pad_id,
+                    hotkey: conflict.binding.sequence.to_string(),
+                    bank: conflict.binding.bank,
+                    profile,
+                    kind: conflict.kind,
+                }
+            })
+            .collect()
+    }
+
+    /// Bind a pad's hotkey in `scope`, replacing its previous one
+    ///
+    /// Fails without changing anything if the hotkey conflicts with a
+    /// binding that can be live at the same time.
+    pub fn bind(&self, binding: HotkeyBinding, scope: HotkeyScope) -> Result<(), HotkeyRegistryError> {
+        let conflicts = self.conflicts(&binding, &scope);
+        if !conflicts.is_empty() {
+            return Err(HotkeyRegistryError::Conflict {
+                hotkey: binding.sequence.to_string(),
+                conflicts,
+            });
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.bindings.retain(|b| b.binding.pad_id != binding.pad_id);
+        state.bindings.push(ScopedBinding { binding, scope });
+        Ok(())
+    }
+
+    /// Remove a pad's hotkey
+    pub fn unbind(&self, pad_id: &str) {
+        self.state.lock().unwrap().bindings.retain(|b| b.binding.pad_id != pad_id);
+    }
+
+    pub fn active_profile(&self) -> Option<String> {
+        self.state.lock().unwrap().active_profile.clone()
+    }
+
+    /// Make `profile` the live one; None leaves only global hotkeys live
+    pub fn set_active_profile(&self, profile: Option<String>) -> ActiveBoard {
+        let mut state = self.state.lock().unwrap();
+        state.activate(profile);
+        state.active_board()
+    }
+
+    pub fn active_board(&self) -> ActiveBoard {
+        self.state.lock().unwrap().active_board()
+    }
+
+    /// Profiles with pad hotkeys in name order; these are the boards
+    pub fn boards(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let mut boards: Vec<String> = state
+            .bindings
+            .iter()
+            .filter_map(|b| b.scope.profile().map(String::from))
+            .collect();
+        boards.sort();
+        boards.dedup();
+        boards
+    }
+
+    /// Step `step` boards forward, or back when negative, wrapping around
+    ///
+    /// The boards are listed and the active one swapped under a single
+    /// lock, so a concurrent press sees either board, never a mix. From no
+    /// board, forward goes to the first and back to the last. Returns None
+    /// when no pad is on a board.
+    pub fn step_board(&self, step: isize) -> Option<ActiveBoard> {
+        let mut state = self.state.lock().unwrap();
+        let mut boards: Vec<&str> = state.bindings.iter().filter_map(|b| b.scope.profile()).collect();
+        boards.sort();
+        boards.dedup();
+        if boards.is_empty() {
+            return None;
+        }
+
+        let count = boards.len() as isize;
+        let index = match boards.iter().position(|b| Some(*b) == state.active_profile.as_deref()) {
+            Some(current) => (current as isize + step).rem_euclid(count),
+            None if step < 0 => count - 1,
+            None => 0,
+        };
+        let board = boards[index as usize].to_string();
+        state.activate(Some(board));
+        Some(state.active_board())
+    }
+
+    /// Whether a press matched under `generation` is still for the live board
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.state.lock().unwrap().generation == generation
+    }
+
+    /// Bindings that are live in the active profile
+    pub fn active_bindings(&self) -> Vec<HotkeyBinding> {
+        let state = self.state.lock().unwrap();
+        state
+            .bindings
+            .iter()
+            .filter(|b| b.scope.is_live(state.active_profile.as_deref()))
+            .map(|b| b.binding.clone())
+            .collect()
+    }
+}
+
+impl Default for HotkeyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::HotkeySequence;
+
+    fn binding(pad: &str, keys: &str) -> HotkeyBinding {
+        HotkeyBinding::new(pad, 0, HotkeySequence::parse(keys).unwrap()).unwrap()
+    }
+
+    fn profile(name: &str) -> HotkeyScope {
+        HotkeyScope::Profile(name.to_string())
+    }
+
+    #[test]
+    fn test_profiles_may_share_keys() {
+        let registry = HotkeyRegistry::new();
+        registry.bind(binding("gaming-horn", "F1"), profile("gaming")).unwrap();
+        registry.bind(binding("podcast-intro", "F1"), profile("podcast")).unwrap();
+
+        // Same profile, or global against anything, conflicts
+        let Err(HotkeyRegistryError::Conflict { conflicts, .. }) =
+            registry.bind(binding("gaming-drum", "F1"), profile("gaming"))
+        else {
+            panic!("expected a conflict");
+        };
+        assert_eq!(conflicts, vec![HotkeyClash {
+            pad_id: "gaming-horn".into(),
+            hotkey: "F1".into(),
+            bank: 0,
+            profile: Some("gaming".into()),
+            kind: HotkeyConflictKind::Duplicate,
+        }]);
+        assert_eq!(registry.conflicts(&binding("any", "F1 A"), &HotkeyScope::Global).len(), 2);
+
+        // Rebinding a pad replaces its own hotkey
+        registry.bind(binding("gaming-horn", "F1"), profile("gaming")).unwrap();
+        assert_eq!(registry.active_bindings(), vec![]);
+    }
+
+    #[test]
+    fn test_only_the_active_profile_is_live() {
+        let registry = HotkeyRegistry::new();
+        registry.bind(binding("mute", "M"), HotkeyScope::from_profile(None)).unwrap();
+        registry.bind(binding("horn", "F1"), HotkeyScope::from_profile(Some("gaming"))).unwrap();
+        registry.bind(binding("intro", "F1"), HotkeyScope::from_profile(Some("podcast"))).unwrap();
+
+        let live = |registry: &HotkeyRegistry| -> Vec<String> {
+            registry.active_bindings().into_iter().map(|b| b.pad_id).collect()
+        };
+        assert_eq!(live(&registry), vec!["mute"]);
+
+        registry.set_active_profile(Some("podcast".into()));
+        assert_eq!(live(&registry), vec!["mute", "intro"]);
+
+        registry.unbind("mute");
+        assert_eq!(live(&registry), vec!["intro"]);
+    }
+
+    #[test]
+    fn test_boards_step_and_wrap() {
+        let registry = HotkeyRegistry::new();
+        assert_eq!(registry.step_board(1), None);
+
+        registry.bind(binding("mute", "M"), HotkeyScope::Global).unwrap();
+        registry.bind(binding("intro", "F1"), profile("podcast")).unwrap();
+        registry.bind(binding("horn", "F1"), profile("gaming")).unwrap();
+        assert_eq!(registry.boards(), vec!["gaming", "podcast"]);
+
+        let board = |step| registry.step_board(step).unwrap().board.unwrap();
+        assert_eq!(board(-1), "podcast");
+        assert_eq!(board(1), "gaming");
+        assert_eq!(board(1), "podcast");
+
+        // A press matched before the last switch is stale
+        let before = registry.active_board().generation;
+        assert!(registry.is_current(before));
+        board(-1);
+        assert!(!registry.is_current(before));
+
+        // Re-activating the same board keeps presses valid
+        let generation = registry.set_active_profile(Some("gaming".into())).generation;
+        assert_eq!(generation, before + 1);
+    }
+}
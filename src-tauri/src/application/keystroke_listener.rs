@@ -0,0 +1,37 @@
+//! Keystroke listener - Feeds key press times to the keyboard suppression
+//!
+//! The system-wide keyboard hook only runs while keyboard suppression is
+//! on; it reports that a key went down, never which one.
+
+use crate::application::audio_engine::AudioEngine;
+use std::sync::Arc;
+
+/// Start or stop listening for key presses
+pub fn set_keystroke_listener(engine: &Arc<AudioEngine>, enabled: bool) {
+    #[cfg(target_os = "windows")]
+    {
+        use crate::adapters::WindowsKeyboardHook;
+
+        if !enabled {
+            WindowsKeyboardHook::stop();
+            return;
+        }
+        let engine = Arc::downgrade(engine);
+        let started = WindowsKeyboardHook::start(move || {
+            if let Some(engine) = engine.upgrade() {
+                engine.record_keystroke();
+            }
+        });
+        if let Err(e) = started {
+            tracing::warn!(error = %e, "Keyboard hook unavailable, keyboard suppression won't react");
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = engine;
+        if enabled {
+            tracing::warn!("No keyboard hook on this platform, keyboard suppression won't react");
+        }
+    }
+}
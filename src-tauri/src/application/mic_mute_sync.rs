@@ -0,0 +1,173 @@
+//! Mic mute sync - Keeps the in-app mute and the OS capture mute in step
+//!
+//! Muting inside Voiceboard mutes the OS endpoint too, and hardware mute
+//! keys (which toggle the OS endpoint) are mirrored back into the engine.
+
+use crate::application::audio_engine::{AudioEngine, AudioEngineCommand};
+use crate::domain::AppSettings;
+use crate::infrastructure::TallyController;
+use crate::ports::{SystemMicMute, SystemMuteError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+
+/// How often the OS mute state is polled
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Two-way bridge between the engine mute and the OS endpoint mute
+pub struct MicMuteSync {
+    backend: Option<Box<dyn SystemMicMute>>,
+    /// Last known OS state, per device
+    last_state: Mutex<Option<(String, bool)>>,
+}
+
+impl MicMuteSync {
+    pub fn new(backend: Box<dyn SystemMicMute>) -> Self {
+        Self {
+            backend: Some(backend),
+            last_state: Mutex::new(None),
+        }
+    }
+
+    /// A sync that does nothing, for platforms without an OS mute API
+    pub fn disabled() -> Self {
+        Self {
+            backend: None,
+            last_state: Mutex::new(None),
+        }
+    }
+
+    /// The OS mute backend for the current platform
+    pub fn for_platform() -> Self {
+        #[cfg(target_os = "windows")]
+        {
+            Self::new(Box::new(crate::adapters::WindowsEndpointMute::new()))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            Self::disabled()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    /// Mirror an in-app mute change to the OS endpoint
+    pub fn apply(&self, device_name: &str, muted: bool) -> Result<(), SystemMuteError> {
+        let Some(backend) = &self.backend else {
+            return Ok(());
+        };
+
+        backend.set_muted(device_name, muted)?;
+        // Remember our own change so the poller doesn't echo it back
+        if let Ok(mut last) = self.last_state.lock() {
+            *last = Some((device_name.to_string(), muted));
+        }
+        Ok(())
+    }
+
+    /// Read the OS mute state
+    ///
+    /// Returns the new state if it changed outside Voiceboard since the
+    /// last poll. The first poll for a device only records a baseline.
+    pub fn poll(&self, device_name: &str) -> Option<bool> {
+        let muted = self.backend.as_ref()?.is_muted(device_name).ok()?;
+        let mut last = self.last_state.lock().ok()?;
+
+        let changed = matches!(&*last, Some((device, was_muted)) if device == device_name && *was_muted != muted);
+        *last = Some((device_name.to_string(), muted));
+        changed.then_some(muted)
+    }
+}
+
+/// Start the thread mirroring OS mute changes into the engine
+///
+/// Emits `mic-mute-changed` so the frontend can update its mute button.
+pub fn spawn_mic_mute_watcher(
+    app: AppHandle,
+    sync: Arc<MicMuteSync>,
+    settings: Arc<RwLock<AppSettings>>,
+    engine: Arc<AudioEngine>,
+    tally: Arc<TallyController>,
+) -> Option<JoinHandle<()>> {
+    if !sync.is_enabled() {
+        return None;
+    }
+
+    Some(thread::spawn(move || loop {
+        let device = settings.blocking_read().audio.input_device().to_string();
+
+        if let Some(muted) = sync.poll(&device) {
+            tracing::info!(muted, device = %device, "System mic mute changed");
+            if let Err(e) = engine.send_command(AudioEngineCommand::SetMicMuted(muted)) {
+                tracing::warn!(error = %e, "Failed to mirror system mute");
+            }
+            tally.set_mic_muted(muted);
+            let _ = app.emit("mic-mute-changed", serde_json::json!({
+                "muted": muted,
+                "source": "system",
+            }));
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::MockSystemMicMute;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn shared_mock(state: Arc<AtomicBool>) -> MockSystemMicMute {
+        let mut mock = MockSystemMicMute::new();
+        let read = state.clone();
+        mock.expect_is_muted()
+            .returning(move |_| Ok(read.load(Ordering::SeqCst)));
+        mock.expect_set_muted()
+            .returning(move |_, muted| {
+                state.store(muted, Ordering::SeqCst);
+                Ok(())
+            });
+        mock
+    }
+
+    #[test]
+    fn test_poll_reports_external_changes_only() {
+        let os_muted = Arc::new(AtomicBool::new(false));
+        let sync = MicMuteSync::new(Box::new(shared_mock(os_muted.clone())));
+
+        // Baseline
+        assert_eq!(sync.poll("Mic"), None);
+
+        // Hardware key pressed
+        os_muted.store(true, Ordering::SeqCst);
+        assert_eq!(sync.poll("Mic"), Some(true));
+        assert_eq!(sync.poll("Mic"), None);
+
+        // Our own change is not echoed back
+        sync.apply("Mic", false).unwrap();
+        assert_eq!(sync.poll("Mic"), None);
+    }
+
+    #[test]
+    fn test_device_switch_resets_baseline() {
+        let os_muted = Arc::new(AtomicBool::new(false));
+        let sync = MicMuteSync::new(Box::new(shared_mock(os_muted.clone())));
+
+        sync.poll("Mic A");
+        os_muted.store(true, Ordering::SeqCst);
+        assert_eq!(sync.poll("Mic B"), None);
+    }
+
+    #[test]
+    fn test_disabled_sync_is_noop() {
+        let sync = MicMuteSync::disabled();
+        assert!(sync.apply("Mic", true).is_ok());
+        assert_eq!(sync.poll("Mic"), None);
+    }
+}
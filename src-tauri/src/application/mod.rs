@@ -3,14 +3,90 @@
 //! This layer coordinates the domain logic and adapters to implement
 //! the application's use cases.
 
+pub mod app_capture;
+pub mod app_info;
 pub mod audio_engine;
+pub mod audio_processing;
+pub mod auto_save;
+pub mod automation;
+pub mod board_share;
+pub mod buffer_tuner;
+pub mod clock;
+pub mod cloud_sync;
 pub mod commands;
+pub mod correlation;
+pub mod decoder_service;
+pub mod device_watcher;
+pub mod engine_watchdog;
+pub mod errors;
+pub mod gain_wizard;
+pub mod global_hotkeys;
+pub mod hot_folder;
+pub mod hotkey_registry;
+pub mod keystroke_listener;
+pub mod mic_mute_sync;
+pub mod now_playing;
+pub mod null_audio;
+pub mod offline_engine;
+pub mod onboarding;
+pub mod pad_actions;
+pub mod play_log;
 pub mod preview_engine;
+pub mod push_to_talk;
+pub mod quick_memo;
+pub mod safe_mode;
+pub mod saved_pads;
+pub mod session_recorder;
+pub mod session_stats;
+pub mod settings_service;
+pub mod sound_cache;
+pub mod sound_pack;
+pub mod sound_stream;
+pub mod startup;
+pub mod updates;
 mod services;
 mod state;
 
+pub use app_capture::*;
+pub use app_info::*;
 pub use audio_engine::*;
+pub use audio_processing::*;
+pub use auto_save::*;
+pub use automation::*;
+pub use board_share::*;
+pub use buffer_tuner::*;
+pub use clock::*;
+pub use cloud_sync::*;
 pub use commands::*;
+pub use correlation::*;
+pub use decoder_service::*;
+pub use device_watcher::*;
+pub use engine_watchdog::*;
+pub use errors::*;
+pub use gain_wizard::*;
+pub use global_hotkeys::*;
+pub use hot_folder::*;
+pub use hotkey_registry::*;
+pub use keystroke_listener::*;
+pub use mic_mute_sync::*;
+pub use now_playing::*;
+pub use null_audio::*;
+pub use offline_engine::*;
+pub use onboarding::*;
+pub use pad_actions::*;
+pub use play_log::*;
 pub use preview_engine::*;
+pub use push_to_talk::*;
+pub use quick_memo::*;
+pub use safe_mode::*;
+pub use saved_pads::*;
 pub use services::*;
+pub use session_recorder::*;
+pub use session_stats::*;
+pub use settings_service::*;
+pub use sound_cache::*;
+pub use sound_pack::*;
+pub use sound_stream::*;
+pub use startup::*;
 pub use state::*;
+pub use updates::*;
@@ -0,0 +1,342 @@
+//! Now playing - What the soundboard is playing, for stream overlays
+//!
+//! The tracker remembers the name and artwork of the sounds started; their
+//! timing comes from the engine, so speed and seeking are accounted for.
+//! A small server on localhost serves the current sound as JSON and as a
+//! plain overlay page, for OBS browser sources.
+
+use crate::application::audio_processing::PlayingSoundInfo;
+use serde::Serialize;
+use std::fs::File;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+
+/// Errors that can occur while serving the overlay
+#[derive(Debug, thiserror::Error)]
+pub enum OverlayError {
+    #[error("Could not start the overlay server: {0}")]
+    Bind(String),
+}
+
+/// The sound shown on the overlay
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NowPlaying {
+    pub id: String,
+    pub name: String,
+    /// URL of the pad's artwork on the overlay server, if it has one
+    pub artwork: Option<String>,
+    pub color: Option<String>,
+    pub duration_secs: f64,
+    pub remaining_secs: f64,
+}
+
+/// Name and artwork of a started sound
+#[derive(Debug, Clone)]
+pub struct NowPlayingEntry {
+    pub id: String,
+    pub name: String,
+    pub artwork: Option<PathBuf>,
+    pub color: Option<String>,
+}
+
+/// Sounds started, most recent last, kept while they play
+pub struct NowPlayingTracker {
+    started: Mutex<Vec<NowPlayingEntry>>,
+}
+
+impl NowPlayingTracker {
+    pub fn new() -> Self {
+        Self {
+            started: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a sound starting; a restart moves it to the top
+    pub fn started(&self, entry: NowPlayingEntry) {
+        let mut started = self.started.lock().unwrap();
+        started.retain(|e| e.id != entry.id);
+        started.push(entry);
+    }
+
+    /// The most recently started sound still playing, forgetting the others
+    pub fn current(&self, playing: &[PlayingSoundInfo]) -> Option<NowPlaying> {
+        let mut started = self.started.lock().unwrap();
+        started.retain(|entry| playing.iter().any(|sound| sound.id == entry.id));
+        let entry = started.last()?;
+        let sound = playing.iter().find(|sound| sound.id == entry.id)?;
+        Some(NowPlaying {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            artwork: entry.artwork.as_ref().map(|_| format!("/artwork/{}", entry.id)),
+            color: entry.color.clone(),
+            duration_secs: sound.duration_secs,
+            remaining_secs: (sound.duration_secs - sound.position_secs).max(0.0),
+        })
+    }
+
+    /// Artwork file of a started sound
+    fn artwork(&self, id: &str) -> Option<PathBuf> {
+        let started = self.started.lock().unwrap();
+        started.iter().find(|entry| entry.id == id)?.artwork.clone()
+    }
+}
+
+impl Default for NowPlayingTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where the overlay can be reached
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayInfo {
+    /// Page to add as an OBS browser source
+    pub url: String,
+    /// Same state as JSON
+    pub json_url: String,
+    pub port: u16,
+}
+
+struct RunningOverlay {
+    server: Arc<Server>,
+    info: OverlayInfo,
+}
+
+/// Serves the now-playing overlay on localhost
+pub struct OverlayServer {
+    running: Mutex<Option<RunningOverlay>>,
+}
+
+impl OverlayServer {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Where the overlay can be reached, if serving
+    pub fn info(&self) -> Option<OverlayInfo> {
+        self.running.lock().unwrap().as_ref().map(|overlay| overlay.info.clone())
+    }
+
+    /// Start serving, replacing any running server
+    ///
+    /// `port` 0 picks a free port. `playing` runs on the server thread for
+    /// every state request and returns the engine's playing sounds.
+    pub fn start(
+        &self,
+        port: u16,
+        tracker: Arc<NowPlayingTracker>,
+        playing: impl Fn() -> Arc<[PlayingSoundInfo]> + Send + 'static,
+    ) -> Result<OverlayInfo, OverlayError> {
+        self.stop();
+
+        // Overlays run on the streaming machine, so never leave localhost
+        let server = Server::http((Ipv4Addr::LOCALHOST, port)).map_err(|e| OverlayError::Bind(e.to_string()))?;
+        let port = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.port())
+            .ok_or_else(|| OverlayError::Bind("not an IP socket".into()))?;
+        let server = Arc::new(server);
+
+        let info = OverlayInfo {
+            url: format!("http://127.0.0.1:{}/overlay", port),
+            json_url: format!("http://127.0.0.1:{}/now-playing.json", port),
+            port,
+        };
+
+        let worker = server.clone();
+        std::thread::Builder::new()
+            .name("now-playing-overlay".into())
+            .spawn(move || {
+                for request in worker.incoming_requests() {
+                    handle(request, &tracker, &playing);
+                }
+            })
+            .map_err(|e| OverlayError::Bind(e.to_string()))?;
+
+        tracing::info!(url = %info.url, "Serving now-playing overlay");
+        *self.running.lock().unwrap() = Some(RunningOverlay {
+            server,
+            info: info.clone(),
+        });
+        Ok(info)
+    }
+
+    pub fn stop(&self) {
+        if let Some(overlay) = self.running.lock().unwrap().take() {
+            overlay.server.unblock();
+            tracing::info!("Now-playing overlay stopped");
+        }
+    }
+}
+
+impl Default for OverlayServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a request URL asks for
+#[derive(Debug, PartialEq)]
+enum Route<'a> {
+    Page,
+    State,
+    Artwork(&'a str),
+    NotFound,
+}
+
+fn route<'a>(method: &Method, url: &'a str) -> Route<'a> {
+    let path = url.split('?').next().unwrap_or_default();
+    if *method != Method::Get {
+        return Route::NotFound;
+    }
+    match path {
+        "/overlay" | "/overlay/" => Route::Page,
+        "/now-playing.json" => Route::State,
+        _ => path
+            .strip_prefix("/artwork/")
+            .filter(|id| !id.is_empty())
+            .map_or(Route::NotFound, Route::Artwork),
+    }
+}
+
+fn handle(request: Request, tracker: &NowPlayingTracker, playing: &dyn Fn() -> Arc<[PlayingSoundInfo]>) {
+    let url = request.url().to_string();
+    let result = match route(request.method(), &url) {
+        Route::Page => request.respond(
+            Response::from_string(OVERLAY_PAGE).with_header(header("Content-Type", "text/html; charset=utf-8")),
+        ),
+        Route::State => {
+            let json = serde_json::to_string(&tracker.current(&playing())).unwrap_or_else(|_| "null".into());
+            request.respond(
+                Response::from_string(json)
+                    .with_header(header("Content-Type", "application/json"))
+                    .with_header(header("Cache-Control", "no-store")),
+            )
+        }
+        Route::Artwork(id) => match tracker.artwork(id).and_then(|path| Some((File::open(&path).ok()?, path))) {
+            Some((file, path)) => {
+                request.respond(Response::from_file(file).with_header(header("Content-Type", image_content_type(&path))))
+            }
+            None => request.respond(Response::empty(StatusCode(404))),
+        },
+        Route::NotFound => request.respond(Response::empty(StatusCode(404))),
+    };
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "Overlay client went away");
+    }
+}
+
+fn image_content_type(path: &std::path::Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+/// Overlay page; transparent so it sits on top of the scene
+const OVERLAY_PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Now playing</title>
+<style>
+  body { margin: 0; background: transparent; font-family: system-ui, sans-serif; color: #fff; }
+  #card { display: none; align-items: center; gap: 12px; padding: 10px 16px; border-left: 6px solid #888;
+          background: rgba(0, 0, 0, .6); width: fit-content; text-shadow: 0 1px 2px #000; }
+  #card img { width: 48px; height: 48px; object-fit: cover; border-radius: 4px; }
+  #label { font-size: 12px; opacity: .8; }
+  #name { font-size: 20px; font-weight: 600; }
+</style>
+</head>
+<body>
+<div id="card">
+  <img id="artwork" alt="">
+  <div><div id="label">Now playing</div><div id="name"></div><div id="remaining"></div></div>
+</div>
+<script>
+  const card = document.getElementById('card');
+  const artwork = document.getElementById('artwork');
+  async function refresh() {
+    try {
+      const sound = await (await fetch('/now-playing.json')).json();
+      card.style.display = sound ? 'flex' : 'none';
+      if (sound) {
+        card.style.borderColor = sound.color || '#888';
+        document.getElementById('name').textContent = sound.name;
+        document.getElementById('remaining').textContent = `-${Math.ceil(sound.remaining_secs)}s`;
+        artwork.style.display = sound.artwork ? '' : 'none';
+        if (sound.artwork && artwork.getAttribute('src') !== sound.artwork) artwork.src = sound.artwork;
+      }
+    } catch (e) {
+      card.style.display = 'none';
+    }
+  }
+  setInterval(refresh, 250);
+  refresh();
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str) -> NowPlayingEntry {
+        NowPlayingEntry {
+            id: id.into(),
+            name: id.to_uppercase(),
+            artwork: None,
+            color: None,
+        }
+    }
+
+    fn playing(id: &str, position_secs: f64) -> PlayingSoundInfo {
+        PlayingSoundInfo {
+            id: id.into(),
+            position_secs,
+            duration_secs: 10.0,
+            volume: 1.0,
+            looping: false,
+            paused: false,
+        }
+    }
+
+    #[test]
+    fn test_latest_playing_sound_is_shown() {
+        let tracker = NowPlayingTracker::new();
+        tracker.started(entry("horn"));
+        tracker.started(entry("drum"));
+
+        let current = tracker.current(&[playing("horn", 1.0), playing("drum", 2.5)]).unwrap();
+        assert_eq!(current.name, "DRUM");
+        assert_eq!(current.remaining_secs, 7.5);
+
+        // Once the drum ends the horn shows again, and nothing after that
+        assert_eq!(tracker.current(&[playing("horn", 3.0)]).unwrap().id, "horn");
+        assert_eq!(tracker.current(&[]), None);
+        assert_eq!(tracker.current(&[playing("horn", 3.0)]), None);
+    }
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(route(&Method::Get, "/overlay"), Route::Page);
+        assert_eq!(route(&Method::Get, "/now-playing.json?t=1"), Route::State);
+        assert_eq!(route(&Method::Get, "/artwork/pad-1"), Route::Artwork("pad-1"));
+        assert_eq!(route(&Method::Get, "/artwork/"), Route::NotFound);
+        assert_eq!(route(&Method::Post, "/overlay"), Route::NotFound);
+    }
+}
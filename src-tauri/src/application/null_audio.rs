@@ -0,0 +1,124 @@
+//! Null audio devices - Runs the real-time engine without sound hardware
+//!
+//! With `--test-audio`, the engine opens these instead of cpal streams: a
+//! thread paced like a sound card feeds queued mic samples (silence when
+//! none are queued) through the input stage and records what the output
+//! stage renders. CI and headless machines can then drive `start_mixing`
+//! and `play_sound` end to end and assert on the captured output, while
+//! the offline engine stays the tool for sample-exact checks.
+
+use crate::application::audio_processing::EngineCore;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Device names the null host reports; any name opens the null devices
+pub const NULL_INPUT_DEVICE: &str = "Null Input";
+pub const NULL_OUTPUT_DEVICE: &str = "Null Output";
+
+/// Length of one simulated device callback
+const BLOCK_MS: u32 = 10;
+
+/// Captured output kept; older audio is dropped
+const MAX_CAPTURE_SECS: usize = 60;
+
+/// Command-line flag that swaps the sound hardware for the null devices
+pub const TEST_AUDIO_FLAG: &str = "--test-audio";
+
+/// Whether the app was started with [`TEST_AUDIO_FLAG`]
+pub fn test_audio_requested() -> bool {
+    std::env::args().any(|arg| arg == TEST_AUDIO_FLAG)
+}
+
+/// Mic feed and output capture of the null devices
+#[derive(Clone, Default)]
+pub struct NullAudioDevices {
+    mic: Arc<Mutex<VecDeque<f32>>>,
+    captured: Arc<Mutex<Vec<f32>>>,
+}
+
+impl NullAudioDevices {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue interleaved samples for the null mic to capture
+    pub fn push_mic(&self, samples: &[f32]) {
+        if let Ok(mut mic) = self.mic.lock() {
+            mic.extend(samples);
+        }
+    }
+
+    /// Interleaved output rendered since the last call
+    pub fn take_captured(&self) -> Vec<f32> {
+        self.captured.lock().map(|mut c| std::mem::take(&mut *c)).unwrap_or_default()
+    }
+
+    /// Number of captured samples waiting to be taken
+    pub fn captured_len(&self) -> usize {
+        self.captured.lock().map(|c| c.len()).unwrap_or(0)
+    }
+
+    /// Run the engine processors on the null devices until the streams drop
+    pub(crate) fn start(&self, core: &EngineCore, sample_rate: u32, channels: u16) -> NullStreams {
+        let mut input = core.input_processor(channels, channels, sample_rate);
+        let mut output = core.output_processor(channels);
+        let mic = self.mic.clone();
+        let captured = self.captured.clone();
+
+        let block = (sample_rate * BLOCK_MS / 1000) as usize * channels as usize;
+        let max_capture = MAX_CAPTURE_SECS * sample_rate as usize * channels as usize;
+        let interval = Duration::from_millis(BLOCK_MS as u64);
+
+        let active = Arc::new(AtomicBool::new(true));
+        let running = active.clone();
+        let handle = thread::spawn(move || {
+            let mut captured_block = vec![0.0; block];
+            let mut mic_block = vec![0.0; block];
+            let mut passthrough = VecDeque::with_capacity(block * 2);
+            let mut next = Instant::now();
+
+            while running.load(Ordering::Relaxed) {
+                if let Ok(mut mic) = mic.lock() {
+                    for sample in mic_block.iter_mut() {
+                        *sample = mic.pop_front().unwrap_or(0.0);
+                    }
+                }
+                input.process(&mic_block, |s| passthrough.push_back(s));
+                output.process(&mut captured_block, || passthrough.pop_front());
+
+                if let Ok(mut captured) = captured.lock() {
+                    captured.extend_from_slice(&captured_block);
+                    let excess = captured.len().saturating_sub(max_capture);
+                    captured.drain(..excess);
+                }
+
+                // Pace blocks like a device clock, without drifting on slow blocks
+                next += interval;
+                thread::sleep(next.saturating_duration_since(Instant::now()));
+            }
+        });
+
+        NullStreams {
+            active,
+            handle: Some(handle),
+        }
+    }
+}
+
+/// Running null streams; dropping them stops the device thread
+pub struct NullStreams {
+    active: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for NullStreams {
+    fn drop(&mut self) {
+        self.active.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Offline engine driver - Runs the mixing pipeline without audio hardware
+//!
+//! Processes audio synchronously, frame-for-frame identical to the
+//! real-time engine, so mixing behaviour can be tested deterministically
+//! and mixes can be rendered faster than real time.
+
+use crate::application::audio_engine::AudioEngineCommand;
+use crate::application::audio_processing::{EngineCore, InputProcessor, OutputProcessor};
+use crate::domain::{AudioBuffer, AudioFormat, SoundInsert};
+use std::collections::VecDeque;
+
+/// Block size used when rendering offline
+const RENDER_BLOCK_FRAMES: usize = 1024;
+
+/// Deterministic, hardware-free driver for the engine processors
+pub struct OfflineEngine {
+    core: EngineCore,
+    input: InputProcessor,
+    output: OutputProcessor,
+    mic_queue: VecDeque<f32>,
+    format: AudioFormat,
+}
+
+impl OfflineEngine {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        let core = EngineCore::new();
+        let input = core.input_processor(channels, channels, sample_rate);
+        let output = core.output_processor(channels);
+
+        Self {
+            core,
+            input,
+            output,
+            mic_queue: VecDeque::new(),
+            format: AudioFormat::new(sample_rate, channels, 32),
+        }
+    }
+
+    /// The format of rendered audio
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    /// Apply an engine command (lifecycle commands are ignored)
+    pub fn send_command(&mut self, command: AudioEngineCommand) {
+        self.core.handle_command(command);
+    }
+
+    /// Number of sounds still playing
+    pub fn playing_count(&self) -> usize {
+        self.core.sounds.lock().map(|s| s.playing_count()).unwrap_or(0)
+    }
+
+    /// Feed captured microphone samples (interleaved) through the input stage
+    pub fn push_mic(&mut self, samples: &[f32]) {
+        let queue = &mut self.mic_queue;
+        self.input.process(samples, |s| queue.push_back(s));
+    }
+
+    /// Render `frames` frames of output (interleaved)
+    pub fn process(&mut self, frames: usize) -> Vec<f32> {
+        let mut data = vec![0.0; frames * self.format.channels as usize];
+        let queue = &mut self.mic_queue;
+        self.output.process(&mut data, || queue.pop_front());
+        data
+    }
+
+    /// Render in fixed-size blocks until no sounds are playing
+    ///
+    /// Stops after `max_frames` to guard against endless rendering.
+    pub fn render_until_idle(&mut self, block_frames: usize, max_frames: usize) -> Vec<f32> {
+        let mut rendered = Vec::new();
+        let mut frames = 0;
+
+        while self.playing_count() > 0 && frames < max_frames {
+            let block = block_frames.min(max_frames - frames);
+            rendered.extend(self.process(block));
+            frames += block;
+        }
+
+        rendered
+    }
+}
+
+/// A sound of an offline mix, played as the live engine plays it
+pub struct MixSound {
+    pub id: String,
+    /// Decoded at the file's own rate and channels
+    pub buffer: AudioBuffer,
+    pub volume: f32,
+    pub inserts: Vec<SoundInsert>,
+}
+
+/// Mix sounds from the start, faster than real time
+///
+/// Each sound is converted to the mix format by the engine, as when it is
+/// queued live, then runs through its inserts at its volume. Rendering
+/// stops when every sound has finished or after `max_secs`.
+pub fn bounce_mix(
+    sounds: Vec<MixSound>,
+    sample_rate: u32,
+    channels: u16,
+    master_volume: f32,
+    max_secs: u32,
+) -> AudioBuffer {
+    let mut engine = OfflineEngine::new(sample_rate, channels);
+    engine.send_command(AudioEngineCommand::SetMasterVolume(master_volume));
+
+    for sound in sounds {
+        if !sound.inserts.is_empty() {
+            engine.send_command(AudioEngineCommand::SetSoundInserts {
+                id: sound.id.clone(),
+                inserts: sound.inserts,
+            });
+        }
+        engine.send_command(AudioEngineCommand::PlaySound {
+            id: sound.id,
+            sample_rate: sound.buffer.sample_rate(),
+            channels: sound.buffer.channels(),
+            samples: sound.buffer.to_raw_f32(),
+            volume: sound.volume,
+            looping: false,
+        });
+    }
+
+    let max_frames = sample_rate as usize * max_secs as usize;
+    let samples = engine.render_until_idle(RENDER_BLOCK_FRAMES, max_frames);
+    AudioBuffer::from_raw_f32(samples, channels, sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mic_passthrough_with_volume() {
+        let mut engine = OfflineEngine::new(48000, 2);
+        engine.send_command(AudioEngineCommand::SetMicVolume(0.5));
+        engine.push_mic(&[0.4, 0.4, 0.2, 0.2]);
+
+        let out = engine.process(2);
+        assert_eq!(out, vec![0.2, 0.2, 0.1, 0.1]);
+    }
+
+    #[test]
+    fn test_sound_mixing_and_master_volume() {
+        let mut engine = OfflineEngine::new(48000, 2);
+        engine.send_command(AudioEngineCommand::SetMasterVolume(0.5));
+        engine.send_command(AudioEngineCommand::PlaySound {
+            id: "s1".into(),
+            samples: vec![0.8; 8],
+            sample_rate: 48000,
+            channels: 2,
+            volume: 1.0,
+            looping: false,
+        });
+
+        let out = engine.process(2);
+        assert_eq!(out, vec![0.4; 4]);
+        assert_eq!(engine.playing_count(), 1);
+
+        let rest = engine.render_until_idle(2, 100);
+        assert_eq!(rest.len(), 4);
+        assert_eq!(engine.playing_count(), 0);
+    }
+
+    #[test]
+    fn test_stop_sound() {
+        let mut engine = OfflineEngine::new(48000, 2);
+        engine.send_command(AudioEngineCommand::PlaySound {
+            id: "s1".into(),
+            samples: vec![0.5; 100],
+            sample_rate: 48000,
+            channels: 2,
+            volume: 1.0,
+            looping: false,
+        });
+        engine.send_command(AudioEngineCommand::StopSound {
+            id: "s1".into(),
+            fade_ms: None,
+        });
+
+        assert_eq!(engine.process(4), vec![0.0; 8]);
+    }
+
+    fn mix_sound(id: &str, samples: Vec<f32>, channels: u16, sample_rate: u32) -> MixSound {
+        MixSound {
+            id: id.into(),
+            buffer: AudioBuffer::from_raw_f32(samples, channels, sample_rate),
+            volume: 1.0,
+            inserts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_bounce_mix_length_follows_longest_sound() {
+        let sounds = vec![
+            mix_sound("a", vec![0.25; 2 * 3000], 2, 48000),
+            mix_sound("b", vec![0.25; 2 * 100], 2, 48000),
+        ];
+
+        let buffer = bounce_mix(sounds, 48000, 2, 1.0, 60);
+
+        // Rendered in whole blocks covering the longest sound
+        assert_eq!(buffer.frame_count(), 3 * RENDER_BLOCK_FRAMES);
+        assert_eq!(buffer.samples()[0].value(), 0.5);
+        assert_eq!(buffer.samples()[2 * 200].value(), 0.25);
+    }
+
+    #[test]
+    fn test_bounce_mix_resamples_and_applies_volume() {
+        // One second of mono at 24 kHz in a 48 kHz stereo mix
+        let mut sound = mix_sound("slow", vec![0.5; 24000], 1, 24000);
+        sound.volume = 0.5;
+
+        let buffer = bounce_mix(vec![sound], 48000, 2, 1.0, 60);
+
+        // Twice the frames, so it keeps its pitch and length
+        let frames = buffer.frame_count();
+        assert!((48000..48000 + RENDER_BLOCK_FRAMES).contains(&frames), "{} frames", frames);
+        let middle = &buffer.samples()[2 * 24000..2 * 24000 + 2];
+        assert!(middle.iter().all(|sample| (sample.value() - 0.25).abs() < 1e-3));
+    }
+}
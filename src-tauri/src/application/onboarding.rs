@@ -0,0 +1,70 @@
+//! Onboarding service - Tracks first-run setup progress in the store
+
+use crate::domain::{OnboardingState, OnboardingStep};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+const ONBOARDING_STORE: &str = "onboarding.json";
+const ONBOARDING_KEY: &str = "state";
+
+/// Service owning the onboarding state machine
+///
+/// Steps are advanced either explicitly by the wizard or automatically
+/// from engine/device events, and every change emits `onboarding-changed`.
+pub struct OnboardingService {
+    state: Mutex<OnboardingState>,
+}
+
+impl OnboardingService {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(OnboardingState::new()),
+        }
+    }
+
+    /// Load the persisted onboarding state
+    pub fn load(&self, app: &AppHandle) {
+        let saved = app
+            .store(ONBOARDING_STORE)
+            .ok()
+            .and_then(|store| store.get(ONBOARDING_KEY))
+            .and_then(|value| serde_json::from_value::<OnboardingState>(value).ok());
+
+        if let Some(saved) = saved {
+            if let Ok(mut state) = self.state.lock() {
+                *state = saved;
+            }
+        }
+    }
+
+    /// Get the current onboarding state
+    pub fn state(&self) -> OnboardingState {
+        self.state.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Complete a step, persisting and notifying the frontend if it changed
+    pub fn complete_step(&self, app: &AppHandle, step: OnboardingStep) -> Result<OnboardingState, String> {
+        let snapshot = {
+            let mut state = self.state.lock().map_err(|e| e.to_string())?;
+            if !state.complete(step) {
+                return Ok(state.clone());
+            }
+            state.clone()
+        };
+
+        let store = app.store(ONBOARDING_STORE).map_err(|e| e.to_string())?;
+        store.set(ONBOARDING_KEY, serde_json::to_value(&snapshot).map_err(|e| e.to_string())?);
+        store.save().map_err(|e| e.to_string())?;
+
+        let _ = app.emit("onboarding-changed", snapshot.progress());
+        tracing::info!(step = ?step, finished = snapshot.is_finished(), "Onboarding step completed");
+        Ok(snapshot)
+    }
+}
+
+impl Default for OnboardingService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,372 @@
+//! Pad actions - Things a pad does besides, or instead of, playing a sound
+//!
+//! A pad's `actions` run in order on each trigger: one of the app's own
+//! commands, an OSC message, an HTTP request, or an OBS scene switch over
+//! obs-websocket (v5). Network actions block, so they run off the async
+//! runtime; a failing or invalid action doesn't stop the ones after it.
+//! OBS passwords are kept in the OS credential store, not on the pad.
+
+use crate::application::hot_folder::HotFolderCommand;
+use crate::ports::SecretStore;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::blocking::Client;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::{Message, WebSocket};
+
+/// Longest an HTTP request or OBS exchange may take
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// obs-websocket's default address
+const DEFAULT_OBS_URL: &str = "ws://localhost:4455";
+
+/// Something a pad does when triggered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum PadAction {
+    /// One of the app's own commands, as in a hot folder trigger
+    App { command: HotFolderCommand },
+    /// An OSC message over UDP, e.g. to a lighting desk
+    Osc {
+        host: String,
+        port: u16,
+        address: String,
+        #[serde(default)]
+        args: Vec<OscArg>,
+    },
+    /// An HTTP request, e.g. a webhook
+    Http {
+        #[serde(default = "default_method")]
+        method: String,
+        url: String,
+        #[serde(default)]
+        body: Option<String>,
+    },
+    /// Switch the OBS program scene
+    ObsScene {
+        scene: String,
+        #[serde(default)]
+        url: Option<String>,
+        /// Only read from boards saved before the credential store held it
+        #[serde(default, skip_serializing)]
+        password: Option<String>,
+    },
+}
+
+fn default_method() -> String {
+    "POST".into()
+}
+
+/// Argument of an OSC message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    String(String),
+}
+
+impl OscArg {
+    fn tag(&self) -> char {
+        match self {
+            OscArg::Int(_) => 'i',
+            OscArg::Float(_) => 'f',
+            OscArg::String(_) => 's',
+        }
+    }
+}
+
+/// Errors from running a pad action
+#[derive(Debug, thiserror::Error)]
+pub enum PadActionError {
+    #[error("Invalid pad action: {0}")]
+    Invalid(String),
+
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("OBS error: {0}")]
+    Obs(String),
+}
+
+impl PadAction {
+    /// Check what can be checked without running the action
+    pub fn validate(&self) -> Result<(), PadActionError> {
+        match self {
+            PadAction::App { .. } => Ok(()),
+            PadAction::Osc { address, .. } if !address.starts_with('/') => Err(PadActionError::Invalid(format!(
+                "OSC address must start with '/': {}",
+                address
+            ))),
+            PadAction::Osc { .. } => Ok(()),
+            PadAction::Http { method, url, .. } => {
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                    .map_err(|_| PadActionError::Invalid(format!("Unknown HTTP method: {}", method)))?;
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(PadActionError::Invalid(format!("Not an HTTP URL: {}", url)));
+                }
+                Ok(())
+            }
+            PadAction::ObsScene { scene, .. } if scene.trim().is_empty() => {
+                Err(PadActionError::Invalid("No OBS scene given".into()))
+            }
+            PadAction::ObsScene { .. } => Ok(()),
+        }
+    }
+}
+
+/// The actions of a saved pad, each parsed and checked on its own
+///
+/// An invalid action is an error in its place, so the others still run.
+/// Empty for a pad without any.
+pub fn pad_actions(pad: &Value) -> Vec<Result<PadAction, PadActionError>> {
+    let Some(actions) = pad.get("actions").filter(|actions| !actions.is_null()) else {
+        return Vec::new();
+    };
+    let Some(actions) = actions.as_array() else {
+        return vec![Err(PadActionError::Invalid("Actions must be a list".into()))];
+    };
+    actions
+        .iter()
+        .map(|action| {
+            let action: PadAction =
+                serde_json::from_value(action.clone()).map_err(|e| PadActionError::Invalid(e.to_string()))?;
+            action.validate()?;
+            Ok(action)
+        })
+        .collect()
+}
+
+/// Name of the OBS password in the credential store
+pub fn obs_secret_name(url: Option<&str>) -> String {
+    format!("obs:{}", url.unwrap_or(DEFAULT_OBS_URL))
+}
+
+/// Run a network action, blocking until it is done
+///
+/// App commands need the app and are run by the caller.
+pub fn run_external_action(action: &PadAction, secrets: &dyn SecretStore) -> Result<(), PadActionError> {
+    match action {
+        PadAction::App { .. } => Ok(()),
+        PadAction::Osc { host, port, address, args } => send_osc(host, *port, address, args),
+        PadAction::Http { method, url, body } => send_http(method, url, body.as_deref()),
+        PadAction::ObsScene { scene, url, password } => {
+            let stored = secrets
+                .get(&obs_secret_name(url.as_deref()))
+                .map_err(|e| PadActionError::Obs(e.to_string()))?;
+            let password = stored.or_else(|| password.clone());
+            set_obs_scene(url.as_deref().unwrap_or(DEFAULT_OBS_URL), password.as_deref(), scene)
+        }
+    }
+}
+
+/// Encode an OSC message: padded address, type tags, big-endian arguments
+pub fn encode_osc(address: &str, args: &[OscArg]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    push_osc_string(&mut packet, address);
+    let tags: String = std::iter::once(',').chain(args.iter().map(OscArg::tag)).collect();
+    push_osc_string(&mut packet, &tags);
+    for arg in args {
+        match arg {
+            OscArg::Int(value) => packet.extend_from_slice(&value.to_be_bytes()),
+            OscArg::Float(value) => packet.extend_from_slice(&value.to_be_bytes()),
+            OscArg::String(value) => push_osc_string(&mut packet, value),
+        }
+    }
+    packet
+}
+
+/// A null-terminated string, padded to 4 bytes
+fn push_osc_string(packet: &mut Vec<u8>, value: &str) {
+    packet.extend_from_slice(value.as_bytes());
+    packet.push(0);
+    while packet.len() % 4 != 0 {
+        packet.push(0);
+    }
+}
+
+fn send_osc(host: &str, port: u16, address: &str, args: &[OscArg]) -> Result<(), PadActionError> {
+    let network = |e: std::io::Error| PadActionError::Network(e.to_string());
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).map_err(network)?;
+    socket.send_to(&encode_osc(address, args), (host, port)).map_err(network)?;
+    tracing::debug!(host, port, address, "OSC message sent");
+    Ok(())
+}
+
+fn send_http(method: &str, url: &str, body: Option<&str>) -> Result<(), PadActionError> {
+    let network = |e: reqwest::Error| PadActionError::Network(e.to_string());
+    let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| PadActionError::Invalid(format!("Unknown HTTP method: {}", method)))?;
+    let client = Client::builder().timeout(TIMEOUT).build().map_err(network)?;
+
+    let mut request = client.request(method, url);
+    if let Some(body) = body {
+        let content_type = match serde_json::from_str::<Value>(body) {
+            Ok(_) => "application/json",
+            Err(_) => "text/plain",
+        };
+        request = request.header("Content-Type", content_type).body(body.to_string());
+    }
+    let response = request.send().map_err(network)?;
+    if !response.status().is_success() {
+        return Err(PadActionError::Network(format!("{} answered {}", url, response.status())));
+    }
+    tracing::debug!(url, status = %response.status(), "HTTP action sent");
+    Ok(())
+}
+
+type ObsSocket = WebSocket<TcpStream>;
+
+/// Connect to obs-websocket, giving up after `TIMEOUT` at every step
+fn connect_obs(url: &str) -> Result<ObsSocket, PadActionError> {
+    let obs = |e: &dyn std::fmt::Display| PadActionError::Obs(e.to_string());
+    let request = url.into_client_request().map_err(|e| obs(&e))?;
+    let uri = request.uri();
+    if uri.scheme_str() != Some("ws") {
+        return Err(PadActionError::Obs(format!("Not a ws:// URL: {}", url)));
+    }
+    let host = uri.host().ok_or_else(|| PadActionError::Obs(format!("No host in {}", url)))?;
+    let address = (host, uri.port_u16().unwrap_or(80))
+        .to_socket_addrs()
+        .map_err(|e| obs(&e))?
+        .next()
+        .ok_or_else(|| PadActionError::Obs(format!("Can't resolve {}", host)))?;
+
+    let stream = TcpStream::connect_timeout(&address, TIMEOUT).map_err(|e| obs(&e))?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(|e| obs(&e))?;
+    stream.set_write_timeout(Some(TIMEOUT)).map_err(|e| obs(&e))?;
+    let (socket, _) = tungstenite::client(request, stream).map_err(|e| obs(&e))?;
+    Ok(socket)
+}
+
+/// Switch the program scene: Hello, Identify, then one request
+fn set_obs_scene(url: &str, password: Option<&str>, scene: &str) -> Result<(), PadActionError> {
+    let mut socket = connect_obs(url)?;
+
+    let hello = read_obs(&mut socket, 0)?;
+    let mut identify = json!({ "rpcVersion": 1 });
+    if let Some(auth) = hello.get("authentication") {
+        let password = password.ok_or_else(|| PadActionError::Obs("OBS asks for a password".into()))?;
+        let field = |key: &str| auth.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+        identify["authentication"] = obs_auth(password, &field("salt"), &field("challenge")).into();
+    }
+    send_obs(&mut socket, 1, identify)?;
+    read_obs(&mut socket, 2)?;
+
+    send_obs(
+        &mut socket,
+        6,
+        json!({
+            "requestType": "SetCurrentProgramScene",
+            "requestId": uuid::Uuid::new_v4().to_string(),
+            "requestData": { "sceneName": scene },
+        }),
+    )?;
+    let response = read_obs(&mut socket, 7)?;
+    let _ = socket.close(None);
+
+    let status = &response["requestStatus"];
+    if status["result"].as_bool() != Some(true) {
+        let comment = status["comment"].as_str().unwrap_or("request failed");
+        return Err(PadActionError::Obs(format!("Scene {}: {}", scene, comment)));
+    }
+    tracing::debug!(scene, "OBS scene switched");
+    Ok(())
+}
+
+/// obs-websocket's challenge answer: base64(sha256(secret + challenge)),
+/// with secret = base64(sha256(password + salt))
+fn obs_auth(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, salt)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+fn send_obs(socket: &mut ObsSocket, op: u8, data: Value) -> Result<(), PadActionError> {
+    let message = json!({ "op": op, "d": data }).to_string();
+    socket
+        .send(Message::Text(message))
+        .map_err(|e| PadActionError::Obs(e.to_string()))
+}
+
+/// Read messages until one with opcode `op`; returns its data
+fn read_obs(socket: &mut ObsSocket, op: u8) -> Result<Value, PadActionError> {
+    loop {
+        match socket.read().map_err(|e| PadActionError::Obs(e.to_string()))? {
+            Message::Text(text) => {
+                let message: Value = serde_json::from_str(&text).map_err(|e| PadActionError::Obs(e.to_string()))?;
+                if message["op"].as_u64() == Some(op as u64) {
+                    return Ok(message["d"].clone());
+                }
+            }
+            // 4009 is a wrong password
+            Message::Close(frame) => {
+                let reason = frame.map(|frame| format!("{} {}", u16::from(frame.code), frame.reason));
+                return Err(PadActionError::Obs(format!(
+                    "OBS closed the connection{}",
+                    reason.map(|r| format!(": {}", r)).unwrap_or_default()
+                )));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc_encoding() {
+        let packet = encode_osc("/scene", &[OscArg::Int(2), OscArg::Float(0.5), OscArg::String("go".into())]);
+        let mut expected = b"/scene\0\0,ifs\0\0\0\0".to_vec();
+        expected.extend_from_slice(&2i32.to_be_bytes());
+        expected.extend_from_slice(&0.5f32.to_be_bytes());
+        expected.extend_from_slice(b"go\0\0");
+        assert_eq!(packet, expected);
+        assert_eq!(encode_osc("/go", &[]), b"/go\0,\0\0\0".to_vec());
+    }
+
+    #[test]
+    fn test_pad_actions_parse() {
+        let pad = json!({
+            "id": "pad-0",
+            "sound": null,
+            "actions": [
+                { "type": "app", "command": { "action": "stop_all" } },
+                { "type": "osc", "host": "127.0.0.1", "port": 9000, "address": "/cue", "args": [1, 0.5, "x"] },
+                { "type": "http", "url": "https://example.com/hook" },
+                { "type": "obsScene", "scene": "Intro" }
+            ]
+        });
+        let actions: Vec<PadAction> = pad_actions(&pad).into_iter().map(Result::unwrap).collect();
+        assert_eq!(actions[0], PadAction::App { command: HotFolderCommand::StopAll { fade_ms: None } });
+        assert!(matches!(&actions[1], PadAction::Osc { args, .. } if args[0] == OscArg::Int(1) && args[1] == OscArg::Float(0.5)));
+        assert!(matches!(&actions[2], PadAction::Http { method, body: None, .. } if method == "POST"));
+        assert!(matches!(&actions[3], PadAction::ObsScene { url: None, .. }));
+
+        assert!(pad_actions(&json!({ "id": "pad-1" })).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_action_keeps_the_others() {
+        let pad = json!({
+            "actions": [
+                { "type": "osc", "host": "h", "port": 1, "address": "cue" },
+                { "type": "teleport" },
+                { "type": "obsScene", "scene": "Intro", "password": "hunter2" }
+            ]
+        });
+        let actions = pad_actions(&pad);
+        assert!(matches!(actions[0], Err(PadActionError::Invalid(_))));
+        assert!(matches!(actions[1], Err(PadActionError::Invalid(_))));
+        let obs = actions[2].as_ref().unwrap();
+        assert!(matches!(obs, PadAction::ObsScene { password: Some(_), .. }));
+        // Passwords are never written back to the board
+        assert!(serde_json::to_value(obs).unwrap().get("password").is_none());
+    }
+}
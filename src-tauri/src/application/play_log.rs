@@ -0,0 +1,162 @@
+//! Play log - Records every triggered sound of the running session
+//!
+//! Each session is one JSON Lines file in the play log folder, named after
+//! the session id. Entries are appended as they happen, so a crash loses
+//! nothing already played.
+
+use crate::application::clock::now_millis;
+use crate::domain::{format_utc_timestamp, PlayLogEntry, PlaySource};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const LOG_EXTENSION: &str = "jsonl";
+
+/// Play log of the current session, plus access to earlier ones
+pub struct PlayLog {
+    session: String,
+    started_at: u64,
+    entries: Mutex<Vec<PlayLogEntry>>,
+}
+
+impl PlayLog {
+    /// Start a new session
+    pub fn new() -> Self {
+        let started_at = now_millis();
+        Self {
+            // e.g. 2024-02-29T12-34-56, sortable and safe as a file name
+            session: format_utc_timestamp(started_at)[..19].replace(':', "-"),
+            started_at,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Id of the running session
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// Log a triggered sound and append it to the session file in `dir`
+    pub fn record(
+        &self,
+        dir: &Path,
+        sound_id: &str,
+        name: &str,
+        path: &str,
+        duration: f64,
+        source: PlaySource,
+    ) -> PlayLogEntry {
+        let timestamp = now_millis();
+        let entry = PlayLogEntry {
+            timestamp,
+            offset_ms: timestamp.saturating_sub(self.started_at),
+            sound_id: sound_id.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            duration,
+            source,
+        };
+
+        if let Err(e) = append_entry(&session_file(dir, &self.session), &entry) {
+            tracing::warn!(error = %e, "Failed to write play log");
+        }
+        self.entries.lock().unwrap().push(entry.clone());
+        entry
+    }
+
+    /// Entries of a session, the running one when `session` is None
+    pub fn entries(&self, dir: &Path, session: Option<&str>) -> std::io::Result<Vec<PlayLogEntry>> {
+        match session {
+            None => Ok(self.current_entries()),
+            Some(session) if session == self.session => Ok(self.current_entries()),
+            Some(session) => read_session(dir, session),
+        }
+    }
+
+    /// Entries of the running session
+    pub fn current_entries(&self) -> Vec<PlayLogEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Ids of all logged sessions, newest first
+    pub fn sessions(&self, dir: &Path) -> Vec<String> {
+        let mut sessions: Vec<String> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != LOG_EXTENSION {
+                    return None;
+                }
+                path.file_stem()?.to_str().map(String::from)
+            })
+            .collect();
+        if !sessions.contains(&self.session) {
+            sessions.push(self.session.clone());
+        }
+        sessions.sort_unstable_by(|a, b| b.cmp(a));
+        sessions
+    }
+}
+
+impl Default for PlayLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn session_file(dir: &Path, session: &str) -> PathBuf {
+    dir.join(format!("{}.{}", session, LOG_EXTENSION))
+}
+
+fn append_entry(file: &Path, entry: &PlayLogEntry) -> std::io::Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    OpenOptions::new().create(true).append(true).open(file)?.write_all(line.as_bytes())
+}
+
+fn read_session(dir: &Path, session: &str) -> std::io::Result<Vec<PlayLogEntry>> {
+    // Session ids come from the frontend; never read outside the log folder
+    if session.contains(['/', '\\']) || session.starts_with('.') {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid session id"));
+    }
+    let text = fs::read_to_string(session_file(dir, session))?;
+    // A line cut short by a crash is skipped rather than failing the session
+    Ok(text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_are_persisted() {
+        let dir = std::env::temp_dir().join(format!("voiceboard_play_log_{}", uuid::Uuid::new_v4()));
+
+        let earlier = PlayLog {
+            session: "2024-01-01T10-00-00".into(),
+            ..PlayLog::new()
+        };
+        earlier.record(&dir, "sound_1", "Horn", "horn.wav", 1.5, PlaySource::Hotkey);
+        earlier.record(&dir, "sound_2", "Applause", "applause.wav", 4.0, PlaySource::Ui);
+
+        let log = PlayLog::new();
+        log.record(&dir, "sound_1", "Horn", "horn.wav", 1.5, PlaySource::Remote);
+
+        assert_eq!(log.sessions(&dir), vec![log.session().to_string(), "2024-01-01T10-00-00".to_string()]);
+        assert_eq!(log.entries(&dir, None).unwrap().len(), 1);
+
+        let entries = log.entries(&dir, Some("2024-01-01T10-00-00")).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].name, "Applause");
+        assert!(entries[0].offset_ms <= entries[1].offset_ms);
+        assert!(log.entries(&dir, Some("../secrets")).is_err());
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
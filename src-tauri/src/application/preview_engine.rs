@@ -2,9 +2,12 @@
 
 use cpal::traits::{DeviceTrait, HostTrait};
 use crossbeam_channel::{bounded, Receiver, Sender};
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
-use std::fs::File;
-use std::io::BufReader;
+use crate::application::decoder_service::DecoderService;
+use crate::domain::find_device_name;
+use crate::dsp::PeakLimiter;
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
@@ -18,6 +21,8 @@ pub enum PreviewCommand {
         path: String,
         device_name: String,
         pad_id: String,
+        /// Headphone limiter ceiling in dBFS, None for none
+        ceiling_db: Option<f32>,
     },
     /// Stop the currently playing preview
     Stop,
@@ -34,13 +39,13 @@ pub struct PreviewEngine {
 
 impl PreviewEngine {
     /// Create and start a new preview engine
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(app_handle: AppHandle, decoder: Arc<DecoderService>) -> Self {
         let (command_tx, command_rx) = bounded(16);
         let current_pad_id = Arc::new(Mutex::new(None::<String>));
         let current_pad_id_clone = current_pad_id.clone();
 
         let thread_handle = thread::spawn(move || {
-            run_preview_thread(command_rx, current_pad_id_clone, app_handle);
+            run_preview_thread(command_rx, current_pad_id_clone, app_handle, decoder);
         });
 
         Self {
@@ -86,12 +91,10 @@ fn find_output_device(name: &str) -> Option<cpal::Device> {
     }
 
     if let Ok(devices) = host.output_devices() {
-        for device in devices {
-            if let Ok(device_name) = device.name() {
-                if device_name == name {
-                    return Some(device);
-                }
-            }
+        let mut devices: Vec<cpal::Device> = devices.collect();
+        let names: Vec<String> = devices.iter().map(|device| device.name().unwrap_or_default()).collect();
+        if let Some(index) = find_device_name(names.iter().map(String::as_str), name) {
+            return Some(devices.swap_remove(index));
         }
     }
 
@@ -104,6 +107,7 @@ fn run_preview_thread(
     command_rx: Receiver<PreviewCommand>,
     current_pad_id: Arc<Mutex<Option<String>>>,
     app_handle: AppHandle,
+    decoder: Arc<DecoderService>,
 ) {
     // Current playback state - these must stay alive during playback
     let mut current_sink: Option<Sink> = None;
@@ -128,7 +132,7 @@ fn run_preview_thread(
 
         match command_rx.recv_timeout(Duration::from_millis(50)) {
             Ok(command) => match command {
-                PreviewCommand::Play { path, device_name, pad_id } => {
+                PreviewCommand::Play { path, device_name, pad_id, ceiling_db } => {
                     // Stop current preview if any
                     if let Some(sink) = current_sink.take() {
                         sink.stop();
@@ -168,22 +172,20 @@ fn run_preview_thread(
                         }
                     };
 
-                    // Open and decode the file
-                    let file = match File::open(&path) {
-                        Ok(f) => f,
-                        Err(e) => {
-                            tracing::error!("Failed to open file for preview: {}", e);
-                            continue;
-                        }
-                    };
-
-                    let source = match Decoder::new(BufReader::new(file)) {
+                    // Decode the file (shared cache with the mixer)
+                    let sound = match decoder.decode(Path::new(&path)) {
                         Ok(s) => s,
                         Err(e) => {
                             tracing::error!("Failed to decode file for preview: {}", e);
                             continue;
                         }
                     };
+                    let mut samples = sound.buffer.to_raw_f32();
+                    if let Some(ceiling_db) = ceiling_db {
+                        PeakLimiter::new(ceiling_db, sound.buffer.sample_rate())
+                            .process(&mut samples, sound.buffer.channels() as usize);
+                    }
+                    let source = SamplesBuffer::new(sound.buffer.channels(), sound.buffer.sample_rate(), samples);
 
                     // Play the sound
                     sink.append(source);
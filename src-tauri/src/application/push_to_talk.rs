@@ -0,0 +1,265 @@
+//! Push-to-talk - Hold a key to open (or close) the virtual mic
+//!
+//! A poll thread reads the key state every few milliseconds while mixing,
+//! so the gate follows the key whatever app has focus. The gate is a mute
+//! source of its own: it is written to the engine directly, combined with
+//! the mute button and the feedback mute rather than overwriting them,
+//! switches the tally and each transition is sent to the frontend as
+//! `talk-state`. Push-to-talk keeps the mic open for a short release tail
+//! after the key is let go, so the end of a word isn't cut.
+
+use crate::application::audio_engine::AudioEngine;
+use crate::domain::{KeyCombo, PushToTalkMode, PushToTalkSettings};
+use crate::infrastructure::TallyController;
+use crate::ports::KeyState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// How often the key is polled
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// How often a stopped engine is checked for having started
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Talk state sent to the frontend on each transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TalkState {
+    /// Whether the key (or its release tail) is active
+    pub talking: bool,
+    pub muted: bool,
+}
+
+/// Mute state of the mic from the key being held
+pub struct TalkGate {
+    mode: PushToTalkMode,
+    release: Duration,
+    released_at: Option<Instant>,
+    talking: bool,
+}
+
+impl TalkGate {
+    pub fn new(settings: &PushToTalkSettings) -> Self {
+        Self {
+            mode: settings.mode,
+            release: Duration::from_millis(settings.release_ms.min(PushToTalkSettings::MAX_RELEASE_MS) as u64),
+            released_at: None,
+            talking: false,
+        }
+    }
+
+    /// Feed the key state; the new talk state when it changed
+    pub fn update(&mut self, held: bool, now: Instant) -> Option<TalkState> {
+        let talking = if held {
+            self.released_at = None;
+            true
+        } else if self.talking {
+            let released_at = *self.released_at.get_or_insert(now);
+            now.duration_since(released_at) < self.release
+        } else {
+            false
+        };
+        if talking == self.talking {
+            return None;
+        }
+        self.talking = talking;
+        if !talking {
+            self.released_at = None;
+        }
+        Some(self.state())
+    }
+
+    pub fn state(&self) -> TalkState {
+        TalkState {
+            talking: self.talking,
+            muted: self.muted(),
+        }
+    }
+
+    pub fn muted(&self) -> bool {
+        match self.mode {
+            PushToTalkMode::Off => false,
+            PushToTalkMode::PushToTalk => !self.talking,
+            PushToTalkMode::PushToMute => self.talking,
+        }
+    }
+}
+
+/// Poll thread driving the mic gate
+pub struct PushToTalkService {
+    keys: Option<Arc<dyn KeyState>>,
+    stop: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl PushToTalkService {
+    pub fn new(keys: Option<Arc<dyn KeyState>>) -> Self {
+        Self {
+            keys,
+            stop: Mutex::new(None),
+        }
+    }
+
+    /// Service with the key state of the platform, if it has one
+    pub fn for_platform() -> Self {
+        #[cfg(target_os = "windows")]
+        let keys: Option<Arc<dyn KeyState>> = Some(Arc::new(crate::adapters::WindowsKeyState::new()));
+        #[cfg(not(target_os = "windows"))]
+        let keys: Option<Arc<dyn KeyState>> = None;
+        Self::new(keys)
+    }
+
+    /// Whether keys can be polled on this platform
+    pub fn is_supported(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// Whether the key of `combo` can be polled on this platform
+    pub fn supports(&self, combo: &KeyCombo) -> bool {
+        self.keys.as_ref().is_some_and(|keys| keys.supports(combo))
+    }
+
+    /// Restart the gate with new settings; Off stops it and opens the gate
+    ///
+    /// Fails, with the gate open, when the key can't be polled here.
+    pub fn configure(
+        &self,
+        app: &AppHandle,
+        engine: &Arc<AudioEngine>,
+        tally: &Arc<TallyController>,
+        settings: &PushToTalkSettings,
+    ) -> Result<(), String> {
+        let mut stop = self.stop.lock().unwrap();
+        let was_running = match stop.take() {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        };
+
+        let apply = {
+            let app = app.clone();
+            let engine = Arc::downgrade(engine);
+            let tally = Arc::clone(tally);
+            move |state: TalkState| {
+                if let Some(engine) = engine.upgrade() {
+                    engine.set_talk_gate_closed(state.muted);
+                }
+                tally.set_talk_muted(state.muted);
+                let _ = app.emit("talk-state", state);
+            }
+        };
+
+        // Don't leave the mic shut by a gate that is gone
+        let reopen = |apply: &dyn Fn(TalkState)| {
+            if was_running {
+                apply(TalkState { talking: false, muted: false });
+            }
+        };
+        if settings.mode == PushToTalkMode::Off {
+            reopen(&apply);
+            return Ok(());
+        }
+        let combo = KeyCombo::parse(&settings.key).map_err(|e| e.to_string());
+        let (keys, combo) = match (self.keys.clone(), combo) {
+            (Some(keys), Ok(combo)) if keys.supports(&combo) => (keys, combo),
+            (None, _) => {
+                reopen(&apply);
+                return Err("Push-to-talk isn't supported on this platform".to_string());
+            }
+            (Some(_), Ok(combo)) => {
+                reopen(&apply);
+                return Err(format!("Key {combo} can't be used for push-to-talk"));
+            }
+            (Some(_), Err(e)) => {
+                reopen(&apply);
+                return Err(e);
+            }
+        };
+
+        let mut gate = TalkGate::new(settings);
+        apply(gate.state());
+
+        let flag = Arc::new(AtomicBool::new(false));
+        *stop = Some(Arc::clone(&flag));
+        let engine = Arc::downgrade(engine);
+        let spawned = thread::Builder::new().name("push-to-talk".into()).spawn(move || {
+            while !flag.load(Ordering::Relaxed) {
+                // Nothing reaches the mic while stopped, so only wait for a start
+                match engine.upgrade().map(|engine| engine.is_running()) {
+                    None => break,
+                    Some(false) => {
+                        thread::sleep(IDLE_POLL_INTERVAL);
+                        continue;
+                    }
+                    Some(true) => {}
+                }
+                if let Some(state) = gate.update(keys.is_held(&combo), Instant::now()) {
+                    apply(state);
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+        match spawned {
+            Ok(_) => tracing::info!(mode = ?settings.mode, key = %combo, "Push-to-talk started"),
+            Err(e) => tracing::warn!(error = %e, "Failed to start the push-to-talk thread"),
+        }
+        Ok(())
+    }
+}
+
+impl Default for PushToTalkService {
+    fn default() -> Self {
+        Self::for_platform()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mode: PushToTalkMode) -> PushToTalkSettings {
+        PushToTalkSettings {
+            mode,
+            key: "Mouse4".into(),
+            release_ms: 200,
+        }
+    }
+
+    #[test]
+    fn test_push_to_talk_holds_the_release_tail() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut gate = TalkGate::new(&settings(PushToTalkMode::PushToTalk));
+        assert!(gate.muted());
+
+        assert_eq!(gate.update(true, at(0)), Some(TalkState { talking: true, muted: false }));
+        assert_eq!(gate.update(true, at(5)), None);
+        // Released: still open through the tail
+        assert_eq!(gate.update(false, at(10)), None);
+        assert_eq!(gate.update(false, at(150)), None);
+        assert_eq!(gate.update(false, at(210)), Some(TalkState { talking: false, muted: true }));
+
+        // Pressing again within a tail restarts it
+        gate.update(true, at(300));
+        gate.update(false, at(310));
+        gate.update(true, at(400));
+        assert_eq!(gate.update(false, at(450)), None);
+        assert!(!gate.muted());
+    }
+
+    #[test]
+    fn test_push_to_mute_mutes_while_held() {
+        let start = Instant::now();
+        let mut gate = TalkGate::new(&PushToTalkSettings {
+            release_ms: 0,
+            ..settings(PushToTalkMode::PushToMute)
+        });
+        assert!(!gate.muted());
+        assert_eq!(gate.update(true, start), Some(TalkState { talking: true, muted: true }));
+        assert_eq!(gate.update(false, start), Some(TalkState { talking: false, muted: false }));
+    }
+}
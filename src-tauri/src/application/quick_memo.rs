@@ -0,0 +1,193 @@
+//! Quick memo - One-shot mic recording that becomes a pad
+//!
+//! Records the mic for a short, bounded time, then trims the silence at
+//! both ends and normalizes the result so it is ready to play.
+
+use crate::domain::{AudioBuffer, AudioFormat, DeviceId};
+use crate::ports::{AudioInput, AudioInputError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+/// Longest memo that can be recorded
+pub const QUICK_MEMO_MAX_SECS: u64 = 30;
+
+/// Frames quieter than this are treated as silence (about -34 dBFS)
+const SILENCE_THRESHOLD: f32 = 0.02;
+
+/// Peak level of the normalized memo (about -1 dBFS)
+const NORMALIZE_PEAK: f32 = 0.89;
+
+/// How long to wait for a buffer before checking for a stop request
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Errors that can occur while recording a memo
+#[derive(Debug, thiserror::Error)]
+pub enum QuickMemoError {
+    #[error("A memo is already being recorded")]
+    AlreadyRecording,
+
+    #[error(transparent)]
+    Input(#[from] AudioInputError),
+
+    #[error("Nothing was recorded above the silence threshold")]
+    NoAudio,
+}
+
+/// Records one memo at a time and lets another command stop it early
+pub struct QuickMemoRecorder {
+    recording: AtomicBool,
+    stop_requested: AtomicBool,
+}
+
+impl QuickMemoRecorder {
+    pub fn new() -> Self {
+        Self {
+            recording: AtomicBool::new(false),
+            stop_requested: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::SeqCst)
+    }
+
+    /// Finish the current recording at the next buffer boundary
+    pub fn request_stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Record from `input` until stopped, disconnected or `max` elapses
+    ///
+    /// `on_progress` is called with the elapsed seconds and the peak level
+    /// of each captured buffer. Blocks the calling thread.
+    pub fn record(
+        &self,
+        input: &mut dyn AudioInput,
+        device: &DeviceId,
+        format: AudioFormat,
+        max: Duration,
+        on_progress: impl FnMut(f32, f32),
+    ) -> Result<AudioBuffer, QuickMemoError> {
+        if self.recording.swap(true, Ordering::SeqCst) {
+            return Err(QuickMemoError::AlreadyRecording);
+        }
+        self.stop_requested.store(false, Ordering::SeqCst);
+
+        let result = capture_input(
+            input,
+            device,
+            format,
+            max,
+            || self.stop_requested.load(Ordering::SeqCst),
+            on_progress,
+        );
+        self.recording.store(false, Ordering::SeqCst);
+        Ok(result?)
+    }
+}
+
+impl Default for QuickMemoRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Capture from `input` until `should_stop`, disconnection or `max` elapses
+///
+/// `on_progress` is called with the elapsed seconds and the peak level
+/// of each captured buffer. Blocks the calling thread; the input is
+/// stopped before returning.
+pub fn capture_input(
+    input: &mut dyn AudioInput,
+    device: &DeviceId,
+    format: AudioFormat,
+    max: Duration,
+    should_stop: impl Fn() -> bool,
+    mut on_progress: impl FnMut(f32, f32),
+) -> Result<AudioBuffer, AudioInputError> {
+    input.start(device, format)?;
+    let Some(receiver) = input.get_receiver() else {
+        let _ = input.stop();
+        return Err(AudioInputError::StreamError("No input stream".into()));
+    };
+
+    let channels = format.channels.max(1) as usize;
+    let max_frames = (max.as_secs_f64() * format.sample_rate as f64) as usize;
+    let mut samples: Vec<f32> = Vec::with_capacity(max_frames * channels);
+
+    while samples.len() / channels < max_frames && !should_stop() {
+        match receiver.recv_timeout(RECV_TIMEOUT) {
+            Ok(buffer) => {
+                let remaining = (max_frames - samples.len() / channels) * channels;
+                let raw = buffer.convert_channels(format.channels).to_raw_f32();
+                samples.extend(raw.iter().take(remaining));
+
+                let elapsed = (samples.len() / channels) as f32 / format.sample_rate as f32;
+                on_progress(elapsed, buffer.peak());
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    let _ = input.stop();
+    Ok(AudioBuffer::from_raw_f32(samples, format.channels, format.sample_rate))
+}
+
+/// Trim leading/trailing silence and normalize a raw memo
+pub fn clean_up_memo(raw: &AudioBuffer) -> Result<AudioBuffer, QuickMemoError> {
+    let mut memo = raw.trim_silence(SILENCE_THRESHOLD);
+    if memo.frame_count() == 0 {
+        return Err(QuickMemoError::NoAudio);
+    }
+
+    memo.normalize(NORMALIZE_PEAK);
+    Ok(memo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MockAudioInput;
+
+    fn buffer(value: f32, frames: usize) -> AudioBuffer {
+        AudioBuffer::from_raw_f32(vec![value; frames * 2], 2, 48000)
+    }
+
+    #[test]
+    fn test_record_stops_at_max_duration() {
+        let recorder = QuickMemoRecorder::new();
+        let mut input = MockAudioInput::new(vec![buffer(0.5, 480); 20]);
+        let mut updates = 0;
+
+        let raw = recorder
+            .record(
+                &mut input,
+                &DeviceId::new("mock"),
+                AudioFormat::new(48000, 2, 16),
+                Duration::from_millis(50),
+                |_, _| updates += 1,
+            )
+            .unwrap();
+
+        assert_eq!(raw.frame_count(), 2400);
+        assert_eq!(updates, 5);
+        assert!(!recorder.is_recording());
+        assert!(!input.is_capturing());
+    }
+
+    #[test]
+    fn test_clean_up_trims_and_normalizes() {
+        let mut raw = buffer(0.0, 100).to_raw_f32();
+        raw.extend(buffer(0.25, 50).to_raw_f32());
+        raw.extend(buffer(0.0, 100).to_raw_f32());
+        let raw = AudioBuffer::from_raw_f32(raw, 2, 48000);
+
+        let memo = clean_up_memo(&raw).unwrap();
+        assert_eq!(memo.frame_count(), 50);
+        assert!((memo.peak() - NORMALIZE_PEAK).abs() < 1e-6);
+
+        assert!(matches!(clean_up_memo(&buffer(0.0, 100)), Err(QuickMemoError::NoAudio)));
+    }
+}
@@ -0,0 +1,143 @@
+//! Safe mode - Start without audio to fix a setting that crashes on boot
+//!
+//! Safe mode starts the app without the audio engine, the preview engine
+//! or any hotkey, so a device or setting that takes the app down can be
+//! changed. It is asked for with [`SAFE_MODE_FLAG`], or entered on its own
+//! after repeated crashed starts: each start bumps a counter in a sentinel
+//! file, and the counter is cleared once the app has run for a while.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// Command-line flag that starts the app in safe mode
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// Starts in a row that didn't last before safe mode is entered
+pub const CRASH_LIMIT: u32 = 3;
+
+/// Uptime after which a start counts as not crashed
+const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+const SENTINEL_FILE: &str = "startup.sentinel";
+
+/// Whether the app was started with [`SAFE_MODE_FLAG`]
+pub fn safe_mode_requested() -> bool {
+    std::env::args().any(|arg| arg == SAFE_MODE_FLAG)
+}
+
+/// Why the app is in safe mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SafeModeReason {
+    /// Started with the command-line flag
+    Requested,
+    /// The last starts ended before the app had run for a while
+    RepeatedCrashes { starts: u32 },
+}
+
+/// Whether audio and hotkeys are off for this run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SafeMode {
+    reason: Option<SafeModeReason>,
+}
+
+impl SafeMode {
+    /// Safe mode for this start, given the flag and the crashed starts
+    /// before it
+    pub fn detect(requested: bool, crashed_starts: u32) -> Self {
+        let reason = if requested {
+            Some(SafeModeReason::Requested)
+        } else if crashed_starts >= CRASH_LIMIT {
+            Some(SafeModeReason::RepeatedCrashes { starts: crashed_starts })
+        } else {
+            None
+        };
+        Self { reason }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.reason.is_some()
+    }
+
+    pub fn reason(&self) -> Option<SafeModeReason> {
+        self.reason
+    }
+}
+
+/// Count of starts that didn't last, kept in the app data folder
+#[derive(Debug, Clone)]
+pub struct StartupSentinel {
+    file: PathBuf,
+}
+
+impl StartupSentinel {
+    pub fn new(app_data_dir: &Path) -> Self {
+        Self {
+            file: app_data_dir.join(SENTINEL_FILE),
+        }
+    }
+
+    /// Record a start; the starts before it that never cleared the sentinel
+    pub fn record_start(&self) -> u32 {
+        // A missing or unreadable sentinel counts as a clean history
+        let crashed = fs::read_to_string(&self.file)
+            .ok()
+            .and_then(|text| text.trim().parse::<u32>().ok())
+            .unwrap_or(0);
+        if let Some(dir) = self.file.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Err(e) = fs::write(&self.file, (crashed + 1).to_string()) {
+            tracing::warn!(error = %e, "Failed to write the startup sentinel");
+        }
+        crashed
+    }
+
+    /// Mark the running start as not crashed
+    pub fn clear(&self) {
+        match fs::remove_file(&self.file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to clear the startup sentinel"),
+        }
+    }
+
+    /// Clear the sentinel once the app has been up for a while
+    pub fn clear_when_stable(self) {
+        let spawned = thread::Builder::new().name("startup-sentinel".into()).spawn(move || {
+            thread::sleep(STABLE_AFTER);
+            self.clear();
+            tracing::debug!("Start is stable, crash counter cleared");
+        });
+        if let Err(e) = spawned {
+            tracing::warn!(error = %e, "Failed to start the startup sentinel timer");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_crashed_starts_enter_safe_mode() {
+        let dir = std::env::temp_dir().join(format!("voiceboard_sentinel_{}", uuid::Uuid::new_v4()));
+        let sentinel = StartupSentinel::new(&dir);
+
+        let starts: Vec<u32> = (0..4).map(|_| sentinel.record_start()).collect();
+        assert_eq!(starts, vec![0, 1, 2, 3]);
+        assert!(!SafeMode::detect(false, starts[2]).is_active());
+        assert_eq!(
+            SafeMode::detect(false, starts[3]).reason(),
+            Some(SafeModeReason::RepeatedCrashes { starts: 3 })
+        );
+        assert_eq!(SafeMode::detect(true, 0).reason(), Some(SafeModeReason::Requested));
+
+        sentinel.clear();
+        assert_eq!(sentinel.record_start(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
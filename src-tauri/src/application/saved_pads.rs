@@ -0,0 +1,150 @@
+//! Saved pads - Keeping what commands wrote when the frontend saves
+//!
+//! The frontend saves the whole board rebuilt from its own pad model, but
+//! some pad and sound fields are only ever written by backend commands
+//! (stem volumes and inserts, say). Its copy of those lags behind the
+//! store, so a save takes them from the stored pads rather than from the
+//! frontend.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Pad fields written by commands, matched by pad id
+const COMMAND_PAD_FIELDS: &[&str] = &["stems", "inserts"];
+
+/// Sound fields written by commands, matched by sound id
+const COMMAND_SOUND_FIELDS: &[&str] = &["path", "archived", "volume", "speed", "loudnessLufs", "autoLevel"];
+
+/// Pads saved by the frontend, with the command-written fields of the
+/// `stored` pads carried over
+///
+/// A field missing from the stored pad is removed from the saved one, so a
+/// reset made by a command (e.g. a volume back to 1) sticks too. Pad fields
+/// only carry over while the pad keeps its sound; pads and sounds the store
+/// doesn't know yet are saved as sent.
+pub fn merge_saved_pads(stored: &Value, mut pads: Value) -> Value {
+    let stored_pads: HashMap<&str, &Map<String, Value>> = stored
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|pad| Some((pad.get("id")?.as_str()?, pad.as_object()?)))
+        .collect();
+    let stored_sounds: HashMap<&str, &Map<String, Value>> = stored_pads
+        .values()
+        .copied()
+        .flat_map(pad_sounds)
+        .filter_map(|sound| Some((sound.get("id")?.as_str()?, sound.as_object()?)))
+        .collect();
+
+    for pad in pads.as_array_mut().into_iter().flatten().filter_map(Value::as_object_mut) {
+        let stored = pad.get("id").and_then(Value::as_str).and_then(|id| stored_pads.get(id)).copied();
+        if let Some(stored) = stored.filter(|stored| sound_id(stored) == sound_id(pad)) {
+            carry_over(pad, stored, COMMAND_PAD_FIELDS);
+        }
+        for sound in pad_sounds_mut(pad) {
+            if let Some(stored) = sound.get("id").and_then(Value::as_str).and_then(|id| stored_sounds.get(id)) {
+                carry_over(sound, stored, COMMAND_SOUND_FIELDS);
+            }
+        }
+    }
+    pads
+}
+
+/// Copy `fields` from `stored` into `target`, removing those it lacks
+fn carry_over(target: &mut Map<String, Value>, stored: &Map<String, Value>, fields: &[&str]) {
+    for &field in fields {
+        match stored.get(field) {
+            Some(value) => target.insert(field.to_string(), value.clone()),
+            None => target.remove(field),
+        };
+    }
+}
+
+/// Id of the sound a pad plays
+fn sound_id(pad: &Map<String, Value>) -> Option<&str> {
+    pad.get("sound")?.get("id")?.as_str()
+}
+
+/// A pad's sound, and those of its variants and stems
+fn pad_sounds(pad: &Map<String, Value>) -> impl Iterator<Item = &Value> {
+    let nested = ["variants", "stems"]
+        .into_iter()
+        .filter_map(|key| pad.get(key)?.as_array())
+        .flatten()
+        .filter_map(|entry| entry.get("sound"));
+    pad.get("sound").into_iter().chain(nested)
+}
+
+fn pad_sounds_mut(pad: &mut Map<String, Value>) -> impl Iterator<Item = &mut Map<String, Value>> {
+    pad.iter_mut()
+        .flat_map(|(key, value)| match key.as_str() {
+            "sound" => vec![value],
+            "variants" | "stems" => value
+                .as_array_mut()
+                .map(|entries| entries.iter_mut().filter_map(|entry| entry.get_mut("sound")).collect())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        })
+        .filter_map(Value::as_object_mut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_save_keeps_command_written_fields() {
+        let stored = json!([{
+            "id": "pad-0",
+            "color": "#e74c3c",
+            "sound": { "id": "s1", "path": "a.wav" },
+            "stems": [{ "sound": { "id": "s2", "path": "b.wav" }, "volume": 0.0 }],
+        }]);
+        // The frontend rebuilt the pad without its stems, and changed the color
+        let saved = json!([{
+            "id": "pad-0",
+            "color": "#3498db",
+            "sound": { "id": "s1", "path": "a.wav" },
+        }]);
+
+        let merged = merge_saved_pads(&stored, saved);
+        assert_eq!(merged[0]["color"], "#3498db");
+        assert_eq!(merged[0]["stems"], stored[0]["stems"]);
+    }
+
+    #[test]
+    fn test_stale_sound_fields_are_replaced() {
+        let stored = json!([{
+            "id": "pad-0",
+            "sound": { "id": "s1", "speed": { "rate": 1.5, "mode": "resample" } },
+            "variants": [{ "sound": { "id": "s2" }, "weight": 1 }],
+        }]);
+        // Speed set on s1 and reset on s2 since the frontend loaded them
+        let saved = json!([{
+            "id": "pad-0",
+            "sound": { "id": "s1", "name": "Airhorn" },
+            "variants": [{ "sound": { "id": "s2", "speed": { "rate": 2.0, "mode": "resample" } }, "weight": 1 }],
+        }]);
+
+        let merged = merge_saved_pads(&stored, saved);
+        assert_eq!(merged[0]["sound"]["name"], "Airhorn");
+        assert_eq!(merged[0]["sound"]["speed"], stored[0]["sound"]["speed"]);
+        assert!(merged[0]["variants"][0]["sound"].get("speed").is_none());
+    }
+
+    #[test]
+    fn test_pad_fields_stay_with_the_sound() {
+        let stored = json!([{ "id": "pad-0", "sound": { "id": "s1" }, "stems": [] }]);
+        let saved = json!([{ "id": "pad-0", "sound": { "id": "s4" } }]);
+        assert_eq!(merge_saved_pads(&stored, saved.clone()), saved);
+    }
+
+    #[test]
+    fn test_new_pads_are_saved_as_sent() {
+        let stored = json!([{ "id": "pad-0", "stems": [] }]);
+        let saved = json!([{ "id": "pad-1", "stems": [{ "sound": { "id": "s3" } }] }]);
+        assert_eq!(merge_saved_pads(&stored, saved.clone()), saved);
+        assert_eq!(merge_saved_pads(&Value::Null, saved.clone()), saved);
+    }
+}
@@ -179,6 +179,7 @@ pub fn mix_buffers(buffers: &[AudioBuffer], weights: &[f32]) -> Option<AudioBuff
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adapters::{MockAudioInput, MockAudioOutput, MockDeviceManager};
 
     #[test]
     fn test_mix_buffers_empty() {
@@ -191,4 +192,19 @@ mod tests {
         let result = mix_buffers(&[], &[1.0]);
         assert!(result.is_none());
     }
+
+    #[tokio::test]
+    async fn test_mixer_service_with_mock_adapters() {
+        let service = MixerService::new(
+            MockAudioInput::new(Vec::new()),
+            MockAudioOutput::new(),
+            MockDeviceManager::with_default_devices(),
+        );
+
+        service.start().await.unwrap();
+        assert!(service.is_running().await);
+
+        service.stop().await.unwrap();
+        assert!(!service.is_running().await);
+    }
 }
@@ -0,0 +1,484 @@
+//! Session recorder - Records the live mix to WAV, with markers
+//!
+//! The output callback copies the final mix into a ring buffer through a
+//! [`RecordingTap`]; a writer thread drains it to disk. Markers are placed
+//! by frame count, so they line up with the audio exactly. On stop, the
+//! markers are embedded in the WAV file and written next to it as a cue
+//! sheet and JSON. Optional mono stems (e.g. each podcast mic) are written
+//! alongside as `<name>.<stem>.wav`. Files reserve room for an RF64 header,
+//! so a recording past the 4 GB limit of plain WAV is kept whole.
+
+use crate::domain::{cue_sheet, wav_marker_chunks, MarkerKind, RecordingMarker};
+use ringbuf::traits::{Consumer, Observer, Producer, Split};
+use ringbuf::{HeapCons, HeapProd, HeapRb};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Audio buffered between the callback and the writer
+const RING_SECONDS: usize = 2;
+
+/// How often the writer drains the ring buffer
+const WRITER_POLL: Duration = Duration::from_millis(20);
+
+/// How long a stopping writer waits for the engine to drop its tap
+const DETACH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Length of the header written by [`WavFile`]: RIFF, JUNK, fmt and data
+const WAV_HEADER_LEN: u64 = 80;
+
+/// Offsets of the fields [`write_wav_sizes`] fixes up
+const WAV_CHANNELS_OFFSET: u64 = 58;
+const WAV_DATA_SIZE_OFFSET: u64 = 76;
+const DS64_DATA_SIZE_OFFSET: u64 = 28;
+
+/// Errors that can occur while recording
+#[derive(Debug, thiserror::Error)]
+pub enum RecorderError {
+    #[error("A recording is already running")]
+    AlreadyRecording,
+
+    #[error("Nothing is being recorded")]
+    NotRecording,
+
+    #[error("WAV error: {0}")]
+    Wav(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Feeds the live mix into the recorder; owned by the output callback
+pub struct RecordingTap {
+    producer: HeapProd<f32>,
+    frames: Arc<AtomicU64>,
+    channels: usize,
+}
+
+impl RecordingTap {
+    /// Queue an output buffer for writing
+    ///
+    /// Real-time safe. Buffers that don't fit are dropped whole, so
+    /// channels never get out of step.
+    pub fn push(&mut self, data: &[f32]) {
+        if self.producer.vacant_len() >= data.len() {
+            self.producer.push_slice(data);
+            self.frames
+                .fetch_add((data.len() / self.channels) as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+impl std::fmt::Debug for RecordingTap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordingTap").field("channels", &self.channels).finish()
+    }
+}
+
+/// Taps for the mix and each requested stem, in order
+#[derive(Debug)]
+pub struct RecordingTaps {
+    pub mix: RecordingTap,
+    pub stems: Vec<RecordingTap>,
+}
+
+/// A finished recording and its marker files
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingSummary {
+    pub path: String,
+    /// Paths of the stem files
+    pub stems: Vec<String>,
+    pub duration: f64,
+    pub markers: Vec<RecordingMarker>,
+    pub cue_path: Option<String>,
+    pub markers_path: Option<String>,
+}
+
+struct ActiveRecording {
+    path: PathBuf,
+    stem_paths: Vec<PathBuf>,
+    sample_rate: u32,
+    frames: Arc<AtomicU64>,
+    markers: Vec<RecordingMarker>,
+    stop: Arc<AtomicBool>,
+    writers: Vec<JoinHandle<Result<(), RecorderError>>>,
+}
+
+/// Records one session at a time
+pub struct SessionRecorder {
+    active: Mutex<Option<ActiveRecording>>,
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(None),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.active.lock().unwrap().is_some()
+    }
+
+    /// Path of the running recording
+    pub fn path(&self) -> Option<PathBuf> {
+        self.active.lock().unwrap().as_ref().map(|r| r.path.clone())
+    }
+
+    /// Open `path` for writing, plus a mono file per name in `stems`, and
+    /// return the taps to hand to the engine
+    pub fn start(
+        &self,
+        path: PathBuf,
+        sample_rate: u32,
+        channels: u16,
+        stems: &[&str],
+    ) -> Result<RecordingTaps, RecorderError> {
+        let mut active = self.active.lock().unwrap();
+        if active.is_some() {
+            return Err(RecorderError::AlreadyRecording);
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut writers = Vec::with_capacity(stems.len() + 1);
+        let mix = spawn_writer(&path, sample_rate, channels, &stop, &mut writers)?;
+
+        let mut stem_paths = Vec::with_capacity(stems.len());
+        let mut stem_taps = Vec::with_capacity(stems.len());
+        for stem in stems {
+            let stem_path = path.with_extension(format!("{}.wav", stem));
+            stem_taps.push(spawn_writer(&stem_path, sample_rate, 1, &stop, &mut writers)?);
+            stem_paths.push(stem_path);
+        }
+
+        tracing::info!(path = %path.display(), stems = stems.len(), "Recording started");
+        *active = Some(ActiveRecording {
+            path,
+            stem_paths,
+            sample_rate,
+            frames: mix.frames.clone(),
+            markers: Vec::new(),
+            stop,
+            writers,
+        });
+
+        Ok(RecordingTaps { mix, stems: stem_taps })
+    }
+
+    /// Mark the current position of the recording
+    pub fn add_marker(&self, label: &str, kind: MarkerKind) -> Result<RecordingMarker, RecorderError> {
+        let mut active = self.active.lock().unwrap();
+        let recording = active.as_mut().ok_or(RecorderError::NotRecording)?;
+
+        let position = recording.frames.load(Ordering::Relaxed);
+        let marker = RecordingMarker::new(position, recording.sample_rate, label, kind);
+        recording.markers.push(marker.clone());
+        Ok(marker)
+    }
+
+    /// Markers of the running recording
+    pub fn markers(&self) -> Vec<RecordingMarker> {
+        self.active
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|r| r.markers.clone())
+            .unwrap_or_default()
+    }
+
+    /// Finish the recording and write its markers
+    ///
+    /// Detach the taps from the engine first: each writer keeps draining
+    /// until its tap is dropped, or gives up after [`DETACH_TIMEOUT`] and
+    /// loses what is pushed later.
+    pub fn stop(&self) -> Result<RecordingSummary, RecorderError> {
+        let recording = self.active.lock().unwrap().take().ok_or(RecorderError::NotRecording)?;
+        recording.stop.store(true, Ordering::Release);
+        for writer in recording.writers {
+            writer
+                .join()
+                .map_err(|_| RecorderError::Wav("writer thread panicked".into()))??;
+        }
+
+        let mut summary = RecordingSummary {
+            path: recording.path.to_string_lossy().to_string(),
+            stems: recording
+                .stem_paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            duration: recording.frames.load(Ordering::Relaxed) as f64 / recording.sample_rate.max(1) as f64,
+            markers: recording.markers,
+            cue_path: None,
+            markers_path: None,
+        };
+
+        if !summary.markers.is_empty() {
+            append_wav_chunks(&recording.path, &wav_marker_chunks(&summary.markers))?;
+
+            let file_name = recording
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let cue_path = recording.path.with_extension("cue");
+            fs::write(&cue_path, cue_sheet(&file_name, &summary.markers, recording.sample_rate))?;
+            let markers_path = recording.path.with_extension("markers.json");
+            let json = serde_json::to_string_pretty(&summary.markers).map_err(std::io::Error::other)?;
+            fs::write(&markers_path, json)?;
+
+            summary.cue_path = Some(cue_path.to_string_lossy().to_string());
+            summary.markers_path = Some(markers_path.to_string_lossy().to_string());
+        }
+
+        tracing::info!(
+            path = %summary.path,
+            duration = summary.duration,
+            markers = summary.markers.len(),
+            "Recording stopped"
+        );
+        Ok(summary)
+    }
+}
+
+impl Default for SessionRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create a float WAV at `path` and a thread draining a new tap into it
+fn spawn_writer(
+    path: &Path,
+    sample_rate: u32,
+    channels: u16,
+    stop: &Arc<AtomicBool>,
+    writers: &mut Vec<JoinHandle<Result<(), RecorderError>>>,
+) -> Result<RecordingTap, RecorderError> {
+    let channels = channels.max(1);
+    let writer = WavFile::create(path, sample_rate, channels)?;
+
+    let (producer, consumer) = HeapRb::<f32>::new(sample_rate as usize * channels as usize * RING_SECONDS).split();
+    let stop = stop.clone();
+    writers.push(
+        thread::Builder::new()
+            .name("session-recorder".into())
+            .spawn(move || write_until_stopped(writer, consumer, &stop))?,
+    );
+
+    Ok(RecordingTap {
+        producer,
+        frames: Arc::new(AtomicU64::new(0)),
+        channels: channels as usize,
+    })
+}
+
+fn write_until_stopped(
+    mut writer: WavFile,
+    mut consumer: HeapCons<f32>,
+    stop: &AtomicBool,
+) -> Result<(), RecorderError> {
+    let mut buffer = vec![0.0f32; 4096];
+    let mut stopped_at = None;
+    loop {
+        // Read the flags first so the last drain sees everything pushed before them
+        let stopping = stop.load(Ordering::Acquire);
+        let detached = !consumer.write_is_held();
+        while consumer.occupied_len() > 0 {
+            let count = consumer.pop_slice(&mut buffer);
+            writer.write(&buffer[..count])?;
+        }
+        if stopping {
+            let stopped_at = *stopped_at.get_or_insert_with(Instant::now);
+            if detached || stopped_at.elapsed() >= DETACH_TIMEOUT {
+                break;
+            }
+        }
+        thread::sleep(WRITER_POLL);
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+/// 32-bit float WAV writer that turns into RF64 past 4 GB
+///
+/// A `JUNK` chunk holds the place of the RF64 `ds64` chunk, as EBU 3306
+/// suggests, so the sizes can be fixed up in place whatever the length.
+struct WavFile {
+    file: BufWriter<File>,
+    channels: u16,
+    data_len: u64,
+}
+
+impl WavFile {
+    fn create(path: &Path, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        let block_align = channels * 4;
+        let mut header = Vec::with_capacity(WAV_HEADER_LEN as usize);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+        header.extend_from_slice(b"JUNK");
+        header.extend_from_slice(&28u32.to_le_bytes());
+        header.extend_from_slice(&[0; 28]);
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&3u16.to_le_bytes()); // WAVE_FORMAT_IEEE_FLOAT
+        header.extend_from_slice(&channels.to_le_bytes());
+        header.extend_from_slice(&sample_rate.to_le_bytes());
+        header.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&32u16.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        debug_assert_eq!(header.len() as u64, WAV_HEADER_LEN);
+
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(&header)?;
+        Ok(Self {
+            file,
+            channels,
+            data_len: 0,
+        })
+    }
+
+    fn write(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            self.file.write_all(&sample.to_le_bytes())?;
+        }
+        self.data_len += samples.len() as u64 * 4;
+        Ok(())
+    }
+
+    fn finalize(self) -> io::Result<()> {
+        let mut file = self.file.into_inner().map_err(|e| e.into_error())?;
+        write_wav_sizes(&mut file, WAV_HEADER_LEN + self.data_len, self.data_len, self.channels)?;
+        file.sync_all()
+    }
+}
+
+/// Write the sizes into a header laid out by [`WavFile`]
+///
+/// Sizes that don't fit 32 bits turn the file into RF64: the `JUNK` chunk
+/// becomes `ds64` with the real sizes, and the 32-bit fields are maxed out.
+fn write_wav_sizes(file: &mut File, file_len: u64, data_len: u64, channels: u16) -> io::Result<()> {
+    let riff_len = file_len - 8;
+    if riff_len <= u32::MAX as u64 {
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(b"RIFF")?;
+        file.write_all(&(riff_len as u32).to_le_bytes())?;
+        file.seek(SeekFrom::Start(WAV_DATA_SIZE_OFFSET))?;
+        return file.write_all(&(data_len as u32).to_le_bytes());
+    }
+
+    let frames = data_len / (channels.max(1) as u64 * 4);
+    let mut ds64 = Vec::with_capacity(36);
+    ds64.extend_from_slice(b"ds64");
+    ds64.extend_from_slice(&28u32.to_le_bytes());
+    ds64.extend_from_slice(&riff_len.to_le_bytes());
+    ds64.extend_from_slice(&data_len.to_le_bytes());
+    ds64.extend_from_slice(&frames.to_le_bytes());
+    ds64.extend_from_slice(&0u32.to_le_bytes());
+
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(b"RF64")?;
+    file.write_all(&u32::MAX.to_le_bytes())?;
+    file.seek(SeekFrom::Start(12))?;
+    file.write_all(&ds64)?;
+    file.seek(SeekFrom::Start(WAV_DATA_SIZE_OFFSET))?;
+    file.write_all(&u32::MAX.to_le_bytes())
+}
+
+/// Append chunks to a finished recording and fix up its sizes
+fn append_wav_chunks(path: &Path, chunks: &[u8]) -> io::Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut header = [0u8; WAV_HEADER_LEN as usize];
+    file.read_exact(&mut header)?;
+    let read_u16 = |at: u64| u16::from_le_bytes([header[at as usize], header[at as usize + 1]]);
+    let read_u32 = |at: u64| u32::from_le_bytes(header[at as usize..at as usize + 4].try_into().unwrap());
+    let data_len = if &header[..4] == b"RF64" {
+        let at = DS64_DATA_SIZE_OFFSET as usize;
+        u64::from_le_bytes(header[at..at + 8].try_into().unwrap())
+    } else {
+        read_u32(WAV_DATA_SIZE_OFFSET) as u64
+    };
+
+    let length = file.seek(SeekFrom::End(0))?;
+    file.write_all(chunks)?;
+    write_wav_sizes(&mut file, length + chunks.len() as u64, data_len, read_u16(WAV_CHANNELS_OFFSET))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_with_markers() {
+        let dir = std::env::temp_dir().join(format!("voiceboard_recorder_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("show.wav");
+        let recorder = SessionRecorder::new();
+
+        let mut taps = recorder.start(path.clone(), 48_000, 2, &["host"]).unwrap();
+        assert!(matches!(
+            recorder.start(path.clone(), 48_000, 2, &[]),
+            Err(RecorderError::AlreadyRecording)
+        ));
+
+        taps.mix.push(&[0.25; 960]);
+        taps.stems[0].push(&[0.1; 480]);
+        let marker = recorder.add_marker("Drop", MarkerKind::Pad).unwrap();
+        assert_eq!(marker.position, 480);
+        taps.mix.push(&[0.5; 960]);
+
+        // What the engine does on detach; the writers drain until then
+        drop(taps);
+        let summary = recorder.stop().unwrap();
+        assert_eq!(summary.stems, vec![dir.join("show.host.wav").to_string_lossy().to_string()]);
+        assert_eq!(hound::WavReader::open(&summary.stems[0]).unwrap().len(), 480);
+        assert!(!recorder.is_recording());
+        assert_eq!(summary.duration, 0.02);
+        assert!(fs::read_to_string(summary.cue_path.unwrap()).unwrap().contains("TITLE \"Drop\""));
+
+        // Still a valid WAV with the marker chunks appended
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 1920);
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize, bytes.len() - 8);
+        assert!(bytes.windows(4).any(|w| w == b"cue "));
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_sizes_past_4_gb_turn_into_rf64() {
+        let path = std::env::temp_dir().join(format!("voiceboard_rf64_{}.wav", uuid::Uuid::new_v4()));
+        let mut wav = WavFile::create(&path, 48_000, 2).unwrap();
+        wav.write(&[0.5; 8]).unwrap();
+        wav.finalize().unwrap();
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 8);
+
+        // Only the header is rewritten, so the file doesn't need to be that long
+        let data_len = 5u64 << 30;
+        let mut file = OpenOptions::new().read(true).write(true).open(&path).unwrap();
+        write_wav_sizes(&mut file, WAV_HEADER_LEN + data_len, data_len, 2).unwrap();
+        drop(file);
+
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], b"RF64");
+        assert_eq!(&bytes[12..16], b"ds64");
+        assert_eq!(u64::from_le_bytes(bytes[20..28].try_into().unwrap()), WAV_HEADER_LEN + data_len - 8);
+        assert_eq!(u64::from_le_bytes(bytes[28..36].try_into().unwrap()), data_len);
+        assert_eq!(u64::from_le_bytes(bytes[36..44].try_into().unwrap()), data_len / 8);
+        assert_eq!(&bytes[76..80], &u32::MAX.to_le_bytes());
+
+        fs::remove_file(path).ok();
+    }
+}
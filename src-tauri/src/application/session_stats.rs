@@ -0,0 +1,158 @@
+//! Session stats - Post-show report when mixing stops
+//!
+//! The engine event thread feeds the running session its output levels
+//! and underruns. A stream rebuild carries on the running session. When
+//! mixing stops, or ends on an error, the summary is emitted as
+//! `session-summary` and appended to the session history: a JSON Lines file in the app data
+//! folder with one summary per line.
+
+use crate::application::clock::now_millis;
+use crate::application::state::AppState;
+use crate::domain::{SessionStats, SessionSummary};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+const HISTORY_FILE: &str = "sessions.jsonl";
+
+/// Statistics of the mixing session, while one runs
+pub struct SessionTracker {
+    current: Mutex<Option<SessionStats>>,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Start a session unless one runs
+    ///
+    /// The engine also reports a start after rebuilding its streams or
+    /// restarting with new settings; the running session goes on then.
+    pub fn start(&self, now: u64) {
+        self.current.lock().unwrap().get_or_insert_with(|| SessionStats::new(now));
+    }
+
+    /// Add an output level report to the running session
+    pub fn record_level(&self, rms: f32, peak: f32) {
+        if let Some(stats) = self.current.lock().unwrap().as_mut() {
+            stats.record_level(rms, peak);
+        }
+    }
+
+    pub fn record_underruns(&self, count: u32) {
+        if let Some(stats) = self.current.lock().unwrap().as_mut() {
+            stats.record_underruns(count);
+        }
+    }
+
+    /// End the running session; None when none was running
+    pub fn finish(&self) -> Option<SessionStats> {
+        self.current.lock().unwrap().take()
+    }
+}
+
+impl Default for SessionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// File of the session history in the app data folder
+pub fn session_history_file(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_data_dir().ok()?.join(HISTORY_FILE))
+}
+
+/// Append a summary to the history file
+pub fn append_summary(file: &Path, summary: &SessionSummary) -> std::io::Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(summary)?;
+    line.push('\n');
+    OpenOptions::new().create(true).append(true).open(file)?.write_all(line.as_bytes())
+}
+
+/// Summaries of the history file, newest first
+pub fn read_history(file: &Path) -> std::io::Result<Vec<SessionSummary>> {
+    let text = match fs::read_to_string(file) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    // A line cut short by a crash is skipped rather than failing the history
+    let mut summaries: Vec<SessionSummary> = text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+    summaries.reverse();
+    Ok(summaries)
+}
+
+/// Summarize the session that just stopped or failed, save it and tell
+/// the frontend
+pub fn on_mixing_stopped(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some(stats) = state.session_tracker.finish() else {
+        return;
+    };
+    let summary = stats.summary(now_millis(), &state.play_log.current_entries());
+
+    match session_history_file(app) {
+        Some(file) => {
+            if let Err(e) = append_summary(&file, &summary) {
+                tracing::warn!(error = %e, "Failed to save the session summary");
+            }
+        }
+        None => tracing::warn!("No app data folder, the session summary isn't saved"),
+    }
+    tracing::info!(
+        duration_secs = summary.duration_secs,
+        triggers = summary.triggers,
+        underruns = summary.underruns,
+        "Session summary"
+    );
+    let _ = app.emit("session-summary", &summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_is_newest_first() {
+        let file = std::env::temp_dir()
+            .join(format!("voiceboard_sessions_{}", uuid::Uuid::new_v4()))
+            .join(HISTORY_FILE);
+        assert!(read_history(&file).unwrap().is_empty());
+
+        let tracker = SessionTracker::new();
+        for started_at in [1_000, 5_000] {
+            tracker.start(started_at);
+            tracker.record_level(0.5, 0.9);
+            let summary = tracker.finish().unwrap().summary(started_at + 2_000, &[]);
+            append_summary(&file, &summary).unwrap();
+        }
+        assert!(tracker.finish().is_none());
+
+        let history = read_history(&file).unwrap();
+        let starts: Vec<u64> = history.iter().map(|s| s.started_at).collect();
+        assert_eq!(starts, vec![5_000, 1_000]);
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+    }
+
+    #[test]
+    fn test_restart_carries_on_the_session() {
+        let tracker = SessionTracker::new();
+        tracker.start(1_000);
+        tracker.record_underruns(2);
+
+        // Streams rebuilt while running
+        tracker.start(3_000);
+        tracker.record_underruns(1);
+        let summary = tracker.finish().unwrap().summary(4_000, &[]);
+        assert_eq!(summary.started_at, 1_000);
+        assert_eq!(summary.underruns, 3);
+    }
+}
@@ -0,0 +1,260 @@
+//! Settings service - The only writer of settings.json
+//!
+//! Commands used to reload and save the settings store on their own, so
+//! two saves could interleave and a crash mid-write left a truncated file.
+//! Every write now goes through one writer thread, which replaces the file
+//! atomically (temp file, fsync, rename) and keeps the last good file as a
+//! backup. A file that no longer parses is recovered from that backup.
+
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// File holding the settings document, in the app data folder
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Errors that can occur while reading or writing settings
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SettingsError {
+    #[error("Settings are not loaded yet")]
+    NotOpen,
+
+    #[error("Could not write settings: {0}")]
+    Write(String),
+
+    #[error("Could not read settings: {0}")]
+    Read(String),
+}
+
+/// How the settings file was found when opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadOutcome {
+    Loaded,
+    /// First run: no file yet
+    Missing,
+    /// The file was unreadable and the backup was used
+    RecoveredFromBackup,
+    /// Neither the file nor the backup could be read; defaults are used
+    Reset,
+}
+
+struct WriteRequest {
+    path: PathBuf,
+    document: Map<String, Value>,
+    done: Sender<Result<(), SettingsError>>,
+}
+
+/// Settings document shared by the commands, written by a single thread
+pub struct SettingsService {
+    path: OnceLock<PathBuf>,
+    document: Mutex<Map<String, Value>>,
+    writer: Sender<WriteRequest>,
+}
+
+impl SettingsService {
+    pub fn new() -> Self {
+        let (writer, requests) = unbounded::<WriteRequest>();
+        std::thread::Builder::new()
+            .name("settings-writer".into())
+            .spawn(move || {
+                while let Ok(request) = requests.recv() {
+                    // Requests queued meanwhile are older snapshots of the
+                    // same document: write only the newest
+                    let mut waiting = vec![request.done];
+                    let mut latest = (request.path, request.document);
+                    while let Ok(next) = requests.try_recv() {
+                        waiting.push(next.done);
+                        latest = (next.path, next.document);
+                    }
+                    let result = write_document(&latest.0, &latest.1);
+                    if let Err(e) = &result {
+                        tracing::error!(error = %e, "Failed to save settings");
+                    }
+                    for done in waiting {
+                        let _ = done.send(result.clone());
+                    }
+                }
+            })
+            .expect("spawn settings writer");
+
+        Self {
+            path: OnceLock::new(),
+            document: Mutex::new(Map::new()),
+            writer,
+        }
+    }
+
+    /// Load the settings file from `dir`, recovering it when corrupt
+    pub fn open(&self, dir: &Path) -> Result<LoadOutcome, SettingsError> {
+        fs::create_dir_all(dir).map_err(|e| SettingsError::Read(e.to_string()))?;
+        let path = dir.join(SETTINGS_FILE);
+        let (document, outcome) = load_document(&path);
+        match outcome {
+            LoadOutcome::RecoveredFromBackup => tracing::warn!(path = %path.display(), "Settings were corrupt, restored the backup"),
+            LoadOutcome::Reset => tracing::error!(path = %path.display(), "Settings and backup are corrupt, using defaults"),
+            LoadOutcome::Loaded | LoadOutcome::Missing => {}
+        }
+
+        *self.document.lock().unwrap() = document;
+        let _ = self.path.set(path);
+        if outcome == LoadOutcome::RecoveredFromBackup {
+            self.flush()?;
+        }
+        Ok(outcome)
+    }
+
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.document.lock().unwrap().get(key).cloned()
+    }
+
+    /// Set a key and wait until the file is safely on disk
+    pub fn set(&self, key: &str, value: Value) -> Result<(), SettingsError> {
+        let done = {
+            let mut document = self.document.lock().unwrap();
+            document.insert(key.to_string(), value);
+            // Queued under the lock, so requests reach the writer in order
+            self.queue(&document)?
+        };
+        done.recv().map_err(|e| SettingsError::Write(e.to_string()))?
+    }
+
+    /// Write the current document
+    pub fn flush(&self) -> Result<(), SettingsError> {
+        let done = self.queue(&self.document.lock().unwrap())?;
+        done.recv().map_err(|e| SettingsError::Write(e.to_string()))?
+    }
+
+    fn queue(&self, document: &Map<String, Value>) -> Result<Receiver<Result<(), SettingsError>>, SettingsError> {
+        let path = self.path.get().ok_or(SettingsError::NotOpen)?.clone();
+        let (done, result) = bounded(1);
+        self.writer
+            .send(WriteRequest {
+                path,
+                document: document.clone(),
+                done,
+            })
+            .map_err(|e| SettingsError::Write(e.to_string()))?;
+        Ok(result)
+    }
+}
+
+impl Default for SettingsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
+fn read_document(path: &Path) -> Result<Option<Map<String, Value>>, String> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice::<Map<String, Value>>(&bytes).map(Some).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Read the settings file, falling back to its backup when it doesn't parse
+fn load_document(path: &Path) -> (Map<String, Value>, LoadOutcome) {
+    match read_document(path) {
+        Ok(Some(document)) => return (document, LoadOutcome::Loaded),
+        Ok(None) if !backup_path(path).exists() => return (Map::new(), LoadOutcome::Missing),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!(error = %e, path = %path.display(), "Settings file is unreadable");
+            // Kept for support, it will be overwritten on the next save
+            let _ = fs::copy(path, path.with_extension("json.corrupt"));
+        }
+    }
+    match read_document(&backup_path(path)) {
+        Ok(Some(document)) => (document, LoadOutcome::RecoveredFromBackup),
+        _ => (Map::new(), LoadOutcome::Reset),
+    }
+}
+
+/// Replace the settings file atomically, backing up the current one
+fn write_document(path: &Path, document: &Map<String, Value>) -> Result<(), SettingsError> {
+    let error = |e: std::io::Error| SettingsError::Write(e.to_string());
+    let bytes = serde_json::to_vec_pretty(document).map_err(|e| SettingsError::Write(e.to_string()))?;
+
+    let temp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&temp).map_err(error)?;
+    file.write_all(&bytes).map_err(error)?;
+    file.sync_all().map_err(error)?;
+    drop(file);
+
+    // Only a file that parses is worth keeping as the backup
+    if matches!(read_document(path), Ok(Some(_))) {
+        fs::copy(path, backup_path(path)).map_err(error)?;
+    }
+    fs::rename(&temp, path).map_err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("voiceboard_settings_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_writes_replace_the_file_and_keep_a_backup() {
+        let dir = temp_dir();
+        let service = SettingsService::new();
+        assert_eq!(service.open(&dir).unwrap(), LoadOutcome::Missing);
+
+        service.set("app_settings", json!({ "volume": 1 })).unwrap();
+        service.set("app_settings", json!({ "volume": 2 })).unwrap();
+
+        let path = dir.join(SETTINGS_FILE);
+        assert_eq!(read_document(&path).unwrap().unwrap()["app_settings"]["volume"], 2);
+        assert_eq!(read_document(&backup_path(&path)).unwrap().unwrap()["app_settings"]["volume"], 1);
+        assert!(!path.with_extension("json.tmp").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_file_is_recovered_from_backup() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE);
+        fs::write(backup_path(&path), r#"{"app_settings": {"volume": 1}}"#).unwrap();
+        fs::write(&path, r#"{"app_settings": {"vol"#).unwrap();
+
+        let service = SettingsService::new();
+        assert_eq!(service.open(&dir).unwrap(), LoadOutcome::RecoveredFromBackup);
+        assert_eq!(service.get("app_settings"), Some(json!({ "volume": 1 })));
+        // The recovered settings are written back
+        assert_eq!(read_document(&path).unwrap().unwrap()["app_settings"]["volume"], 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_writes_all_land() {
+        let dir = temp_dir();
+        let service = std::sync::Arc::new(SettingsService::new());
+        service.open(&dir).unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let service = service.clone();
+                std::thread::spawn(move || service.set(&format!("key{}", i), json!(i)).unwrap())
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let document = read_document(&dir.join(SETTINGS_FILE)).unwrap().unwrap();
+        assert_eq!(document.len(), 8);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
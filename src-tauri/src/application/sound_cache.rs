@@ -0,0 +1,222 @@
+//! Sound cache - Decoded sounds kept in memory for instant pad presses
+//!
+//! Decoding a file on every press delays the start by the decode. The
+//! cache keeps decoded sounds up to a size limit, dropping the least
+//! recently played first, and drops a sound whose file changed on disk.
+
+use crate::application::decoder_service::DecodedSound;
+use crate::domain::PlaybackSettings;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// A cached decode, invalidated when the file changes on disk
+struct CacheEntry {
+    modified: Option<SystemTime>,
+    sound: Arc<DecodedSound>,
+    /// Tick of the last lookup or insert, for least-recently-used eviction
+    last_used: u64,
+}
+
+struct CacheState {
+    entries: HashMap<PathBuf, CacheEntry>,
+    limit_bytes: u64,
+    bytes: u64,
+    tick: u64,
+}
+
+impl CacheState {
+    fn remove(&mut self, path: &Path) {
+        if let Some(entry) = self.entries.remove(path) {
+            self.bytes -= sound_bytes(&entry.sound);
+        }
+    }
+
+    /// Drop the least recently used sounds until `incoming` more bytes fit
+    fn evict_for(&mut self, incoming: u64) {
+        while self.bytes + incoming > self.limit_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            tracing::debug!("Evicted {} from the sound cache", oldest.display());
+            self.remove(&oldest);
+        }
+    }
+}
+
+/// Decoded sounds by file, within a size limit
+pub struct SoundCache {
+    state: Mutex<CacheState>,
+}
+
+impl SoundCache {
+    pub fn new(limit_mb: u32) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                limit_bytes: mb_to_bytes(limit_mb),
+                bytes: 0,
+                tick: 0,
+            }),
+        }
+    }
+
+    /// The cached sound of a file, unless the file changed since
+    pub fn get(&self, path: &Path) -> Option<Arc<DecodedSound>> {
+        let mut state = self.state.lock().ok()?;
+        let entry = state.entries.get(path)?;
+        if entry.modified != modified_time(path) {
+            state.remove(path);
+            return None;
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        let entry = state.entries.get_mut(path)?;
+        entry.last_used = tick;
+        Some(entry.sound.clone())
+    }
+
+    /// Whether a file is cached, without counting as a use
+    pub fn contains(&self, path: &Path) -> bool {
+        self.state.lock().is_ok_and(|state| state.entries.contains_key(path))
+    }
+
+    /// Cache a decoded sound, evicting the least recently used to make
+    /// room; a sound bigger than the whole cache isn't kept
+    pub fn insert(&self, path: &Path, sound: Arc<DecodedSound>) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let bytes = sound_bytes(&sound);
+        state.remove(path);
+        if bytes > state.limit_bytes {
+            tracing::debug!("{} is too big for the sound cache", path.display());
+            return;
+        }
+        state.evict_for(bytes);
+
+        state.tick += 1;
+        let entry = CacheEntry {
+            modified: modified_time(path),
+            sound,
+            last_used: state.tick,
+        };
+        state.entries.insert(path.to_path_buf(), entry);
+        state.bytes += bytes;
+    }
+
+    /// Drop a file from the cache
+    pub fn remove(&self, path: &Path) {
+        if let Ok(mut state) = self.state.lock() {
+            state.remove(path);
+        }
+    }
+
+    /// Drop every cached sound
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.entries.clear();
+            state.bytes = 0;
+        }
+    }
+
+    /// Change the size limit, evicting what no longer fits
+    pub fn set_limit(&self, limit_mb: u32) {
+        if let Ok(mut state) = self.state.lock() {
+            state.limit_bytes = mb_to_bytes(limit_mb);
+            state.evict_for(0);
+        }
+    }
+
+    /// Whether `bytes` more would fit without evicting anything
+    pub fn has_room(&self, bytes: u64) -> bool {
+        self.state
+            .lock()
+            .is_ok_and(|state| state.bytes + bytes <= state.limit_bytes)
+    }
+
+    /// Number of cached sounds
+    pub fn len(&self) -> usize {
+        self.state.lock().map(|state| state.entries.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Memory held by the decoded samples, in bytes
+    pub fn bytes(&self) -> u64 {
+        self.state.lock().map(|state| state.bytes).unwrap_or(0)
+    }
+}
+
+impl Default for SoundCache {
+    fn default() -> Self {
+        Self::new(PlaybackSettings::default().sound_cache_mb)
+    }
+}
+
+fn sound_bytes(sound: &DecodedSound) -> u64 {
+    std::mem::size_of_val(sound.buffer.samples()) as u64
+}
+
+fn mb_to_bytes(mb: u32) -> u64 {
+    mb as u64 * 1024 * 1024
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AudioBuffer, AudioFileFormat, AudioFormat};
+    use crate::ports::AudioFileMetadata;
+    use std::time::Duration;
+
+    /// A mono sound of `mb` megabytes of samples
+    fn sound(mb: usize) -> Arc<DecodedSound> {
+        let samples = vec![0.0f32; mb * 1024 * 1024 / 4];
+        Arc::new(DecodedSound {
+            metadata: AudioFileMetadata {
+                format: AudioFileFormat::Wav,
+                duration: Duration::ZERO,
+                audio_format: AudioFormat::new(48000, 1, 16),
+                title: None,
+                artist: None,
+            },
+            buffer: AudioBuffer::from_raw_f32(samples, 1, 48000),
+        })
+    }
+
+    #[test]
+    fn test_evicts_the_least_recently_used() {
+        let cache = SoundCache::new(3);
+        let (a, b, c, d) = (Path::new("a.wav"), Path::new("b.wav"), Path::new("c.wav"), Path::new("d.wav"));
+        cache.insert(a, sound(1));
+        cache.insert(b, sound(1));
+        cache.insert(c, sound(1));
+        assert_eq!(cache.bytes(), 3 * 1024 * 1024);
+
+        // Using `a` makes `b` the oldest
+        assert!(cache.get(a).is_some());
+        cache.insert(d, sound(1));
+        assert!(cache.contains(a) && !cache.contains(b) && cache.contains(c) && cache.contains(d));
+
+        // Too big for the whole cache: not kept, nothing evicted for it
+        cache.insert(b, sound(4));
+        assert_eq!(cache.len(), 3);
+
+        cache.set_limit(1);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains(d));
+    }
+}
@@ -0,0 +1,438 @@
+//! Sound packs - Installing third-party sound collections in one action
+//!
+//! A Voiceboard pack is a folder, or a `.zip` of one, with a
+//! `manifest.json` next to the audio files:
+//!
+//! ```json
+//! {
+//!   "name": "Meme Classics",
+//!   "author": "someone",
+//!   "version": "1.0",
+//!   "sounds": [
+//!     { "file": "airhorn.wav", "name": "Air Horn", "hotkey": "Ctrl+1", "color": "#e74c3c", "gainDb": -3,
+//!       "license": "CC-BY-4.0", "sourceUrl": "https://example.com/airhorn", "attribution": "someone" }
+//!   ]
+//! }
+//! ```
+//!
+//! Only `name`, `sounds` and each sound's `file` are required. A sound with
+//! a license but no attribution is credited to the pack's author. Files are
+//! relative to the manifest and may not leave the pack folder.
+//!
+//! Soundpad sound lists (`.spl`) are imported too: the referenced files
+//! are copied into the pack folder and pads are named after their titles.
+
+use crate::domain::SoundAttribution;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// File name of the pack manifest
+pub const PACK_MANIFEST: &str = "manifest.json";
+
+/// Errors that can occur while importing a pack
+#[derive(Debug, thiserror::Error)]
+pub enum SoundPackError {
+    #[error("Sound pack not found: {0}")]
+    NotFound(String),
+
+    #[error("Unsupported sound pack: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+
+    #[error("Archive error: {0}")]
+    Archive(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("The pack contains no playable sounds")]
+    Empty,
+}
+
+/// `manifest.json` of a Voiceboard pack
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackManifest {
+    pub name: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    pub sounds: Vec<PackSound>,
+}
+
+/// One sound listed in a manifest
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackSound {
+    pub file: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub gain_db: Option<f32>,
+    #[serde(flatten)]
+    pub attribution: SoundAttribution,
+}
+
+/// A sound installed from a pack, ready to be put on a pad
+#[derive(Debug, Clone)]
+pub struct InstalledSound {
+    pub path: PathBuf,
+    pub name: String,
+    pub hotkey: Option<String>,
+    pub color: Option<String>,
+    pub gain_db: Option<f32>,
+    pub attribution: SoundAttribution,
+}
+
+/// Result of installing a pack
+#[derive(Debug, Clone)]
+pub struct InstalledPack {
+    pub name: String,
+    pub author: Option<String>,
+    pub folder: PathBuf,
+    pub sounds: Vec<InstalledSound>,
+    /// Listed files that were missing or unsafe
+    pub skipped: Vec<String>,
+}
+
+/// Install the pack at `source` into a new folder under `packs_dir`
+///
+/// `source` can be a pack folder, a `.zip` pack or a Soundpad `.spl` list.
+pub fn install_sound_pack(source: &Path, packs_dir: &Path) -> Result<InstalledPack, SoundPackError> {
+    if !source.exists() {
+        return Err(SoundPackError::NotFound(source.display().to_string()));
+    }
+
+    let extension = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase);
+
+    let pack = match extension.as_deref() {
+        _ if source.is_dir() => {
+            let manifest = read_manifest(&source.join(PACK_MANIFEST))?;
+            let folder = unique_folder(packs_dir, &manifest.name)?;
+            copy_dir(source, &folder)?;
+            install_manifest(manifest, folder)
+        }
+        Some("zip") => {
+            let folder = unique_folder(packs_dir, &file_stem(source))?;
+            match extract_zip_pack(source, &folder) {
+                Ok(pack) => pack,
+                Err(e) => {
+                    let _ = fs::remove_dir_all(&folder);
+                    return Err(e);
+                }
+            }
+        }
+        Some("spl") => install_soundpad_list(source, packs_dir)?,
+        _ => return Err(SoundPackError::UnsupportedFormat(source.display().to_string())),
+    };
+
+    if pack.sounds.is_empty() {
+        return Err(SoundPackError::Empty);
+    }
+    Ok(pack)
+}
+
+fn extract_zip_pack(archive: &Path, folder: &Path) -> Result<InstalledPack, SoundPackError> {
+    extract_zip(archive, folder)?;
+    // Zips often wrap the pack in a single top-level folder
+    let root = find_manifest_root(folder)
+        .ok_or_else(|| SoundPackError::InvalidManifest(format!("no {} in archive", PACK_MANIFEST)))?;
+    let manifest = read_manifest(&root.join(PACK_MANIFEST))?;
+    Ok(install_manifest(manifest, root))
+}
+
+fn read_manifest(path: &Path) -> Result<PackManifest, SoundPackError> {
+    let text = fs::read_to_string(path).map_err(|_| SoundPackError::InvalidManifest(format!("missing {}", PACK_MANIFEST)))?;
+    serde_json::from_str(&text).map_err(|e| SoundPackError::InvalidManifest(e.to_string()))
+}
+
+/// Resolve manifest entries against the installed folder
+fn install_manifest(manifest: PackManifest, folder: PathBuf) -> InstalledPack {
+    let mut sounds = Vec::new();
+    let mut skipped = Vec::new();
+
+    for sound in manifest.sounds {
+        let path = folder.join(&sound.file);
+        if !is_contained(&sound.file) || !path.is_file() {
+            skipped.push(sound.file);
+            continue;
+        }
+
+        let mut attribution = sound.attribution;
+        if attribution.license.is_some() && attribution.attribution.is_none() {
+            attribution.attribution = manifest.author.clone();
+        }
+        sounds.push(InstalledSound {
+            name: sound.name.unwrap_or_else(|| file_stem(&path)),
+            path,
+            hotkey: sound.hotkey,
+            color: sound.color,
+            gain_db: sound.gain_db,
+            attribution,
+        });
+    }
+
+    InstalledPack {
+        name: manifest.name,
+        author: manifest.author,
+        folder,
+        sounds,
+        skipped,
+    }
+}
+
+/// Import a Soundpad sound list by copying the sounds it references
+fn install_soundpad_list(source: &Path, packs_dir: &Path) -> Result<InstalledPack, SoundPackError> {
+    let xml = fs::read_to_string(source)?;
+    let name = file_stem(source);
+    let folder = unique_folder(packs_dir, &name)?;
+
+    let mut sounds = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in parse_soundpad_list(&xml) {
+        let original = PathBuf::from(&entry.url);
+        let Some(file_name) = original.file_name() else {
+            skipped.push(entry.url);
+            continue;
+        };
+
+        let path = folder.join(file_name);
+        if fs::copy(&original, &path).is_err() {
+            skipped.push(entry.url);
+            continue;
+        }
+
+        sounds.push(InstalledSound {
+            name: entry.title.unwrap_or_else(|| file_stem(&path)),
+            path,
+            hotkey: None,
+            color: None,
+            gain_db: None,
+            attribution: SoundAttribution::default(),
+        });
+    }
+
+    Ok(InstalledPack {
+        name,
+        author: None,
+        folder,
+        sounds,
+        skipped,
+    })
+}
+
+/// A `<Sound>` entry of a Soundpad list
+#[derive(Debug, Clone, PartialEq)]
+struct SoundpadEntry {
+    url: String,
+    title: Option<String>,
+}
+
+/// Read the `url` and `title` attributes of every `<Sound .../>` tag
+fn parse_soundpad_list(xml: &str) -> Vec<SoundpadEntry> {
+    xml.split("<Sound ")
+        .skip(1)
+        .filter_map(|tag| {
+            let tag = &tag[..tag.find('>')?];
+            Some(SoundpadEntry {
+                url: xml_attribute(tag, "url")?,
+                title: xml_attribute(tag, "title").filter(|t| !t.is_empty()),
+            })
+        })
+        .collect()
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let tag = format!(" {}", tag);
+    let key = format!(" {}=\"", name);
+    let start = tag.find(&key)? + key.len();
+    let end = start + tag[start..].find('"')?;
+
+    Some(
+        tag[start..end]
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Check that a manifest path stays inside the pack folder
+fn is_contained(file: &str) -> bool {
+    Path::new(file)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Sound pack")
+        .to_string()
+}
+
+/// Create a new folder for a pack, numbered if the name is taken
+fn unique_folder(packs_dir: &Path, name: &str) -> Result<PathBuf, SoundPackError> {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let slug = if slug.trim_matches('-').is_empty() { "pack".to_string() } else { slug };
+
+    let mut folder = packs_dir.join(&slug);
+    let mut n = 2;
+    while folder.exists() {
+        folder = packs_dir.join(format!("{}-{}", slug, n));
+        n += 1;
+    }
+    fs::create_dir_all(&folder)?;
+    Ok(folder)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), SoundPackError> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_zip(archive: &Path, to: &Path) -> Result<(), SoundPackError> {
+    let file = fs::File::open(archive)?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| SoundPackError::Archive(e.to_string()))?;
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| SoundPackError::Archive(e.to_string()))?;
+        // Entries that would escape the folder are skipped
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = to.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            std::io::copy(&mut entry, &mut fs::File::create(&target)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// The folder holding the manifest: the root or its only subfolder
+fn find_manifest_root(folder: &Path) -> Option<PathBuf> {
+    if folder.join(PACK_MANIFEST).is_file() {
+        return Some(folder.to_path_buf());
+    }
+
+    let mut subfolders = fs::read_dir(folder)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir());
+    let only = subfolders.next()?;
+    if subfolders.next().is_some() {
+        return None;
+    }
+    only.path().join(PACK_MANIFEST).is_file().then(|| only.path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_folder() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("voiceboard_pack_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_install_folder_pack() {
+        let source = temp_folder();
+        fs::write(source.join("horn.wav"), b"RIFF").unwrap();
+        fs::write(
+            source.join(PACK_MANIFEST),
+            r#"{
+                "name": "Test Pack",
+                "author": "someone",
+                "sounds": [
+                    { "file": "horn.wav", "name": "Air Horn", "gainDb": -3, "license": "CC-BY-4.0" },
+                    { "file": "missing.wav" },
+                    { "file": "../escape.wav" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let packs = temp_folder();
+        let pack = install_sound_pack(&source, &packs).unwrap();
+
+        assert_eq!(pack.name, "Test Pack");
+        assert_eq!(pack.folder, packs.join("Test-Pack"));
+        assert_eq!(pack.sounds.len(), 1);
+        assert_eq!(pack.sounds[0].name, "Air Horn");
+        assert_eq!(pack.sounds[0].gain_db, Some(-3.0));
+        assert_eq!(pack.sounds[0].attribution.license.as_deref(), Some("CC-BY-4.0"));
+        assert_eq!(pack.sounds[0].attribution.attribution.as_deref(), Some("someone"));
+        assert!(pack.sounds[0].path.starts_with(&pack.folder));
+        assert_eq!(pack.skipped, vec!["missing.wav", "../escape.wav"]);
+
+        // Installing again does not overwrite the first copy
+        let again = install_sound_pack(&source, &packs).unwrap();
+        assert_eq!(again.folder, packs.join("Test-Pack-2"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_sources() {
+        let packs = temp_folder();
+        let empty = temp_folder();
+        assert!(matches!(
+            install_sound_pack(&empty, &packs),
+            Err(SoundPackError::InvalidManifest(_))
+        ));
+        assert!(matches!(
+            install_sound_pack(&empty.join("nope.zip"), &packs),
+            Err(SoundPackError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_soundpad_list() {
+        let xml = r#"<?xml version="1.0"?>
+            <Soundlist>
+              <Sound url="C:\sounds\bruh.mp3" artist="" title="Bruh &amp; more" duration="0:01"/>
+              <Sound url="C:\sounds\oof.wav" title=""/>
+            </Soundlist>"#;
+
+        assert_eq!(parse_soundpad_list(xml), vec![
+            SoundpadEntry {
+                url: r"C:\sounds\bruh.mp3".into(),
+                title: Some("Bruh & more".into()),
+            },
+            SoundpadEntry {
+                url: r"C:\sounds\oof.wav".into(),
+                title: None,
+            },
+        ]);
+    }
+}
@@ -0,0 +1,516 @@
+//! Sound streams - Long sounds decoded while they play
+//!
+//! Decoding a whole music bed up front holds every sample in memory and
+//! delays the start by the full decode. A streamed sound is instead decoded
+//! by its own thread into a ring buffer the output callback reads from.
+//! The play only starts once the ring holds a prebuffer, so the first
+//! buffers never run dry. A looping stream rewinds the decoder at the end,
+//! through the same resampler, so the loop point has no seam.
+//!
+//! A seek is handed to the decoder thread. The reader holds the ring until
+//! the thread has tried to move the decoder: it then drops what the ring
+//! holds, so no audio from before the seek plays after it, or keeps it when
+//! the seek failed, so the sound carries on from where it was.
+
+use crate::domain::AudioBuffer;
+use crate::dsp::StreamingResampler;
+use crate::ports::{FileDecoder, FileDecoderError};
+use ringbuf::traits::{Consumer, Observer, Producer, Split};
+use ringbuf::{HeapCons, HeapRb};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+/// Sounds at least this long are streamed instead of decoded up front
+pub const STREAM_MIN_SECS: f64 = 30.0;
+
+/// Audio the ring holds ahead of the playhead
+const RING_SECS: f64 = 2.0;
+
+/// Audio decoded before the play starts
+const PREBUFFER_SECS: f64 = 0.5;
+
+/// Wait of the decoder thread while the ring is full
+const REFILL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Flags shared by a stream's decoder thread and its reader
+#[derive(Default)]
+struct StreamFlags {
+    /// Everything was decoded into the ring
+    finished: AtomicBool,
+    /// The reader is gone; the decoder thread stops
+    closed: AtomicBool,
+    /// Target of the last seek, in microseconds from the start of the file
+    seek_to_us: AtomicU64,
+    /// Seeks asked for by the reader
+    seeks_requested: AtomicU64,
+    /// Seeks the decoder thread tried
+    seeks_done: AtomicU64,
+    /// The last seek tried left the decoder where it was
+    seek_failed: AtomicBool,
+    /// Seeks whose older audio the reader dropped from the ring
+    seeks_cleared: AtomicU64,
+    /// The decoder thread stopped and carries out no more seeks
+    exited: AtomicBool,
+}
+
+/// Format a stream is converted to, with its level and looping
+#[derive(Debug, Clone, Copy)]
+pub struct StreamFormat {
+    pub sample_rate: u32,
+    pub channels: usize,
+    /// Gain applied to every sample, e.g. from auto-level
+    pub gain: f32,
+    pub looping: bool,
+    /// Where in the file the sound starts, and a loop rewinds to
+    pub start: Duration,
+}
+
+/// Reading end of a sound decoded while it plays
+pub struct SoundStream {
+    consumer: HeapCons<f32>,
+    flags: Arc<StreamFlags>,
+    channels: usize,
+    start: Duration,
+    /// Samples of the whole sound at the stream format, from the decoder's
+    /// duration
+    len_hint: usize,
+    /// Seeks whose outcome the reader has taken
+    seeks_seen: u64,
+}
+
+impl SoundStream {
+    /// Start decoding on a new thread, returning once the prebuffer is in
+    pub fn spawn(
+        mut decoder: Box<dyn FileDecoder>,
+        format: StreamFormat,
+    ) -> Result<Self, FileDecoderError> {
+        let channels = format.channels.max(1);
+        let samples_per_sec = format.sample_rate as f64 * channels as f64;
+        let len_hint = decoder.duration().map_or(0, |duration| {
+            (duration.saturating_sub(format.start).as_secs_f64() * samples_per_sec) as usize
+        });
+        if !format.start.is_zero() {
+            decoder.seek(format.start)?;
+        }
+        let ring = (RING_SECS * samples_per_sec) as usize;
+        let prebuffer = (PREBUFFER_SECS * samples_per_sec) as usize;
+
+        let (mut producer, consumer) = HeapRb::<f32>::new(ring - ring % channels).split();
+        let flags = Arc::new(StreamFlags::default());
+        let (ready_tx, ready_rx) = mpsc::channel();
+
+        let thread_flags = flags.clone();
+        thread::Builder::new()
+            .name("sound-stream".into())
+            .spawn(move || {
+                let flags = thread_flags;
+                let mut ready = Some(ready_tx);
+                let mut resampler: Option<StreamingResampler> = None;
+                let mut block: Vec<f32> = Vec::new();
+                let mut offset = 0;
+                let mut ended = false;
+                let mut decoded_any = false;
+                let mut seeks_done = 0;
+                let mut moved = false;
+
+                while !flags.closed.load(Ordering::Relaxed) {
+                    let seeks = flags.seeks_requested.load(Ordering::Acquire);
+                    if seeks != seeks_done {
+                        let target = Duration::from_micros(flags.seek_to_us.load(Ordering::Relaxed));
+                        moved = match decoder.seek(target) {
+                            Ok(()) => {
+                                resampler = None;
+                                block.clear();
+                                offset = 0;
+                                ended = false;
+                                flags.finished.store(false, Ordering::Relaxed);
+                                true
+                            }
+                            Err(e) => {
+                                tracing::warn!(error = %e, "Failed to seek a streamed sound");
+                                false
+                            }
+                        };
+                        seeks_done = seeks;
+                        flags.seek_failed.store(!moved, Ordering::Relaxed);
+                        flags.seeks_done.store(seeks, Ordering::Release);
+                    }
+                    // Nothing from after the seek goes in until the reader
+                    // has dropped what came before it
+                    if moved {
+                        if flags.seeks_cleared.load(Ordering::Acquire) != seeks_done {
+                            thread::sleep(REFILL_INTERVAL);
+                            continue;
+                        }
+                        moved = false;
+                    }
+
+                    // Push what is left of the last block before decoding more
+                    if offset < block.len() {
+                        offset += producer.push_slice(&block[offset..]);
+                        if offset < block.len() {
+                            if producer.occupied_len() >= prebuffer {
+                                signal(&mut ready, Ok(()));
+                            }
+                            thread::sleep(REFILL_INTERVAL);
+                            continue;
+                        }
+                    }
+                    if ended {
+                        // Wait for a seek back, or for the reader to go. A
+                        // sound shorter than the prebuffer is ready now
+                        flags.finished.store(true, Ordering::Relaxed);
+                        signal(&mut ready, Ok(()));
+                        thread::sleep(REFILL_INTERVAL);
+                        continue;
+                    }
+                    if producer.occupied_len() >= prebuffer {
+                        signal(&mut ready, Ok(()));
+                    }
+
+                    let decoded = match decoder.read_next() {
+                        Ok(Some(buffer)) => buffer,
+                        Ok(None) if format.looping && decoded_any => match rewind(decoder.as_mut(), format.start) {
+                            Ok(()) => continue,
+                            Err(e) => {
+                                tracing::warn!(error = %e, "Failed to loop a streamed sound");
+                                ended = true;
+                                block = resampler.as_mut().map(StreamingResampler::flush).unwrap_or_default();
+                                offset = 0;
+                                continue;
+                            }
+                        },
+                        Ok(None) => {
+                            ended = true;
+                            block = resampler.as_mut().map(StreamingResampler::flush).unwrap_or_default();
+                            offset = 0;
+                            continue;
+                        }
+                        Err(e) => {
+                            if ready.is_none() {
+                                tracing::warn!(error = %e, "Streamed sound cut short");
+                            }
+                            signal(&mut ready, Err(e));
+                            break;
+                        }
+                    };
+
+                    decoded_any = true;
+                    let resampler = resampler.get_or_insert_with(|| {
+                        StreamingResampler::new(decoded.sample_rate(), format.sample_rate, channels)
+                    });
+                    block = resampler.process(&to_stream_channels(decoded, channels));
+                    if format.gain != 1.0 {
+                        block.iter_mut().for_each(|sample| *sample *= format.gain);
+                    }
+                    offset = 0;
+                }
+
+                flags.finished.store(true, Ordering::Relaxed);
+                flags.exited.store(true, Ordering::Release);
+                signal(&mut ready, Ok(()));
+                decoder.close();
+            })
+            .map_err(|e| FileDecoderError::IoError(e.to_string()))?;
+
+        ready_rx
+            .recv()
+            .map_err(|_| FileDecoderError::DecodeError("Stream decoder stopped".into()))??;
+        Ok(Self {
+            consumer,
+            flags,
+            channels,
+            start: format.start,
+            len_hint,
+            seeks_seen: 0,
+        })
+    }
+
+    /// Ask the decoder thread to move to `position` from where the sound
+    /// starts
+    ///
+    /// The stream is silent until [`Self::seek_result`] has reported the
+    /// outcome. Seek again only once it has.
+    pub fn seek(&mut self, position: Duration) {
+        let target = self.start + position;
+        self.flags.seek_to_us.store(target.as_micros() as u64, Ordering::Relaxed);
+        self.flags.seeks_requested.fetch_add(1, Ordering::Release);
+    }
+
+    /// Outcome of the last seek, once: true when the stream moved, false
+    /// when it failed and plays on from where it was
+    ///
+    /// None while the decoder thread is still on it, or with no seek asked.
+    pub fn seek_result(&mut self) -> Option<bool> {
+        let flags = &self.flags;
+        let requested = flags.seeks_requested.load(Ordering::Acquire);
+        if requested == self.seeks_seen {
+            return None;
+        }
+        if flags.seeks_done.load(Ordering::Acquire) != requested {
+            if !flags.exited.load(Ordering::Acquire) {
+                return None;
+            }
+            // The decoder thread is gone, so the seek never happens
+            self.seeks_seen = requested;
+            return Some(false);
+        }
+        self.seeks_seen = requested;
+        if flags.seek_failed.load(Ordering::Relaxed) {
+            return Some(false);
+        }
+        self.consumer.clear();
+        flags.seeks_cleared.store(requested, Ordering::Release);
+        Some(true)
+    }
+
+    fn seek_pending(&self) -> bool {
+        self.flags.seeks_requested.load(Ordering::Acquire) != self.seeks_seen
+    }
+
+    /// Move up to `max` decoded samples into `out`, whole frames only
+    ///
+    /// `out` is cleared first; it ends up empty when the ring ran dry.
+    pub fn pop_into(&mut self, out: &mut Vec<f32>, max: usize) {
+        if self.seek_pending() {
+            // Held until the seek's outcome is known
+            out.clear();
+            return;
+        }
+        let available = self.consumer.occupied_len();
+        let count = max.min(available - available % self.channels);
+        out.clear();
+        out.resize(count, 0.0);
+        let popped = self.consumer.pop_slice(out);
+        out.truncate(popped);
+    }
+
+    /// Whether the sound was decoded to its end and fully read
+    pub fn is_drained(&self) -> bool {
+        self.flags.finished.load(Ordering::Relaxed) && self.consumer.is_empty() && !self.seek_pending()
+    }
+
+    /// Length of the whole sound in samples, 0 when the file doesn't say
+    pub fn len_hint(&self) -> usize {
+        self.len_hint
+    }
+}
+
+impl Drop for SoundStream {
+    fn drop(&mut self) {
+        self.flags.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for SoundStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SoundStream")
+            .field("channels", &self.channels)
+            .field("len_hint", &self.len_hint)
+            .finish()
+    }
+}
+
+type Ready = mpsc::Sender<Result<(), FileDecoderError>>;
+
+/// Tell the caller waiting on the prebuffer how the start went, once
+fn signal(ready: &mut Option<Ready>, result: Result<(), FileDecoderError>) {
+    if let Some(ready) = ready.take() {
+        let _ = ready.send(result);
+    }
+}
+
+/// Rewind the decoder to where the stream starts
+fn rewind(decoder: &mut dyn FileDecoder, start: Duration) -> Result<(), FileDecoderError> {
+    if start.is_zero() {
+        decoder.reset()
+    } else {
+        decoder.seek(start)
+    }
+}
+
+/// Decoded samples at the stream's channel count
+fn to_stream_channels(buffer: AudioBuffer, channels: usize) -> Vec<f32> {
+    if buffer.channels() as usize == channels {
+        buffer.to_raw_f32()
+    } else {
+        buffer.convert_channels(channels as u16).to_raw_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::MockFileDecoder;
+
+    fn decoder(blocks: usize, frames: usize) -> MockFileDecoder {
+        let mut decoder = MockFileDecoder::new();
+        let mut left = blocks;
+        decoder.expect_read_next().returning(move || {
+            if left == 0 {
+                return Ok(None);
+            }
+            left -= 1;
+            Ok(Some(AudioBuffer::from_raw_f32(vec![0.5; frames], 1, 1000)))
+        });
+        decoder
+            .expect_duration()
+            .return_const(Some(Duration::from_millis((blocks * frames) as u64)));
+        decoder.expect_close().return_const(());
+        decoder
+    }
+
+    #[test]
+    fn test_stream_plays_through_to_its_end() {
+        let format = StreamFormat {
+            sample_rate: 1000,
+            channels: 2,
+            gain: 0.5,
+            looping: false,
+            start: Duration::ZERO,
+        };
+        // 5 s of mono at 1 kHz: more than the ring holds
+        let mut stream = SoundStream::spawn(Box::new(decoder(50, 100)), format).unwrap();
+        assert_eq!(stream.len_hint(), 10_000);
+
+        let mut chunk = Vec::new();
+        let mut read = 0;
+        for _ in 0..10_000 {
+            stream.pop_into(&mut chunk, 256);
+            assert!(chunk.iter().all(|sample| *sample == 0.25));
+            read += chunk.len();
+            if stream.is_drained() {
+                break;
+            }
+            if chunk.is_empty() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+        assert_eq!(read, 10_000);
+        assert!(stream.is_drained());
+    }
+
+    #[test]
+    fn test_seek_drops_the_audio_from_before_it() {
+        use std::sync::atomic::AtomicBool;
+
+        let seeked = Arc::new(AtomicBool::new(false));
+        let mut decoder = MockFileDecoder::new();
+        let read_seeked = seeked.clone();
+        decoder.expect_read_next().returning(move || {
+            let value = if read_seeked.load(Ordering::Relaxed) { 1.0 } else { 0.5 };
+            Ok(Some(AudioBuffer::from_raw_f32(vec![value; 100], 1, 1000)))
+        });
+        decoder
+            .expect_seek()
+            .withf(|position| *position == Duration::from_millis(500))
+            .times(1)
+            .returning(|_| Ok(()));
+        let seek_flag = seeked.clone();
+        decoder
+            .expect_seek()
+            .withf(|position| *position == Duration::from_millis(1500))
+            .times(1)
+            .returning(move |_| {
+                seek_flag.store(true, Ordering::Relaxed);
+                Ok(())
+            });
+        decoder.expect_duration().return_const(Some(Duration::from_secs(60)));
+        decoder.expect_close().return_const(());
+
+        let format = StreamFormat {
+            sample_rate: 1000,
+            channels: 1,
+            gain: 1.0,
+            looping: false,
+            start: Duration::from_millis(500),
+        };
+        let mut stream = SoundStream::spawn(Box::new(decoder), format).unwrap();
+        let mut chunk = Vec::new();
+        stream.pop_into(&mut chunk, 256);
+        assert!(!chunk.is_empty() && chunk.iter().all(|sample| *sample == 0.5));
+
+        // One second into the sound, which starts half a second in
+        stream.seek(Duration::from_secs(1));
+        assert_eq!(wait_for_seek(&mut stream), Some(true));
+        let mut read = 0;
+        for _ in 0..10_000 {
+            stream.pop_into(&mut chunk, 256);
+            assert!(chunk.iter().all(|sample| *sample == 1.0));
+            read += chunk.len();
+            if read > 1000 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(read > 1000);
+    }
+
+    #[test]
+    fn test_failed_seek_plays_on_from_where_it_was() {
+        let mut decoder = MockFileDecoder::new();
+        let mut next = 0.0;
+        decoder.expect_read_next().returning(move || {
+            let block: Vec<f32> = (0..100).map(|i| next + i as f32).collect();
+            next += 100.0;
+            Ok(Some(AudioBuffer::from_raw_f32(block, 1, 1000)))
+        });
+        decoder
+            .expect_seek()
+            .returning(|_| Err(FileDecoderError::DecodeError("not seekable".into())));
+        decoder.expect_duration().return_const(Some(Duration::from_secs(60)));
+        decoder.expect_close().return_const(());
+
+        let format = StreamFormat {
+            sample_rate: 1000,
+            channels: 1,
+            gain: 1.0,
+            looping: false,
+            start: Duration::ZERO,
+        };
+        let mut stream = SoundStream::spawn(Box::new(decoder), format).unwrap();
+        let mut chunk = Vec::new();
+        stream.pop_into(&mut chunk, 10);
+        assert_eq!(chunk.last(), Some(&9.0));
+
+        stream.seek(Duration::from_secs(30));
+        assert_eq!(wait_for_seek(&mut stream), Some(false));
+        stream.pop_into(&mut chunk, 10);
+        assert_eq!(chunk.first(), Some(&10.0));
+    }
+
+    /// Outcome of a seek, reading nothing until it is known
+    fn wait_for_seek(stream: &mut SoundStream) -> Option<bool> {
+        let mut chunk = Vec::new();
+        for _ in 0..10_000 {
+            if let Some(moved) = stream.seek_result() {
+                return Some(moved);
+            }
+            stream.pop_into(&mut chunk, 256);
+            assert!(chunk.is_empty());
+            thread::sleep(Duration::from_millis(1));
+        }
+        None
+    }
+
+    #[test]
+    fn test_stream_reports_a_file_that_fails_to_decode() {
+        let mut decoder = MockFileDecoder::new();
+        decoder
+            .expect_read_next()
+            .returning(|| Err(FileDecoderError::DecodeError("bad frame".into())));
+        decoder.expect_duration().return_const(None);
+        decoder.expect_close().return_const(());
+
+        let format = StreamFormat {
+            sample_rate: 1000,
+            channels: 1,
+            gain: 1.0,
+            looping: false,
+            start: Duration::ZERO,
+        };
+        assert!(SoundStream::spawn(Box::new(decoder), format).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Startup - Deferred initialisation of the slow parts of the backend
+//!
+//! Device enumeration and the preview engine used to be set up before the
+//! first window paint. They now start on a background thread once setup
+//! has returned; each part emits its own readiness event and
+//! `backend-ready` follows once every part is up. The frontend waits on
+//! that event, or asks `is_backend_ready` if it subscribed too late.
+//! Last, the most played sounds are decoded into the cache so their
+//! first press doesn't wait on the disk.
+
+use crate::application::commands::preload_board_sounds;
+use crate::application::device_watcher::spawn_device_watcher;
+use crate::application::preview_engine::PreviewEngine;
+use crate::application::state::AppState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// A part of the backend initialised after the window is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendPart {
+    /// The device cache holds its first enumeration
+    Devices,
+    /// The preview engine accepts commands
+    Preview,
+}
+
+impl BackendPart {
+    /// Event emitted once the part is ready
+    pub fn event(&self) -> &'static str {
+        match self {
+            BackendPart::Devices => "devices-ready",
+            BackendPart::Preview => "preview-ready",
+        }
+    }
+}
+
+/// Which deferred parts are ready
+pub struct BackendReadiness {
+    devices: AtomicBool,
+    preview: AtomicBool,
+}
+
+impl BackendReadiness {
+    pub fn new() -> Self {
+        Self {
+            devices: AtomicBool::new(false),
+            preview: AtomicBool::new(false),
+        }
+    }
+
+    fn flag(&self, part: BackendPart) -> &AtomicBool {
+        match part {
+            BackendPart::Devices => &self.devices,
+            BackendPart::Preview => &self.preview,
+        }
+    }
+
+    /// Mark `part` ready
+    ///
+    /// Returns true for the call that completes the backend, so
+    /// `backend-ready` is emitted exactly once.
+    pub fn mark_ready(&self, part: BackendPart) -> bool {
+        if self.flag(part).swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        self.is_ready()
+    }
+
+    pub fn is_part_ready(&self, part: BackendPart) -> bool {
+        self.flag(part).load(Ordering::SeqCst)
+    }
+
+    /// Whether every deferred part is ready
+    pub fn is_ready(&self) -> bool {
+        self.is_part_ready(BackendPart::Devices) && self.is_part_ready(BackendPart::Preview)
+    }
+}
+
+impl Default for BackendReadiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Report a deferred part as ready, emitting `backend-ready` after the last
+pub fn part_ready(app: &AppHandle, part: BackendPart) {
+    let state = app.state::<AppState>();
+    let complete = state.readiness.mark_ready(part);
+    let _ = app.emit(part.event(), ());
+    if complete {
+        tracing::info!("Backend ready");
+        let _ = app.emit("backend-ready", ());
+    }
+}
+
+/// Start the preview engine and the device watcher off the setup path
+///
+/// The watcher's first poll is the first enumeration; it reports the
+/// devices ready once it has filled the cache.
+pub fn spawn_deferred_init(app: AppHandle) {
+    let spawned = thread::Builder::new().name("deferred-init".into()).spawn(move || {
+        let started = Instant::now();
+        let state = app.state::<AppState>();
+
+        // Safe mode opens no audio device; previews then report the
+        // engine as not running
+        if !state.safe_mode.is_active() {
+            let preview = PreviewEngine::new(app.clone(), state.decoder.clone());
+            *state.preview_engine.blocking_lock() = Some(preview);
+        }
+        part_ready(&app, BackendPart::Preview);
+
+        spawn_device_watcher(app.clone(), state.device_manager.clone());
+
+        // A file crashing the decoder may be why safe mode is on
+        if !state.safe_mode.is_active() {
+            preload_board_sounds(&app);
+        }
+        tracing::debug!(elapsed_ms = started.elapsed().as_millis() as u64, "Deferred init spawned");
+    });
+    if let Err(e) = spawned {
+        tracing::error!(error = %e, "Failed to start the deferred init");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_is_ready_once_every_part_is() {
+        let readiness = BackendReadiness::new();
+        assert!(!readiness.mark_ready(BackendPart::Preview));
+        assert!(!readiness.is_ready());
+
+        assert!(readiness.mark_ready(BackendPart::Devices));
+        assert!(readiness.is_ready());
+
+        // A part reported twice doesn't complete the backend again
+        assert!(!readiness.mark_ready(BackendPart::Devices));
+    }
+}
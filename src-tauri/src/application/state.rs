@@ -1,8 +1,32 @@
 //! Application state management
 
+use crate::adapters::{CpalDeviceManager, KeyringSecretStore};
+use crate::application::app_capture::AppCaptureService;
 use crate::application::audio_engine::AudioEngine;
+use crate::application::auto_save::AutoSave;
+use crate::application::board_share::BoardShare;
+use crate::application::buffer_tuner::BufferTuner;
+use crate::application::cloud_sync::CloudSync;
+use crate::application::decoder_service::DecoderService;
+use crate::application::gain_wizard::GainWizard;
+use crate::application::global_hotkeys::GlobalHotkeyTable;
+use crate::application::hotkey_registry::HotkeyRegistry;
+use crate::application::mic_mute_sync::MicMuteSync;
+use crate::application::now_playing::{NowPlayingTracker, OverlayServer};
+use crate::application::onboarding::OnboardingService;
+use crate::application::play_log::PlayLog;
 use crate::application::preview_engine::PreviewEngine;
-use crate::domain::{AppSettings, MixerConfig};
+use crate::application::push_to_talk::PushToTalkService;
+use crate::application::quick_memo::QuickMemoRecorder;
+use crate::application::safe_mode::SafeMode;
+use crate::application::session_recorder::SessionRecorder;
+use crate::application::session_stats::SessionTracker;
+use crate::application::settings_service::SettingsService;
+use crate::application::startup::BackendReadiness;
+use crate::application::updates::UpdateDownloader;
+use crate::domain::{ActiveScene, AppSettings, MixerConfig, TimerRegistry, TriggerRateLimiter, VariantPicker};
+use crate::infrastructure::{TallyController, TelemetryCollector};
+use crate::ports::SecretStore;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
@@ -10,9 +34,48 @@ use tokio::sync::{Mutex, RwLock};
 pub struct AppState {
     pub mixer_config: Arc<RwLock<MixerConfig>>,
     pub settings: Arc<RwLock<AppSettings>>,
+    /// Persistence of the settings file; the only writer of settings.json
+    pub settings_store: Arc<SettingsService>,
+    /// Debounced saves for values changed in quick succession
+    pub auto_save: Arc<AutoSave>,
     pub is_mixing: Arc<RwLock<bool>>,
-    pub audio_engine: Arc<Mutex<AudioEngine>>,
+    /// Lock-free handle, shared by every command
+    pub audio_engine: Arc<AudioEngine>,
     pub preview_engine: Arc<Mutex<Option<PreviewEngine>>>,
+    pub decoder: Arc<DecoderService>,
+    pub device_manager: Arc<RwLock<CpalDeviceManager>>,
+    pub mic_mute_sync: Arc<MicMuteSync>,
+    /// Passwords, kept in the OS credential store rather than settings.json
+    pub secrets: Arc<dyn SecretStore>,
+    pub tally: Arc<TallyController>,
+    pub quick_memo: Arc<QuickMemoRecorder>,
+    pub pad_variants: Arc<Mutex<VariantPicker>>,
+    pub timers: Arc<TimerRegistry>,
+    pub active_scene: Arc<Mutex<ActiveScene>>,
+    pub trigger_limiter: Arc<TriggerRateLimiter>,
+    pub telemetry: Arc<TelemetryCollector>,
+    pub update_downloader: Arc<UpdateDownloader>,
+    pub onboarding: Arc<OnboardingService>,
+    pub cloud_sync: Arc<CloudSync>,
+    pub board_share: Arc<BoardShare>,
+    pub play_log: Arc<PlayLog>,
+    pub recorder: Arc<SessionRecorder>,
+    /// Statistics of the running mixing session
+    pub session_tracker: Arc<SessionTracker>,
+    pub gain_wizard: Arc<GainWizard>,
+    pub hotkeys: Arc<HotkeyRegistry>,
+    /// Pad hotkeys registered with the OS
+    pub global_hotkeys: Arc<GlobalHotkeyTable>,
+    /// Key-held gate on the mic
+    pub push_to_talk: Arc<PushToTalkService>,
+    pub app_capture: Arc<AppCaptureService>,
+    pub now_playing: Arc<NowPlayingTracker>,
+    pub overlay: Arc<OverlayServer>,
+    pub buffer_tuner: Arc<BufferTuner>,
+    /// Parts of the backend started after the window is shown
+    pub readiness: Arc<BackendReadiness>,
+    /// Audio and hotkeys stay off for this run when active
+    pub safe_mode: SafeMode,
 }
 
 impl AppState {
@@ -20,9 +83,39 @@ impl AppState {
         Self {
             mixer_config: Arc::new(RwLock::new(MixerConfig::default())),
             settings: Arc::new(RwLock::new(AppSettings::default())),
+            settings_store: Arc::new(SettingsService::new()),
+            auto_save: Arc::new(AutoSave::new()),
             is_mixing: Arc::new(RwLock::new(false)),
-            audio_engine: Arc::new(Mutex::new(AudioEngine::new())),
+            audio_engine: Arc::new(AudioEngine::new()),
             preview_engine: Arc::new(Mutex::new(None)),
+            decoder: Arc::new(DecoderService::new()),
+            device_manager: Arc::new(RwLock::new(CpalDeviceManager::new())),
+            mic_mute_sync: Arc::new(MicMuteSync::for_platform()),
+            secrets: Arc::new(KeyringSecretStore::new()),
+            tally: Arc::new(TallyController::new()),
+            quick_memo: Arc::new(QuickMemoRecorder::new()),
+            pad_variants: Arc::new(Mutex::new(VariantPicker::new())),
+            timers: Arc::new(TimerRegistry::new()),
+            active_scene: Arc::new(Mutex::new(ActiveScene::default())),
+            trigger_limiter: Arc::new(TriggerRateLimiter::new()),
+            telemetry: Arc::new(TelemetryCollector::new()),
+            update_downloader: Arc::new(UpdateDownloader::new()),
+            onboarding: Arc::new(OnboardingService::new()),
+            cloud_sync: Arc::new(CloudSync::new()),
+            board_share: Arc::new(BoardShare::new()),
+            play_log: Arc::new(PlayLog::new()),
+            recorder: Arc::new(SessionRecorder::new()),
+            session_tracker: Arc::new(SessionTracker::new()),
+            gain_wizard: Arc::new(GainWizard::new()),
+            hotkeys: Arc::new(HotkeyRegistry::new()),
+            global_hotkeys: Arc::new(GlobalHotkeyTable::new()),
+            push_to_talk: Arc::new(PushToTalkService::for_platform()),
+            app_capture: Arc::new(AppCaptureService::for_platform()),
+            now_playing: Arc::new(NowPlayingTracker::new()),
+            overlay: Arc::new(OverlayServer::new()),
+            buffer_tuner: Arc::new(BufferTuner::new()),
+            readiness: Arc::new(BackendReadiness::new()),
+            safe_mode: SafeMode::default(),
         }
     }
 
@@ -32,13 +125,45 @@ impl AppState {
             master_volume: settings.audio.master_volume,
             ..Default::default()
         };
+        let hotkeys = HotkeyRegistry::new();
+        hotkeys.set_active_profile(settings.active_hotkey_profile.clone());
 
         Self {
             mixer_config: Arc::new(RwLock::new(mixer_config)),
             settings: Arc::new(RwLock::new(settings)),
+            settings_store: Arc::new(SettingsService::new()),
+            auto_save: Arc::new(AutoSave::new()),
             is_mixing: Arc::new(RwLock::new(false)),
-            audio_engine: Arc::new(Mutex::new(AudioEngine::new())),
+            audio_engine: Arc::new(AudioEngine::new()),
             preview_engine: Arc::new(Mutex::new(None)),
+            decoder: Arc::new(DecoderService::new()),
+            device_manager: Arc::new(RwLock::new(CpalDeviceManager::new())),
+            mic_mute_sync: Arc::new(MicMuteSync::for_platform()),
+            secrets: Arc::new(KeyringSecretStore::new()),
+            tally: Arc::new(TallyController::new()),
+            quick_memo: Arc::new(QuickMemoRecorder::new()),
+            pad_variants: Arc::new(Mutex::new(VariantPicker::new())),
+            timers: Arc::new(TimerRegistry::new()),
+            active_scene: Arc::new(Mutex::new(ActiveScene::default())),
+            trigger_limiter: Arc::new(TriggerRateLimiter::new()),
+            telemetry: Arc::new(TelemetryCollector::new()),
+            update_downloader: Arc::new(UpdateDownloader::new()),
+            onboarding: Arc::new(OnboardingService::new()),
+            cloud_sync: Arc::new(CloudSync::new()),
+            board_share: Arc::new(BoardShare::new()),
+            play_log: Arc::new(PlayLog::new()),
+            recorder: Arc::new(SessionRecorder::new()),
+            session_tracker: Arc::new(SessionTracker::new()),
+            gain_wizard: Arc::new(GainWizard::new()),
+            hotkeys: Arc::new(hotkeys),
+            global_hotkeys: Arc::new(GlobalHotkeyTable::new()),
+            push_to_talk: Arc::new(PushToTalkService::for_platform()),
+            app_capture: Arc::new(AppCaptureService::for_platform()),
+            now_playing: Arc::new(NowPlayingTracker::new()),
+            overlay: Arc::new(OverlayServer::new()),
+            buffer_tuner: Arc::new(BufferTuner::new()),
+            readiness: Arc::new(BackendReadiness::new()),
+            safe_mode: SafeMode::default(),
         }
     }
 }
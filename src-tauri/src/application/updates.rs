@@ -0,0 +1,323 @@
+//! Update channels, staged rollouts and release notes parsing
+
+use crate::domain::UpdateChannel;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::Update;
+
+/// Update manifest for the stable channel
+const STABLE_ENDPOINT: &str =
+    "https://github.com/didouye/voiceboard/releases/latest/download/latest.json";
+
+/// Update manifest for the beta channel
+const BETA_ENDPOINT: &str =
+    "https://github.com/didouye/voiceboard/releases/download/beta/latest.json";
+
+/// Manifest field holding the staged rollout percentage (0-100)
+const ROLLOUT_FIELD: &str = "rollout";
+
+/// Get the update manifest URL for a channel
+pub fn channel_endpoint(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+/// Read the staged rollout percentage from a raw update manifest
+///
+/// Returns None when the manifest doesn't define a rollout (full release).
+pub fn rollout_percentage(manifest: &serde_json::Value) -> Option<u8> {
+    manifest
+        .get(ROLLOUT_FIELD)
+        .and_then(|v| v.as_u64())
+        .map(|v| v.min(100) as u8)
+}
+
+/// Rollout bucket (0-99) of a random installation id
+///
+/// Taken from the whole id so every bucket is equally likely; a single
+/// byte modulo 100 favors the low buckets.
+pub fn rollout_bucket_for(id: uuid::Uuid) -> u8 {
+    (id.as_u128() % 100) as u8
+}
+
+/// Check whether this installation falls inside a staged rollout
+///
+/// `bucket` is a stable per-installation value in 0..100.
+pub fn is_in_rollout(bucket: u8, rollout: Option<u8>) -> bool {
+    match rollout {
+        Some(percentage) => bucket < percentage,
+        None => true,
+    }
+}
+
+/// A section of the release notes (e.g. "Features", "Bug Fixes")
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReleaseNotesSection {
+    pub title: String,
+    pub items: Vec<String>,
+}
+
+/// Parse a markdown changelog body into sections
+///
+/// Headings (`#`, `##`, `###`) start a new section and list items
+/// (`-`, `*`) become section entries. Items before the first heading
+/// are grouped under "Changes".
+pub fn parse_release_notes(body: &str) -> Vec<ReleaseNotesSection> {
+    let mut sections: Vec<ReleaseNotesSection> = Vec::new();
+
+    for line in body.lines() {
+        let line = line.trim();
+
+        if line.starts_with('#') {
+            let title = line.trim_start_matches('#').trim();
+            if !title.is_empty() {
+                sections.push(ReleaseNotesSection {
+                    title: title.to_string(),
+                    items: Vec::new(),
+                });
+            }
+            continue;
+        }
+
+        let item = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .map(str::trim);
+
+        if let Some(item) = item.filter(|i| !i.is_empty()) {
+            if sections.is_empty() {
+                sections.push(ReleaseNotesSection {
+                    title: "Changes".to_string(),
+                    items: Vec::new(),
+                });
+            }
+            if let Some(section) = sections.last_mut() {
+                section.items.push(item.to_string());
+            }
+        }
+    }
+
+    sections.retain(|s| !s.items.is_empty());
+    sections
+}
+
+/// State of the background update download, as shown in the UI
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UpdateDownloadState {
+    /// No download in progress
+    Idle,
+    /// Downloading an update
+    Downloading {
+        version: String,
+        downloaded: u64,
+        total: Option<u64>,
+    },
+    /// Update downloaded and ready to install
+    Ready { version: String },
+    /// Download failed
+    Failed { version: String, error: String },
+}
+
+/// Progress payload for `update-download-progress` events
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgress {
+    version: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+/// Background downloader keeping a downloaded update until it is installed
+pub struct UpdateDownloader {
+    state: Mutex<UpdateDownloadState>,
+    pending: Mutex<Option<(Update, Vec<u8>)>>,
+}
+
+impl UpdateDownloader {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(UpdateDownloadState::Idle),
+            pending: Mutex::new(None),
+        }
+    }
+
+    /// Get the current download state
+    pub fn state(&self) -> UpdateDownloadState {
+        self.state
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or(UpdateDownloadState::Idle)
+    }
+
+    fn set_state(&self, state: UpdateDownloadState) {
+        if let Ok(mut current) = self.state.lock() {
+            *current = state;
+        }
+    }
+
+    /// Start downloading an update in the background
+    ///
+    /// Does nothing if this version is already downloading or downloaded.
+    pub fn start(self: &Arc<Self>, app: AppHandle, update: Update) {
+        let version = update.version.clone();
+
+        match self.state() {
+            UpdateDownloadState::Downloading { version: v, .. }
+            | UpdateDownloadState::Ready { version: v }
+                if v == version =>
+            {
+                return;
+            }
+            _ => {}
+        }
+
+        self.set_state(UpdateDownloadState::Downloading {
+            version: version.clone(),
+            downloaded: 0,
+            total: None,
+        });
+
+        let downloader = self.clone();
+        tauri::async_runtime::spawn(async move {
+            tracing::info!(version = %version, "Downloading update in background");
+
+            let mut downloaded: u64 = 0;
+            let mut last_percent: Option<u64> = None;
+            let result = update
+                .download(
+                    |chunk_length, total| {
+                        downloaded += chunk_length as u64;
+                        downloader.set_state(UpdateDownloadState::Downloading {
+                            version: version.clone(),
+                            downloaded,
+                            total,
+                        });
+
+                        // Throttle progress events to one per percent
+                        let percent = total.map(|t| downloaded * 100 / t.max(1));
+                        if percent != last_percent {
+                            last_percent = percent;
+                            let _ = app.emit("update-download-progress", DownloadProgress {
+                                version: version.clone(),
+                                downloaded,
+                                total,
+                            });
+                        }
+                    },
+                    || {
+                        tracing::info!("Background update download complete");
+                    },
+                )
+                .await;
+
+            match result {
+                Ok(bytes) => {
+                    if let Ok(mut pending) = downloader.pending.lock() {
+                        *pending = Some((update, bytes));
+                    }
+                    downloader.set_state(UpdateDownloadState::Ready {
+                        version: version.clone(),
+                    });
+                    let _ = app.emit("update-downloaded", &version);
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Background update download failed");
+                    downloader.set_state(UpdateDownloadState::Failed {
+                        version: version.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        });
+    }
+
+    /// Take the downloaded update, if any
+    pub fn take_pending(&self) -> Option<(Update, Vec<u8>)> {
+        let pending = self.pending.lock().ok()?.take();
+        if pending.is_some() {
+            self.set_state(UpdateDownloadState::Idle);
+        }
+        pending
+    }
+
+    /// Install the downloaded update without restarting
+    ///
+    /// Returns Ok(false) if no update has been downloaded.
+    pub fn install_pending(&self) -> Result<bool, String> {
+        match self.take_pending() {
+            Some((update, bytes)) => {
+                tracing::info!(version = %update.version, "Installing downloaded update");
+                update
+                    .install(bytes)
+                    .map_err(|e| format!("Failed to install update: {}", e))?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+impl Default for UpdateDownloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release_notes() {
+        let body = "## Features\n- Ducking\n- Hotkeys\n\n## Bug Fixes\n* Crash on start\n";
+        let sections = parse_release_notes(body);
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].title, "Features");
+        assert_eq!(sections[0].items, vec!["Ducking", "Hotkeys"]);
+        assert_eq!(sections[1].items, vec!["Crash on start"]);
+    }
+
+    #[test]
+    fn test_parse_release_notes_without_headings() {
+        let sections = parse_release_notes("- Small fix");
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].title, "Changes");
+    }
+
+    #[test]
+    fn test_rollout() {
+        let manifest = serde_json::json!({ "version": "1.0.0", "rollout": 25 });
+        let rollout = rollout_percentage(&manifest);
+
+        assert_eq!(rollout, Some(25));
+        assert!(is_in_rollout(10, rollout));
+        assert!(!is_in_rollout(60, rollout));
+        assert!(is_in_rollout(99, None));
+    }
+
+    #[test]
+    fn test_rollout_buckets_are_uniform() {
+        let mut counts = [0u32; 100];
+        for _ in 0..100_000 {
+            counts[rollout_bucket_for(uuid::Uuid::new_v4()) as usize] += 1;
+        }
+        // 1000 expected per bucket, about 31 standard deviation
+        assert!(counts.iter().all(|&count| (800..=1200).contains(&count)), "{:?}", counts);
+
+        // Half the buckets hold half the installs
+        let lower_half: u32 = counts[..50].iter().sum();
+        assert!((48_500..=51_500).contains(&lower_half));
+    }
+
+    #[test]
+    fn test_download_state_serialization() {
+        let state = UpdateDownloadState::Ready { version: "1.2.0".into() };
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["status"], "ready");
+        assert_eq!(json["version"], "1.2.0");
+    }
+}
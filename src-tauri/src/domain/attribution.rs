@@ -0,0 +1,104 @@
+//! Sound attribution - License and credit of the sounds on the board
+//!
+//! Stored on each sound as optional `license`, `sourceUrl` and
+//! `attribution` fields, so they travel with the pads wherever the board
+//! goes. Creators paste the exported credits into VOD descriptions.
+
+use serde::{Deserialize, Serialize};
+
+/// Where a sound comes from and how it may be used
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoundAttribution {
+    /// License name or SPDX id, e.g. `CC-BY-4.0`
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Credit line, usually the author
+    #[serde(default)]
+    pub attribution: Option<String>,
+}
+
+impl SoundAttribution {
+    pub fn is_empty(&self) -> bool {
+        self.license.is_none() && self.source_url.is_none() && self.attribution.is_none()
+    }
+}
+
+/// A sound to credit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoundCredit {
+    pub name: String,
+    pub attribution: SoundAttribution,
+}
+
+/// The sounds that have attribution, each once, in order of appearance
+pub fn unique_credits(credits: impl IntoIterator<Item = SoundCredit>) -> Vec<SoundCredit> {
+    let mut unique: Vec<SoundCredit> = Vec::new();
+    for credit in credits.into_iter().filter(|c| !c.attribution.is_empty()) {
+        if !unique.contains(&credit) {
+            unique.push(credit);
+        }
+    }
+    unique
+}
+
+/// Credits text listing `credits`
+pub fn credits_text(credits: &[SoundCredit]) -> String {
+    let mut text = String::from("Sound credits\n");
+    for credit in credits {
+        text.push('\n');
+        text.push_str(&credit.name);
+        text.push('\n');
+        let attribution = &credit.attribution;
+        let lines = [
+            ("By", &attribution.attribution),
+            ("License", &attribution.license),
+            ("Source", &attribution.source_url),
+        ];
+        for (label, value) in lines {
+            if let Some(value) = value {
+                text.push_str(&format!("  {}: {}\n", label, value));
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credits_list_each_sound_once() {
+        let horn = SoundCredit {
+            name: "Air Horn".into(),
+            attribution: SoundAttribution {
+                license: Some("CC-BY-4.0".into()),
+                source_url: Some("https://example.com/horn".into()),
+                attribution: Some("someone".into()),
+            },
+        };
+        let unknown = SoundCredit {
+            name: "Mystery".into(),
+            attribution: SoundAttribution::default(),
+        };
+
+        let credits = unique_credits([horn.clone(), unknown, horn]);
+        assert_eq!(credits.len(), 1);
+        assert_eq!(
+            credits_text(&credits),
+            "Sound credits\n\nAir Horn\n  By: someone\n  License: CC-BY-4.0\n  Source: https://example.com/horn\n"
+        );
+    }
+
+    #[test]
+    fn test_read_from_sound_json() {
+        let attribution: SoundAttribution =
+            serde_json::from_str(r#"{"id": "sound_1", "name": "Gong", "license": "CC0-1.0"}"#).unwrap();
+        assert_eq!(attribution.license.as_deref(), Some("CC0-1.0"));
+        assert!(attribution.source_url.is_none());
+        assert!(!attribution.is_empty());
+    }
+}
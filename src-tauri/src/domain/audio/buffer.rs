@@ -106,6 +106,81 @@ impl AudioBuffer {
             *sample = sample.apply_gain(gain);
         }
     }
+
+    /// Convert the buffer to a different channel count
+    ///
+    /// Mono is duplicated to every channel, downmixing to mono averages
+    /// all channels, other conversions keep the first channels and pad
+    /// with silence.
+    pub fn convert_channels(&self, channels: u16) -> AudioBuffer {
+        if channels == self.channels || channels == 0 || self.channels == 0 {
+            return self.clone();
+        }
+
+        let from = self.channels as usize;
+        let to = channels as usize;
+        let mut samples = Vec::with_capacity(self.frame_count() * to);
+
+        for frame in self.samples.chunks_exact(from) {
+            if from == 1 {
+                samples.extend(std::iter::repeat(frame[0]).take(to));
+            } else if to == 1 {
+                let sum: f32 = frame.iter().map(|s| s.value()).sum();
+                samples.push(Sample::new(sum / from as f32));
+            } else {
+                for ch in 0..to {
+                    samples.push(frame.get(ch).copied().unwrap_or_default());
+                }
+            }
+        }
+
+        AudioBuffer::new(samples, channels, self.sample_rate)
+    }
+
+    /// Copy a range of frames, clamped to the buffer length
+    pub fn slice_frames(&self, start: usize, end: usize) -> AudioBuffer {
+        let channels = self.channels.max(1) as usize;
+        let end = end.min(self.frame_count());
+        let start = start.min(end);
+        AudioBuffer::new(
+            self.samples[start * channels..end * channels].to_vec(),
+            self.channels,
+            self.sample_rate,
+        )
+    }
+
+    /// Peak absolute sample value
+    pub fn peak(&self) -> f32 {
+        self.samples.iter().map(|s| s.value().abs()).fold(0.0, f32::max)
+    }
+
+    /// Remove leading and trailing frames quieter than `threshold`
+    ///
+    /// Returns an empty buffer if every frame is below the threshold.
+    pub fn trim_silence(&self, threshold: f32) -> AudioBuffer {
+        let channels = self.channels.max(1) as usize;
+        let is_loud = |frame: &[Sample]| frame.iter().any(|s| s.value().abs() >= threshold);
+        let frames: Vec<&[Sample]> = self.samples.chunks_exact(channels).collect();
+
+        let start = frames.iter().position(|f| is_loud(f));
+        let end = frames.iter().rposition(|f| is_loud(f));
+        let samples = match (start, end) {
+            (Some(start), Some(end)) => self.samples[start * channels..(end + 1) * channels].to_vec(),
+            _ => Vec::new(),
+        };
+
+        AudioBuffer::new(samples, self.channels, self.sample_rate)
+    }
+
+    /// Scale the buffer so its peak reaches `target_peak`
+    ///
+    /// Silent buffers are left untouched.
+    pub fn normalize(&mut self, target_peak: f32) {
+        let peak = self.peak();
+        if peak > 0.0 {
+            self.apply_gain(target_peak / peak);
+        }
+    }
 }
 
 /// Errors that can occur when working with audio buffers
@@ -158,4 +233,36 @@ mod tests {
         let result = buffer1.mix(&buffer2);
         assert!(matches!(result, Err(BufferError::ChannelMismatch { .. })));
     }
+
+    #[test]
+    fn test_trim_and_normalize() {
+        let raw = vec![0.0, 0.0, 0.01, 0.0, 0.2, -0.4, 0.1, 0.0, 0.0, 0.0];
+        let buffer = AudioBuffer::from_raw_f32(raw, 2, 48000);
+
+        let mut trimmed = buffer.trim_silence(0.05);
+        assert_eq!(trimmed.to_raw_f32(), vec![0.2, -0.4, 0.1, 0.0]);
+
+        trimmed.normalize(0.8);
+        assert!((trimmed.peak() - 0.8).abs() < 1e-6);
+
+        assert_eq!(AudioBuffer::silence(10, 2, 48000).trim_silence(0.05).frame_count(), 0);
+    }
+
+    #[test]
+    fn test_slice_frames() {
+        let buffer = AudioBuffer::from_raw_f32(vec![0.0, 0.5, 1.0, 0.5], 1, 4);
+
+        assert_eq!(buffer.slice_frames(1, 3).to_raw_f32(), vec![0.5, 1.0]);
+        assert_eq!(buffer.slice_frames(3, 10).frame_count(), 1);
+    }
+
+    #[test]
+    fn test_convert_channels() {
+        let mono = AudioBuffer::from_raw_f32(vec![0.5, -0.5], 1, 44100);
+        let stereo = mono.convert_channels(2);
+        assert_eq!(stereo.to_raw_f32(), vec![0.5, 0.5, -0.5, -0.5]);
+
+        let back = stereo.convert_channels(1);
+        assert_eq!(back.to_raw_f32(), vec![0.5, -0.5]);
+    }
 }
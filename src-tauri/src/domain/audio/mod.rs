@@ -3,7 +3,9 @@
 mod sample;
 mod buffer;
 mod format;
+mod noise_profile;
 
 pub use sample::*;
 pub use buffer::*;
 pub use format::*;
+pub use noise_profile::*;
@@ -0,0 +1,82 @@
+//! Noise profile - Learned spectrum of a room's background noise
+
+use serde::{Deserialize, Serialize};
+
+/// Average magnitude spectrum of background noise ("noise print")
+///
+/// Learned from a few seconds of room tone and used to subtract that
+/// noise from the mic signal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseProfile {
+    /// FFT frame size the profile was learned with
+    pub fft_size: usize,
+    /// Mean magnitude per bin, DC to Nyquist (`fft_size / 2 + 1` values)
+    pub magnitudes: Vec<f32>,
+}
+
+impl NoiseProfile {
+    pub fn new(fft_size: usize, magnitudes: Vec<f32>) -> Self {
+        Self { fft_size, magnitudes }
+    }
+
+    /// Check the profile has one magnitude per bin
+    pub fn is_valid(&self) -> bool {
+        self.fft_size >= 4
+            && self.fft_size.is_power_of_two()
+            && self.magnitudes.len() == self.fft_size / 2 + 1
+    }
+
+    /// Convert the profile to another FFT size
+    ///
+    /// Bins are interpolated across frequency, and magnitudes rescaled
+    /// since noise energy per bin grows with the frame length.
+    pub fn resized(&self, fft_size: usize) -> Self {
+        if fft_size == self.fft_size || self.magnitudes.is_empty() {
+            return self.clone();
+        }
+
+        let ratio = self.fft_size as f32 / fft_size as f32;
+        let scale = (fft_size as f32 / self.fft_size as f32).sqrt();
+        let last = self.magnitudes.len() - 1;
+
+        let magnitudes = (0..=fft_size / 2)
+            .map(|k| {
+                let position = (k as f32 * ratio).min(last as f32);
+                let low = position.floor() as usize;
+                let high = (low + 1).min(last);
+                let frac = position - low as f32;
+                let magnitude = self.magnitudes[low] + (self.magnitudes[high] - self.magnitudes[low]) * frac;
+                magnitude * scale
+            })
+            .collect();
+
+        Self::new(fft_size, magnitudes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_validity() {
+        assert!(NoiseProfile::new(8, vec![0.0; 5]).is_valid());
+        assert!(!NoiseProfile::new(8, vec![0.0; 4]).is_valid());
+        assert!(!NoiseProfile::new(6, vec![0.0; 4]).is_valid());
+    }
+
+    #[test]
+    fn test_resized_profile() {
+        let profile = NoiseProfile::new(8, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        let larger = profile.resized(16);
+        assert!(larger.is_valid());
+        let scale = 2.0f32.sqrt();
+        assert!((larger.magnitudes[3] - 1.5 * scale).abs() < 1e-6);
+        assert!((larger.magnitudes[8] - 4.0 * scale).abs() < 1e-6);
+
+        let smaller = profile.resized(4);
+        assert!(smaller.is_valid());
+        assert!((smaller.magnitudes[1] - 2.0 / scale).abs() < 1e-6);
+    }
+}
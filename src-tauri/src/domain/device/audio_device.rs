@@ -42,6 +42,16 @@ pub enum DeviceType {
 }
 
 impl DeviceType {
+    /// Type of a device of the given direction and kind
+    pub fn from_parts(is_input: bool, is_virtual: bool) -> Self {
+        match (is_input, is_virtual) {
+            (true, false) => DeviceType::InputPhysical,
+            (true, true) => DeviceType::InputVirtual,
+            (false, false) => DeviceType::OutputPhysical,
+            (false, true) => DeviceType::OutputVirtual,
+        }
+    }
+
     pub fn is_input(&self) -> bool {
         matches!(self, DeviceType::InputPhysical | DeviceType::InputVirtual)
     }
@@ -97,6 +107,11 @@ impl AudioDevice {
         self.device_type
     }
 
+    /// Reclassify the device, e.g. after the user marked it as virtual
+    pub fn set_device_type(&mut self, device_type: DeviceType) {
+        self.device_type = device_type;
+    }
+
     pub fn is_default(&self) -> bool {
         self.is_default
     }
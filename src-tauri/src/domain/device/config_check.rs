@@ -0,0 +1,276 @@
+//! Mixing configuration check - Problems to fix before the engine starts
+
+use super::{find_device_name, AudioDevice, DeviceType};
+use serde::Serialize;
+
+/// Which stream a device is selected for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceRole {
+    Input,
+    Output,
+    Monitor,
+}
+
+/// Whether an issue stops mixing or only degrades it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// Start would fail or do something harmful
+    Error,
+    /// Mixing works, but not as the user probably expects
+    Warning,
+}
+
+/// A problem with the selected devices, with what the UI needs to fix it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConfigIssue {
+    NoDeviceSelected { role: DeviceRole },
+    DeviceNotFound { role: DeviceRole, device: String },
+    /// Windows resamples in shared mode, which costs quality and latency
+    UnsupportedSampleRate {
+        role: DeviceRole,
+        device: String,
+        sample_rate: u32,
+        supported: Vec<u32>,
+    },
+    UnsupportedChannels {
+        role: DeviceRole,
+        device: String,
+        channels: u16,
+        supported: Vec<u16>,
+    },
+    /// No virtual cable is installed, so other apps can't hear the mix
+    VirtualDriverMissing,
+    /// The mix goes to a real device instead of a virtual cable
+    OutputNotVirtual { device: String },
+    /// The output is also the input, so the mix feeds itself
+    CircularRouting { device: String },
+    /// The mic is the recording end of the cable the mix plays into, so
+    /// the mix comes straight back in and howls
+    FeedbackLoop { input: String, output: String },
+    /// The monitor plays into a virtual cable instead of headphones, so
+    /// listeners get the mix twice (or it loops back through the mic)
+    MonitorOnVirtualCable { device: String },
+}
+
+impl ConfigIssue {
+    pub fn severity(&self) -> IssueSeverity {
+        match self {
+            ConfigIssue::UnsupportedSampleRate { .. } | ConfigIssue::OutputNotVirtual { .. } => IssueSeverity::Warning,
+            ConfigIssue::DeviceNotFound {
+                role: DeviceRole::Monitor,
+                ..
+            } => IssueSeverity::Warning,
+            _ => IssueSeverity::Error,
+        }
+    }
+}
+
+/// Devices and format the engine would start with
+#[derive(Debug, Clone, Copy)]
+pub struct MixingRouting<'a> {
+    pub input_device: Option<&'a str>,
+    pub output_device: Option<&'a str>,
+    /// Preview device, when the monitor is on
+    pub monitor_device: Option<&'a str>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Channels the input opens with, more than `channels` for a channel map
+    pub input_channels: u16,
+}
+
+/// Name shared by both ends of a virtual cable, e.g. "cable (vb-audio
+/// virtual cable)" for "CABLE Input" and "CABLE Output"
+fn cable_key(name: &str) -> String {
+    name.to_lowercase()
+        .split_whitespace()
+        .filter(|word| !matches!(*word, "input" | "output" | "in" | "out"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Check a routing against the devices present, most severe issues first
+pub fn check_routing(routing: &MixingRouting, devices: &[AudioDevice]) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let find = |name: &str, input: bool| {
+        let candidates: Vec<&AudioDevice> =
+            devices.iter().filter(|device| device.device_type().is_input() == input).collect();
+        find_device_name(candidates.iter().map(|device| device.id().as_str()), name).map(|index| candidates[index])
+    };
+
+    let selected = [
+        (DeviceRole::Input, routing.input_device, routing.input_channels, true),
+        (DeviceRole::Output, routing.output_device, routing.channels, false),
+        (DeviceRole::Monitor, routing.monitor_device, routing.channels, false),
+    ];
+    for (role, name, channels, input) in selected {
+        let Some(name) = name else {
+            if role != DeviceRole::Monitor {
+                issues.push(ConfigIssue::NoDeviceSelected { role });
+            }
+            continue;
+        };
+        let Some(device) = find(name, input) else {
+            issues.push(ConfigIssue::DeviceNotFound {
+                role,
+                device: name.to_string(),
+            });
+            continue;
+        };
+        if !device.supports_sample_rate(routing.sample_rate) {
+            issues.push(ConfigIssue::UnsupportedSampleRate {
+                role,
+                device: name.to_string(),
+                sample_rate: routing.sample_rate,
+                supported: device.sample_rates().to_vec(),
+            });
+        }
+        if !device.channels().iter().any(|supported| *supported >= channels) {
+            issues.push(ConfigIssue::UnsupportedChannels {
+                role,
+                device: name.to_string(),
+                channels,
+                supported: device.channels().to_vec(),
+            });
+        }
+    }
+
+    if !devices.iter().any(|device| device.device_type() == DeviceType::OutputVirtual) {
+        issues.push(ConfigIssue::VirtualDriverMissing);
+    }
+    if let Some(output) = routing.output_device.and_then(|name| find(name, false)) {
+        if !output.device_type().is_virtual() {
+            issues.push(ConfigIssue::OutputNotVirtual {
+                device: output.name().to_string(),
+            });
+        }
+    }
+    if let (Some(input), Some(output)) = (routing.input_device, routing.output_device) {
+        if input == output {
+            issues.push(ConfigIssue::CircularRouting {
+                device: output.to_string(),
+            });
+        } else if find(input, true).is_some_and(|device| device.device_type().is_virtual())
+            && find(output, false).is_some_and(|device| device.device_type().is_virtual())
+            && cable_key(input) == cable_key(output)
+        {
+            issues.push(ConfigIssue::FeedbackLoop {
+                input: input.to_string(),
+                output: output.to_string(),
+            });
+        }
+    }
+    if let Some(monitor) = routing.monitor_device.and_then(|name| find(name, false)) {
+        if monitor.device_type().is_virtual() {
+            issues.push(ConfigIssue::MonitorOnVirtualCable {
+                device: monitor.name().to_string(),
+            });
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.severity() != IssueSeverity::Error);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DeviceId;
+
+    fn device(name: &str, device_type: DeviceType) -> AudioDevice {
+        AudioDevice::new(DeviceId::new(name), name.into(), device_type, false, vec![44100, 48000], vec![1, 2])
+    }
+
+    fn routing<'a>(input: &'a str, output: &'a str) -> MixingRouting<'a> {
+        MixingRouting {
+            input_device: Some(input),
+            output_device: Some(output),
+            monitor_device: None,
+            sample_rate: 48000,
+            channels: 2,
+            input_channels: 2,
+        }
+    }
+
+    #[test]
+    fn test_valid_routing_has_no_issues() {
+        let devices = [device("Mic", DeviceType::InputPhysical), device("CABLE Input", DeviceType::OutputVirtual)];
+        assert!(check_routing(&routing("Mic", "CABLE Input"), &devices).is_empty());
+    }
+
+    #[test]
+    fn test_missing_devices_and_formats_are_reported() {
+        let devices = [device("Mic", DeviceType::InputPhysical), device("Speakers", DeviceType::OutputPhysical)];
+        let mut routing = routing("Mic", "Gone");
+        routing.monitor_device = Some("Headphones");
+        routing.sample_rate = 96000;
+        routing.input_channels = 4;
+
+        let issues = check_routing(&routing, &devices);
+        assert_eq!(
+            issues,
+            vec![
+                ConfigIssue::UnsupportedChannels {
+                    role: DeviceRole::Input,
+                    device: "Mic".into(),
+                    channels: 4,
+                    supported: vec![1, 2],
+                },
+                ConfigIssue::DeviceNotFound {
+                    role: DeviceRole::Output,
+                    device: "Gone".into(),
+                },
+                ConfigIssue::VirtualDriverMissing,
+                ConfigIssue::UnsupportedSampleRate {
+                    role: DeviceRole::Input,
+                    device: "Mic".into(),
+                    sample_rate: 96000,
+                    supported: vec![44100, 48000],
+                },
+                ConfigIssue::DeviceNotFound {
+                    role: DeviceRole::Monitor,
+                    device: "Headphones".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cable_loops_are_detected() {
+        let devices = [
+            device("CABLE Output (VB-Audio Virtual Cable)", DeviceType::InputVirtual),
+            device("CABLE Input (VB-Audio Virtual Cable)", DeviceType::OutputVirtual),
+            device("VoiceMeeter Input (VB-Audio VoiceMeeter VAIO)", DeviceType::OutputVirtual),
+        ];
+        let looped = routing("CABLE Output (VB-Audio Virtual Cable)", "CABLE Input (VB-Audio Virtual Cable)");
+        assert_eq!(
+            check_routing(&looped, &devices),
+            vec![ConfigIssue::FeedbackLoop {
+                input: "CABLE Output (VB-Audio Virtual Cable)".into(),
+                output: "CABLE Input (VB-Audio Virtual Cable)".into(),
+            }]
+        );
+
+        // A different cable is fine, but not as the monitor
+        let mut other = routing("CABLE Output (VB-Audio Virtual Cable)", "VoiceMeeter Input (VB-Audio VoiceMeeter VAIO)");
+        assert!(check_routing(&other, &devices).is_empty());
+        other.monitor_device = Some("CABLE Input (VB-Audio Virtual Cable)");
+        assert_eq!(
+            check_routing(&other, &devices),
+            vec![ConfigIssue::MonitorOnVirtualCable {
+                device: "CABLE Input (VB-Audio Virtual Cable)".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_issues_serialize_with_codes() {
+        let issue = ConfigIssue::NoDeviceSelected { role: DeviceRole::Input };
+        assert_eq!(
+            serde_json::to_value(&issue).unwrap(),
+            serde_json::json!({ "code": "NO_DEVICE_SELECTED", "role": "input" })
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Device names - Matching saved names against what the drivers report
+//!
+//! Devices are identified by name, but the same device is reported a bit
+//! differently across locales and driver updates: decomposed accents,
+//! stray NULs or replacement characters from a mis-decoded name, a
+//! "2- " instance prefix Windows adds for a second identical device, or a
+//! host API suffix. Names are compared on a normalized key instead.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Host API tags some drivers append to the name
+const DRIVER_SUFFIXES: &[&str] = &["(wdm)", "(mme)", "(wasapi)", "(directsound)", "(ks)"];
+
+/// Clean up a name for display: NFC, no control or replacement
+/// characters, single spaces
+pub fn normalize_device_name(name: &str) -> String {
+    let cleaned: String = name
+        .nfc()
+        .filter(|c| !c.is_control() && *c != char::REPLACEMENT_CHARACTER)
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Key two names of the same device share
+pub fn device_name_key(name: &str) -> String {
+    let mut key = normalize_device_name(name).to_lowercase();
+    while let Some(suffix) = DRIVER_SUFFIXES.iter().find(|suffix| key.ends_with(*suffix)) {
+        key.truncate(key.len() - suffix.len());
+        key.truncate(key.trim_end().len());
+    }
+    strip_instance_prefix(&key)
+}
+
+/// "microphone (2- usb audio)" -> "microphone (usb audio)"
+fn strip_instance_prefix(key: &str) -> String {
+    let Some(open) = key.find('(') else {
+        return key.to_string();
+    };
+    let inner = &key[open + 1..];
+    let digits = inner.chars().take_while(char::is_ascii_digit).count();
+    match inner[digits..].strip_prefix("- ") {
+        Some(rest) if digits > 0 => format!("{}({}", &key[..open], rest),
+        _ => key.to_string(),
+    }
+}
+
+/// Whether two names refer to the same device
+pub fn same_device_name(a: &str, b: &str) -> bool {
+    a == b || device_name_key(a) == device_name_key(b)
+}
+
+/// Pick the device named `name` among `names`: the exact name when present,
+/// else the first one matching after normalization
+pub fn find_device_name<'a, I>(names: I, name: &str) -> Option<usize>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let key = device_name_key(name);
+    let mut normalized = None;
+    for (index, candidate) in names.into_iter().enumerate() {
+        if candidate == name {
+            return Some(index);
+        }
+        if normalized.is_none() && device_name_key(candidate) == key {
+            normalized = Some(index);
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_are_cleaned_for_display() {
+        // "e" + combining acute, a NUL and doubled spaces
+        assert_eq!(
+            normalize_device_name("Microphone  (Re\u{301}altek Audio)\0"),
+            "Microphone (R\u{e9}altek Audio)"
+        );
+    }
+
+    #[test]
+    fn test_driver_variants_share_a_key() {
+        assert!(same_device_name("Microphone (2- USB Audio Device)", "Microphone (USB Audio Device)"));
+        assert!(same_device_name("Line In (Scarlett 2i2) (WDM)", "Line In (Scarlett 2i2)"));
+        assert!(same_device_name("Micro (Re\u{301}altek)", "micro (r\u{e9}altek)"));
+        // The model number isn't an instance prefix
+        assert!(!same_device_name("Line In (2i2 USB)", "Line In (USB)"));
+    }
+
+    #[test]
+    fn test_exact_name_wins() {
+        let names = ["Microphone (USB Audio Device)", "Microphone (2- USB Audio Device)"];
+        assert_eq!(find_device_name(names, "Microphone (2- USB Audio Device)"), Some(1));
+        assert_eq!(find_device_name(names, "Microphone (3- USB Audio Device)"), Some(0));
+        assert_eq!(find_device_name(names, "Speakers"), None);
+    }
+}
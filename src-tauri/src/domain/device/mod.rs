@@ -1,5 +1,9 @@
 //! Audio device domain entities
 
 mod audio_device;
+mod config_check;
+mod device_name;
 
 pub use audio_device::*;
+pub use config_check::*;
+pub use device_name::*;
@@ -0,0 +1,378 @@
+//! Pad hotkeys - Key combos, multi-key sequences and banks
+//!
+//! A hotkey is a sequence of one or more combos separated by spaces,
+//! e.g. `"Ctrl+Shift+A"` or `"Space 1"` (leader key, then a key).
+//! Banks are layers selected by holding a modifier: a binding in bank 1
+//! written as `"A"` fires on `Shift+A`. Conflicts are detected on the
+//! effective keys, so they are caught across banks too.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of hotkey banks (bank 0 needs no modifier)
+pub const HOTKEY_BANKS: u8 = 4;
+
+/// Errors that can occur when parsing a hotkey
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HotkeyError {
+    #[error("Hotkey is empty")]
+    Empty,
+
+    #[error("Invalid key combo: {0}")]
+    InvalidCombo(String),
+
+    #[error("Bank {0} does not exist")]
+    InvalidBank(u8),
+
+    #[error("Bank {bank} already uses {modifier} to select it")]
+    BankModifierUsed { bank: u8, modifier: String },
+
+    #[error("{0} can't be global: use a single combo with Ctrl, Alt or Meta, or a function key")]
+    NotGlobal(String),
+}
+
+/// A key pressed together with modifiers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    /// Key name, lowercase for single characters (`"a"`, `"space"`, `"f1"`)
+    pub key: String,
+}
+
+impl KeyCombo {
+    /// Parse a combo like `"Ctrl+Shift+A"`
+    pub fn parse(text: &str) -> Result<Self, HotkeyError> {
+        let mut combo = KeyCombo::default();
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts
+            .split_last()
+            .filter(|(key, _)| !key.is_empty())
+            .ok_or_else(|| HotkeyError::InvalidCombo(text.to_string()))?;
+
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => combo.ctrl = true,
+                "alt" | "option" => combo.alt = true,
+                "shift" => combo.shift = true,
+                "meta" | "cmd" | "super" | "win" => combo.meta = true,
+                _ => return Err(HotkeyError::InvalidCombo(text.to_string())),
+            }
+        }
+
+        combo.key = key.to_lowercase();
+        Ok(combo)
+    }
+
+    /// Whether the key is F1 to F24
+    pub fn is_function_key(&self) -> bool {
+        self.key
+            .strip_prefix('f')
+            .and_then(|n| n.parse::<u8>().ok())
+            .is_some_and(|n| (1..=24).contains(&n))
+    }
+
+    /// Add the modifiers that select a bank
+    fn with_bank(&self, bank: u8) -> Self {
+        let (alt, shift) = bank_modifiers(bank);
+        Self {
+            alt: self.alt || alt,
+            shift: self.shift || shift,
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.meta, "Meta"),
+        ] {
+            if held {
+                write!(f, "{}+", name)?;
+            }
+        }
+
+        let mut chars = self.key.chars();
+        match chars.next() {
+            Some(first) => write!(f, "{}{}", first.to_uppercase(), chars.as_str()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Modifiers held to select a bank: (alt, shift)
+fn bank_modifiers(bank: u8) -> (bool, bool) {
+    match bank {
+        1 => (false, true),
+        2 => (true, false),
+        3 => (true, true),
+        _ => (false, false),
+    }
+}
+
+/// One or more combos pressed in order
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HotkeySequence(Vec<KeyCombo>);
+
+impl HotkeySequence {
+    /// Parse a space-separated sequence like `"Space 1"`
+    pub fn parse(text: &str) -> Result<Self, HotkeyError> {
+        let combos = text
+            .split_whitespace()
+            .map(KeyCombo::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if combos.is_empty() {
+            return Err(HotkeyError::Empty);
+        }
+        Ok(Self(combos))
+    }
+
+    pub fn combos(&self) -> &[KeyCombo] {
+        &self.0
+    }
+
+    /// Check if this sequence is a strict prefix of another
+    pub fn is_prefix_of(&self, other: &HotkeySequence) -> bool {
+        self.0.len() < other.0.len() && other.0.starts_with(&self.0)
+    }
+
+    /// The keys actually pressed when this sequence is used in a bank
+    pub fn in_bank(&self, bank: u8) -> HotkeySequence {
+        HotkeySequence(self.0.iter().map(|c| c.with_bank(bank)).collect())
+    }
+
+    /// The combo to register system-wide for this hotkey
+    ///
+    /// A global hotkey swallows its keys in every app, so it must be one
+    /// combo that can't be typed: held Ctrl, Alt or Meta, or a function key.
+    pub fn global_combo(&self) -> Result<&KeyCombo, HotkeyError> {
+        match self.0.as_slice() {
+            [combo] if combo.ctrl || combo.alt || combo.meta || combo.is_function_key() => Ok(combo),
+            _ => Err(HotkeyError::NotGlobal(self.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for HotkeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let combos: Vec<String> = self.0.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", combos.join(" "))
+    }
+}
+
+/// A pad hotkey in a bank
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyBinding {
+    pub pad_id: String,
+    pub bank: u8,
+    pub sequence: HotkeySequence,
+}
+
+impl HotkeyBinding {
+    pub fn new(pad_id: impl Into<String>, bank: u8, sequence: HotkeySequence) -> Result<Self, HotkeyError> {
+        if bank >= HOTKEY_BANKS {
+            return Err(HotkeyError::InvalidBank(bank));
+        }
+
+        // A combo can't also hold the modifier that selects its bank
+        let (alt, shift) = bank_modifiers(bank);
+        for combo in sequence.combos() {
+            if (alt && combo.alt) || (shift && combo.shift) {
+                let modifier = if shift && combo.shift { "Shift" } else { "Alt" };
+                return Err(HotkeyError::BankModifierUsed {
+                    bank,
+                    modifier: modifier.to_string(),
+                });
+            }
+        }
+
+        Ok(Self {
+            pad_id: pad_id.into(),
+            bank,
+            sequence,
+        })
+    }
+
+    /// The keys actually pressed to trigger this binding
+    pub fn effective(&self) -> HotkeySequence {
+        self.sequence.in_bank(self.bank)
+    }
+}
+
+/// How two bindings conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyConflictKind {
+    /// Both bindings use exactly the same keys
+    Duplicate,
+    /// One binding is the start of the other, so the longer one can't fire
+    Prefix,
+}
+
+/// A binding that conflicts with a candidate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyConflict {
+    pub binding: HotkeyBinding,
+    pub kind: HotkeyConflictKind,
+}
+
+/// Find existing bindings that conflict with a candidate
+///
+/// Bindings for the candidate's own pad are ignored.
+pub fn find_conflicts(existing: &[HotkeyBinding], candidate: &HotkeyBinding) -> Vec<HotkeyConflict> {
+    let keys = candidate.effective();
+
+    existing
+        .iter()
+        .filter(|b| b.pad_id != candidate.pad_id)
+        .filter_map(|binding| {
+            let other = binding.effective();
+            let kind = if other == keys {
+                HotkeyConflictKind::Duplicate
+            } else if other.is_prefix_of(&keys) || keys.is_prefix_of(&other) {
+                HotkeyConflictKind::Prefix
+            } else {
+                return None;
+            };
+            Some(HotkeyConflict {
+                binding: binding.clone(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Result of feeding a combo to a [`SequenceMatcher`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceMatch {
+    /// A binding fired
+    Matched(String),
+    /// The keys so far start at least one sequence
+    Pending,
+    /// No binding starts with these keys
+    NoMatch,
+}
+
+/// Matches pressed combos against bindings, one key at a time
+pub struct SequenceMatcher {
+    bindings: Vec<HotkeyBinding>,
+    pressed: Vec<KeyCombo>,
+}
+
+impl SequenceMatcher {
+    pub fn new(bindings: Vec<HotkeyBinding>) -> Self {
+        Self {
+            bindings,
+            pressed: Vec::new(),
+        }
+    }
+
+    /// Feed the next pressed combo (with any bank modifiers held)
+    pub fn press(&mut self, combo: KeyCombo) -> SequenceMatch {
+        self.pressed.push(combo);
+        let pressed = HotkeySequence(self.pressed.clone());
+
+        if let Some(binding) = self.bindings.iter().find(|b| b.effective() == pressed) {
+            self.pressed.clear();
+            return SequenceMatch::Matched(binding.pad_id.clone());
+        }
+
+        if self.bindings.iter().any(|b| pressed.is_prefix_of(&b.effective())) {
+            return SequenceMatch::Pending;
+        }
+
+        self.pressed.clear();
+        SequenceMatch::NoMatch
+    }
+
+    /// Abandon a partially typed sequence (e.g. after a timeout)
+    pub fn reset(&mut self) {
+        self.pressed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(pad: &str, bank: u8, keys: &str) -> HotkeyBinding {
+        HotkeyBinding::new(pad, bank, HotkeySequence::parse(keys).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_normalize() {
+        let sequence = HotkeySequence::parse("control+shift+a  space").unwrap();
+        assert_eq!(sequence.to_string(), "Ctrl+Shift+A Space");
+
+        assert_eq!(HotkeySequence::parse("  "), Err(HotkeyError::Empty));
+        assert!(matches!(KeyCombo::parse("Hyper+A"), Err(HotkeyError::InvalidCombo(_))));
+        assert!(matches!(KeyCombo::parse("Ctrl+"), Err(HotkeyError::InvalidCombo(_))));
+    }
+
+    #[test]
+    fn test_global_hotkeys_cannot_be_typed() {
+        let global = |keys: &str| HotkeySequence::parse(keys).unwrap().global_combo().is_ok();
+        assert!(global("Ctrl+Shift+A"));
+        assert!(global("F13"));
+        assert!(global("Alt+1"));
+
+        assert!(!global("A"));
+        assert!(!global("Shift+A"));
+        assert!(!global("Ctrl+A B"));
+        assert!(!global("Fn"));
+    }
+
+    #[test]
+    fn test_bank_modifier_rules() {
+        let sequence = HotkeySequence::parse("Shift+A").unwrap();
+        assert!(HotkeyBinding::new("p", 1, sequence.clone()).is_err());
+        assert!(HotkeyBinding::new("p", 2, sequence.clone()).is_ok());
+        assert_eq!(
+            HotkeyBinding::new("p", HOTKEY_BANKS, sequence),
+            Err(HotkeyError::InvalidBank(HOTKEY_BANKS))
+        );
+    }
+
+    #[test]
+    fn test_conflicts_across_banks_and_prefixes() {
+        let existing = vec![
+            binding("a", 0, "Shift+Q"),
+            binding("b", 0, "Space"),
+            binding("c", 2, "W"),
+        ];
+
+        // Bank 1 "Q" is pressed as Shift+Q
+        let conflicts = find_conflicts(&existing, &binding("new", 1, "Q"));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, HotkeyConflictKind::Duplicate);
+
+        // "Space 1" can never fire while "Space" is bound
+        let conflicts = find_conflicts(&existing, &binding("new", 0, "Space 1"));
+        assert_eq!(conflicts[0].binding.pad_id, "b");
+        assert_eq!(conflicts[0].kind, HotkeyConflictKind::Prefix);
+
+        assert!(find_conflicts(&existing, &binding("new", 0, "W")).is_empty());
+        assert!(find_conflicts(&existing, &binding("b", 0, "Space")).is_empty());
+    }
+
+    #[test]
+    fn test_sequence_matcher() {
+        let mut matcher = SequenceMatcher::new(vec![
+            binding("leader-1", 0, "Space 1"),
+            binding("shifted", 1, "A"),
+        ]);
+
+        let key = |k: &str| KeyCombo::parse(k).unwrap();
+        assert_eq!(matcher.press(key("Space")), SequenceMatch::Pending);
+        assert_eq!(matcher.press(key("1")), SequenceMatch::Matched("leader-1".into()));
+        assert_eq!(matcher.press(key("Shift+A")), SequenceMatch::Matched("shifted".into()));
+        assert_eq!(matcher.press(key("A")), SequenceMatch::NoMatch);
+    }
+}
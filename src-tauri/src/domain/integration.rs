@@ -0,0 +1,255 @@
+//! Integration permissions - What remote triggers are allowed to do
+//!
+//! Every integration that can trigger the board from outside (HTTP,
+//! WebSocket, Twitch chat, OSC, MIDI, the hot folder) has its own policy:
+//! whether it is on, which sounds and actions it may trigger, and how
+//! often. Chat gets a tight policy out of the box, so exposing the board
+//! to viewers doesn't let them blast every pad.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Something that triggers the board from outside the app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Integration {
+    Http,
+    #[serde(rename = "websocket")]
+    WebSocket,
+    Twitch,
+    Osc,
+    Midi,
+    HotFolder,
+}
+
+impl Integration {
+    pub const ALL: [Integration; 6] = [
+        Integration::Http,
+        Integration::WebSocket,
+        Integration::Twitch,
+        Integration::Osc,
+        Integration::Midi,
+        Integration::HotFolder,
+    ];
+}
+
+impl fmt::Display for Integration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Integration::Http => "HTTP",
+            Integration::WebSocket => "WebSocket",
+            Integration::Twitch => "Twitch",
+            Integration::Osc => "OSC",
+            Integration::Midi => "MIDI",
+            Integration::HotFolder => "hot folder",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Kind of trigger an integration may send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationAction {
+    PlaySound,
+    StopSound,
+    StopAll,
+    /// Mute or unmute the mic
+    MuteMic,
+    /// Run the non-audio actions of a pad
+    PadActions,
+    RecallScene,
+}
+
+impl IntegrationAction {
+    pub const ALL: [IntegrationAction; 6] = [
+        IntegrationAction::PlaySound,
+        IntegrationAction::StopSound,
+        IntegrationAction::StopAll,
+        IntegrationAction::MuteMic,
+        IntegrationAction::PadActions,
+        IntegrationAction::RecallScene,
+    ];
+}
+
+/// At most `max_triggers` in any `per_secs` seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub max_triggers: u32,
+    pub per_secs: u32,
+}
+
+/// What one integration may do
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrationPolicy {
+    pub enabled: bool,
+    /// Sound ids it may play, None for any
+    pub allowed_sounds: Option<Vec<String>>,
+    pub allowed_actions: Vec<IntegrationAction>,
+    /// None for no limit
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl IntegrationPolicy {
+    /// Everything allowed, as integrations worked before policies
+    pub fn open() -> Self {
+        Self {
+            enabled: true,
+            allowed_sounds: None,
+            allowed_actions: IntegrationAction::ALL.to_vec(),
+            rate_limit: None,
+        }
+    }
+
+    /// Default policy of an integration; chat may only play sounds, a few
+    /// times a minute
+    pub fn default_for(integration: Integration) -> Self {
+        match integration {
+            Integration::Twitch => Self {
+                enabled: true,
+                allowed_sounds: None,
+                allowed_actions: vec![IntegrationAction::PlaySound],
+                rate_limit: Some(RateLimit {
+                    max_triggers: 3,
+                    per_secs: 30,
+                }),
+            },
+            _ => Self::open(),
+        }
+    }
+
+    /// Check an action against the policy, without the rate limit
+    pub fn check(
+        &self,
+        integration: Integration,
+        action: IntegrationAction,
+        sound_id: Option<&str>,
+    ) -> Result<(), PermissionDenied> {
+        if !self.enabled {
+            return Err(PermissionDenied::Disabled(integration));
+        }
+        if !self.allowed_actions.contains(&action) {
+            return Err(PermissionDenied::Action(integration, action));
+        }
+        match (sound_id, &self.allowed_sounds) {
+            (Some(sound_id), Some(allowed)) if !allowed.iter().any(|id| id == sound_id) => {
+                Err(PermissionDenied::Sound(integration, sound_id.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Policies of the integrations that differ from their default
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrationPermissions {
+    pub policies: HashMap<Integration, IntegrationPolicy>,
+}
+
+impl IntegrationPermissions {
+    pub fn policy(&self, integration: Integration) -> IntegrationPolicy {
+        self.policies
+            .get(&integration)
+            .cloned()
+            .unwrap_or_else(|| IntegrationPolicy::default_for(integration))
+    }
+}
+
+/// Why a trigger was refused
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PermissionDenied {
+    #[error("{0} triggers are disabled")]
+    Disabled(Integration),
+
+    #[error("{0} may not {1:?}")]
+    Action(Integration, IntegrationAction),
+
+    #[error("{0} may not play sound {1}")]
+    Sound(Integration, String),
+
+    #[error("Too many {0} triggers, try again later")]
+    RateLimited(Integration),
+}
+
+/// Recent triggers of each integration, for the rate limits
+#[derive(Debug, Default)]
+pub struct TriggerRateLimiter {
+    hits: Mutex<HashMap<Integration, VecDeque<Instant>>>,
+}
+
+impl TriggerRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a trigger at `now` if the limit allows it
+    pub fn allow(&self, integration: Integration, limit: &RateLimit, now: Instant) -> bool {
+        let window = Duration::from_secs(limit.per_secs as u64);
+        let mut hits = self.hits.lock().unwrap();
+        let hits = hits.entry(integration).or_default();
+        while hits.front().is_some_and(|hit| now.duration_since(*hit) >= window) {
+            hits.pop_front();
+        }
+        if hits.len() >= limit.max_triggers as usize {
+            return false;
+        }
+        hits.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_checks() {
+        let mut policy = IntegrationPolicy::default_for(Integration::Twitch);
+        policy.allowed_sounds = Some(vec!["airhorn".into()]);
+        let twitch = Integration::Twitch;
+
+        assert_eq!(policy.check(twitch, IntegrationAction::PlaySound, Some("airhorn")), Ok(()));
+        assert_eq!(
+            policy.check(twitch, IntegrationAction::PlaySound, Some("scream")),
+            Err(PermissionDenied::Sound(twitch, "scream".into()))
+        );
+        assert_eq!(
+            policy.check(twitch, IntegrationAction::MuteMic, None),
+            Err(PermissionDenied::Action(twitch, IntegrationAction::MuteMic))
+        );
+
+        policy.enabled = false;
+        assert_eq!(
+            policy.check(twitch, IntegrationAction::PlaySound, Some("airhorn")),
+            Err(PermissionDenied::Disabled(twitch))
+        );
+        assert_eq!(IntegrationPermissions::default().policy(Integration::Osc), IntegrationPolicy::open());
+    }
+
+    #[test]
+    fn test_rate_limit_window() {
+        let limiter = TriggerRateLimiter::new();
+        let limit = RateLimit {
+            max_triggers: 2,
+            per_secs: 10,
+        };
+        let start = Instant::now();
+        assert!(limiter.allow(Integration::Twitch, &limit, start));
+        assert!(limiter.allow(Integration::Twitch, &limit, start + Duration::from_secs(1)));
+        assert!(!limiter.allow(Integration::Twitch, &limit, start + Duration::from_secs(2)));
+        // Other integrations count on their own
+        assert!(limiter.allow(Integration::Osc, &limit, start + Duration::from_secs(2)));
+        // The first trigger left the window
+        assert!(limiter.allow(Integration::Twitch, &limit, start + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_permissions_json() {
+        let json = r#"{"policies": {"websocket": {"enabled": false, "allowed_sounds": null, "allowed_actions": [], "rate_limit": null}}}"#;
+        let permissions: IntegrationPermissions = serde_json::from_str(json).unwrap();
+        assert!(!permissions.policy(Integration::WebSocket).enabled);
+    }
+}
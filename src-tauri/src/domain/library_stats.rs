@@ -0,0 +1,110 @@
+//! Library stats - How much disk and memory the sound library takes
+//!
+//! Files are grouped by category: their format (`wav`, `mp3`, ...) or
+//! `archived` once moved out of the way. WAV exports usually dominate,
+//! which is what users on small SSDs want to see.
+
+use serde::Serialize;
+
+/// Category of archived files, whatever their format
+pub const ARCHIVED_CATEGORY: &str = "archived";
+
+/// A file of the library
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryFile {
+    pub category: String,
+    /// 0 when the file is missing
+    pub size_bytes: u64,
+    pub duration_secs: f64,
+    /// Whether the file exists on disk
+    pub present: bool,
+}
+
+/// Disk use of one category
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryUsage {
+    pub category: String,
+    pub files: usize,
+    pub size_bytes: u64,
+}
+
+/// Size of the sound library on disk and in memory
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStats {
+    pub total_sounds: usize,
+    /// Duration of every sound, in seconds
+    pub total_duration_secs: f64,
+    pub total_size_bytes: u64,
+    /// Categories, biggest first
+    pub categories: Vec<CategoryUsage>,
+    /// Sounds whose file is gone
+    pub missing_files: usize,
+    pub cached_sounds: usize,
+    /// Memory held by the decode cache
+    pub cache_bytes: u64,
+}
+
+impl LibraryStats {
+    /// Totals and per-category use of `files`; cache fields are left at 0
+    pub fn from_files(files: &[LibraryFile]) -> Self {
+        let mut categories: Vec<CategoryUsage> = Vec::new();
+        for file in files.iter().filter(|file| file.present) {
+            match categories.iter_mut().find(|usage| usage.category == file.category) {
+                Some(usage) => {
+                    usage.files += 1;
+                    usage.size_bytes += file.size_bytes;
+                }
+                None => categories.push(CategoryUsage {
+                    category: file.category.clone(),
+                    files: 1,
+                    size_bytes: file.size_bytes,
+                }),
+            }
+        }
+        categories.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then_with(|| a.category.cmp(&b.category)));
+
+        Self {
+            total_sounds: files.len(),
+            total_duration_secs: files.iter().map(|file| file.duration_secs).sum(),
+            total_size_bytes: categories.iter().map(|usage| usage.size_bytes).sum(),
+            categories,
+            missing_files: files.iter().filter(|file| !file.present).count(),
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(category: &str, size_bytes: u64, duration_secs: f64) -> LibraryFile {
+        LibraryFile {
+            category: category.into(),
+            size_bytes,
+            duration_secs,
+            present: size_bytes > 0,
+        }
+    }
+
+    #[test]
+    fn test_categories_are_summed_biggest_first() {
+        let stats = LibraryStats::from_files(&[
+            file("mp3", 100, 2.0),
+            file("wav", 5000, 3.0),
+            file("mp3", 200, 1.5),
+            file(ARCHIVED_CATEGORY, 300, 4.0),
+            file("ogg", 0, 1.0),
+        ]);
+
+        assert_eq!(stats.total_sounds, 5);
+        assert_eq!(stats.total_duration_secs, 11.5);
+        assert_eq!(stats.total_size_bytes, 5600);
+        assert_eq!(stats.missing_files, 1);
+        let categories: Vec<(&str, usize, u64)> =
+            stats.categories.iter().map(|c| (c.category.as_str(), c.files, c.size_bytes)).collect();
+        assert_eq!(categories, vec![("wav", 1, 5000), ("archived", 1, 300), ("mp3", 2, 300)]);
+    }
+}
@@ -0,0 +1,137 @@
+//! Recording markers - Named moments in a recording, for editors
+//!
+//! Markers are exported three ways: embedded in the WAV file (`cue ` and
+//! `LIST/adtl` chunks, read by most DAWs), as a cue sheet, and as JSON.
+
+use serde::{Deserialize, Serialize};
+
+/// Why a marker was placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkerKind {
+    /// Added by the user
+    Manual,
+    /// Placed automatically when a pad fired
+    Pad,
+}
+
+/// A named position in a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingMarker {
+    /// Position in sample frames from the start of the recording
+    pub position: u64,
+    /// Position in seconds, for readers of the JSON export
+    pub seconds: f64,
+    pub label: String,
+    pub kind: MarkerKind,
+}
+
+impl RecordingMarker {
+    pub fn new(position: u64, sample_rate: u32, label: impl Into<String>, kind: MarkerKind) -> Self {
+        Self {
+            position,
+            seconds: position as f64 / sample_rate.max(1) as f64,
+            label: label.into(),
+            kind,
+        }
+    }
+}
+
+/// Build the `cue ` and `LIST/adtl` chunks to append to a WAV file
+pub fn wav_marker_chunks(markers: &[RecordingMarker]) -> Vec<u8> {
+    if markers.is_empty() {
+        return Vec::new();
+    }
+
+    let mut cue = Vec::new();
+    cue.extend_from_slice(&(markers.len() as u32).to_le_bytes());
+    let mut labels = b"adtl".to_vec();
+    for (i, marker) in markers.iter().enumerate() {
+        let id = i as u32 + 1;
+        // Cue point: id, play order position, "data" chunk, chunk start, block start, sample offset
+        cue.extend_from_slice(&id.to_le_bytes());
+        cue.extend_from_slice(&(marker.position as u32).to_le_bytes());
+        cue.extend_from_slice(b"data");
+        cue.extend_from_slice(&[0; 8]);
+        cue.extend_from_slice(&(marker.position as u32).to_le_bytes());
+
+        let mut text = marker.label.as_bytes().to_vec();
+        text.push(0);
+        let mut label = id.to_le_bytes().to_vec();
+        label.extend_from_slice(&text);
+        push_chunk(&mut labels, b"labl", &label);
+    }
+
+    let mut chunks = Vec::new();
+    push_chunk(&mut chunks, b"cue ", &cue);
+    push_chunk(&mut chunks, b"LIST", &labels);
+    chunks
+}
+
+/// Append a RIFF chunk, padded to an even length
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Render markers as a cue sheet for `wav_file`, one track per marker
+///
+/// Cue sheets count in CD frames (75 per second). A "Start" track is
+/// added when the first marker isn't at the very beginning.
+pub fn cue_sheet(wav_file: &str, markers: &[RecordingMarker], sample_rate: u32) -> String {
+    let mut sheet = format!("FILE \"{}\" WAVE\n", wav_file.replace('"', "'"));
+    let start = RecordingMarker::new(0, sample_rate, "Start", MarkerKind::Manual);
+    let needs_start = !markers.first().is_some_and(|m| m.position == 0);
+
+    let tracks = needs_start.then_some(&start).into_iter().chain(markers);
+    for (i, marker) in tracks.enumerate() {
+        let cd_frames = marker.position * 75 / sample_rate.max(1) as u64;
+        sheet.push_str(&format!("  TRACK {:02} AUDIO\n", i + 1));
+        sheet.push_str(&format!("    TITLE \"{}\"\n", marker.label.replace('"', "'")));
+        sheet.push_str(&format!(
+            "    INDEX 01 {:02}:{:02}:{:02}\n",
+            cd_frames / (75 * 60),
+            cd_frames / 75 % 60,
+            cd_frames % 75
+        ));
+    }
+    sheet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cue_sheet() {
+        let markers = [
+            RecordingMarker::new(48_000 * 61 + 24_000, 48_000, "Intro \"drop\"", MarkerKind::Pad),
+        ];
+        let sheet = cue_sheet("show.wav", &markers, 48_000);
+        assert_eq!(
+            sheet,
+            "FILE \"show.wav\" WAVE\n\
+             \x20 TRACK 01 AUDIO\n    TITLE \"Start\"\n    INDEX 01 00:00:00\n\
+             \x20 TRACK 02 AUDIO\n    TITLE \"Intro 'drop'\"\n    INDEX 01 01:01:37\n"
+        );
+    }
+
+    #[test]
+    fn test_wav_marker_chunks() {
+        let markers = [RecordingMarker::new(1000, 48_000, "ab", MarkerKind::Manual)];
+        let chunks = wav_marker_chunks(&markers);
+
+        assert_eq!(&chunks[..4], b"cue ");
+        assert_eq!(u32::from_le_bytes(chunks[4..8].try_into().unwrap()), 4 + 24);
+        assert_eq!(u32::from_le_bytes(chunks[32..36].try_into().unwrap()), 1000);
+        assert_eq!(&chunks[36..40], b"LIST");
+        // "adtl" + labl header (8) + id (4) + "ab\0" padded to 4
+        assert_eq!(u32::from_le_bytes(chunks[40..44].try_into().unwrap()), 4 + 8 + 4 + 4);
+        assert_eq!(chunks.len() % 2, 0);
+        assert!(wav_marker_chunks(&[]).is_empty());
+    }
+}
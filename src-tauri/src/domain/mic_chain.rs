@@ -0,0 +1,210 @@
+//! Mic chain layout - Order and on/off state of the mic effects
+//!
+//! Each effect appears exactly once. A node that is switched off is skipped
+//! without losing its settings; the settings themselves (cutoff, AGC target,
+//! noise print...) live with each effect.
+
+use serde::{Deserialize, Serialize};
+
+/// Errors that can occur when editing the chain layout
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MicChainError {
+    #[error("Effect index {index} is out of range (chain has {len} effects)")]
+    IndexOutOfRange { index: usize, len: usize },
+
+    #[error("Mix must be between 0 and 1, got {0}")]
+    InvalidMix(f32),
+}
+
+/// An effect that can be placed in the mic chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MicEffectKind {
+    Highpass,
+    EchoCancellation,
+    NoiseReduction,
+    KeyboardSuppression,
+    NoiseGate,
+    Agc,
+    VoiceChanger,
+}
+
+impl MicEffectKind {
+    /// Every effect, in the default order
+    pub const ALL: [MicEffectKind; 7] = [
+        MicEffectKind::Highpass,
+        MicEffectKind::EchoCancellation,
+        MicEffectKind::NoiseReduction,
+        MicEffectKind::KeyboardSuppression,
+        MicEffectKind::NoiseGate,
+        MicEffectKind::Agc,
+        MicEffectKind::VoiceChanger,
+    ];
+}
+
+/// One slot in the mic chain
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MicEffectNode {
+    pub kind: MicEffectKind,
+    pub enabled: bool,
+    /// Blend of processed and dry signal (0 = dry, 1 = fully processed)
+    #[serde(default = "full_mix")]
+    pub mix: f32,
+}
+
+fn full_mix() -> f32 {
+    1.0
+}
+
+impl MicEffectNode {
+    pub fn new(kind: MicEffectKind) -> Self {
+        Self {
+            kind,
+            enabled: true,
+            mix: full_mix(),
+        }
+    }
+}
+
+/// Ordered list of mic effects
+///
+/// Serialized as a plain list of nodes. Missing effects are appended and
+/// duplicates dropped when loading, so older settings stay valid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<MicEffectNode>", into = "Vec<MicEffectNode>")]
+pub struct MicChainLayout(Vec<MicEffectNode>);
+
+impl MicChainLayout {
+    pub fn nodes(&self) -> &[MicEffectNode] {
+        &self.0
+    }
+
+    /// Move the effect at `from` so it ends up at `to`
+    pub fn move_effect(&mut self, from: usize, to: usize) -> Result<(), MicChainError> {
+        self.check_index(from)?;
+        self.check_index(to)?;
+
+        let node = self.0.remove(from);
+        self.0.insert(to, node);
+        Ok(())
+    }
+
+    /// Switch the effect at `index` on or off
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) -> Result<(), MicChainError> {
+        self.check_index(index)?;
+        self.0[index].enabled = enabled;
+        Ok(())
+    }
+
+    /// Set the wet/dry blend of the effect at `index`
+    pub fn set_mix(&mut self, index: usize, mix: f32) -> Result<(), MicChainError> {
+        self.check_index(index)?;
+        if !(0.0..=1.0).contains(&mix) {
+            return Err(MicChainError::InvalidMix(mix));
+        }
+        self.0[index].mix = mix;
+        Ok(())
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), MicChainError> {
+        if index >= self.0.len() {
+            return Err(MicChainError::IndexOutOfRange {
+                index,
+                len: self.0.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for MicChainLayout {
+    fn default() -> Self {
+        Self(
+            MicEffectKind::ALL
+                .into_iter()
+                .map(MicEffectNode::new)
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<MicEffectNode>> for MicChainLayout {
+    fn from(nodes: Vec<MicEffectNode>) -> Self {
+        let mut layout: Vec<MicEffectNode> = Vec::with_capacity(MicEffectKind::ALL.len());
+        for node in nodes {
+            if !layout.iter().any(|n| n.kind == node.kind) {
+                layout.push(node);
+            }
+        }
+        for kind in MicEffectKind::ALL {
+            if !layout.iter().any(|n| n.kind == kind) {
+                layout.push(MicEffectNode::new(kind));
+            }
+        }
+        Self(layout)
+    }
+}
+
+impl From<MicChainLayout> for Vec<MicEffectNode> {
+    fn from(layout: MicChainLayout) -> Self {
+        layout.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(layout: &MicChainLayout) -> Vec<MicEffectKind> {
+        layout.nodes().iter().map(|n| n.kind).collect()
+    }
+
+    #[test]
+    fn test_move_and_toggle() {
+        let mut layout = MicChainLayout::default();
+        layout.move_effect(5, 0).unwrap();
+        assert_eq!(
+            kinds(&layout),
+            vec![
+                MicEffectKind::Agc,
+                MicEffectKind::Highpass,
+                MicEffectKind::EchoCancellation,
+                MicEffectKind::NoiseReduction,
+                MicEffectKind::KeyboardSuppression,
+                MicEffectKind::NoiseGate,
+                MicEffectKind::VoiceChanger,
+            ]
+        );
+
+        layout.set_enabled(1, false).unwrap();
+        assert!(!layout.nodes()[1].enabled);
+
+        layout.set_mix(2, 0.25).unwrap();
+        assert_eq!(layout.nodes()[2].mix, 0.25);
+        assert_eq!(layout.set_mix(2, 1.5), Err(MicChainError::InvalidMix(1.5)));
+
+        assert_eq!(
+            layout.move_effect(0, 7),
+            Err(MicChainError::IndexOutOfRange { index: 7, len: 7 })
+        );
+    }
+
+    #[test]
+    fn test_loading_repairs_the_layout() {
+        let layout: MicChainLayout = serde_json::from_str(
+            r#"[{"kind":"agc","enabled":false},{"kind":"agc","enabled":true}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(layout.nodes().len(), MicEffectKind::ALL.len());
+        assert_eq!(
+            layout.nodes()[0],
+            MicEffectNode {
+                kind: MicEffectKind::Agc,
+                enabled: false,
+                mix: 1.0,
+            }
+        );
+        assert_eq!(layout.nodes()[1].kind, MicEffectKind::Highpass);
+    }
+}
@@ -0,0 +1,90 @@
+//! Mic profiles - Mic settings remembered per input device
+//!
+//! A headset mic and a studio condenser need different levels and
+//! filtering, so the mic's volume, rumble filter, AGC and noise gate are
+//! kept per input device and swapped in when the device is selected. A
+//! device used for the first time starts from the default profile.
+
+use crate::domain::{AgcSettings, HighpassSettings, NoiseGateSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Mic settings of one input device
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MicProfile {
+    /// Mic volume (0.0 - 2.0)
+    pub volume: f32,
+    pub highpass: HighpassSettings,
+    pub agc: AgcSettings,
+    pub noise_gate: NoiseGateSettings,
+}
+
+impl MicProfile {
+    pub const MAX_VOLUME: f32 = 2.0;
+}
+
+impl Default for MicProfile {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            highpass: HighpassSettings::default(),
+            agc: AgcSettings::default(),
+            noise_gate: NoiseGateSettings::default(),
+        }
+    }
+}
+
+/// Mic profiles keyed by input device name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MicProfiles {
+    /// Profile of devices that have none of their own
+    pub default: MicProfile,
+    pub devices: HashMap<String, MicProfile>,
+}
+
+impl MicProfiles {
+    /// The profile of a device, or the default one
+    pub fn for_device(&self, device: &str) -> &MicProfile {
+        self.devices.get(device).unwrap_or(&self.default)
+    }
+
+    /// The profile of a device to change, started from the default one
+    pub fn for_device_mut(&mut self, device: &str) -> &mut MicProfile {
+        self.devices.entry(device.to_string()).or_insert(self.default)
+    }
+
+    /// Whether a device has a profile of its own
+    pub fn has_device(&self, device: &str) -> bool {
+        self.devices.contains_key(device)
+    }
+
+    /// Forget a device's profile, so it falls back to the default one
+    pub fn remove(&mut self, device: &str) -> Option<MicProfile> {
+        self.devices.remove(device)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devices_fall_back_to_the_default_profile() {
+        let mut profiles = MicProfiles::default();
+        profiles.default.noise_gate.enabled = true;
+        assert!(profiles.for_device("USB Mic").noise_gate.enabled);
+        assert!(!profiles.has_device("USB Mic"));
+
+        // A device's own profile starts from the default and then diverges
+        profiles.for_device_mut("USB Mic").volume = 1.5;
+        assert!(profiles.for_device("USB Mic").noise_gate.enabled);
+        profiles.default.volume = 0.5;
+        assert_eq!(profiles.for_device("USB Mic").volume, 1.5);
+        assert_eq!(profiles.for_device("Headset").volume, 0.5);
+
+        profiles.remove("USB Mic");
+        assert_eq!(profiles.for_device("USB Mic").volume, 0.5);
+    }
+}
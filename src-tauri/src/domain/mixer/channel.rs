@@ -1,5 +1,6 @@
 //! Mixer channel entity
 
+use super::GeneratorSettings;
 use serde::{Deserialize, Serialize};
 
 /// Type of mixer channel
@@ -11,6 +12,10 @@ pub enum ChannelType {
     AudioFile,
     /// System audio loopback
     SystemAudio,
+    /// Audio output of a single application
+    Application,
+    /// Test signal generated by the engine, for calibration
+    Generator,
 }
 
 /// Represents a channel in the mixer
@@ -22,6 +27,14 @@ pub struct MixerChannel {
     volume: f32,
     muted: bool,
     solo: bool,
+    /// Signal of a generator channel
+    #[serde(default)]
+    generator: Option<GeneratorSettings>,
+    /// Generation of the momentary (cough) mute holding the channel, if any
+    #[serde(skip)]
+    momentary_mute: Option<u64>,
+    #[serde(skip)]
+    momentary_generation: u64,
 }
 
 impl MixerChannel {
@@ -33,6 +46,9 @@ impl MixerChannel {
             volume: 1.0,
             muted: false,
             solo: false,
+            generator: None,
+            momentary_mute: None,
+            momentary_generation: 0,
         }
     }
 
@@ -48,6 +64,22 @@ impl MixerChannel {
         self.channel_type
     }
 
+    /// A generator channel playing `settings`
+    pub fn generator(id: impl Into<String>, name: impl Into<String>, settings: GeneratorSettings) -> Self {
+        Self {
+            generator: Some(settings),
+            ..Self::new(id, name, ChannelType::Generator)
+        }
+    }
+
+    pub fn generator_settings(&self) -> Option<GeneratorSettings> {
+        self.generator
+    }
+
+    pub fn set_generator_settings(&mut self, settings: GeneratorSettings) {
+        self.generator = Some(settings);
+    }
+
     pub fn volume(&self) -> f32 {
         self.volume
     }
@@ -62,10 +94,42 @@ impl MixerChannel {
 
     pub fn set_muted(&mut self, muted: bool) {
         self.muted = muted;
+        self.momentary_mute = None;
     }
 
     pub fn toggle_mute(&mut self) {
-        self.muted = !self.muted;
+        self.set_muted(!self.muted);
+    }
+
+    /// Whether the mute is only a running momentary mute
+    pub fn is_momentarily_muted(&self) -> bool {
+        self.momentary_mute.is_some()
+    }
+
+    /// Mute the channel until [`Self::end_momentary_mute`] is called with
+    /// the returned generation
+    ///
+    /// A press during a running momentary mute takes it over, extending it.
+    /// Returns `None` if the channel was already muted for good, which a
+    /// cough button must not undo.
+    pub fn start_momentary_mute(&mut self) -> Option<u64> {
+        if self.muted && self.momentary_mute.is_none() {
+            return None;
+        }
+        self.momentary_generation += 1;
+        self.muted = true;
+        self.momentary_mute = Some(self.momentary_generation);
+        self.momentary_mute
+    }
+
+    /// Unmute after the momentary mute `generation`; false if a later press
+    /// or a manual mute change took over
+    pub fn end_momentary_mute(&mut self, generation: u64) -> bool {
+        if self.momentary_mute != Some(generation) {
+            return false;
+        }
+        self.set_muted(false);
+        true
     }
 
     pub fn is_solo(&self) -> bool {
@@ -123,6 +187,25 @@ mod tests {
         assert_eq!(channel.effective_volume(), 0.0);
     }
 
+    #[test]
+    fn test_momentary_mute() {
+        let mut channel = MixerChannel::new("mic", "Mic", ChannelType::Microphone);
+
+        let first = channel.start_momentary_mute().unwrap();
+        let second = channel.start_momentary_mute().unwrap();
+        assert!(channel.is_muted());
+        // The first press ending does not cut the extended mute short
+        assert!(!channel.end_momentary_mute(first));
+        assert!(channel.is_muted());
+        assert!(channel.end_momentary_mute(second));
+        assert!(!channel.is_muted());
+
+        // A channel muted by hand stays muted
+        channel.set_muted(true);
+        assert_eq!(channel.start_momentary_mute(), None);
+        assert!(channel.is_muted());
+    }
+
     #[test]
     fn test_toggle_mute() {
         let mut channel = MixerChannel::new("ch1", "Test", ChannelType::Microphone);
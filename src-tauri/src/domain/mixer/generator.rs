@@ -0,0 +1,44 @@
+//! Generator channels - Known test signals for calibrating downstream apps
+
+use serde::{Deserialize, Serialize};
+
+/// Test signal of a generator channel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorSignal {
+    /// 1 kHz sine, the usual line-up tone
+    #[default]
+    Reference1k,
+    /// Logarithmic sine sweep from 20 Hz to 20 kHz, repeating
+    SineSweep,
+    /// Noise with equal energy per octave, close to the spectrum of speech
+    /// and music
+    PinkNoise,
+}
+
+/// Signal and level of a generator channel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorSettings {
+    pub signal: GeneratorSignal,
+    /// Level in dBFS: the peak of the sines, the RMS of the noise
+    pub level_dbfs: f32,
+}
+
+impl GeneratorSettings {
+    pub const LEVELS_DBFS: std::ops::RangeInclusive<f32> = -60.0..=0.0;
+
+    /// Linear amplitude of the level
+    pub fn amplitude(&self) -> f32 {
+        10f32.powf(self.level_dbfs.clamp(*Self::LEVELS_DBFS.start(), *Self::LEVELS_DBFS.end()) / 20.0)
+    }
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            signal: GeneratorSignal::default(),
+            // The common digital reference level, leaving headroom
+            level_dbfs: -18.0,
+        }
+    }
+}
@@ -1,8 +1,9 @@
 //! Mixer configuration
 
-use super::MixerChannel;
+use super::{ChannelType, MixerChannel, MuteGroup};
 use crate::domain::audio::AudioFormat;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// Configuration for the audio mixer
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +16,9 @@ pub struct MixerConfig {
     pub master_volume: f32,
     /// Channels in the mixer
     pub channels: Vec<MixerChannel>,
+    /// Channel groups muted together
+    #[serde(default)]
+    pub mute_groups: Vec<MuteGroup>,
 }
 
 impl MixerConfig {
@@ -24,6 +28,7 @@ impl MixerConfig {
             buffer_size,
             master_volume: 1.0,
             channels: Vec::new(),
+            mute_groups: Vec::new(),
         }
     }
 
@@ -44,6 +49,16 @@ impl MixerConfig {
         }
     }
 
+    /// Channel of the mic input
+    ///
+    /// The engine has a single mic input, so only one channel can drive it;
+    /// further mic channels are refused.
+    pub fn mic_channel(&self) -> Option<&MixerChannel> {
+        self.channels
+            .iter()
+            .find(|c| c.channel_type() == ChannelType::Microphone)
+    }
+
     pub fn get_channel(&self, channel_id: &str) -> Option<&MixerChannel> {
         self.channels.iter().find(|c| c.id() == channel_id)
     }
@@ -51,6 +66,37 @@ impl MixerConfig {
     pub fn get_channel_mut(&mut self, channel_id: &str) -> Option<&mut MixerChannel> {
         self.channels.iter_mut().find(|c| c.id() == channel_id)
     }
+
+    /// Mute or unmute a group, every group linked from it (transitively)
+    /// and all their channels
+    ///
+    /// Returns the ids of the channels whose mute changed, or `None` if the
+    /// group does not exist.
+    pub fn set_group_muted(&mut self, group_id: &str, muted: bool) -> Option<Vec<String>> {
+        self.mute_groups.iter().find(|g| g.id == group_id)?;
+
+        let mut visited = HashSet::new();
+        let mut queue = vec![group_id.to_string()];
+        let mut channel_ids = HashSet::new();
+        while let Some(id) = queue.pop() {
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+            if let Some(group) = self.mute_groups.iter().find(|g| g.id == id) {
+                channel_ids.extend(group.channel_ids.iter().cloned());
+                queue.extend(group.linked_groups.iter().cloned());
+            }
+        }
+
+        let mut changed = Vec::new();
+        for channel in &mut self.channels {
+            if channel_ids.contains(channel.id()) && channel.is_muted() != muted {
+                channel.set_muted(muted);
+                changed.push(channel.id().to_string());
+            }
+        }
+        Some(changed)
+    }
 }
 
 impl Default for MixerConfig {
@@ -62,7 +108,6 @@ impl Default for MixerConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::mixer::ChannelType;
 
     #[test]
     fn test_mixer_config_creation() {
@@ -85,6 +130,37 @@ mod tests {
         assert!(config.channels.is_empty());
     }
 
+    #[test]
+    fn test_mic_channel_is_the_microphone() {
+        let mut config = MixerConfig::default();
+        config.add_channel(MixerChannel::new("music", "Music", ChannelType::AudioFile));
+        assert!(config.mic_channel().is_none());
+
+        config.add_channel(MixerChannel::new("mic1", "Microphone", ChannelType::Microphone));
+        assert_eq!(config.mic_channel().map(|c| c.id()), Some("mic1"));
+    }
+
+    #[test]
+    fn test_linked_mute_groups() {
+        let mut config = MixerConfig::default();
+        for id in ["mic", "music", "game"] {
+            config.add_channel(MixerChannel::new(id, id, ChannelType::AudioFile));
+        }
+        config.mute_groups = vec![
+            MuteGroup::new("a", "Music", vec!["music".into()]).with_linked_groups(vec!["b".into()]),
+            // Linked back to A: the cycle must not loop
+            MuteGroup::new("b", "Game", vec!["game".into()]).with_linked_groups(vec!["a".into()]),
+        ];
+
+        let mut changed = config.set_group_muted("a", true).unwrap();
+        changed.sort();
+        assert_eq!(changed, vec!["game", "music"]);
+        assert!(!config.get_channel("mic").unwrap().is_muted());
+
+        assert_eq!(config.set_group_muted("a", true), Some(Vec::new()));
+        assert_eq!(config.set_group_muted("missing", true), None);
+    }
+
     #[test]
     fn test_master_volume_clamping() {
         let config = MixerConfig::default().with_master_volume(1.5);
@@ -2,6 +2,10 @@
 
 mod mixer_config;
 mod channel;
+mod generator;
+mod mute_group;
 
 pub use mixer_config::*;
 pub use channel::*;
+pub use generator::*;
+pub use mute_group::*;
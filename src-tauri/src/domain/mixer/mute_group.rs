@@ -0,0 +1,30 @@
+//! Mute groups - Channels muted together
+
+use serde::{Deserialize, Serialize};
+
+/// Channels muted and unmuted as one, e.g. "all music"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuteGroup {
+    pub id: String,
+    pub name: String,
+    pub channel_ids: Vec<String>,
+    /// Groups that follow this one: muting A mutes every group linked from A
+    #[serde(default)]
+    pub linked_groups: Vec<String>,
+}
+
+impl MuteGroup {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, channel_ids: Vec<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            channel_ids,
+            linked_groups: Vec::new(),
+        }
+    }
+
+    pub fn with_linked_groups(mut self, linked_groups: Vec<String>) -> Self {
+        self.linked_groups = linked_groups;
+        self
+    }
+}
@@ -3,12 +3,52 @@
 //! This layer contains the pure business logic and domain entities.
 //! It has no dependencies on external frameworks or infrastructure.
 
+pub mod attribution;
 pub mod audio;
 pub mod device;
+pub mod hotkey;
+pub mod integration;
+pub mod library_stats;
+pub mod marker;
+pub mod mic_chain;
+pub mod mic_profile;
 pub mod mixer;
+pub mod onboarding;
+pub mod pad_variant;
+pub mod play_log;
+pub mod playback_speed;
+pub mod scene;
+pub mod session_stats;
 pub mod settings;
+pub mod sound_cleanup;
+pub mod sound_edits;
+pub mod sound_insert;
+pub mod sync;
+pub mod timer;
+pub mod ui_state;
+pub mod voice_preset;
 
+pub use attribution::*;
 pub use audio::*;
 pub use device::*;
+pub use hotkey::*;
+pub use integration::*;
+pub use library_stats::*;
+pub use marker::*;
+pub use mic_chain::*;
+pub use mic_profile::*;
 pub use mixer::*;
+pub use onboarding::*;
+pub use pad_variant::*;
+pub use play_log::*;
+pub use playback_speed::*;
+pub use scene::*;
+pub use session_stats::*;
 pub use settings::*;
+pub use sound_cleanup::*;
+pub use sound_edits::*;
+pub use sound_insert::*;
+pub use sync::*;
+pub use timer::*;
+pub use ui_state::*;
+pub use voice_preset::*;
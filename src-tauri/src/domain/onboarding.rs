@@ -0,0 +1,122 @@
+//! First-run onboarding progress
+
+use serde::{Deserialize, Serialize};
+
+/// A setup step of the first-run wizard, in the order they are presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OnboardingStep {
+    /// A virtual audio driver is installed
+    VirtualDriverInstalled,
+    /// A microphone has been selected
+    InputSelected,
+    /// A virtual output device has been selected
+    OutputSelected,
+    /// Mixing was started successfully at least once
+    TestPassed,
+}
+
+impl OnboardingStep {
+    /// All steps in wizard order
+    pub const ALL: [OnboardingStep; 4] = [
+        OnboardingStep::VirtualDriverInstalled,
+        OnboardingStep::InputSelected,
+        OnboardingStep::OutputSelected,
+        OnboardingStep::TestPassed,
+    ];
+}
+
+/// Progress through the onboarding steps
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OnboardingState {
+    completed: Vec<OnboardingStep>,
+}
+
+impl OnboardingState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark a step as completed
+    ///
+    /// Returns true if the step was not completed before.
+    pub fn complete(&mut self, step: OnboardingStep) -> bool {
+        if self.is_completed(step) {
+            return false;
+        }
+        self.completed.push(step);
+        true
+    }
+
+    pub fn is_completed(&self, step: OnboardingStep) -> bool {
+        self.completed.contains(&step)
+    }
+
+    /// Completed steps, in completion order
+    pub fn completed(&self) -> &[OnboardingStep] {
+        &self.completed
+    }
+
+    /// The first step that still needs to be completed
+    pub fn current_step(&self) -> Option<OnboardingStep> {
+        OnboardingStep::ALL
+            .iter()
+            .copied()
+            .find(|step| !self.is_completed(*step))
+    }
+
+    /// Check if every step is completed
+    pub fn is_finished(&self) -> bool {
+        self.current_step().is_none()
+    }
+
+    /// Snapshot of the progress, as reported to the frontend
+    pub fn progress(&self) -> OnboardingProgress {
+        OnboardingProgress {
+            completed: self.completed.clone(),
+            current_step: self.current_step(),
+            finished: self.is_finished(),
+        }
+    }
+}
+
+/// Completed steps and the step the wizard is on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OnboardingProgress {
+    pub completed: Vec<OnboardingStep>,
+    pub current_step: Option<OnboardingStep>,
+    pub finished: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_step_follows_wizard_order() {
+        let mut state = OnboardingState::new();
+        assert_eq!(state.current_step(), Some(OnboardingStep::VirtualDriverInstalled));
+
+        state.complete(OnboardingStep::InputSelected);
+        assert_eq!(state.current_step(), Some(OnboardingStep::VirtualDriverInstalled));
+
+        state.complete(OnboardingStep::VirtualDriverInstalled);
+        assert_eq!(state.current_step(), Some(OnboardingStep::OutputSelected));
+    }
+
+    #[test]
+    fn test_complete_is_idempotent() {
+        let mut state = OnboardingState::new();
+        assert!(state.complete(OnboardingStep::TestPassed));
+        assert!(!state.complete(OnboardingStep::TestPassed));
+        assert_eq!(state.completed().len(), 1);
+    }
+
+    #[test]
+    fn test_finished() {
+        let mut state = OnboardingState::new();
+        for step in OnboardingStep::ALL {
+            state.complete(step);
+        }
+        assert!(state.is_finished());
+    }
+}
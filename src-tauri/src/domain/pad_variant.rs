@@ -0,0 +1,150 @@
+//! Pad variants - Several files under one pad, picked on each trigger
+//!
+//! Round-robin uses smooth weighted round-robin, so a variant with weight 2
+//! plays twice as often as one with weight 1, spread evenly. Random picks
+//! are weighted too and never repeat the previous variant.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How a pad chooses among its variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantMode {
+    #[default]
+    RoundRobin,
+    Random,
+}
+
+/// Remembers per-pad rotation state between triggers
+#[derive(Debug, Default)]
+pub struct VariantPicker {
+    /// Smooth weighted round-robin counters per pad
+    current: HashMap<String, Vec<f32>>,
+    last: HashMap<String, usize>,
+}
+
+impl VariantPicker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pick the variant to play for a pad
+    ///
+    /// `weights` has one entry per variant; zero-weight variants never play
+    /// unless all weights are zero. `random` is a uniform value in `[0, 1)`,
+    /// only used in random mode.
+    pub fn pick(&mut self, pad_id: &str, weights: &[f32], mode: VariantMode, random: f64) -> Option<usize> {
+        if weights.is_empty() {
+            return None;
+        }
+
+        let weights: Vec<f32> = if weights.iter().all(|&w| w <= 0.0) {
+            vec![1.0; weights.len()]
+        } else {
+            weights.iter().map(|&w| w.max(0.0)).collect()
+        };
+
+        let index = match mode {
+            VariantMode::RoundRobin => self.next_round_robin(pad_id, &weights),
+            VariantMode::Random => {
+                let last = self.last.get(pad_id).copied();
+                weighted_random(&weights, last, random)
+            }
+        };
+        self.last.insert(pad_id.to_string(), index);
+        Some(index)
+    }
+
+    /// Forget the rotation of a pad, e.g. after its variants changed
+    pub fn reset(&mut self, pad_id: &str) {
+        self.current.remove(pad_id);
+        self.last.remove(pad_id);
+    }
+
+    fn next_round_robin(&mut self, pad_id: &str, weights: &[f32]) -> usize {
+        let current = self.current.entry(pad_id.to_string()).or_default();
+        if current.len() != weights.len() {
+            *current = vec![0.0; weights.len()];
+        }
+
+        let total: f32 = weights.iter().sum();
+        for (value, &weight) in current.iter_mut().zip(weights) {
+            *value += weight;
+        }
+
+        // First variant wins ties, so equal weights cycle in order
+        let mut best = 0;
+        for (i, &value) in current.iter().enumerate() {
+            if value > current[best] {
+                best = i;
+            }
+        }
+        current[best] -= total;
+        best
+    }
+}
+
+/// Weighted random pick that avoids `exclude` when anything else can play
+fn weighted_random(weights: &[f32], exclude: Option<usize>, random: f64) -> usize {
+    let allowed = |i: usize| Some(i) != exclude && weights[i] > 0.0;
+    let total: f64 = (0..weights.len()).filter(|&i| allowed(i)).map(|i| weights[i] as f64).sum();
+    if total <= 0.0 {
+        return exclude.unwrap_or(0);
+    }
+
+    let mut target = random.clamp(0.0, 1.0) * total;
+    let mut chosen = 0;
+    for i in (0..weights.len()).filter(|&i| allowed(i)) {
+        chosen = i;
+        target -= weights[i] as f64;
+        if target < 0.0 {
+            break;
+        }
+    }
+    chosen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_robin_follows_weights() {
+        let mut picker = VariantPicker::new();
+        let picks: Vec<usize> = (0..6)
+            .map(|_| picker.pick("pad", &[1.0, 1.0, 1.0], VariantMode::RoundRobin, 0.0).unwrap())
+            .collect();
+        assert_eq!(picks, vec![0, 1, 2, 0, 1, 2]);
+
+        let picks: Vec<usize> = (0..3)
+            .map(|_| picker.pick("weighted", &[2.0, 1.0], VariantMode::RoundRobin, 0.0).unwrap())
+            .collect();
+        assert_eq!(picks, vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn test_random_never_repeats() {
+        let mut picker = VariantPicker::new();
+        let first = picker.pick("pad", &[1.0, 1.0], VariantMode::Random, 0.1).unwrap();
+        for random in [0.0, 0.3, 0.6, 0.99] {
+            let next = picker.pick("pad", &[1.0, 1.0], VariantMode::Random, random).unwrap();
+            assert_ne!(next, first);
+            picker.pick("pad", &[1.0, 1.0], VariantMode::Random, random);
+        }
+
+        // A single variant always plays
+        assert_eq!(picker.pick("solo", &[1.0], VariantMode::Random, 0.5), Some(0));
+        assert_eq!(picker.pick("solo", &[1.0], VariantMode::Random, 0.5), Some(0));
+    }
+
+    #[test]
+    fn test_zero_weights() {
+        let mut picker = VariantPicker::new();
+        for _ in 0..4 {
+            assert_eq!(picker.pick("pad", &[0.0, 3.0], VariantMode::RoundRobin, 0.0), Some(1));
+        }
+        assert_eq!(picker.pick("all-zero", &[0.0, 0.0], VariantMode::RoundRobin, 0.0), Some(0));
+        assert_eq!(picker.pick("empty", &[], VariantMode::Random, 0.0), None);
+    }
+}
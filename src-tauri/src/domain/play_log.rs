@@ -0,0 +1,153 @@
+//! Play log - Timeline of the sounds triggered during a session
+//!
+//! Podcast editors line the log up with their recording to find where
+//! drops landed, so every entry carries its offset from the session start.
+
+use serde::{Deserialize, Serialize};
+
+/// What triggered a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaySource {
+    #[default]
+    Ui,
+    Hotkey,
+    /// A co-host or another app through a remote API
+    Remote,
+    Twitch,
+}
+
+impl PlaySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ui => "ui",
+            Self::Hotkey => "hotkey",
+            Self::Remote => "remote",
+            Self::Twitch => "twitch",
+        }
+    }
+}
+
+/// One triggered sound
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayLogEntry {
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Milliseconds since the session started
+    pub offset_ms: u64,
+    pub sound_id: String,
+    pub name: String,
+    pub path: String,
+    /// Length of the sound in seconds
+    pub duration: f64,
+    pub source: PlaySource,
+}
+
+/// CSV header matching [`play_log_csv`] rows
+const CSV_HEADER: &str = "timestamp,offset,offset_ms,name,duration_s,source,sound_id,path";
+
+/// Render entries as CSV, one row per play
+///
+/// `offset` is `HH:MM:SS.mmm` from the session start, the format
+/// editing software expects for markers.
+pub fn play_log_csv(entries: &[PlayLogEntry]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for entry in entries {
+        let fields = [
+            format_utc_timestamp(entry.timestamp),
+            format_offset(entry.offset_ms),
+            entry.offset_ms.to_string(),
+            csv_field(&entry.name),
+            format!("{:.3}", entry.duration),
+            entry.source.as_str().to_string(),
+            csv_field(&entry.sound_id),
+            csv_field(&entry.path),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format a session offset as `HH:MM:SS.mmm`
+pub fn format_offset(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Format Unix milliseconds as ISO 8601 UTC, e.g. `2024-02-29T12:34:56.789Z`
+pub fn format_utc_timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_export() {
+        let entries = [PlayLogEntry {
+            timestamp: 1_709_210_096_789,
+            offset_ms: 3_723_004,
+            sound_id: "sound_1".into(),
+            name: "Horn, \"loud\"".into(),
+            path: "C:\\sounds\\horn.wav".into(),
+            duration: 1.5,
+            source: PlaySource::Hotkey,
+        }];
+
+        let csv = play_log_csv(&entries);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        assert_eq!(
+            lines.next(),
+            Some("2024-02-29T12:34:56.789Z,01:02:03.004,3723004,\"Horn, \"\"loud\"\"\",1.500,hotkey,sound_1,C:\\sounds\\horn.wav")
+        );
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_format_utc_timestamp() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(format_utc_timestamp(951_782_400_000), "2000-02-29T00:00:00.000Z");
+    }
+}
@@ -0,0 +1,44 @@
+//! Playback speed - Per-sound speed, with or without a pitch change
+
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Playback rates a sound can be set to
+pub const PLAYBACK_RATES: RangeInclusive<f32> = 0.5..=2.0;
+
+/// How a sound is sped up or slowed down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeedMode {
+    /// Resampled like a tape: faster is higher pitched
+    #[default]
+    Resample,
+    /// Phase-vocoder time-stretch: the pitch stays the same
+    TimeStretch,
+}
+
+/// Playback speed of a sound
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlaybackSpeed {
+    /// Speed factor, 2.0 plays twice as fast
+    pub rate: f32,
+    #[serde(default)]
+    pub mode: SpeedMode,
+}
+
+impl PlaybackSpeed {
+    pub fn new(rate: f32, mode: SpeedMode) -> Self {
+        Self { rate, mode }
+    }
+
+    /// Whether the sound plays as recorded
+    pub fn is_normal(&self) -> bool {
+        (self.rate - 1.0).abs() < f32::EPSILON
+    }
+}
+
+impl Default for PlaybackSpeed {
+    fn default() -> Self {
+        Self::new(1.0, SpeedMode::Resample)
+    }
+}
@@ -0,0 +1,157 @@
+//! Scenes - Snapshots of the soundboard's audio state to jump between
+//!
+//! A scene holds which pads are armed and at what volume, the mixer
+//! channel levels and the mic's voice changer. Unlike a hotkey profile it
+//! changes no bindings: recalling "Intro", "Gameplay" or "Outro" mid-stream
+//! crossfades the volumes and switches the rest halfway through.
+
+use crate::domain::VoiceChangerSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// State of a pad in a scene
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScenePad {
+    /// Disarmed pads ignore triggers while the scene is active
+    pub armed: bool,
+    /// Gain on the pad's sounds (0.0 - 2.0), on top of their own volume
+    pub volume: f32,
+}
+
+impl Default for ScenePad {
+    fn default() -> Self {
+        Self { armed: true, volume: 1.0 }
+    }
+}
+
+/// Level of a mixer channel in a scene
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SceneChannel {
+    pub volume: f32,
+    pub muted: bool,
+}
+
+/// A saved scene
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub id: String,
+    pub name: String,
+    /// Keyed by pad id; pads not listed keep their state
+    #[serde(default)]
+    pub pads: HashMap<String, ScenePad>,
+    /// Keyed by channel id; channels not listed keep their level
+    #[serde(default)]
+    pub channels: HashMap<String, SceneChannel>,
+    /// Voice changer of the mic, None to leave it
+    #[serde(default)]
+    pub voice_changer: Option<VoiceChangerSettings>,
+}
+
+impl Scene {
+    /// Check that every volume is in range
+    pub fn is_valid(&self) -> bool {
+        let volume_ok = |volume: f32| (0.0..=2.0).contains(&volume);
+        self.pads.values().all(|pad| volume_ok(pad.volume))
+            && self.channels.values().all(|channel| volume_ok(channel.volume))
+            && self.voice_changer.iter().all(|voice| voice.is_valid())
+    }
+}
+
+/// The scene last recalled, with the pads it disarmed
+#[derive(Debug, Default)]
+pub struct ActiveScene {
+    pub scene_id: Option<String>,
+    /// Pads that ignore triggers, by id
+    pub disarmed: HashSet<String>,
+    /// Bumped on each recall, so a crossfade still running stops
+    pub generation: u64,
+}
+
+impl ActiveScene {
+    /// Make `scene` the active one, arming or disarming the pads it lists;
+    /// the new generation
+    pub fn recall(&mut self, scene: &Scene) -> u64 {
+        self.scene_id = Some(scene.id.clone());
+        for (pad_id, pad) in &scene.pads {
+            if pad.armed {
+                self.disarmed.remove(pad_id);
+            } else {
+                self.disarmed.insert(pad_id.clone());
+            }
+        }
+        self.generation += 1;
+        self.generation
+    }
+
+    pub fn is_armed(&self, pad_id: &str) -> bool {
+        !self.disarmed.contains(pad_id)
+    }
+}
+
+/// Volumes `t` (0.0 - 1.0) of the way from `from` to `to`, for the keys
+/// of `to`; keys missing from `from` start at unity
+pub fn crossfade_volumes(from: &HashMap<String, f32>, to: &HashMap<String, f32>, t: f32) -> Vec<(String, f32)> {
+    let t = t.clamp(0.0, 1.0);
+    let mut volumes: Vec<(String, f32)> = to
+        .iter()
+        .map(|(key, &target)| {
+            let start = from.get(key).copied().unwrap_or(1.0);
+            (key.clone(), start + (target - start) * t)
+        })
+        .collect();
+    volumes.sort_by(|a, b| a.0.cmp(&b.0));
+    volumes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossfade_volumes() {
+        let from = HashMap::from([("music".to_string(), 0.2)]);
+        let to = HashMap::from([("music".to_string(), 1.0), ("game".to_string(), 0.5)]);
+
+        let start = crossfade_volumes(&from, &to, 0.0);
+        assert_eq!(start, vec![("game".to_string(), 1.0), ("music".to_string(), 0.2)]);
+        let middle = crossfade_volumes(&from, &to, 0.5);
+        assert!((middle[0].1 - 0.75).abs() < 1e-6);
+        assert!((middle[1].1 - 0.6).abs() < 1e-6);
+        assert_eq!(crossfade_volumes(&from, &to, 2.0), vec![("game".to_string(), 0.5), ("music".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn test_recall_keeps_unlisted_pads() {
+        let mut active = ActiveScene::default();
+        let scene = |pads: &[(&str, bool)]| Scene {
+            id: "intro".into(),
+            name: "Intro".into(),
+            pads: pads.iter().map(|&(id, armed)| (id.to_string(), ScenePad { armed, volume: 1.0 })).collect(),
+            channels: HashMap::new(),
+            voice_changer: None,
+        };
+
+        assert_eq!(active.recall(&scene(&[("pad-0", false), ("pad-1", false)])), 1);
+        assert!(!active.is_armed("pad-0") && !active.is_armed("pad-1"));
+
+        assert_eq!(active.recall(&scene(&[("pad-0", true)])), 2);
+        assert!(active.is_armed("pad-0"));
+        assert!(!active.is_armed("pad-1"));
+        assert!(active.is_armed("pad-2"));
+    }
+
+    #[test]
+    fn test_scene_validation() {
+        let mut scene = Scene {
+            id: "intro".into(),
+            name: "Intro".into(),
+            pads: HashMap::from([("pad-0".to_string(), ScenePad::default())]),
+            channels: HashMap::new(),
+            voice_changer: None,
+        };
+        assert!(scene.is_valid());
+
+        scene.pads.insert("pad-1".into(), ScenePad { armed: false, volume: 3.0 });
+        assert!(!scene.is_valid());
+    }
+}
@@ -0,0 +1,170 @@
+//! Session statistics - Post-show report of a mixing session
+//!
+//! Levels and underruns are accumulated while the engine mixes; pad
+//! triggers come from the play log. The summary is taken when mixing
+//! stops.
+
+use crate::domain::PlayLogEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Sounds listed in a summary, most played first
+pub const TOP_SOUNDS: usize = 5;
+
+/// Level reported for silence, in dBFS
+const SILENCE_DB: f32 = -100.0;
+
+/// How often a sound was played in the session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoundPlays {
+    pub sound_id: String,
+    pub name: String,
+    pub plays: u32,
+}
+
+/// Report of a finished mixing session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// Milliseconds since the Unix epoch
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub duration_secs: f64,
+    /// Loudest output peak, in dBFS
+    pub peak_db: f32,
+    /// Output RMS averaged over the session, in dBFS
+    pub average_db: f32,
+    /// Sounds triggered, from any source
+    pub triggers: u32,
+    /// Output buffers that ran dry
+    pub underruns: u32,
+    pub top_sounds: Vec<SoundPlays>,
+}
+
+fn to_db(level: f32) -> f32 {
+    if level > 0.0 {
+        (20.0 * level.log10()).max(SILENCE_DB)
+    } else {
+        SILENCE_DB
+    }
+}
+
+/// Statistics of the running session
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    started_at: u64,
+    peak: f32,
+    sum_squares: f64,
+    levels: u64,
+    underruns: u32,
+}
+
+impl SessionStats {
+    pub fn new(started_at: u64) -> Self {
+        Self {
+            started_at,
+            peak: 0.0,
+            sum_squares: 0.0,
+            levels: 0,
+            underruns: 0,
+        }
+    }
+
+    /// Add an output level report (linear RMS and peak)
+    pub fn record_level(&mut self, rms: f32, peak: f32) {
+        self.peak = self.peak.max(peak);
+        self.sum_squares += (rms as f64).powi(2);
+        self.levels += 1;
+    }
+
+    pub fn record_underruns(&mut self, count: u32) {
+        self.underruns = self.underruns.saturating_add(count);
+    }
+
+    /// Summary of the session ending at `ended_at`
+    ///
+    /// `entries` may span more than the session; only plays between its
+    /// start and end count.
+    pub fn summary(&self, ended_at: u64, entries: &[PlayLogEntry]) -> SessionSummary {
+        let plays: Vec<&PlayLogEntry> = entries
+            .iter()
+            .filter(|entry| (self.started_at..=ended_at).contains(&entry.timestamp))
+            .collect();
+
+        let mut counts: HashMap<&str, SoundPlays> = HashMap::new();
+        for entry in &plays {
+            counts
+                .entry(entry.sound_id.as_str())
+                .or_insert_with(|| SoundPlays {
+                    sound_id: entry.sound_id.clone(),
+                    name: entry.name.clone(),
+                    plays: 0,
+                })
+                .plays += 1;
+        }
+        let mut top_sounds: Vec<SoundPlays> = counts.into_values().collect();
+        top_sounds.sort_by(|a, b| b.plays.cmp(&a.plays).then_with(|| a.name.cmp(&b.name)));
+        top_sounds.truncate(TOP_SOUNDS);
+
+        let mean_square = if self.levels > 0 { self.sum_squares / self.levels as f64 } else { 0.0 };
+        SessionSummary {
+            started_at: self.started_at,
+            ended_at,
+            duration_secs: ended_at.saturating_sub(self.started_at) as f64 / 1000.0,
+            peak_db: to_db(self.peak),
+            average_db: to_db(mean_square.sqrt() as f32),
+            triggers: plays.len() as u32,
+            underruns: self.underruns,
+            top_sounds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::PlaySource;
+
+    fn play(timestamp: u64, sound_id: &str) -> PlayLogEntry {
+        PlayLogEntry {
+            timestamp,
+            offset_ms: 0,
+            sound_id: sound_id.into(),
+            name: sound_id.to_uppercase(),
+            path: format!("{}.wav", sound_id),
+            duration: 1.0,
+            source: PlaySource::Ui,
+        }
+    }
+
+    #[test]
+    fn test_summary_of_a_session() {
+        let mut stats = SessionStats::new(10_000);
+        stats.record_level(0.1, 0.5);
+        stats.record_level(0.1, 1.0);
+        stats.record_underruns(2);
+        stats.record_underruns(1);
+
+        let mut entries = vec![play(5_000, "before")];
+        for (i, id) in ["a", "b", "a", "c", "d", "e", "f", "a", "b"].iter().enumerate() {
+            entries.push(play(11_000 + i as u64, id));
+        }
+        let summary = stats.summary(70_000, &entries);
+
+        assert_eq!(summary.duration_secs, 60.0);
+        assert!((summary.peak_db - 0.0).abs() < 1e-4);
+        assert!((summary.average_db + 20.0).abs() < 1e-3);
+        assert_eq!(summary.triggers, 9);
+        assert_eq!(summary.underruns, 3);
+
+        let top: Vec<(&str, u32)> = summary.top_sounds.iter().map(|s| (s.sound_id.as_str(), s.plays)).collect();
+        assert_eq!(top, vec![("a", 3), ("b", 2), ("c", 1), ("d", 1), ("e", 1)]);
+    }
+
+    #[test]
+    fn test_silent_session() {
+        let summary = SessionStats::new(0).summary(1_000, &[]);
+        assert_eq!(summary.peak_db, SILENCE_DB);
+        assert_eq!(summary.average_db, SILENCE_DB);
+        assert!(summary.top_sounds.is_empty());
+    }
+}
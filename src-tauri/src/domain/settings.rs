@@ -1,6 +1,10 @@
 //! Application settings and preferences
 
+use crate::domain::{
+    normalize_device_name, same_device_name, DeviceType, IntegrationPermissions, MicChainLayout, MicProfiles, NoiseProfile, VoiceChangerSettings,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// User preferences for audio devices
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -17,9 +21,15 @@ pub struct AudioSettings {
     pub sample_rate: u32,
     /// Buffer size in frames
     pub buffer_size: u32,
+    /// Mic ducking while soundboard clips play
+    #[serde(default)]
+    pub ducking: DuckingSettings,
 }
 
 impl AudioSettings {
+    /// Device name standing for the system's default device
+    pub const DEFAULT_DEVICE: &'static str = "default";
+
     pub fn new() -> Self {
         Self {
             input_device_id: None,
@@ -28,6 +38,835 @@ impl AudioSettings {
             master_volume: 1.0,
             sample_rate: 48000,
             buffer_size: 1024,
+            ducking: DuckingSettings::default(),
+        }
+    }
+
+    /// Selected input device, keying its mic profile and channel map
+    pub fn input_device(&self) -> &str {
+        self.input_device_id.as_deref().unwrap_or(Self::DEFAULT_DEVICE)
+    }
+}
+
+/// Lowers the mic while soundboard clips play, so they aren't talked over
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DuckingSettings {
+    pub enabled: bool,
+    /// Cut of the mic while a clip plays, in dB
+    pub depth_db: f32,
+    /// Time to duck once a clip starts, in milliseconds
+    pub attack_ms: u32,
+    /// Time to come back once the last clip ends, in milliseconds
+    pub release_ms: u32,
+}
+
+impl DuckingSettings {
+    pub const DEPTHS_DB: std::ops::RangeInclusive<f32> = -40.0..=0.0;
+    pub const ATTACKS_MS: std::ops::RangeInclusive<u32> = 1..=1000;
+    pub const RELEASES_MS: std::ops::RangeInclusive<u32> = 10..=5000;
+
+    pub fn is_valid(&self) -> bool {
+        Self::DEPTHS_DB.contains(&self.depth_db)
+            && Self::ATTACKS_MS.contains(&self.attack_ms)
+            && Self::RELEASES_MS.contains(&self.release_ms)
+    }
+}
+
+impl Default for DuckingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            depth_db: -12.0,
+            attack_ms: 50,
+            release_ms: 500,
+        }
+    }
+}
+
+/// Release channel used by the updater
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+/// Hardware used as an "on air" tally light
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TallyDevice {
+    /// USB/serial relay board (LCUS-1 style command protocol)
+    SerialRelay { port: String, baud_rate: u32 },
+    /// Luxafor-compatible HID busylight
+    Busylight,
+}
+
+/// Tally light configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct TallySettings {
+    /// Light the tally while mixing with the mic unmuted
+    pub enabled: bool,
+    /// Device to drive; nothing is driven when unset
+    pub device: Option<TallyDevice>,
+}
+
+/// Spectral noise reduction tuned to learned noise prints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct NoiseReductionSettings {
+    /// Denoise the mic when a print exists for the input device
+    pub enabled: bool,
+    /// Learned noise prints, keyed by input device name
+    pub profiles: HashMap<String, NoiseProfile>,
+}
+
+impl NoiseReductionSettings {
+    /// The print to apply for an input device, if enabled
+    pub fn active_profile(&self, device: &str) -> Option<&NoiseProfile> {
+        if !self.enabled {
+            return None;
+        }
+        self.profiles.get(device)
+    }
+}
+
+/// Automatic gain control for the mic
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AgcSettings {
+    pub enabled: bool,
+    /// Level the mic is steered towards, in dBFS RMS
+    pub target_db: f32,
+    /// Most the mic is ever boosted, in dB
+    pub max_gain_db: f32,
+    /// Time after a gain reduction before the gain may rise again
+    pub hold_ms: u32,
+}
+
+impl Default for AgcSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_db: -18.0,
+            max_gain_db: 20.0,
+            hold_ms: 500,
+        }
+    }
+}
+
+/// Noise gate on the mic, silencing it between phrases
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoiseGateSettings {
+    pub enabled: bool,
+    /// Level the gate opens above, in dBFS RMS before mic volume
+    pub threshold_db: f32,
+}
+
+impl Default for NoiseGateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_db: -50.0,
+        }
+    }
+}
+
+/// One of the two mics in podcast mode
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PodcastMic {
+    /// Zero-based device channel
+    pub channel: u16,
+    /// Linear gain (0.0 - 2.0)
+    pub gain: f32,
+    /// Position from left (-1.0) to right (1.0)
+    pub pan: f32,
+    pub muted: bool,
+}
+
+impl PodcastMic {
+    pub fn new(channel: u16) -> Self {
+        Self {
+            channel,
+            gain: 1.0,
+            pan: 0.0,
+            muted: false,
+        }
+    }
+
+    /// The mic's sample from an interleaved device frame, after gain and mute
+    pub fn level(&self, frame: &[f32]) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+        frame.get(self.channel as usize).copied().unwrap_or(0.0) * self.gain.clamp(0.0, 2.0)
+    }
+
+    /// Left and right gains of a constant-power pan
+    pub fn pan_gains(&self) -> (f32, f32) {
+        let angle = (self.pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+        (angle.cos(), angle.sin())
+    }
+}
+
+/// Which channels of a multi-channel interface feed the mic
+///
+/// Channel numbers are zero-based device channels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum InputChannelMap {
+    /// Use the device's channels as they are
+    #[default]
+    AsIs,
+    /// One channel as a mono mic, sent to every engine channel
+    Mono { channel: u16 },
+    /// Two channels as a stereo pair
+    Stereo { left: u16, right: u16 },
+    /// Podcast mode: two mono mics, each with its own gain, pan and mute
+    DualMono { host: PodcastMic, guest: PodcastMic },
+}
+
+impl InputChannelMap {
+    pub fn is_as_is(&self) -> bool {
+        matches!(self, InputChannelMap::AsIs)
+    }
+
+    /// Highest device channel read, None when the device is used as is
+    pub fn highest_channel(&self) -> Option<u16> {
+        match self {
+            InputChannelMap::AsIs => None,
+            InputChannelMap::Mono { channel } => Some(*channel),
+            InputChannelMap::Stereo { left, right } => Some(*left.max(right)),
+            InputChannelMap::DualMono { host, guest } => Some(host.channel.max(guest.channel)),
+        }
+    }
+
+    /// Remap interleaved `input` with `input_channels` into `out` with
+    /// `output_channels`, replacing its contents
+    ///
+    /// Channels missing from the device read as silence.
+    pub fn remap(&self, input: &[f32], input_channels: usize, output_channels: usize, out: &mut Vec<f32>) {
+        out.clear();
+        let input_channels = input_channels.max(1);
+        let output_channels = output_channels.max(1);
+        if self.is_as_is() && input_channels == output_channels {
+            out.extend_from_slice(input);
+            return;
+        }
+
+        for frame in input.chunks_exact(input_channels) {
+            let pick = |channel: u16| frame.get(channel as usize).copied().unwrap_or(0.0);
+            match *self {
+                InputChannelMap::AsIs => {
+                    out.extend((0..output_channels).map(|channel| frame[channel % input_channels]));
+                }
+                InputChannelMap::Mono { channel } => {
+                    out.extend(std::iter::repeat(pick(channel)).take(output_channels));
+                }
+                InputChannelMap::Stereo { left, right } => {
+                    if output_channels == 1 {
+                        out.push((pick(left) + pick(right)) * 0.5);
+                    } else {
+                        out.push(pick(left));
+                        out.push(pick(right));
+                        out.extend(std::iter::repeat(0.0).take(output_channels - 2));
+                    }
+                }
+                InputChannelMap::DualMono { host, guest } => {
+                    let (host_level, guest_level) = (host.level(frame), guest.level(frame));
+                    if output_channels == 1 {
+                        out.push(host_level + guest_level);
+                    } else {
+                        let (host_left, host_right) = host.pan_gains();
+                        let (guest_left, guest_right) = guest.pan_gains();
+                        out.push(host_level * host_left + guest_level * guest_left);
+                        out.push(host_level * host_right + guest_level * guest_right);
+                        out.extend(std::iter::repeat(0.0).take(output_channels - 2));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Steepness of a filter, in dB per octave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum FilterSlope {
+    Db6,
+    #[default]
+    Db12,
+    Db24,
+}
+
+impl FilterSlope {
+    pub fn db_per_octave(&self) -> u8 {
+        match self {
+            FilterSlope::Db6 => 6,
+            FilterSlope::Db12 => 12,
+            FilterSlope::Db24 => 24,
+        }
+    }
+}
+
+impl TryFrom<u8> for FilterSlope {
+    type Error = String;
+
+    fn try_from(db: u8) -> Result<Self, Self::Error> {
+        match db {
+            6 => Ok(FilterSlope::Db6),
+            12 => Ok(FilterSlope::Db12),
+            24 => Ok(FilterSlope::Db24),
+            other => Err(format!("Unsupported slope: {} dB/octave", other)),
+        }
+    }
+}
+
+impl From<FilterSlope> for u8 {
+    fn from(slope: FilterSlope) -> Self {
+        slope.db_per_octave()
+    }
+}
+
+/// Low-cut filter removing rumble from the mic
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HighpassSettings {
+    pub enabled: bool,
+    /// Cutoff frequency (40-200 Hz)
+    pub cutoff_hz: f32,
+    pub slope: FilterSlope,
+}
+
+impl HighpassSettings {
+    pub const MIN_CUTOFF_HZ: f32 = 40.0;
+    pub const MAX_CUTOFF_HZ: f32 = 200.0;
+}
+
+impl Default for HighpassSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cutoff_hz: 80.0,
+            slope: FilterSlope::Db12,
+        }
+    }
+}
+
+/// FFT size and overlap of spectral effects, trading latency for quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SpectralQuality {
+    LowLatency,
+    #[default]
+    Balanced,
+    HighQuality,
+}
+
+impl SpectralQuality {
+    pub fn fft_size(&self) -> usize {
+        match self {
+            SpectralQuality::LowLatency => 512,
+            SpectralQuality::Balanced => 1024,
+            SpectralQuality::HighQuality => 2048,
+        }
+    }
+
+    /// Number of overlapping frames per sample
+    pub fn overlap(&self) -> usize {
+        match self {
+            SpectralQuality::LowLatency | SpectralQuality::Balanced => 2,
+            SpectralQuality::HighQuality => 4,
+        }
+    }
+
+    /// Delay of the FFT itself at the given sample rate, in milliseconds
+    ///
+    /// Running the effect off the audio thread adds its own delay on top.
+    pub fn latency_ms(&self, sample_rate: u32) -> f32 {
+        self.fft_size() as f32 * 1000.0 / sample_rate.max(1) as f32
+    }
+}
+
+/// Sample format the mix is delivered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputBitDepth {
+    /// Follow the output device's native format
+    #[default]
+    Auto,
+    Int16,
+    Int24,
+    Float32,
+}
+
+impl OutputBitDepth {
+    /// Integer depth the mix is rounded to, or None to keep full float
+    ///
+    /// `device_bits` is the device's native integer depth, None for float
+    /// devices. WASAPI shared mode always reports float, so pick a depth
+    /// explicitly for a 16-bit virtual cable.
+    pub fn quantize_bits(&self, device_bits: Option<u32>) -> Option<u32> {
+        match self {
+            OutputBitDepth::Auto => device_bits.filter(|bits| *bits < 32),
+            OutputBitDepth::Int16 => Some(16),
+            OutputBitDepth::Int24 => Some(24),
+            OutputBitDepth::Float32 => None,
+        }
+    }
+}
+
+/// How the float mix is converted for the output device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputFormatSettings {
+    pub bit_depth: OutputBitDepth,
+    /// Add TPDF dither when rounding to an integer depth
+    pub dither: bool,
+}
+
+impl Default for OutputFormatSettings {
+    fn default() -> Self {
+        Self {
+            bit_depth: OutputBitDepth::Auto,
+            dither: true,
+        }
+    }
+}
+
+/// Channel layout the output device is opened with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputLayout {
+    /// The device's own channel count
+    Auto,
+    /// The default, so existing setups keep the layout they had
+    #[default]
+    Stereo,
+    Surround51,
+    Surround71,
+}
+
+impl OutputLayout {
+    /// Channels to open the device with; `device_channels` is the count the
+    /// device reports, None if unknown
+    pub fn device_channels(&self, device_channels: Option<u16>, mix_channels: u16) -> u16 {
+        match self {
+            OutputLayout::Auto => device_channels.filter(|c| *c > 0).unwrap_or(mix_channels),
+            OutputLayout::Stereo => 2,
+            OutputLayout::Surround51 => 6,
+            OutputLayout::Surround71 => 8,
+        }
+    }
+}
+
+/// How the stereo mix is laid out on a multi-channel output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct OutputLayoutSettings {
+    pub layout: OutputLayout,
+    /// Also feed the center channel with the front pair
+    pub center_from_stereo: bool,
+}
+
+/// Headphone monitor of the processed mix on the preview device
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MonitorSettings {
+    pub enabled: bool,
+    /// Monitor volume (0.0 - 2.0)
+    pub volume: f32,
+    /// Extra delay so the monitor lines up with what remote listeners hear
+    pub delay_ms: u32,
+    /// Opus bitrate to code the monitor at, to hear the mix as voice chats
+    /// do (e.g. 64 for Discord's default, 12 for a phone call); None
+    /// monitors the mix untouched
+    #[serde(default)]
+    pub codec_bitrate_kbps: Option<u32>,
+}
+
+impl MonitorSettings {
+    pub const MAX_DELAY_MS: u32 = 2000;
+    /// Bitrates Opus accepts
+    pub const CODEC_BITRATES_KBPS: std::ops::RangeInclusive<u32> = 6..=510;
+}
+
+impl Default for MonitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 1.0,
+            delay_ms: 0,
+            codec_bitrate_kbps: None,
+        }
+    }
+}
+
+/// Brick-wall limiter on what the operator hears (monitor and preview),
+/// independent of the virtual mic
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeadphoneLimiterSettings {
+    pub enabled: bool,
+    /// Highest level reaching the headphones, in dBFS
+    pub ceiling_db: f32,
+}
+
+impl HeadphoneLimiterSettings {
+    pub const CEILINGS_DB: std::ops::RangeInclusive<f32> = -30.0..=0.0;
+
+    /// Ceiling to limit to, None when disabled
+    pub fn effective_ceiling_db(&self) -> Option<f32> {
+        self.enabled.then_some(self.ceiling_db)
+    }
+}
+
+impl Default for HeadphoneLimiterSettings {
+    fn default() -> Self {
+        // On by default: a 0 dB airhorn in the ears is the case to prevent
+        Self {
+            enabled: true,
+            ceiling_db: -6.0,
+        }
+    }
+}
+
+/// Soundboard playback behaviour
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlaybackSettings {
+    /// Fade-out of every stop (stop, stop all, pad retrigger, engine stop)
+    /// without its own fade, in milliseconds; 0 cuts instantly
+    pub stop_fade_ms: u32,
+    /// Bring every pad to `auto_level_target_lufs` from its measured
+    /// loudness, unless the sound opts out
+    pub auto_level: bool,
+    pub auto_level_target_lufs: f32,
+    /// Fade of the whole output from silence when mixing starts, and back
+    /// before it stops, against driver pops; 0 for none
+    pub output_ramp_ms: u32,
+    /// Memory kept for decoded pads, so a press doesn't wait on a decode
+    pub sound_cache_mb: u32,
+}
+
+impl PlaybackSettings {
+    pub const MAX_STOP_FADE_MS: u32 = 5000;
+    pub const MAX_OUTPUT_RAMP_MS: u32 = 1000;
+    pub const SOUND_CACHE_MB: std::ops::RangeInclusive<u32> = 16..=4096;
+    /// Auto-level targets, from quiet to streaming-loud
+    pub const AUTO_LEVEL_TARGETS: std::ops::RangeInclusive<f32> = -36.0..=-6.0;
+    /// Most auto-level can boost or cut a sound
+    pub const MAX_AUTO_LEVEL_DB: f32 = 18.0;
+
+    /// Linear gain bringing a sound at `lufs` to the target, within
+    /// [`Self::MAX_AUTO_LEVEL_DB`]; `peak` keeps a boost from clipping
+    pub fn auto_level_gain(&self, lufs: f32, peak: f32) -> f32 {
+        let db = (self.auto_level_target_lufs - lufs).clamp(-Self::MAX_AUTO_LEVEL_DB, Self::MAX_AUTO_LEVEL_DB);
+        // Boosts stop at full scale; cuts always apply
+        let ceiling = if peak > 0.0 { (1.0 / peak).max(1.0) } else { f32::MAX };
+        10f32.powf(db / 20.0).min(ceiling)
+    }
+}
+
+impl Default for PlaybackSettings {
+    fn default() -> Self {
+        Self {
+            // Long enough to avoid a click, short enough to feel instant
+            stop_fade_ms: 20,
+            auto_level: false,
+            // Loud enough to sit over voice, like most streaming platforms
+            auto_level_target_lufs: -16.0,
+            output_ramp_ms: 200,
+            // A few minutes of stereo audio at 48 kHz
+            sound_cache_mb: 256,
+        }
+    }
+}
+
+/// Delay of the virtual mic output, to catch accidents before listeners
+/// hear them; the monitor stays live
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcastDelaySettings {
+    pub enabled: bool,
+    pub delay_ms: u32,
+}
+
+/// What replaces censored audio in the broadcast delay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CensorMode {
+    /// A 1 kHz tone, the classic bleep
+    #[default]
+    Beep,
+    Silence,
+}
+
+impl BroadcastDelaySettings {
+    pub const MAX_DELAY_MS: u32 = 30_000;
+
+    /// Delay applied to the output, 0 when disabled
+    pub fn effective_delay_ms(&self) -> u32 {
+        if self.enabled { self.delay_ms } else { 0 }
+    }
+}
+
+/// Speaking detection on the processed mic, for the `voice-activity` event
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VoiceActivitySettings {
+    pub enabled: bool,
+    /// Mic level (dBFS RMS) above which the user counts as speaking
+    pub threshold_db: f32,
+    /// Quiet time before speech counts as stopped, in milliseconds
+    pub hangover_ms: u32,
+}
+
+impl VoiceActivitySettings {
+    pub const DEFAULT_THRESHOLD_DB: f32 = -45.0;
+    pub const DEFAULT_HANGOVER_MS: u32 = 300;
+    pub const MAX_HANGOVER_MS: u32 = 5_000;
+}
+
+impl Default for VoiceActivitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_db: Self::DEFAULT_THRESHOLD_DB,
+            hangover_ms: Self::DEFAULT_HANGOVER_MS,
+        }
+    }
+}
+
+/// Trigger folder watched for `.play` and `.json` command files
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotFolderSettings {
+    pub enabled: bool,
+    pub path: Option<String>,
+}
+
+impl HotFolderSettings {
+    /// Folder to watch, None when off
+    pub fn active_path(&self) -> Option<&str> {
+        self.path.as_deref().filter(|_| self.enabled)
+    }
+}
+
+/// Experimental: ducks keyboard clatter in the mic for a moment after each
+/// key press, using the press times from a keyboard hook
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KeyboardSuppressionSettings {
+    pub enabled: bool,
+    /// Strongest cut of a click, in dB
+    pub depth_db: f32,
+    /// How long the gate stays open after a press, in milliseconds
+    pub window_ms: u32,
+}
+
+impl KeyboardSuppressionSettings {
+    pub const DEPTHS_DB: std::ops::RangeInclusive<f32> = -40.0..=0.0;
+    pub const WINDOWS_MS: std::ops::RangeInclusive<u32> = 10..=100;
+
+    pub fn is_valid(&self) -> bool {
+        Self::DEPTHS_DB.contains(&self.depth_db) && Self::WINDOWS_MS.contains(&self.window_ms)
+    }
+}
+
+impl Default for KeyboardSuppressionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            depth_db: -24.0,
+            window_ms: 30,
+        }
+    }
+}
+
+/// Sound played, and scene recalled, when mixing starts
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartJingleSettings {
+    pub enabled: bool,
+    /// Soundboard sound id
+    pub sound_id: Option<String>,
+    /// Scene recalled along with it, if any
+    pub scene_id: Option<String>,
+}
+
+/// Name fragments of the virtual audio drivers detected out of the box
+pub const DEFAULT_VIRTUAL_DEVICE_PATTERNS: &[&str] = &[
+    "virtual audio",
+    "vb-audio",
+    "cable",
+    "voicemeeter",
+    "blackhole",
+    "loopback",
+    "virtual cable",
+];
+
+/// How virtual devices are told apart from hardware
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtualDeviceSettings {
+    /// Lowercase name fragments; a device whose name contains one is virtual
+    pub patterns: Vec<String>,
+    /// Devices the user marked as virtual, by id
+    #[serde(default)]
+    pub marked_devices: Vec<String>,
+    /// Type forced on a device by the user, by id; wins over the patterns
+    /// and marks
+    #[serde(default)]
+    pub type_overrides: HashMap<String, DeviceType>,
+}
+
+impl VirtualDeviceSettings {
+    /// Type of the device named `name`, enumerated as an input or output
+    ///
+    /// An override only applies to the direction it names, so a device
+    /// enumerated both ways keeps its other entry.
+    pub fn classify(&self, name: &str, is_input: bool) -> DeviceType {
+        self.type_overrides
+            .iter()
+            .find(|(id, device_type)| device_type.is_input() == is_input && same_device_name(id, name))
+            .map(|(_, device_type)| *device_type)
+            .unwrap_or_else(|| DeviceType::from_parts(is_input, self.is_virtual(name)))
+    }
+
+    /// Whether the device named `name` counts as virtual
+    pub fn is_virtual(&self, name: &str) -> bool {
+        if self.marked_devices.iter().any(|marked| same_device_name(marked, name)) {
+            return true;
+        }
+        let name = normalize_device_name(name).to_lowercase();
+        self.patterns.iter().any(|pattern| name.contains(pattern.as_str()))
+    }
+}
+
+impl Default for VirtualDeviceSettings {
+    fn default() -> Self {
+        Self {
+            patterns: DEFAULT_VIRTUAL_DEVICE_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            marked_devices: Vec::new(),
+            type_overrides: HashMap::new(),
+        }
+    }
+}
+
+/// Runtime detection of a mic-to-output loop, which mutes the mic
+///
+/// Off unless turned on, so a loud room never mutes an existing setup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedbackProtectionSettings {
+    pub enabled: bool,
+}
+
+/// Buffer size picked from measured underruns instead of by the user
+///
+/// Mixing starts at a small buffer for low latency; when the output keeps
+/// running dry the buffer is doubled and remembered for the device pair.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferAutoTuneSettings {
+    pub enabled: bool,
+    /// Tuned buffer size in frames, keyed by [`Self::pair_key`]
+    #[serde(default)]
+    pub tuned: HashMap<String, u32>,
+}
+
+impl BufferAutoTuneSettings {
+    /// Buffer a device pair starts at before any underrun
+    pub const START_FRAMES: u32 = 128;
+    pub const MAX_FRAMES: u32 = 4096;
+    /// Underruns within [`Self::UNDERRUN_WINDOW_SECS`] that grow the buffer
+    pub const UNDERRUN_THRESHOLD: u32 = 5;
+    pub const UNDERRUN_WINDOW_SECS: u64 = 30;
+
+    /// Key of an input and output device pair
+    pub fn pair_key(input_device: &str, output_device: &str) -> String {
+        format!("{} -> {}", input_device, output_device)
+    }
+
+    /// Frames per buffer the pair opens with, None for the driver's default
+    /// when auto-tune is off
+    pub fn buffer_frames(&self, input_device: &str, output_device: &str) -> Option<u32> {
+        self.enabled.then(|| {
+            self.tuned
+                .get(&Self::pair_key(input_device, output_device))
+                .copied()
+                .unwrap_or(Self::START_FRAMES)
+        })
+    }
+
+    /// Size after `frames` underran, None once at the largest
+    pub fn next_frames(frames: u32) -> Option<u32> {
+        (frames < Self::MAX_FRAMES).then(|| (frames.max(Self::START_FRAMES / 2) * 2).min(Self::MAX_FRAMES))
+    }
+}
+
+/// Hotkeys stepping through the boards (hotkey profiles)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardHotkeySettings {
+    /// Switch to the next board, None to leave unbound
+    pub next: Option<String>,
+    /// Switch to the previous board
+    pub previous: Option<String>,
+}
+
+impl Default for BoardHotkeySettings {
+    fn default() -> Self {
+        Self {
+            next: Some("Ctrl+PageDown".to_string()),
+            previous: Some("Ctrl+PageUp".to_string()),
+        }
+    }
+}
+
+/// Pad hotkeys that fire while other apps have focus
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalHotkeySettings {
+    /// Master switch; off unregisters every global hotkey
+    pub enabled: bool,
+    /// Global combo by pad id
+    #[serde(default)]
+    pub pads: HashMap<String, String>,
+    /// Register the board switching hotkeys globally too
+    #[serde(default)]
+    pub board_switching: bool,
+}
+
+impl Default for GlobalHotkeySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pads: HashMap::new(),
+            board_switching: false,
+        }
+    }
+}
+
+/// What holding the push-to-talk key does to the mic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PushToTalkMode {
+    /// The mic follows the mute button only
+    #[default]
+    Off,
+    /// Muted unless the key is held
+    PushToTalk,
+    /// Muted while the key is held
+    PushToMute,
+}
+
+/// Hold-a-key gate on the virtual mic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushToTalkSettings {
+    pub mode: PushToTalkMode,
+    /// Key held to talk (or mute), as a combo like `"Mouse4"` or `"Ctrl+Space"`
+    pub key: String,
+    /// Talking keeps going this long after the key is released, so word
+    /// endings aren't clipped
+    #[serde(default)]
+    pub release_ms: u32,
+}
+
+impl PushToTalkSettings {
+    /// Longest release tail accepted
+    pub const MAX_RELEASE_MS: u32 = 2000;
+}
+
+impl Default for PushToTalkSettings {
+    fn default() -> Self {
+        Self {
+            mode: PushToTalkMode::Off,
+            key: "Mouse4".to_string(),
+            release_ms: 200,
         }
     }
 }
@@ -41,6 +880,93 @@ pub struct AppSettings {
     pub start_minimized: bool,
     /// Auto-start mixing when app launches
     pub auto_start_mixing: bool,
+    /// Opt-in anonymous usage telemetry
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+    /// Release channel for updates
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// Install downloaded updates when the app quits instead of immediately
+    #[serde(default)]
+    pub install_on_quit: bool,
+    /// "On air" tally light output
+    #[serde(default)]
+    pub tally: TallySettings,
+    /// Learned-noise-print denoising
+    #[serde(default)]
+    pub noise_reduction: NoiseReductionSettings,
+    /// Cancel soundboard playback re-captured by the mic (speaker monitoring)
+    #[serde(default)]
+    pub echo_cancellation: bool,
+    /// Mic volume, rumble filter, AGC and noise gate, per input device
+    #[serde(default)]
+    pub mic_profiles: MicProfiles,
+    /// Order and on/off state of the mic effects
+    #[serde(default)]
+    pub mic_chain: MicChainLayout,
+    /// FFT size and overlap of spectral mic effects
+    #[serde(default)]
+    pub spectral_quality: SpectralQuality,
+    /// Character voice applied to the mic
+    #[serde(default)]
+    pub voice_changer: VoiceChangerSettings,
+    /// Bit depth and dithering of the output
+    #[serde(default)]
+    pub output_format: OutputFormatSettings,
+    /// Input channel selection, keyed by input device name
+    #[serde(default)]
+    pub input_channel_maps: HashMap<String, InputChannelMap>,
+    /// Monitor of the processed mix on the preview device
+    #[serde(default)]
+    pub monitor: MonitorSettings,
+    /// Profile whose pad hotkeys are live, besides the global ones
+    #[serde(default)]
+    pub active_hotkey_profile: Option<String>,
+    /// Stop fades of soundboard playback
+    #[serde(default)]
+    pub playback: PlaybackSettings,
+    /// Delay of the output bus
+    #[serde(default)]
+    pub broadcast_delay: BroadcastDelaySettings,
+    /// Speaking detection on the mic
+    #[serde(default)]
+    pub voice_activity: VoiceActivitySettings,
+    /// Safety limiter on the monitor and preview outputs
+    #[serde(default)]
+    pub headphone_limiter: HeadphoneLimiterSettings,
+    /// Auto-mute of the mic on a feedback loop
+    #[serde(default)]
+    pub feedback_protection: FeedbackProtectionSettings,
+    /// File-drop triggers for external tools
+    #[serde(default)]
+    pub hot_folder: HotFolderSettings,
+    /// Detection of virtual devices
+    #[serde(default)]
+    pub virtual_devices: VirtualDeviceSettings,
+    /// Channel layout of the output device
+    #[serde(default)]
+    pub output_layout: OutputLayoutSettings,
+    /// What remote triggers may do, per integration
+    #[serde(default)]
+    pub integrations: IntegrationPermissions,
+    /// Jingle of the stream start
+    #[serde(default)]
+    pub start_jingle: StartJingleSettings,
+    /// Keystroke-timed gate on the mic
+    #[serde(default)]
+    pub keyboard_suppression: KeyboardSuppressionSettings,
+    /// Buffer size grown from measured underruns
+    #[serde(default)]
+    pub buffer_auto_tune: BufferAutoTuneSettings,
+    /// Hotkeys switching to the next and previous board
+    #[serde(default)]
+    pub board_hotkeys: BoardHotkeySettings,
+    /// Pad hotkeys registered system-wide
+    #[serde(default)]
+    pub global_hotkeys: GlobalHotkeySettings,
+    /// Push-to-talk / push-to-mute gate on the mic
+    #[serde(default)]
+    pub push_to_talk: PushToTalkSettings,
 }
 
 impl AppSettings {
@@ -49,6 +975,35 @@ impl AppSettings {
             audio: AudioSettings::new(),
             start_minimized: false,
             auto_start_mixing: false,
+            telemetry_enabled: false,
+            update_channel: UpdateChannel::Stable,
+            install_on_quit: false,
+            tally: TallySettings::default(),
+            noise_reduction: NoiseReductionSettings::default(),
+            echo_cancellation: false,
+            mic_profiles: MicProfiles::default(),
+            mic_chain: MicChainLayout::default(),
+            spectral_quality: SpectralQuality::default(),
+            voice_changer: VoiceChangerSettings::default(),
+            output_format: OutputFormatSettings::default(),
+            input_channel_maps: HashMap::new(),
+            monitor: MonitorSettings::default(),
+            active_hotkey_profile: None,
+            playback: PlaybackSettings::default(),
+            broadcast_delay: BroadcastDelaySettings::default(),
+            voice_activity: VoiceActivitySettings::default(),
+            headphone_limiter: HeadphoneLimiterSettings::default(),
+            feedback_protection: FeedbackProtectionSettings::default(),
+            hot_folder: HotFolderSettings::default(),
+            virtual_devices: VirtualDeviceSettings::default(),
+            output_layout: OutputLayoutSettings::default(),
+            integrations: IntegrationPermissions::default(),
+            start_jingle: StartJingleSettings::default(),
+            keyboard_suppression: KeyboardSuppressionSettings::default(),
+            buffer_auto_tune: BufferAutoTuneSettings::default(),
+            board_hotkeys: BoardHotkeySettings::default(),
+            global_hotkeys: GlobalHotkeySettings::default(),
+            push_to_talk: PushToTalkSettings::default(),
         }
     }
 }
@@ -69,6 +1024,7 @@ mod tests {
         assert_eq!(settings.audio.master_volume, 1.0);
         assert_eq!(settings.audio.sample_rate, 48000);
         assert!(settings.audio.input_device_id.is_none());
+        assert!(!settings.telemetry_enabled);
     }
 
     #[test]
@@ -78,4 +1034,129 @@ mod tests {
         let deserialized: AppSettings = serde_json::from_str(&json).unwrap();
         assert_eq!(settings.audio.master_volume, deserialized.audio.master_volume);
     }
+
+    #[test]
+    fn test_virtual_device_overrides() {
+        let mut virtual_devices = VirtualDeviceSettings::default();
+        assert!(virtual_devices.is_virtual("CABLE Output (VB-Audio Virtual Cable)"));
+        assert!(!virtual_devices.is_virtual("Synchronous Audio Router (SAR)"));
+
+        virtual_devices.patterns.push("synchronous audio router".into());
+        virtual_devices.marked_devices.push("Line 1 (Virtual Audio Cable)".into());
+        assert!(virtual_devices.is_virtual("Synchronous Audio Router (SAR)"));
+        assert!(virtual_devices.is_virtual("Line 1 (2- Virtual Audio Cable)"));
+        assert!(!virtual_devices.is_virtual("Realtek HD Audio"));
+    }
+
+    #[test]
+    fn test_type_override_wins_for_its_direction() {
+        let mut virtual_devices = VirtualDeviceSettings::default();
+        virtual_devices
+            .type_overrides
+            .insert("CABLE Output (VB-Audio Virtual Cable)".into(), DeviceType::InputPhysical);
+
+        let cable = "CABLE Output (VB-Audio Virtual Cable)";
+        assert_eq!(virtual_devices.classify(cable, true), DeviceType::InputPhysical);
+        assert_eq!(virtual_devices.classify(cable, false), DeviceType::OutputVirtual);
+        assert_eq!(virtual_devices.classify("Realtek HD Audio", false), DeviceType::OutputPhysical);
+    }
+
+    #[test]
+    fn test_filter_slope_serializes_as_number() {
+        let json = serde_json::to_string(&FilterSlope::Db24).unwrap();
+        assert_eq!(json, "24");
+        assert!(serde_json::from_str::<FilterSlope>("18").is_err());
+    }
+
+    #[test]
+    fn test_input_channel_map() {
+        // Two frames of a 4-channel interface
+        let input = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
+        let mut out = Vec::new();
+
+        InputChannelMap::Stereo { left: 2, right: 3 }.remap(&input, 4, 2, &mut out);
+        assert_eq!(out, vec![0.3, 0.4, 0.7, 0.8]);
+
+        InputChannelMap::Mono { channel: 0 }.remap(&input, 4, 2, &mut out);
+        assert_eq!(out, vec![0.1, 0.1, 0.5, 0.5]);
+
+        // Missing channels are silent
+        InputChannelMap::Mono { channel: 7 }.remap(&input, 4, 2, &mut out);
+        assert_eq!(out, vec![0.0; 4]);
+
+        InputChannelMap::AsIs.remap(&[0.1, 0.2], 1, 2, &mut out);
+        assert_eq!(out, vec![0.1, 0.1, 0.2, 0.2]);
+        assert_eq!(InputChannelMap::Stereo { left: 5, right: 2 }.highest_channel(), Some(5));
+    }
+
+    #[test]
+    fn test_dual_mono_pans_each_mic() {
+        let host = PodcastMic { pan: -1.0, ..PodcastMic::new(0) };
+        let guest = PodcastMic { pan: 1.0, gain: 0.5, ..PodcastMic::new(1) };
+        let mut out = Vec::new();
+
+        InputChannelMap::DualMono { host, guest }.remap(&[0.4, 0.8], 2, 2, &mut out);
+        assert!((out[0] - 0.4).abs() < 1e-6);
+        assert!((out[1] - 0.4).abs() < 1e-6);
+
+        let muted = PodcastMic { muted: true, ..host };
+        InputChannelMap::DualMono { host: muted, guest }.remap(&[0.4, 0.8], 2, 2, &mut out);
+        assert!(out[0].abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_output_bit_depth_resolution() {
+        assert_eq!(OutputBitDepth::Auto.quantize_bits(Some(16)), Some(16));
+        assert_eq!(OutputBitDepth::Auto.quantize_bits(Some(32)), None);
+        assert_eq!(OutputBitDepth::Auto.quantize_bits(None), None);
+        assert_eq!(OutputBitDepth::Int24.quantize_bits(None), Some(24));
+        assert_eq!(OutputBitDepth::Float32.quantize_bits(Some(16)), None);
+    }
+
+    #[test]
+    fn test_output_layout_channels() {
+        assert_eq!(OutputLayout::Auto.device_channels(Some(6), 2), 6);
+        assert_eq!(OutputLayout::Auto.device_channels(None, 2), 2);
+        assert_eq!(OutputLayout::Stereo.device_channels(Some(8), 2), 2);
+        assert_eq!(OutputLayout::Surround71.device_channels(Some(2), 2), 8);
+    }
+
+    #[test]
+    fn test_noise_profile_per_device() {
+        let mut noise = NoiseReductionSettings::default();
+        noise.profiles.insert("USB Mic".into(), NoiseProfile::new(4, vec![0.1; 3]));
+        assert!(noise.active_profile("USB Mic").is_none());
+
+        noise.enabled = true;
+        assert!(noise.active_profile("USB Mic").is_some());
+        assert!(noise.active_profile("Headset").is_none());
+    }
+
+    #[test]
+    fn test_auto_level_gain() {
+        let playback = PlaybackSettings::default();
+        // A -22 LUFS sound needs +6 dB to reach -16
+        assert!((playback.auto_level_gain(-22.0, 0.25) - 1.995).abs() < 0.01);
+        // ...but the boost stops before the peak clips
+        assert!((playback.auto_level_gain(-22.0, 0.8) - 1.25).abs() < 1e-6);
+        // Cuts ignore the peak, and extremes are capped
+        assert!((playback.auto_level_gain(-10.0, 1.5) - 0.501).abs() < 0.01);
+        assert!((playback.auto_level_gain(-60.0, 0.0) - 7.943).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_buffer_auto_tune_per_device_pair() {
+        let mut tune = BufferAutoTuneSettings::default();
+        assert_eq!(tune.buffer_frames("Mic", "Cable"), None);
+
+        tune.enabled = true;
+        assert_eq!(tune.buffer_frames("Mic", "Cable"), Some(BufferAutoTuneSettings::START_FRAMES));
+        tune.tuned.insert(BufferAutoTuneSettings::pair_key("Mic", "Cable"), 512);
+        assert_eq!(tune.buffer_frames("Mic", "Cable"), Some(512));
+        assert_eq!(tune.buffer_frames("Headset", "Cable"), Some(128));
+
+        assert_eq!(BufferAutoTuneSettings::next_frames(128), Some(256));
+        assert_eq!(BufferAutoTuneSettings::next_frames(3000), Some(4096));
+        assert_eq!(BufferAutoTuneSettings::next_frames(4096), None);
+    }
 }
@@ -0,0 +1,221 @@
+//! Sound cleanup - Finding the sounds a board no longer uses
+//!
+//! Boards grow for years and the files behind them with them. Play counts
+//! and last-played dates come from every logged session; sounds unused for
+//! long enough are proposed for archiving, and long-forgotten ones for
+//! deletion, biggest first so the first few reclaim the most space.
+
+use crate::domain::PlayLogEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const DAY_MS: u64 = 86_400_000;
+
+/// How long a sound may go unplayed before it is proposed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupPolicy {
+    /// Unplayed days before a sound is proposed for archiving
+    pub archive_after_days: u32,
+    /// Unplayed days before a sound is proposed for deletion
+    pub delete_after_days: u32,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        Self {
+            archive_after_days: 90,
+            delete_after_days: 365,
+        }
+    }
+}
+
+/// Plays of one sound across the logged sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayStats {
+    pub play_count: u32,
+    /// Milliseconds since the Unix epoch
+    pub last_played: u64,
+}
+
+/// Play counts and last-played dates by sound id
+pub fn play_stats(entries: &[PlayLogEntry]) -> HashMap<String, PlayStats> {
+    let mut stats: HashMap<String, PlayStats> = HashMap::new();
+    for entry in entries {
+        let sound = stats.entry(entry.sound_id.clone()).or_default();
+        sound.play_count += 1;
+        sound.last_played = sound.last_played.max(entry.timestamp);
+    }
+    stats
+}
+
+/// A sound on the board with what is known of its use
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundUsage {
+    pub sound_id: String,
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// When the file landed on disk, in Unix milliseconds, when known
+    pub added: Option<u64>,
+    pub stats: Option<PlayStats>,
+}
+
+/// What to do with an unused sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupAction {
+    Archive,
+    Delete,
+}
+
+/// A sound proposed for cleanup
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupSuggestion {
+    pub sound_id: String,
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub play_count: u32,
+    /// Unix milliseconds, None if never played
+    pub last_played: Option<u64>,
+    /// Days since the last play, or since the file was added if never played
+    pub idle_days: u32,
+    pub action: CleanupAction,
+}
+
+/// Proposed cleanups with the disk space they would free
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub suggestions: Vec<CleanupSuggestion>,
+    /// Size of every suggested file, freed once they are all deleted; a
+    /// file several sounds play counts once
+    pub reclaimable_bytes: u64,
+}
+
+/// Propose sounds to archive or delete as of `now` (Unix milliseconds)
+///
+/// A sound never played counts as idle since its file was added; one
+/// whose age is unknown is never proposed.
+pub fn cleanup_report(sounds: &[SoundUsage], now: u64, policy: CleanupPolicy) -> CleanupReport {
+    let mut suggestions: Vec<CleanupSuggestion> = sounds
+        .iter()
+        .filter_map(|sound| {
+            let last_played = sound.stats.map(|stats| stats.last_played);
+            let idle_since = last_played.or(sound.added)?;
+            let idle_days = (now.saturating_sub(idle_since) / DAY_MS) as u32;
+            let action = if idle_days >= policy.delete_after_days {
+                CleanupAction::Delete
+            } else if idle_days >= policy.archive_after_days {
+                CleanupAction::Archive
+            } else {
+                return None;
+            };
+            Some(CleanupSuggestion {
+                sound_id: sound.sound_id.clone(),
+                name: sound.name.clone(),
+                path: sound.path.clone(),
+                size_bytes: sound.size_bytes,
+                play_count: sound.stats.map_or(0, |stats| stats.play_count),
+                last_played,
+                idle_days,
+                action,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then_with(|| a.name.cmp(&b.name)));
+
+    let mut files: HashMap<&str, u64> = HashMap::new();
+    for suggestion in &suggestions {
+        files.insert(&suggestion.path, suggestion.size_bytes);
+    }
+    CleanupReport {
+        reclaimable_bytes: files.values().sum(),
+        suggestions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::PlaySource;
+
+    fn entry(sound_id: &str, timestamp: u64) -> PlayLogEntry {
+        PlayLogEntry {
+            timestamp,
+            offset_ms: 0,
+            sound_id: sound_id.into(),
+            name: sound_id.into(),
+            path: format!("{}.wav", sound_id),
+            duration: 1.0,
+            source: PlaySource::Ui,
+        }
+    }
+
+    fn usage(sound_id: &str, size_bytes: u64, added: Option<u64>, stats: Option<PlayStats>) -> SoundUsage {
+        SoundUsage {
+            sound_id: sound_id.into(),
+            name: sound_id.into(),
+            path: format!("{}.wav", sound_id),
+            size_bytes,
+            added,
+            stats,
+        }
+    }
+
+    #[test]
+    fn test_play_stats_count_and_keep_the_latest_play() {
+        let stats = play_stats(&[entry("horn", 10), entry("horn", 30), entry("clap", 5), entry("horn", 20)]);
+        assert_eq!(stats["horn"], PlayStats { play_count: 3, last_played: 30 });
+        assert_eq!(stats["clap"].play_count, 1);
+    }
+
+    #[test]
+    fn test_idle_sounds_are_proposed_biggest_first() {
+        let now = 400 * DAY_MS;
+        let played = |day: u64| Some(PlayStats { play_count: 2, last_played: day * DAY_MS });
+        let sounds = [
+            usage("recent", 1000, Some(0), played(390)),
+            usage("stale", 2000, Some(0), played(300)),
+            usage("forgotten", 500, Some(0), played(10)),
+            usage("never", 3000, Some(0), None),
+            usage("new", 4000, Some(395 * DAY_MS), None),
+            usage("unknown", 5000, None, None),
+        ];
+
+        let report = cleanup_report(&sounds, now, CleanupPolicy::default());
+        let proposed: Vec<(&str, CleanupAction)> =
+            report.suggestions.iter().map(|s| (s.sound_id.as_str(), s.action)).collect();
+        assert_eq!(
+            proposed,
+            vec![
+                ("never", CleanupAction::Delete),
+                ("stale", CleanupAction::Archive),
+                ("forgotten", CleanupAction::Delete),
+            ]
+        );
+        assert_eq!(report.reclaimable_bytes, 5500);
+        assert_eq!(report.suggestions[0].last_played, None);
+        assert_eq!(report.suggestions[1].idle_days, 100);
+    }
+
+    #[test]
+    fn test_shared_files_are_reclaimed_once() {
+        let shared = SoundUsage {
+            path: "horn.wav".into(),
+            ..usage("horn", 1000, Some(0), None)
+        };
+        let sounds = [
+            shared.clone(),
+            SoundUsage {
+                sound_id: "horn-variant".into(),
+                ..shared
+            },
+        ];
+        let report = cleanup_report(&sounds, 400 * DAY_MS, CleanupPolicy::default());
+        assert_eq!(report.suggestions.len(), 2);
+        assert_eq!(report.reclaimable_bytes, 1000);
+    }
+}
@@ -0,0 +1,78 @@
+//! Sound edits - Trim and gain stored on a soundboard sound
+//!
+//! Applied wherever a sound is heard or written out, so playback, offline
+//! renders and exports cut and level it the same way.
+
+use crate::domain::AudioBuffer;
+use std::time::Duration;
+
+/// Trim and gain saved with a sound, read from the soundboard entry
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SoundEdits {
+    /// Seconds cut from the start
+    pub trim_start: Option<f64>,
+    /// Seconds from the start of the file where the sound ends
+    pub trim_end: Option<f64>,
+    pub gain_db: Option<f32>,
+}
+
+impl SoundEdits {
+    /// Read the edits of a soundboard sound entry; unset or invalid
+    /// values leave the sound as recorded
+    pub fn from_sound(sound: &serde_json::Value) -> Self {
+        let number = |key: &str| sound.get(key).and_then(|v| v.as_f64()).filter(|v| v.is_finite());
+        Self {
+            trim_start: number("trimStart").filter(|start| *start > 0.0),
+            trim_end: number("trimEnd").map(|end| end.max(0.0)),
+            gain_db: number("gainDb").map(|gain| gain as f32),
+        }
+    }
+
+    /// Where the sound starts in the file
+    pub fn start(&self) -> Duration {
+        Duration::from_secs_f64(self.trim_start.unwrap_or(0.0))
+    }
+
+    /// Linear gain of `gain_db`, 1.0 when unset
+    pub fn gain(&self) -> f32 {
+        self.gain_db.map_or(1.0, |db| 10f32.powf(db / 20.0))
+    }
+
+    /// The trimmed, levelled copy of a decoded sound
+    pub fn apply(&self, buffer: &AudioBuffer) -> AudioBuffer {
+        let rate = buffer.sample_rate() as f64;
+        let mut buffer = if self.trim_start.is_some() || self.trim_end.is_some() {
+            let start = (self.trim_start.unwrap_or(0.0) * rate) as usize;
+            let end = self.trim_end.map_or(buffer.frame_count(), |end| (end * rate) as usize);
+            buffer.slice_frames(start, end)
+        } else {
+            buffer.clone()
+        };
+        if self.gain_db.is_some() {
+            buffer.apply_gain(self.gain());
+        }
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_trims_and_levels() {
+        let sound = serde_json::json!({ "trimStart": 0.25, "trimEnd": 0.75, "gainDb": -6.0206 });
+        let edits = SoundEdits::from_sound(&sound);
+        assert_eq!(edits.start(), Duration::from_millis(250));
+
+        let buffer = AudioBuffer::from_raw_f32((0..8).map(|i| i as f32 / 8.0).collect(), 1, 8);
+        let edited = edits.apply(&buffer);
+        let samples = edited.to_raw_f32();
+        assert_eq!(samples.len(), 4);
+        assert!((samples[0] - 0.125).abs() < 1e-4);
+
+        let untouched = SoundEdits::from_sound(&serde_json::json!({ "trimStart": -1.0 }));
+        assert_eq!(untouched, SoundEdits::default());
+        assert_eq!(untouched.apply(&buffer).to_raw_f32(), buffer.to_raw_f32());
+    }
+}
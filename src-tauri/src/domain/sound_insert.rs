@@ -0,0 +1,21 @@
+//! Sound inserts - Effects applied to a single playing sound
+
+use serde::{Deserialize, Serialize};
+
+/// An effect inserted on one sound, e.g. a music pad
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SoundInsert {
+    /// Karaoke: attenuates centre-panned vocals, keeping the bass
+    /// (strength 0.0 - 1.0)
+    VocalReduction { strength: f32 },
+}
+
+impl SoundInsert {
+    /// Whether the parameters are in range
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::VocalReduction { strength } => (0.0..=1.0).contains(strength),
+        }
+    }
+}
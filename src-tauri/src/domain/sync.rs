@@ -0,0 +1,203 @@
+//! Cloud sync - Backend configuration and conflict detection
+//!
+//! The remote holds one board archive plus a small metadata document. Each
+//! upload gets a fresh revision id; a machine remembers the revision it last
+//! saw and a hash of its local content at that point. Comparing both tells
+//! which side changed since the last sync.
+
+use serde::{Deserialize, Serialize};
+
+/// Where boards are synced to
+///
+/// The password or secret key is kept in the OS credential store under
+/// [`SyncBackendConfig::secret_name`]. It is read from the frontend but
+/// never written out, so neither the sync store nor the webview sees it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyncBackendConfig {
+    /// Any WebDAV server (Nextcloud, ownCloud, NAS...)
+    WebDav {
+        /// Folder URL the sync files are stored in
+        url: String,
+        username: String,
+        #[serde(default, skip_serializing)]
+        password: String,
+    },
+    /// AWS S3 or an S3-compatible store (MinIO, R2, B2...)
+    S3 {
+        /// e.g. `https://s3.eu-west-1.amazonaws.com`
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        #[serde(default, skip_serializing)]
+        secret_access_key: String,
+        /// Key prefix inside the bucket
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+impl SyncBackendConfig {
+    /// Server the backend talks to
+    pub fn url(&self) -> &str {
+        match self {
+            Self::WebDav { url, .. } => url,
+            Self::S3 { endpoint, .. } => endpoint,
+        }
+    }
+
+    /// Whether the server is reached over TLS, so credentials aren't sent in the clear
+    pub fn is_https(&self) -> bool {
+        self.url()
+            .get(..8)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+    }
+
+    /// Name of the password or secret key in the credential store
+    pub fn secret_name(&self) -> String {
+        match self {
+            Self::WebDav { url, username, .. } => format!("sync:{}@{}", username, url),
+            Self::S3 { endpoint, access_key_id, .. } => format!("sync:{}@{}", access_key_id, endpoint),
+        }
+    }
+
+    /// The password or secret key
+    pub fn secret_mut(&mut self) -> &mut String {
+        match self {
+            Self::WebDav { password, .. } => password,
+            Self::S3 { secret_access_key, .. } => secret_access_key,
+        }
+    }
+}
+
+/// Sync settings, kept per machine and never synced themselves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct SyncConfig {
+    pub enabled: bool,
+    pub backend: Option<SyncBackendConfig>,
+}
+
+/// Metadata stored next to the remote archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncMetadata {
+    /// Random id of the upload, changes on every upload
+    pub revision: String,
+    /// Upload time, milliseconds since the Unix epoch
+    pub updated_at: u64,
+    /// Name of the machine that uploaded
+    pub device: String,
+}
+
+/// What this machine knew at its last successful sync
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct SyncState {
+    pub last_revision: Option<String>,
+    /// Hash of the local board and settings right after the last sync
+    pub last_local_hash: Option<String>,
+    pub last_synced_at: Option<u64>,
+}
+
+/// How to settle a conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncResolution {
+    KeepLocal,
+    KeepRemote,
+}
+
+/// What a sync has to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    UpToDate,
+    Upload,
+    Download,
+    /// Both sides changed since the last sync
+    Conflict,
+}
+
+/// Decide what to do from the local hash and the remote metadata
+pub fn plan_sync(local_hash: &str, remote: Option<&SyncMetadata>, state: &SyncState) -> SyncAction {
+    let Some(remote) = remote else {
+        return SyncAction::Upload;
+    };
+
+    let local_changed = state.last_local_hash.as_deref() != Some(local_hash);
+    let remote_changed = state.last_revision.as_deref() != Some(remote.revision.as_str());
+
+    match (local_changed, remote_changed) {
+        (false, false) => SyncAction::UpToDate,
+        (true, false) => SyncAction::Upload,
+        (false, true) => SyncAction::Download,
+        // Also the first sync of a machine against an existing remote
+        (true, true) => SyncAction::Conflict,
+    }
+}
+
+/// Apply a user choice to a planned action
+pub fn resolve_sync(action: SyncAction, resolution: Option<SyncResolution>) -> SyncAction {
+    match (action, resolution) {
+        (SyncAction::Conflict, Some(SyncResolution::KeepLocal)) => SyncAction::Upload,
+        (SyncAction::Conflict, Some(SyncResolution::KeepRemote)) => SyncAction::Download,
+        (action, _) => action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(revision: &str) -> SyncMetadata {
+        SyncMetadata {
+            revision: revision.to_string(),
+            updated_at: 0,
+            device: "desk".to_string(),
+        }
+    }
+
+    fn synced(revision: &str, hash: &str) -> SyncState {
+        SyncState {
+            last_revision: Some(revision.to_string()),
+            last_local_hash: Some(hash.to_string()),
+            last_synced_at: Some(0),
+        }
+    }
+
+    #[test]
+    fn test_plan_sync() {
+        let state = synced("r1", "h1");
+        assert_eq!(plan_sync("h1", None, &SyncState::default()), SyncAction::Upload);
+        assert_eq!(plan_sync("h1", Some(&remote("r1")), &state), SyncAction::UpToDate);
+        assert_eq!(plan_sync("h2", Some(&remote("r1")), &state), SyncAction::Upload);
+        assert_eq!(plan_sync("h1", Some(&remote("r2")), &state), SyncAction::Download);
+        assert_eq!(plan_sync("h2", Some(&remote("r2")), &state), SyncAction::Conflict);
+        // First sync against an existing remote needs a decision
+        assert_eq!(plan_sync("h1", Some(&remote("r1")), &SyncState::default()), SyncAction::Conflict);
+    }
+
+    #[test]
+    fn test_resolve_sync() {
+        assert_eq!(resolve_sync(SyncAction::Conflict, None), SyncAction::Conflict);
+        assert_eq!(resolve_sync(SyncAction::Conflict, Some(SyncResolution::KeepLocal)), SyncAction::Upload);
+        assert_eq!(resolve_sync(SyncAction::Conflict, Some(SyncResolution::KeepRemote)), SyncAction::Download);
+        assert_eq!(resolve_sync(SyncAction::UpToDate, Some(SyncResolution::KeepLocal)), SyncAction::UpToDate);
+    }
+
+    #[test]
+    fn test_backend_secret_is_never_serialized() {
+        let json = r#"{"kind":"web_dav","url":"HTTPS://dav.example.com","username":"me","password":"hunter2"}"#;
+        let mut backend: SyncBackendConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(backend.secret_mut().as_str(), "hunter2");
+        assert!(!serde_json::to_string(&backend).unwrap().contains("hunter2"));
+        assert_eq!(backend.secret_name(), "sync:me@HTTPS://dav.example.com");
+        assert!(backend.is_https());
+
+        let plain = SyncBackendConfig::WebDav {
+            url: "http://dav.example.com".to_string(),
+            username: "me".to_string(),
+            password: String::new(),
+        };
+        assert!(!plain.is_https());
+    }
+}
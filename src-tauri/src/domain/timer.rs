@@ -0,0 +1,199 @@
+//! Timer pads - Countdowns with audible cues
+//!
+//! A timer pad counts its duration down in whole seconds and plays cue
+//! sounds on the way: a tick every few seconds, one at halfway and one at
+//! the end. An interval timer starts over at the end until cancelled.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Where the cue sounds play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CueRoute {
+    /// Only the user hears them, on the preview device
+    #[default]
+    Monitor,
+    /// Mixed into the virtual mic, for the audience
+    Mic,
+}
+
+/// Timer of a pad, saved with it as `timer`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerConfig {
+    pub duration_secs: u32,
+    /// Start over at the end until cancelled
+    #[serde(default)]
+    pub repeat: bool,
+    /// Seconds between ticks, None for no ticks
+    #[serde(default)]
+    pub tick_every_secs: Option<u32>,
+    /// Soundboard sound ids of the cues
+    #[serde(default)]
+    pub tick_sound: Option<String>,
+    #[serde(default)]
+    pub halfway_sound: Option<String>,
+    #[serde(default)]
+    pub end_sound: Option<String>,
+    #[serde(default)]
+    pub route: CueRoute,
+}
+
+/// Moment of a timer that has a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerCue {
+    Tick,
+    Halfway,
+    End,
+}
+
+impl TimerConfig {
+    /// The cue due `elapsed_secs` into a cycle; halfway and end take the
+    /// place of a tick falling on the same second
+    pub fn cue_at(&self, elapsed_secs: u32) -> Option<TimerCue> {
+        if elapsed_secs == 0 || elapsed_secs > self.duration_secs {
+            return None;
+        }
+        if elapsed_secs == self.duration_secs {
+            return Some(TimerCue::End);
+        }
+        if self.duration_secs >= 2 && elapsed_secs == self.duration_secs / 2 {
+            return Some(TimerCue::Halfway);
+        }
+        match self.tick_every_secs {
+            Some(every) if every > 0 && elapsed_secs % every == 0 => Some(TimerCue::Tick),
+            _ => None,
+        }
+    }
+
+    /// The sound of a cue, if one is set
+    pub fn cue_sound(&self, cue: TimerCue) -> Option<&str> {
+        match cue {
+            TimerCue::Tick => self.tick_sound.as_deref(),
+            TimerCue::Halfway => self.halfway_sound.as_deref(),
+            TimerCue::End => self.end_sound.as_deref(),
+        }
+    }
+}
+
+/// Running timers, keyed by pad, with the flag that cancels each
+#[derive(Debug, Default)]
+pub struct TimerRegistry {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl TimerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a timer for a pad, cancelling the one it replaces; returns
+    /// its cancel flag
+    pub fn start(&self, pad_id: &str) -> Arc<AtomicBool> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let previous = self.running.lock().unwrap().insert(pad_id.to_string(), cancelled.clone());
+        if let Some(previous) = previous {
+            previous.store(true, Ordering::Relaxed);
+        }
+        cancelled
+    }
+
+    /// Cancel the timer of a pad; false if none was running
+    ///
+    /// The timer stays registered until it notices, so it can still report
+    /// its end.
+    pub fn cancel(&self, pad_id: &str) -> bool {
+        match self.running.lock().unwrap().get(pad_id) {
+            Some(cancelled) => !cancelled.swap(true, Ordering::Relaxed),
+            None => false,
+        }
+    }
+
+    /// Forget a timer that ended; false if it had been replaced, in which
+    /// case its end isn't worth reporting
+    pub fn finish(&self, pad_id: &str, cancelled: &Arc<AtomicBool>) -> bool {
+        let mut running = self.running.lock().unwrap();
+        let current = running.get(pad_id).is_some_and(|flag| Arc::ptr_eq(flag, cancelled));
+        if current {
+            running.remove(pad_id);
+        }
+        current
+    }
+
+    /// Pads with a running timer
+    pub fn running(&self) -> Vec<String> {
+        let running = self.running.lock().unwrap();
+        running
+            .iter()
+            .filter(|(_, cancelled)| !cancelled.load(Ordering::Relaxed))
+            .map(|(pad_id, _)| pad_id.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(duration_secs: u32, tick_every_secs: Option<u32>) -> TimerConfig {
+        TimerConfig {
+            duration_secs,
+            repeat: false,
+            tick_every_secs,
+            tick_sound: None,
+            halfway_sound: None,
+            end_sound: None,
+            route: CueRoute::Monitor,
+        }
+    }
+
+    #[test]
+    fn test_cues() {
+        let timer = config(60, Some(10));
+        let cues: Vec<(u32, TimerCue)> = (0..=61).filter_map(|s| timer.cue_at(s).map(|cue| (s, cue))).collect();
+        assert_eq!(
+            cues,
+            vec![
+                (10, TimerCue::Tick),
+                (20, TimerCue::Tick),
+                (30, TimerCue::Halfway),
+                (40, TimerCue::Tick),
+                (50, TimerCue::Tick),
+                (60, TimerCue::End),
+            ]
+        );
+        assert_eq!(config(1, None).cue_at(1), Some(TimerCue::End));
+        assert_eq!(config(5, Some(0)).cue_at(1), None);
+    }
+
+    #[test]
+    fn test_pad_json() {
+        let timer: TimerConfig =
+            serde_json::from_str(r#"{"durationSecs": 300, "endSound": "gong", "route": "mic"}"#).unwrap();
+        assert_eq!(timer.route, CueRoute::Mic);
+        assert_eq!(timer.cue_sound(TimerCue::End), Some("gong"));
+        assert_eq!(timer.cue_sound(TimerCue::Tick), None);
+    }
+
+    #[test]
+    fn test_restart_replaces_the_running_timer() {
+        let timers = TimerRegistry::new();
+        let first = timers.start("pad-1");
+        let second = timers.start("pad-1");
+        assert!(first.load(Ordering::Relaxed));
+
+        // The replaced timer ending doesn't forget the new one
+        assert!(!timers.finish("pad-1", &first));
+        assert_eq!(timers.running(), vec!["pad-1".to_string()]);
+
+        assert!(timers.cancel("pad-1"));
+        assert!(second.load(Ordering::Relaxed));
+        assert!(!timers.cancel("pad-1"));
+        assert!(timers.running().is_empty());
+        assert!(timers.finish("pad-1", &second));
+    }
+}
@@ -0,0 +1,97 @@
+//! UI state - Window context restored on the next launch
+//!
+//! Kept in the settings store next to the app settings, so it moves with
+//! them. The stored value carries a schema version: older layouts are
+//! migrated, newer ones (from a later build) are ignored rather than
+//! half-read. The active board is a setting of its own.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Position and size of the main window, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl WindowGeometry {
+    /// Smallest size worth restoring; anything less is a broken save
+    pub const MIN_SIZE: u32 = 200;
+
+    pub fn is_valid(&self) -> bool {
+        self.width >= Self::MIN_SIZE && self.height >= Self::MIN_SIZE
+    }
+}
+
+/// How the app looked when it closed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub schema_version: u32,
+    pub window: Option<WindowGeometry>,
+}
+
+impl UiState {
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    pub fn new() -> Self {
+        Self {
+            schema_version: Self::SCHEMA_VERSION,
+            ..Self::default()
+        }
+    }
+
+    /// Read a stored UI state, migrating older schemas; None when it is
+    /// unreadable or from a newer schema
+    pub fn from_stored(value: Value) -> Option<Self> {
+        // Unversioned saves predate the field and share schema 1; later
+        // migrations go here, one step per version
+        let version = value.get("schema_version").and_then(Value::as_u64).unwrap_or(1);
+        if version > u64::from(Self::SCHEMA_VERSION) {
+            return None;
+        }
+
+        let mut state: UiState = serde_json::from_value(value).ok()?;
+        state.schema_version = Self::SCHEMA_VERSION;
+        state.window = state.window.filter(WindowGeometry::is_valid);
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_current_schema_round_trips() {
+        let mut state = UiState::new();
+        state.window = Some(WindowGeometry { x: -1200, y: 40, width: 1024, height: 768, maximized: false });
+        let stored = serde_json::to_value(&state).unwrap();
+        assert_eq!(UiState::from_stored(stored), Some(state));
+    }
+
+    #[test]
+    fn test_unversioned_state_is_stamped() {
+        // Fields of earlier builds are ignored
+        let stored = json!({
+            "selected_board": "Gaming",
+            "window": { "x": 0, "y": 0, "width": 50, "height": 50 },
+        });
+        let state = UiState::from_stored(stored).unwrap();
+        assert_eq!(state.schema_version, UiState::SCHEMA_VERSION);
+        // A window too small to use is dropped
+        assert_eq!(state.window, None);
+    }
+
+    #[test]
+    fn test_newer_schema_is_ignored() {
+        let stored = json!({ "schema_version": UiState::SCHEMA_VERSION + 1, "window": null });
+        assert_eq!(UiState::from_stored(stored), None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Voice changer - Character settings and the presets shipped with the app
+
+use serde::{Deserialize, Serialize};
+
+/// Parameters of the voice changer stage
+///
+/// Every effect is off at its zero value, so the default is a clean voice.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct VoiceChangerSettings {
+    /// Pitch shift in semitones (-12 to +12)
+    pub pitch_semitones: f32,
+    /// Ring modulator frequency, for robotic voices
+    pub ring_mod_hz: f32,
+    /// Band-limit the voice, like a phone or radio speaker
+    pub low_cut_hz: f32,
+    pub high_cut_hz: f32,
+    /// Soft clipping amount (0-1)
+    pub drive: f32,
+    /// Echo delay, for rooms and caves
+    pub echo_ms: f32,
+    /// Echo feedback (0-0.9)
+    pub echo_feedback: f32,
+}
+
+impl VoiceChangerSettings {
+    pub const MAX_PITCH_SEMITONES: f32 = 12.0;
+    pub const MAX_ECHO_FEEDBACK: f32 = 0.9;
+
+    /// Check that every parameter is in range
+    pub fn is_valid(&self) -> bool {
+        self.pitch_semitones.abs() <= Self::MAX_PITCH_SEMITONES
+            && self.ring_mod_hz >= 0.0
+            && self.low_cut_hz >= 0.0
+            && self.high_cut_hz >= 0.0
+            && (0.0..=1.0).contains(&self.drive)
+            && self.echo_ms >= 0.0
+            && (0.0..=Self::MAX_ECHO_FEEDBACK).contains(&self.echo_feedback)
+    }
+
+    /// Check that at least one effect is switched on
+    pub fn is_active(&self) -> bool {
+        self.pitch_semitones != 0.0
+            || self.ring_mod_hz > 0.0
+            || self.low_cut_hz > 0.0
+            || self.high_cut_hz > 0.0
+            || self.drive > 0.0
+            || self.echo_ms > 0.0
+    }
+}
+
+/// A named character voice shipped with the app
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct VoicePreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub settings: VoiceChangerSettings,
+}
+
+const CLEAN: VoiceChangerSettings = VoiceChangerSettings {
+    pitch_semitones: 0.0,
+    ring_mod_hz: 0.0,
+    low_cut_hz: 0.0,
+    high_cut_hz: 0.0,
+    drive: 0.0,
+    echo_ms: 0.0,
+    echo_feedback: 0.0,
+};
+
+/// Built-in character presets, tuned by ear
+pub const BUILTIN_VOICE_PRESETS: [VoicePreset; 6] = [
+    VoicePreset {
+        id: "robot",
+        name: "Robot",
+        settings: VoiceChangerSettings {
+            ring_mod_hz: 60.0,
+            echo_ms: 12.0,
+            echo_feedback: 0.5,
+            ..CLEAN
+        },
+    },
+    VoicePreset {
+        id: "deep",
+        name: "Deep",
+        settings: VoiceChangerSettings {
+            pitch_semitones: -5.0,
+            ..CLEAN
+        },
+    },
+    VoicePreset {
+        id: "helium",
+        name: "Helium",
+        settings: VoiceChangerSettings {
+            pitch_semitones: 7.0,
+            ..CLEAN
+        },
+    },
+    VoicePreset {
+        id: "radio",
+        name: "Radio",
+        settings: VoiceChangerSettings {
+            low_cut_hz: 300.0,
+            high_cut_hz: 3000.0,
+            drive: 0.3,
+            ..CLEAN
+        },
+    },
+    VoicePreset {
+        id: "cave",
+        name: "Cave",
+        settings: VoiceChangerSettings {
+            high_cut_hz: 6000.0,
+            echo_ms: 180.0,
+            echo_feedback: 0.45,
+            ..CLEAN
+        },
+    },
+    VoicePreset {
+        id: "megaphone",
+        name: "Megaphone",
+        settings: VoiceChangerSettings {
+            low_cut_hz: 500.0,
+            high_cut_hz: 2500.0,
+            drive: 0.7,
+            ..CLEAN
+        },
+    },
+];
+
+/// Look up a built-in preset by id
+pub fn builtin_voice_preset(id: &str) -> Option<&'static VoicePreset> {
+    BUILTIN_VOICE_PRESETS.iter().find(|p| p.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_presets_are_valid_and_active() {
+        for preset in &BUILTIN_VOICE_PRESETS {
+            assert!(preset.settings.is_valid(), "{}", preset.id);
+            assert!(preset.settings.is_active(), "{}", preset.id);
+        }
+        assert_eq!(builtin_voice_preset("helium").unwrap().name, "Helium");
+        assert!(builtin_voice_preset("unknown").is_none());
+    }
+
+    #[test]
+    fn test_default_is_clean() {
+        assert!(!VoiceChangerSettings::default().is_active());
+        assert!(!VoiceChangerSettings {
+            echo_feedback: 1.5,
+            ..CLEAN
+        }
+        .is_valid());
+    }
+}
@@ -0,0 +1,167 @@
+//! Automatic gain control - Keeps the mic near a target level
+
+use crate::domain::AgcSettings;
+
+/// Time constant of the level detector
+const DETECTOR_MS: f32 = 50.0;
+/// How fast the gain drops when the voice gets louder
+const ATTACK_MS: f32 = 10.0;
+/// How fast the gain recovers when the voice gets quieter
+const RELEASE_MS: f32 = 800.0;
+/// Below this level (dBFS RMS) the signal is treated as silence and the gain is frozen
+const GATE_DB: f32 = -55.0;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// One-pole smoothing coefficient for a time constant
+fn coefficient(ms: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (ms / 1000.0 * sample_rate.max(1) as f32)).exp()
+}
+
+/// AGC linked across channels, with a hold before the gain rises again
+///
+/// The gain stays within +/- `max_gain_db` and is frozen during silence
+/// so room noise isn't pumped up between sentences.
+pub struct AutomaticGainControl {
+    settings: AgcSettings,
+    sample_rate: u32,
+    target: f32,
+    max_gain: f32,
+    gate: f32,
+    detector: f32,
+    attack: f32,
+    release: f32,
+    hold_frames: u32,
+    power: f32,
+    gain: f32,
+    hold: u32,
+}
+
+impl AutomaticGainControl {
+    pub fn new(settings: AgcSettings, sample_rate: u32) -> Self {
+        let mut agc = Self {
+            settings,
+            sample_rate,
+            target: 0.0,
+            max_gain: 0.0,
+            gate: db_to_linear(GATE_DB).powi(2),
+            detector: 0.0,
+            attack: 0.0,
+            release: 0.0,
+            hold_frames: 0,
+            power: 0.0,
+            gain: 1.0,
+            hold: 0,
+        };
+        agc.set_sample_rate(sample_rate);
+        agc
+    }
+
+    pub fn settings(&self) -> AgcSettings {
+        self.settings
+    }
+
+    /// Recompute time constants for a new sample rate
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+        self.target = db_to_linear(self.settings.target_db);
+        self.max_gain = db_to_linear(self.settings.max_gain_db.max(0.0));
+        self.detector = coefficient(DETECTOR_MS, sample_rate);
+        self.attack = coefficient(ATTACK_MS, sample_rate);
+        self.release = coefficient(RELEASE_MS, sample_rate);
+        self.hold_frames = (self.settings.hold_ms as u64 * sample_rate as u64 / 1000) as u32;
+    }
+
+    /// Current gain, linear
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Apply gain to interleaved samples in place
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+        for frame in data.chunks_exact_mut(channels) {
+            let frame_power = frame.iter().map(|s| s * s).sum::<f32>() / channels as f32;
+            self.power = self.detector * self.power + (1.0 - self.detector) * frame_power;
+
+            if self.power > self.gate {
+                let desired = (self.target / self.power.sqrt()).clamp(1.0 / self.max_gain, self.max_gain);
+                if desired < self.gain {
+                    self.gain = self.attack * self.gain + (1.0 - self.attack) * desired;
+                    self.hold = self.hold_frames;
+                } else if self.hold > 0 {
+                    self.hold -= 1;
+                } else {
+                    self.gain = self.release * self.gain + (1.0 - self.release) * desired;
+                }
+            }
+
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(amplitude: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|n| (n as f32 * 0.05).sin() * amplitude)
+            .collect()
+    }
+
+    fn rms_db(samples: &[f32]) -> f32 {
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        20.0 * rms.log10()
+    }
+
+    #[test]
+    fn test_quiet_voice_is_raised_to_target() {
+        let mut agc = AutomaticGainControl::new(AgcSettings::default(), 48000);
+        let mut data = tone(0.02, 48000 * 4);
+        agc.process(&mut data, 1);
+
+        let level = rms_db(&data[48000 * 3..]);
+        assert!((level - -18.0).abs() < 1.5, "level {}", level);
+    }
+
+    #[test]
+    fn test_gain_is_capped_and_silence_is_not_boosted() {
+        let settings = AgcSettings {
+            max_gain_db: 6.0,
+            ..AgcSettings::default()
+        };
+        let mut agc = AutomaticGainControl::new(settings, 48000);
+
+        let mut silence = vec![0.0; 48000];
+        agc.process(&mut silence, 1);
+        assert_eq!(agc.gain(), 1.0);
+
+        let mut whisper = tone(0.003, 48000 * 4);
+        agc.process(&mut whisper, 1);
+        assert!(agc.gain() > 1.5);
+        assert!(agc.gain() <= db_to_linear(6.0) + 1e-4);
+    }
+
+    #[test]
+    fn test_hold_delays_recovery() {
+        let mut agc = AutomaticGainControl::new(AgcSettings::default(), 1000);
+        let mut loud = tone(0.9, 1000);
+        agc.process(&mut loud, 1);
+        let reduced = agc.gain();
+
+        // Within the 500 ms hold the gain doesn't rise
+        let mut quiet = tone(0.05, 300);
+        agc.process(&mut quiet, 1);
+        assert!(agc.gain() <= reduced);
+
+        let mut quiet = tone(0.05, 3000);
+        agc.process(&mut quiet, 1);
+        assert!(agc.gain() > reduced);
+    }
+}
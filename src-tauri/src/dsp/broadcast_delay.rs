@@ -0,0 +1,200 @@
+//! Broadcast delay - Holds the outgoing mix back by a few seconds
+//!
+//! Like a radio "dump" unit: listeners hear the mix seconds late, so an
+//! accident can be dumped before it reaches them. Dumping silences what
+//! is queued (with a short fade, so it doesn't click); the delay then
+//! refills with live audio and listeners hear silence meanwhile. A
+//! censor is narrower: only the latest seconds are overwritten, with a
+//! beep or silence, while older queued audio still plays.
+
+use crate::domain::CensorMode;
+use std::f32::consts::PI;
+
+/// Fade applied to the queued audio at a dump (about 5 ms at 48 kHz)
+const DUMP_FADE_FRAMES: usize = 240;
+
+/// Pitch and level of the censor beep
+const BEEP_HZ: f32 = 1000.0;
+const BEEP_LEVEL: f32 = 0.25;
+
+/// Fixed delay line for interleaved audio
+pub struct BroadcastDelay {
+    delay_ms: u32,
+    sample_rate: u32,
+    channels: usize,
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl BroadcastDelay {
+    /// A delay of `delay_ms` (0 passes audio through)
+    pub fn new(delay_ms: u32, sample_rate: u32, channels: usize) -> Self {
+        let mut delay = Self {
+            delay_ms,
+            sample_rate,
+            channels: channels.max(1),
+            buffer: Vec::new(),
+            position: 0,
+        };
+        delay.allocate();
+        delay
+    }
+
+    pub fn delay_ms(&self) -> u32 {
+        self.delay_ms
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Change the delay; the line restarts empty, so listeners hear
+    /// silence until it has filled
+    pub fn set_delay_ms(&mut self, delay_ms: u32) {
+        if delay_ms != self.delay_ms {
+            self.delay_ms = delay_ms;
+            self.allocate();
+        }
+    }
+
+    /// Follow the stream format; restarts the line when it changes
+    pub fn set_format(&mut self, sample_rate: u32, channels: usize) {
+        let channels = channels.max(1);
+        if sample_rate != self.sample_rate || channels != self.channels {
+            self.sample_rate = sample_rate;
+            self.channels = channels;
+            self.allocate();
+        }
+    }
+
+    fn allocate(&mut self) {
+        let frames = self.delay_ms as usize * self.sample_rate as usize / 1000;
+        self.buffer = vec![0.0; frames * self.channels];
+        self.position = 0;
+    }
+
+    /// Delay interleaved audio in place
+    pub fn process(&mut self, data: &mut [f32]) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        for sample in data.iter_mut() {
+            std::mem::swap(sample, &mut self.buffer[self.position]);
+            self.position += 1;
+            if self.position == self.buffer.len() {
+                self.position = 0;
+            }
+        }
+    }
+
+    /// Overwrite the latest `frames` frames still queued, those heard last
+    ///
+    /// Returns the number of frames censored, at most the delay length.
+    pub fn censor_last(&mut self, frames: usize, mode: CensorMode) -> usize {
+        let len = self.buffer.len();
+        // Nothing is held while the delay is off
+        if len == 0 {
+            return 0;
+        }
+        let frames = frames.min(len / self.channels);
+        // The newest sample sits just before the write position
+        let start = (self.position + len - frames * self.channels) % len;
+        for frame in 0..frames {
+            let value = match mode {
+                CensorMode::Beep => {
+                    (2.0 * PI * BEEP_HZ * frame as f32 / self.sample_rate.max(1) as f32).sin() * BEEP_LEVEL
+                }
+                CensorMode::Silence => 0.0,
+            };
+            for channel in 0..self.channels {
+                self.buffer[(start + frame * self.channels + channel) % len] = value;
+            }
+        }
+        frames
+    }
+
+    /// Silence everything queued, fading out what is about to play
+    pub fn dump(&mut self) {
+        let len = self.buffer.len();
+        if len == 0 {
+            return;
+        }
+        let fade = (DUMP_FADE_FRAMES * self.channels).min(len);
+        let channels = self.channels;
+
+        // Queued audio plays from the write position to the end, then from the start
+        let (later, next) = self.buffer.split_at_mut(self.position);
+        for (offset, sample) in next.iter_mut().chain(later.iter_mut()).take(fade).enumerate() {
+            *sample *= 1.0 - (offset / channels) as f32 / DUMP_FADE_FRAMES as f32;
+        }
+        let faded = fade.min(next.len());
+        next[faded..].fill(0.0);
+        later[fade - faded..].fill(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_comes_out_late() {
+        // 2 ms at 1 kHz: two stereo frames late
+        let mut delay = BroadcastDelay::new(2, 1000, 2);
+        let mut data = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+        delay.process(&mut data);
+        assert_eq!(data, vec![0.0, 0.0, 0.0, 0.0, 0.1, 0.2]);
+
+        let mut passthrough = BroadcastDelay::new(0, 1000, 2);
+        let mut data = vec![0.5; 4];
+        passthrough.process(&mut data);
+        assert_eq!(data, vec![0.5; 4]);
+    }
+
+    #[test]
+    fn test_censor_rewrites_only_the_latest_audio() {
+        let mut delay = BroadcastDelay::new(10, 1000, 2);
+        let mut data = vec![0.5; 20];
+        delay.process(&mut data);
+
+        // Ten frames queued; the last four are silenced, even past the end
+        assert_eq!(delay.censor_last(4, CensorMode::Silence), 4);
+        let mut data = vec![0.0; 20];
+        delay.process(&mut data);
+        assert_eq!(&data[..12], &[0.5; 12]);
+        assert_eq!(&data[12..], &[0.0; 8]);
+        assert_eq!(delay.censor_last(100, CensorMode::Beep), 10);
+    }
+
+    #[test]
+    fn test_censor_without_delay_does_nothing() {
+        let mut delay = BroadcastDelay::new(0, 1000, 2);
+        assert_eq!(delay.censor_last(4, CensorMode::Beep), 0);
+        assert_eq!(delay.censor_last(0, CensorMode::Silence), 0);
+    }
+
+    #[test]
+    fn test_dump_silences_the_queue() {
+        let mut delay = BroadcastDelay::new(1000, 1000, 1);
+        let mut data = vec![0.5; 1000];
+        delay.process(&mut data);
+        delay.dump();
+
+        let mut data = vec![0.0; 1000];
+        delay.process(&mut data);
+        // A short fade, then nothing of the dumped second
+        assert_eq!(data[0], 0.5);
+        assert!(data[1..DUMP_FADE_FRAMES].windows(2).all(|w| w[1] < w[0]));
+        assert!(data[DUMP_FADE_FRAMES..].iter().all(|s| *s == 0.0));
+
+        // Queued audio that wraps around the end of the line
+        let mut data = vec![0.5; 1100];
+        delay.process(&mut data);
+        delay.dump();
+        let mut data = vec![0.0; 1000];
+        delay.process(&mut data);
+        assert_eq!(data[0], 0.5);
+        assert!(data[1..DUMP_FADE_FRAMES].windows(2).all(|w| w[1] < w[0]));
+        assert!(data[DUMP_FADE_FRAMES..].iter().all(|s| *s == 0.0));
+    }
+}
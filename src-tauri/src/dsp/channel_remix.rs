@@ -0,0 +1,133 @@
+//! Channel remix - Fits the mix to an output with another channel count
+//!
+//! The mix is stereo, but some virtual cables only open with their full
+//! 5.1 or 7.1 layout. Channels follow the WAVE_FORMAT_EXTENSIBLE order
+//! (FL, FR, FC, LFE, BL, BR, SL, SR): upmixing puts the mix on the front
+//! pair, optionally the center, and leaves the rest silent; downmixing a
+//! 5.1 or 7.1 mix to stereo folds the other channels in at -3 dB.
+
+/// Gain of the channels folded into the front pair, and of the center
+/// derived from it (-3 dB)
+const FOLD_GAIN: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Converts interleaved audio from one channel count to another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelRemixer {
+    in_channels: usize,
+    out_channels: usize,
+    /// Feed the center with the front pair when upmixing
+    center: bool,
+}
+
+impl ChannelRemixer {
+    pub fn new(in_channels: usize, out_channels: usize, center: bool) -> Self {
+        Self {
+            in_channels: in_channels.max(1),
+            out_channels: out_channels.max(1),
+            center,
+        }
+    }
+
+    pub fn in_channels(&self) -> usize {
+        self.in_channels
+    }
+
+    pub fn out_channels(&self) -> usize {
+        self.out_channels
+    }
+
+    /// Whether the channel counts differ, i.e. there is anything to do
+    pub fn is_active(&self) -> bool {
+        self.in_channels != self.out_channels
+    }
+
+    /// Remix interleaved `input` into interleaved `output`, frame by frame
+    /// until either runs out
+    pub fn process(&self, input: &[f32], output: &mut [f32]) {
+        for (frame, out) in input.chunks_exact(self.in_channels).zip(output.chunks_exact_mut(self.out_channels)) {
+            self.remix_frame(frame, out);
+        }
+    }
+
+    fn remix_frame(&self, input: &[f32], out: &mut [f32]) {
+        out.fill(0.0);
+        match (input.len(), out.len()) {
+            (a, b) if a == b => out.copy_from_slice(input),
+            // Mono output: average everything
+            (_, 1) => out[0] = input.iter().sum::<f32>() / input.len() as f32,
+            // Mono mix: the same on both fronts
+            (1, _) => {
+                out[0] = input[0];
+                out[1] = input[0];
+                if self.center && out.len() > 2 {
+                    out[2] = input[0];
+                }
+            }
+            // 5.1 / 7.1 to stereo: fold center and surrounds into the fronts
+            (6 | 8, 2) => {
+                let center = input[2] * FOLD_GAIN;
+                let (left, right) = input[4..]
+                    .chunks_exact(2)
+                    .fold((0.0, 0.0), |(l, r), pair| (l + pair[0], r + pair[1]));
+                out[0] = (input[0] + center + left * FOLD_GAIN).clamp(-1.0, 1.0);
+                out[1] = (input[1] + center + right * FOLD_GAIN).clamp(-1.0, 1.0);
+            }
+            // Stereo up: the mix stays on the front pair
+            (2, _) => {
+                out[0] = input[0];
+                out[1] = input[1];
+                if self.center && out.len() > 2 {
+                    out[2] = (input[0] + input[1]) * 0.5 * FOLD_GAIN;
+                }
+            }
+            // Anything else: the channels both sides have
+            (a, b) => {
+                let shared = a.min(b);
+                out[..shared].copy_from_slice(&input[..shared]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stereo_goes_to_the_front_pair() {
+        let remixer = ChannelRemixer::new(2, 6, false);
+        let mut out = [1.0; 12];
+        remixer.process(&[0.5, -0.5, 0.25, 0.75], &mut out);
+        assert_eq!(out, [0.5, -0.5, 0.0, 0.0, 0.0, 0.0, 0.25, 0.75, 0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_center_duplication_is_optional() {
+        let remixer = ChannelRemixer::new(2, 8, true);
+        let mut out = [0.0; 8];
+        remixer.process(&[0.4, 0.4], &mut out);
+        assert_eq!(&out[..2], &[0.4, 0.4]);
+        assert!((out[2] - 0.4 * FOLD_GAIN).abs() < 1e-6);
+        assert_eq!(&out[3..], &[0.0; 5]);
+    }
+
+    #[test]
+    fn test_surround_folds_down_to_stereo() {
+        let remixer = ChannelRemixer::new(6, 2, false);
+        let mut out = [0.0; 2];
+        // Only the center and the back left carry signal
+        remixer.process(&[0.0, 0.0, 0.5, 0.0, 0.5, 0.0], &mut out);
+        assert!((out[0] - FOLD_GAIN).abs() < 1e-6);
+        assert!((out[1] - 0.5 * FOLD_GAIN).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mono_output_averages() {
+        let remixer = ChannelRemixer::new(2, 1, false);
+        let mut out = [0.0; 2];
+        remixer.process(&[0.2, 0.4, -1.0, 1.0], &mut out);
+        assert!((out[0] - 0.3).abs() < 1e-6);
+        assert_eq!(out[1], 0.0);
+        assert!(!ChannelRemixer::new(2, 2, true).is_active());
+    }
+}
@@ -0,0 +1,260 @@
+//! Codec simulator - Opus round trip, to hear the mix as voice chats do
+//!
+//! The mix is folded to mono, encoded with Opus in VoIP mode at the
+//! chosen bitrate and decoded again, one 20 ms frame at a time. Opus only
+//! runs at 8, 12, 16, 24 and 48 kHz; streams at other rates are resampled
+//! to the next supported rate above and back, so the codec's band limits
+//! stay where voice chats have them. The resamplers add a few frames of
+//! delay, reported by [`codec_latency_frames`].
+
+use rubato::{FftFixedIn, Resampler};
+use std::collections::VecDeque;
+
+/// Sample rates Opus can code
+const OPUS_RATES: [u32; 5] = [8_000, 12_000, 16_000, 24_000, 48_000];
+
+/// Length of an Opus frame
+pub const CODEC_FRAME_MS: u32 = 20;
+
+/// Largest packet Opus produces for one frame
+const MAX_PACKET_BYTES: usize = 1275;
+
+/// Rate the codec runs at for a stream at `sample_rate`
+fn codec_rate(sample_rate: u32) -> u32 {
+    OPUS_RATES
+        .into_iter()
+        .find(|rate| *rate >= sample_rate)
+        .unwrap_or(48_000)
+}
+
+/// Samples in one codec frame at `rate`
+fn frame_len(rate: u32) -> usize {
+    (rate * CODEC_FRAME_MS / 1000) as usize
+}
+
+/// Converts the stream to the codec rate and back, one frame per chunk
+struct RateBridge {
+    to_codec: FftFixedIn<f32>,
+    from_codec: FftFixedIn<f32>,
+    /// Scratch output of the resampler that ran last
+    scratch: Vec<Vec<f32>>,
+}
+
+impl RateBridge {
+    /// None when Opus codes the stream's own rate
+    fn new(sample_rate: u32) -> Option<Self> {
+        let rate = codec_rate(sample_rate);
+        if rate == sample_rate {
+            return None;
+        }
+        let to_codec = FftFixedIn::new(sample_rate as usize, rate as usize, frame_len(sample_rate), 1, 1)
+            .map_err(|e| tracing::warn!("Cannot resample {} Hz for the codec: {}", sample_rate, e))
+            .ok()?;
+        let from_codec = FftFixedIn::new(rate as usize, sample_rate as usize, frame_len(rate), 1, 1)
+            .map_err(|e| tracing::warn!("Cannot resample the codec to {} Hz: {}", sample_rate, e))
+            .ok()?;
+        let scratch = vec![vec![0.0; to_codec.output_frames_max().max(from_codec.output_frames_max())]];
+        Some(Self {
+            to_codec,
+            from_codec,
+            scratch,
+        })
+    }
+
+    /// Stream frames held back at most before reaching the output
+    fn holdback(&self, sample_rate: u32) -> usize {
+        let rate = codec_rate(sample_rate);
+        let to_stream = |frames: usize| (frames as u64 * sample_rate as u64).div_ceil(rate as u64) as usize;
+        self.to_codec.input_frames_next()
+            + to_stream(frame_len(rate) + self.from_codec.input_frames_next() + self.to_codec.output_frames_max())
+            + self.from_codec.output_frames_max()
+    }
+
+    /// Stream frames of delay inside the resamplers
+    fn delay(&self, sample_rate: u32) -> usize {
+        let rate = codec_rate(sample_rate);
+        let to_codec = (self.to_codec.output_delay() as u64 * sample_rate as u64).div_ceil(rate as u64) as usize;
+        to_codec + self.from_codec.output_delay()
+    }
+}
+
+/// Delay of the codec simulator for a stream at `sample_rate`, in frames
+pub fn codec_latency_frames(sample_rate: u32) -> usize {
+    match RateBridge::new(sample_rate) {
+        Some(bridge) => bridge.holdback(sample_rate) + bridge.delay(sample_rate),
+        None => frame_len(sample_rate),
+    }
+}
+
+/// Opus encode/decode round trip with a fixed latency
+pub struct CodecSimulator {
+    encoder: opus::Encoder,
+    decoder: opus::Decoder,
+    bridge: Option<RateBridge>,
+    /// Mono stream samples waiting for the resampler
+    input: Vec<f32>,
+    /// Mono samples at the codec rate waiting for a full frame
+    codec_input: Vec<f32>,
+    /// Decoded samples at the codec rate waiting for the resampler
+    codec_output: Vec<f32>,
+    /// Coded mono samples at the stream rate, latency first
+    output: VecDeque<f32>,
+    frame: Vec<f32>,
+    packet: Vec<u8>,
+}
+
+impl CodecSimulator {
+    /// Simulator coding at `bitrate_kbps` for a stream at `sample_rate`
+    pub fn new(bitrate_kbps: u32, sample_rate: u32) -> Result<Self, opus::Error> {
+        let rate = codec_rate(sample_rate);
+        let mut encoder = opus::Encoder::new(rate, opus::Channels::Mono, opus::Application::Voip)?;
+        encoder.set_bitrate(opus::Bitrate::Bits(bitrate_kbps as i32 * 1000))?;
+        let decoder = opus::Decoder::new(rate, opus::Channels::Mono)?;
+
+        let bridge = RateBridge::new(sample_rate);
+        let holdback = bridge
+            .as_ref()
+            .map_or(frame_len(sample_rate), |bridge| bridge.holdback(sample_rate));
+        // Room for a second of callbacks, so pushing never allocates
+        let capacity = sample_rate as usize + holdback;
+        let mut output = VecDeque::with_capacity(capacity);
+        // Silence up front covers everything the stages hold back, so the
+        // output never runs dry once it starts
+        output.extend(std::iter::repeat(0.0).take(holdback));
+
+        Ok(Self {
+            encoder,
+            decoder,
+            bridge,
+            input: Vec::with_capacity(capacity),
+            codec_input: Vec::with_capacity(capacity),
+            codec_output: Vec::with_capacity(capacity),
+            output,
+            frame: vec![0.0; frame_len(rate)],
+            packet: vec![0; MAX_PACKET_BYTES],
+        })
+    }
+
+    /// Replace interleaved samples with their coded version, on every channel
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+        let stage = match self.bridge {
+            Some(_) => &mut self.input,
+            None => &mut self.codec_input,
+        };
+        stage.extend(
+            data.chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+
+        if let Some(bridge) = self.bridge.as_mut() {
+            resample(&mut bridge.to_codec, &mut self.input, &mut self.codec_input, &mut bridge.scratch);
+        }
+        let frame = self.frame.len();
+        while self.codec_input.len() >= frame {
+            self.frame.copy_from_slice(&self.codec_input[..frame]);
+            self.codec_input.drain(..frame);
+            self.code_frame();
+            match self.bridge {
+                Some(_) => self.codec_output.extend_from_slice(&self.frame),
+                None => self.output.extend(self.frame.iter().copied()),
+            }
+        }
+        if let Some(bridge) = self.bridge.as_mut() {
+            resample(&mut bridge.from_codec, &mut self.codec_output, &mut self.output, &mut bridge.scratch);
+        }
+
+        for frame in data.chunks_exact_mut(channels) {
+            frame.fill(self.output.pop_front().unwrap_or(0.0));
+        }
+    }
+
+    /// Encode and decode `frame` in place
+    fn code_frame(&mut self) {
+        let decoded = self
+            .encoder
+            .encode_float(&self.frame, &mut self.packet)
+            .and_then(|len| self.decoder.decode_float(&self.packet[..len], &mut self.frame, false));
+
+        // A frame the codec rejected plays as silence rather than stale audio
+        if decoded.is_err() {
+            self.frame.fill(0.0);
+        }
+    }
+}
+
+/// Run every full chunk of `input` through `resampler` into `output`
+fn resample(
+    resampler: &mut FftFixedIn<f32>,
+    input: &mut Vec<f32>,
+    output: &mut impl Extend<f32>,
+    scratch: &mut [Vec<f32>],
+) {
+    let mut used = 0;
+    while input.len() - used >= resampler.input_frames_next() {
+        let chunk = &input[used..used + resampler.input_frames_next()];
+        match resampler.process_into_buffer(&[chunk], scratch, None) {
+            Ok((read, written)) => {
+                used += read;
+                output.extend(scratch[0][..written].iter().copied());
+            }
+            Err(_) => break,
+        }
+    }
+    input.drain(..used);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rms(data: &[f32]) -> f32 {
+        (data.iter().map(|s| s * s).sum::<f32>() / data.len() as f32).sqrt()
+    }
+
+    /// 440 Hz in stereo, a quarter second
+    fn tone(sample_rate: u32) -> Vec<f32> {
+        (0..sample_rate as usize / 4)
+            .flat_map(|n| {
+                let s = (n as f32 * 2.0 * std::f32::consts::PI * 440.0 / sample_rate as f32).sin() * 0.5;
+                [s, s]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_codec_rate() {
+        assert_eq!(codec_rate(16_000), 16_000);
+        assert_eq!(codec_rate(44_100), 48_000);
+        assert_eq!(codec_rate(96_000), 48_000);
+    }
+
+    #[test]
+    fn test_voice_survives_the_round_trip_a_frame_late() {
+        let mut codec = CodecSimulator::new(64, 48_000).unwrap();
+        let mut data = tone(48_000);
+        codec.process(&mut data, 2);
+
+        // The first frame is latency; afterwards the tone is back on both channels
+        assert_eq!(codec_latency_frames(48_000), 960);
+        assert!(data[..960 * 2].iter().all(|s| *s == 0.0));
+        let tail = &data[6_000 * 2..];
+        assert!((rms(tail) - 0.5 / 2f32.sqrt()).abs() < 0.1);
+        assert!(tail.chunks_exact(2).all(|f| f[0] == f[1]));
+    }
+
+    #[test]
+    fn test_other_rates_are_resampled_around_the_codec() {
+        let mut codec = CodecSimulator::new(64, 44_100).unwrap();
+        let latency = codec_latency_frames(44_100);
+        assert!(latency > frame_len(44_100));
+
+        // Small blocks, as the callback hands them over, never run dry
+        let mut data = tone(44_100);
+        for block in data.chunks_mut(441 * 2) {
+            codec.process(block, 2);
+        }
+        let tail = &data[(latency + 2_000) * 2..];
+        assert!((rms(tail) - 0.5 / 2f32.sqrt()).abs() < 0.1);
+    }
+}
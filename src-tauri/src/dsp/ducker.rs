@@ -0,0 +1,116 @@
+//! Ducker - Lowers the mic while soundboard clips play
+//!
+//! The gain glides to the ducked level with the attack time when a clip
+//! starts, and back to unity with the release time once none is playing,
+//! so the voice dips under a clip instead of being cut.
+
+use crate::domain::DuckingSettings;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// One-pole smoothing coefficient for a time constant
+fn coefficient(ms: u32, sample_rate: u32) -> f32 {
+    (-1.0 / (ms.max(1) as f32 / 1000.0 * sample_rate.max(1) as f32)).exp()
+}
+
+/// Smoothed mic gain following whether sounds are playing
+pub struct MicDucker {
+    settings: DuckingSettings,
+    sample_rate: u32,
+    depth: f32,
+    attack: f32,
+    release: f32,
+    gain: f32,
+}
+
+impl MicDucker {
+    pub fn new(settings: DuckingSettings, sample_rate: u32) -> Self {
+        let mut ducker = Self {
+            settings,
+            sample_rate,
+            depth: 1.0,
+            attack: 0.0,
+            release: 0.0,
+            gain: 1.0,
+        };
+        ducker.set_settings(settings);
+        ducker
+    }
+
+    pub fn settings(&self) -> DuckingSettings {
+        self.settings
+    }
+
+    /// Change the settings; the current gain glides to the new depth
+    pub fn set_settings(&mut self, settings: DuckingSettings) {
+        self.settings = settings;
+        self.depth = db_to_linear(settings.depth_db.min(0.0));
+        self.set_sample_rate(self.sample_rate);
+    }
+
+    /// Recompute time constants for a new sample rate
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+        self.attack = coefficient(self.settings.attack_ms, sample_rate);
+        self.release = coefficient(self.settings.release_ms, sample_rate);
+    }
+
+    /// Gain of the next frame, ducked while `active`
+    pub fn next_gain(&mut self, active: bool) -> f32 {
+        let (target, smoothing) = if active && self.settings.enabled {
+            (self.depth, self.attack)
+        } else {
+            (1.0, self.release)
+        };
+        self.gain = smoothing * self.gain + (1.0 - smoothing) * target;
+        self.gain
+    }
+
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> DuckingSettings {
+        DuckingSettings {
+            enabled: true,
+            depth_db: -20.0,
+            attack_ms: 10,
+            release_ms: 100,
+        }
+    }
+
+    #[test]
+    fn test_ducks_with_attack_and_recovers_with_release() {
+        let mut ducker = MicDucker::new(settings(), 1000);
+
+        // 50 ms is five attack time constants: down to the depth
+        let gains: Vec<f32> = (0..50).map(|_| ducker.next_gain(true)).collect();
+        assert!(gains.windows(2).all(|w| w[1] < w[0]));
+        assert!((ducker.gain() - 0.1).abs() < 0.01);
+
+        // After 50 ms of release, still well under unity
+        for _ in 0..50 {
+            ducker.next_gain(false);
+        }
+        assert!(ducker.gain() > 0.4 && ducker.gain() < 0.8);
+        for _ in 0..1000 {
+            ducker.next_gain(false);
+        }
+        assert!((ducker.gain() - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_disabled_ducker_stays_at_unity() {
+        let mut ducker = MicDucker::new(DuckingSettings { enabled: false, ..settings() }, 1000);
+        for _ in 0..100 {
+            assert!((ducker.next_gain(true) - 1.0).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Acoustic echo canceller - Removes speaker playback picked up by the mic
+
+/// Adaptive filter length; covers ~43 ms of echo path at 48 kHz
+pub const ECHO_CANCELLER_TAPS: usize = 2048;
+
+/// NLMS step size; smaller adapts slower but is more stable during double-talk
+const STEP_SIZE: f32 = 0.2;
+
+/// Regularization so silence in the reference doesn't blow up the update
+const REGULARIZATION: f32 = 1e-3;
+
+/// NLMS adaptive filter estimating the echo of a reference signal
+///
+/// The reference is what the speakers play (mono, one value per frame).
+/// The estimated echo is subtracted from every mic channel.
+pub struct EchoCanceller {
+    weights: Vec<f32>,
+    /// Reference history, newest at `position`, stored twice for contiguous reads
+    history: Vec<f32>,
+    position: usize,
+    energy: f32,
+}
+
+impl EchoCanceller {
+    pub fn new(taps: usize) -> Self {
+        let taps = taps.max(1);
+        Self {
+            weights: vec![0.0; taps],
+            history: vec![0.0; taps * 2],
+            position: 0,
+            energy: 0.0,
+        }
+    }
+
+    pub fn taps(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Cancel echo in interleaved mic samples, given one reference value per frame
+    ///
+    /// Missing reference values are treated as silence.
+    pub fn process(&mut self, data: &mut [f32], channels: usize, reference: &[f32]) {
+        let channels = channels.max(1);
+        for (i, frame) in data.chunks_exact_mut(channels).enumerate() {
+            let x = reference.get(i).copied().unwrap_or(0.0);
+            let echo = self.push_reference(x);
+
+            let mut error_sum = 0.0;
+            for sample in frame.iter_mut() {
+                *sample -= echo;
+                error_sum += *sample;
+            }
+            self.adapt(error_sum / channels as f32);
+        }
+    }
+
+    /// Add a reference sample and return the current echo estimate
+    fn push_reference(&mut self, x: f32) -> f32 {
+        let taps = self.weights.len();
+        let oldest = self.history[self.position + taps - 1];
+        self.energy = (self.energy + x * x - oldest * oldest).max(0.0);
+
+        self.position = if self.position == 0 { taps - 1 } else { self.position - 1 };
+        self.history[self.position] = x;
+        self.history[self.position + taps] = x;
+
+        let recent = &self.history[self.position..self.position + taps];
+        self.weights.iter().zip(recent).map(|(w, x)| w * x).sum()
+    }
+
+    fn adapt(&mut self, error: f32) {
+        let taps = self.weights.len();
+        let step = STEP_SIZE * error / (self.energy + REGULARIZATION);
+        let recent = &self.history[self.position..self.position + taps];
+        for (w, x) in self.weights.iter_mut().zip(recent) {
+            *w += step * x;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converges_on_delayed_echo() {
+        let mut state = 0x9E37_79B9u32;
+        let reference: Vec<f32> = (0..24000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as f32 / u32::MAX as f32 - 0.5
+            })
+            .collect();
+
+        // Echo path: 10 samples of delay at half volume
+        let mut mic: Vec<f32> = (0..reference.len())
+            .map(|n| if n >= 10 { reference[n - 10] * 0.5 } else { 0.0 })
+            .collect();
+
+        let mut aec = EchoCanceller::new(64);
+        aec.process(&mut mic, 1, &reference);
+
+        let tail = &mic[20000..];
+        let residual = (tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32).sqrt();
+        assert!(residual < 0.01, "residual {}", residual);
+    }
+}
@@ -0,0 +1,774 @@
+//! Mic effect chain - The processing stages applied to the microphone
+
+use super::{
+    AutomaticGainControl, EchoCanceller, HighpassFilter, KeystrokeClock, KeystrokeGate, NoiseGate,
+    SpectralDenoiser, VoiceChanger, WorkerOffload,
+};
+use crate::domain::{
+    AgcSettings, HighpassSettings, KeyboardSuppressionSettings, MicChainLayout, MicEffectKind,
+    NoiseGateSettings, NoiseProfile, SpectralQuality, VoiceChangerSettings,
+};
+use ringbuf::traits::{Consumer, Producer, Split};
+use ringbuf::{HeapCons, HeapProd, HeapRb};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Sample rate assumed until the engine reports the real one
+const DEFAULT_SAMPLE_RATE: u32 = 48_000;
+
+/// Channel count assumed until the first buffer (the engine runs in stereo)
+const DEFAULT_CHANNELS: usize = 2;
+
+/// Length of the crossfade when toggling bypass
+const BYPASS_CROSSFADE_MS: f32 = 20.0;
+
+/// Frames reserved for a dry signal and its delay line, enough for a
+/// large device buffer and the latency of every stage together, so the
+/// callback never grows them
+const DRY_RESERVE_FRAMES: usize = 8192;
+
+/// Changes that can wait for the next buffer; past that the editor
+/// applies them under the lock itself
+const UPDATE_CAPACITY: usize = 32;
+
+/// A configured effect
+enum Stage {
+    Highpass(HighpassFilter),
+    EchoCanceller(EchoCanceller),
+    Denoiser(SpectralDenoiser),
+    KeystrokeGate(KeystrokeGate),
+    NoiseGate(NoiseGate),
+    Agc(AutomaticGainControl),
+    VoiceChanger(VoiceChanger),
+    /// A heavy effect running on a worker thread
+    Offloaded(WorkerOffload),
+}
+
+impl Stage {
+    fn process(&mut self, data: &mut [f32], channels: usize, reference: &[f32]) {
+        match self {
+            Stage::Highpass(highpass) => highpass.process(data, channels),
+            Stage::EchoCanceller(echo_canceller) => {
+                echo_canceller.process(data, channels, reference)
+            }
+            Stage::Denoiser(denoiser) => denoiser.process(data, channels),
+            Stage::KeystrokeGate(gate) => gate.process(data, channels),
+            Stage::NoiseGate(gate) => gate.process(data, channels),
+            Stage::Agc(agc) => agc.process(data, channels),
+            Stage::VoiceChanger(changer) => changer.process(data, channels),
+            Stage::Offloaded(worker) => worker.process(data, channels),
+        }
+    }
+
+    /// Retune for a new stream; runs off the audio thread, so stages
+    /// size their per-channel state here
+    fn set_format(&mut self, sample_rate: u32, channels: usize) {
+        match self {
+            Stage::Highpass(highpass) => {
+                highpass.set_channels(channels);
+                highpass.set_sample_rate(sample_rate);
+            }
+            Stage::Denoiser(denoiser) => denoiser.set_channels(channels),
+            Stage::NoiseGate(gate) => gate.set_sample_rate(sample_rate),
+            Stage::KeystrokeGate(gate) => {
+                gate.set_channels(channels);
+                gate.set_sample_rate(sample_rate);
+            }
+            Stage::Agc(agc) => agc.set_sample_rate(sample_rate),
+            Stage::VoiceChanger(changer) => {
+                changer.set_channels(channels);
+                changer.set_sample_rate(sample_rate);
+            }
+            Stage::EchoCanceller(_) | Stage::Offloaded(_) => {}
+        }
+    }
+
+    fn latency(&self) -> usize {
+        match self {
+            Stage::Denoiser(denoiser) => denoiser.latency(),
+            Stage::KeystrokeGate(gate) => gate.latency(),
+            Stage::Offloaded(worker) => worker.latency(),
+            _ => 0,
+        }
+    }
+}
+
+/// A change built off the audio thread for the chain to swap in
+///
+/// Once applied it holds whatever it replaced, and goes back to the editor
+/// to be dropped there.
+enum ChainUpdate {
+    Stage(MicEffectKind, Option<Stage>),
+    Layout(MicChainLayout),
+    Bypass(bool),
+}
+
+/// One slot in the chain: an effect kind and its stage, if configured
+struct EffectNode {
+    kind: MicEffectKind,
+    enabled: bool,
+    /// 0 = dry, 1 = fully processed
+    mix: f32,
+    stage: Option<Stage>,
+    /// Dry signal delayed by the stage's latency, for partial mixes
+    dry_delay: VecDeque<f32>,
+    dry: Vec<f32>,
+}
+
+impl EffectNode {
+    fn new(kind: MicEffectKind) -> Self {
+        let mut node = Self {
+            kind,
+            enabled: true,
+            mix: 1.0,
+            stage: None,
+            dry_delay: VecDeque::new(),
+            dry: Vec::new(),
+        };
+        reserve_dry(&mut node.dry_delay, &mut node.dry, DEFAULT_CHANNELS);
+        node
+    }
+
+    fn is_active(&self) -> bool {
+        self.enabled && self.stage.is_some()
+    }
+
+    /// Run the stage, blending with the time-aligned dry signal
+    fn process(&mut self, data: &mut [f32], channels: usize, reference: &[f32]) {
+        let Some(stage) = self.stage.as_mut() else {
+            return;
+        };
+        if !self.enabled {
+            return;
+        }
+        if self.mix >= 1.0 {
+            stage.process(data, channels, reference);
+            return;
+        }
+
+        delay_into(
+            &mut self.dry_delay,
+            &mut self.dry,
+            data,
+            stage.latency() * channels,
+        );
+        stage.process(data, channels, reference);
+        for (sample, &dry) in data.iter_mut().zip(&self.dry) {
+            *sample = dry + (*sample - dry) * self.mix;
+        }
+    }
+}
+
+/// Make room in a delay line and its output for `channels` channels
+fn reserve_dry(line: &mut VecDeque<f32>, out: &mut Vec<f32>, channels: usize) {
+    line.reserve(DRY_RESERVE_FRAMES * channels);
+    out.reserve(DRY_RESERVE_FRAMES * channels);
+}
+
+/// Push `data` through a delay line of `delay` samples into `out`
+fn delay_into(line: &mut VecDeque<f32>, out: &mut Vec<f32>, data: &[f32], delay: usize) {
+    if line.len() != delay {
+        line.clear();
+        line.resize(delay, 0.0);
+    }
+
+    out.clear();
+    for &sample in data {
+        line.push_back(sample);
+        out.push(line.pop_front().unwrap_or(sample));
+    }
+}
+
+/// Processing applied to the mic before it reaches the mix
+///
+/// Effects run in the order of the chain layout, by default: rumble
+/// filter, echo cancellation, noise reduction, keyboard suppression, noise
+/// gate, automatic gain control, then the voice changer.
+/// Effects that are not configured or switched off cost nothing.
+///
+/// While bypassed the stages keep running, and the output crossfades to
+/// the dry signal delayed by the chain's latency, so toggling is click-free
+/// and A/B comparisons stay time-aligned.
+pub struct EffectChain {
+    sample_rate: u32,
+    /// Channel count of the stream the chain is set up for
+    channels: usize,
+    nodes: Vec<EffectNode>,
+    bypassed: bool,
+    /// Crossfade position: 0 = dry, 1 = processed
+    wet: f32,
+    /// Dry signal delayed to line up with the processed one
+    dry_delay: VecDeque<f32>,
+    dry: Vec<f32>,
+    /// Changes queued by the editor, applied before each buffer
+    updates: Option<HeapCons<ChainUpdate>>,
+    /// Applied changes on their way back to the editor
+    retired: Option<HeapProd<ChainUpdate>>,
+}
+
+impl EffectChain {
+    pub fn new() -> Self {
+        let mut chain = Self {
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            channels: DEFAULT_CHANNELS,
+            nodes: MicEffectKind::ALL
+                .into_iter()
+                .map(EffectNode::new)
+                .collect(),
+            bypassed: false,
+            wet: 1.0,
+            dry_delay: VecDeque::new(),
+            dry: Vec::new(),
+            updates: None,
+            retired: None,
+        };
+        reserve_dry(&mut chain.dry_delay, &mut chain.dry, DEFAULT_CHANNELS);
+        chain.set_layout(&MicChainLayout::default());
+        chain
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Retune the stages for a new stream's rate and channel count
+    pub fn set_format(&mut self, sample_rate: u32, channels: usize) {
+        self.sample_rate = sample_rate;
+        self.channels = channels.max(1);
+        reserve_dry(&mut self.dry_delay, &mut self.dry, self.channels);
+        for node in &mut self.nodes {
+            // Each node blends against its own delayed dry copy
+            reserve_dry(&mut node.dry_delay, &mut node.dry, self.channels);
+            if let Some(stage) = node.stage.as_mut() {
+                stage.set_format(sample_rate, self.channels);
+            }
+        }
+    }
+
+    /// Reorder and switch effects on or off, keeping their running state
+    ///
+    /// Nodes are swapped into place rather than rebuilt, so a layout change
+    /// neither allocates nor drops a stage.
+    fn set_layout(&mut self, layout: &MicChainLayout) {
+        for (index, node) in layout.nodes().iter().enumerate() {
+            let Some(found) = self
+                .nodes
+                .get(index..)
+                .and_then(|rest| rest.iter().position(|n| n.kind == node.kind))
+            else {
+                continue;
+            };
+            self.nodes.swap(index, index + found);
+            let slot = &mut self.nodes[index];
+            slot.enabled = node.enabled;
+            slot.mix = node.mix;
+        }
+    }
+
+    /// Kinds of the effects, in processing order
+    pub fn order(&self) -> Vec<MicEffectKind> {
+        self.nodes.iter().map(|n| n.kind).collect()
+    }
+
+    /// Swap a queued change in, leaving what it replaced in `update`
+    fn apply(&mut self, update: &mut ChainUpdate) {
+        match update {
+            ChainUpdate::Stage(kind, stage) => {
+                if let Some(node) = self.nodes.iter_mut().find(|n| n.kind == *kind) {
+                    std::mem::swap(&mut node.stage, stage);
+                }
+            }
+            ChainUpdate::Layout(layout) => self.set_layout(layout),
+            ChainUpdate::Bypass(bypassed) => self.bypassed = *bypassed,
+        }
+    }
+
+    /// Apply every change the editor queued, handing each back to it
+    fn apply_queued(&mut self) {
+        let Some(mut updates) = self.updates.take() else {
+            return;
+        };
+        while let Some(mut update) = updates.try_pop() {
+            self.apply(&mut update);
+            if let Some(retired) = self.retired.as_mut() {
+                // Sized like the queue, so this never drops on the audio thread
+                let _ = retired.try_push(update);
+            }
+        }
+        self.updates = Some(updates);
+    }
+
+    pub fn is_bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    /// Delay added by the active stages, in frames
+    pub fn latency(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|n| n.enabled)
+            .filter_map(|n| n.stage.as_ref())
+            .map(Stage::latency)
+            .sum()
+    }
+
+    /// Check if any stage is active
+    pub fn is_active(&self) -> bool {
+        self.nodes.iter().any(EffectNode::is_active)
+    }
+
+    /// Process interleaved mic samples in place
+    ///
+    /// `reference` is the mono speaker signal, one value per frame, used
+    /// by the echo canceller.
+    pub fn process(&mut self, data: &mut [f32], channels: usize, reference: &[f32]) {
+        self.apply_queued();
+        if !self.is_active() {
+            return;
+        }
+        let channels = channels.max(1);
+        self.delay_dry(data, channels);
+
+        for node in self.nodes.iter_mut() {
+            node.process(data, channels, reference);
+        }
+
+        self.crossfade(data, channels);
+    }
+
+    /// Copy `data` through the dry delay line into `self.dry`
+    fn delay_dry(&mut self, data: &[f32], channels: usize) {
+        let delay = self.latency() * channels;
+        delay_into(&mut self.dry_delay, &mut self.dry, data, delay);
+    }
+
+    /// Blend processed and dry signal, moving towards the bypass target
+    fn crossfade(&mut self, data: &mut [f32], channels: usize) {
+        let target = if self.bypassed { 0.0 } else { 1.0 };
+        if self.wet == target && target == 1.0 {
+            return;
+        }
+
+        let step = 1000.0 / (BYPASS_CROSSFADE_MS * self.sample_rate as f32);
+        for (frame, dry) in data.chunks_mut(channels).zip(self.dry.chunks(channels)) {
+            self.wet = if self.wet < target {
+                (self.wet + step).min(target)
+            } else {
+                (self.wet - step).max(target)
+            };
+            for (sample, &dry) in frame.iter_mut().zip(dry) {
+                *sample = dry + (*sample - dry) * self.wet;
+            }
+        }
+    }
+}
+
+impl Default for EffectChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Engine-side control of an [`EffectChain`]
+///
+/// Stages are built here, off the audio thread, and queued for the chain
+/// to swap in before its next buffer. What they replace comes back the
+/// same way and is dropped here, so a settings change never makes the
+/// audio callback wait, allocate or free.
+pub struct ChainEditor {
+    chain: Arc<Mutex<EffectChain>>,
+    updates: HeapProd<ChainUpdate>,
+    retired: HeapCons<ChainUpdate>,
+    sample_rate: u32,
+    channels: usize,
+    layout: MicChainLayout,
+    /// Settings of the stages sent so far; resending the same settings
+    /// keeps the running stage and its state
+    highpass: Option<HighpassSettings>,
+    keystroke_gate: Option<KeyboardSuppressionSettings>,
+    noise_gate: Option<NoiseGateSettings>,
+    agc: Option<AgcSettings>,
+    voice_changer: Option<VoiceChangerSettings>,
+    /// Noise print, quality and channel count of the offloaded denoiser
+    denoiser: Option<(NoiseProfile, SpectralQuality, usize)>,
+    /// Delay of the stage sent for each effect, in frames
+    latencies: HashMap<MicEffectKind, usize>,
+}
+
+impl ChainEditor {
+    /// Take over the changes of `chain`
+    pub fn new(chain: Arc<Mutex<EffectChain>>) -> Self {
+        let (updates, queued) = HeapRb::new(UPDATE_CAPACITY).split();
+        let (applied, retired) = HeapRb::new(UPDATE_CAPACITY).split();
+        let (sample_rate, channels) = match chain.lock() {
+            Ok(mut chain) => {
+                chain.updates = Some(queued);
+                chain.retired = Some(applied);
+                (chain.sample_rate, chain.channels)
+            }
+            Err(_) => (DEFAULT_SAMPLE_RATE, DEFAULT_CHANNELS),
+        };
+        Self {
+            chain,
+            updates,
+            retired,
+            sample_rate,
+            channels,
+            layout: MicChainLayout::default(),
+            highpass: None,
+            keystroke_gate: None,
+            noise_gate: None,
+            agc: None,
+            voice_changer: None,
+            denoiser: None,
+            latencies: HashMap::new(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Retune the chain for a new stream's rate and channel count
+    ///
+    /// Takes the chain's lock; call it while the stream is being built,
+    /// not while it runs.
+    pub fn set_format(&mut self, sample_rate: u32, channels: usize) {
+        self.sample_rate = sample_rate;
+        self.channels = channels.max(1);
+        if let Ok(mut chain) = self.chain.lock() {
+            // Stages still queued are retuned with the rest
+            chain.apply_queued();
+            chain.set_format(sample_rate, self.channels);
+        }
+        self.drop_retired();
+
+        // The offloaded denoiser can't be retuned, only rebuilt
+        if let Some((profile, quality, _)) = self.denoiser.clone() {
+            self.set_noise_profile(Some(profile), quality);
+        }
+    }
+
+    /// Reorder and switch effects on or off, keeping their running state
+    pub fn set_layout(&mut self, layout: &MicChainLayout) {
+        self.layout = layout.clone();
+        self.send(ChainUpdate::Layout(layout.clone()));
+    }
+
+    /// Bypass the whole chain, crossfading to the latency-compensated dry signal
+    pub fn set_bypassed(&mut self, bypassed: bool) {
+        self.send(ChainUpdate::Bypass(bypassed));
+    }
+
+    /// Enable the rumble filter with the given settings, or disable it
+    pub fn set_highpass(&mut self, settings: Option<HighpassSettings>) {
+        let settings = settings.filter(|s| s.enabled);
+        if settings == self.highpass {
+            return;
+        }
+        self.highpass = settings;
+        let stage = settings
+            .map(|s| Stage::Highpass(HighpassFilter::new(s, self.sample_rate, self.channels)));
+        self.set_stage(MicEffectKind::Highpass, stage);
+    }
+
+    pub fn set_echo_canceller(&mut self, echo_canceller: Option<EchoCanceller>) {
+        self.set_stage(
+            MicEffectKind::EchoCancellation,
+            echo_canceller.map(Stage::EchoCanceller),
+        );
+    }
+
+    pub fn set_denoiser(&mut self, denoiser: Option<SpectralDenoiser>) {
+        self.denoiser = None;
+        self.set_stage(MicEffectKind::NoiseReduction, denoiser.map(Stage::Denoiser));
+    }
+
+    /// Run the spectral denoiser on a worker thread with the given noise
+    /// print, or switch it off
+    ///
+    /// The worker adds [`OFFLOAD_LATENCY_FRAMES`](super::OFFLOAD_LATENCY_FRAMES)
+    /// on top of the FFT and its ring buffers are sized for one channel
+    /// count, so it is only rebuilt when the print, the quality or the
+    /// channel count changes.
+    pub fn set_noise_profile(&mut self, profile: Option<NoiseProfile>, quality: SpectralQuality) {
+        let wanted = profile
+            .filter(|p| p.is_valid())
+            .map(|p| (p, quality, self.channels));
+        if wanted == self.denoiser {
+            return;
+        }
+        let worker = wanted.as_ref().map(|(profile, quality, channels)| {
+            let denoiser = SpectralDenoiser::with_quality(profile, *quality, *channels);
+            WorkerOffload::spawn(Box::new(denoiser), *channels)
+        });
+        self.denoiser = wanted;
+        self.set_stage(MicEffectKind::NoiseReduction, worker.map(Stage::Offloaded));
+    }
+
+    /// Enable keyboard suppression, gated by the presses of `keystrokes`,
+    /// or disable it
+    pub fn set_keystroke_gate(
+        &mut self,
+        settings: Option<KeyboardSuppressionSettings>,
+        keystrokes: &KeystrokeClock,
+    ) {
+        let settings = settings.filter(|s| s.enabled);
+        if settings == self.keystroke_gate {
+            return;
+        }
+        self.keystroke_gate = settings;
+        let stage = settings.map(|s| {
+            Stage::KeystrokeGate(KeystrokeGate::new(
+                s,
+                self.sample_rate,
+                self.channels,
+                keystrokes.clone(),
+            ))
+        });
+        self.set_stage(MicEffectKind::KeyboardSuppression, stage);
+    }
+
+    /// Enable the noise gate with the given settings, or disable it
+    pub fn set_noise_gate(&mut self, settings: Option<NoiseGateSettings>) {
+        let settings = settings.filter(|s| s.enabled);
+        if settings == self.noise_gate {
+            return;
+        }
+        self.noise_gate = settings;
+        let stage = settings.map(|s| Stage::NoiseGate(NoiseGate::new(s, self.sample_rate)));
+        self.set_stage(MicEffectKind::NoiseGate, stage);
+    }
+
+    /// Enable AGC with the given settings, or disable it
+    pub fn set_agc(&mut self, settings: Option<AgcSettings>) {
+        let settings = settings.filter(|s| s.enabled);
+        if settings == self.agc {
+            return;
+        }
+        self.agc = settings;
+        let stage = settings.map(|s| Stage::Agc(AutomaticGainControl::new(s, self.sample_rate)));
+        self.set_stage(MicEffectKind::Agc, stage);
+    }
+
+    /// Enable the voice changer with the given settings, or disable it
+    pub fn set_voice_changer(&mut self, settings: Option<VoiceChangerSettings>) {
+        let settings = settings.filter(|s| s.is_active());
+        if settings == self.voice_changer {
+            return;
+        }
+        self.voice_changer = settings;
+        let stage = settings
+            .map(|s| Stage::VoiceChanger(VoiceChanger::new(s, self.sample_rate, self.channels)));
+        self.set_stage(MicEffectKind::VoiceChanger, stage);
+    }
+
+    /// Delay added by the active stages, in frames
+    ///
+    /// Read from what was sent, so it never touches the chain's lock.
+    pub fn latency(&self) -> usize {
+        self.layout
+            .nodes()
+            .iter()
+            .filter(|n| n.enabled)
+            .filter_map(|n| self.latencies.get(&n.kind))
+            .sum()
+    }
+
+    /// Drop the stages the chain has swapped out
+    pub fn drop_retired(&mut self) {
+        self.retired.clear();
+    }
+
+    fn set_stage(&mut self, kind: MicEffectKind, stage: Option<Stage>) {
+        match &stage {
+            Some(stage) => self.latencies.insert(kind, stage.latency()),
+            None => self.latencies.remove(&kind),
+        };
+        self.send(ChainUpdate::Stage(kind, stage));
+    }
+
+    fn send(&mut self, update: ChainUpdate) {
+        self.drop_retired();
+        let Err(mut update) = self.updates.try_push(update) else {
+            return;
+        };
+
+        // Nothing is draining the queue (no stream running), so the lock
+        // is free: apply what is queued, in order, then this change
+        if let Ok(mut chain) = self.chain.lock() {
+            chain.apply_queued();
+            chain.apply(&mut update);
+        }
+        self.drop_retired();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MicEffectNode, NoiseProfile};
+
+    /// A mono chain and its editor, as the engine sets them up
+    fn chain() -> (Arc<Mutex<EffectChain>>, ChainEditor) {
+        let chain = Arc::new(Mutex::new(EffectChain::new()));
+        let mut editor = ChainEditor::new(chain.clone());
+        editor.set_format(DEFAULT_SAMPLE_RATE, 1);
+        (chain, editor)
+    }
+
+    fn process(chain: &Mutex<EffectChain>, data: &mut [f32]) {
+        chain.lock().unwrap().process(data, 1, &[]);
+    }
+
+    fn highpass() -> HighpassSettings {
+        HighpassSettings {
+            enabled: true,
+            ..HighpassSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_layout_reorders_and_switches_stages() {
+        let (chain, mut editor) = chain();
+        editor.set_highpass(Some(highpass()));
+
+        let mut layout = MicChainLayout::default();
+        layout.move_effect(0, 3).unwrap();
+        editor.set_layout(&layout);
+        // Queued changes land with the next buffer
+        process(&chain, &mut []);
+        assert_eq!(chain.lock().unwrap().order()[3], MicEffectKind::Highpass);
+        assert!(chain.lock().unwrap().is_active());
+
+        layout.set_enabled(3, false).unwrap();
+        editor.set_layout(&layout);
+        process(&chain, &mut []);
+        assert!(!chain.lock().unwrap().is_active());
+
+        let mut data = vec![0.5; 480];
+        process(&chain, &mut data);
+        assert!(data.iter().all(|&s| s == 0.5));
+
+        // Configuration survives being switched off
+        editor.set_layout(&MicChainLayout::from(vec![MicEffectNode::new(
+            MicEffectKind::Highpass,
+        )]));
+        process(&chain, &mut []);
+        assert!(chain.lock().unwrap().is_active());
+    }
+
+    #[test]
+    fn test_denoiser_is_rebuilt_for_a_new_channel_count() {
+        let (chain, mut editor) = chain();
+        let profile = NoiseProfile::new(256, vec![0.0; 129]);
+        editor.set_noise_profile(Some(profile), SpectralQuality::Balanced);
+        assert_eq!(editor.denoiser.as_ref().map(|d| d.2), Some(1));
+
+        editor.set_format(DEFAULT_SAMPLE_RATE, 2);
+        assert_eq!(editor.denoiser.as_ref().map(|d| d.2), Some(2));
+        process(&chain, &mut []);
+        assert!(chain.lock().unwrap().is_active());
+    }
+
+    #[test]
+    fn test_changes_apply_in_order_when_nothing_drains_them() {
+        let (chain, mut editor) = chain();
+
+        // Only the change past the queue's capacity bypasses; applied out
+        // of order, an earlier one would win
+        for n in 0..=UPDATE_CAPACITY {
+            editor.set_bypassed(n == UPDATE_CAPACITY);
+        }
+        assert!(chain.lock().unwrap().is_bypassed());
+
+        process(&chain, &mut []);
+        assert!(chain.lock().unwrap().is_bypassed());
+    }
+
+    #[test]
+    fn test_partial_mix_blends_with_dry_signal() {
+        let (chain, mut editor) = chain();
+        editor.set_highpass(Some(highpass()));
+        let mut layout = MicChainLayout::default();
+        layout.set_mix(0, 0.25).unwrap();
+        editor.set_layout(&layout);
+
+        // The filter removes DC, so a quarter-wet mix keeps three quarters of it
+        let mut data = vec![0.4; 48000];
+        process(&chain, &mut data);
+        assert!((data[47999] - 0.3).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_same_settings_keep_running_stage() {
+        let (chain, mut editor) = chain();
+        editor.set_highpass(Some(highpass()));
+
+        // Settle the filter on a DC offset
+        let mut data = vec![0.5; 48000];
+        process(&chain, &mut data);
+        assert!(data[47999].abs() < 0.01);
+
+        // A fresh filter would pass the step again; the settled one doesn't
+        editor.set_highpass(Some(highpass()));
+        let mut data = vec![0.5; 480];
+        process(&chain, &mut data);
+        assert!(data[0].abs() < 0.01);
+
+        editor.set_highpass(Some(HighpassSettings {
+            cutoff_hz: highpass().cutoff_hz * 2.0,
+            ..highpass()
+        }));
+        let mut data = vec![0.5; 480];
+        process(&chain, &mut data);
+        assert!(data[0] > 0.1);
+    }
+
+    #[test]
+    fn test_bypass_crossfades_to_dry_signal() {
+        let (chain, mut editor) = chain();
+        editor.set_highpass(Some(highpass()));
+        editor.set_bypassed(true);
+
+        let mut data = vec![0.5; 4800];
+        process(&chain, &mut data);
+
+        // Ramps from processed to dry, then passes the input through
+        assert!(data[0] < 0.5);
+        assert!(data[960..].iter().all(|&s| (s - 0.5).abs() < 1e-6));
+
+        editor.set_bypassed(false);
+        let mut data = vec![0.5; 4800];
+        process(&chain, &mut data);
+        assert!((data[0] - 0.5).abs() < 1e-3);
+        assert!(data[4799].abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bypass_is_latency_compensated() {
+        let (chain, mut editor) = chain();
+        editor.set_denoiser(Some(SpectralDenoiser::new(
+            NoiseProfile::new(256, vec![0.0; 129]),
+            1,
+        )));
+        editor.set_bypassed(true);
+        assert_eq!(editor.latency(), 256);
+
+        let input: Vec<f32> = (0..4800).map(|n| (n as f32 * 0.01).sin()).collect();
+        let mut data = input.clone();
+        process(&chain, &mut data);
+
+        for n in 1000..4800 {
+            assert!((data[n] - input[n - 256]).abs() < 1e-6);
+        }
+    }
+}
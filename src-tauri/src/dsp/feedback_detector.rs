@@ -0,0 +1,158 @@
+//! Feedback detector - Spots the mic hearing its own output
+//!
+//! Fed with the mic and output levels, block by block. A loop shows up as
+//! both levels climbing together for a second or more, or as both pinned
+//! near full scale once the loop has saturated; speech does neither.
+
+use std::collections::VecDeque;
+
+/// Level updates looked at, about a second at the engine's level rate
+const HISTORY: usize = 30;
+/// Rise over the history that counts as a growing loop
+const RISE_DB: f32 = 12.0;
+/// Drop between updates still counted as rising, for measurement jitter
+const JITTER_DB: f32 = 1.0;
+/// Updates allowed to break the rise
+const MAX_DIPS: usize = 2;
+/// A growing loop only matters once it is this loud
+const LOUD_DB: f32 = -24.0;
+/// Input and output levels must move this much alike
+const MIN_CORRELATION: f32 = 0.9;
+/// Level of a saturated loop, and how long it must last
+const HOWL_DB: f32 = -6.0;
+const HOWL_MS: u32 = 2000;
+/// Floor of the level history, so silence doesn't read as -inf
+const FLOOR_DB: f32 = -90.0;
+
+fn to_db(rms: f32) -> f32 {
+    (20.0 * rms.max(1e-9).log10()).max(FLOOR_DB)
+}
+
+/// Pearson correlation of two equally long series
+fn correlation(a: &VecDeque<f32>, b: &VecDeque<f32>) -> f32 {
+    let n = a.len() as f32;
+    let (mean_a, mean_b) = (a.iter().sum::<f32>() / n, b.iter().sum::<f32>() / n);
+    let (mut covariance, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return 0.0;
+    }
+    covariance / (var_a * var_b).sqrt()
+}
+
+/// Detector of a mic-to-output loop from level updates
+pub struct FeedbackDetector {
+    input_db: VecDeque<f32>,
+    output_db: VecDeque<f32>,
+    howl_ms: u32,
+}
+
+impl FeedbackDetector {
+    pub fn new() -> Self {
+        Self {
+            input_db: VecDeque::with_capacity(HISTORY + 1),
+            output_db: VecDeque::with_capacity(HISTORY + 1),
+            howl_ms: 0,
+        }
+    }
+
+    /// Feed the RMS levels of the last `elapsed_ms`; true when a loop is
+    /// detected, after which the detector starts over
+    pub fn update(&mut self, input_rms: f32, output_rms: f32, elapsed_ms: u32) -> bool {
+        let (input, output) = (to_db(input_rms), to_db(output_rms));
+        self.input_db.push_back(input);
+        self.output_db.push_back(output);
+        if self.input_db.len() > HISTORY {
+            self.input_db.pop_front();
+            self.output_db.pop_front();
+        }
+
+        self.howl_ms = if input >= HOWL_DB && output >= HOWL_DB {
+            self.howl_ms.saturating_add(elapsed_ms)
+        } else {
+            0
+        };
+
+        let detected = self.howl_ms >= HOWL_MS || self.is_growing();
+        if detected {
+            self.reset();
+        }
+        detected
+    }
+
+    /// Both levels climbing steadily together up to a loud level
+    fn is_growing(&self) -> bool {
+        if self.input_db.len() < HISTORY {
+            return false;
+        }
+        let (first, last) = (self.input_db[0], self.input_db[HISTORY - 1]);
+        let dips = self
+            .input_db
+            .iter()
+            .zip(self.input_db.iter().skip(1))
+            .filter(|(before, after)| **after < **before - JITTER_DB)
+            .count();
+
+        last - first >= RISE_DB
+            && last >= LOUD_DB
+            && dips <= MAX_DIPS
+            && correlation(&self.input_db, &self.output_db) >= MIN_CORRELATION
+    }
+
+    pub fn reset(&mut self) {
+        self.input_db.clear();
+        self.output_db.clear();
+        self.howl_ms = 0;
+    }
+}
+
+impl Default for FeedbackDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(db: f32) -> f32 {
+        10f32.powf(db / 20.0)
+    }
+
+    #[test]
+    fn test_growing_loop_is_detected() {
+        let mut detector = FeedbackDetector::new();
+        // 1 dB louder every update, the output a little above the mic
+        let detected = (0..40).any(|i| {
+            let db = -50.0 + i as f32;
+            detector.update(level(db), level(db + 3.0), 33)
+        });
+        assert!(detected);
+    }
+
+    #[test]
+    fn test_saturated_loop_is_detected() {
+        let mut detector = FeedbackDetector::new();
+        let updates = (0..100).position(|_| detector.update(0.9, 0.95, 33)).unwrap();
+        assert_eq!(updates, 60);
+    }
+
+    #[test]
+    fn test_speech_is_not_feedback() {
+        let mut detector = FeedbackDetector::new();
+        // Syllables: loud and quiet in turn, for ten seconds
+        for i in 0..300 {
+            let db = if i % 6 < 3 { -12.0 } else { -40.0 };
+            assert!(!detector.update(level(db), level(db), 33));
+        }
+        // A loud mic with a quiet output isn't a loop either
+        for _ in 0..100 {
+            assert!(!detector.update(0.9, 0.01, 33));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! High-pass (low-cut) filter - Removes rumble below the voice
+
+use crate::domain::{FilterSlope, HighpassSettings};
+use std::f32::consts::PI;
+
+/// Normalized biquad coefficients (a0 = 1)
+#[derive(Debug, Clone, Copy)]
+struct Section {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Section {
+    /// First-order high-pass (6 dB/octave)
+    fn first_order(cutoff: f32, sample_rate: f32) -> Self {
+        let k = (PI * cutoff / sample_rate).tan();
+        let b0 = 1.0 / (1.0 + k);
+        Self {
+            b0,
+            b1: -b0,
+            b2: 0.0,
+            a1: (k - 1.0) / (k + 1.0),
+            a2: 0.0,
+        }
+    }
+
+    /// Second-order high-pass (12 dB/octave) with quality `q`
+    fn second_order(cutoff: f32, sample_rate: f32, q: f32) -> Self {
+        let w0 = 2.0 * PI * cutoff / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 + cos) / 2.0 / a0,
+            b1: -(1.0 + cos) / a0,
+            b2: (1.0 + cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+}
+
+/// Butterworth high-pass with a selectable slope
+pub struct HighpassFilter {
+    settings: HighpassSettings,
+    sections: Vec<Section>,
+    channels: usize,
+    /// Transposed direct form II state per channel and section
+    state: Vec<[f32; 2]>,
+}
+
+impl HighpassFilter {
+    /// A filter for audio with `channels` channels
+    pub fn new(settings: HighpassSettings, sample_rate: u32, channels: usize) -> Self {
+        let mut filter = Self {
+            settings,
+            sections: Vec::new(),
+            channels: channels.max(1),
+            state: Vec::new(),
+        };
+        filter.set_sample_rate(sample_rate);
+        filter
+    }
+
+    pub fn settings(&self) -> HighpassSettings {
+        self.settings
+    }
+
+    /// Recompute coefficients for a new sample rate
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        let rate = sample_rate.max(1) as f32;
+        let cutoff = self
+            .settings
+            .cutoff_hz
+            .clamp(HighpassSettings::MIN_CUTOFF_HZ, HighpassSettings::MAX_CUTOFF_HZ)
+            .min(rate * 0.45);
+
+        self.sections = match self.settings.slope {
+            FilterSlope::Db6 => vec![Section::first_order(cutoff, rate)],
+            FilterSlope::Db12 => vec![Section::second_order(cutoff, rate, std::f32::consts::FRAC_1_SQRT_2)],
+            // 4th-order Butterworth as two biquads
+            FilterSlope::Db24 => vec![
+                Section::second_order(cutoff, rate, 0.541_196_1),
+                Section::second_order(cutoff, rate, 1.306_563),
+            ],
+        };
+        self.reset_state();
+    }
+
+    /// Resize the filter state for a new channel count, clearing it
+    pub fn set_channels(&mut self, channels: usize) {
+        self.channels = channels.max(1);
+        self.reset_state();
+    }
+
+    fn reset_state(&mut self) {
+        self.state = vec![[0.0; 2]; self.channels * self.sections.len()];
+    }
+
+    /// Filter interleaved samples in place
+    ///
+    /// Channels beyond those the filter was built for pass through.
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+        let sections = self.sections.len();
+
+        for frame in data.chunks_exact_mut(channels) {
+            for (ch, sample) in frame.iter_mut().enumerate().take(self.channels) {
+                let mut x = *sample;
+                for (s, section) in self.sections.iter().enumerate() {
+                    let z = &mut self.state[ch * sections + s];
+                    let y = section.b0 * x + z[0];
+                    z[0] = section.b1 * x - section.a1 * y + z[1];
+                    z[1] = section.b2 * x - section.a2 * y;
+                    x = y;
+                }
+                *sample = x;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(slope: FilterSlope) -> HighpassSettings {
+        HighpassSettings {
+            enabled: true,
+            cutoff_hz: 100.0,
+            slope,
+        }
+    }
+
+    fn sine_rms_after(filter: &mut HighpassFilter, freq: f32) -> f32 {
+        let mut data: Vec<f32> = (0..48000)
+            .map(|n| (2.0 * PI * freq * n as f32 / 48000.0).sin())
+            .collect();
+        filter.process(&mut data, 1);
+        let tail = &data[24000..];
+        (tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_removes_dc_and_keeps_voice() {
+        for slope in [FilterSlope::Db6, FilterSlope::Db12, FilterSlope::Db24] {
+            let mut filter = HighpassFilter::new(settings(slope), 48000, 1);
+            let mut dc = vec![0.5; 48000];
+            filter.process(&mut dc, 1);
+            assert!(dc[47999].abs() < 1e-3);
+
+            let mut filter = HighpassFilter::new(settings(slope), 48000, 1);
+            let voice = sine_rms_after(&mut filter, 1000.0);
+            assert!((voice - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_steeper_slopes_cut_more_rumble() {
+        let rumble: Vec<f32> = [FilterSlope::Db6, FilterSlope::Db12, FilterSlope::Db24]
+            .into_iter()
+            .map(|slope| sine_rms_after(&mut HighpassFilter::new(settings(slope), 48000, 1), 25.0))
+            .collect();
+
+        assert!(rumble[0] > rumble[1] && rumble[1] > rumble[2]);
+    }
+}
@@ -0,0 +1,263 @@
+//! Keystroke gate - Ducks keyboard clatter in the mic around key presses
+//!
+//! Instead of guessing clicks from the audio alone, the gate is told when
+//! a key went down. For a short window after each press it clamps the
+//! bins above the voice band that jump over their usual level; between
+//! presses it only learns that level, so speech is left untouched.
+
+use super::{scale_bin, Stft};
+use crate::domain::KeyboardSuppressionSettings;
+use rustfft::num_complex::Complex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Short frames, so a click isn't smeared over the words around it
+const FFT_SIZE: usize = 256;
+const OVERLAP: usize = 4;
+/// Bins below this are left alone: voice dominates there and clicks
+/// carry little energy
+const MIN_FREQUENCY_HZ: f32 = 1_000.0;
+/// Per-hop smoothing of the learned bin levels
+const LEVEL_SMOOTHING: f32 = 0.95;
+/// A bin this many times above its learned level is taken for a click
+const TRANSIENT_RATIO: f32 = 2.0;
+
+/// Count of key presses, bumped by the keyboard hook and read by the
+/// audio callback
+#[derive(Debug, Clone, Default)]
+pub struct KeystrokeClock(Arc<AtomicU32>);
+
+impl KeystrokeClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a key press
+    pub fn press(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Presses recorded so far (wraps around)
+    pub fn presses(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// One channel: its STFT and the learned level of each bin
+struct GateChannel {
+    stft: Stft,
+    levels: Vec<f32>,
+    /// Whether `levels` holds a measured hop yet
+    seeded: bool,
+}
+
+impl GateChannel {
+    fn new() -> Self {
+        Self {
+            stft: Stft::with_overlap(FFT_SIZE, OVERLAP),
+            levels: vec![0.0; FFT_SIZE / 2 + 1],
+            seeded: false,
+        }
+    }
+}
+
+/// Spectral gate opened by key presses
+pub struct KeystrokeGate {
+    settings: KeyboardSuppressionSettings,
+    keystrokes: KeystrokeClock,
+    last_presses: u32,
+    depth: f32,
+    window_frames: usize,
+    min_bin: usize,
+    /// Frames left in the current window
+    remaining: usize,
+    channels: Vec<GateChannel>,
+}
+
+impl KeystrokeGate {
+    pub fn new(
+        settings: KeyboardSuppressionSettings,
+        sample_rate: u32,
+        channels: usize,
+        keystrokes: KeystrokeClock,
+    ) -> Self {
+        let mut gate = Self {
+            settings,
+            last_presses: keystrokes.presses(),
+            keystrokes,
+            depth: 10f32.powf(settings.depth_db / 20.0),
+            window_frames: 0,
+            min_bin: 0,
+            remaining: 0,
+            channels: Vec::new(),
+        };
+        gate.set_sample_rate(sample_rate);
+        gate.set_channels(channels);
+        gate
+    }
+
+    pub fn settings(&self) -> KeyboardSuppressionSettings {
+        self.settings
+    }
+
+    /// Recompute the window and the lowest gated bin for a new sample rate
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        let sample_rate = sample_rate.max(1);
+        self.window_frames = (self.settings.window_ms as u64 * sample_rate as u64 / 1000) as usize;
+        self.min_bin =
+            ((MIN_FREQUENCY_HZ * FFT_SIZE as f32 / sample_rate as f32).ceil() as usize).max(1);
+    }
+
+    /// Build the per-channel transforms for a stream of `channels`
+    ///
+    /// Done when the stage is set up, never from `process`, so the
+    /// callback doesn't allocate.
+    pub fn set_channels(&mut self, channels: usize) {
+        let channels = channels.max(1);
+        if self.channels.len() != channels {
+            self.channels = (0..channels).map(|_| GateChannel::new()).collect();
+        }
+    }
+
+    /// Delay added by the gate, in frames
+    pub fn latency(&self) -> usize {
+        FFT_SIZE
+    }
+
+    /// Gate interleaved samples in place
+    ///
+    /// Adds `FFT_SIZE` samples of latency per channel. The key event
+    /// reaches the gate before the click has made it through the input
+    /// buffer, so a window starting at the press covers it. Channels
+    /// beyond those the gate was set up for pass through.
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+
+        let presses = self.keystrokes.presses();
+        if presses != self.last_presses {
+            self.last_presses = presses;
+            self.remaining = self.window_frames;
+        }
+
+        let (min_bin, depth) = (self.min_bin, self.depth);
+        for frame in data.chunks_exact_mut(channels) {
+            let active = self.remaining > 0;
+            self.remaining = self.remaining.saturating_sub(1);
+
+            for (sample, channel) in frame.iter_mut().zip(self.channels.iter_mut()) {
+                let GateChannel {
+                    stft,
+                    levels,
+                    seeded,
+                } = channel;
+                *sample = stft.process(*sample, &mut |spectrum| {
+                    gate_frame(spectrum, levels, seeded, active, min_bin, depth)
+                });
+            }
+        }
+    }
+}
+
+/// Clamp transient bins while a window is open, learn the levels otherwise
+///
+/// The first hop seeds the levels outright, so a press right after the
+/// gate starts isn't judged against silence.
+fn gate_frame(
+    spectrum: &mut [Complex<f32>],
+    levels: &mut [f32],
+    seeded: &mut bool,
+    active: bool,
+    min_bin: usize,
+    depth: f32,
+) {
+    if !*seeded {
+        *seeded = true;
+        for (level, bin) in levels.iter_mut().zip(spectrum.iter()).skip(min_bin) {
+            *level = bin.norm();
+        }
+        return;
+    }
+
+    for k in min_bin..levels.len() {
+        let magnitude = spectrum[k].norm();
+        if active {
+            let limit = levels[k] * TRANSIENT_RATIO;
+            if magnitude > limit {
+                scale_bin(spectrum, k, (limit / magnitude).max(depth));
+            }
+        } else {
+            levels[k] = LEVEL_SMOOTHING * levels[k] + (1.0 - LEVEL_SMOOTHING) * magnitude;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> KeyboardSuppressionSettings {
+        KeyboardSuppressionSettings {
+            enabled: true,
+            ..KeyboardSuppressionSettings::default()
+        }
+    }
+
+    /// Quiet hum, then a burst of clicky noise at `click_at`
+    fn hum_with_click(len: usize, click_at: usize) -> Vec<f32> {
+        let mut seed = 12345u32;
+        (0..len)
+            .map(|n| {
+                let hum = (n as f32 * 0.05).sin() * 0.05;
+                if (click_at..click_at + 96).contains(&n) {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    hum + ((seed >> 16) as f32 / 32768.0 - 1.0) * 0.8
+                } else {
+                    hum
+                }
+            })
+            .collect()
+    }
+
+    fn energy(samples: &[f32]) -> f32 {
+        samples.iter().map(|s| s * s).sum()
+    }
+
+    #[test]
+    fn test_untouched_without_keystrokes() {
+        let mut gate = KeystrokeGate::new(settings(), 48_000, 1, KeystrokeClock::new());
+        let input = hum_with_click(4800, 2400);
+        let mut data = input.clone();
+        gate.process(&mut data, 1);
+
+        for n in 0..input.len() - FFT_SIZE {
+            assert!((data[n + FFT_SIZE] - input[n]).abs() < 1e-4, "sample {}", n);
+        }
+    }
+
+    #[test]
+    fn test_click_after_press_is_ducked() {
+        let clock = KeystrokeClock::new();
+        let mut gate = KeystrokeGate::new(settings(), 48_000, 1, clock.clone());
+        let mut reference = KeystrokeGate::new(settings(), 48_000, 1, KeystrokeClock::new());
+        let input = hum_with_click(48_000, 24_000);
+
+        let (before, after) = input.split_at(24_000);
+        let mut gated = before.to_vec();
+        gate.process(&mut gated, 1);
+        let mut untouched = before.to_vec();
+        reference.process(&mut untouched, 1);
+
+        clock.press();
+        let mut gated = after.to_vec();
+        gate.process(&mut gated, 1);
+        let mut untouched = after.to_vec();
+        reference.process(&mut untouched, 1);
+
+        // The click leaves the gate FFT_SIZE samples later
+        let click = FFT_SIZE..FFT_SIZE + 96;
+        assert!(energy(&gated[click.clone()]) < energy(&untouched[click]) * 0.25);
+        // The hum after the window is left alone
+        let tail = 4_000..8_000;
+        assert!((energy(&gated[tail.clone()]) - energy(&untouched[tail])).abs() < 1e-3);
+    }
+}
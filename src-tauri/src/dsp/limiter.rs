@@ -0,0 +1,85 @@
+//! Peak limiter - Brick-wall ceiling for the operator's headphones
+
+/// How fast the gain recovers once the peak has passed
+const RELEASE_MS: f32 = 200.0;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Limiter linked across channels, with no lookahead so it adds no latency
+///
+/// The gain drops to the ceiling on the very sample that would exceed it,
+/// so the output never goes over; it only recovers over the release.
+pub struct PeakLimiter {
+    ceiling: f32,
+    release: f32,
+    gain: f32,
+}
+
+impl PeakLimiter {
+    pub fn new(ceiling_db: f32, sample_rate: u32) -> Self {
+        Self {
+            ceiling: db_to_linear(ceiling_db),
+            release: (-1.0 / (RELEASE_MS / 1000.0 * sample_rate.max(1) as f32)).exp(),
+            gain: 1.0,
+        }
+    }
+
+    pub fn set_ceiling_db(&mut self, ceiling_db: f32) {
+        self.ceiling = db_to_linear(ceiling_db);
+    }
+
+    /// Current gain reduction, 1.0 when not limiting
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Limit interleaved samples in place
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        for frame in data.chunks_mut(channels.max(1)) {
+            let peak = frame.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+            let target = if peak > self.ceiling { self.ceiling / peak } else { 1.0 };
+            self.gain = if target < self.gain {
+                target
+            } else {
+                target + (self.gain - target) * self.release
+            };
+            for sample in frame {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_never_exceeds_the_ceiling() {
+        let mut limiter = PeakLimiter::new(-6.0, 48_000);
+        let ceiling = db_to_linear(-6.0);
+        // A full-scale square wave, the airhorn case
+        let mut data: Vec<f32> = (0..9600).map(|i| if (i / 20) % 2 == 0 { 1.0 } else { -1.0 }).collect();
+        limiter.process(&mut data, 2);
+        assert!(data.iter().all(|sample| sample.abs() <= ceiling + 1e-6));
+        assert!(data.iter().any(|sample| sample.abs() > ceiling - 1e-3));
+    }
+
+    #[test]
+    fn test_quiet_audio_passes_and_gain_recovers() {
+        let mut limiter = PeakLimiter::new(-6.0, 48_000);
+        let mut quiet = vec![0.25; 480];
+        limiter.process(&mut quiet, 2);
+        assert!(quiet.iter().all(|sample| *sample == 0.25));
+
+        limiter.process(&mut [1.0, 1.0], 2);
+        assert!(limiter.gain() < 0.6);
+
+        // A second of quiet audio brings the gain back to unity
+        let mut quiet = vec![0.25; 96_000];
+        limiter.process(&mut quiet, 2);
+        assert!(limiter.gain() > 0.99);
+    }
+}
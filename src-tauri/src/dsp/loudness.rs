@@ -0,0 +1,162 @@
+//! Loudness - Integrated loudness (ITU-R BS.1770) of decoded sounds
+//!
+//! Measured once per sound, off the audio thread, so pads can be levelled
+//! against each other.
+
+/// Gating block length
+const BLOCK_MS: u32 = 400;
+/// Blocks overlap by 75%, so they start every quarter block
+const STEP_MS: u32 = BLOCK_MS / 4;
+/// Blocks quieter than this are ignored outright
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+/// Blocks this far below the ungated loudness are ignored
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Biquad in direct form I, in double precision for long sums
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+}
+
+/// The two K-weighting stages, head shelf then high-pass, for any rate
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let rate = sample_rate.max(1) as f64;
+
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [(vh + vb * k / q + k * k) / a0, 2.0 * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (std::f64::consts::PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    [shelf, highpass]
+}
+
+/// Weight of a channel in the sum: the LFE of a 5.1 layout is left out
+/// and the surrounds count more
+fn channel_weight(channel: usize, channels: usize) -> f64 {
+    match (channels, channel) {
+        (6.., 3) => 0.0,
+        (6.., 4 | 5) => 1.41,
+        _ => 1.0,
+    }
+}
+
+fn to_lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+/// Integrated loudness of interleaved samples in LUFS, None for silence
+///
+/// Sounds shorter than one gating block are measured as a single block,
+/// so short stingers still get a value.
+pub fn integrated_loudness(samples: &[f32], channels: usize, sample_rate: u32) -> Option<f32> {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    let step = (sample_rate as usize * STEP_MS as usize / 1000).max(1);
+    if frames == 0 {
+        return None;
+    }
+
+    // Weighted K-filtered energy of every 100 ms step
+    let mut filters: Vec<[Biquad; 2]> = (0..channels).map(|_| k_weighting(sample_rate)).collect();
+    let mut steps = Vec::with_capacity(frames / step + 1);
+    let mut energy = 0.0f64;
+    for (index, frame) in samples.chunks_exact(channels).enumerate() {
+        for (channel, (sample, [shelf, highpass])) in frame.iter().zip(filters.iter_mut()).enumerate() {
+            let weighted = highpass.process(shelf.process(*sample as f64));
+            energy += channel_weight(channel, channels) * weighted * weighted;
+        }
+        if (index + 1) % step == 0 {
+            steps.push(std::mem::take(&mut energy));
+        }
+    }
+
+    // Mean power of every 400 ms block
+    let per_block = (BLOCK_MS / STEP_MS) as usize;
+    let blocks: Vec<f64> = if steps.len() < per_block {
+        let total: f64 = steps.iter().sum::<f64>() + energy;
+        vec![total / frames as f64]
+    } else {
+        steps
+            .windows(per_block)
+            .map(|window| window.iter().sum::<f64>() / (per_block * step) as f64)
+            .collect()
+    };
+
+    let gated_mean = |gate: f64| {
+        let passing: Vec<f64> = blocks.iter().copied().filter(|power| to_lufs(*power) > gate).collect();
+        (!passing.is_empty()).then(|| passing.iter().sum::<f64>() / passing.len() as f64)
+    };
+    let ungated = gated_mean(ABSOLUTE_GATE_LUFS)?;
+    let integrated = gated_mean(to_lufs(ungated) + RELATIVE_GATE_LU)?;
+    Some(to_lufs(integrated) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, amplitude: f32, seconds: f32, channels: usize, rate: u32) -> Vec<f32> {
+        let frames = (seconds * rate as f32) as usize;
+        (0..frames)
+            .flat_map(|i| {
+                let sample = amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / rate as f32).sin();
+                std::iter::repeat_n(sample, channels)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_full_scale_sine_reads_minus_three_lufs_per_channel() {
+        // The BS.1770 reference: a 0 dBFS 997 Hz sine on one channel
+        let mono = integrated_loudness(&sine(997.0, 1.0, 3.0, 1, 48_000), 1, 48_000).unwrap();
+        assert!((mono + 3.01).abs() < 0.1, "{}", mono);
+
+        let stereo = integrated_loudness(&sine(997.0, 1.0, 3.0, 2, 44_100), 2, 44_100).unwrap();
+        assert!(stereo.abs() < 0.1, "{}", stereo);
+    }
+
+    #[test]
+    fn test_gating_ignores_silence_and_short_sounds_still_measure() {
+        let rate = 48_000;
+        let mut samples = sine(997.0, 0.1, 2.0, 1, rate);
+        samples.extend(std::iter::repeat_n(0.0, rate as usize * 4));
+        // Ungated, the silence would pull this down to about -27.8
+        let gated = integrated_loudness(&samples, 1, rate).unwrap();
+        assert!((gated + 23.01).abs() < 0.5, "{}", gated);
+
+        let short = integrated_loudness(&sine(997.0, 0.1, 0.2, 1, rate), 1, rate).unwrap();
+        assert!((short + 23.01).abs() < 0.3, "{}", short);
+
+        assert_eq!(integrated_loudness(&vec![0.0; 96_000], 2, rate), None);
+    }
+}
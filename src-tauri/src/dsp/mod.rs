@@ -0,0 +1,58 @@
+//! DSP building blocks - Signal processing for the audio engine
+//!
+//! Everything here is allocation-free once constructed, so it can run
+//! inside the real-time audio callbacks.
+
+mod agc;
+mod broadcast_delay;
+mod channel_remix;
+mod codec_simulator;
+mod dither;
+mod ducker;
+mod echo_canceller;
+mod effect_chain;
+mod feedback_detector;
+mod highpass;
+mod keystroke_gate;
+mod limiter;
+mod loudness;
+mod noise_gate;
+mod output_ramp;
+mod resampler;
+mod signal_generator;
+mod sound_inserts;
+mod spectral_denoise;
+mod stereo_widener;
+mod stft;
+mod time_stretch;
+mod vocal_reducer;
+mod voice_activity;
+mod voice_changer;
+mod worker;
+
+pub use agc::*;
+pub use broadcast_delay::*;
+pub use channel_remix::*;
+pub use codec_simulator::*;
+pub use dither::*;
+pub use ducker::*;
+pub use echo_canceller::*;
+pub use effect_chain::*;
+pub use feedback_detector::*;
+pub use highpass::*;
+pub use keystroke_gate::*;
+pub use limiter::*;
+pub use loudness::*;
+pub use noise_gate::*;
+pub use output_ramp::*;
+pub use resampler::*;
+pub use signal_generator::*;
+pub use sound_inserts::*;
+pub use spectral_denoise::*;
+pub use stereo_widener::*;
+pub use stft::*;
+pub use time_stretch::*;
+pub use vocal_reducer::*;
+pub use voice_activity::*;
+pub use voice_changer::*;
+pub use worker::*;
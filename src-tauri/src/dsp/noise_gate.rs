@@ -0,0 +1,161 @@
+//! Noise gate - Silences the mic between phrases
+
+use crate::domain::NoiseGateSettings;
+
+/// Time constant of the level detector
+const DETECTOR_MS: f32 = 10.0;
+/// How fast the gate opens
+const ATTACK_MS: f32 = 1.0;
+/// How fast the gate closes once the hold has run out
+const RELEASE_MS: f32 = 80.0;
+/// Time the gate stays open after the level drops, so word endings survive
+const HOLD_MS: u32 = 150;
+/// The gate closes this far below the threshold, so it doesn't chatter
+const HYSTERESIS_DB: f32 = 4.0;
+/// Gain of the closed gate
+const FLOOR_DB: f32 = -80.0;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// One-pole smoothing coefficient for a time constant
+fn coefficient(ms: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (ms / 1000.0 * sample_rate.max(1) as f32)).exp()
+}
+
+/// Gate linked across channels, with hysteresis and a hold
+pub struct NoiseGate {
+    settings: NoiseGateSettings,
+    /// Detector power above which the gate opens
+    open_power: f32,
+    /// Detector power below which the gate starts closing
+    close_power: f32,
+    floor: f32,
+    detector: f32,
+    attack: f32,
+    release: f32,
+    hold_frames: u32,
+    power: f32,
+    gain: f32,
+    open: bool,
+    hold: u32,
+}
+
+impl NoiseGate {
+    pub fn new(settings: NoiseGateSettings, sample_rate: u32) -> Self {
+        let mut gate = Self {
+            settings,
+            open_power: db_to_linear(settings.threshold_db).powi(2),
+            close_power: db_to_linear(settings.threshold_db - HYSTERESIS_DB).powi(2),
+            floor: db_to_linear(FLOOR_DB),
+            detector: 0.0,
+            attack: 0.0,
+            release: 0.0,
+            hold_frames: 0,
+            power: 0.0,
+            gain: 0.0,
+            open: false,
+            hold: 0,
+        };
+        gate.set_sample_rate(sample_rate);
+        gate
+    }
+
+    pub fn settings(&self) -> NoiseGateSettings {
+        self.settings
+    }
+
+    /// Recompute time constants for a new sample rate
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.detector = coefficient(DETECTOR_MS, sample_rate);
+        self.attack = coefficient(ATTACK_MS, sample_rate);
+        self.release = coefficient(RELEASE_MS, sample_rate);
+        self.hold_frames = HOLD_MS * sample_rate / 1000;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Gate interleaved samples in place
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+        for frame in data.chunks_exact_mut(channels) {
+            let frame_power = frame.iter().map(|s| s * s).sum::<f32>() / channels as f32;
+            self.power = self.detector * self.power + (1.0 - self.detector) * frame_power;
+
+            if self.power > self.open_power {
+                self.open = true;
+                self.hold = self.hold_frames;
+            } else if self.power < self.close_power {
+                if self.hold > 0 {
+                    self.hold -= 1;
+                } else {
+                    self.open = false;
+                }
+            }
+
+            let (target, smoothing) = if self.open {
+                (1.0, self.attack)
+            } else {
+                (self.floor, self.release)
+            };
+            self.gain = smoothing * self.gain + (1.0 - smoothing) * target;
+
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(amplitude: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|n| (n as f32 * 0.05).sin() * amplitude)
+            .collect()
+    }
+
+    fn gate() -> NoiseGate {
+        NoiseGate::new(
+            NoiseGateSettings {
+                enabled: true,
+                threshold_db: -40.0,
+            },
+            48_000,
+        )
+    }
+
+    #[test]
+    fn test_quiet_noise_is_silenced() {
+        let mut gate = gate();
+        // About -60 dBFS RMS
+        let mut data = tone(0.0014, 48_000);
+        gate.process(&mut data, 1);
+
+        assert!(!gate.is_open());
+        assert!(data[24_000..].iter().all(|s| s.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_voice_passes_and_hold_keeps_the_tail() {
+        let mut gate = gate();
+        // About -20 dBFS RMS
+        let mut data = tone(0.14, 4800);
+        gate.process(&mut data, 1);
+        assert!(gate.is_open());
+        let last = data.len() - 1;
+        assert!((data[last] - tone(0.14, 4800)[last]).abs() < 1e-4);
+
+        // Still open shortly after the voice stops, closed after the hold
+        let mut silence = vec![0.0; 48_000];
+        gate.process(&mut silence[..2400], 1);
+        assert!(gate.is_open());
+        gate.process(&mut silence[2400..], 1);
+        assert!(!gate.is_open());
+    }
+}
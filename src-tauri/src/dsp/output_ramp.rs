@@ -0,0 +1,114 @@
+//! Output ramp - Fades the whole output in on start and out before stop
+//!
+//! Some drivers pop when a stream opens or closes on a non-zero sample.
+//! The ramp raises the master gain linearly from silence over the first
+//! moments of a stream, and lowers it back to silence once closing.
+//! Sounds use the same ramp to dip around a jump in their playhead.
+
+/// Linear master gain ramp, stepped once per frame
+pub struct OutputRamp {
+    /// Frames of a full ramp; 0 jumps straight to the target
+    length: u32,
+    /// Frames into the ramp, from 0 (silent) to `length` (unity)
+    position: u32,
+    closing: bool,
+}
+
+impl OutputRamp {
+    /// Ramp in from silence over `ramp_ms`
+    pub fn new(ramp_ms: u32, sample_rate: u32) -> Self {
+        Self {
+            length: (ramp_ms as u64 * sample_rate as u64 / 1000) as u32,
+            position: 0,
+            closing: false,
+        }
+    }
+
+    /// Already at unity, ramping only once closed
+    pub fn unity(ramp_ms: u32, sample_rate: u32) -> Self {
+        let mut ramp = Self::new(ramp_ms, sample_rate);
+        ramp.position = ramp.length;
+        ramp
+    }
+
+    /// Start ramping down to silence from the current gain
+    pub fn close(&mut self) {
+        self.closing = true;
+    }
+
+    /// Start ramping back up to unity from the current gain
+    pub fn reopen(&mut self) {
+        self.closing = false;
+    }
+
+    /// Whether every frame from here on has unity gain
+    pub fn is_open(&self) -> bool {
+        !self.closing && self.position >= self.length
+    }
+
+    /// Whether the ramp has closed all the way to silence
+    pub fn is_closed(&self) -> bool {
+        self.closing && self.position == 0
+    }
+
+    /// Gain of the next frame
+    pub fn next_gain(&mut self) -> f32 {
+        if self.length == 0 {
+            return if self.closing { 0.0 } else { 1.0 };
+        }
+        if self.closing {
+            self.position = self.position.saturating_sub(1);
+        } else if self.position < self.length {
+            self.position += 1;
+        }
+        self.position as f32 / self.length as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramps_in_then_out() {
+        // 10 ms at 1 kHz: ten frames each way
+        let mut ramp = OutputRamp::new(10, 1000);
+        let gains: Vec<f32> = (0..10).map(|_| ramp.next_gain()).collect();
+        assert!(gains.windows(2).all(|w| w[1] > w[0]));
+        assert!(gains[0] > 0.0 && gains[0] < 0.2);
+        assert_eq!(gains[9], 1.0);
+        assert!(ramp.is_open());
+
+        ramp.close();
+        assert!(!ramp.is_open());
+        let gains: Vec<f32> = (0..12).map(|_| ramp.next_gain()).collect();
+        assert!(gains[0] < 1.0);
+        assert_eq!(gains[9], 0.0);
+        assert_eq!(gains[11], 0.0);
+    }
+
+    #[test]
+    fn test_reopens_from_silence() {
+        let mut ramp = OutputRamp::unity(4, 1000);
+        assert!(ramp.is_open());
+        ramp.close();
+        let gains: Vec<f32> = (0..4).map(|_| ramp.next_gain()).collect();
+        assert_eq!(gains, vec![0.75, 0.5, 0.25, 0.0]);
+        assert!(ramp.is_closed());
+
+        ramp.reopen();
+        assert!(!ramp.is_closed() && !ramp.is_open());
+        let gains: Vec<f32> = (0..4).map(|_| ramp.next_gain()).collect();
+        assert_eq!(gains, vec![0.25, 0.5, 0.75, 1.0]);
+        assert!(ramp.is_open());
+    }
+
+    #[test]
+    fn test_zero_length_is_a_switch() {
+        let mut ramp = OutputRamp::new(0, 48_000);
+        assert!(ramp.is_open());
+        assert_eq!(ramp.next_gain(), 1.0);
+        ramp.close();
+        assert_eq!(ramp.next_gain(), 0.0);
+    }
+}
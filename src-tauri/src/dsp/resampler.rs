@@ -0,0 +1,265 @@
+//! Resampler - Sample rate conversion of decoded sounds
+//!
+//! Sound files come at whatever rate they were exported with while the
+//! engine mixes at the device's. Sounds are converted once, when they are
+//! queued, not in the callback: rubato's FFT resampler runs over the whole
+//! buffer and its delay is trimmed, so the result lines up with the source.
+
+use rubato::{FftFixedIn, Resampler};
+
+/// Frames fed to the resampler per chunk
+const CHUNK_FRAMES: usize = 1024;
+
+/// Sub-chunks of the FFT resampler; more lowers the latency, not the quality
+const SUB_CHUNKS: usize = 2;
+
+/// Converts interleaved audio from one sample rate to another
+#[derive(Debug, Clone, Copy)]
+pub struct AudioResampler {
+    from: u32,
+    to: u32,
+    channels: usize,
+}
+
+impl AudioResampler {
+    pub fn new(from: u32, to: u32, channels: usize) -> Self {
+        Self {
+            from,
+            to,
+            channels: channels.max(1),
+        }
+    }
+
+    /// Whether samples come out unchanged (same or unknown rates)
+    pub fn is_passthrough(&self) -> bool {
+        self.from == self.to || self.from == 0 || self.to == 0
+    }
+
+    /// Frames `frames` input frames turn into
+    pub fn output_frames(&self, frames: usize) -> usize {
+        if self.is_passthrough() {
+            return frames;
+        }
+        (frames as f64 * self.to as f64 / self.from as f64).round() as usize
+    }
+
+    /// Convert interleaved `samples`, keeping their duration
+    pub fn process(&self, samples: &[f32]) -> Vec<f32> {
+        if self.is_passthrough() || samples.is_empty() {
+            return samples.to_vec();
+        }
+        let mut resampler =
+            match FftFixedIn::<f32>::new(self.from as usize, self.to as usize, CHUNK_FRAMES, SUB_CHUNKS, self.channels) {
+                Ok(resampler) => resampler,
+                Err(e) => {
+                    tracing::warn!("Cannot resample {} Hz to {} Hz: {}", self.from, self.to, e);
+                    return samples.to_vec();
+                }
+            };
+
+        let channels = self.channels;
+        let frames = samples.len() / channels;
+        let out_frames = self.output_frames(frames);
+        let delay = resampler.output_delay();
+
+        let planar: Vec<Vec<f32>> = (0..channels)
+            .map(|ch| samples.iter().skip(ch).step_by(channels).copied().collect())
+            .collect();
+        let mut output: Vec<Vec<f32>> = vec![Vec::with_capacity(delay + out_frames + CHUNK_FRAMES); channels];
+
+        let mut position = 0;
+        while position < frames {
+            let end = (position + resampler.input_frames_next()).min(frames);
+            let chunk: Vec<&[f32]> = planar.iter().map(|channel| &channel[position..end]).collect();
+            let result = if end - position == resampler.input_frames_next() {
+                resampler.process(&chunk, None)
+            } else {
+                resampler.process_partial(Some(&chunk), None)
+            };
+            match result {
+                Ok(chunk) => append(&mut output, chunk),
+                Err(_) => break,
+            }
+            position = end;
+        }
+        // Flush the tail still held back by the resampler's delay
+        while output[0].len() < delay + out_frames {
+            match resampler.process_partial::<&[f32]>(None, None) {
+                Ok(chunk) if !chunk[0].is_empty() => append(&mut output, chunk),
+                _ => break,
+            }
+        }
+
+        let mut out = Vec::with_capacity(out_frames * channels);
+        for i in delay..delay + out_frames {
+            out.extend(output.iter().map(|channel| channel.get(i).copied().unwrap_or(0.0)));
+        }
+        out
+    }
+}
+
+/// Converts interleaved audio block by block, for sounds decoded while
+/// they play
+///
+/// The resampler's filter state carries over from one block to the next,
+/// so blocks join without a seam. Its delay is trimmed from the start and
+/// [`Self::flush`] returns the tail, so the whole stream lines up with the
+/// source like [`AudioResampler::process`] does.
+pub struct StreamingResampler {
+    /// None when samples pass through unchanged
+    resampler: Option<FftFixedIn<f32>>,
+    rates: AudioResampler,
+    /// Input frames waiting for a full chunk, per channel
+    pending: Vec<Vec<f32>>,
+    /// Output frames still to drop for the resampler's delay
+    skip: usize,
+    frames_in: usize,
+    frames_out: usize,
+}
+
+impl StreamingResampler {
+    pub fn new(from: u32, to: u32, channels: usize) -> Self {
+        let rates = AudioResampler::new(from, to, channels);
+        let resampler = if rates.is_passthrough() {
+            None
+        } else {
+            FftFixedIn::<f32>::new(from as usize, to as usize, CHUNK_FRAMES, SUB_CHUNKS, rates.channels)
+                .map_err(|e| tracing::warn!("Cannot resample {} Hz to {} Hz: {}", from, to, e))
+                .ok()
+        };
+        Self {
+            skip: resampler.as_ref().map_or(0, |resampler| resampler.output_delay()),
+            resampler,
+            rates,
+            pending: vec![Vec::new(); rates.channels],
+            frames_in: 0,
+            frames_out: 0,
+        }
+    }
+
+    /// Convert the next block; output lags the input by up to a chunk
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let Some(resampler) = self.resampler.as_mut() else {
+            return samples.to_vec();
+        };
+        let channels = self.rates.channels;
+        for (ch, pending) in self.pending.iter_mut().enumerate() {
+            pending.extend(samples.iter().skip(ch).step_by(channels));
+        }
+        self.frames_in += samples.len() / channels;
+
+        let mut output = vec![Vec::new(); channels];
+        while self.pending[0].len() >= resampler.input_frames_next() {
+            let frames = resampler.input_frames_next();
+            let chunk: Vec<&[f32]> = self.pending.iter().map(|channel| &channel[..frames]).collect();
+            match resampler.process(&chunk, None) {
+                Ok(chunk) => append(&mut output, chunk),
+                Err(_) => break,
+            }
+            self.pending.iter_mut().for_each(|channel| drop(channel.drain(..frames)));
+        }
+        self.interleave(output)
+    }
+
+    /// Convert what is still held back, at the end of the stream
+    pub fn flush(&mut self) -> Vec<f32> {
+        let Some(resampler) = self.resampler.as_mut() else {
+            return Vec::new();
+        };
+        let channels = self.rates.channels;
+        let mut output = vec![Vec::new(); channels];
+        if !self.pending[0].is_empty() {
+            let chunk: Vec<&[f32]> = self.pending.iter().map(Vec::as_slice).collect();
+            if let Ok(chunk) = resampler.process_partial(Some(&chunk), None) {
+                append(&mut output, chunk);
+            }
+            self.pending.iter_mut().for_each(Vec::clear);
+        }
+        let expected = self.rates.output_frames(self.frames_in);
+        while self.frames_out + output[0].len().saturating_sub(self.skip) < expected {
+            match resampler.process_partial::<&[f32]>(None, None) {
+                Ok(chunk) if !chunk[0].is_empty() => append(&mut output, chunk),
+                _ => break,
+            }
+        }
+
+        let mut out = self.interleave(output);
+        let remaining = expected.saturating_sub(self.frames_out - out.len() / channels);
+        out.truncate(remaining * channels);
+        out
+    }
+
+    /// Interleave converted frames, dropping the delay still to skip
+    fn interleave(&mut self, output: Vec<Vec<f32>>) -> Vec<f32> {
+        let frames = output[0].len();
+        let skipped = self.skip.min(frames);
+        self.skip -= skipped;
+
+        let mut out = Vec::with_capacity((frames - skipped) * output.len());
+        for i in skipped..frames {
+            out.extend(output.iter().map(|channel| channel[i]));
+        }
+        self.frames_out += frames - skipped;
+        out
+    }
+}
+
+fn append(output: &mut [Vec<f32>], chunk: Vec<Vec<f32>>) {
+    for (channel, values) in output.iter_mut().zip(chunk) {
+        channel.extend(values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn sine(freq: f32, rate: u32, frames: usize, channels: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| std::iter::repeat_n((2.0 * PI * freq * i as f32 / rate as f32).sin() * 0.5, channels))
+            .collect()
+    }
+
+    #[test]
+    fn test_same_rate_is_passthrough() {
+        let samples = sine(440.0, 48000, 100, 2);
+        let resampler = AudioResampler::new(48000, 48000, 2);
+        assert!(resampler.is_passthrough());
+        assert_eq!(resampler.process(&samples), samples);
+    }
+
+    #[test]
+    fn test_resampling_keeps_duration_and_pitch() {
+        let samples = sine(1000.0, 44100, 44100, 2);
+        let out = AudioResampler::new(44100, 48000, 2).process(&samples);
+        assert_eq!(out.len(), 48000 * 2);
+
+        // Both channels stay identical, and the tone is still 1 kHz at 48 kHz
+        let expected = sine(1000.0, 48000, 48000, 2);
+        let middle = 2 * 24000..2 * 24100;
+        for (a, b) in out[middle.clone()].iter().zip(&expected[middle]) {
+            assert!((a - b).abs() < 0.01, "{} vs {}", a, b);
+        }
+        assert!(out.chunks_exact(2).all(|frame| frame[0] == frame[1]));
+    }
+
+    #[test]
+    fn test_streaming_matches_whole_buffer() {
+        let samples = sine(1000.0, 44100, 44100, 2);
+        let whole = AudioResampler::new(44100, 48000, 2).process(&samples);
+
+        // Odd block sizes, as decoders hand them out
+        let mut resampler = StreamingResampler::new(44100, 48000, 2);
+        let mut streamed = Vec::new();
+        for block in samples.chunks(2 * 1152) {
+            streamed.extend(resampler.process(block));
+        }
+        streamed.extend(resampler.flush());
+
+        assert_eq!(streamed.len(), whole.len());
+        for (a, b) in streamed.iter().zip(&whole) {
+            assert!((a - b).abs() < 1e-4, "{} vs {}", a, b);
+        }
+    }
+}
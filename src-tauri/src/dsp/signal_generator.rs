@@ -0,0 +1,145 @@
+//! Signal generator - Test tones and noise for calibration
+//!
+//! Produces the signals of generator channels: a 1 kHz reference sine, a
+//! repeating logarithmic sweep and pink noise. Pink noise is white noise
+//! through Paul Kellet's filter, scaled so its RMS matches the level.
+
+use crate::domain::{GeneratorSettings, GeneratorSignal};
+use std::f32::consts::TAU;
+
+const REFERENCE_HZ: f32 = 1000.0;
+const SWEEP_START_HZ: f32 = 20.0;
+const SWEEP_END_HZ: f32 = 20_000.0;
+const SWEEP_SECS: f32 = 10.0;
+
+/// RMS of the pink filter's output for white noise in [-1, 1)
+const PINK_RMS: f32 = 1.752;
+
+/// Seed of the noise generator; any non-zero value works
+const SEED: u32 = 0x2545_F491;
+
+/// Generates one test signal, the same on every channel
+pub struct SignalGenerator {
+    signal: GeneratorSignal,
+    amplitude: f32,
+    sample_rate: f32,
+    phase: f32,
+    /// Frames into the current sweep
+    sweep_frame: u32,
+    noise: u32,
+    pink: [f32; 7],
+}
+
+impl SignalGenerator {
+    pub fn new(settings: &GeneratorSettings, sample_rate: u32) -> Self {
+        Self {
+            signal: settings.signal,
+            amplitude: settings.amplitude(),
+            sample_rate: sample_rate.max(1) as f32,
+            phase: 0.0,
+            sweep_frame: 0,
+            noise: SEED,
+            pink: [0.0; 7],
+        }
+    }
+
+    /// Change the signal or level; the phase carries on, so it doesn't click
+    pub fn set_settings(&mut self, settings: &GeneratorSettings) {
+        if settings.signal != self.signal {
+            self.signal = settings.signal;
+            self.sweep_frame = 0;
+        }
+        self.amplitude = settings.amplitude();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate.max(1) as f32;
+        self.sweep_frame = 0;
+    }
+
+    /// Add the signal times `gain` into interleaved `data`
+    pub fn mix_into(&mut self, data: &mut [f32], channels: usize, gain: f32) {
+        for frame in data.chunks_mut(channels.max(1)) {
+            let value = self.next_value() * self.amplitude * gain;
+            for sample in frame {
+                *sample = (*sample + value).clamp(-1.0, 1.0);
+            }
+        }
+    }
+
+    /// Next value of the signal at full level
+    fn next_value(&mut self) -> f32 {
+        match self.signal {
+            GeneratorSignal::Reference1k => self.next_sine(REFERENCE_HZ),
+            GeneratorSignal::SineSweep => {
+                let sweep_frames = (SWEEP_SECS * self.sample_rate) as u32;
+                let progress = self.sweep_frame as f32 / sweep_frames as f32;
+                self.sweep_frame = (self.sweep_frame + 1) % sweep_frames.max(1);
+                // Stops short of Nyquist at low device rates
+                let hz = SWEEP_START_HZ * (SWEEP_END_HZ / SWEEP_START_HZ).powf(progress);
+                self.next_sine(hz.min(self.sample_rate * 0.45))
+            }
+            GeneratorSignal::PinkNoise => self.next_pink() / PINK_RMS,
+        }
+    }
+
+    fn next_sine(&mut self, hz: f32) -> f32 {
+        let value = self.phase.sin();
+        self.phase = (self.phase + TAU * hz / self.sample_rate) % TAU;
+        value
+    }
+
+    fn next_pink(&mut self) -> f32 {
+        let white = self.next_white();
+        let b = &mut self.pink;
+        b[0] = 0.99886 * b[0] + white * 0.0555179;
+        b[1] = 0.99332 * b[1] + white * 0.0750759;
+        b[2] = 0.96900 * b[2] + white * 0.1538520;
+        b[3] = 0.86650 * b[3] + white * 0.3104856;
+        b[4] = 0.55000 * b[4] + white * 0.5329522;
+        b[5] = -0.7616 * b[5] - white * 0.0168980;
+        let pink = b.iter().sum::<f32>() + white * 0.5362;
+        b[6] = white * 0.115926;
+        pink
+    }
+
+    /// Uniform value in [-1, 1) from a xorshift generator
+    fn next_white(&mut self) -> f32 {
+        self.noise ^= self.noise << 13;
+        self.noise ^= self.noise >> 17;
+        self.noise ^= self.noise << 5;
+        (self.noise >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(signal: GeneratorSignal, level_dbfs: f32, frames: usize) -> Vec<f32> {
+        let mut generator = SignalGenerator::new(&GeneratorSettings { signal, level_dbfs }, 48_000);
+        let mut data = vec![0.0; frames * 2];
+        generator.mix_into(&mut data, 2, 1.0);
+        data
+    }
+
+    fn rms(data: &[f32]) -> f32 {
+        (data.iter().map(|s| s * s).sum::<f32>() / data.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_reference_tone_peaks_at_the_level() {
+        let data = render(GeneratorSignal::Reference1k, -6.0, 4800);
+        let peak = data.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.501).abs() < 0.01, "peak {}", peak);
+        // Both channels carry the same signal
+        assert!(data.chunks(2).all(|frame| frame[0] == frame[1]));
+    }
+
+    #[test]
+    fn test_pink_noise_rms_matches_the_level() {
+        let data = render(GeneratorSignal::PinkNoise, -20.0, 480_000);
+        let db = 20.0 * rms(&data).log10();
+        assert!((db + 20.0).abs() < 1.0, "rms {} dBFS", db);
+    }
+}
@@ -0,0 +1,60 @@
+//! Sound inserts - Per-sound effect chain
+//!
+//! Built from the insert list of a sound when it starts playing, so the
+//! audio callback only runs it.
+
+use super::VocalReducer;
+use crate::domain::SoundInsert;
+
+enum Insert {
+    VocalReduction(VocalReducer),
+}
+
+/// Effects inserted on one playing sound, in order
+pub struct SoundInsertChain {
+    inserts: Vec<Insert>,
+}
+
+impl SoundInsertChain {
+    pub fn new(inserts: &[SoundInsert], sample_rate: u32) -> Self {
+        Self {
+            inserts: inserts
+                .iter()
+                .map(|insert| match *insert {
+                    SoundInsert::VocalReduction { strength } => {
+                        Insert::VocalReduction(VocalReducer::new(strength, sample_rate))
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inserts.is_empty()
+    }
+
+    /// Follow changed parameters, keeping filter state when the list
+    /// keeps its shape; returns false when the chain must be rebuilt
+    pub fn update(&mut self, inserts: &[SoundInsert]) -> bool {
+        if inserts.len() != self.inserts.len() {
+            return false;
+        }
+        for (current, insert) in self.inserts.iter_mut().zip(inserts) {
+            match (current, *insert) {
+                (Insert::VocalReduction(reducer), SoundInsert::VocalReduction { strength }) => {
+                    reducer.set_strength(strength)
+                }
+            }
+        }
+        true
+    }
+
+    /// Run every insert on interleaved audio in place
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        for insert in &mut self.inserts {
+            match insert {
+                Insert::VocalReduction(reducer) => reducer.process(data, channels),
+            }
+        }
+    }
+}
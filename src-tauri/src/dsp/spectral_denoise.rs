@@ -0,0 +1,180 @@
+//! Spectral-subtraction denoiser tuned to a learned noise print
+
+use super::stft::{scale_bin, Stft};
+use crate::domain::{NoiseProfile, SpectralQuality};
+
+/// FFT size used when learning a new noise print
+///
+/// Prints are resized to the FFT size of the selected quality when used.
+pub const NOISE_PROFILE_FFT_SIZE: usize = 1024;
+
+/// How much of the noise print to subtract (>1 removes more, with more artifacts)
+const OVER_SUBTRACTION: f32 = 1.5;
+
+/// Lowest gain applied to a bin, so noise is reduced rather than gated
+const SPECTRAL_FLOOR: f32 = 0.08;
+
+/// Learn a noise print from room tone (mono samples)
+///
+/// Returns `None` if there is not enough audio for at least one full frame.
+pub fn learn_noise_profile(samples: &[f32], fft_size: usize) -> Option<NoiseProfile> {
+    let mut stft = Stft::new(fft_size);
+    let bins = stft.bins();
+    let mut sums = vec![0.0f32; bins];
+    let mut frames = 0usize;
+    // The first frame is half empty, so it is skipped
+    let mut seen = 0usize;
+
+    for &sample in samples {
+        stft.process(sample, &mut |spectrum| {
+            seen += 1;
+            if seen < 2 {
+                return;
+            }
+            for (sum, bin) in sums.iter_mut().zip(spectrum.iter()) {
+                *sum += bin.norm();
+            }
+            frames += 1;
+        });
+    }
+
+    if frames == 0 {
+        return None;
+    }
+
+    let magnitudes = sums.into_iter().map(|s| s / frames as f32).collect();
+    Some(NoiseProfile::new(stft.size(), magnitudes))
+}
+
+/// Removes a learned noise print from interleaved audio
+pub struct SpectralDenoiser {
+    profile: NoiseProfile,
+    overlap: usize,
+    channels: Vec<Stft>,
+}
+
+impl SpectralDenoiser {
+    /// Denoise audio with `channels` channels at the profile's own FFT
+    /// size with 50% overlap
+    pub fn new(profile: NoiseProfile, channels: usize) -> Self {
+        let mut denoiser = Self {
+            profile,
+            overlap: 2,
+            channels: Vec::new(),
+        };
+        denoiser.set_channels(channels);
+        denoiser
+    }
+
+    /// Denoise with the FFT size and overlap of a quality setting
+    pub fn with_quality(profile: &NoiseProfile, quality: SpectralQuality, channels: usize) -> Self {
+        let mut denoiser = Self {
+            profile: profile.resized(quality.fft_size()),
+            overlap: quality.overlap(),
+            channels: Vec::new(),
+        };
+        denoiser.set_channels(channels);
+        denoiser
+    }
+
+    /// Build the per-channel transforms, clearing buffered audio
+    ///
+    /// Done up front rather than in `process`, which must not allocate.
+    pub fn set_channels(&mut self, channels: usize) {
+        let (size, overlap) = (self.profile.fft_size, self.overlap);
+        self.channels = (0..channels.max(1)).map(|_| Stft::with_overlap(size, overlap)).collect();
+    }
+
+    pub fn profile(&self) -> &NoiseProfile {
+        &self.profile
+    }
+
+    /// Delay added by the denoiser, in frames
+    pub fn latency(&self) -> usize {
+        self.profile.fft_size
+    }
+
+    /// Denoise interleaved samples in place
+    ///
+    /// Adds `fft_size` samples of latency per channel. Channels beyond
+    /// those the denoiser was built for pass through.
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+
+        let noise = &self.profile.magnitudes;
+        let mut subtract = |spectrum: &mut [rustfft::num_complex::Complex<f32>]| {
+            for (k, &noise_mag) in noise.iter().enumerate() {
+                let magnitude = spectrum[k].norm();
+                let gain = if magnitude > 0.0 {
+                    (1.0 - OVER_SUBTRACTION * noise_mag / magnitude).max(SPECTRAL_FLOOR)
+                } else {
+                    SPECTRAL_FLOOR
+                };
+                scale_bin(spectrum, k, gain);
+            }
+        };
+
+        for frame in data.chunks_exact_mut(channels) {
+            for (sample, stft) in frame.iter_mut().zip(self.channels.iter_mut()) {
+                *sample = stft.process(*sample, &mut subtract);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic white-ish noise
+    fn noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut state = 0x1234_5678u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_learned_profile_shape() {
+        let profile = learn_noise_profile(&noise(8192, 0.1), 256).unwrap();
+        assert!(profile.is_valid());
+        assert_eq!(profile.magnitudes.len(), 129);
+        assert!(learn_noise_profile(&[0.0; 100], 256).is_none());
+    }
+
+    #[test]
+    fn test_denoiser_reduces_learned_noise() {
+        let room_tone = noise(48000, 0.1);
+        let profile = learn_noise_profile(&room_tone, 512).unwrap();
+        let mut denoiser = SpectralDenoiser::new(profile, 1);
+
+        let mut data = noise(48000, 0.1);
+        let before = rms(&data[4096..]);
+        denoiser.process(&mut data, 1);
+
+        assert!(rms(&data[4096..]) < before * 0.5);
+    }
+
+    #[test]
+    fn test_denoiser_follows_quality() {
+        let room_tone = noise(48000, 0.1);
+        let profile = learn_noise_profile(&room_tone, NOISE_PROFILE_FFT_SIZE).unwrap();
+        let mut denoiser = SpectralDenoiser::with_quality(&profile, SpectralQuality::HighQuality, 1);
+        assert_eq!(denoiser.latency(), 2048);
+
+        let mut data = noise(48000, 0.1);
+        let before = rms(&data[8192..]);
+        denoiser.process(&mut data, 1);
+
+        assert!(rms(&data[8192..]) < before * 0.5);
+    }
+}
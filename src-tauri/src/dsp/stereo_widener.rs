@@ -0,0 +1,112 @@
+//! Stereo widener - Mid/side width control that stays mono-compatible
+
+/// Widest setting; 1.0 leaves the image untouched
+pub const MAX_STEREO_WIDTH: f32 = 2.0;
+
+/// Delay of the synthesized side signal (about 12 ms at 48 kHz)
+const SIDE_DELAY_FRAMES: usize = 576;
+
+/// How much delayed mid is turned into side per unit of extra width
+const SYNTH_SIDE_AMOUNT: f32 = 0.35;
+
+/// Scales the side signal of stereo audio
+///
+/// Width 0 collapses to mono, 1 is unchanged and up to 2 widens. Beyond 1
+/// a delayed copy of the mid is added as extra side, so mono sources get
+/// wider too. Only the side changes, so the mono fold-down (L + R) is
+/// always identical to the input's.
+pub struct StereoWidener {
+    width: f32,
+    delay: Vec<f32>,
+    position: usize,
+}
+
+impl StereoWidener {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width: width.clamp(0.0, MAX_STEREO_WIDTH),
+            delay: vec![0.0; SIDE_DELAY_FRAMES],
+            position: 0,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width.clamp(0.0, MAX_STEREO_WIDTH);
+    }
+
+    /// Widen interleaved audio in place
+    ///
+    /// Anything other than two channels (e.g. a forced mono output) is
+    /// passed through untouched.
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        if channels != 2 {
+            return;
+        }
+
+        let synth = (self.width - 1.0).max(0.0) * SYNTH_SIDE_AMOUNT;
+        for frame in data.chunks_exact_mut(2) {
+            let mid = (frame[0] + frame[1]) * 0.5;
+            let side = (frame[0] - frame[1]) * 0.5;
+
+            let delayed_mid = self.delay[self.position];
+            self.delay[self.position] = mid;
+            self.position = (self.position + 1) % self.delay.len();
+
+            let side = side * self.width + delayed_mid * synth;
+            frame[0] = mid + side;
+            frame[1] = mid - side;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stereo(frames: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|n| {
+                let t = n as f32 * 0.01;
+                [t.sin() * 0.5, (t * 1.3).cos() * 0.3]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mono_fold_down_is_preserved() {
+        let input = stereo(2000);
+        for width in [0.0, 0.5, 1.0, 1.5, 2.0] {
+            let mut data = input.clone();
+            StereoWidener::new(width).process(&mut data, 2);
+
+            for (a, b) in input.chunks(2).zip(data.chunks(2)) {
+                assert!((a[0] + a[1] - (b[0] + b[1])).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_width_zero_collapses_and_one_is_transparent() {
+        let input = stereo(100);
+
+        let mut data = input.clone();
+        StereoWidener::new(0.0).process(&mut data, 2);
+        assert!(data.chunks(2).all(|f| (f[0] - f[1]).abs() < 1e-6));
+
+        let mut data = input.clone();
+        StereoWidener::new(1.0).process(&mut data, 2);
+        assert_eq!(data, input);
+    }
+
+    #[test]
+    fn test_mono_output_is_bypassed() {
+        let input = vec![0.1, 0.2, 0.3];
+        let mut data = input.clone();
+        StereoWidener::new(2.0).process(&mut data, 1);
+        assert_eq!(data, input);
+    }
+}
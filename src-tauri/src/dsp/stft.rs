@@ -0,0 +1,173 @@
+//! Short-time Fourier transform with overlap-add resynthesis
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Streaming STFT for a single channel
+///
+/// Uses a square-root Hann window for both analysis and synthesis, so an
+/// untouched spectrum reconstructs the input exactly. Frames overlap by
+/// 50% by default; more overlap costs CPU but smooths heavy processing.
+/// Output lags input by `size` samples whatever the overlap.
+pub struct Stft {
+    size: usize,
+    hop: usize,
+    /// Inverse FFT and overlap-add normalization
+    norm: f32,
+    window: Vec<f32>,
+    forward: Arc<dyn Fft<f32>>,
+    inverse: Arc<dyn Fft<f32>>,
+    input: Vec<f32>,
+    output: Vec<f32>,
+    ready: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+    filled: usize,
+}
+
+impl Stft {
+    /// Create an STFT with a frame of `size` samples (rounded up to a power of two)
+    pub fn new(size: usize) -> Self {
+        Self::with_overlap(size, 2)
+    }
+
+    /// Create an STFT whose frames overlap `overlap` times (2, 4 or 8)
+    pub fn with_overlap(size: usize, overlap: usize) -> Self {
+        let size = size.max(4).next_power_of_two();
+        let overlap = overlap.clamp(2, 8).next_power_of_two().min(size / 2);
+        let hop = size / overlap;
+
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(size);
+        let inverse = planner.plan_fft_inverse(size);
+        let scratch_len = forward
+            .get_inplace_scratch_len()
+            .max(inverse.get_inplace_scratch_len());
+
+        // Periodic Hann, so overlapping windows sum to exactly `overlap / 2`
+        let window = (0..size)
+            .map(|n| (0.5 - 0.5 * (2.0 * PI * n as f32 / size as f32).cos()).sqrt())
+            .collect();
+
+        Self {
+            size,
+            hop,
+            norm: 2.0 / (overlap * size) as f32,
+            window,
+            forward,
+            inverse,
+            input: vec![0.0; size],
+            output: vec![0.0; size],
+            ready: vec![0.0; hop],
+            spectrum: vec![Complex::default(); size],
+            scratch: vec![Complex::default(); scratch_len],
+            filled: 0,
+        }
+    }
+
+    /// Frame size in samples
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of overlapping frames covering each sample
+    pub fn overlap(&self) -> usize {
+        self.size / self.hop
+    }
+
+    /// Number of unique frequency bins (DC to Nyquist)
+    pub fn bins(&self) -> usize {
+        self.size / 2 + 1
+    }
+
+    /// Push one sample and get the delayed output sample
+    ///
+    /// `modify` is called with the full spectrum every hop. Changes to
+    /// bin `k` should be mirrored to bin `size - k` to keep the output real.
+    pub fn process(&mut self, sample: f32, modify: &mut impl FnMut(&mut [Complex<f32>])) -> f32 {
+        let out = self.ready[self.filled];
+        self.input[self.size - self.hop + self.filled] = sample;
+        self.filled += 1;
+
+        if self.filled == self.hop {
+            self.filled = 0;
+            self.frame(modify);
+        }
+        out
+    }
+
+    /// Clear all buffered audio
+    pub fn reset(&mut self) {
+        self.input.fill(0.0);
+        self.output.fill(0.0);
+        self.ready.fill(0.0);
+        self.filled = 0;
+    }
+
+    fn frame(&mut self, modify: &mut impl FnMut(&mut [Complex<f32>])) {
+        for ((bin, &x), &w) in self.spectrum.iter_mut().zip(&self.input).zip(&self.window) {
+            *bin = Complex::new(x * w, 0.0);
+        }
+
+        self.forward.process_with_scratch(&mut self.spectrum, &mut self.scratch);
+        modify(&mut self.spectrum);
+        self.inverse.process_with_scratch(&mut self.spectrum, &mut self.scratch);
+
+        for ((acc, bin), &w) in self.output.iter_mut().zip(&self.spectrum).zip(&self.window) {
+            *acc += bin.re * self.norm * w;
+        }
+
+        self.ready.copy_from_slice(&self.output[..self.hop]);
+        self.output.copy_within(self.hop.., 0);
+        self.output[self.size - self.hop..].fill(0.0);
+        self.input.copy_within(self.hop.., 0);
+    }
+}
+
+/// Scale bin `k` and its mirror image by `gain`
+pub fn scale_bin(spectrum: &mut [Complex<f32>], k: usize, gain: f32) {
+    let size = spectrum.len();
+    spectrum[k] *= gain;
+    if k != 0 && k != size - k {
+        spectrum[size - k] *= gain;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untouched_spectrum_reconstructs_input() {
+        let mut stft = Stft::new(64);
+        let input: Vec<f32> = (0..512).map(|n| (n as f32 * 0.1).sin() * 0.5).collect();
+
+        let output: Vec<f32> = input.iter().map(|&x| stft.process(x, &mut |_| {})).collect();
+
+        for n in 0..input.len() - 64 {
+            assert!((output[n + 64] - input[n]).abs() < 1e-4, "sample {}", n);
+        }
+    }
+
+    #[test]
+    fn test_higher_overlap_reconstructs_input() {
+        let mut stft = Stft::with_overlap(64, 4);
+        assert_eq!(stft.overlap(), 4);
+        let input: Vec<f32> = (0..512).map(|n| (n as f32 * 0.1).sin() * 0.5).collect();
+
+        let output: Vec<f32> = input.iter().map(|&x| stft.process(x, &mut |_| {})).collect();
+
+        for n in 0..input.len() - 64 {
+            assert!((output[n + 64] - input[n]).abs() < 1e-4, "sample {}", n);
+        }
+    }
+
+    #[test]
+    fn test_size_is_power_of_two() {
+        let stft = Stft::new(1000);
+        assert_eq!(stft.size(), 1024);
+        assert_eq!(stft.bins(), 513);
+    }
+}
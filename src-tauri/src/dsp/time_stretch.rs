@@ -0,0 +1,211 @@
+//! Time stretch - Playback speed with or without a pitch change
+//!
+//! Sounds are sped up once, when they are decoded, not in the callback.
+//! The resample mode reads the sound faster or slower like a tape, through
+//! the same band-limited resampler as rate conversion so it doesn't alias.
+//! The time-stretch mode is a phase vocoder: frames are taken `rate` hops
+//! apart and laid down one hop apart, each bin's phase advanced by its
+//! measured frequency, so durations change and the pitch does not.
+
+use super::AudioResampler;
+use crate::domain::{PlaybackSpeed, SpeedMode};
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Analysis frame of the phase vocoder (about 43 ms at 48 kHz)
+const FRAME: usize = 2048;
+
+/// Synthesis hop: frames overlap four times
+const HOP: usize = FRAME / 4;
+
+/// Rate a resampled speed is expressed against: a sound read `rate` times
+/// faster is converted as if recorded at `rate` times this rate
+const SPEED_BASE_RATE: f32 = 48_000.0;
+
+/// Apply `speed` to interleaved samples with `channels` channels
+pub fn change_speed(samples: &[f32], channels: usize, speed: PlaybackSpeed) -> Vec<f32> {
+    if speed.is_normal() || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let rate = speed.rate.max(f32::EPSILON);
+    match speed.mode {
+        SpeedMode::Resample => resample_speed(samples, channels.max(1), rate),
+        SpeedMode::TimeStretch => time_stretch(samples, channels.max(1), rate),
+    }
+}
+
+/// Read the sound `rate` times faster, shifting its pitch by the same ratio
+fn resample_speed(samples: &[f32], channels: usize, rate: f32) -> Vec<f32> {
+    let from = (SPEED_BASE_RATE * rate).round() as u32;
+    AudioResampler::new(from, SPEED_BASE_RATE as u32, channels).process(samples)
+}
+
+/// Change the duration by `1 / rate` keeping the pitch
+fn time_stretch(samples: &[f32], channels: usize, rate: f32) -> Vec<f32> {
+    let frames = samples.len() / channels;
+    let out_frames = (frames as f64 / rate as f64).round() as usize;
+    let mut vocoder = PhaseVocoder::new();
+    let mut out = vec![0.0; out_frames * channels];
+
+    let mut channel = Vec::with_capacity(frames);
+    for ch in 0..channels {
+        channel.clear();
+        channel.extend(samples.iter().skip(ch).step_by(channels));
+        let stretched = vocoder.stretch(&channel, rate, out_frames);
+        for (frame, value) in out.chunks_exact_mut(channels).zip(stretched) {
+            frame[ch] = value;
+        }
+    }
+    out
+}
+
+/// Offline phase vocoder for one channel
+struct PhaseVocoder {
+    window: Vec<f32>,
+    forward: Arc<dyn Fft<f32>>,
+    inverse: Arc<dyn Fft<f32>>,
+    spectrum: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+    last_phase: Vec<f32>,
+    phase: Vec<f32>,
+}
+
+impl PhaseVocoder {
+    fn new() -> Self {
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(FRAME);
+        let inverse = planner.plan_fft_inverse(FRAME);
+        let scratch_len = forward
+            .get_inplace_scratch_len()
+            .max(inverse.get_inplace_scratch_len());
+        let bins = FRAME / 2 + 1;
+
+        Self {
+            // Periodic Hann, applied on analysis and synthesis
+            window: (0..FRAME)
+                .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f32 / FRAME as f32).cos())
+                .collect(),
+            forward,
+            inverse,
+            spectrum: vec![Complex::default(); FRAME],
+            scratch: vec![Complex::default(); scratch_len],
+            last_phase: vec![0.0; bins],
+            phase: vec![0.0; bins],
+        }
+    }
+
+    fn stretch(&mut self, input: &[f32], rate: f32, out_len: usize) -> Vec<f32> {
+        let bins = FRAME / 2 + 1;
+        let mut output = vec![0.0; out_len + FRAME];
+        // Squared Hann windows overlapping four times sum to 1.5
+        let norm = 1.0 / (1.5 * FRAME as f32);
+
+        let mut previous = 0usize;
+        let mut frame = 0usize;
+        loop {
+            let start = (frame as f64 * HOP as f64 * rate as f64).round() as usize;
+            let target = frame * HOP;
+            if start >= input.len() || target >= out_len {
+                break;
+            }
+
+            for (n, bin) in self.spectrum.iter_mut().enumerate() {
+                let x = input.get(start + n).copied().unwrap_or(0.0);
+                *bin = Complex::new(x * self.window[n], 0.0);
+            }
+            self.forward.process_with_scratch(&mut self.spectrum, &mut self.scratch);
+
+            // Actual analysis hop, as rounding makes it vary by a sample
+            let hop = (start - previous) as f32;
+            for k in 0..bins {
+                let (magnitude, phase) = self.spectrum[k].to_polar();
+                if frame == 0 {
+                    self.phase[k] = phase;
+                } else {
+                    let bin_freq = 2.0 * PI * k as f32 / FRAME as f32;
+                    let deviation = wrap_phase(phase - self.last_phase[k] - bin_freq * hop);
+                    let freq = bin_freq + deviation / hop.max(1.0);
+                    self.phase[k] = wrap_phase(self.phase[k] + freq * HOP as f32);
+                }
+                self.last_phase[k] = phase;
+                self.spectrum[k] = Complex::from_polar(magnitude, self.phase[k]);
+            }
+            for k in 1..FRAME / 2 {
+                self.spectrum[FRAME - k] = self.spectrum[k].conj();
+            }
+            self.inverse.process_with_scratch(&mut self.spectrum, &mut self.scratch);
+
+            for (n, bin) in self.spectrum.iter().enumerate() {
+                output[target + n] += bin.re * self.window[n] * norm;
+            }
+
+            previous = start;
+            frame += 1;
+        }
+
+        output.truncate(out_len);
+        output
+    }
+}
+
+/// Wrap a phase to -π..π
+fn wrap_phase(phase: f32) -> f32 {
+    phase - 2.0 * PI * (phase / (2.0 * PI)).round()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(hz: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|n| (2.0 * PI * hz * n as f32 / 48_000.0).sin() * 0.5)
+            .collect()
+    }
+
+    /// Frequency from the zero crossings of the settled middle half
+    fn frequency(samples: &[f32]) -> f32 {
+        let middle = &samples[samples.len() / 4..samples.len() * 3 / 4];
+        let crossings = middle.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count();
+        crossings as f32 / 2.0 * 48_000.0 / middle.len() as f32
+    }
+
+    #[test]
+    fn test_time_stretch_keeps_pitch() {
+        let input = sine(440.0, 48_000);
+        for rate in [0.5, 2.0] {
+            let out = change_speed(&input, 1, PlaybackSpeed::new(rate, SpeedMode::TimeStretch));
+            assert_eq!(out.len(), (48_000.0 / rate) as usize);
+            let hz = frequency(&out);
+            assert!((hz - 440.0).abs() < 10.0, "rate {rate}: {hz} Hz");
+        }
+    }
+
+    #[test]
+    fn test_resample_shifts_pitch() {
+        let input: Vec<f32> = sine(440.0, 48_000).into_iter().flat_map(|s| [s, s]).collect();
+        let out = change_speed(&input, 2, PlaybackSpeed::new(2.0, SpeedMode::Resample));
+        assert_eq!(out.len(), 48_000);
+        let left: Vec<f32> = out.iter().step_by(2).copied().collect();
+        assert!((frequency(&left) - 880.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn test_resample_does_not_alias() {
+        // Doubled, 15 kHz lands above Nyquist and must be filtered out,
+        // not folded back down to 18 kHz
+        let input = sine(15_000.0, 48_000);
+        let out = change_speed(&input, 1, PlaybackSpeed::new(2.0, SpeedMode::Resample));
+        let middle = &out[out.len() / 4..out.len() * 3 / 4];
+        let rms = (middle.iter().map(|s| s * s).sum::<f32>() / middle.len() as f32).sqrt();
+        assert!(rms < 0.01, "{rms}");
+    }
+
+    #[test]
+    fn test_normal_speed_is_untouched() {
+        let input = sine(440.0, 1_000);
+        assert_eq!(change_speed(&input, 1, PlaybackSpeed::default()), input);
+    }
+}
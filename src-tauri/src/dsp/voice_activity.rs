@@ -0,0 +1,141 @@
+//! Voice activity - Tells when the user is speaking from the mic level
+
+use crate::domain::VoiceActivitySettings;
+
+/// Speech ends this far below the threshold, so the indicator doesn't chatter
+const HYSTERESIS_DB: f32 = 6.0;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Speaking detector fed with the processed mic level, block by block
+///
+/// Speech starts as soon as the level crosses the threshold and ends once
+/// it has stayed below it for the hangover, so pauses between words don't
+/// count as stopping.
+pub struct VoiceActivityDetector {
+    settings: VoiceActivitySettings,
+    start_level: f32,
+    stop_level: f32,
+    speaking: bool,
+    quiet_ms: u32,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(settings: VoiceActivitySettings) -> Self {
+        Self {
+            settings,
+            start_level: db_to_linear(settings.threshold_db),
+            stop_level: db_to_linear(settings.threshold_db - HYSTERESIS_DB),
+            speaking: false,
+            quiet_ms: 0,
+        }
+    }
+
+    pub fn settings(&self) -> VoiceActivitySettings {
+        self.settings
+    }
+
+    /// Change the threshold and hangover, keeping the current state
+    pub fn set_settings(&mut self, settings: VoiceActivitySettings) {
+        let speaking = self.speaking;
+        *self = Self::new(settings);
+        self.speaking = speaking;
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// Feed the RMS level of the last `elapsed_ms`; returns the new state
+    /// when speech started or stopped
+    pub fn update(&mut self, rms: f32, elapsed_ms: u32) -> Option<bool> {
+        if !self.settings.enabled {
+            return self.reset();
+        }
+
+        if !self.speaking {
+            if rms >= self.start_level {
+                self.speaking = true;
+                self.quiet_ms = 0;
+                return Some(true);
+            }
+            return None;
+        }
+
+        if rms >= self.stop_level {
+            self.quiet_ms = 0;
+            return None;
+        }
+        self.quiet_ms = self.quiet_ms.saturating_add(elapsed_ms);
+        if self.quiet_ms >= self.settings.hangover_ms {
+            self.speaking = false;
+            self.quiet_ms = 0;
+            return Some(false);
+        }
+        None
+    }
+
+    /// Back to silence; returns `Some(false)` if speech was cut short
+    pub fn reset(&mut self) -> Option<bool> {
+        self.quiet_ms = 0;
+        std::mem::replace(&mut self.speaking, false).then_some(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> VoiceActivitySettings {
+        VoiceActivitySettings {
+            enabled: true,
+            threshold_db: -40.0,
+            hangover_ms: 300,
+        }
+    }
+
+    #[test]
+    fn test_speech_stops_only_after_the_hangover() {
+        let mut vad = VoiceActivityDetector::new(settings());
+        assert_eq!(vad.update(0.001, 33), None);
+        assert_eq!(vad.update(0.1, 33), Some(true));
+
+        // A short pause between words keeps the speaker active
+        for _ in 0..8 {
+            assert_eq!(vad.update(0.0, 33), None);
+        }
+        assert_eq!(vad.update(0.1, 33), None);
+
+        for _ in 0..9 {
+            assert_eq!(vad.update(0.0, 33), None);
+        }
+        assert_eq!(vad.update(0.0, 33), Some(false));
+        assert!(!vad.is_speaking());
+    }
+
+    #[test]
+    fn test_hysteresis_holds_speech_just_under_the_threshold() {
+        let mut vad = VoiceActivityDetector::new(settings());
+        assert_eq!(vad.update(0.02, 33), Some(true));
+        // -37 dB starts speech, -43 dB is quieter but within the hysteresis
+        for _ in 0..20 {
+            assert_eq!(vad.update(0.007, 33), None);
+        }
+        assert!(vad.is_speaking());
+    }
+
+    #[test]
+    fn test_disabling_ends_speech() {
+        let mut vad = VoiceActivityDetector::new(settings());
+        vad.update(0.1, 33);
+        vad.set_settings(VoiceActivitySettings {
+            enabled: false,
+            ..settings()
+        });
+        assert!(vad.is_speaking());
+        assert_eq!(vad.update(0.1, 33), Some(false));
+        assert_eq!(vad.update(0.1, 33), None);
+    }
+}
@@ -0,0 +1,306 @@
+//! Voice changer - Pitch, ring modulation, band-limiting, drive and echo
+
+use crate::domain::VoiceChangerSettings;
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+/// Length of the pitch shifter's crossfaded delay window
+const PITCH_WINDOW_MS: f32 = 40.0;
+
+/// Longest echo the delay line holds
+const MAX_ECHO_MS: f32 = 1000.0;
+
+/// Normalized biquad coefficients (a0 = 1)
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Biquad {
+    /// Butterworth low-pass (`high_pass = false`) or high-pass
+    fn butterworth(cutoff: f32, sample_rate: f32, high_pass: bool) -> Self {
+        let w0 = 2.0 * PI * cutoff.min(sample_rate * 0.45) / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * FRAC_1_SQRT_2);
+        let a0 = 1.0 + alpha;
+        let (b0, b1) = if high_pass {
+            ((1.0 + cos) / 2.0, -(1.0 + cos))
+        } else {
+            ((1.0 - cos) / 2.0, 1.0 - cos)
+        };
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+
+    /// Transposed direct form II
+    fn process(&self, x: f32, z: &mut [f32; 2]) -> f32 {
+        let y = self.b0 * x + z[0];
+        z[0] = self.b1 * x - self.a1 * y + z[1];
+        z[1] = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+/// Per-channel delay lines and filter state
+struct ChannelState {
+    pitch_buffer: Vec<f32>,
+    pitch_write: usize,
+    /// Delay of the first pitch tap, in samples
+    pitch_delay: f32,
+    low_cut: [f32; 2],
+    high_cut: [f32; 2],
+    echo: Vec<f32>,
+    echo_pos: usize,
+}
+
+impl ChannelState {
+    fn new(window: usize, echo_len: usize) -> Self {
+        Self {
+            pitch_buffer: vec![0.0; window + 2],
+            pitch_write: 0,
+            pitch_delay: 0.0,
+            low_cut: [0.0; 2],
+            high_cut: [0.0; 2],
+            echo: vec![0.0; echo_len],
+            echo_pos: 0,
+        }
+    }
+
+    /// Read the pitch buffer `delay` samples back, interpolating
+    fn read_delayed(&self, delay: f32) -> f32 {
+        let len = self.pitch_buffer.len();
+        let position = self.pitch_write as f32 - delay;
+        let position = if position < 0.0 { position + len as f32 } else { position };
+        let index = position.floor() as usize % len;
+        let frac = position - position.floor();
+        let next = (index + 1) % len;
+        self.pitch_buffer[index] + (self.pitch_buffer[next] - self.pitch_buffer[index]) * frac
+    }
+
+    /// Delay-line pitch shifter with two crossfaded taps
+    fn shift_pitch(&mut self, x: f32, ratio: f32, window: f32) -> f32 {
+        let len = self.pitch_buffer.len();
+        self.pitch_buffer[self.pitch_write] = x;
+
+        let first = self.pitch_delay;
+        let second = (first + window / 2.0) % window;
+        // sin² and cos² windows, so the two taps always sum to unity gain
+        let gain = |delay: f32| (PI * delay / window).sin().powi(2);
+        let y = self.read_delayed(first) * gain(first) + self.read_delayed(second) * gain(second);
+
+        self.pitch_delay = (self.pitch_delay + 1.0 - ratio).rem_euclid(window);
+        self.pitch_write = (self.pitch_write + 1) % len;
+        y
+    }
+
+    fn echo(&mut self, x: f32, feedback: f32) -> f32 {
+        let Some(delayed) = self.echo.get(self.echo_pos).copied() else {
+            return x;
+        };
+        let y = x + delayed * feedback;
+        self.echo[self.echo_pos] = y;
+        self.echo_pos = (self.echo_pos + 1) % self.echo.len();
+        y
+    }
+}
+
+/// Character voice effect for the mic
+///
+/// Runs, in order: pitch shift, ring modulation, low cut, high cut, soft
+/// clipping and echo. Effects at their zero value are skipped.
+pub struct VoiceChanger {
+    settings: VoiceChangerSettings,
+    sample_rate: f32,
+    pitch_ratio: f32,
+    pitch_window: usize,
+    low_cut: Option<Biquad>,
+    high_cut: Option<Biquad>,
+    echo_len: usize,
+    ring_phase: f32,
+    ring_step: f32,
+    /// Channels of the stream the delay lines are built for
+    channel_count: usize,
+    channels: Vec<ChannelState>,
+}
+
+impl VoiceChanger {
+    pub fn new(settings: VoiceChangerSettings, sample_rate: u32, channels: usize) -> Self {
+        let mut changer = Self {
+            settings,
+            sample_rate: 0.0,
+            pitch_ratio: 1.0,
+            pitch_window: 0,
+            low_cut: None,
+            high_cut: None,
+            echo_len: 0,
+            ring_phase: 0.0,
+            ring_step: 0.0,
+            channel_count: channels.max(1),
+            channels: Vec::new(),
+        };
+        changer.set_sample_rate(sample_rate);
+        changer
+    }
+
+    pub fn settings(&self) -> VoiceChangerSettings {
+        self.settings
+    }
+
+    /// Recompute rate-dependent parameters, clearing all delay lines
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        let rate = sample_rate.max(1) as f32;
+        let settings = &self.settings;
+
+        self.sample_rate = rate;
+        self.pitch_ratio = 2f32.powf(
+            settings
+                .pitch_semitones
+                .clamp(-VoiceChangerSettings::MAX_PITCH_SEMITONES, VoiceChangerSettings::MAX_PITCH_SEMITONES)
+                / 12.0,
+        );
+        self.pitch_window = (PITCH_WINDOW_MS * rate / 1000.0) as usize;
+        self.low_cut = (settings.low_cut_hz > 0.0).then(|| Biquad::butterworth(settings.low_cut_hz, rate, true));
+        self.high_cut = (settings.high_cut_hz > 0.0).then(|| Biquad::butterworth(settings.high_cut_hz, rate, false));
+        self.echo_len = (settings.echo_ms.min(MAX_ECHO_MS) * rate / 1000.0) as usize;
+        self.ring_step = 2.0 * PI * settings.ring_mod_hz / rate;
+        self.reset_channels();
+    }
+
+    /// Build the delay lines for a stream of `channels`, clearing them
+    pub fn set_channels(&mut self, channels: usize) {
+        self.channel_count = channels.max(1);
+        self.reset_channels();
+    }
+
+    /// Delay lines are sized here, never in `process`, so the audio
+    /// callback doesn't allocate
+    fn reset_channels(&mut self) {
+        self.channels = (0..self.channel_count)
+            .map(|_| ChannelState::new(self.pitch_window, self.echo_len))
+            .collect();
+    }
+
+    /// Process interleaved samples in place
+    ///
+    /// Channels beyond those the changer was set up for pass through.
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+
+        let settings = self.settings;
+        let shift = settings.pitch_semitones != 0.0;
+        let window = self.pitch_window as f32;
+        let drive = 1.0 + 9.0 * settings.drive.clamp(0.0, 1.0);
+        let drive_norm = drive.tanh();
+        let feedback = settings.echo_feedback.clamp(0.0, VoiceChangerSettings::MAX_ECHO_FEEDBACK);
+
+        for frame in data.chunks_exact_mut(channels) {
+            let ring = if self.ring_step > 0.0 {
+                let ring = self.ring_phase.sin();
+                self.ring_phase = (self.ring_phase + self.ring_step) % (2.0 * PI);
+                ring
+            } else {
+                1.0
+            };
+
+            for (sample, state) in frame.iter_mut().zip(self.channels.iter_mut()) {
+                let mut y = *sample;
+                if shift {
+                    y = state.shift_pitch(y, self.pitch_ratio, window);
+                }
+                y *= ring;
+                if let Some(low_cut) = &self.low_cut {
+                    y = low_cut.process(y, &mut state.low_cut);
+                }
+                if let Some(high_cut) = &self.high_cut {
+                    y = high_cut.process(y, &mut state.high_cut);
+                }
+                if settings.drive > 0.0 {
+                    y = (y * drive).tanh() / drive_norm;
+                }
+                *sample = state.echo(y, feedback);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| (2.0 * PI * freq * n as f32 / 48000.0).sin() * 0.5)
+            .collect()
+    }
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count()
+    }
+
+    #[test]
+    fn test_octave_up_doubles_frequency() {
+        let mut changer = VoiceChanger::new(
+            VoiceChangerSettings {
+                pitch_semitones: 12.0,
+                ..Default::default()
+            },
+            48000,
+            1,
+        );
+        let mut data = sine(200.0, 48000);
+        changer.process(&mut data, 1);
+
+        let crossings = zero_crossings(&data[4800..]) as f32;
+        let expected = zero_crossings(&sine(400.0, 43200)) as f32;
+        assert!((crossings - expected).abs() / expected < 0.1);
+    }
+
+    #[test]
+    fn test_ring_modulation_and_echo() {
+        let mut changer = VoiceChanger::new(
+            VoiceChangerSettings {
+                ring_mod_hz: 100.0,
+                ..Default::default()
+            },
+            48000,
+            1,
+        );
+        let mut dc = vec![0.5; 480];
+        changer.process(&mut dc, 1);
+        // Half a period of the 100 Hz carrier
+        assert!((dc[120] - 0.5).abs() < 1e-3);
+        assert!(dc[240].abs() < 1e-2);
+
+        let mut changer = VoiceChanger::new(
+            VoiceChangerSettings {
+                echo_ms: 10.0,
+                echo_feedback: 0.5,
+                ..Default::default()
+            },
+            48000,
+            1,
+        );
+        let mut impulse = vec![0.0; 1000];
+        impulse[0] = 1.0;
+        changer.process(&mut impulse, 1);
+        assert_eq!(impulse[480], 0.5);
+        assert_eq!(impulse[960], 0.25);
+    }
+
+    #[test]
+    fn test_clean_settings_pass_through() {
+        let mut changer = VoiceChanger::new(VoiceChangerSettings::default(), 48000, 1);
+        let input = sine(440.0, 960);
+        let mut data = input.clone();
+        changer.process(&mut data, 1);
+        assert_eq!(data, input);
+    }
+}
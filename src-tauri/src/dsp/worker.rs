@@ -0,0 +1,188 @@
+//! Worker offload - Runs heavy effects on their own thread
+//!
+//! The audio callback only copies samples into a ring buffer and copies
+//! processed samples back out. A worker thread does the actual work, so
+//! an expensive FFT can't starve the callback. The price is latency: the
+//! output runs [`OFFLOAD_LATENCY_FRAMES`] behind, on top of the effect's own.
+
+use super::SpectralDenoiser;
+use ringbuf::traits::{Consumer, Observer, Producer, Split};
+use ringbuf::{HeapCons, HeapProd, HeapRb};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+/// Frames the worker handles at a time
+pub const OFFLOAD_BLOCK_FRAMES: usize = 512;
+
+/// Delay the worker handshake adds to the effect's own, in frames
+pub const OFFLOAD_LATENCY_FRAMES: usize = 2 * OFFLOAD_BLOCK_FRAMES;
+
+/// Headroom in the ring buffers, in blocks
+const RING_BLOCKS: usize = 8;
+
+/// Longest a worker sleeps before checking for new input
+const WORKER_POLL: Duration = Duration::from_millis(2);
+
+/// An effect that can be moved off the audio thread
+pub trait BlockProcessor: Send + 'static {
+    /// Process interleaved samples in place
+    fn process(&mut self, data: &mut [f32], channels: usize);
+
+    /// Delay added by the effect, in frames
+    fn latency(&self) -> usize {
+        0
+    }
+}
+
+impl BlockProcessor for SpectralDenoiser {
+    fn process(&mut self, data: &mut [f32], channels: usize) {
+        SpectralDenoiser::process(self, data, channels);
+    }
+
+    fn latency(&self) -> usize {
+        SpectralDenoiser::latency(self)
+    }
+}
+
+/// State shared between the audio callback and the worker
+struct Shared {
+    running: AtomicBool,
+    channels: AtomicUsize,
+    /// Samples the callback had to output as silence because the worker was late
+    late_samples: AtomicU64,
+}
+
+/// A [`BlockProcessor`] running on a dedicated worker thread
+///
+/// Uses a double-buffer handshake: the output starts two blocks ahead,
+/// so the worker has a whole block period to process while the callback
+/// fills the next one. Latency is fixed at two blocks plus the effect's
+/// own latency.
+pub struct WorkerOffload {
+    to_worker: HeapProd<f32>,
+    from_worker: HeapCons<f32>,
+    shared: Arc<Shared>,
+    worker: Thread,
+    latency: usize,
+}
+
+impl WorkerOffload {
+    /// Start a worker running `processor` on audio with `channels` channels
+    pub fn spawn(mut processor: Box<dyn BlockProcessor>, channels: usize) -> Self {
+        let channels = channels.max(1);
+        let capacity = OFFLOAD_BLOCK_FRAMES * channels * RING_BLOCKS;
+        let (to_worker, mut worker_input) = HeapRb::<f32>::new(capacity).split();
+        let (mut worker_output, from_worker) = HeapRb::<f32>::new(capacity).split();
+
+        // Double buffer: two blocks of silence before the first processed one
+        let prefill = vec![0.0; OFFLOAD_LATENCY_FRAMES * channels];
+        worker_output.push_slice(&prefill);
+        let latency = OFFLOAD_LATENCY_FRAMES + processor.latency();
+
+        let shared = Arc::new(Shared {
+            running: AtomicBool::new(true),
+            channels: AtomicUsize::new(channels),
+            late_samples: AtomicU64::new(0),
+        });
+
+        let worker_shared = shared.clone();
+        let handle = thread::Builder::new()
+            .name("dsp-worker".into())
+            .spawn(move || {
+                let mut block = Vec::new();
+                while worker_shared.running.load(Ordering::Acquire) {
+                    let channels = worker_shared.channels.load(Ordering::Relaxed).max(1);
+                    let size = OFFLOAD_BLOCK_FRAMES * channels;
+                    if worker_input.occupied_len() < size {
+                        thread::park_timeout(WORKER_POLL);
+                        continue;
+                    }
+
+                    block.resize(size, 0.0);
+                    worker_input.pop_slice(&mut block);
+                    processor.process(&mut block, channels);
+                    worker_output.push_slice(&block);
+                }
+            })
+            .expect("failed to spawn DSP worker");
+
+        Self {
+            to_worker,
+            from_worker,
+            shared,
+            worker: handle.thread().clone(),
+            latency,
+        }
+    }
+
+    /// Delay from input to output, in frames
+    pub fn latency(&self) -> usize {
+        self.latency
+    }
+
+    /// Samples replaced by silence because the worker fell behind
+    pub fn late_samples(&self) -> u64 {
+        self.shared.late_samples.load(Ordering::Relaxed)
+    }
+
+    /// Hand `data` to the worker and replace it with processed samples
+    ///
+    /// Real-time safe: never blocks or allocates.
+    pub fn process(&mut self, data: &mut [f32], channels: usize) {
+        self.shared.channels.store(channels.max(1), Ordering::Relaxed);
+        self.to_worker.push_slice(data);
+        self.worker.unpark();
+
+        let received = self.from_worker.pop_slice(data);
+        if received < data.len() {
+            data[received..].fill(0.0);
+            self.shared
+                .late_samples
+                .fetch_add((data.len() - received) as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for WorkerOffload {
+    fn drop(&mut self) {
+        // The worker exits on its own; joining here could block the caller
+        self.shared.running.store(false, Ordering::Release);
+        self.worker.unpark();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Gain(f32);
+
+    impl BlockProcessor for Gain {
+        fn process(&mut self, data: &mut [f32], _channels: usize) {
+            data.iter_mut().for_each(|s| *s *= self.0);
+        }
+    }
+
+    #[test]
+    fn test_output_is_processed_with_fixed_latency() {
+        let mut offload = WorkerOffload::spawn(Box::new(Gain(0.5)), 1);
+        assert_eq!(offload.latency(), 2 * OFFLOAD_BLOCK_FRAMES);
+
+        let input: Vec<f32> = (0..OFFLOAD_BLOCK_FRAMES * 8).map(|n| n as f32).collect();
+        let mut output = Vec::new();
+        for chunk in input.chunks(OFFLOAD_BLOCK_FRAMES / 2) {
+            let mut data = chunk.to_vec();
+            offload.process(&mut data, 1);
+            output.extend(data);
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(offload.late_samples(), 0);
+        assert!(output[..offload.latency()].iter().all(|&s| s == 0.0));
+        for n in offload.latency()..output.len() {
+            assert_eq!(output[n], input[n - offload.latency()] * 0.5);
+        }
+    }
+}
@@ -5,6 +5,10 @@
 
 mod logging;
 mod sentry;
+mod tally;
+mod telemetry;
 
 pub use logging::*;
-pub use sentry::init_sentry;
+pub use sentry::{init_sentry, set_sentry_context};
+pub use tally::*;
+pub use telemetry::*;
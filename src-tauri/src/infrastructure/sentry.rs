@@ -1,6 +1,8 @@
 //! Sentry error tracking configuration
 
+use sentry::protocol::Context;
 use sentry::ClientInitGuard;
+use serde::Serialize;
 use std::env;
 
 /// Initialize Sentry error tracking
@@ -35,3 +37,13 @@ pub fn init_sentry() -> Option<ClientInitGuard> {
         None
     }
 }
+
+/// Attach a named context to every later report, e.g. the app info
+///
+/// A no-op when Sentry is disabled.
+pub fn set_sentry_context(name: &str, value: &impl Serialize) {
+    let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(value) else {
+        return;
+    };
+    sentry::configure_scope(|scope| scope.set_context(name, Context::Other(fields.into_iter().collect())));
+}
@@ -0,0 +1,266 @@
+//! Tally light output - Drives an "on air" light from engine state
+//!
+//! The light is on while mixing is active and the mic is unmuted.
+//! Supported hardware: serial/USB relay boards and HID busylights.
+
+use crate::domain::{TallyDevice, TallySettings};
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Errors that can occur when driving a tally light
+#[derive(Debug, thiserror::Error)]
+pub enum TallyError {
+    #[error("Device not found: {0}")]
+    DeviceNotFound(String),
+
+    #[error("IO error: {0}")]
+    IoError(String),
+}
+
+/// A light that can be switched on and off
+pub trait TallyLight: Send {
+    fn set_on_air(&mut self, on_air: bool) -> Result<(), TallyError>;
+}
+
+/// Single-channel relay board driven over a serial port
+pub struct SerialRelayTally {
+    port: Box<dyn serialport::SerialPort>,
+}
+
+impl SerialRelayTally {
+    /// LCUS-1 relay commands: start byte, relay index, state, checksum
+    const ON: [u8; 4] = [0xA0, 0x01, 0x01, 0xA2];
+    const OFF: [u8; 4] = [0xA0, 0x01, 0x00, 0xA1];
+
+    pub fn open(port: &str, baud_rate: u32) -> Result<Self, TallyError> {
+        let port = serialport::new(port, baud_rate)
+            .timeout(Duration::from_millis(200))
+            .open()
+            .map_err(|e| match e.kind() {
+                serialport::ErrorKind::NoDevice => TallyError::DeviceNotFound(port.to_string()),
+                _ => TallyError::IoError(e.to_string()),
+            })?;
+        Ok(Self { port })
+    }
+}
+
+impl TallyLight for SerialRelayTally {
+    fn set_on_air(&mut self, on_air: bool) -> Result<(), TallyError> {
+        let command = if on_air { Self::ON } else { Self::OFF };
+        self.port
+            .write_all(&command)
+            .and_then(|_| self.port.flush())
+            .map_err(|e| TallyError::IoError(e.to_string()))
+    }
+}
+
+/// Luxafor-compatible HID busylight
+pub struct BusylightTally {
+    device: hidapi::HidDevice,
+}
+
+impl BusylightTally {
+    const VENDOR_ID: u16 = 0x04D8;
+    const PRODUCT_ID: u16 = 0xF372;
+    /// Red while on air
+    const ON_AIR_COLOR: (u8, u8, u8) = (0xFF, 0x00, 0x00);
+
+    pub fn open() -> Result<Self, TallyError> {
+        let api = hidapi::HidApi::new().map_err(|e| TallyError::IoError(e.to_string()))?;
+        let device = api
+            .open(Self::VENDOR_ID, Self::PRODUCT_ID)
+            .map_err(|_| TallyError::DeviceNotFound("busylight".into()))?;
+        Ok(Self { device })
+    }
+}
+
+impl TallyLight for BusylightTally {
+    fn set_on_air(&mut self, on_air: bool) -> Result<(), TallyError> {
+        let (r, g, b) = if on_air { Self::ON_AIR_COLOR } else { (0, 0, 0) };
+        // Report id, static color command, all LEDs, RGB
+        let report = [0x00, 0x01, 0xFF, r, g, b, 0x00, 0x00, 0x00];
+        self.device
+            .write(&report)
+            .map(|_| ())
+            .map_err(|e| TallyError::IoError(e.to_string()))
+    }
+}
+
+/// Open the light described by the settings
+fn open_light(device: &TallyDevice) -> Result<Box<dyn TallyLight>, TallyError> {
+    match device {
+        TallyDevice::SerialRelay { port, baud_rate } => {
+            Ok(Box::new(SerialRelayTally::open(port, *baud_rate)?))
+        }
+        TallyDevice::Busylight => Ok(Box::new(BusylightTally::open()?)),
+    }
+}
+
+struct TallyState {
+    light: Option<Box<dyn TallyLight>>,
+    mixing: bool,
+    mic_muted: bool,
+    /// Push-to-talk gate closed, apart from the mute button
+    talk_muted: bool,
+    lit: bool,
+}
+
+impl TallyState {
+    /// Switch the light if the on-air state changed
+    fn update(&mut self) {
+        let on_air = self.mixing && !self.mic_muted && !self.talk_muted;
+        if on_air == self.lit {
+            return;
+        }
+
+        if let Some(light) = self.light.as_mut() {
+            match light.set_on_air(on_air) {
+                Ok(()) => {
+                    self.lit = on_air;
+                    tracing::debug!(on_air, "Tally light switched");
+                }
+                Err(e) => tracing::warn!(error = %e, "Failed to switch tally light"),
+            }
+        }
+    }
+}
+
+/// Follows engine state transitions and switches the tally light
+pub struct TallyController {
+    state: Mutex<TallyState>,
+}
+
+impl TallyController {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(TallyState {
+                light: None,
+                mixing: false,
+                mic_muted: false,
+                talk_muted: false,
+                lit: false,
+            }),
+        }
+    }
+
+    /// Apply tally settings, opening (or releasing) the hardware
+    pub fn configure(&self, settings: &TallySettings) -> Result<(), TallyError> {
+        let light = match (&settings.device, settings.enabled) {
+            (Some(device), true) => Some(open_light(device)?),
+            _ => None,
+        };
+        self.attach(light);
+        Ok(())
+    }
+
+    /// Replace the driven light, switching the previous one off
+    pub fn attach(&self, light: Option<Box<dyn TallyLight>>) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+
+        if let Some(mut old) = state.light.take() {
+            if state.lit {
+                let _ = old.set_on_air(false);
+            }
+        }
+        state.light = light;
+        state.lit = false;
+        state.update();
+    }
+
+    pub fn set_mixing(&self, mixing: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.mixing = mixing;
+            state.update();
+        }
+    }
+
+    pub fn set_mic_muted(&self, muted: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.mic_muted = muted;
+            state.update();
+        }
+    }
+
+    pub fn set_talk_muted(&self, muted: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.talk_muted = muted;
+            state.update();
+        }
+    }
+
+    /// Check if the light is currently lit
+    pub fn is_on_air(&self) -> bool {
+        self.state.lock().map(|s| s.lit).unwrap_or(false)
+    }
+}
+
+impl Default for TallyController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    struct RecordingLight(Arc<Mutex<Vec<bool>>>);
+
+    impl TallyLight for RecordingLight {
+        fn set_on_air(&mut self, on_air: bool) -> Result<(), TallyError> {
+            self.0.lock().unwrap().push(on_air);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_on_air_while_mixing_and_unmuted() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let tally = TallyController::new();
+        tally.attach(Some(Box::new(RecordingLight(calls.clone()))));
+
+        tally.set_mixing(true);
+        assert!(tally.is_on_air());
+
+        tally.set_mic_muted(true);
+        assert!(!tally.is_on_air());
+
+        // No redundant writes
+        tally.set_mixing(false);
+        tally.set_mic_muted(false);
+        assert_eq!(*calls.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn test_talk_gate_and_mute_button_both_keep_it_off() {
+        let tally = TallyController::new();
+        tally.attach(Some(Box::new(RecordingLight(Arc::new(Mutex::new(Vec::new()))))));
+        tally.set_mixing(true);
+
+        tally.set_mic_muted(true);
+        tally.set_talk_muted(false);
+        assert!(!tally.is_on_air());
+
+        tally.set_mic_muted(false);
+        tally.set_talk_muted(true);
+        assert!(!tally.is_on_air());
+        tally.set_talk_muted(false);
+        assert!(tally.is_on_air());
+    }
+
+    #[test]
+    fn test_replacing_light_switches_old_one_off() {
+        let old_calls = Arc::new(Mutex::new(Vec::new()));
+        let tally = TallyController::new();
+        tally.attach(Some(Box::new(RecordingLight(old_calls.clone()))));
+        tally.set_mixing(true);
+
+        tally.attach(None);
+        assert_eq!(*old_calls.lock().unwrap(), vec![true, false]);
+        assert!(!tally.is_on_air());
+    }
+}
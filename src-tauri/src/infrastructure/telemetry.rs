@@ -0,0 +1,174 @@
+//! Opt-in anonymous usage telemetry
+//!
+//! Feature usage is aggregated locally as plain counters (no content, no
+//! identifiers). Reports are only uploaded when the user has opted in and
+//! a `TELEMETRY_ENDPOINT` is configured at runtime.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Interval between telemetry uploads (1 hour)
+pub const TELEMETRY_UPLOAD_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Errors that can occur while uploading telemetry
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("Telemetry endpoint not configured")]
+    NotConfigured,
+
+    #[error("Telemetry upload failed: {0}")]
+    UploadFailed(String),
+}
+
+/// Aggregated usage report, exactly as it would be uploaded
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryReport {
+    pub app_version: String,
+    pub os: String,
+    /// Start of the aggregation period (unix seconds)
+    pub period_start: u64,
+    /// End of the aggregation period (unix seconds)
+    pub period_end: u64,
+    /// Number of times each feature was used during the period
+    pub feature_counts: BTreeMap<String, u64>,
+}
+
+struct TelemetryData {
+    period_start: u64,
+    counts: BTreeMap<String, u64>,
+    last_upload: Option<u64>,
+}
+
+/// Thread-safe local aggregator for feature usage counters
+pub struct TelemetryCollector {
+    data: Mutex<TelemetryData>,
+}
+
+impl TelemetryCollector {
+    pub fn new() -> Self {
+        Self {
+            data: Mutex::new(TelemetryData {
+                period_start: unix_now(),
+                counts: BTreeMap::new(),
+                last_upload: None,
+            }),
+        }
+    }
+
+    /// Record one use of a feature
+    pub fn record(&self, feature: &str) {
+        if let Ok(mut data) = self.data.lock() {
+            *data.counts.entry(feature.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    /// Build the report for the current aggregation period
+    pub fn report(&self) -> TelemetryReport {
+        let (period_start, feature_counts) = match self.data.lock() {
+            Ok(data) => (data.period_start, data.counts.clone()),
+            Err(_) => (unix_now(), BTreeMap::new()),
+        };
+
+        TelemetryReport {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            period_start,
+            period_end: unix_now(),
+            feature_counts,
+        }
+    }
+
+    /// Time of the last successful upload (unix seconds)
+    pub fn last_upload(&self) -> Option<u64> {
+        self.data.lock().ok().and_then(|data| data.last_upload)
+    }
+
+    /// Remove the uploaded counts, keeping anything recorded since the report was built
+    fn mark_uploaded(&self, report: &TelemetryReport) {
+        if let Ok(mut data) = self.data.lock() {
+            for (feature, count) in &report.feature_counts {
+                if let Some(current) = data.counts.get_mut(feature) {
+                    *current = current.saturating_sub(*count);
+                }
+            }
+            data.counts.retain(|_, count| *count > 0);
+            data.period_start = report.period_end;
+            data.last_upload = Some(report.period_end);
+        }
+    }
+
+    /// Upload the current report to the configured endpoint
+    pub async fn upload(&self) -> Result<(), TelemetryError> {
+        let endpoint = telemetry_endpoint().ok_or(TelemetryError::NotConfigured)?;
+        let report = self.report();
+
+        if report.feature_counts.is_empty() {
+            tracing::debug!("No telemetry recorded, skipping upload");
+            return Ok(());
+        }
+
+        reqwest::Client::new()
+            .post(&endpoint)
+            .json(&report)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| TelemetryError::UploadFailed(e.to_string()))?;
+
+        self.mark_uploaded(&report);
+        tracing::info!(features = report.feature_counts.len(), "Telemetry uploaded");
+        Ok(())
+    }
+}
+
+impl Default for TelemetryCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the telemetry endpoint (runtime env var)
+pub fn telemetry_endpoint() -> Option<String> {
+    env::var("TELEMETRY_ENDPOINT").ok().filter(|s| !s.is_empty())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts() {
+        let collector = TelemetryCollector::new();
+        collector.record("play_sound");
+        collector.record("play_sound");
+        collector.record("start_mixing");
+
+        let report = collector.report();
+        assert_eq!(report.feature_counts.get("play_sound"), Some(&2));
+        assert_eq!(report.feature_counts.get("start_mixing"), Some(&1));
+    }
+
+    #[test]
+    fn test_mark_uploaded_keeps_new_counts() {
+        let collector = TelemetryCollector::new();
+        collector.record("play_sound");
+        let report = collector.report();
+        collector.record("play_sound");
+
+        collector.mark_uploaded(&report);
+
+        let next = collector.report();
+        assert_eq!(next.feature_counts.get("play_sound"), Some(&1));
+        assert!(collector.last_upload().is_some());
+    }
+}
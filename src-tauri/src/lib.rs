@@ -12,40 +12,90 @@
 //! - **Adapters**: Concrete implementations (cpal, rodio, WASAPI)
 //! - **Application**: Use cases and orchestration
 //! - **Infrastructure**: Cross-cutting concerns (logging, config)
+//! - **DSP**: Real-time signal processing building blocks (FFT, denoising)
 
 pub mod domain;
 pub mod ports;
 pub mod adapters;
 pub mod application;
+pub mod dsp;
 pub mod infrastructure;
 
 use tauri::{Manager, Emitter};
 use tauri::menu::{Menu, MenuItem, Submenu};
+use tauri_plugin_global_shortcut::ShortcutState;
 use crate::application::audio_engine::AudioEngineEvent;
+use crate::application::correlation::Traced;
+use crate::domain::OnboardingStep;
 use application::{
     commands::{
+        // Startup
+        is_backend_ready, get_safe_mode,
         // Device management
         get_audio_devices, get_input_devices, get_virtual_output_devices, check_virtual_driver,
+        refresh_devices, validate_mixing_config, get_virtual_device_settings, add_virtual_device_pattern,
+        remove_virtual_device_pattern, mark_device_as_virtual, set_device_type_override,
         // Settings
         get_settings, save_settings, load_settings, set_input_device, set_output_device, set_preview_device,
         // Mixer configuration
-        get_mixer_config, set_master_volume,
+        get_mixer_config, set_master_volume, get_output_format, set_output_format, match_device_sample_rate, get_output_layout, set_output_layout,
+        get_monitor, set_monitor, get_headphone_limiter, set_headphone_limiter, get_feedback_protection, set_feedback_protection,
+        clear_feedback_mute,
+        get_ducking_config, set_ducking_config, get_buffer_auto_tune, set_buffer_auto_tune, get_broadcast_delay, set_broadcast_delay, dump_delay, censor_last,
         // Channel management
-        add_microphone_channel, add_audio_file_channel, remove_channel,
-        set_channel_volume, toggle_channel_mute,
+        add_microphone_channel, add_audio_file_channel, list_capturable_apps, add_app_channel, add_generator_channel, set_generator, remove_channel,
+        set_channel_volume, toggle_channel_mute, momentary_mute, set_mute_groups, set_mute_group_muted,
         // Mixing control
-        start_mixing, stop_mixing, is_mixing,
+        start_mixing, stop_mixing, is_mixing, get_engine_snapshot, get_watchdog_diagnostics, take_test_audio_capture,
         // Sound playback
-        load_sound_file, play_sound, stop_sound, preview_sound, stop_preview, get_preview_state,
-        set_mic_volume, set_mic_muted,
+        load_sound_file, play_sound, play_sounds_synced, play_stem_sound, set_stem_volume, stop_sound, pause_sound, resume_sound, seek_sound, set_sound_volume, set_sound_width, set_sound_inserts, set_sound_speed, measure_sound_loudness, set_sound_auto_level, stop_all_sounds, get_playback_settings, set_playback_settings, preview_sound, stop_preview, get_preview_state,
+        set_mic_volume, set_mic_muted, get_voice_activity, set_voice_activity,
         // Soundboard persistence
-        save_soundboard, load_soundboard,
+        save_soundboard, load_soundboard, pick_pad_variant, trigger_pad_actions, start_timer, cancel_timer, get_running_timers,
+        get_scenes, save_scene, delete_scene, get_active_scene, recall_scene, get_start_jingle, set_start_jingle,
+        import_sound_pack,
+        get_play_log, list_play_sessions, export_play_log_csv, get_session_history, export_attributions, suggest_cleanup, archive_sounds, get_library_stats,
+        start_recording, stop_recording, quit_app, is_recording, add_marker, get_markers,
+        start_board_share, stop_board_share, get_board_share,
+        get_now_playing, start_overlay, stop_overlay, get_overlay,
+        get_hot_folder, set_hot_folder,
+        get_integration_permissions, set_integration_policy, run_integration_trigger,
+        get_sync_config, set_sync_config, get_sync_status, sync_now,
+        // Hotkeys
+        validate_hotkey, set_sound_hotkey, get_active_hotkeys, set_active_hotkey_profile, get_board, next_board, previous_board,
+        get_board_hotkeys, set_board_hotkeys,
+        // Global hotkeys
+        get_global_hotkeys, register_global_hotkey, unregister_global_hotkey, set_global_hotkeys_enabled,
+        // Push-to-talk
+        get_push_to_talk, set_push_to_talk,
+        // Offline render
+        render_mix,
+        // Mic processing
+        learn_noise_profile, set_noise_reduction_enabled, clear_noise_profile, set_echo_cancellation,
+        get_mic_agc, set_mic_agc, get_noise_gate, set_noise_gate, get_mic_profile, save_mic_profile_as_default, reset_mic_profile, get_keyboard_suppression, set_keyboard_suppression,
+        get_input_channel_map, set_input_channel_map, setup_podcast_mode, set_podcast_mic,
+        set_mic_highpass, set_mic_chain_bypass,
+        get_mic_chain, move_effect, set_effect_enabled, set_effect_mix,
+        get_spectral_quality, set_spectral_quality,
+        get_builtin_presets, apply_builtin_preset, set_voice_changer,
+        // Export
+        export_sound,
+        // Quick memo
+        quick_memo, stop_quick_memo,
+        start_gain_wizard, get_gain_wizard, cancel_gain_wizard, apply_gain_recommendation,
         // Updates
-        check_for_update, install_update,
+        check_for_update, install_update, get_release_notes, set_update_channel,
+        get_update_download_state, set_install_on_quit,
+        // Onboarding
+        get_onboarding_state, complete_onboarding_step,
+        // Telemetry
+        get_telemetry_status, set_telemetry_enabled, get_telemetry_report,
+        // Tally light
+        get_tally_settings, set_tally_settings, is_on_air,
         // Debug
-        get_debug_mode, set_debug_mode, get_sentry_dsn,
+        get_debug_mode, set_debug_mode, get_sentry_dsn, get_app_info,
     },
-    AppState, PreviewEngine,
+    AppState, AudioEngine, NullAudioDevices,
 };
 
 /// Run the Tauri application
@@ -64,8 +114,38 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    if event.state() == ShortcutState::Pressed {
+                        application::run_global_hotkey(app, shortcut.id());
+                    }
+                })
+                .build(),
+        )
         .setup(|app| {
-            let state = AppState::new();
+            let app_data_dir = app.path().app_data_dir()?;
+            let mut state = AppState::new();
+
+            // Counted before anything that may crash; cleared once the app
+            // has run for a while, or on a clean quit before that
+            let sentinel = application::StartupSentinel::new(&app_data_dir);
+            state.safe_mode = application::SafeMode::detect(application::safe_mode_requested(), sentinel.record_start());
+            sentinel.clear_when_stable();
+
+            // Recovers a settings file left corrupt by a crash
+            state.settings_store.open(&app_data_dir)?;
+
+            if let Some(reason) = state.safe_mode.reason() {
+                tracing::warn!(reason = ?reason, "Safe mode: audio and hotkeys stay off");
+                state.audio_engine = std::sync::Arc::new(AudioEngine::disabled());
+            } else if application::test_audio_requested() {
+                // Headless runs (CI) mix on null devices and expose the output
+                tracing::info!("Test audio mode: the engine runs on the null devices");
+                let devices = NullAudioDevices::new();
+                state.audio_engine = std::sync::Arc::new(AudioEngine::with_null_devices(devices.clone()));
+                app.manage(devices);
+            }
             app.manage(state);
 
             // Create application menu with Debug toggle
@@ -79,38 +159,163 @@ pub fn run() {
             let menu = Menu::with_items(app, &[&app_submenu])?;
             app.set_menu(menu)?;
 
-            // Initialize preview engine with app handle
             let app_handle = app.handle().clone();
             let state_ref = app.state::<AppState>();
-            let preview_engine = PreviewEngine::new(app_handle.clone());
-            {
-                let mut preview = state_ref.preview_engine.blocking_lock();
-                *preview = Some(preview_engine);
-            }
+            application::restore_window(&app_handle);
+
+            // The preview engine and device enumeration start once setup
+            // returns, so they don't hold back the first paint
+            application::spawn_deferred_init(app_handle.clone());
 
-            // Start level event forwarding
-            let engine_for_levels = state_ref.audio_engine.clone();
-            std::thread::spawn(move || {
+            // Periodically upload telemetry when the user has opted in
+            let settings_for_telemetry = state_ref.settings.clone();
+            let telemetry = state_ref.telemetry.clone();
+            tauri::async_runtime::spawn(async move {
+                let interval = std::time::Duration::from_secs(infrastructure::TELEMETRY_UPLOAD_INTERVAL_SECS);
                 loop {
-                    if let Ok(engine) = engine_for_levels.try_lock() {
-                        while let Some(event) = engine.try_recv_event() {
-                            match event {
-                                AudioEngineEvent::LevelUpdate { input_rms, input_peak, output_rms, output_peak } => {
-                                    let _ = app_handle.emit("audio-levels", serde_json::json!({
-                                        "inputRms": input_rms,
-                                        "inputPeak": input_peak,
-                                        "outputRms": output_rms,
-                                        "outputPeak": output_peak,
-                                    }));
-                                }
-                                _ => {}
-                            }
-                        }
+                    tokio::time::sleep(interval).await;
+                    if !settings_for_telemetry.read().await.telemetry_enabled {
+                        continue;
+                    }
+                    if let Err(e) = telemetry.upload().await {
+                        tracing::warn!(error = %e, "Telemetry upload skipped");
                     }
-                    std::thread::sleep(std::time::Duration::from_millis(16));
                 }
             });
 
+            // Mirror hardware mic-mute keys into the engine
+            if !state_ref.safe_mode.is_active() {
+                application::spawn_mic_mute_watcher(
+                    app_handle.clone(),
+                    state_ref.mic_mute_sync.clone(),
+                    state_ref.settings.clone(),
+                    state_ref.audio_engine.clone(),
+                    state_ref.tally.clone(),
+                );
+            }
+
+            // Write fader and mute changes once they settle
+            application::spawn_auto_save(state_ref.auto_save.clone(), {
+                let app_handle = app_handle.clone();
+                move |target| application::save_dirty(&app_handle, target)
+            });
+
+            // Run trigger files dropped by external tools
+            application::spawn_hot_folder_watcher(
+                app_handle.clone(),
+                state_ref.settings.clone(),
+                application::run_hot_folder_command,
+            );
+
+            // Restore onboarding progress
+            state_ref.onboarding.load(&app_handle);
+
+            // Start engine event forwarding; the forwarder owns the receiver,
+            // so commands never contend with it for the engine
+            let events = state_ref
+                .audio_engine
+                .take_event_receiver()
+                .ok_or("Engine events are already forwarded")?;
+            let onboarding = state_ref.onboarding.clone();
+            let tally = state_ref.tally.clone();
+            let session_tracker = state_ref.session_tracker.clone();
+            let automations = application::Automations::new();
+            std::thread::Builder::new().name("engine-events".into()).spawn(move || {
+                // Blocks until the next event; ends when the engine is dropped
+                for Traced { span, value: event } in events.iter() {
+                    // Under the span of the command the event results from
+                    let _entered = span.enter();
+                    if !matches!(event, AudioEngineEvent::LevelUpdate { .. } | AudioEngineEvent::VoiceActivity { .. }) {
+                        tracing::debug!(event = ?event, "Engine event");
+                    }
+                    for rule in automations.rules_for(&event) {
+                        application::run_automation(&app_handle, rule);
+                    }
+                    match event {
+                        AudioEngineEvent::LevelUpdate { input_rms, input_peak, output_rms, output_peak } => {
+                            session_tracker.record_level(output_rms, output_peak);
+                            let _ = app_handle.emit("audio-levels", serde_json::json!({
+                                "inputRms": input_rms,
+                                "inputPeak": input_peak,
+                                "outputRms": output_rms,
+                                "outputPeak": output_peak,
+                            }));
+                        }
+                        AudioEngineEvent::VoiceActivity { speaking } => {
+                            let _ = app_handle.emit("voice-activity", serde_json::json!({
+                                "speaking": speaking,
+                            }));
+                        }
+                        AudioEngineEvent::FeedbackDetected => {
+                            // The engine already muted the mic, as its own mute source;
+                            // `clear_feedback_mute` reopens it
+                            let _ = app_handle.emit("feedback-detected", serde_json::json!({
+                                "code": "FEEDBACK_DETECTED",
+                                "message": "Feedback loop detected, the microphone was muted. \
+                                            Check that the mic isn't the virtual cable the mix plays into.",
+                            }));
+                        }
+                        AudioEngineEvent::SampleRateMismatch { device, device_rate, engine_rate } => {
+                            let _ = app_handle.emit("sample-rate-mismatch", serde_json::json!({
+                                "device": device,
+                                "deviceRate": device_rate,
+                                "engineRate": engine_rate,
+                            }));
+                        }
+                        AudioEngineEvent::Started => {
+                            // A successful start completes the setup test
+                            let _ = onboarding.complete_step(&app_handle, OnboardingStep::TestPassed);
+                            tally.set_mixing(true);
+                            session_tracker.start(application::now_millis());
+                        }
+                        AudioEngineEvent::DeviceInUse { device, is_input } => {
+                            let _ = app_handle.emit("device-in-use", serde_json::json!({
+                                "code": "DEVICE_IN_USE",
+                                "device": device,
+                                "isInput": is_input,
+                                "message": format!(
+                                    "\"{}\" is held in exclusive mode by another application. \
+                                     Close apps that may own it (DAWs, games, other voice tools) or \
+                                     untick \"Allow applications to take exclusive control\" in the \
+                                     Windows sound settings for this device.",
+                                    device
+                                ),
+                            }));
+                            tally.set_mixing(false);
+                            application::on_mixing_stopped(&app_handle);
+                        }
+                        AudioEngineEvent::Stalled { stream, silent_ms, rebuilding } => {
+                            let _ = app_handle.emit("engine-stalled", serde_json::json!({
+                                "stream": stream,
+                                "silentMs": silent_ms,
+                                "rebuilding": rebuilding,
+                            }));
+                        }
+                        AudioEngineEvent::Underruns { count } => {
+                            session_tracker.record_underruns(count);
+                            application::on_underruns(&app_handle, count);
+                        }
+                        AudioEngineEvent::SeekFailed { id } => {
+                            let _ = app_handle.emit("seek-failed", serde_json::json!({
+                                "code": "SEEK_FAILED",
+                                "id": id,
+                                "message": "The sound couldn't be moved to that position and played on from where it was.",
+                            }));
+                        }
+                        AudioEngineEvent::Stopped => {
+                            tally.set_mixing(false);
+                            application::on_mixing_stopped(&app_handle);
+                        }
+                        AudioEngineEvent::Error(_) => {
+                            // The streams are gone, so the session ends here
+                            tally.set_mixing(false);
+                            application::on_mixing_stopped(&app_handle);
+                        }
+                        _ => {}
+                    }
+                }
+            })?;
+
             Ok(())
         })
         .on_menu_event(|app, event| {
@@ -127,52 +332,293 @@ pub fn run() {
                 }
             }
         })
-        .invoke_handler(tauri::generate_handler![
-            // Device management
-            get_audio_devices,
-            get_input_devices,
-            get_virtual_output_devices,
-            check_virtual_driver,
-            // Settings
-            get_settings,
-            save_settings,
-            load_settings,
-            set_input_device,
-            set_output_device,
-            set_preview_device,
-            // Mixer configuration
-            get_mixer_config,
-            set_master_volume,
-            // Channel management
-            add_microphone_channel,
-            add_audio_file_channel,
-            remove_channel,
-            set_channel_volume,
-            toggle_channel_mute,
-            // Mixing control
-            start_mixing,
-            stop_mixing,
-            is_mixing,
-            // Sound playback
-            load_sound_file,
-            play_sound,
-            stop_sound,
-            preview_sound,
-            stop_preview,
-            get_preview_state,
-            set_mic_volume,
-            set_mic_muted,
-            // Soundboard persistence
-            save_soundboard,
-            load_soundboard,
-            // Updates
-            check_for_update,
-            install_update,
-            // Debug
-            get_debug_mode,
-            set_debug_mode,
-            get_sentry_dsn,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        // Every command runs in its own span with a correlation id; async
+        // commands keep it through the spans Tauri instruments them with
+        .invoke_handler({
+            let handler = tauri::generate_handler![
+                // Startup
+                is_backend_ready,
+                get_safe_mode,
+                // Device management
+                get_audio_devices,
+                get_input_devices,
+                get_virtual_output_devices,
+                check_virtual_driver,
+                get_virtual_device_settings,
+                add_virtual_device_pattern,
+                remove_virtual_device_pattern,
+                mark_device_as_virtual,
+                set_device_type_override,
+                refresh_devices,
+                validate_mixing_config,
+                // Settings
+                get_settings,
+                save_settings,
+                load_settings,
+                set_input_device,
+                set_output_device,
+                set_preview_device,
+                // Mixer configuration
+                get_mixer_config,
+                set_master_volume,
+                get_output_format,
+                set_output_format,
+                get_output_layout,
+                set_output_layout,
+                get_start_jingle,
+                set_start_jingle,
+                match_device_sample_rate,
+                get_monitor,
+                set_monitor,
+                get_headphone_limiter,
+                set_headphone_limiter,
+                get_feedback_protection,
+                set_feedback_protection,
+                clear_feedback_mute,
+                get_ducking_config,
+                set_ducking_config,
+                get_buffer_auto_tune,
+                set_buffer_auto_tune,
+                get_broadcast_delay,
+                set_broadcast_delay,
+                dump_delay,
+                censor_last,
+                // Channel management
+                add_microphone_channel,
+                add_audio_file_channel,
+                list_capturable_apps,
+                add_app_channel,
+                add_generator_channel,
+                set_generator,
+                remove_channel,
+                set_channel_volume,
+                toggle_channel_mute,
+                momentary_mute,
+                set_mute_groups,
+                set_mute_group_muted,
+                // Mixing control
+                start_mixing,
+                stop_mixing,
+                is_mixing,
+                get_engine_snapshot,
+                get_watchdog_diagnostics,
+                take_test_audio_capture,
+                // Sound playback
+                load_sound_file,
+                play_sound,
+                play_sounds_synced,
+                play_stem_sound,
+                set_stem_volume,
+                stop_sound,
+                pause_sound,
+                resume_sound,
+                seek_sound,
+                set_sound_volume,
+                set_sound_width,
+                set_sound_inserts,
+                set_sound_speed,
+                measure_sound_loudness,
+                set_sound_auto_level,
+                stop_all_sounds,
+                get_playback_settings,
+                set_playback_settings,
+                preview_sound,
+                stop_preview,
+                get_preview_state,
+                set_mic_volume,
+                set_mic_muted,
+                get_voice_activity,
+                set_voice_activity,
+                // Soundboard persistence
+                save_soundboard,
+                load_soundboard,
+                pick_pad_variant,
+                trigger_pad_actions,
+                start_timer,
+                cancel_timer,
+                get_running_timers,
+                get_scenes,
+                save_scene,
+                delete_scene,
+                get_active_scene,
+                recall_scene,
+                import_sound_pack,
+                get_play_log,
+                list_play_sessions,
+                export_play_log_csv,
+                get_session_history,
+                export_attributions,
+                suggest_cleanup,
+                archive_sounds,
+                get_library_stats,
+                start_recording,
+                stop_recording,
+                quit_app,
+                is_recording,
+                add_marker,
+                get_markers,
+                start_board_share,
+                stop_board_share,
+                get_board_share,
+                get_now_playing,
+                start_overlay,
+                stop_overlay,
+                get_overlay,
+                get_hot_folder,
+                set_hot_folder,
+                get_integration_permissions,
+                set_integration_policy,
+                run_integration_trigger,
+                get_sync_config,
+                set_sync_config,
+                get_sync_status,
+                sync_now,
+                // Hotkeys
+                validate_hotkey,
+                set_sound_hotkey,
+                get_active_hotkeys,
+                set_active_hotkey_profile,
+                get_board,
+                next_board,
+                previous_board,
+                get_board_hotkeys,
+                set_board_hotkeys,
+                get_global_hotkeys,
+                register_global_hotkey,
+                unregister_global_hotkey,
+                set_global_hotkeys_enabled,
+                // Push-to-talk
+                get_push_to_talk,
+                set_push_to_talk,
+                // Offline render
+                render_mix,
+                // Mic processing
+                learn_noise_profile,
+                set_noise_reduction_enabled,
+                clear_noise_profile,
+                set_echo_cancellation,
+                get_mic_agc,
+                set_mic_agc,
+                get_noise_gate,
+                set_noise_gate,
+                get_mic_profile,
+                save_mic_profile_as_default,
+                reset_mic_profile,
+                get_keyboard_suppression,
+                set_keyboard_suppression,
+                get_input_channel_map,
+                set_input_channel_map,
+                setup_podcast_mode,
+                set_podcast_mic,
+                set_mic_highpass,
+                set_mic_chain_bypass,
+                get_mic_chain,
+                move_effect,
+                set_effect_enabled,
+                set_effect_mix,
+                get_spectral_quality,
+                set_spectral_quality,
+                get_builtin_presets,
+                apply_builtin_preset,
+                set_voice_changer,
+                // Export
+                export_sound,
+                // Quick memo
+                quick_memo,
+                stop_quick_memo,
+                start_gain_wizard,
+                get_gain_wizard,
+                cancel_gain_wizard,
+                apply_gain_recommendation,
+                // Updates
+                check_for_update,
+                install_update,
+                get_release_notes,
+                set_update_channel,
+                get_update_download_state,
+                set_install_on_quit,
+                // Onboarding
+                get_onboarding_state,
+                complete_onboarding_step,
+                // Telemetry
+                get_telemetry_status,
+                set_telemetry_enabled,
+                get_telemetry_report,
+                // Tally light
+                get_tally_settings,
+                set_tally_settings,
+                is_on_air,
+                // Debug
+                get_debug_mode,
+                set_debug_mode,
+                get_sentry_dsn,
+                get_app_info,
+            ];
+            move |invoke| {
+                let span = application::command_span(invoke.message.command());
+                let _entered = span.enter();
+                handler(invoke)
+            }
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Closing or quitting mid-recording asks the UI to confirm;
+            // it then quits through `quit_app`, which finalizes the file
+            tauri::RunEvent::WindowEvent {
+                event: tauri::WindowEvent::CloseRequested { api, .. },
+                ..
+            } => {
+                if let Some(path) = app.state::<AppState>().recorder.path() {
+                    api.prevent_close();
+                    let _ = app.emit("recording-active", serde_json::json!({ "path": path }));
+                } else {
+                    application::save_window(app);
+                }
+            }
+            tauri::RunEvent::ExitRequested { api, .. } => {
+                if let Some(path) = app.state::<AppState>().recorder.path() {
+                    api.prevent_exit();
+                    let _ = app.emit("recording-active", serde_json::json!({ "path": path }));
+                } else {
+                    // A quit from the app leaves the window open until now
+                    application::save_window(app);
+                }
+            }
+            tauri::RunEvent::Exit => {
+                let state = app.state::<AppState>();
+
+                // Never leave a recording unfinished on disk
+                if state.recorder.is_recording() {
+                    use application::AudioEngineCommand;
+                    let engine = &state.audio_engine;
+                    let _ = engine.send_command(AudioEngineCommand::SetRecordingTap(None));
+                    let _ = engine.send_command(AudioEngineCommand::SetStemTaps(Vec::new()));
+                    if let Err(e) = state.recorder.stop() {
+                        tracing::error!(error = %e, "Failed to finalize recording on exit");
+                    }
+                }
+
+                // Changes still waiting for the auto-save
+                state.auto_save.flush(|target| {
+                    if let Err(e) = application::save_dirty(app, target) {
+                        tracing::error!(target = ?target, error = %e, "Failed to save on exit");
+                    }
+                });
+
+                // A quit, however soon after the start, isn't a crash
+                if let Ok(dir) = app.path().app_data_dir() {
+                    application::StartupSentinel::new(&dir).clear();
+                }
+
+                // Apply a deferred update so it never interrupts a live session;
+                // last, once everything else is on disk
+                if state.settings.blocking_read().install_on_quit {
+                    if let Err(e) = state.update_downloader.install_pending() {
+                        tracing::error!(error = %e, "Failed to install update on quit");
+                    }
+                }
+            }
+            _ => {}
+        });
 }
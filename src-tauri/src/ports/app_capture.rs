@@ -0,0 +1,75 @@
+//! App capture port - Interface for capturing one application's audio
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// Errors that can occur when capturing an application
+#[derive(Debug, thiserror::Error)]
+pub enum AppCaptureError {
+    #[error("Application capture is not supported on this system")]
+    NotSupported,
+
+    #[error("Process not found: {0}")]
+    ProcessNotFound(u32),
+
+    #[error("Capture failed: {0}")]
+    CaptureFailed(String),
+}
+
+/// An application currently playing audio
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CapturableApp {
+    pub process_id: u32,
+    /// Executable name without extension, e.g. "Spotify"
+    pub name: String,
+}
+
+/// Receives captured interleaved samples on the capture thread
+pub type AppAudioSink = Box<dyn FnMut(&[f32]) + Send>;
+
+/// A running capture; dropping it stops the capture thread
+pub struct AppCaptureStream {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AppCaptureStream {
+    /// Wrap a capture thread that exits once `stop` is set
+    pub fn new(stop: Arc<AtomicBool>, thread: JoinHandle<()>) -> Self {
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for AppCaptureStream {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Port for capturing the audio output of a single application
+///
+/// Unlike system loopback, only the chosen process (and its child
+/// processes) is heard, e.g. a music player without game sounds.
+#[cfg_attr(test, mockall::automock)]
+pub trait AppCapture: Send + Sync {
+    /// Applications with an audio session on the default output
+    fn list_apps(&self) -> Result<Vec<CapturableApp>, AppCaptureError>;
+
+    /// Capture `process_id` as float samples at `sample_rate` with
+    /// `channels`, handing each packet to `sink`
+    fn start(
+        &self,
+        process_id: u32,
+        sample_rate: u32,
+        channels: u16,
+        sink: AppAudioSink,
+    ) -> Result<AppCaptureStream, AppCaptureError>;
+}
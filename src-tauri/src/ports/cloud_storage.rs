@@ -0,0 +1,26 @@
+//! Cloud storage port - Interface to a remote object store
+
+/// Errors that can occur when talking to a remote store
+#[derive(Debug, thiserror::Error)]
+pub enum CloudStorageError {
+    #[error("Authentication failed")]
+    Unauthorized,
+
+    #[error("Connection error: {0}")]
+    Connection(String),
+
+    #[error("Server error: {0}")]
+    Server(String),
+}
+
+/// Port for storing small files on a remote server
+///
+/// Keys are relative paths such as `voiceboard/sync.json`. Calls block,
+/// so run them off the async runtime.
+pub trait CloudStorage: Send + Sync {
+    /// Download a file, or None if it doesn't exist
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CloudStorageError>;
+
+    /// Upload a file, replacing any existing one
+    fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), CloudStorageError>;
+}
@@ -21,6 +21,17 @@ pub enum FileDecoderError {
 
     #[error("Invalid file: {0}")]
     InvalidFile(String),
+
+    /// The container is readable but its audio codec isn't
+    #[error("Unsupported codec: {0}")]
+    UnsupportedCodec(String),
+
+    /// The file is damaged past what the decoder can skip over
+    #[error("Corrupt file: {0}")]
+    CorruptFile(String),
+
+    #[error("Seek failed: {0}")]
+    SeekFailed(String),
 }
 
 /// Metadata for an audio file
@@ -55,6 +66,9 @@ pub trait FileDecoder: Send + Sync {
     /// Get the total duration of the file
     fn duration(&self) -> Option<Duration>;
 
+    /// Metadata read when the file was opened
+    fn metadata(&self) -> Option<AudioFileMetadata>;
+
     /// Check if the decoder has reached the end of the file
     fn is_finished(&self) -> bool;
 
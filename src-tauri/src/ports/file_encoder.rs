@@ -0,0 +1,35 @@
+//! File encoder port - Interface for writing audio files
+
+use crate::domain::{AudioBuffer, AudioFileFormat};
+use std::path::Path;
+
+/// Errors that can occur during audio file encoding
+#[derive(Debug, thiserror::Error)]
+pub enum FileEncoderError {
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Encode error: {0}")]
+    EncodeError(String),
+
+    #[error("IO error: {0}")]
+    IoError(String),
+}
+
+/// Port for encoding audio files
+///
+/// This trait defines the contract for writing decoded audio
+/// to disk in a given file format.
+#[cfg_attr(test, mockall::automock)]
+pub trait FileEncoder: Send + Sync {
+    /// Encode a buffer to a file
+    fn encode(
+        &self,
+        path: &Path,
+        buffer: &AudioBuffer,
+        bits_per_sample: u16,
+    ) -> Result<(), FileEncoderError>;
+
+    /// Check if a file format is supported
+    fn supports_format(&self, format: AudioFileFormat) -> bool;
+}
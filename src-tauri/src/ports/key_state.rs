@@ -0,0 +1,14 @@
+//! Key state port - Interface to the keys held right now, system-wide
+
+use crate::domain::KeyCombo;
+
+/// Port for polling whether keys are held, whatever app has focus
+pub trait KeyState: Send + Sync {
+    /// Whether the platform has a code for the key of `combo`
+    fn supports(&self, combo: &KeyCombo) -> bool;
+
+    /// Whether the key of `combo` and all of its modifiers are held
+    ///
+    /// Keys the platform has no code for are never held.
+    fn is_held(&self, combo: &KeyCombo) -> bool;
+}
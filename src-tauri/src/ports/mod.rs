@@ -3,12 +3,24 @@
 //! Ports define the contracts between the domain and the outside world.
 //! They are implemented by adapters.
 
+mod app_capture;
 mod audio_input;
 mod audio_output;
+mod cloud_storage;
 mod file_decoder;
+mod file_encoder;
+mod key_state;
 mod device_manager;
+mod secret_store;
+mod system_mute;
 
+pub use app_capture::*;
 pub use audio_input::*;
 pub use audio_output::*;
+pub use cloud_storage::*;
 pub use file_decoder::*;
+pub use file_encoder::*;
+pub use key_state::*;
 pub use device_manager::*;
+pub use secret_store::*;
+pub use system_mute::*;
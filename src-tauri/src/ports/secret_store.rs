@@ -0,0 +1,23 @@
+//! Secret store port - Interface to the OS credential store
+
+/// Errors that can occur when accessing stored secrets
+#[derive(Debug, thiserror::Error)]
+pub enum SecretStoreError {
+    #[error("Credential store unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// Port for keeping passwords out of the settings files
+///
+/// Secrets are named by what they unlock, such as
+/// `obs:ws://localhost:4455`.
+pub trait SecretStore: Send + Sync {
+    /// Read a secret, or None if none is stored under `name`
+    fn get(&self, name: &str) -> Result<Option<String>, SecretStoreError>;
+
+    /// Store a secret, replacing any existing one
+    fn set(&self, name: &str, secret: &str) -> Result<(), SecretStoreError>;
+
+    /// Remove a secret; removing a missing one is not an error
+    fn delete(&self, name: &str) -> Result<(), SecretStoreError>;
+}
@@ -0,0 +1,28 @@
+//! System mute port - Interface to the OS capture endpoint mute
+
+/// Errors that can occur when accessing the OS mute state
+#[derive(Debug, thiserror::Error)]
+pub enum SystemMuteError {
+    #[error("Device not found: {0}")]
+    DeviceNotFound(String),
+
+    #[error("Not supported on this platform")]
+    NotSupported,
+
+    #[error("System error: {0}")]
+    SystemError(String),
+}
+
+/// Port for reading and setting the OS-level microphone mute
+///
+/// This is the mute toggled by hardware mic-mute keys and headset
+/// buttons. A device name of `"default"` (or empty) targets the default
+/// capture device.
+#[cfg_attr(test, mockall::automock)]
+pub trait SystemMicMute: Send + Sync {
+    /// Check if the capture endpoint is muted
+    fn is_muted(&self, device_name: &str) -> Result<bool, SystemMuteError>;
+
+    /// Mute or unmute the capture endpoint
+    fn set_muted(&self, device_name: &str, muted: bool) -> Result<(), SystemMuteError>;
+}
@@ -13,19 +13,122 @@ export interface AudioDevice {
 export interface MixerChannel {
   id: string;
   name: string;
-  channelType: 'Microphone' | 'AudioFile' | 'SystemAudio';
+  channelType: 'Microphone' | 'AudioFile' | 'SystemAudio' | 'Application' | 'Generator';
   volume: number;
   muted: boolean;
   solo: boolean;
+  generator: GeneratorSettings | null;  // signal of a generator channel
+}
+
+/**
+ * Test signal of a generator channel, for calibrating downstream apps
+ */
+export interface GeneratorSettings {
+  signal: 'reference1k' | 'sine_sweep' | 'pink_noise';
+  levelDbfs: number;  // peak of the sines, RMS of the noise, -60 - 0
+}
+
+/**
+ * Application playing audio, capturable as its own channel
+ */
+export interface CapturableApp {
+  processId: number;
+  name: string;  // executable name, e.g. "Spotify"
+}
+
+/**
+ * Channels muted together; muting a group also mutes its linked groups
+ */
+export interface MuteGroup {
+  id: string;
+  name: string;
+  channelIds: string[];
+  linkedGroups: string[];
 }
 
 export interface MixerConfig {
   masterVolume: number;
   channels: MixerChannel[];
+  muteGroups: MuteGroup[];
   sampleRate: number;
   bufferSize: number;
 }
 
+/**
+ * A problem with the selected devices, found before Start; `code` says
+ * which fields are present
+ */
+export type DeviceRole = 'input' | 'output' | 'monitor';
+
+export interface ConfigIssue {
+  severity: 'error' | 'warning';
+  code:
+    | 'NO_DEVICE_SELECTED'
+    | 'DEVICE_NOT_FOUND'
+    | 'UNSUPPORTED_SAMPLE_RATE'
+    | 'UNSUPPORTED_CHANNELS'
+    | 'VIRTUAL_DRIVER_MISSING'
+    | 'OUTPUT_NOT_VIRTUAL'
+    | 'CIRCULAR_ROUTING'
+    | 'FEEDBACK_LOOP'
+    | 'MONITOR_ON_VIRTUAL_CABLE';
+  role?: DeviceRole;
+  device?: string;
+  input?: string;   // FEEDBACK_LOOP
+  output?: string;  // FEEDBACK_LOOP
+  sampleRate?: number;
+  channels?: number;
+  supported?: number[];
+}
+
+/**
+ * What start_mixing did: nothing while already running with the same setup,
+ * a monitor-only hot switch, or a stream rebuild for device/format changes
+ */
+export type StartAction = 'started' | 'unchanged' | 'hot_switched' | 'restarted';
+export type SetupChange = 'input_device' | 'output_device' | 'sample_rate' | 'channels' | 'input_channels' | 'monitor' | 'output_layout' | 'buffer_size';
+
+export interface StartOutcome {
+  action: StartAction;
+  changes: SetupChange[];
+}
+
+/**
+ * A sound playing on the soundboard
+ */
+export interface PlayingSound {
+  id: string;
+  positionSecs: number;
+  durationSecs: number;
+  volume: number;
+  looping: boolean;
+  paused: boolean;
+}
+
+/**
+ * Everything the UI shows about the engine, from one call
+ */
+export interface EngineSnapshot {
+  running: boolean;
+  inputDevice: string | null;    // null while stopped
+  outputDevice: string | null;
+  monitorDevice: string | null;
+  sampleRate: number;
+  deviceSampleRate: number | null;
+  mixer: MixerConfig;
+  micVolume: number;
+  micMuted: boolean;
+  feedbackMuted: boolean;  // held closed by the feedback guard, apart from micMuted
+  playingSounds: PlayingSound[];
+  previewPadId: string | null;
+  micChain: MicEffectNode[];
+  voiceChanger: VoiceChangerSettings;
+  monitor: MonitorSettings;
+  broadcastDelay: BroadcastDelaySettings;
+  headphoneLimiter: HeadphoneLimiterSettings;
+  recordingPath: string | null;
+}
+
 export interface AudioSettings {
   inputDeviceId: string | null;
   outputDeviceId: string | null;
@@ -33,6 +136,159 @@ export interface AudioSettings {
   masterVolume: number;
   sampleRate: number;
   bufferSize: number;
+  ducking: DuckingSettings;
+}
+
+/**
+ * Mic lowered while soundboard clips play
+ */
+export interface DuckingSettings {
+  enabled: boolean;
+  depthDb: number;    // -40 to 0
+  attackMs: number;   // 1 to 1000
+  releaseMs: number;  // 10 to 5000
+}
+
+/**
+ * Sample format the mix is delivered in; 'auto' follows the device
+ */
+export type OutputBitDepth = 'auto' | 'int16' | 'int24' | 'float32';
+
+export interface OutputFormatSettings {
+  bitDepth: OutputBitDepth;
+  dither: boolean;  // TPDF dither when rounding to an integer depth
+}
+
+/**
+ * Channel layout the output device is opened with; 'auto' follows the device
+ */
+export type OutputLayout = 'auto' | 'stereo' | 'surround51' | 'surround71';
+
+export interface OutputLayoutSettings {
+  layout: OutputLayout;
+  centerFromStereo: boolean;  // Also feed the center with the front pair
+}
+
+/**
+ * Headphone monitor of the processed mix on the preview device
+ */
+export interface MonitorSettings {
+  enabled: boolean;
+  volume: number;   // 0 - 2
+  delayMs: number;  // extra delay to line up with remote listeners
+  codecBitrateKbps: number | null;  // Opus round trip, e.g. 64 for Discord, 12 for a phone; null for none
+}
+
+/**
+ * Safety limiter on the monitor and preview outputs; the virtual mic is not affected
+ */
+export interface HeadphoneLimiterSettings {
+  enabled: boolean;
+  ceilingDb: number;  // -30 - 0 dBFS
+}
+
+/**
+ * Delay of the virtual mic output; the monitor stays live
+ */
+export interface BroadcastDelaySettings {
+  enabled: boolean;
+  delayMs: number;  // 0 - 30000
+}
+
+/**
+ * Auto-mute of the mic when a feedback loop is detected while mixing; off by default
+ */
+export interface FeedbackProtectionSettings {
+  enabled: boolean;
+}
+
+/**
+ * Buffer size grown automatically when the audio breaks up, remembered per
+ * device pair ("input -> output")
+ */
+export interface BufferAutoTuneSettings {
+  enabled: boolean;
+  tuned: Record<string, number>;  // frames, 128 - 4096
+}
+
+/**
+ * The buffer was raised after repeated underruns
+ */
+export interface BufferAutoTuned {
+  previousFrames: number;
+  frames: number;
+  latencyMs: number;
+  message: string;
+}
+
+/**
+ * Speaking detection on the processed mic
+ */
+export interface VoiceActivitySettings {
+  enabled: boolean;
+  thresholdDb: number;  // dBFS, at most 0
+  hangoverMs: number;   // 0 - 5000
+}
+
+/**
+ * Soundboard playback behaviour
+ */
+export interface PlaybackSettings {
+  stopFadeMs: number;  // fade-out of every stop without its own, 0 - 5000; 0 cuts
+  autoLevel: boolean;  // bring every pad to the target loudness at play time
+  autoLevelTargetLufs: number;  // -36 to -6
+  outputRampMs: number;  // fade of the whole output on start and stop, 0 - 1000
+  soundCacheMb: number;  // memory kept for decoded pads, 16 - 4096
+}
+
+export interface MonitorInfo {
+  settings: MonitorSettings;
+  effectLatencyMs: number;  // already part of the monitored mix
+  totalDelayMs: number;
+}
+
+/**
+ * Output device running at a different rate than the engine
+ */
+export interface SampleRateMismatch {
+  device: string;
+  deviceRate: number;
+  engineRate: number;
+}
+
+/**
+ * A device held in exclusive mode by another application
+ */
+export interface DeviceInUse {
+  code: 'DEVICE_IN_USE';
+  device: string;
+  isInput: boolean;
+  message: string;  // names the likely cause, English fallback
+}
+
+/**
+ * Audio callbacks that stopped while mixing, e.g. a hung driver
+ */
+export interface EngineStall {
+  stream: 'input' | 'output';
+  silentMs: number;
+  rebuilding: boolean;  // false once the rebuild attempts are used up
+}
+
+/**
+ * Stalls found by the engine watchdog
+ */
+export interface WatchdogDiagnostics {
+  stalls: {
+    timestamp: number;  // ms since the Unix epoch
+    stream: 'input' | 'output';
+    silentMs: number;
+    inputDevice: string;
+    outputDevice: string;
+    sampleRate: number;
+    rebuilt: boolean;
+  }[];
+  rebuilds: number;  // since the last start
 }
 
 export interface AppSettings {
@@ -41,10 +297,56 @@ export interface AppSettings {
   autoStartMixing: boolean;
 }
 
-export interface ApiResponse<T> {
-  success: boolean;
-  data: T | null;
-  error: string | null;
+/**
+ * Safe mode: audio and hotkeys are off for this run so a setting that
+ * crashes on boot can be fixed
+ */
+export interface SafeModeStatus {
+  active: boolean;
+  reason: { kind: 'requested' } | { kind: 'repeated_crashes'; starts: number } | null;
+  flag: string;  // command-line flag that starts in safe mode
+}
+
+/**
+ * Build and runtime environment, for the About dialog and bug reports
+ */
+export interface AppInfo {
+  version: string;
+  build: {
+    profile: 'debug' | 'release';
+    targetOs: string;
+    targetArch: string;
+    features: string[];  // cargo features compiled in
+  };
+  audioHost: string;         // cpal host, e.g. WASAPI
+  osVersion: string | null;
+  devices: AppDeviceInfo[];  // devices of the running mix, or the selected ones when stopped
+  deviceSampleRate: number | null;
+  bufferFrames: number | null;  // null for the driver's default
+  virtualDriverInstalled: boolean | null;  // null off Windows or before devices were listed
+  updateChannel: 'Stable' | 'Beta';
+  safeMode: boolean;
+}
+
+/**
+ * A device the engine has open, or will open
+ */
+export interface AppDeviceInfo {
+  role: 'input' | 'output' | 'monitor';
+  name: string;
+  isVirtual: boolean;  // counted as a virtual cable
+  driver: string | null;  // audio adapter of the device; null when unknown or off Windows
+}
+
+/**
+ * Error payload returned by failing backend commands
+ */
+export interface CommandError {
+  code: string;     // Stable code, e.g. DEVICE_NOT_FOUND
+  params: Record<string, string>;  // values for the localized text, e.g. { device }
+  message: string;  // English fallback message
+  conflicts?: unknown[];  // conflicting pads, with HOTKEY_CONFLICT
+  path?: string;  // file being recorded, with RECORDING_ACTIVE
 }
 
 /**
@@ -57,8 +359,31 @@ export interface SoundFile {
   duration: number;  // in seconds
   sampleRate: number;
   channels: number;
+  trimStart?: number;  // in seconds
+  trimEnd?: number;    // in seconds
+  gainDb?: number;
+  volume?: number;     // 0 - 2, changeable while playing
+  looping?: boolean;   // ambience or music bed, plays until stopped
+  width?: number;      // stereo width, 1 = unchanged
+  speed?: PlaybackSpeed;
+  loudnessLufs?: number;  // measured on the first auto-levelled play
+  autoLevel?: boolean;    // false opts the sound out of auto-level
+  license?: string;       // e.g. CC-BY-4.0, listed by export_attributions
+  sourceUrl?: string;
+  attribution?: string;   // credit line, usually the author
+  archived?: boolean;     // file moved to the archive folder by archive_sounds
 }
 
+/**
+ * Playback speed of a sound; time_stretch keeps the pitch, resample shifts it
+ */
+export interface PlaybackSpeed {
+  rate: number;  // 0.5 - 2
+  mode: SpeedMode;
+}
+
+export type SpeedMode = 'resample' | 'time_stretch';
+
 /**
  * Sound pad configuration (position + sound)
  */
@@ -67,5 +392,629 @@ export interface SoundPad {
   sound: SoundFile | null;
   color: string;
   hotkey?: string;
+  hotkeyBank?: number;  // 0-3, selected by holding Shift / Alt / Alt+Shift
+  hotkeyProfile?: string;  // hotkey only live in this profile; global when unset
+  variants?: PadVariant[];
+  variantMode?: VariantMode;
+  stems?: PadStem[];  // files always played together, e.g. music, drums, vocals
+  inserts?: SoundInsert[];  // effects on the pad's sound, e.g. vocal reduction
+  artwork?: string;  // image file shown by the now-playing overlay
+  actions?: PadAction[];  // run on each trigger, with or without a sound
+  timer?: TimerConfig;  // makes the pad a timer pad
+  armed?: boolean;  // false while the active scene disarms the pad
   isPlaying: boolean;
 }
+
+/**
+ * Pad state in a scene
+ */
+export interface ScenePad {
+  armed: boolean;
+  volume: number;  // 0 - 2, gain on top of the sounds' own volume
+}
+
+/**
+ * Snapshot of pad states, channel levels and the voice changer
+ */
+export interface Scene {
+  id: string;
+  name: string;
+  pads: Record<string, ScenePad>;
+  channels: Record<string, { volume: number; muted: boolean }>;
+  voiceChanger: VoiceChangerSettings | null;
+}
+
+/**
+ * Non-audio action of a pad; app commands are those of the hot folder
+ */
+export type PadAction =
+  | { type: 'app'; command: HotFolderCommand }
+  | { type: 'osc'; host: string; port: number; address: string; args?: (number | string)[] }
+  | { type: 'http'; method?: string; url: string; body?: string }  // method defaults to POST
+  // url defaults to ws://localhost:4455; a password given is moved to the OS credential store on save
+  | { type: 'obsScene'; scene: string; url?: string; password?: string };
+
+/**
+ * Countdown of a timer pad; cue sounds are soundboard sound ids
+ */
+export interface TimerConfig {
+  durationSecs: number;
+  repeat?: boolean;  // interval timer: starts over until cancelled
+  tickEverySecs?: number;
+  tickSound?: string;
+  halfwaySound?: string;
+  endSound?: string;
+  route?: 'monitor' | 'mic';  // monitor (preview device) by default
+}
+
+export type TimerCue = 'tick' | 'halfway' | 'end';
+
+export interface TimerProgress {
+  padId: string;
+  elapsedSecs: number;
+  remainingSecs: number;
+  durationSecs: number;
+  cycle: number;  // cycles an interval timer has completed
+  cue: TimerCue | null;
+}
+
+export type HotFolderCommand =
+  | { action: 'play'; sound_id: string }
+  | { action: 'stop'; sound_id: string; fade_ms?: number }
+  | { action: 'stop_all'; fade_ms?: number }
+  | { action: 'mute_mic' }
+  | { action: 'unmute_mic' };
+
+/**
+ * Effect inserted on one sound
+ */
+export type SoundInsert =
+  | { kind: 'vocal_reduction'; strength: number };  // 0 - 1, karaoke
+
+/**
+ * One file of a stem pad, with its own live volume
+ */
+export interface PadStem {
+  sound: SoundFile;
+  volume: number;  // 0 - 2, 0 mutes the stem
+}
+
+/**
+ * Alternative file played by a pad, picked by weight on each trigger
+ */
+export interface PadVariant {
+  sound: SoundFile;
+  weight: number;
+}
+
+export type VariantMode = 'round_robin' | 'random';
+
+/**
+ * What triggered a sound, recorded in the play log
+ */
+export type PlaySource = 'ui' | 'hotkey' | 'remote' | 'twitch';
+
+/**
+ * One triggered sound in a session's play log
+ */
+export interface PlayLogEntry {
+  timestamp: number;  // ms since epoch
+  offsetMs: number;   // ms since the session started
+  soundId: string;
+  name: string;
+  path: string;
+  duration: number;   // in seconds
+  source: PlaySource;
+}
+
+/**
+ * Post-show report of a mixing session
+ */
+export interface SessionSummary {
+  startedAt: number;  // ms since epoch
+  endedAt: number;
+  durationSecs: number;
+  peakDb: number;     // loudest output peak, dBFS
+  averageDb: number;  // output RMS over the session, dBFS
+  triggers: number;
+  underruns: number;
+  topSounds: { soundId: string; name: string; plays: number }[];
+}
+
+/**
+ * Unplayed days before a sound is proposed for cleanup
+ */
+export interface CleanupPolicy {
+  archiveAfterDays: number;
+  deleteAfterDays: number;
+}
+
+export type CleanupAction = 'archive' | 'delete';
+
+/**
+ * A sound proposed for archiving or deletion
+ */
+export interface CleanupSuggestion {
+  soundId: string;
+  name: string;
+  path: string;
+  sizeBytes: number;
+  playCount: number;
+  lastPlayed: number | null;  // ms since epoch, null if never played
+  idleDays: number;
+  action: CleanupAction;
+}
+
+/**
+ * Proposed cleanups, biggest files first
+ */
+export interface CleanupReport {
+  suggestions: CleanupSuggestion[];
+  reclaimableBytes: number;  // freed once every suggested file is deleted
+}
+
+/**
+ * A sound whose file couldn't be archived
+ */
+export interface ArchiveFailure {
+  soundId: string;
+  error: string;
+}
+
+/**
+ * What archiving sounds did, per sound
+ */
+export interface ArchiveResult {
+  archived: number;
+  shared: string[];  // left in place, other sounds on the board play the file
+  failed: ArchiveFailure[];
+}
+
+/**
+ * Disk use of one library category: a format (wav, mp3, ...) or archived
+ */
+export interface CategoryUsage {
+  category: string;
+  files: number;
+  sizeBytes: number;
+}
+
+/**
+ * Size of the sound library on disk and in the decode cache
+ */
+export interface LibraryStats {
+  totalSounds: number;
+  totalDurationSecs: number;
+  totalSizeBytes: number;
+  categories: CategoryUsage[];  // biggest first
+  missingFiles: number;
+  cachedSounds: number;
+  cacheBytes: number;
+}
+
+/**
+ * Why a recording marker was placed
+ */
+export type MarkerKind = 'manual' | 'pad';
+
+/**
+ * A named position in a session recording
+ */
+export interface RecordingMarker {
+  position: number;  // in sample frames
+  seconds: number;
+  label: string;
+  kind: MarkerKind;
+}
+
+/**
+ * A finished session recording and its exported marker files
+ */
+export interface RecordingSummary {
+  path: string;
+  duration: number;  // in seconds
+  markers: RecordingMarker[];
+  cuePath: string | null;
+  markersPath: string | null;
+  stems: string[];  // per-mic files in podcast mode
+}
+
+/**
+ * Read-only board shared with co-hosts on the LAN
+ */
+export interface ShareInfo {
+  url: string;
+  port: number;
+  padCount: number;
+}
+
+/**
+ * A co-host asking the host to play a shared pad
+ */
+export interface ShareRequest {
+  padId: string;
+  name: string;
+  from: string;  // co-host IP address
+}
+
+/**
+ * Sound shown on the now-playing overlay
+ */
+export interface NowPlaying {
+  id: string;
+  name: string;
+  artwork: string | null;  // path on the overlay server, from the pad's artwork
+  color: string | null;
+  durationSecs: number;
+  remainingSecs: number;
+}
+
+/**
+ * Folder watched for trigger files: `<sound-id>.play` plays the sound, a
+ * `.json` file holds a command such as `{"action": "stop_all"}`
+ */
+export interface HotFolderSettings {
+  enabled: boolean;
+  path: string | null;
+}
+
+/** Sound played, and scene recalled, when mixing starts */
+export interface StartJingleSettings {
+  enabled: boolean;
+  sound_id: string | null;
+  scene_id: string | null;
+}
+
+/** Something that triggers the board from outside the app */
+export type Integration = 'http' | 'websocket' | 'twitch' | 'osc' | 'midi' | 'hot_folder';
+
+/** Kind of trigger an integration may send */
+export type IntegrationAction =
+  | 'play_sound'
+  | 'stop_sound'
+  | 'stop_all'
+  | 'mute_mic'
+  | 'pad_actions'
+  | 'recall_scene';
+
+/** What an integration asks the board to do: a pad's actions, a scene or a command */
+export type IntegrationTrigger =
+  | { pad_id: string }
+  | { scene_id: string; crossfade_ms: number | null }
+  | HotFolderCommand;
+
+/**
+ * What one integration may do; `allowed_sounds` null allows any sound,
+ * `rate_limit` null sets no limit
+ */
+export interface IntegrationPolicy {
+  enabled: boolean;
+  allowed_sounds: string[] | null;
+  allowed_actions: IntegrationAction[];
+  rate_limit: { max_triggers: number; per_secs: number } | null;
+}
+
+/**
+ * How virtual devices are told apart from hardware: a device is virtual
+ * when its name contains one of the lowercase patterns, or when the user
+ * marked it
+ */
+export interface VirtualDeviceSettings {
+  patterns: string[];
+  marked_devices: string[];
+  /** Type forced on a device by id, winning over patterns and marks */
+  type_overrides: Record<string, DeviceType>;
+}
+
+export type DeviceType = 'InputPhysical' | 'OutputPhysical' | 'InputVirtual' | 'OutputVirtual';
+
+/**
+ * Now-playing overlay served on localhost for OBS browser sources
+ */
+export interface OverlayInfo {
+  url: string;      // HTML overlay page
+  jsonUrl: string;  // same state as JSON
+  port: number;
+}
+
+/**
+ * Remote the soundboard and settings are synced to
+ */
+export type SyncBackendConfig =
+  | { kind: 'web_dav'; url: string; username: string; password: string }
+  | {
+      kind: 's3';
+      endpoint: string;
+      bucket: string;
+      region: string;
+      accessKeyId: string;
+      secretAccessKey: string;
+      prefix: string;
+    };
+
+/**
+ * Cloud sync settings, kept on this machine only
+ */
+export interface SyncConfig {
+  enabled: boolean;
+  backend: SyncBackendConfig | null;
+  hasPassword: boolean; // a secret is stored for the backend; it is never read back
+}
+
+export type SyncAction = 'up_to_date' | 'upload' | 'download' | 'conflict';
+
+/**
+ * Outcome of the last cloud sync
+ */
+export interface SyncStatus {
+  enabled: boolean;
+  backend: 'web_dav' | 's3' | null;
+  syncing: boolean;
+  lastAction: SyncAction | null;
+  conflict: boolean;             // both sides changed, needs a resolution
+  lastAttemptAt: number | null;  // ms since epoch
+  lastSyncedAt: number | null;
+  remoteUpdatedAt: number | null;
+  remoteDevice: string | null;
+  error: string | null;
+}
+
+export type SyncResolution = 'keep_local' | 'keep_remote';
+
+/**
+ * Sound installed from a pack, with the pad settings it ships with
+ */
+export interface PackSound extends SoundFile {
+  hotkey?: string;
+  color?: string;
+}
+
+/**
+ * Sound pack installed by import_sound_pack
+ */
+export interface SoundPack {
+  name: string;
+  author?: string;
+  sounds: PackSound[];
+  skipped: string[];  // entries that were missing or failed to decode
+}
+
+/**
+ * Result of validating a pad hotkey
+ */
+export interface HotkeyValidation {
+  normalized: string;
+  conflicts: HotkeyConflict[];
+}
+
+/**
+ * A pad hotkey conflicting with another one
+ */
+export interface HotkeyConflict {
+  padId: string;
+  hotkey: string;
+  bank: number;
+  profile?: string;  // unset for global hotkeys
+  kind: 'duplicate' | 'prefix';
+}
+
+/**
+ * A pad hotkey live in the active profile
+ */
+export interface ActiveHotkey {
+  padId: string;
+  hotkey: string;
+  bank: number;
+  keys: string;  // keys actually pressed, bank modifiers included
+}
+
+/**
+ * The board whose pad hotkeys are live; boards are the hotkey profiles
+ */
+export interface Board {
+  board: string | null;
+  generation: number;  // passed back with hotkey presses so stale ones are dropped
+  boards: string[];
+  hotkeys: ActiveHotkey[];
+}
+
+/**
+ * Hotkeys stepping through the boards, null when unbound
+ */
+export interface BoardHotkeySettings {
+  next: string | null;
+  previous: string | null;
+}
+
+/**
+ * Pad hotkeys that fire while other apps have focus
+ */
+export interface GlobalHotkeys {
+  enabled: boolean;
+  pads: Record<string, string>;  // combo by pad id
+  boardSwitching: boolean;       // board hotkeys are global too
+  unavailable: string[];         // combos another app already holds
+}
+
+/**
+ * Hold-a-key gate on the virtual mic
+ */
+export interface PushToTalkSettings {
+  mode: 'off' | 'push_to_talk' | 'push_to_mute';
+  key: string;        // combo, e.g. "Mouse4" or "Ctrl+Space"
+  releaseMs: number;  // mic stays open this long after release, up to 2000
+}
+
+/**
+ * Talk state sent on each push-to-talk transition
+ */
+export interface TalkState {
+  talking: boolean;  // key held, or within its release tail
+  muted: boolean;
+}
+
+/**
+ * Automatic gain control for the mic (stored per input device)
+ */
+export interface MicAgcSettings {
+  enabled: boolean;
+  targetDb: number;   // dBFS RMS
+  maxGainDb: number;
+  holdMs: number;
+}
+
+/**
+ * Noise gate on the mic (stored per input device)
+ */
+export interface NoiseGateSettings {
+  enabled: boolean;
+  thresholdDb: number;  // dBFS RMS, before mic volume
+}
+
+/**
+ * Experimental keyboard suppression: the mic is gated for a moment after
+ * each key press, timed by a system-wide keyboard hook
+ */
+export interface KeyboardSuppressionSettings {
+  enabled: boolean;
+  depthDb: number;   // -40 to 0
+  windowMs: number;  // 10 to 100
+}
+
+/**
+ * Step of the gain wizard
+ */
+export type GainWizardStep =
+  | 'idle'
+  | 'measuring_noise'
+  | 'measuring_speech'
+  | 'analyzing'
+  | 'done'
+  | 'failed'
+  | 'cancelled';
+
+/**
+ * Levels measured by the gain wizard and the settings it recommends
+ */
+export interface GainRecommendation {
+  noiseFloorDb: number;     // dBFS RMS
+  speechRmsDb: number;      // dBFS RMS
+  peakDb: number;           // dBFS
+  clipped: boolean;         // turn the interface gain down
+  micVolume: number;        // 0 - 2
+  gateThresholdDb: number;  // dBFS RMS
+}
+
+/**
+ * Progress of the gain wizard
+ */
+export interface GainWizardState {
+  step: GainWizardStep;
+  elapsed: number;   // seconds into the current step
+  duration: number;  // length of the current step in seconds
+  levelDb: number;   // peak of the last captured buffer
+  result?: GainRecommendation;
+  error?: string;
+}
+
+/**
+ * One of the two mics in podcast mode
+ */
+export interface PodcastMic {
+  channel: number;  // zero-based device channel
+  gain: number;     // linear, 0 - 2
+  pan: number;      // -1 (left) to 1 (right)
+  muted: boolean;
+}
+
+/**
+ * Which channels of a multi-channel interface feed the mic (zero-based)
+ */
+export type InputChannelMap =
+  | { mode: 'as_is' }
+  | { mode: 'mono'; channel: number }
+  | { mode: 'stereo'; left: number; right: number }
+  | { mode: 'dual_mono'; host: PodcastMic; guest: PodcastMic };
+
+export interface InputChannels {
+  map: InputChannelMap;
+  channelCount: number | null;  // most channels the device offers
+}
+
+/**
+ * Rumble filter at the start of the mic chain
+ */
+export interface MicHighpassSettings {
+  enabled: boolean;
+  cutoffHz: number;   // 40-200 Hz
+  slope: 6 | 12 | 24; // dB per octave
+}
+
+/**
+ * Mic settings remembered for one input device
+ */
+export interface MicProfile {
+  volume: number;     // 0 - 2
+  highpass: MicHighpassSettings;
+  agc: MicAgcSettings;
+  noiseGate: NoiseGateSettings;
+}
+
+/**
+ * Mic profile of the current input device
+ */
+export interface MicProfileStatus {
+  device: string;
+  profile: MicProfile;
+  isDefault: boolean; // the device uses the default profile
+}
+
+/**
+ * FFT quality of spectral mic effects and the latency it adds
+ */
+export type SpectralQuality = 'low_latency' | 'balanced' | 'high_quality';
+
+export interface SpectralQualityInfo {
+  quality: SpectralQuality;
+  fftSize: number;
+  overlap: number;
+  latencyMs: number;
+}
+
+/**
+ * Voice changer parameters (each effect is off at zero)
+ */
+export interface VoiceChangerSettings {
+  pitchSemitones: number;  // -12 to +12
+  ringModHz: number;
+  lowCutHz: number;
+  highCutHz: number;
+  drive: number;           // 0-1
+  echoMs: number;
+  echoFeedback: number;    // 0-0.9
+}
+
+/**
+ * Character voice shipped with the app
+ */
+export interface VoicePreset {
+  id: string;
+  name: string;
+  settings: VoiceChangerSettings;
+}
+
+/**
+ * One slot in the mic effect chain, in processing order
+ */
+export interface MicEffectNode {
+  kind:
+    | 'highpass'
+    | 'echo_cancellation'
+    | 'noise_reduction'
+    | 'keyboard_suppression'
+    | 'noise_gate'
+    | 'agc'
+    | 'voice_changer';
+  enabled: boolean;
+  mix: number;  // 0 = dry, 1 = fully processed
+}
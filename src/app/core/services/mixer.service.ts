@@ -1,6 +1,6 @@
 import { Injectable, signal, computed } from '@angular/core';
-import { TauriService } from './tauri.service';
-import { MixerConfig, MixerChannel, AudioDevice } from '../models';
+import { CommandFailedError, TauriService } from './tauri.service';
+import { MixerConfig, MixerChannel, AudioDevice, MicProfileStatus } from '../models';
 
 /**
  * Service for managing mixer state and operations
@@ -15,6 +15,7 @@ export class MixerService {
   private _isRunning = signal(false);
   private _devices = signal<AudioDevice[]>([]);
   private _virtualDriverInstalled = signal<boolean | null>(null);
+  private _micProfile = signal<MicProfileStatus | null>(null);
   private _loading = signal(false);
   private _error = signal<string | null>(null);
 
@@ -23,6 +24,7 @@ export class MixerService {
   readonly isRunning = this._isRunning.asReadonly();
   readonly devices = this._devices.asReadonly();
   readonly virtualDriverInstalled = this._virtualDriverInstalled.asReadonly();
+  readonly micProfile = this._micProfile.asReadonly();
   readonly loading = this._loading.asReadonly();
   readonly error = this._error.asReadonly();
 
@@ -44,17 +46,29 @@ export class MixerService {
 
     try {
       // Load all initial data in parallel
-      const [config, devices, virtualDriver, isMixing] = await Promise.all([
+      const [config, devices, virtualDriver, isMixing, micProfile] = await Promise.all([
         this.tauri.getMixerConfig(),
         this.tauri.getAudioDevices(),
         this.tauri.checkVirtualDriver(),
-        this.tauri.isMixing()
+        this.tauri.isMixing(),
+        this.tauri.getMicProfile()
       ]);
 
       this._config.set(config);
       this._devices.set(devices);
       this._virtualDriverInstalled.set(virtualDriver);
       this._isRunning.set(isMixing);
+      this._micProfile.set(micProfile);
+
+      // The profile follows the input device, which any window can switch
+      await this.tauri.listenMicProfileChanged(() => this.refreshMicProfile());
+
+      // Quitting mid-recording: confirm, then finish the file and quit
+      await this.tauri.listenRecordingActive(async path => {
+        if (confirm(`A recording is in progress (${path}). Finish the recording and quit?`)) {
+          await this.tauri.quitApp(true);
+        }
+      });
     } catch (err) {
       this._error.set(err instanceof Error ? err.message : 'Initialization failed');
       console.error('Failed to initialize mixer:', err);
@@ -75,6 +89,17 @@ export class MixerService {
     }
   }
 
+  /**
+   * Reload the mic profile of the current input device
+   */
+  async refreshMicProfile(): Promise<void> {
+    try {
+      this._micProfile.set(await this.tauri.getMicProfile());
+    } catch (err) {
+      this._error.set(err instanceof Error ? err.message : 'Failed to load the mic profile');
+    }
+  }
+
   /**
    * Set the master volume
    */
@@ -173,7 +198,14 @@ export class MixerService {
    */
   async stop(): Promise<void> {
     try {
-      await this.tauri.stopMixing();
+      try {
+        await this.tauri.stopMixing();
+      } catch (err) {
+        // A running recording would be cut short: ask before finishing it
+        if (!(err instanceof CommandFailedError && err.code === 'RECORDING_ACTIVE')) throw err;
+        if (!confirm(`A recording is in progress (${err.path}). Stop mixing and finish the recording?`)) return;
+        await this.tauri.stopMixing(true);
+      }
       this._isRunning.set(false);
     } catch (err) {
       this._error.set(err instanceof Error ? err.message : 'Failed to stop mixing');
@@ -1,7 +1,7 @@
 import { Injectable, signal, computed } from '@angular/core';
-import { TauriService } from './tauri.service';
-import { SoundFile, SoundPad } from '../models';
-import { open } from '@tauri-apps/plugin-dialog';
+import { CommandFailedError, TauriService } from './tauri.service';
+import { ArchiveResult, Board, BoardHotkeySettings, PadAction, PadStem, PlaySource, SoundFile, SoundInsert, SoundPack, SoundPad, TimerConfig, VariantMode } from '../models';
+import { open, save } from '@tauri-apps/plugin-dialog';
 
 const PAD_COLORS = [
   '#e74c3c', '#e67e22', '#f1c40f', '#2ecc71',
@@ -15,6 +15,15 @@ interface SavedPad {
   sound: SoundFile | null;
   color: string;
   hotkey?: string;
+  hotkeyBank?: number;
+  hotkeyProfile?: string;
+  variants?: { sound: SoundFile; weight: number }[];
+  variantMode?: VariantMode;
+  stems?: PadStem[];
+  inserts?: SoundInsert[];
+  artwork?: string;
+  actions?: PadAction[];
+  timer?: TimerConfig;
 }
 
 @Injectable({
@@ -33,8 +42,21 @@ export class SoundboardService {
   readonly previewingPadId = this._previewingPadId.asReadonly();
   readonly previewDeviceId = this._previewDeviceId.asReadonly();
 
+  // Board whose pad hotkeys are live, and the hotkeys switching it
+  private _board = signal<Board | null>(null);
+  private _boardHotkeys = signal<BoardHotkeySettings | null>(null);
+  readonly board = this._board.asReadonly();
+  readonly boardHotkeys = this._boardHotkeys.asReadonly();
+
+  /** Sound id currently playing on each pad (differs from pad.sound with variants) */
+  private playingSoundIds = new Map<string, string>();
+
   private unlistenPreviewStarted?: () => void;
   private unlistenPreviewStopped?: () => void;
+  private unlistenSyncApplied?: () => void;
+  private unlistenSceneRecalled?: () => void;
+  private unlistenBoardChanged?: () => void;
+  private unlistenGlobalHotkey?: () => void;
 
   // Public readonly signals
   readonly pads = this._pads.asReadonly();
@@ -61,6 +83,19 @@ export class SoundboardService {
         this._previewingPadId.set(null);
       }
     });
+
+    // A cloud sync replaced the saved board
+    this.unlistenSyncApplied = await this.tauri.listenSyncApplied(() => this.loadState());
+
+    // Pads a scene lists are armed or disarmed; the others keep their state
+    this.unlistenSceneRecalled = await this.tauri.listenSceneRecalled((scene) => {
+      this._pads.update(pads => pads.map(p =>
+        scene.pads[p.id] ? { ...p, armed: scene.pads[p.id].armed } : p
+      ));
+    });
+
+    this.unlistenBoardChanged = await this.tauri.listenBoardChanged((board) => this._board.set(board));
+    this.unlistenGlobalHotkey = await this.tauri.listenGlobalHotkey((padId) => this.playSound(padId, 'hotkey'));
   }
 
   private createInitialPads(count: number): SoundPad[] {
@@ -77,16 +112,7 @@ export class SoundboardService {
    */
   private async loadState(): Promise<void> {
     try {
-      const saved = await this.tauri.loadSoundboardState();
-      if (saved && saved.length > 0) {
-        // Restore pads, ensuring isPlaying is false
-        const restoredPads: SoundPad[] = saved.map(p => ({
-          ...p,
-          isPlaying: false
-        }));
-        this._pads.set(restoredPads);
-        console.log(`Loaded ${saved.filter(p => p.sound).length} sounds from storage`);
-      }
+      await this.loadPads();
     } catch (err) {
       console.error('Failed to load soundboard state:', err);
     }
@@ -94,6 +120,57 @@ export class SoundboardService {
 
     // Also load preview device setting
     this.loadPreviewDevice();
+    this.loadBoard();
+  }
+
+  private async loadPads(): Promise<void> {
+    const saved = await this.tauri.loadSoundboardState();
+    if (saved && saved.length > 0) {
+      // Restore pads, ensuring isPlaying is false
+      const restoredPads: SoundPad[] = saved.map(p => ({
+        ...p,
+        isPlaying: false
+      }));
+      this._pads.set(restoredPads);
+      console.log(`Loaded ${saved.filter(p => p.sound).length} sounds from storage`);
+    }
+  }
+
+  /**
+   * Move the files of sounds to the archive folder, then reload the pads
+   * so they play the archived files
+   */
+  async archiveSounds(soundIds: string[]): Promise<ArchiveResult> {
+    const result = await this.tauri.archiveSounds(soundIds);
+    await this.loadPads();
+    return result;
+  }
+
+  private async loadBoard(): Promise<void> {
+    try {
+      this._board.set(await this.tauri.getBoard());
+      this._boardHotkeys.set(await this.tauri.getBoardHotkeys());
+    } catch (err) {
+      console.error('Failed to load the board:', err);
+    }
+  }
+
+  /**
+   * Switch to the next board (previous with -1)
+   */
+  async stepBoard(step: 1 | -1): Promise<void> {
+    try {
+      this._board.set(step > 0 ? await this.tauri.nextBoard() : await this.tauri.previousBoard());
+    } catch (err) {
+      this._error.set(err instanceof Error ? err.message : 'Failed to switch board');
+    }
+  }
+
+  /**
+   * Whether a pad's hotkey is live on the active board
+   */
+  isOnActiveBoard(pad: SoundPad): boolean {
+    return !pad.hotkeyProfile || pad.hotkeyProfile === this._board()?.board;
   }
 
   /**
@@ -107,7 +184,16 @@ export class SoundboardService {
         id: p.id,
         sound: p.sound,
         color: p.color,
-        hotkey: p.hotkey
+        hotkey: p.hotkey,
+        hotkeyBank: p.hotkeyBank,
+        hotkeyProfile: p.hotkeyProfile,
+        variants: p.variants,
+        variantMode: p.variantMode,
+        stems: p.stems,
+        inserts: p.inserts,
+        artwork: p.artwork,
+        actions: p.actions,
+        timer: p.timer
       }));
       await this.tauri.saveSoundboardState(padsToSave);
     } catch (err) {
@@ -186,12 +272,182 @@ export class SoundboardService {
     }
   }
 
+  /**
+   * Install a sound pack and put each of its sounds on a new pad
+   */
+  async importSoundPack(): Promise<SoundPack | null> {
+    try {
+      this._loading.set(true);
+      this._error.set(null);
+
+      const selected = await open({
+        multiple: false,
+        filters: [{
+          name: 'Sound Packs',
+          extensions: ['zip', 'spl', 'json']
+        }]
+      });
+      if (!selected) return null;
+
+      // A manifest.json stands for the folder it sits in
+      let path = selected as string;
+      if (path.toLowerCase().endsWith('.json')) {
+        path = path.replace(/[\\/][^\\/]*$/, '');
+      }
+
+      const pack = await this.tauri.importSoundPack(path);
+      const current = this._pads();
+      const newPads: SoundPad[] = pack.sounds.map(({ hotkey, color, ...sound }, i) => ({
+        id: `pad-${current.length + i}`,
+        sound,
+        color: color ?? PAD_COLORS[(current.length + i) % PAD_COLORS.length],
+        hotkey,
+        isPlaying: false
+      }));
+      this._pads.set([...current, ...newPads]);
+      await this.saveState();
+
+      if (pack.skipped.length > 0) {
+        this._error.set(`Skipped ${pack.skipped.length} sound(s): ${pack.skipped.join(', ')}`);
+      }
+      return pack;
+    } catch (err) {
+      this._error.set(err instanceof Error ? err.message : String(err));
+      return null;
+    } finally {
+      this._loading.set(false);
+    }
+  }
+
+  /**
+   * Add another file to a pad, played in rotation with its other variants
+   */
+  async addVariant(padId: string): Promise<void> {
+    const pad = this._pads().find(p => p.id === padId);
+    if (!pad?.sound) {
+      return this.importSound(padId);
+    }
+
+    try {
+      this._loading.set(true);
+      this._error.set(null);
+
+      const selected = await open({
+        multiple: false,
+        filters: [{
+          name: 'Audio Files',
+          extensions: ['mp3', 'ogg', 'wav', 'flac']
+        }]
+      });
+      if (!selected) return;
+
+      const soundFile = await this.tauri.loadSoundFile(selected as string);
+
+      // The pad's own sound becomes the first variant
+      this._pads.update(pads => pads.map(p =>
+        p.id === padId
+          ? { ...p, variants: [...(p.variants ?? [{ sound: p.sound!, weight: 1 }]), { sound: soundFile, weight: 1 }] }
+          : p
+      ));
+      await this.saveState();
+    } catch (err) {
+      this._error.set(err instanceof Error ? err.message : String(err));
+    } finally {
+      this._loading.set(false);
+    }
+  }
+
+  /**
+   * Remove a variant from a pad
+   */
+  async removeVariant(padId: string, index: number): Promise<void> {
+    this._pads.update(pads => pads.map(p => {
+      if (p.id !== padId || !p.variants) return p;
+      const variants = p.variants.filter((_, i) => i !== index);
+      return {
+        ...p,
+        sound: variants[0]?.sound ?? null,
+        variants: variants.length > 1 ? variants : undefined
+      };
+    }));
+    await this.saveState();
+  }
+
+  /**
+   * Set how often a variant plays relative to the others
+   */
+  async setVariantWeight(padId: string, index: number, weight: number): Promise<void> {
+    this._pads.update(pads => pads.map(p =>
+      p.id === padId && p.variants
+        ? { ...p, variants: p.variants.map((v, i) => i === index ? { ...v, weight: Math.max(0, weight) } : v) }
+        : p
+    ));
+    await this.saveState();
+  }
+
+  /**
+   * Cycle through variants in order or pick them at random
+   */
+  async setVariantMode(padId: string, variantMode: VariantMode): Promise<void> {
+    this._pads.update(pads => pads.map(p =>
+      p.id === padId ? { ...p, variantMode } : p
+    ));
+    await this.saveState();
+  }
+
+  /**
+   * Record a voice memo and put it on the first empty pad (or a new one)
+   */
+  async quickMemo(): Promise<void> {
+    try {
+      this._loading.set(true);
+      this._error.set(null);
+
+      const memo = await this.tauri.quickMemo();
+
+      if (!this._pads().some(p => p.sound === null)) {
+        this.addPads(1);
+      }
+      const target = this._pads().find(p => p.sound === null)!;
+      this._pads.update(pads => pads.map(pad =>
+        pad.id === target.id
+          ? { ...pad, sound: memo }
+          : pad
+      ));
+
+      await this.saveState();
+    } catch (err) {
+      console.error('[Soundboard] Quick memo error:', err);
+      this._error.set(err instanceof Error ? err.message : String(err));
+    } finally {
+      this._loading.set(false);
+    }
+  }
+
   /**
    * Play a sound from a pad
+   *
+   * Hotkey presses pass the board generation they were matched under, so
+   * a press racing a board switch is dropped instead of firing the old pad.
    */
-  async playSound(padId: string): Promise<void> {
+  async playSound(padId: string, source: PlaySource = 'ui', boardGeneration?: number): Promise<void> {
     const pad = this._pads().find(p => p.id === padId);
-    if (!pad?.sound) return;
+    if (!pad || pad.armed === false) return;
+    if (boardGeneration !== undefined && boardGeneration !== this._board()?.generation) return;
+
+    // Actions run on every trigger, also the one stopping the pad's sound
+    if (pad.actions?.length) {
+      this.tauri.triggerPadActions(padId, source).catch(err => {
+        this._error.set(err instanceof Error ? err.message : 'Pad action failed');
+      });
+    }
+    if (pad.timer && !pad.isPlaying) {
+      // Pressing a timer pad again restarts its countdown
+      this.tauri.startTimer(padId).catch(err => {
+        this._error.set(err instanceof Error ? err.message : 'Failed to start timer');
+      });
+    }
+    if (!pad.sound) return;
 
     try {
       // If already playing, stop it first
@@ -205,18 +461,32 @@ export class SoundboardService {
         p.id === padId ? { ...p, isPlaying: true } : p
       ));
 
-      // Play the sound
-      await this.tauri.playSound(pad.sound.id, pad.sound.path);
+      const sound = pad.variants?.length
+        ? (await this.tauri.pickPadVariant(padId)) ?? pad.sound
+        : pad.sound;
+      this.playingSoundIds.set(padId, sound.id);
 
-      // Auto-stop after duration (with small buffer)
-      setTimeout(() => {
-        this._pads.update(pads => pads.map(p =>
-          p.id === padId ? { ...p, isPlaying: false } : p
-        ));
-      }, (pad.sound.duration + 0.5) * 1000);
+      if (sound.width !== undefined) {
+        await this.tauri.setSoundWidth(sound.id, sound.width);
+      }
+
+      // Play the sound
+      await this.tauri.playSound(sound.id, sound.path, source, sound.looping ?? false, boardGeneration);
+
+      // Auto-stop after duration (with small buffer); loops play until stopped
+      if (!sound.looping) {
+        setTimeout(() => {
+          this._pads.update(pads => pads.map(p =>
+            p.id === padId ? { ...p, isPlaying: false } : p
+          ));
+        }, (sound.duration + 0.5) * 1000);
+      }
 
     } catch (err) {
-      this._error.set(err instanceof Error ? err.message : 'Failed to play sound');
+      // A press of a board switched away from in the meantime isn't a failure
+      if (!(err instanceof CommandFailedError && err.code === 'STALE_BOARD')) {
+        this._error.set(err instanceof Error ? err.message : 'Failed to play sound');
+      }
       this._pads.update(pads => pads.map(p =>
         p.id === padId ? { ...p, isPlaying: false } : p
       ));
@@ -231,7 +501,7 @@ export class SoundboardService {
     if (!pad?.sound) return;
 
     try {
-      await this.tauri.stopSound(pad.sound.id);
+      await this.tauri.stopSound(this.playingSoundIds.get(padId) ?? pad.sound.id);
       this._pads.update(pads => pads.map(p =>
         p.id === padId ? { ...p, isPlaying: false } : p
       ));
@@ -244,8 +514,12 @@ export class SoundboardService {
    * Stop all playing sounds
    */
   async stopAll(): Promise<void> {
-    const playingPads = this._pads().filter(p => p.isPlaying);
-    await Promise.all(playingPads.map(p => this.stopSound(p.id)));
+    try {
+      await this.tauri.stopAllSounds();
+      this._pads.update(pads => pads.map(p => p.isPlaying ? { ...p, isPlaying: false } : p));
+    } catch (err) {
+      this._error.set(err instanceof Error ? err.message : 'Failed to stop sounds');
+    }
   }
 
   /**
@@ -326,6 +600,36 @@ export class SoundboardService {
     this.saveState();
   }
 
+  /**
+   * Set the license, source and credit of a pad's sound
+   */
+  setSoundAttribution(
+    padId: string,
+    attribution: Pick<SoundFile, 'license' | 'sourceUrl' | 'attribution'>
+  ): void {
+    this._pads.update(pads => pads.map(pad =>
+      pad.id === padId && pad.sound ? { ...pad, sound: { ...pad.sound, ...attribution } } : pad
+    ));
+    this.saveState();
+  }
+
+  /**
+   * Save the credits of the board's sounds to a text file
+   */
+  async exportAttributions(): Promise<number | null> {
+    try {
+      const path = await save({
+        defaultPath: 'credits.txt',
+        filters: [{ name: 'Text', extensions: ['txt'] }]
+      });
+      if (!path) return null;
+      return await this.tauri.exportAttributions(path);
+    } catch (err) {
+      this._error.set(err instanceof Error ? err.message : String(err));
+      return null;
+    }
+  }
+
   /**
    * Clear any error
    */
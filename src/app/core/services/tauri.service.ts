@@ -1,14 +1,164 @@
 import { Injectable } from '@angular/core';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke, InvokeArgs } from '@tauri-apps/api/core';
 import {
+  ActiveHotkey,
   AudioDevice,
+  Board,
+  BoardHotkeySettings,
+  GlobalHotkeys,
+  PushToTalkSettings,
+  SafeModeStatus,
+  MicProfile,
+  MicProfileStatus,
+  GeneratorSettings,
+  AppInfo,
+  TalkState,
+  BroadcastDelaySettings,
+  HeadphoneLimiterSettings,
+  CapturableApp,
+  ConfigIssue,
   MixerChannel,
   MixerConfig,
+  MonitorInfo,
+  MonitorSettings,
+  MuteGroup,
+  PlaybackSettings,
+  OutputFormatSettings,
+  OutputLayoutSettings,
+  SampleRateMismatch,
+  SoundInsert,
+  SpeedMode,
+  StartOutcome,
+  VoiceActivitySettings,
   AppSettings,
-  ApiResponse,
-  SoundFile
+  CommandError,
+  DeviceInUse,
+  EngineSnapshot,
+  EngineStall,
+  FeedbackProtectionSettings,
+  DuckingSettings,
+  BufferAutoTuneSettings,
+  BufferAutoTuned,
+  GainRecommendation,
+  GainWizardState,
+  HotkeyConflict,
+  HotkeyValidation,
+  InputChannelMap,
+  InputChannels,
+  MicAgcSettings,
+  MicHighpassSettings,
+  MicEffectNode,
+  NoiseGateSettings,
+  KeyboardSuppressionSettings,
+  SpectralQuality,
+  SpectralQualityInfo,
+  VoiceChangerSettings,
+  VoicePreset,
+  WatchdogDiagnostics,
+  SoundFile,
+  SoundPack,
+  PlayLogEntry,
+  SessionSummary,
+  PlaySource,
+  Scene,
+  ScenePad,
+  TimerConfig,
+  TimerProgress,
+  PodcastMic,
+  RecordingMarker,
+  RecordingSummary,
+  ShareInfo,
+  ShareRequest,
+  NowPlaying,
+  OverlayInfo,
+  HotFolderSettings,
+  StartJingleSettings,
+  Integration,
+  IntegrationTrigger,
+  IntegrationPolicy,
+  VirtualDeviceSettings,
+  DeviceType,
+  SyncConfig,
+  SyncResolution,
+  SyncStatus,
+  CleanupPolicy,
+  CleanupReport,
+  ArchiveResult,
+  LibraryStats
 } from '../models';
 
+/**
+ * Error thrown when a backend command fails, carrying its stable error code
+ */
+export class CommandFailedError extends Error {
+  constructor(
+    public readonly code: string,
+    message: string,
+    public readonly conflicts: HotkeyConflict[] = [],
+    public readonly path?: string,
+    public readonly params: Record<string, string> = {}
+  ) {
+    super(message);
+    this.name = 'CommandFailedError';
+  }
+
+  static from(err: unknown): CommandFailedError {
+    const payload = err as Partial<CommandError> | null;
+    if (payload && typeof payload.code === 'string') {
+      const conflicts = (payload.conflicts ?? []).map(mapHotkeyConflict);
+      return new CommandFailedError(
+        payload.code, payload.message ?? payload.code, conflicts, payload.path, payload.params ?? {}
+      );
+    }
+    return new CommandFailedError('INTERNAL', String(err));
+  }
+
+  /**
+   * Localized text from per-code templates using `{param}` placeholders,
+   * falling back to the English message for codes without a template
+   */
+  localize(templates: Record<string, string>): string {
+    const template = templates[this.code];
+    if (!template) return this.message;
+    return template.replace(/\{(\w+)\}/g, (match, name) => this.params[name] ?? match);
+  }
+}
+
+function mapHotkeyConflict(c: any): HotkeyConflict {
+  return {
+    padId: c.pad_id,
+    hotkey: c.hotkey,
+    bank: c.bank,
+    profile: c.profile ?? undefined,
+    kind: c.kind
+  };
+}
+
+function mapActiveHotkey(h: any): ActiveHotkey {
+  return { padId: h.pad_id, hotkey: h.hotkey, bank: h.bank, keys: h.keys };
+}
+
+function mapSessionSummary(s: any): SessionSummary {
+  return {
+    startedAt: s.started_at,
+    endedAt: s.ended_at,
+    durationSecs: s.duration_secs,
+    peakDb: s.peak_db,
+    averageDb: s.average_db,
+    triggers: s.triggers,
+    underruns: s.underruns,
+    topSounds: s.top_sounds.map((t: any) => ({ soundId: t.sound_id, name: t.name, plays: t.plays }))
+  };
+}
+
+function mapGlobalHotkeys(g: any): GlobalHotkeys {
+  return { enabled: g.enabled, pads: g.pads, boardSwitching: g.board_switching, unavailable: g.unavailable };
+}
+
+function mapBoard(b: any): Board {
+  return { board: b.board, generation: b.generation, boards: b.boards, hotkeys: b.hotkeys.map(mapActiveHotkey) };
+}
+
 /**
  * Service for communicating with the Tauri/Rust backend
  */
@@ -17,6 +167,73 @@ import {
 })
 export class TauriService {
 
+  /**
+   * Invoke a backend command, converting its error payload to a CommandFailedError
+   */
+  private async invoke<T>(command: string, args?: InvokeArgs): Promise<T> {
+    try {
+      return await invoke<T>(command, args);
+    } catch (err) {
+      throw CommandFailedError.from(err);
+    }
+  }
+
+  // =========================================================================
+  // Startup
+  // =========================================================================
+
+  private backendReady?: Promise<void>;
+
+  /**
+   * Resolve once the device list and preview engine, started after the
+   * window is shown, are up
+   */
+  waitForBackend(): Promise<void> {
+    this.backendReady ??= (async () => {
+      const { listen } = await import('@tauri-apps/api/event');
+      let markReady!: () => void;
+      const ready = new Promise<void>(resolve => markReady = resolve);
+      const unlisten = await listen('backend-ready', () => markReady());
+      // The event may have fired before we subscribed
+      if (!(await this.invoke<boolean>('is_backend_ready'))) {
+        await ready;
+      }
+      unlisten();
+    })();
+    return this.backendReady;
+  }
+
+  /**
+   * Whether this run is in safe mode, and why
+   */
+  async getSafeMode(): Promise<SafeModeStatus> {
+    return this.invoke<SafeModeStatus>('get_safe_mode');
+  }
+
+  /**
+   * Build and runtime environment, for the About dialog and bug reports
+   */
+  async getAppInfo(): Promise<AppInfo> {
+    const info = await this.invoke<any>('get_app_info');
+    return {
+      version: info.version,
+      build: {
+        profile: info.build.profile,
+        targetOs: info.build.target_os,
+        targetArch: info.build.target_arch,
+        features: info.build.features,
+      },
+      audioHost: info.audio_host,
+      osVersion: info.os_version,
+      devices: info.devices.map((d: any) => ({ role: d.role, name: d.name, isVirtual: d.is_virtual, driver: d.driver })),
+      deviceSampleRate: info.device_sample_rate,
+      bufferFrames: info.buffer_frames,
+      virtualDriverInstalled: info.virtual_driver_installed,
+      updateChannel: info.update_channel,
+      safeMode: info.safe_mode,
+    };
+  }
+
   // =========================================================================
   // Device Management
   // =========================================================================
@@ -25,41 +242,75 @@ export class TauriService {
    * Get all available audio devices
    */
   async getAudioDevices(): Promise<AudioDevice[]> {
-    const response = await invoke<ApiResponse<AudioDevice[]>>('get_audio_devices');
-    if (!response.success || !response.data) {
-      throw new Error(response.error || 'Failed to get audio devices');
-    }
-    return this.mapDevices(response.data);
+    const devices = await this.invoke<any[]>('get_audio_devices');
+    return this.mapDevices(devices);
   }
 
   /**
    * Get input devices only (microphones)
    */
   async getInputDevices(): Promise<AudioDevice[]> {
-    const response = await invoke<ApiResponse<AudioDevice[]>>('get_input_devices');
-    if (!response.success || !response.data) {
-      throw new Error(response.error || 'Failed to get input devices');
-    }
-    return this.mapDevices(response.data);
+    const devices = await this.invoke<any[]>('get_input_devices');
+    return this.mapDevices(devices);
   }
 
   /**
    * Get virtual output devices (for sending mixed audio)
    */
   async getVirtualOutputDevices(): Promise<AudioDevice[]> {
-    const response = await invoke<ApiResponse<AudioDevice[]>>('get_virtual_output_devices');
-    if (!response.success || !response.data) {
-      throw new Error(response.error || 'Failed to get virtual output devices');
-    }
-    return this.mapDevices(response.data);
+    const devices = await this.invoke<any[]>('get_virtual_output_devices');
+    return this.mapDevices(devices);
   }
 
   /**
    * Check if virtual audio driver is installed
    */
   async checkVirtualDriver(): Promise<boolean> {
-    const response = await invoke<ApiResponse<boolean>>('check_virtual_driver');
-    return response.success && response.data === true;
+    return this.invoke<boolean>('check_virtual_driver').catch(() => false);
+  }
+
+  /**
+   * Get the name patterns and marks deciding which devices are virtual
+   */
+  async getVirtualDeviceSettings(): Promise<VirtualDeviceSettings> {
+    return this.invoke<VirtualDeviceSettings>('get_virtual_device_settings');
+  }
+
+  /**
+   * Detect devices whose name contains the pattern as virtual
+   */
+  async addVirtualDevicePattern(pattern: string): Promise<VirtualDeviceSettings> {
+    return this.invoke<VirtualDeviceSettings>('add_virtual_device_pattern', { pattern });
+  }
+
+  /**
+   * Stop detecting devices by a pattern
+   */
+  async removeVirtualDevicePattern(pattern: string): Promise<VirtualDeviceSettings> {
+    return this.invoke<VirtualDeviceSettings>('remove_virtual_device_pattern', { pattern });
+  }
+
+  /**
+   * Treat a device as virtual whatever its name, or remove the mark
+   */
+  async markDeviceAsVirtual(deviceId: string, isVirtual = true): Promise<VirtualDeviceSettings> {
+    return this.invoke<VirtualDeviceSettings>('mark_device_as_virtual', { deviceId, isVirtual });
+  }
+
+  /**
+   * Force the type of a mis-detected device, or clear it with null; the
+   * type must keep the device's direction
+   */
+  async setDeviceTypeOverride(deviceId: string, deviceType: DeviceType | null): Promise<VirtualDeviceSettings> {
+    return this.invoke<VirtualDeviceSettings>('set_device_type_override', { deviceId, deviceType });
+  }
+
+  /**
+   * Re-enumerate audio devices (the backend also refreshes on hot-plug)
+   */
+  async refreshDevices(): Promise<AudioDevice[]> {
+    const devices = await this.invoke<any[]>('refresh_devices');
+    return this.mapDevices(devices);
   }
 
   /**
@@ -83,7 +334,7 @@ export class TauriService {
    * Get current application settings
    */
   async getSettings(): Promise<AppSettings> {
-    const settings = await invoke<any>('get_settings');
+    const settings = await this.invoke<any>('get_settings');
     return this.mapSettings(settings);
   }
 
@@ -91,14 +342,14 @@ export class TauriService {
    * Save application settings
    */
   async saveSettings(settings: AppSettings): Promise<void> {
-    await invoke('save_settings', { settings: this.unmapSettings(settings) });
+    await this.invoke('save_settings', { settings: this.unmapSettings(settings) });
   }
 
   /**
    * Load settings from persistent storage
    */
   async loadSettings(): Promise<AppSettings> {
-    const settings = await invoke<any>('load_settings');
+    const settings = await this.invoke<any>('load_settings');
     return this.mapSettings(settings);
   }
 
@@ -106,14 +357,14 @@ export class TauriService {
    * Set input device (microphone)
    */
   async setInputDevice(deviceId: string | null): Promise<void> {
-    await invoke('set_input_device', { deviceId });
+    await this.invoke('set_input_device', { deviceId });
   }
 
   /**
    * Set output device (virtual microphone)
    */
   async setOutputDevice(deviceId: string | null): Promise<void> {
-    await invoke('set_output_device', { deviceId });
+    await this.invoke('set_output_device', { deviceId });
   }
 
   /**
@@ -127,7 +378,8 @@ export class TauriService {
         previewDeviceId: s.audio.preview_device_id,
         masterVolume: s.audio.master_volume,
         sampleRate: s.audio.sample_rate,
-        bufferSize: s.audio.buffer_size
+        bufferSize: s.audio.buffer_size,
+        ducking: this.mapDucking(s.audio.ducking)
       },
       startMinimized: s.start_minimized,
       autoStartMixing: s.auto_start_mixing
@@ -145,13 +397,22 @@ export class TauriService {
         preview_device_id: s.audio.previewDeviceId,
         master_volume: s.audio.masterVolume,
         sample_rate: s.audio.sampleRate,
-        buffer_size: s.audio.bufferSize
+        buffer_size: s.audio.bufferSize,
+        ducking: this.unmapDucking(s.audio.ducking)
       },
       start_minimized: s.startMinimized,
       auto_start_mixing: s.autoStartMixing
     };
   }
 
+  private mapDucking(d: any): DuckingSettings {
+    return { enabled: d.enabled, depthDb: d.depth_db, attackMs: d.attack_ms, releaseMs: d.release_ms };
+  }
+
+  private unmapDucking(d: DuckingSettings): any {
+    return { enabled: d.enabled, depth_db: d.depthDb, attack_ms: d.attackMs, release_ms: d.releaseMs };
+  }
+
   // =========================================================================
   // Mixer Configuration
   // =========================================================================
@@ -160,16 +421,30 @@ export class TauriService {
    * Get current mixer configuration
    */
   async getMixerConfig(): Promise<MixerConfig> {
-    const config = await invoke<any>('get_mixer_config');
+    return this.mapMixerConfig(await this.invoke<any>('get_mixer_config'));
+  }
+
+  private mapChannel(c: any): MixerChannel {
+    return {
+      id: c.id,
+      name: c.name,
+      channelType: c.channel_type,
+      volume: c.volume,
+      muted: c.muted,
+      solo: c.solo,
+      generator: c.generator && { signal: c.generator.signal, levelDbfs: c.generator.level_dbfs }
+    };
+  }
+
+  private mapMixerConfig(config: any): MixerConfig {
     return {
       masterVolume: config.master_volume,
-      channels: config.channels.map((c: any) => ({
-        id: c.id,
-        name: c.name,
-        channelType: c.channel_type,
-        volume: c.volume,
-        muted: c.muted,
-        solo: c.solo
+      channels: config.channels.map((c: any) => this.mapChannel(c)),
+      muteGroups: config.mute_groups.map((g: any) => ({
+        id: g.id,
+        name: g.name,
+        channelIds: g.channel_ids,
+        linkedGroups: g.linked_groups
       })),
       sampleRate: config.sample_rate,
       bufferSize: config.buffer_size
@@ -180,195 +455,1799 @@ export class TauriService {
    * Set master volume (0.0 to 1.0)
    */
   async setMasterVolume(volume: number): Promise<void> {
-    await invoke('set_master_volume', { volume: Math.max(0, Math.min(1, volume)) });
+    await this.invoke('set_master_volume', { volume: Math.max(0, Math.min(1, volume)) });
   }
 
-  // =========================================================================
-  // Channel Management
-  // =========================================================================
+  /**
+   * Get the output bit depth and dithering
+   */
+  async getOutputFormat(): Promise<OutputFormatSettings> {
+    const format = await this.invoke<any>('get_output_format');
+    return { bitDepth: format.bit_depth, dither: format.dither };
+  }
 
   /**
-   * Add a microphone channel
+   * Set the output bit depth and dithering
    */
-  async addMicrophoneChannel(id: string, name: string): Promise<MixerChannel> {
-    return invoke<MixerChannel>('add_microphone_channel', { id, name });
+  async setOutputFormat(format: OutputFormatSettings): Promise<void> {
+    await this.invoke('set_output_format', {
+      format: { bit_depth: format.bitDepth, dither: format.dither }
+    });
   }
 
   /**
-   * Add an audio file channel
+   * Get the channel layout of the output device
    */
-  async addAudioFileChannel(id: string, name: string): Promise<MixerChannel> {
-    return invoke<MixerChannel>('add_audio_file_channel', { id, name });
+  async getOutputLayout(): Promise<OutputLayoutSettings> {
+    const layout = await this.invoke<any>('get_output_layout');
+    return { layout: layout.layout, centerFromStereo: layout.center_from_stereo };
   }
 
   /**
-   * Remove a channel
+   * Set the output channel layout; restarts the streams while mixing
    */
-  async removeChannel(channelId: string): Promise<void> {
-    await invoke('remove_channel', { channelId });
+  async setOutputLayout(layout: OutputLayoutSettings): Promise<void> {
+    await this.invoke('set_output_layout', {
+      layout: { layout: layout.layout, center_from_stereo: layout.centerFromStereo }
+    });
   }
 
   /**
-   * Set channel volume (0.0 to 2.0)
+   * Get the headphone monitor settings and how far it lags the mic
    */
-  async setChannelVolume(channelId: string, volume: number): Promise<void> {
-    await invoke('set_channel_volume', { channelId, volume });
+  async getMonitor(): Promise<MonitorInfo> {
+    const monitor = await this.invoke<any>('get_monitor');
+    return {
+      settings: {
+        enabled: monitor.settings.enabled,
+        volume: monitor.settings.volume,
+        delayMs: monitor.settings.delay_ms,
+        codecBitrateKbps: monitor.settings.codec_bitrate_kbps ?? null
+      },
+      effectLatencyMs: monitor.effect_latency_ms,
+      totalDelayMs: monitor.total_delay_ms
+    };
   }
 
   /**
-   * Toggle channel mute state
+   * Monitor the processed mix on the preview device
    */
-  async toggleChannelMute(channelId: string): Promise<boolean> {
-    return invoke<boolean>('toggle_channel_mute', { channelId });
+  async setMonitor(monitor: MonitorSettings): Promise<void> {
+    await this.invoke('set_monitor', {
+      monitor: {
+        enabled: monitor.enabled,
+        volume: monitor.volume,
+        delay_ms: monitor.delayMs,
+        codec_bitrate_kbps: monitor.codecBitrateKbps
+      }
+    });
   }
 
-  // =========================================================================
-  // Mixing Control
-  // =========================================================================
+  /**
+   * Switch the engine to the output device's rate, returning the new rate
+   */
+  async matchDeviceSampleRate(): Promise<number> {
+    return this.invoke<number>('match_device_sample_rate');
+  }
 
   /**
-   * Start audio mixing
+   * Get the headphone safety limiter of the monitor and previews
    */
-  async startMixing(): Promise<void> {
-    await invoke('start_mixing');
+  async getHeadphoneLimiter(): Promise<HeadphoneLimiterSettings> {
+    const limiter = await this.invoke<any>('get_headphone_limiter');
+    return { enabled: limiter.enabled, ceilingDb: limiter.ceiling_db };
   }
 
   /**
-   * Stop audio mixing
+   * Set the headphone safety ceiling of the monitor and previews
    */
-  async stopMixing(): Promise<void> {
-    await invoke('stop_mixing');
+  async setHeadphoneLimiter(limiter: HeadphoneLimiterSettings): Promise<void> {
+    await this.invoke('set_headphone_limiter', {
+      limiter: { enabled: limiter.enabled, ceiling_db: limiter.ceilingDb }
+    });
   }
 
   /**
-   * Check if currently mixing
+   * Get the broadcast delay of the output
    */
-  async isMixing(): Promise<boolean> {
-    return invoke<boolean>('is_mixing');
+  async getBroadcastDelay(): Promise<BroadcastDelaySettings> {
+    const delay = await this.invoke<any>('get_broadcast_delay');
+    return { enabled: delay.enabled, delayMs: delay.delay_ms };
   }
 
-  // =========================================================================
-  // Sound Playback (Soundboard)
-  // =========================================================================
+  /**
+   * Set the broadcast delay; changing it restarts the delay with silence
+   */
+  async setBroadcastDelay(delay: BroadcastDelaySettings): Promise<void> {
+    await this.invoke('set_broadcast_delay', {
+      delay: { enabled: delay.enabled, delay_ms: delay.delayMs }
+    });
+  }
 
   /**
-   * Load and decode an audio file, returning its metadata
+   * Silence everything held in the broadcast delay (panic button)
    */
-  async loadSoundFile(path: string): Promise<SoundFile> {
-    console.log('[TauriService] loadSoundFile called with path:', path);
-    try {
-      const result = await invoke<any>('load_sound_file', { path });
-      console.log('[TauriService] loadSoundFile result:', result);
-      return {
-        id: result.id,
-        name: result.name,
-        path: result.path,
-        duration: result.duration,
-        sampleRate: result.sample_rate,
-        channels: result.channels
-      };
-    } catch (err) {
-      console.error('[TauriService] loadSoundFile error:', err);
-      throw err;
-    }
+  async dumpDelay(): Promise<void> {
+    await this.invoke('dump_delay');
   }
 
   /**
-   * Play a sound file (mixed with microphone)
+   * Bleep (or silence) the last seconds still held in the broadcast delay
    */
-  async playSound(id: string, path: string): Promise<void> {
-    await invoke('play_sound', { id, path });
+  async censorLast(seconds: number, mode: 'beep' | 'silence' = 'beep'): Promise<void> {
+    await this.invoke('censor_last', { seconds, mode });
   }
 
   /**
-   * Stop a playing sound
+   * Listen for a device the engine couldn't open because another app holds it exclusively
    */
-  async stopSound(id: string): Promise<void> {
-    await invoke('stop_sound', { id });
+  async listenDeviceInUse(callback: (event: DeviceInUse) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<DeviceInUse>('device-in-use', (event) => callback(event.payload));
   }
 
   /**
-   * Preview a sound on a specific output device
+   * Get the auto-mute of the mic on a feedback loop
    */
-  async previewSound(path: string, deviceName: string, padId: string): Promise<void> {
-    await invoke('preview_sound', { path, deviceName, padId });
+  async getFeedbackProtection(): Promise<FeedbackProtectionSettings> {
+    return this.invoke<FeedbackProtectionSettings>('get_feedback_protection');
   }
 
   /**
-   * Stop the currently playing preview
+   * Turn the feedback loop auto-mute on or off
    */
-  async stopPreview(): Promise<void> {
-    await invoke('stop_preview');
+  async setFeedbackProtection(protection: FeedbackProtectionSettings): Promise<void> {
+    await this.invoke('set_feedback_protection', { protection });
   }
 
   /**
-   * Get the currently previewing pad ID
+   * Reopen a mic the feedback guard muted; the user's mute is left as set
    */
-  async getPreviewState(): Promise<string | null> {
-    return invoke<string | null>('get_preview_state');
+  async clearFeedbackMute(): Promise<void> {
+    await this.invoke('clear_feedback_mute');
   }
 
   /**
-   * Set the preview output device
+   * Get the ducking of the mic under playing sounds
    */
-  async setPreviewDevice(deviceId: string | null): Promise<void> {
-    await invoke('set_preview_device', { deviceId });
+  async getDuckingConfig(): Promise<DuckingSettings> {
+    return this.mapDucking(await this.invoke<any>('get_ducking_config'));
   }
 
   /**
-   * Set microphone volume (0.0 to 2.0)
+   * Set how far and how fast the mic ducks while a sound plays
    */
-  async setMicVolume(volume: number): Promise<void> {
-    await invoke('set_mic_volume', { volume: Math.max(0, Math.min(2, volume)) });
+  async setDuckingConfig(config: DuckingSettings): Promise<void> {
+    await this.invoke('set_ducking_config', { config: this.unmapDucking(config) });
   }
 
   /**
-   * Mute or unmute microphone
+   * Get the buffer auto-tune setting and the sizes tuned so far
    */
-  async setMicMuted(muted: boolean): Promise<void> {
-    await invoke('set_mic_muted', { muted });
+  async getBufferAutoTune(): Promise<BufferAutoTuneSettings> {
+    return this.invoke<BufferAutoTuneSettings>('get_buffer_auto_tune');
   }
 
-  // =========================================================================
-  // Soundboard Persistence
-  // =========================================================================
+  /**
+   * Turn buffer auto-tune on or off; running streams restart with the new size
+   */
+  async setBufferAutoTune(autoTune: BufferAutoTuneSettings): Promise<void> {
+    await this.invoke('set_buffer_auto_tune', { autoTune });
+  }
 
   /**
-   * Save soundboard state to persistent storage
+   * Listen for the buffer being raised after the audio kept breaking up
    */
-  async saveSoundboardState(pads: any[]): Promise<void> {
-    await invoke('save_soundboard', { pads });
+  async listenBufferAutoTuned(callback: (event: BufferAutoTuned) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<BufferAutoTuned>('buffer-auto-tuned', (event) => callback(event.payload));
   }
 
   /**
-   * Load soundboard state from persistent storage
+   * Listen for a feedback loop the engine detected; the mic is already muted
+   * until `clearFeedbackMute`, apart from the user's mute
    */
-  async loadSoundboardState(): Promise<any[] | null> {
-    return invoke<any[] | null>('load_soundboard');
+  async listenFeedbackDetected(callback: (message: string) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<{ code: string; message: string }>('feedback-detected', (event) => callback(event.payload.message));
+  }
+
+  /**
+   * Get the speaking detection settings
+   */
+  async getVoiceActivity(): Promise<VoiceActivitySettings> {
+    const settings = await this.invoke<any>('get_voice_activity');
+    return {
+      enabled: settings.enabled,
+      thresholdDb: settings.threshold_db,
+      hangoverMs: settings.hangover_ms
+    };
+  }
+
+  /**
+   * Set the threshold and hangover of the speaking detection
+   */
+  async setVoiceActivity(settings: VoiceActivitySettings): Promise<void> {
+    await this.invoke('set_voice_activity', {
+      settings: {
+        enabled: settings.enabled,
+        threshold_db: settings.thresholdDb,
+        hangover_ms: settings.hangoverMs
+      }
+    });
+  }
+
+  /**
+   * Listen for the user starting or stopping speaking into the mic
+   */
+  async listenVoiceActivity(callback: (speaking: boolean) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<{ speaking: boolean }>('voice-activity', (event) => callback(event.payload.speaking));
+  }
+
+  /**
+   * Listen for a window close or quit held back because a recording is running
+   */
+  async listenRecordingActive(callback: (path: string) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<{ path: string }>('recording-active', (event) => callback(event.payload.path));
+  }
+
+  /**
+   * Listen for audio callbacks that stopped while mixing
+   */
+  async listenEngineStalled(callback: (stall: EngineStall) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<EngineStall>('engine-stalled', (event) => callback(event.payload));
+  }
+
+  /**
+   * Get the stalls found by the engine watchdog
+   */
+  async getWatchdogDiagnostics(): Promise<WatchdogDiagnostics> {
+    const diagnostics = await this.invoke<any>('get_watchdog_diagnostics');
+    return {
+      stalls: diagnostics.stalls.map((s: any) => ({
+        timestamp: s.timestamp,
+        stream: s.stream,
+        silentMs: s.silent_ms,
+        inputDevice: s.input_device,
+        outputDevice: s.output_device,
+        sampleRate: s.sample_rate,
+        rebuilt: s.rebuilt
+      })),
+      rebuilds: diagnostics.rebuilds
+    };
+  }
+
+  /**
+   * Take the mix rendered on the null devices (app started with --test-audio)
+   */
+  async takeTestAudioCapture(): Promise<number[]> {
+    return this.invoke<number[]>('take_test_audio_capture');
+  }
+
+  /**
+   * Listen for the output device running at a different rate than the engine
+   */
+  async listenSampleRateMismatch(callback: (mismatch: SampleRateMismatch) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<SampleRateMismatch>('sample-rate-mismatch', (event) => callback(event.payload));
   }
 
   // =========================================================================
-  // Preview Event Listeners
+  // Channel Management
   // =========================================================================
 
   /**
-   * Listen for preview started events
+   * Add a microphone channel
    */
-  async listenPreviewStarted(callback: (padId: string) => void): Promise<() => void> {
-    const { listen } = await import('@tauri-apps/api/event');
-    const unlisten = await listen<string>('preview-started', (event) => {
-      callback(event.payload);
+  async addMicrophoneChannel(id: string, name: string): Promise<MixerChannel> {
+    return this.invoke<MixerChannel>('add_microphone_channel', { id, name });
+  }
+
+  /**
+   * Add an audio file channel
+   */
+  async addAudioFileChannel(id: string, name: string): Promise<MixerChannel> {
+    return this.invoke<MixerChannel>('add_audio_file_channel', { id, name });
+  }
+
+  /**
+   * List applications playing audio that can be captured (Windows 10 20348+)
+   */
+  async listCapturableApps(): Promise<CapturableApp[]> {
+    const apps = await this.invoke<any[]>('list_capturable_apps');
+    return apps.map(a => ({ processId: a.process_id, name: a.name }));
+  }
+
+  /**
+   * Add a channel carrying only one application's audio
+   */
+  async addAppChannel(id: string, name: string, processId: number): Promise<MixerChannel> {
+    return this.invoke<MixerChannel>('add_app_channel', { id, name, processId });
+  }
+
+  /**
+   * Add a channel generating a test signal at a known level
+   */
+  async addGeneratorChannel(id: string, name: string, settings: GeneratorSettings): Promise<MixerChannel> {
+    const channel = await this.invoke<any>('add_generator_channel', {
+      id, name, settings: { signal: settings.signal, level_dbfs: settings.levelDbfs }
     });
-    return unlisten;
+    return this.mapChannel(channel);
   }
 
   /**
-   * Listen for preview stopped events
+   * Change the signal or level of a generator channel
    */
-  async listenPreviewStopped(callback: (padId: string) => void): Promise<() => void> {
-    const { listen } = await import('@tauri-apps/api/event');
-    const unlisten = await listen<string>('preview-stopped', (event) => {
-      callback(event.payload);
+  async setGenerator(channelId: string, settings: GeneratorSettings): Promise<void> {
+    await this.invoke('set_generator', {
+      channelId, settings: { signal: settings.signal, level_dbfs: settings.levelDbfs }
+    });
+  }
+
+  /**
+   * Remove a channel
+   */
+  async removeChannel(channelId: string): Promise<void> {
+    await this.invoke('remove_channel', { channelId });
+  }
+
+  /**
+   * Set channel volume (0.0 to 2.0)
+   */
+  async setChannelVolume(channelId: string, volume: number): Promise<void> {
+    await this.invoke('set_channel_volume', { channelId, volume });
+  }
+
+  /**
+   * Toggle channel mute state
+   */
+  async toggleChannelMute(channelId: string): Promise<boolean> {
+    return this.invoke<boolean>('toggle_channel_mute', { channelId });
+  }
+
+  /**
+   * Mute a channel for a moment (cough button); pressing again extends it
+   */
+  async momentaryMute(channelId: string, durationMs?: number): Promise<void> {
+    await this.invoke('momentary_mute', { channelId, durationMs: durationMs ?? null });
+  }
+
+  /**
+   * Replace the mixer's mute groups
+   */
+  async setMuteGroups(groups: MuteGroup[]): Promise<void> {
+    await this.invoke('set_mute_groups', {
+      groups: groups.map(g => ({
+        id: g.id,
+        name: g.name,
+        channel_ids: g.channelIds,
+        linked_groups: g.linkedGroups
+      }))
+    });
+  }
+
+  /**
+   * Mute or unmute a group and its linked groups, returning the changed channel ids
+   */
+  async setMuteGroupMuted(groupId: string, muted: boolean): Promise<string[]> {
+    return this.invoke<string[]>('set_mute_group_muted', { groupId, muted });
+  }
+
+  // =========================================================================
+  // Mixing Control
+  // =========================================================================
+
+  /**
+   * Check the selected devices before starting; empty when Start should succeed
+   */
+  async validateMixingConfig(): Promise<ConfigIssue[]> {
+    const issues = await this.invoke<any[]>('validate_mixing_config');
+    return issues.map(i => ({
+      severity: i.severity,
+      code: i.code,
+      role: i.role,
+      device: i.device,
+      sampleRate: i.sample_rate,
+      channels: i.channels,
+      supported: i.supported
+    }));
+  }
+
+  /**
+   * Start audio mixing; a no-op while already running with the same setup
+   */
+  async startMixing(): Promise<StartOutcome> {
+    return this.invoke<StartOutcome>('start_mixing');
+  }
+
+  /**
+   * Stop audio mixing
+   */
+  async stopMixing(finalizeRecording = false): Promise<void> {
+    await this.invoke('stop_mixing', { finalizeRecording });
+  }
+
+  /**
+   * Quit the app; fails with RECORDING_ACTIVE while recording unless finalizeRecording
+   */
+  async quitApp(finalizeRecording = false): Promise<void> {
+    await this.invoke('quit_app', { finalizeRecording });
+  }
+
+  /**
+   * Check if currently mixing
+   */
+  async isMixing(): Promise<boolean> {
+    return this.invoke<boolean>('is_mixing');
+  }
+
+  /**
+   * Get the whole engine state at once, e.g. after a reload
+   */
+  async getEngineSnapshot(): Promise<EngineSnapshot> {
+    const s = await this.invoke<any>('get_engine_snapshot');
+    return {
+      running: s.running,
+      inputDevice: s.input_device,
+      outputDevice: s.output_device,
+      monitorDevice: s.monitor_device,
+      sampleRate: s.sample_rate,
+      deviceSampleRate: s.device_sample_rate,
+      mixer: this.mapMixerConfig(s.mixer),
+      micVolume: s.mic_volume,
+      micMuted: s.mic_muted,
+      feedbackMuted: s.feedback_muted,
+      playingSounds: s.playing_sounds.map((p: any) => ({
+        id: p.id,
+        positionSecs: p.position_secs,
+        durationSecs: p.duration_secs,
+        volume: p.volume,
+        looping: p.looping,
+        paused: p.paused
+      })),
+      previewPadId: s.preview_pad_id,
+      micChain: s.mic_chain,
+      voiceChanger: this.mapVoiceChanger(s.voice_changer),
+      monitor: {
+        enabled: s.monitor.enabled,
+        volume: s.monitor.volume,
+        delayMs: s.monitor.delay_ms,
+        codecBitrateKbps: s.monitor.codec_bitrate_kbps ?? null
+      },
+      broadcastDelay: { enabled: s.broadcast_delay.enabled, delayMs: s.broadcast_delay.delay_ms },
+      headphoneLimiter: { enabled: s.headphone_limiter.enabled, ceilingDb: s.headphone_limiter.ceiling_db },
+      recordingPath: s.recording_path
+    };
+  }
+
+  // =========================================================================
+  // Sound Playback (Soundboard)
+  // =========================================================================
+
+  /**
+   * Load and decode an audio file, returning its metadata
+   */
+  async loadSoundFile(path: string): Promise<SoundFile> {
+    console.log('[TauriService] loadSoundFile called with path:', path);
+    try {
+      const result = await this.invoke<any>('load_sound_file', { path });
+      console.log('[TauriService] loadSoundFile result:', result);
+      return {
+        id: result.id,
+        name: result.name,
+        path: result.path,
+        duration: result.duration,
+        sampleRate: result.sample_rate,
+        channels: result.channels
+      };
+    } catch (err) {
+      console.error('[TauriService] loadSoundFile error:', err);
+      throw err;
+    }
+  }
+
+  /**
+   * Capture room tone and learn a noise print for the current mic
+   */
+  async learnNoiseProfile(seconds?: number): Promise<{ device: string; fftSize: number; seconds: number }> {
+    const result = await this.invoke<any>('learn_noise_profile', { seconds });
+    return { device: result.device, fftSize: result.fft_size, seconds: result.seconds };
+  }
+
+  /**
+   * Turn learned-noise-print denoising on or off
+   */
+  async setNoiseReductionEnabled(enabled: boolean): Promise<void> {
+    await this.invoke('set_noise_reduction_enabled', { enabled });
+  }
+
+  /**
+   * Forget the noise print of the current mic
+   */
+  async clearNoiseProfile(): Promise<void> {
+    await this.invoke('clear_noise_profile');
+  }
+
+  /**
+   * Cancel soundboard playback picked up by the mic from speakers
+   */
+  async setEchoCancellation(enabled: boolean): Promise<void> {
+    await this.invoke('set_echo_cancellation', { enabled });
+  }
+
+  /**
+   * Get the automatic gain control settings of the current mic
+   */
+  async getMicAgc(): Promise<MicAgcSettings> {
+    const agc = await this.invoke<any>('get_mic_agc');
+    return {
+      enabled: agc.enabled,
+      targetDb: agc.target_db,
+      maxGainDb: agc.max_gain_db,
+      holdMs: agc.hold_ms
+    };
+  }
+
+  /**
+   * Configure automatic gain control for the current mic
+   */
+  async setMicAgc(agc: MicAgcSettings): Promise<void> {
+    await this.invoke('set_mic_agc', {
+      agc: {
+        enabled: agc.enabled,
+        target_db: agc.targetDb,
+        max_gain_db: agc.maxGainDb,
+        hold_ms: agc.holdMs
+      }
+    });
+  }
+
+  /**
+   * Get the noise gate settings of the current mic
+   */
+  async getNoiseGate(): Promise<NoiseGateSettings> {
+    const gate = await this.invoke<any>('get_noise_gate');
+    return { enabled: gate.enabled, thresholdDb: gate.threshold_db };
+  }
+
+  /**
+   * Configure the noise gate of the current mic
+   */
+  async setNoiseGate(gate: NoiseGateSettings): Promise<void> {
+    await this.invoke('set_noise_gate', {
+      gate: { enabled: gate.enabled, threshold_db: gate.thresholdDb }
+    });
+  }
+
+  /**
+   * Get the mic profile of the current input device
+   */
+  async getMicProfile(): Promise<MicProfileStatus> {
+    const status = await this.invoke<any>('get_mic_profile');
+    return { device: status.device, profile: this.mapMicProfile(status.profile), isDefault: status.is_default };
+  }
+
+  /**
+   * Make the current mic's profile the one new mics start from
+   */
+  async saveMicProfileAsDefault(): Promise<void> {
+    await this.invoke('save_mic_profile_as_default');
+  }
+
+  /**
+   * Forget the current mic's profile, going back to the default one
+   */
+  async resetMicProfile(): Promise<MicProfile> {
+    return this.mapMicProfile(await this.invoke<any>('reset_mic_profile'));
+  }
+
+  private mapMicProfile(profile: any): MicProfile {
+    return {
+      volume: profile.volume,
+      highpass: {
+        enabled: profile.highpass.enabled,
+        cutoffHz: profile.highpass.cutoff_hz,
+        slope: profile.highpass.slope
+      },
+      agc: {
+        enabled: profile.agc.enabled,
+        targetDb: profile.agc.target_db,
+        maxGainDb: profile.agc.max_gain_db,
+        holdMs: profile.agc.hold_ms
+      },
+      noiseGate: { enabled: profile.noise_gate.enabled, thresholdDb: profile.noise_gate.threshold_db }
+    };
+  }
+
+  /**
+   * Get the keyboard suppression settings
+   */
+  async getKeyboardSuppression(): Promise<KeyboardSuppressionSettings> {
+    const s = await this.invoke<any>('get_keyboard_suppression');
+    return { enabled: s.enabled, depthDb: s.depth_db, windowMs: s.window_ms };
+  }
+
+  /**
+   * Configure the keystroke-timed keyboard suppression
+   */
+  async setKeyboardSuppression(suppression: KeyboardSuppressionSettings): Promise<void> {
+    await this.invoke('set_keyboard_suppression', {
+      suppression: { enabled: suppression.enabled, depth_db: suppression.depthDb, window_ms: suppression.windowMs }
+    });
+  }
+
+  /**
+   * Get which channels of the current input device feed the mic
+   */
+  async getInputChannelMap(): Promise<InputChannels> {
+    const channels = await this.invoke<any>('get_input_channel_map');
+    return { map: channels.map, channelCount: channels.channel_count };
+  }
+
+  /**
+   * Choose which channels of the current input device feed the mic
+   */
+  async setInputChannelMap(map: InputChannelMap): Promise<void> {
+    await this.invoke('set_input_channel_map', { map });
+  }
+
+  /**
+   * Map two mono mics of the current interface to host and guest channels
+   */
+  async setupPodcastMode(hostChannel: number, guestChannel: number): Promise<InputChannels> {
+    const channels = await this.invoke<any>('setup_podcast_mode', { hostChannel, guestChannel });
+    return { map: channels.map, channelCount: channels.channel_count };
+  }
+
+  /**
+   * Adjust the gain, pan or mute of one podcast mic
+   */
+  async setPodcastMic(role: 'host' | 'guest', mic: PodcastMic): Promise<void> {
+    await this.invoke('set_podcast_mic', { role, mic });
+  }
+
+  /**
+   * Configure the rumble filter at the start of the mic chain
+   */
+  async setMicHighpass(highpass: MicHighpassSettings): Promise<void> {
+    await this.invoke('set_mic_highpass', {
+      highpass: {
+        enabled: highpass.enabled,
+        cutoff_hz: highpass.cutoffHz,
+        slope: highpass.slope
+      }
+    });
+  }
+
+  /**
+   * Get the FFT quality of spectral mic effects
+   */
+  async getSpectralQuality(): Promise<SpectralQualityInfo> {
+    return this.toSpectralQualityInfo(await this.invoke<any>('get_spectral_quality'));
+  }
+
+  /**
+   * Choose Low-latency, Balanced or High-quality FFT processing
+   */
+  async setSpectralQuality(quality: SpectralQuality): Promise<SpectralQualityInfo> {
+    return this.toSpectralQualityInfo(await this.invoke<any>('set_spectral_quality', { quality }));
+  }
+
+  private toSpectralQualityInfo(dto: any): SpectralQualityInfo {
+    return {
+      quality: dto.quality,
+      fftSize: dto.fft_size,
+      overlap: dto.overlap,
+      latencyMs: dto.latency_ms
+    };
+  }
+
+  /**
+   * List the character voices shipped with the app
+   */
+  async getBuiltinPresets(): Promise<VoicePreset[]> {
+    const presets = await this.invoke<any[]>('get_builtin_presets');
+    return presets.map(p => ({
+      id: p.id,
+      name: p.name,
+      settings: this.mapVoiceChanger(p.settings)
+    }));
+  }
+
+  /**
+   * Apply a built-in character voice to the mic
+   */
+  async applyBuiltinPreset(id: string): Promise<VoiceChangerSettings> {
+    return this.mapVoiceChanger(await this.invoke<any>('apply_builtin_preset', { id }));
+  }
+
+  /**
+   * Set the voice changer parameters (all zero for a clean voice)
+   */
+  async setVoiceChanger(settings: VoiceChangerSettings): Promise<void> {
+    await this.invoke('set_voice_changer', {
+      voiceChanger: {
+        pitch_semitones: settings.pitchSemitones,
+        ring_mod_hz: settings.ringModHz,
+        low_cut_hz: settings.lowCutHz,
+        high_cut_hz: settings.highCutHz,
+        drive: settings.drive,
+        echo_ms: settings.echoMs,
+        echo_feedback: settings.echoFeedback
+      }
+    });
+  }
+
+  private mapVoiceChanger(s: any): VoiceChangerSettings {
+    return {
+      pitchSemitones: s.pitch_semitones,
+      ringModHz: s.ring_mod_hz,
+      lowCutHz: s.low_cut_hz,
+      highCutHz: s.high_cut_hz,
+      drive: s.drive,
+      echoMs: s.echo_ms,
+      echoFeedback: s.echo_feedback
+    };
+  }
+
+  /**
+   * Get the mic effects in processing order
+   */
+  async getMicChain(): Promise<MicEffectNode[]> {
+    return this.invoke<MicEffectNode[]>('get_mic_chain');
+  }
+
+  /**
+   * Move a mic effect to another position in the chain
+   */
+  async moveEffect(indexFrom: number, indexTo: number): Promise<MicEffectNode[]> {
+    return this.invoke<MicEffectNode[]>('move_effect', { indexFrom, indexTo });
+  }
+
+  /**
+   * Switch a mic effect on or off without losing its settings
+   */
+  async setEffectEnabled(index: number, enabled: boolean): Promise<MicEffectNode[]> {
+    return this.invoke<MicEffectNode[]>('set_effect_enabled', { index, enabled });
+  }
+
+  /**
+   * Blend a mic effect with the dry signal (0 = dry, 1 = fully processed)
+   */
+  async setEffectMix(index: number, mix: number): Promise<MicEffectNode[]> {
+    return this.invoke<MicEffectNode[]>('set_effect_mix', { index, mix });
+  }
+
+  /**
+   * Bypass the whole mic chain to compare processed and dry mic
+   */
+  async setMicChainBypass(enabled: boolean): Promise<void> {
+    await this.invoke('set_mic_chain_bypass', { enabled });
+  }
+
+  /**
+   * Export a soundboard sound to WAV, FLAC or OGG with its trim and gain applied
+   */
+  async exportSound(
+    id: string,
+    format: 'wav' | 'flac' | 'ogg',
+    path: string,
+    settings?: { sampleRate?: number; bitsPerSample?: number }
+  ): Promise<void> {
+    await this.invoke('export_sound', {
+      id,
+      format,
+      path,
+      settings: settings && {
+        sample_rate: settings.sampleRate,
+        bits_per_sample: settings.bitsPerSample
+      }
+    });
+  }
+
+  /**
+   * Record a quick voice memo (up to 30 s), trimmed and normalized
+   */
+  async quickMemo(): Promise<SoundFile> {
+    const result = await this.invoke<any>('quick_memo');
+    return {
+      id: result.id,
+      name: result.name,
+      path: result.path,
+      duration: result.duration,
+      sampleRate: result.sample_rate,
+      channels: result.channels
+    };
+  }
+
+  /**
+   * Stop the quick memo being recorded
+   */
+  async stopQuickMemo(): Promise<void> {
+    await this.invoke('stop_quick_memo');
+  }
+
+  /**
+   * Listen for quick memo recording progress
+   */
+  async listenQuickMemoProgress(
+    callback: (progress: { elapsed: number; maxSecs: number; level: number }) => void
+  ): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<{ elapsed: number; maxSecs: number; level: number }>('quick-memo-progress', (event) => {
+      callback(event.payload);
+    });
+  }
+
+  /**
+   * Run the gain wizard: 3 s of silence, then 10 s of speech
+   *
+   * With `apply`, the recommended mic volume and noise gate are applied.
+   */
+  async startGainWizard(apply = false): Promise<GainRecommendation> {
+    const result = await this.invoke<any>('start_gain_wizard', { apply });
+    return this.mapGainRecommendation(result);
+  }
+
+  /**
+   * Get the gain wizard state, including the last result
+   */
+  async getGainWizard(): Promise<GainWizardState> {
+    return this.mapGainWizardState(await this.invoke<any>('get_gain_wizard'));
+  }
+
+  /**
+   * Stop the running gain wizard
+   */
+  async cancelGainWizard(): Promise<void> {
+    await this.invoke('cancel_gain_wizard');
+  }
+
+  /**
+   * Apply a gain wizard recommendation to the mic volume and noise gate
+   */
+  async applyGainRecommendation(recommendation: GainRecommendation): Promise<void> {
+    await this.invoke('apply_gain_recommendation', {
+      recommendation: {
+        noise_floor_db: recommendation.noiseFloorDb,
+        speech_rms_db: recommendation.speechRmsDb,
+        peak_db: recommendation.peakDb,
+        clipped: recommendation.clipped,
+        mic_volume: recommendation.micVolume,
+        gate_threshold_db: recommendation.gateThresholdDb
+      }
+    });
+  }
+
+  /**
+   * Listen for gain wizard progress
+   */
+  async listenGainWizardProgress(callback: (state: GainWizardState) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<any>('gain-wizard-progress', (event) => {
+      callback(this.mapGainWizardState(event.payload));
+    });
+  }
+
+  private mapGainWizardState(state: any): GainWizardState {
+    return {
+      step: state.step,
+      elapsed: state.elapsed,
+      duration: state.duration,
+      levelDb: state.level_db,
+      result: state.result ? this.mapGainRecommendation(state.result) : undefined,
+      error: state.error ?? undefined
+    };
+  }
+
+  private mapGainRecommendation(rec: any): GainRecommendation {
+    return {
+      noiseFloorDb: rec.noise_floor_db,
+      speechRmsDb: rec.speech_rms_db,
+      peakDb: rec.peak_db,
+      clipped: rec.clipped,
+      micVolume: rec.mic_volume,
+      gateThresholdDb: rec.gate_threshold_db
+    };
+  }
+
+  /**
+   * Play a sound file (mixed with microphone)
+   */
+  async playSound(id: string, path: string, source: PlaySource = 'ui', looping = false, boardGeneration?: number): Promise<void> {
+    await this.invoke('play_sound', { id, path, source, looping, boardGeneration });
+  }
+
+  /**
+   * Play soundboard sounds starting on the same frame (layered stingers, stems)
+   */
+  async playSoundsSynced(ids: string[], source: PlaySource = 'ui'): Promise<void> {
+    await this.invoke('play_sounds_synced', { ids, source });
+  }
+
+  /**
+   * Play all stems of a stem pad together, each at its saved volume
+   */
+  async playStemSound(padId: string, source: PlaySource = 'ui'): Promise<void> {
+    await this.invoke('play_stem_sound', { padId, source });
+  }
+
+  /**
+   * Set the volume of one stem, live and saved with the pad (0 mutes it)
+   */
+  async setStemVolume(padId: string, stemId: string, volume: number): Promise<void> {
+    await this.invoke('set_stem_volume', { padId, stemId, volume });
+  }
+
+  /**
+   * Stop a playing sound, fading out over fadeMs or the default stop fade
+   */
+  async stopSound(id: string, fadeMs?: number): Promise<void> {
+    await this.invoke('stop_sound', { id, fadeMs: fadeMs ?? null });
+  }
+
+  /**
+   * Pause a playing sound, keeping its position
+   */
+  async pauseSound(id: string): Promise<void> {
+    await this.invoke('pause_sound', { id });
+  }
+
+  /**
+   * Resume a paused sound from where it was paused
+   */
+  async resumeSound(id: string): Promise<void> {
+    await this.invoke('resume_sound', { id });
+  }
+
+  /**
+   * Jump a playing sound to positionSecs from its start; past the end finishes it
+   */
+  async seekSound(id: string, positionSecs: number): Promise<void> {
+    await this.invoke('seek_sound', { id, positionSecs });
+  }
+
+  /**
+   * Listen for a streamed sound that couldn't seek and played on from where it was
+   */
+  async listenSeekFailed(callback: (id: string, message: string) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<{ code: string; id: string; message: string }>('seek-failed', (event) =>
+      callback(event.payload.id, event.payload.message)
+    );
+  }
+
+  /**
+   * Stop every playing sound, fading out over fadeMs or the default stop fade
+   */
+  async stopAllSounds(fadeMs?: number): Promise<void> {
+    await this.invoke('stop_all_sounds', { fadeMs: fadeMs ?? null });
+  }
+
+  /**
+   * Get the soundboard playback settings
+   */
+  async getPlaybackSettings(): Promise<PlaybackSettings> {
+    const playback = await this.invoke<any>('get_playback_settings');
+    return {
+      stopFadeMs: playback.stop_fade_ms,
+      autoLevel: playback.auto_level,
+      autoLevelTargetLufs: playback.auto_level_target_lufs,
+      outputRampMs: playback.output_ramp_ms,
+      soundCacheMb: playback.sound_cache_mb
+    };
+  }
+
+  /**
+   * Set the soundboard playback settings
+   */
+  async setPlaybackSettings(playback: PlaybackSettings): Promise<void> {
+    await this.invoke('set_playback_settings', {
+      playback: {
+        stop_fade_ms: playback.stopFadeMs,
+        auto_level: playback.autoLevel,
+        auto_level_target_lufs: playback.autoLevelTargetLufs,
+        output_ramp_ms: playback.outputRampMs,
+        sound_cache_mb: playback.soundCacheMb
+      }
+    });
+  }
+
+  /**
+   * Set the volume of a soundboard sound (0 - 2), live and saved
+   */
+  async setSoundVolume(soundId: string, volume: number): Promise<void> {
+    await this.invoke('set_sound_volume', { soundId, volume });
+  }
+
+  /**
+   * Set the stereo width of a sound (0 = mono, 1 = unchanged, 2 = widest)
+   */
+  async setSoundWidth(id: string, width: number): Promise<void> {
+    await this.invoke('set_sound_width', { id, width });
+  }
+
+  /**
+   * Set the playback speed of a soundboard sound (0.5 - 2), applied from the next play
+   */
+  async setSoundSpeed(soundId: string, rate: number, mode: SpeedMode = 'resample'): Promise<void> {
+    await this.invoke('set_sound_speed', { soundId, rate, mode });
+  }
+
+  /**
+   * Measure and save the integrated loudness of a sound (null when silent)
+   */
+  async measureSoundLoudness(soundId: string): Promise<number | null> {
+    return this.invoke<number | null>('measure_sound_loudness', { soundId });
+  }
+
+  /**
+   * Opt a sound out of (or back into) auto-level
+   */
+  async setSoundAutoLevel(soundId: string, enabled: boolean): Promise<void> {
+    await this.invoke('set_sound_auto_level', { soundId, enabled });
+  }
+
+  /**
+   * Set the effect inserts of a sound, e.g. vocal reduction; empty removes them
+   */
+  async setSoundInserts(id: string, inserts: SoundInsert[]): Promise<void> {
+    await this.invoke('set_sound_inserts', { id, inserts });
+  }
+
+  /**
+   * Preview a sound on a specific output device
+   */
+  async previewSound(path: string, deviceName: string, padId: string): Promise<void> {
+    await this.invoke('preview_sound', { path, deviceName, padId });
+  }
+
+  /**
+   * Stop the currently playing preview
+   */
+  async stopPreview(): Promise<void> {
+    await this.invoke('stop_preview');
+  }
+
+  /**
+   * Get the currently previewing pad ID
+   */
+  async getPreviewState(): Promise<string | null> {
+    return this.invoke<string | null>('get_preview_state');
+  }
+
+  /**
+   * Set the preview output device
+   */
+  async setPreviewDevice(deviceId: string | null): Promise<void> {
+    await this.invoke('set_preview_device', { deviceId });
+  }
+
+  /**
+   * Set microphone volume (0.0 to 2.0)
+   */
+  async setMicVolume(volume: number): Promise<void> {
+    await this.invoke('set_mic_volume', { volume: Math.max(0, Math.min(2, volume)) });
+  }
+
+  /**
+   * Mute or unmute microphone
+   */
+  async setMicMuted(muted: boolean): Promise<void> {
+    await this.invoke('set_mic_muted', { muted });
+  }
+
+  // =========================================================================
+  // Soundboard Persistence
+  // =========================================================================
+
+  /**
+   * Save soundboard state to persistent storage
+   */
+  async saveSoundboardState(pads: any[]): Promise<void> {
+    await this.invoke('save_soundboard', { pads });
+  }
+
+  /**
+   * Pick the sound a pad plays on this trigger (rotates through variants)
+   */
+  async pickPadVariant(padId: string): Promise<SoundFile | null> {
+    return this.invoke<SoundFile | null>('pick_pad_variant', { padId });
+  }
+
+  /**
+   * Run the non-audio actions of a saved pad
+   */
+  async triggerPadActions(padId: string, source?: PlaySource): Promise<void> {
+    await this.invoke('trigger_pad_actions', { padId, source });
+  }
+
+  /**
+   * Start (or restart) the timer of a saved timer pad
+   */
+  async startTimer(padId: string): Promise<TimerConfig> {
+    return this.invoke<TimerConfig>('start_timer', { padId });
+  }
+
+  /**
+   * Cancel a pad's timer; false if it wasn't running
+   */
+  async cancelTimer(padId: string): Promise<boolean> {
+    return this.invoke<boolean>('cancel_timer', { padId });
+  }
+
+  /**
+   * Get the pads whose timer is running
+   */
+  async getRunningTimers(): Promise<string[]> {
+    return this.invoke<string[]>('get_running_timers');
+  }
+
+  /**
+   * Listen for timer progress, once a second
+   */
+  async listenTimerProgress(callback: (progress: TimerProgress) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<TimerProgress>('timer-progress', (event) => callback(event.payload));
+  }
+
+  /**
+   * List the saved scenes
+   */
+  async getScenes(): Promise<Scene[]> {
+    const scenes = await this.invoke<any[]>('get_scenes');
+    return scenes.map(scene => this.mapScene(scene));
+  }
+
+  /**
+   * Save the current channel levels and voice changer with the given pad
+   * states as a scene; pass an id to overwrite that scene
+   */
+  async saveScene(name: string, pads: Record<string, ScenePad>, id?: string): Promise<Scene> {
+    return this.mapScene(await this.invoke<any>('save_scene', { id, name, pads }));
+  }
+
+  /**
+   * Delete a saved scene
+   */
+  async deleteScene(id: string): Promise<void> {
+    await this.invoke('delete_scene', { id });
+  }
+
+  /**
+   * Get the id of the scene last recalled
+   */
+  async getActiveScene(): Promise<string | null> {
+    return this.invoke<string | null>('get_active_scene');
+  }
+
+  /**
+   * Recall a scene, crossfading volumes over crossfadeMs
+   */
+  async recallScene(id: string, crossfadeMs?: number): Promise<Scene> {
+    return this.mapScene(await this.invoke<any>('recall_scene', { id, crossfadeMs }));
+  }
+
+  /**
+   * Listen for recalled scenes, to arm and disarm pads
+   */
+  async listenSceneRecalled(callback: (scene: Scene) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<any>('scene-recalled', (event) => callback(this.mapScene(event.payload)));
+  }
+
+  private mapScene(scene: any): Scene {
+    return {
+      id: scene.id,
+      name: scene.name,
+      pads: scene.pads,
+      channels: scene.channels,
+      voiceChanger: scene.voice_changer ? this.mapVoiceChanger(scene.voice_changer) : null
+    };
+  }
+
+  /**
+   * Listen for timers ending or being cancelled
+   */
+  async listenTimerEnded(callback: (padId: string, cancelled: boolean) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<{ padId: string; cancelled: boolean }>('timer-ended', (event) =>
+      callback(event.payload.padId, event.payload.cancelled)
+    );
+  }
+
+  /**
+   * Install a sound pack (folder, .zip or Soundpad .spl)
+   */
+  async importSoundPack(path: string): Promise<SoundPack> {
+    const result = await this.invoke<any>('import_sound_pack', { path });
+    return {
+      name: result.name,
+      author: result.author ?? undefined,
+      sounds: result.sounds.map((s: any) => ({
+        id: s.id,
+        name: s.name,
+        path: s.path,
+        duration: s.duration,
+        sampleRate: s.sample_rate,
+        channels: s.channels,
+        gainDb: s.gain_db ?? undefined,
+        license: s.license ?? undefined,
+        sourceUrl: s.source_url ?? undefined,
+        attribution: s.attribution ?? undefined,
+        hotkey: s.hotkey ?? undefined,
+        color: s.color ?? undefined
+      })),
+      skipped: result.skipped
+    };
+  }
+
+  /**
+   * Load soundboard state from persistent storage
+   */
+  async loadSoundboardState(): Promise<any[] | null> {
+    return this.invoke<any[] | null>('load_soundboard');
+  }
+
+  // =========================================================================
+  // Play Log
+  // =========================================================================
+
+  /**
+   * Get the sounds played in a session (the running one by default)
+   */
+  async getPlayLog(session?: string): Promise<PlayLogEntry[]> {
+    const entries = await this.invoke<any[]>('get_play_log', { session: session ?? null });
+    return entries.map(e => ({
+      timestamp: e.timestamp,
+      offsetMs: e.offset_ms,
+      soundId: e.sound_id,
+      name: e.name,
+      path: e.path,
+      duration: e.duration,
+      source: e.source
+    }));
+  }
+
+  /**
+   * List logged sessions, newest first
+   */
+  async listPlaySessions(): Promise<string[]> {
+    return this.invoke<string[]>('list_play_sessions');
+  }
+
+  /**
+   * Export a session's play log as CSV, returning the number of rows
+   */
+  async exportPlayLogCsv(path: string, session?: string): Promise<number> {
+    return this.invoke<number>('export_play_log_csv', { session: session ?? null, path });
+  }
+
+  /**
+   * Get the reports of past mixing sessions, newest first
+   */
+  async getSessionHistory(limit?: number): Promise<SessionSummary[]> {
+    const history = await this.invoke<any[]>('get_session_history', { limit: limit ?? null });
+    return history.map(mapSessionSummary);
+  }
+
+  /**
+   * Listen for the report of a mixing session that just stopped
+   */
+  async listenSessionSummary(callback: (summary: SessionSummary) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<any>('session-summary', (event) => callback(mapSessionSummary(event.payload)));
+  }
+
+  /**
+   * Write the credits of the board's sounds to a text file, returning the
+   * number of sounds credited
+   */
+  async exportAttributions(path: string): Promise<number> {
+    return this.invoke<number>('export_attributions', { path });
+  }
+
+  // =========================================================================
+  // Library Cleanup
+  // =========================================================================
+
+  /**
+   * Propose unused sounds to archive or delete, with the space they take
+   */
+  async suggestCleanup(policy?: CleanupPolicy): Promise<CleanupReport> {
+    return this.invoke<CleanupReport>('suggest_cleanup', { policy: policy ?? null });
+  }
+
+  /**
+   * Move the files of sounds to the archive folder, keeping them on the
+   * board flagged as archived; shared files and failures are reported per sound
+   */
+  async archiveSounds(soundIds: string[]): Promise<ArchiveResult> {
+    return this.invoke<ArchiveResult>('archive_sounds', { soundIds });
+  }
+
+  /**
+   * Size of the sound library on disk, by category, and of the decode cache
+   */
+  async getLibraryStats(): Promise<LibraryStats> {
+    return this.invoke<LibraryStats>('get_library_stats');
+  }
+
+  // =========================================================================
+  // Recording
+  // =========================================================================
+
+  /**
+   * Start recording the live mix, returning the WAV path being written
+   */
+  async startRecording(path?: string): Promise<string> {
+    return this.invoke<string>('start_recording', { path: path ?? null });
+  }
+
+  /**
+   * Stop recording and export its markers
+   */
+  async stopRecording(): Promise<RecordingSummary> {
+    const summary = await this.invoke<any>('stop_recording');
+    return {
+      path: summary.path,
+      duration: summary.duration,
+      markers: summary.markers,
+      cuePath: summary.cue_path,
+      markersPath: summary.markers_path,
+      stems: summary.stems
+    };
+  }
+
+  async isRecording(): Promise<boolean> {
+    return this.invoke<boolean>('is_recording');
+  }
+
+  /**
+   * Mark the current position of the recording
+   */
+  async addMarker(label: string): Promise<RecordingMarker> {
+    return this.invoke<RecordingMarker>('add_marker', { label });
+  }
+
+  async getMarkers(): Promise<RecordingMarker[]> {
+    return this.invoke<RecordingMarker[]>('get_markers');
+  }
+
+  // =========================================================================
+  // Board Sharing
+  // =========================================================================
+
+  /**
+   * Share pads read-only with co-hosts on the LAN
+   */
+  async startBoardShare(padIds: string[], port?: number): Promise<ShareInfo> {
+    return this.mapShareInfo(await this.invoke<any>('start_board_share', { padIds, port: port ?? null }));
+  }
+
+  /**
+   * Stop sharing the board
+   */
+  async stopBoardShare(): Promise<void> {
+    await this.invoke('stop_board_share');
+  }
+
+  /**
+   * Get the current board share, if any
+   */
+  async getBoardShare(): Promise<ShareInfo | null> {
+    const result = await this.invoke<any>('get_board_share');
+    return result ? this.mapShareInfo(result) : null;
+  }
+
+  /**
+   * Listen for co-hosts asking for a shared pad to be played
+   */
+  async listenShareSoundRequested(callback: (request: ShareRequest) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<any>('share-sound-requested', (event) => {
+      callback({ padId: event.payload.pad_id, name: event.payload.name, from: event.payload.from });
+    });
+  }
+
+  private mapShareInfo(info: any): ShareInfo {
+    return { url: info.url, port: info.port, padCount: info.pad_count };
+  }
+
+  // =========================================================================
+  // Hot Folder
+  // =========================================================================
+
+  /**
+   * Get the folder watched for trigger files
+   */
+  async getHotFolder(): Promise<HotFolderSettings> {
+    return this.invoke<HotFolderSettings>('get_hot_folder');
+  }
+
+  /**
+   * Set the folder watched for trigger files
+   */
+  async setHotFolder(hotFolder: HotFolderSettings): Promise<void> {
+    await this.invoke('set_hot_folder', { hotFolder });
+  }
+
+  /**
+   * Get the jingle played when mixing starts
+   */
+  async getStartJingle(): Promise<StartJingleSettings> {
+    return this.invoke<StartJingleSettings>('get_start_jingle');
+  }
+
+  /**
+   * Set the sound played, and the scene recalled, when mixing starts
+   */
+  async setStartJingle(startJingle: StartJingleSettings): Promise<void> {
+    await this.invoke('set_start_jingle', { startJingle });
+  }
+
+  /**
+   * Get what each integration may trigger
+   */
+  async getIntegrationPermissions(): Promise<Record<Integration, IntegrationPolicy>> {
+    return this.invoke<Record<Integration, IntegrationPolicy>>('get_integration_permissions');
+  }
+
+  /**
+   * Set what an integration may trigger
+   */
+  async setIntegrationPolicy(integration: Integration, policy: IntegrationPolicy): Promise<void> {
+    await this.invoke('set_integration_policy', { integration, policy });
+  }
+
+  /**
+   * Run a trigger of an integration after checking its policy; rejects
+   * with PERMISSION_DENIED when it isn't allowed and RATE_LIMITED when the
+   * integration is over its rate limit
+   */
+  async runIntegrationTrigger(integration: Integration, trigger: IntegrationTrigger): Promise<void> {
+    await this.invoke('run_integration_trigger', { integration, trigger });
+  }
+
+  /**
+   * Listen for trigger files that couldn't be run
+   */
+  async listenHotFolderError(callback: (error: { file: string; message: string }) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<{ file: string; message: string }>('hot-folder-error', (event) => callback(event.payload));
+  }
+
+  // =========================================================================
+  // Now Playing Overlay
+  // =========================================================================
+
+  /**
+   * Get the sound shown on the now-playing overlay, if one is playing
+   */
+  async getNowPlaying(): Promise<NowPlaying | null> {
+    const s = await this.invoke<any>('get_now_playing');
+    return s ? {
+      id: s.id,
+      name: s.name,
+      artwork: s.artwork,
+      color: s.color,
+      durationSecs: s.duration_secs,
+      remainingSecs: s.remaining_secs
+    } : null;
+  }
+
+  /**
+   * Serve the now-playing overlay on localhost, for OBS browser sources
+   */
+  async startOverlay(port?: number): Promise<OverlayInfo> {
+    return this.mapOverlayInfo(await this.invoke<any>('start_overlay', { port: port ?? null }));
+  }
+
+  /**
+   * Stop serving the now-playing overlay
+   */
+  async stopOverlay(): Promise<void> {
+    await this.invoke('stop_overlay');
+  }
+
+  /**
+   * Get where the now-playing overlay is served, if it is
+   */
+  async getOverlay(): Promise<OverlayInfo | null> {
+    const result = await this.invoke<any>('get_overlay');
+    return result ? this.mapOverlayInfo(result) : null;
+  }
+
+  private mapOverlayInfo(info: any): OverlayInfo {
+    return { url: info.url, jsonUrl: info.json_url, port: info.port };
+  }
+
+  // =========================================================================
+  // Cloud Sync
+  // =========================================================================
+
+  /**
+   * Get the cloud sync backend configuration
+   */
+  async getSyncConfig(): Promise<SyncConfig> {
+    const result = await this.invoke<any>('get_sync_config');
+    const backend = result.backend;
+    return {
+      enabled: result.enabled,
+      backend: !backend ? null : backend.kind === 's3'
+        ? {
+            kind: 's3',
+            endpoint: backend.endpoint,
+            bucket: backend.bucket,
+            region: backend.region,
+            accessKeyId: backend.access_key_id,
+            secretAccessKey: '',
+            prefix: backend.prefix
+          }
+        : { kind: 'web_dav', url: backend.url, username: backend.username, password: '' },
+      hasPassword: result.has_password
+    };
+  }
+
+  /**
+   * Configure the cloud sync backend; an empty password keeps the stored one
+   */
+  async setSyncConfig(config: SyncConfig): Promise<void> {
+    const backend = config.backend;
+    await this.invoke('set_sync_config', {
+      config: {
+        enabled: config.enabled,
+        backend: !backend ? null : backend.kind === 's3'
+          ? {
+              kind: 's3',
+              endpoint: backend.endpoint,
+              bucket: backend.bucket,
+              region: backend.region,
+              access_key_id: backend.accessKeyId,
+              secret_access_key: backend.secretAccessKey,
+              prefix: backend.prefix
+            }
+          : backend
+      }
+    });
+  }
+
+  /**
+   * Get the outcome of the last cloud sync
+   */
+  async getSyncStatus(): Promise<SyncStatus> {
+    return this.mapSyncStatus(await this.invoke<any>('get_sync_status'));
+  }
+
+  /**
+   * Sync the soundboard and settings now, settling a conflict if given
+   */
+  async syncNow(resolution?: SyncResolution): Promise<SyncStatus> {
+    return this.mapSyncStatus(await this.invoke<any>('sync_now', { resolution: resolution ?? null }));
+  }
+
+  /**
+   * Listen for synced boards and settings replacing the local ones
+   */
+  async listenSyncApplied(callback: () => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen('sync-applied', () => callback());
+  }
+
+  private mapSyncStatus(status: any): SyncStatus {
+    return {
+      enabled: status.enabled,
+      backend: status.backend,
+      syncing: status.syncing,
+      lastAction: status.last_action,
+      conflict: status.conflict,
+      lastAttemptAt: status.last_attempt_at,
+      lastSyncedAt: status.last_synced_at,
+      remoteUpdatedAt: status.remote_updated_at,
+      remoteDevice: status.remote_device,
+      error: status.error
+    };
+  }
+
+  // =========================================================================
+  // Hotkeys
+  // =========================================================================
+
+  /**
+   * Validate a pad hotkey and list conflicting pads
+   */
+  async validateHotkey(padId: string, hotkey: string, bank = 0, profile?: string): Promise<HotkeyValidation> {
+    const result = await this.invoke<any>('validate_hotkey', { padId, hotkey, bank, profile });
+    return {
+      normalized: result.normalized,
+      conflicts: result.conflicts.map(mapHotkeyConflict)
+    };
+  }
+
+  /**
+   * Set a pad's hotkey, optionally only for a profile
+   *
+   * Throws a CommandFailedError with code HOTKEY_CONFLICT and the
+   * conflicting pads when the hotkey is taken. Returns its canonical spelling.
+   */
+  async setSoundHotkey(padId: string, hotkey: string, bank = 0, profile?: string): Promise<string> {
+    return this.invoke<string>('set_sound_hotkey', { padId, hotkey, bank, profile });
+  }
+
+  /**
+   * Get the hotkeys live in the active profile
+   */
+  async getActiveHotkeys(): Promise<ActiveHotkey[]> {
+    const hotkeys = await this.invoke<any[]>('get_active_hotkeys');
+    return hotkeys.map(mapActiveHotkey);
+  }
+
+  /**
+   * Make a profile's hotkeys live (undefined leaves only global hotkeys)
+   */
+  async setActiveHotkeyProfile(profile?: string): Promise<ActiveHotkey[]> {
+    const hotkeys = await this.invoke<any[]>('set_active_hotkey_profile', { profile });
+    return hotkeys.map(mapActiveHotkey);
+  }
+
+  /**
+   * Get the active board and the boards hotkeys switch between
+   */
+  async getBoard(): Promise<Board> {
+    return mapBoard(await this.invoke<any>('get_board'));
+  }
+
+  /**
+   * Switch to the next board, wrapping after the last
+   */
+  async nextBoard(): Promise<Board> {
+    return mapBoard(await this.invoke<any>('next_board'));
+  }
+
+  /**
+   * Switch to the previous board, wrapping before the first
+   */
+  async previousBoard(): Promise<Board> {
+    return mapBoard(await this.invoke<any>('previous_board'));
+  }
+
+  /**
+   * Get the hotkeys switching boards
+   */
+  async getBoardHotkeys(): Promise<BoardHotkeySettings> {
+    return this.invoke<BoardHotkeySettings>('get_board_hotkeys');
+  }
+
+  /**
+   * Set the hotkeys switching boards; refused with HOTKEY_CONFLICT when a
+   * pad uses one. Returns them in canonical spelling.
+   */
+  async setBoardHotkeys(hotkeys: BoardHotkeySettings): Promise<BoardHotkeySettings> {
+    return this.invoke<BoardHotkeySettings>('set_board_hotkeys', { hotkeys });
+  }
+
+  /**
+   * Listen for the active board changing
+   */
+  async listenBoardChanged(callback: (board: Board) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<any>('board-changed', (event) => callback(mapBoard(event.payload)));
+  }
+
+  /**
+   * Get the global pad hotkeys, with the combos the OS refused
+   */
+  async getGlobalHotkeys(): Promise<GlobalHotkeys> {
+    return mapGlobalHotkeys(await this.invoke<any>('get_global_hotkeys'));
+  }
+
+  /**
+   * Bind a pad to a combo that works while other apps have focus; refused
+   * with HOTKEY_CONFLICT when another pad or a board hotkey uses it
+   */
+  async registerGlobalHotkey(padId: string, hotkey: string): Promise<GlobalHotkeys> {
+    return mapGlobalHotkeys(await this.invoke<any>('register_global_hotkey', { padId, hotkey }));
+  }
+
+  /**
+   * Remove a pad's global combo
+   */
+  async unregisterGlobalHotkey(padId: string): Promise<GlobalHotkeys> {
+    return mapGlobalHotkeys(await this.invoke<any>('unregister_global_hotkey', { padId }));
+  }
+
+  /**
+   * Turn every global hotkey on or off, and whether board switching is global
+   */
+  async setGlobalHotkeysEnabled(enabled: boolean, boardSwitching?: boolean): Promise<GlobalHotkeys> {
+    return mapGlobalHotkeys(await this.invoke<any>('set_global_hotkeys_enabled', { enabled, boardSwitching }));
+  }
+
+  /**
+   * Listen for a pad's global hotkey pressed while another app has focus
+   */
+  async listenGlobalHotkey(callback: (padId: string) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<string>('global-hotkey', (event) => callback(event.payload));
+  }
+
+  // =========================================================================
+  // Push-to-talk
+  // =========================================================================
+
+  /**
+   * Get the push-to-talk mode, key and release tail
+   */
+  async getPushToTalk(): Promise<PushToTalkSettings> {
+    const p = await this.invoke<any>('get_push_to_talk');
+    return { mode: p.mode, key: p.key, releaseMs: p.release_ms };
+  }
+
+  /**
+   * Set push-to-talk; refused with INVALID_ARGUMENT for an unknown key or
+   * where keys can't be polled
+   */
+  async setPushToTalk(settings: PushToTalkSettings): Promise<void> {
+    await this.invoke('set_push_to_talk', {
+      settings: { mode: settings.mode, key: settings.key, release_ms: settings.releaseMs }
+    });
+  }
+
+  /**
+   * Listen for the mic opening or closing under push-to-talk
+   */
+  async listenTalkState(callback: (state: TalkState) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    return listen<TalkState>('talk-state', (event) => callback(event.payload));
+  }
+
+  // =========================================================================
+  // Preview Event Listeners
+  // =========================================================================
+
+  /**
+   * Listen for preview started events
+   */
+  async listenPreviewStarted(callback: (padId: string) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    const unlisten = await listen<string>('preview-started', (event) => {
+      callback(event.payload);
+    });
+    return unlisten;
+  }
+
+  /**
+   * Listen for preview stopped events
+   */
+  async listenPreviewStopped(callback: (padId: string) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    const unlisten = await listen<string>('preview-stopped', (event) => {
+      callback(event.payload);
+    });
+    return unlisten;
+  }
+
+  /**
+   * Listen for mic mute changes made outside the app (hardware mute keys)
+   */
+  async listenMicMuteChanged(callback: (muted: boolean) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    const unlisten = await listen<{ muted: boolean }>('mic-mute-changed', (event) => {
+      callback(event.payload.muted);
+    });
+    return unlisten;
+  }
+
+  /**
+   * Listen for the mic profile in use changing (input device switched or profile reset)
+   */
+  async listenMicProfileChanged(callback: (profile: MicProfile) => void): Promise<() => void> {
+    const { listen } = await import('@tauri-apps/api/event');
+    const unlisten = await listen<any>('mic-profile-changed', (event) => {
+      callback(this.mapMicProfile(event.payload));
     });
     return unlisten;
   }
@@ -277,6 +277,8 @@ export class DeviceSelectorComponent implements OnInit {
 
     try {
       console.log('[DeviceSelector] Loading devices and settings...');
+      // Devices are enumerated in the background after startup
+      await this.tauri.waitForBackend();
 
       const [inputDevices, allDevices, settings] = await Promise.all([
         this.tauri.getInputDevices(),
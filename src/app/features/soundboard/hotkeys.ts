@@ -0,0 +1,80 @@
+/**
+ * Pad hotkey matching - mirrors the backend rules in domain/hotkey.rs
+ *
+ * A hotkey is a space-separated sequence of combos ("Ctrl+A", "Space 1").
+ * Bank N is selected by holding its modifier while typing the sequence.
+ */
+
+const MODIFIER_ORDER = ['Ctrl', 'Alt', 'Shift', 'Meta'];
+
+const MODIFIER_ALIASES: Record<string, string> = {
+  ctrl: 'Ctrl', control: 'Ctrl',
+  alt: 'Alt', option: 'Alt',
+  shift: 'Shift',
+  meta: 'Meta', cmd: 'Meta', super: 'Meta', win: 'Meta'
+};
+
+/** Modifiers held to select each bank */
+const BANK_MODIFIERS: string[][] = [[], ['Shift'], ['Alt'], ['Alt', 'Shift']];
+
+function normalizeKey(key: string): string {
+  const lower = key.toLowerCase();
+  return lower.charAt(0).toUpperCase() + lower.slice(1);
+}
+
+function formatCombo(modifiers: Set<string>, key: string): string {
+  const held = MODIFIER_ORDER.filter(m => modifiers.has(m));
+  return [...held, normalizeKey(key)].join('+');
+}
+
+/**
+ * Canonical keys actually pressed for a hotkey in a bank, or null if invalid
+ */
+export function effectiveKeys(hotkey: string | undefined, bank: number): string | null {
+  if (!hotkey) {
+    return null;
+  }
+
+  const combos = hotkey.trim().split(/\s+/).map(combo => {
+    const parts = combo.split('+').map(p => p.trim());
+    const key = parts.pop();
+    const modifiers = new Set(BANK_MODIFIERS[bank] ?? []);
+    for (const part of parts) {
+      const modifier = MODIFIER_ALIASES[part.toLowerCase()];
+      if (!modifier) {
+        return null;
+      }
+      modifiers.add(modifier);
+    }
+    return key ? formatCombo(modifiers, key) : null;
+  });
+
+  return combos.every(c => c !== null) ? combos.join(' ') : null;
+}
+
+/**
+ * Canonical combo for a keyboard event
+ *
+ * Letters and digits use the physical key so Shift+1 stays "Shift+1"
+ * instead of "!".
+ */
+export function comboFromEvent(event: KeyboardEvent): string {
+  const modifiers = new Set<string>();
+  if (event.ctrlKey) modifiers.add('Ctrl');
+  if (event.altKey) modifiers.add('Alt');
+  if (event.shiftKey) modifiers.add('Shift');
+  if (event.metaKey) modifiers.add('Meta');
+
+  let key = event.key === ' ' ? 'Space' : event.key;
+  if (event.code.startsWith('Key')) {
+    key = event.code.slice(3);
+  } else if (event.code.startsWith('Digit')) {
+    key = event.code.slice(5);
+  }
+  return formatCombo(modifiers, key);
+}
+
+/** Check if a key event is a lone modifier press */
+export function isModifierKey(event: KeyboardEvent): boolean {
+  return ['Control', 'Shift', 'Alt', 'Meta'].includes(event.key);
+}
@@ -2,10 +2,14 @@ import { Component, HostListener } from '@angular/core';
 import { CommonModule } from '@angular/common';
 import { SoundboardService } from '../../core/services/soundboard.service';
 import { SoundPadComponent } from './sound-pad/sound-pad.component';
+import { comboFromEvent, effectiveKeys, isModifierKey } from './hotkeys';
 
 // Default hotkeys for first 12 pads: 1-9, 0, -, =
 const DEFAULT_HOTKEYS = ['1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '-', '='];
 
+// Time allowed between the keys of a sequence
+const SEQUENCE_TIMEOUT_MS = 1500;
+
 @Component({
   selector: 'app-soundboard',
   standalone: true,
@@ -140,6 +144,9 @@ const DEFAULT_HOTKEYS = ['1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '-',
   `]
 })
 export class SoundboardComponent {
+  private pressed: string[] = [];
+  private sequenceTimer?: ReturnType<typeof setTimeout>;
+
   constructor(public soundboard: SoundboardService) {}
 
   @HostListener('window:keydown', ['$event'])
@@ -155,20 +162,55 @@ export class SoundboardComponent {
       return;
     }
 
-    // Find pad by hotkey
+    if (isModifierKey(event)) {
+      return;
+    }
+
+    // Find pad by hotkey, allowing multi-key sequences
+    clearTimeout(this.sequenceTimer);
+    this.pressed.push(comboFromEvent(event));
+    const typed = this.pressed.join(' ');
+
+    // Board switching takes precedence; the backend refuses pad hotkeys that clash with it
+    const boardHotkeys = this.soundboard.boardHotkeys();
+    const boardStep = typed === effectiveKeys(boardHotkeys?.next ?? undefined, 0) ? 1
+      : typed === effectiveKeys(boardHotkeys?.previous ?? undefined, 0) ? -1
+      : 0;
+    if (boardStep !== 0) {
+      this.pressed = [];
+      event.preventDefault();
+      this.soundboard.stepBoard(boardStep);
+      return;
+    }
+
+    // Only pads live on the active board, matched under its generation
+    const generation = this.soundboard.board()?.generation;
     const pads = this.soundboard.pads();
-    const padIndex = pads.findIndex(p => {
-      const hotkey = p.hotkey || DEFAULT_HOTKEYS[pads.indexOf(p)];
-      return hotkey === event.key;
-    });
-
-    if (padIndex >= 0) {
-      const pad = pads[padIndex];
-      if (pad.sound) {
+    const bindings = pads
+      .map((pad, i) => ({
+        pad,
+        keys: effectiveKeys(pad.hotkey || DEFAULT_HOTKEYS[i], pad.hotkeyBank ?? 0)
+      }))
+      .filter(b => this.soundboard.isOnActiveBoard(b.pad));
+
+    const match = bindings.find(b => b.keys === typed);
+    if (match) {
+      this.pressed = [];
+      if (match.pad.sound) {
         event.preventDefault();
-        this.soundboard.playSound(pad.id);
+        this.soundboard.playSound(match.pad.id, 'hotkey', generation);
       }
+      return;
+    }
+
+    if (bindings.some(b => b.keys?.startsWith(typed + ' '))) {
+      // Wait for the rest of the sequence
+      event.preventDefault();
+      this.sequenceTimer = setTimeout(() => this.pressed = [], SEQUENCE_TIMEOUT_MS);
+      return;
     }
+
+    this.pressed = [];
   }
 
   /**